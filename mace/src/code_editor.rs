@@ -459,95 +459,77 @@ impl CodeEditor {
                     view.view.redraw_view(cx);
                 }
             }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowLeft,
-                modifiers: KeyModifiers { shift, .. },
-                ..
-            }) => {
+            Event::KeyDown(ke) => {
                 let view = &self.views_by_view_id[view_id];
                 if let Some(session_id) = view.session_id {
-                    state.move_cursors_left(session_id, shift);
-                    let view = &mut self.views_by_view_id[view_id];
-                    view.view.redraw_view(cx);
-                }
-            }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowRight,
-                modifiers: KeyModifiers { shift, .. },
-                ..
-            }) => {
-                let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
-                    state.move_cursors_right(session_id, shift);
-                    let view = &mut self.views_by_view_id[view_id];
-                    view.view.redraw_view(cx);
-                }
-            }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowUp,
-                modifiers: KeyModifiers { shift, .. },
-                ..
-            }) => {
-                let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
-                    state.move_cursors_up(session_id, shift);
-                    let view = &mut self.views_by_view_id[view_id];
-                    view.view.redraw_view(cx);
-                }
-            }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowDown,
-                modifiers: KeyModifiers { shift, .. },
-                ..
-            }) => {
-                let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
-                    state.move_cursors_down(session_id, shift);
-                    let view = &mut self.views_by_view_id[view_id];
-                    view.view.redraw_view(cx);
-                }
-            }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::Backspace,
-                ..
-            }) => {
-                let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
-                    state.insert_backspace(session_id, send_request);
-                    let session = &state.sessions_by_session_id[session_id];
-                    self.redraw_document_views(cx, state, session.document_id);
-                }
-            }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::KeyZ,
-                modifiers,
-                ..
-            }) if modifiers.control || modifiers.logo => {
-                let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
-                    if modifiers.shift {
-                        state.redo(session_id, send_request);
-                    } else {
-                        state.undo(session_id, send_request);
+                    let command = cx.keymap_command(KEYMAP_EDITOR, &ke);
+                    let select = command
+                        .as_deref()
+                        .map_or(false, |command| command.starts_with("editor.select_"));
+                    match command.as_deref() {
+                        Some("editor.move_left") | Some("editor.select_left") => {
+                            state.move_cursors_left(session_id, select);
+                            let view = &mut self.views_by_view_id[view_id];
+                            view.view.redraw_view(cx);
+                        }
+                        Some("editor.move_right") | Some("editor.select_right") => {
+                            state.move_cursors_right(session_id, select);
+                            let view = &mut self.views_by_view_id[view_id];
+                            view.view.redraw_view(cx);
+                        }
+                        Some("editor.move_up") | Some("editor.select_up") => {
+                            state.move_cursors_up(session_id, select);
+                            let view = &mut self.views_by_view_id[view_id];
+                            view.view.redraw_view(cx);
+                        }
+                        Some("editor.move_down") | Some("editor.select_down") => {
+                            state.move_cursors_down(session_id, select);
+                            let view = &mut self.views_by_view_id[view_id];
+                            view.view.redraw_view(cx);
+                        }
+                        Some("editor.backspace") => {
+                            state.insert_backspace(session_id, send_request);
+                            let session = &state.sessions_by_session_id[session_id];
+                            self.redraw_document_views(cx, state, session.document_id);
+                        }
+                        Some("editor.undo") => {
+                            state.undo(session_id, send_request);
+                            let session = &state.sessions_by_session_id[session_id];
+                            self.redraw_document_views(cx, state, session.document_id);
+                        }
+                        Some("editor.redo") => {
+                            state.redo(session_id, send_request);
+                            let session = &state.sessions_by_session_id[session_id];
+                            self.redraw_document_views(cx, state, session.document_id);
+                        }
+                        Some("editor.newline") => {
+                            state.insert_text(session_id, Text::from(vec![vec![], vec![]]), send_request);
+                            let session = &state.sessions_by_session_id[session_id];
+                            self.redraw_document_views(cx, state, session.document_id);
+                        }
+                        _ => {}
                     }
-                    let session = &state.sessions_by_session_id[session_id];
-                    self.redraw_document_views(cx, state, session.document_id);
                 }
             }
-            Event::KeyDown(KeyEvent {
-                key_code: KeyCode::Return,
-                ..
-            }) => {
+            Event::TextInput(TextInputEvent { input, .. }) => {
                 let view = &self.views_by_view_id[view_id];
                 if let Some(session_id) = view.session_id {
-                    state.insert_text(session_id, Text::from(vec![vec![], vec![]]), send_request);
+                    state.insert_text(
+                        session_id,
+                        input
+                            .lines()
+                            .map(|line| line.chars().collect::<Vec<_>>())
+                            .collect::<Vec<_>>()
+                            .into(),
+                        send_request,
+                    );
                     let session = &state.sessions_by_session_id[session_id];
                     self.redraw_document_views(cx, state, session.document_id);
                 }
             }
-            Event::TextInput(TextInputEvent { input, .. }) => {
+            Event::Paste(PasteEvent { data }) => {
                 let view = &self.views_by_view_id[view_id];
-                if let Some(session_id) = view.session_id {
+                if let (Some(session_id), Some(input)) = (view.session_id, data.text()) {
                     state.insert_text(
                         session_id,
                         input
@@ -6,38 +6,26 @@ pub fn builder(ws: &mut HubBuilder, htc: FromHubMsg) -> Result<(), HubWsError> {
         HubMsg::ListPackagesRequest {uid} => {
             // lets read our Cargo.toml in the root
             let packages = ws.read_packages(uid);
-            let builds = &["check", "debug", "release", "small"];
+            let builds = ws.build_profile_names();
+            let builds: Vec<&str> = builds.iter().map( | v | v.as_ref()).collect();
             ws.packages_response(
                 htc.from,
                 uid,
-                packages.iter().map( | (project, v) | HubPackage::new(project, v, builds)).collect()
+                packages.iter().map( | (project, v) | HubPackage::new(project, v, &builds)).collect()
             );   
             Ok(())
         },  
         HubMsg::Build {uid, workspace, package, config} => {
-            let mut args = Vec::new();
-            let mut env = Vec::new();
-            match config.as_ref() {
-                "small" => args.extend_from_slice(&["build", "--release", "-p", &package]),
-                "release" => args.extend_from_slice(&["build", "--release", "-p", &package]),
-                "debug" => args.extend_from_slice(&["build", "-p", &package]),
-                "check" => args.extend_from_slice(&["check", "-p", &package]),
-                _ => return ws.cannot_find_build(uid, &package, &config)
-            }
-            
-            if config == "small" {
-                env.push(("RUSTFLAGS", "-C opt-level=z -C panic=abort -C codegen-units=1"))
-            }
+            let mut profile = match ws.build_profile(&config) {
+                Some(profile) => profile,
+                None => return ws.cannot_find_build(uid, &package, &config)
+            };
             
-            if package.ends_with("wasm") {
-                args.push("--target=wasm32-unknown-unknown");
+            if profile.target.is_none() && package.ends_with("wasm") {
+                profile.target = Some("wasm32-unknown-unknown".to_string());
             }
             
-            if let BuildResult::Wasm {path} = ws.cargo(uid, &workspace, &args, &env) ? {
-                if config == "small" { // strip the build
-                    ws.wasm_strip_debug(uid, &path) ?;
-                }
-            }
+            ws.cargo_profile(uid, &workspace, &package, &profile) ?;
             Ok(())
         },
         _ => ws.default(htc)
@@ -39,9 +39,22 @@ pub struct ActiveBuild {
     pub build_target: BuildTarget,
     pub build_result: Option<BuildResult>,
     pub build_uid: Option<HubUid>,
+    pub job_uid: Option<HubUid>,
     pub run_uid: Option<HubUid>,
 }
 
+impl ActiveBuild {
+    // a queueing builder can run our build under the uid of an identical request
+    pub fn is_build_uid(&self, uid: &HubUid) -> bool {
+        self.build_uid == Some(*uid) || self.build_uid.is_some() && self.job_uid == Some(*uid)
+    }
+    
+    pub fn build_done(&mut self) {
+        self.build_uid = None;
+        self.job_uid = None;
+    }
+}
+
 impl BuildManager {
     
     fn clear_textbuffer_messages(&self, cx: &mut Cx, makepad_storage: &mut MakepadStorage) {
@@ -60,7 +73,7 @@ impl BuildManager {
     
     pub fn is_running_uid(&self, uid: &HubUid) -> bool {
         for ab in &self.active_builds {
-            if ab.build_uid == Some(*uid) {
+            if ab.is_build_uid(uid) {
                 return true
             }
             if ab.run_uid == Some(*uid) {
@@ -183,6 +196,9 @@ impl BuildManager {
                         HubLogItem::LocError(_) => TextBufferMessageLevel::Error,
                         HubLogItem::LocWarning(_) => TextBufferMessageLevel::Warning,
                         HubLogItem::LocMessage(_) => TextBufferMessageLevel::Log,
+                        HubLogItem::TestOk(_) => TextBufferMessageLevel::Log,
+                        HubLogItem::TestIgnored(_) => TextBufferMessageLevel::Log,
+                        HubLogItem::TestFailed(_) => TextBufferMessageLevel::Error,
                         HubLogItem::Error(_) => TextBufferMessageLevel::Error,
                         HubLogItem::Warning(_) => TextBufferMessageLevel::Warning,
                         HubLogItem::Message(_) => TextBufferMessageLevel::Log,
@@ -199,15 +215,27 @@ impl BuildManager {
             HubMsg::BuildFailure {uid} => if self.is_running_uid(uid) {
                 // if we didnt have any errors, check if we need to run
                 for ab in &mut self.active_builds {
-                    if ab.build_uid == Some(*uid) {
-                        ab.build_uid = None;
+                    if ab.is_build_uid(uid) {
+                        ab.build_done();
+                    }
+                }
+            },
+            HubMsg::BuildQueueState {builder, jobs} => {
+                for ab in &mut self.active_builds {
+                    if ab.build_target.builder != *builder {
+                        continue
+                    }
+                    if let Some(build_uid) = ab.build_uid {
+                        if let Some(job) = jobs.iter().find( | j | j.uids.contains(&build_uid)) {
+                            ab.job_uid = Some(job.uid);
+                        }
                     }
                 }
             },
             HubMsg::CargoEnd {uid, build_result} => if self.is_running_uid(uid) {
                 for ab in &mut self.active_builds {
-                    if ab.build_uid == Some(*uid) {
-                        ab.build_uid = None;
+                    if ab.is_build_uid(uid) {
+                        ab.build_done();
                         ab.build_result = Some(build_result.clone());
                     }
                 }
@@ -216,6 +244,18 @@ impl BuildManager {
                 }
                 cx.send_signal(self.signal, BuildManager::status_cargo_end());
             },
+            HubMsg::TestEnd {uid, passed, failed, ignored} => if self.is_running_uid(uid) {
+                for ab in &mut self.active_builds {
+                    if ab.is_build_uid(uid) {
+                        ab.build_done();
+                    }
+                }
+                self.handle_log_item_limit(cx);
+                self.log_items.push(HubLogItem::Message(
+                    format!("Tests done - passed: {} failed: {} ignored: {}", passed, failed, ignored)
+                ));
+                cx.send_signal(self.signal, BuildManager::status_cargo_end());
+            },
             HubMsg::ProgramEnd {uid} => if self.is_running_uid(uid) {
                 // if we didnt have any errors, check if we need to run
                 for ab in &mut self.active_builds {
@@ -314,6 +354,14 @@ impl BuildManager {
     }
     
     pub fn restart_build(&mut self, cx: &mut Cx, makepad_storage: &mut MakepadStorage) {
+        self.restart_active_builds(cx, makepad_storage, false)
+    }
+    
+    pub fn restart_tests(&mut self, cx: &mut Cx, makepad_storage: &mut MakepadStorage) {
+        self.restart_active_builds(cx, makepad_storage, true)
+    }
+    
+    fn restart_active_builds(&mut self, cx: &mut Cx, makepad_storage: &mut MakepadStorage, run_tests: bool) {
         if !cx.platform_type.is_desktop() {
             return
         }
@@ -324,7 +372,7 @@ impl BuildManager {
         self.clear_textbuffer_messages(cx, makepad_storage);
         
         let hub_ui = makepad_storage.hub_ui.as_mut().unwrap();
-        self.exec_when_done = makepad_storage.settings.exec_when_done;
+        self.exec_when_done = !run_tests && makepad_storage.settings.exec_when_done;
         for ab in &mut self.active_builds {
             ab.build_result = None;
             if let Some(build_uid) = ab.build_uid {
@@ -354,17 +402,28 @@ impl BuildManager {
             let uid = hub_ui.route_send.alloc_uid();
             hub_ui.route_send.send(ToHubMsg {
                 to: HubMsgTo::Builder(build_target.builder.clone()),
-                msg: HubMsg::Build {
-                    uid: uid.clone(),
-                    workspace: build_target.workspace.clone(),
-                    package: build_target.package.clone(),
-                    config: build_target.config.clone()
+                msg: if run_tests {
+                    HubMsg::TestRun {
+                        uid: uid.clone(),
+                        workspace: build_target.workspace.clone(),
+                        package: build_target.package.clone(),
+                        filter: "".to_string()
+                    }
+                }
+                else {
+                    HubMsg::Build {
+                        uid: uid.clone(),
+                        workspace: build_target.workspace.clone(),
+                        package: build_target.package.clone(),
+                        config: build_target.config.clone()
+                    }
                 }
             });
             self.active_builds.push(ActiveBuild {
                 build_target: build_target.clone(),
                 build_result: None,
                 build_uid: Some(uid),
+                job_uid: None,
                 run_uid: None
             })
         }
@@ -1,6 +1,8 @@
 use makepad_render::*;
 use makepad_widget::*;
 
+// what the tree puts on the clipboard next to the plain text, one path per line
+pub const FILE_TREE_PATHS_MIME: &str = "application/x-makepad-file-paths";
 
 #[derive(Clone, DrawQuad)]
 #[repr(C)]
@@ -20,7 +22,7 @@ pub struct FileTree {
     pub root_node: FileNode,
     pub drag_bg: DrawColor,
     pub _shadow_area: Area,
-
+    
     pub filler: DrawFileTreeFiller,
     pub tree_text: DrawText,
     pub node_bg: DrawColor,
@@ -52,28 +54,28 @@ impl FileTree {
             root_node: FileNode::Folder {name: "".to_string(), state: NodeState::Open, draw: None, folder: vec![
                 FileNode::File {name: "loading...".to_string(), draw: None},
             ]},
-
+            
             drag_bg: DrawColor::new(cx, live_shader!(cx, self::shader_drag_bg)),
-
+            
             view: ScrollView::new()
-                .with_scroll_v(ScrollBar::new(cx).with_smoothing(0.15)),
-
+                .with_scroll_v(ScrollBar::new(cx).with_smoothing(Spring::critical(1000.0))),
+            
             drag_view: View::new()
                 .with_is_overlay(true),
-
+            
             _drag_move: None,
             _shadow_area: Area::Empty,
-
+            
             tree_text: DrawText::new(cx, default_shader!())
                 .with_draw_depth(0.1),
             node_bg: DrawColor::new(cx, default_shader!()),
             //node_layout: LayoutFileTreeNode::id(),
             filler: DrawFileTreeFiller::new(cx, default_shader!())
                 .with_draw_depth(0.2),
-
+            
             shadow: ScrollShadow::new(cx)
                 .with_draw_depth(0.25),
-
+            
             node_layout: Layout::default(),
             row_height: 0.,
             filler_walk: Walk::default(),
@@ -193,9 +195,11 @@ impl FileTree {
                     bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
                     ease: Ease::Lin,
                     keys: vec![(1.0, default_color)],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -214,9 +218,11 @@ impl FileTree {
                     bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
                     ease: Ease::Lin,
                     keys: vec![(0., over_color), (1., over_color)],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -310,7 +316,7 @@ impl FileTree {
             let is_filenode = if let FileNode::File {..} = node {true} else {false};
             
             let node_draw = if let Some(node_draw) = node.get_draw() {node_draw}else {continue};
-
+            
             if let Some(ae) = event.is_animate(cx, &node_draw.animator) {
                 self.node_bg.set_area(node_draw.area);
                 self.node_bg.animate(cx, &mut node_draw.animator, ae.time);
@@ -332,23 +338,12 @@ impl FileTree {
                     node_draw.animator.play_anim(cx, Self::get_over_anim(cx, counter, node_draw.marked != 0));
                     
                     if let FileNode::Folder {state, ..} = node {
-                        *state = match state {
-                            NodeState::Opening(fac) => {
-                                NodeState::Closing(1.0 - *fac)
-                            },
-                            NodeState::Closing(fac) => {
-                                NodeState::Opening(1.0 - *fac)
-                            },
-                            NodeState::Open => {
-                                NodeState::Closing(1.0)
-                            },
-                            NodeState::Closed => {
-                                NodeState::Opening(1.0)
-                            }
-                        };
+                        state.toggle();
                         // start the redraw loop
                         self.view.redraw_view(cx);
                     }
+                    // keys go to the tree once it has been clicked
+                    cx.set_key_focus(self.view.area());
                 },
                 Event::FingerUp(fe) => {
                     if !self._drag_move.is_none() {
@@ -391,6 +386,35 @@ impl FileTree {
             counter += 1;
         }
         
+        if let Event::TextCopy(_) = event.hits(cx, self.view.area(), HitOpt::default()) {
+            // the marked paths as text, and one per line under our own mime for a drop back into the tree
+            let paths = Self::get_marked_paths(&mut self.root_node);
+            if paths.len() > 0 {
+                let text = paths.join("\n");
+                cx.set_clipboard(ClipboardData::from_text(&text).with_custom(FILE_TREE_PATHS_MIME, text.as_bytes()));
+            }
+        }
+
+        if let Event::KeyDown(ke) = event.hits(cx, self.view.area(), HitOpt::default()) {
+            if cx.keymap_command(KEYMAP_FILE_TREE, &ke).as_deref() == Some("file_tree.open") {
+                // open the marked file or fold the marked folder
+                let mut file_walker = FileWalker::new(&mut self.root_node);
+                while let Some((_depth, _index, _len, node)) = file_walker.walk() {
+                    if node.get_draw().as_ref().map_or(true, | node_draw | node_draw.marked == 0) {
+                        continue
+                    }
+                    match node {
+                        FileNode::File {..} => select_node = 1,
+                        FileNode::Folder {state, ..} => {
+                            state.toggle();
+                            self.view.redraw_view(cx);
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+        
         //unmark non selected nodes and also set even/odd animations to make sure its rendered properly
         if unmark_nodes {
             let mut file_walker = FileWalker::new(&mut self.root_node);
@@ -456,8 +480,9 @@ impl FileTree {
     
     pub fn draw_file_tree(&mut self, cx: &mut Cx) {
         if self.view.begin_view(cx, Layout::default()).is_err() {return}
-
+        
         self.apply_style(cx);
+        cx.begin_access_node(AccessNode::new(AccessRole::Tree, self.view.area()).with_name("files"));
         
         let mut file_walker = FileWalker::new(&mut self.root_node);
         
@@ -465,6 +490,7 @@ impl FileTree {
         let mut counter = 0;
         let mut scale_stack = Vec::new();
         let mut last_stack = Vec::new();
+        let mut access_stack = Vec::new();
         scale_stack.push(1.0f64);
         
         while let Some((depth, index, len, node)) = file_walker.walk() {
@@ -472,6 +498,12 @@ impl FileTree {
             let is_first = index == 0;
             let is_last = index == len - 1;
             
+            // close the folders we walked out of
+            while access_stack.last().map_or(false, | d | *d >= depth) {
+                access_stack.pop();
+                cx.end_access_node();
+            }
+            
             while depth < scale_stack.len() {
                 scale_stack.pop();
                 last_stack.pop();
@@ -502,10 +534,11 @@ impl FileTree {
             
             let mut node_layout = self.node_layout.clone();
             node_layout.walk.height = Height::Fix(self.row_height * scale as f32);
-
+            
             self.node_bg.begin_quad(cx, node_layout);
             node_draw.area = self.node_bg.area();
-
+            let node_area = node_draw.area;
+            
             let is_marked = node_draw.marked != 0;
             
             for i in 0..(depth - 1) {
@@ -563,6 +596,12 @@ impl FileTree {
                     self.tree_text.wrapping = Wrapping::Ellipsis(wleft);
                     self.tree_text.draw_text_walk(cx, name);
                     
+                    let expanded = match state {NodeState::Open | NodeState::Opening(_) => true, _ => false};
+                    cx.begin_access_node(
+                        AccessNode::new(AccessRole::TreeItem, node_area).with_name(name).with_expanded(expanded).with_selected(is_marked)
+                    );
+                    access_stack.push(depth);
+                    
                     let (new_scale, new_state) = match state {
                         NodeState::Opening(fac) => {
                             self.view.redraw_view(cx);
@@ -606,6 +645,7 @@ impl FileTree {
                         self.color_tree_file
                     };
                     self.tree_text.draw_text_walk(cx, name);
+                    cx.add_access_node(AccessNode::new(AccessRole::TreeItem, node_area).with_name(name).with_selected(is_marked));
                 }
             }
             
@@ -617,6 +657,9 @@ impl FileTree {
                 counter += 1;
             }
         }
+        for _ in access_stack {
+            cx.end_access_node();
+        }
         
         // draw filler nodes
         if self.row_height > 0. {
@@ -668,6 +711,7 @@ impl FileTree {
         self.shadow.draw_shadow_top(cx);
         
         self.view.end_view(cx);
+        cx.end_access_node();
     }
 }
 
@@ -679,6 +723,25 @@ pub enum NodeState {
     Closed
 }
 
+impl NodeState {
+    fn toggle(&mut self) {
+        *self = match self {
+            NodeState::Opening(fac) => {
+                NodeState::Closing(1.0 - *fac)
+            },
+            NodeState::Closing(fac) => {
+                NodeState::Opening(1.0 - *fac)
+            },
+            NodeState::Open => {
+                NodeState::Closing(1.0)
+            },
+            NodeState::Closed => {
+                NodeState::Opening(1.0)
+            }
+        };
+    }
+}
+
 #[derive(Clone)]
 pub struct NodeDraw {
     area: Area,
@@ -19,8 +19,6 @@ pub mod jseditor;
 pub mod plaineditor;
 pub mod itemdisplay;
 pub mod liveitems;
-pub mod colorpicker;
-pub mod floatslider;
 //mod rustcompiler;
 //pub use crate::rustcompiler::*;
 pub use makepad_render::*;
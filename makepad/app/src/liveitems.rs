@@ -2,8 +2,6 @@ use makepad_render::*;
 use makepad_widget::*;
 use crate::mprstokenizer::*;
 use crate::makepadstorage::*;
-use crate::colorpicker::*;
-use crate::floatslider::*;
 use std::fmt;
 use std::collections::HashMap;
 
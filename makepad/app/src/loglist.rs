@@ -91,9 +91,11 @@ impl LogList {
                     keys: vec![
                         (1.0, default_color)
                     ],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -115,9 +117,11 @@ impl LogList {
                         (0., over_color),
                         (1., over_color)
                     ],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -134,29 +138,29 @@ impl LogList {
         let mut select_at_end = false;
         // global key handle
         match event {
-            Event::KeyDown(ke) => match ke.key_code {
-                KeyCode::Period => if ke.modifiers.logo || ke.modifiers.control {
+            Event::KeyDown(ke) => match cx.keymap_command("log", ke).as_deref() {
+                Some(command @ "log.next") | Some(command @ "log.next_at_end") => {
                     select = self.list.get_next_single_selection();
                     self.list.scroll_item_in_view = select.item_index();
                     bm.tail_log_items = false;
-                    select_at_end = ke.modifiers.shift;
+                    select_at_end = command == "log.next_at_end";
                 },
-                KeyCode::Comma => if ke.modifiers.logo || ke.modifiers.control {
+                Some(command @ "log.prev") | Some(command @ "log.prev_at_end") => {
                     // lets find the
                     select = self.list.get_prev_single_selection();
                     bm.tail_log_items = false;
                     self.list.scroll_item_in_view = select.item_index();
-                    select_at_end = ke.modifiers.shift;
+                    select_at_end = command == "log.prev_at_end";
                 },
-                KeyCode::KeyM => if ke.modifiers.logo || ke.modifiers.control {
+                Some("log.select_all") => {
                     select = ListSelect::All;
                 },
-                KeyCode::KeyT => if ke.modifiers.logo || ke.modifiers.control {
+                Some("log.tail") => {
                     // lock scroll
                     bm.tail_log_items = true;
                     self.view.redraw_view(cx);
                 },
-                KeyCode::KeyK => if ke.modifiers.logo || ke.modifiers.control {
+                Some("log.clear") => {
                     // clear and tail log
                     bm.tail_log_items = true;
                     bm.log_items.truncate(0);
@@ -276,7 +280,8 @@ impl LogList {
         
         let mut counter = 0;
         for i in self.list.start_item..self.list.end_item {
-            self.item_draw.draw_log_item(cx, i, &mut self.list.list_items[i], &bm.log_items[i]);
+            let prev_item = if i > 0 {Some(&bm.log_items[i - 1])}else {None};
+            self.item_draw.draw_log_item(cx, i, &mut self.list.list_items[i], &bm.log_items[i], prev_item);
             counter += 1;
         }
         
@@ -341,7 +346,24 @@ impl LogItemDraw {
         }
     }
     
-    pub fn draw_log_item(&mut self, cx: &mut Cx, index: usize, list_item: &mut ListItem, log_item: &HubLogItem) {
+    pub fn draw_test_name(&mut self, cx: &mut Cx, test_msg: &TestMessage, prev_item: Option<&HubLogItem>) {
+        // only label the first test of a suite so the results read as a group
+        let same_suite = match prev_item {
+            Some(HubLogItem::TestOk(prev)) | Some(HubLogItem::TestIgnored(prev)) | Some(HubLogItem::TestFailed(prev)) => prev.suite == test_msg.suite,
+            _ => false
+        };
+        self.text.color = live_vec4!(cx, self::color_path);
+        if same_suite {
+            self.text.draw_text_walk(cx, "    ");
+        }
+        else {
+            self.text.draw_text_walk(cx, &format!("{} - ", test_msg.suite));
+        }
+        self.text.color = live_vec4!(cx, self::color_message);
+        self.text.draw_text_walk(cx, &test_msg.name);
+    }
+    
+    pub fn draw_log_item(&mut self, cx: &mut Cx, index: usize, list_item: &mut ListItem, log_item: &HubLogItem, prev_item: Option<&HubLogItem>) {
         
         if list_item.animator.need_init(cx) {
             list_item.animator.init(cx, LogList::get_default_anim(cx, index, false));
@@ -376,6 +398,29 @@ impl LogItemDraw {
                 self.draw_log_path(cx, &loc_msg.path, loc_msg.line);
                 self.draw_log_body(cx, &loc_msg.body);
             },
+            HubLogItem::TestOk(test_msg) => {
+                self.code_icon.draw_icon(cx, CodeIconType::Ok);
+                cx.turtle_align_y();
+                self.draw_test_name(cx, test_msg, prev_item);
+            },
+            HubLogItem::TestIgnored(test_msg) => {
+                self.code_icon.draw_icon(cx, CodeIconType::Wait);
+                cx.turtle_align_y();
+                self.draw_test_name(cx, test_msg, prev_item);
+                self.draw_log_body(cx, " (ignored)");
+            },
+            HubLogItem::TestFailed(test_msg) => {
+                self.code_icon.draw_icon(cx, CodeIconType::Panic);
+                cx.turtle_align_y();
+                self.draw_test_name(cx, test_msg, prev_item);
+                if let Some(loc_msg) = &test_msg.loc {
+                    self.text.draw_text_walk(cx, " - ");
+                    if loc_msg.path.len() > 0 {
+                        self.draw_log_path(cx, &loc_msg.path, loc_msg.line);
+                    }
+                    self.draw_log_body(cx, &loc_msg.body);
+                }
+            },
             HubLogItem::Error(msg) => {
                 self.code_icon.draw_icon(cx, CodeIconType::Error);
                 cx.turtle_align_y();
@@ -9,6 +9,35 @@ use crate::filetree::*;
 use crate::buildmanager::*;
 use crate::makepadstyle::*;
 
+// makepads own keys, makepad_keymap.ron is layered on top of these
+pub const MAKEPAD_KEYMAP_RON: &'static str = r#"(
+    bindings: [
+        (scope: "global", keys: "Mod+Comma", command: "preferences"),
+        (scope: "global", keys: "Mod+N", command: "new_file"),
+        (scope: "global", keys: "Mod+Shift+N", command: "new_window"),
+        (scope: "global", keys: "Mod+Shift+S", command: "save_as"),
+        (scope: "global", keys: "Mod+W", command: "close_editor"),
+        (scope: "global", keys: "Mod+Shift+W", command: "close_window"),
+        (scope: "global", keys: "Mod+R", command: "reload_builders"),
+        (scope: "global", keys: "Mod+0", command: "reset_zoom"),
+        
+        (scope: "build", keys: "Mod+Backtick", command: "build.run"),
+        (scope: "build", keys: "Mod+Shift+Backtick", command: "build.test"),
+        
+        (scope: "layout", keys: "Mod+1", command: "layout.default"),
+        (scope: "layout", keys: "Mod+2", command: "layout.editor"),
+        (scope: "layout", keys: "Mod+3", command: "layout.build"),
+        
+        (scope: "log", keys: "Mod+Period", command: "log.next"),
+        (scope: "log", keys: "Mod+Shift+Period", command: "log.next_at_end"),
+        (scope: "log", keys: "Mod+Comma", command: "log.prev"),
+        (scope: "log", keys: "Mod+Shift+Comma", command: "log.prev_at_end"),
+        (scope: "log", keys: "Mod+M", command: "log.select_all"),
+        (scope: "log", keys: "Mod+T", command: "log.tail"),
+        (scope: "log", keys: "Mod+K", command: "log.clear"),
+    ]
+)"#;
+
 pub struct MakepadApp {
     pub makepad_window_state_template: MakepadWindowState,
     pub makepad_window_template: MakepadWindow,
@@ -45,12 +74,13 @@ impl MakepadApp {
     pub fn new(cx: &mut Cx) -> Self {
         let ms = cx.new_signal();
         // set up the keyboard map
-        Self::command_preferences().set_key(cx, KeyCode::Comma);
-        Self::command_new_file().set_key(cx, KeyCode::KeyN);
-        Self::command_new_window().set_key_shift(cx, KeyCode::KeyN);
-        Self::command_save_as().set_key_shift(cx, KeyCode::KeyS);
-        Self::command_close_editor().set_key(cx, KeyCode::KeyW);
-        Self::command_close_window().set_key_shift(cx, KeyCode::KeyW);
+        cx.load_keymap_ron("makepad", MAKEPAD_KEYMAP_RON).expect("makepad keymap doesn't parse");
+        Self::command_preferences().set_keymap_command(cx, "preferences");
+        Self::command_new_file().set_keymap_command(cx, "new_file");
+        Self::command_new_window().set_keymap_command(cx, "new_window");
+        Self::command_save_as().set_keymap_command(cx, "save_as");
+        Self::command_close_editor().set_keymap_command(cx, "close_editor");
+        Self::command_close_window().set_keymap_command(cx, "close_window");
         
         cx.command_default_keymap();
         
@@ -121,111 +151,13 @@ impl MakepadApp {
                 open_folders: Vec::new(),
                 window_inner_size: Vec2::default(),
                 window_position: Vec2::default(),
-                dock_items: DockItem::Splitter {
-                    axis: Axis::Vertical,
-                    align: SplitterAlign::First,
-                    pos: 200.0,
-                    
-                    first: Box::new(DockItem::Splitter {
-                        axis: Axis::Horizontal,
-                        align: SplitterAlign::Last,
-                        pos: 250.0,
-                        first: Box::new(DockItem::TabControl {
-                            current: 0,
-                            previous: 0,
-                            tabs: vec![
-                                DockTab {
-                                    closeable: false,
-                                    title: "Files".to_string(),
-                                    item: Panel::FileTree
-                                },
-                                DockTab {
-                                    closeable: false,
-                                    title: "".to_string(),
-                                    item: Panel::SearchResults
-                                }
-                            ]
-                        }),
-                        last: Box::new(DockItem::TabControl {
-                            current: 0,
-                            previous: 0,
-                            tabs: vec![
-                                DockTab {
-                                    closeable: false,
-                                    title: "WorldView".to_string(),
-                                    item: Panel::WorldView
-                                },
-                            ],
-                        }),
-                    }),
-                    last: Box::new(DockItem::Splitter {
-                        axis: Axis::Horizontal,
-                        align: SplitterAlign::Last,
-                        pos: 100.0,
-                        first: Box::new(DockItem::TabControl {
-                            current: 1,
-                            previous: 0,
-                            tabs: vec![
-                                DockTab {
-                                    closeable: false,
-                                    title: "Edit".to_string(),
-                                    item: Panel::FileEditorTarget
-                                },
-                                DockTab {
-                                    closeable: true,
-                                    title: "treeworld.rs".to_string(),
-                                    //item: Panel::FileEditor {path: "main/makepad/makepad/app/src/shaderview.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
-                                    item: Panel::FileEditor {path: "main/makepad/worlds/src/treeworld.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
-                                },
-                                DockTab {
-                                    closeable: true,
-                                    title: "skybox.rs".to_string(),
-                                    //item: Panel::FileEditor {path: "main/makepad/makepad/app/src/shaderview.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
-                                    item: Panel::FileEditor {path: "main/makepad/worlds/src/skybox.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 3}
-                                },
-                            ],
-                        }),
-                        
-                        last: Box::new(DockItem::Splitter {
-                            axis: Axis::Vertical,
-                            align: SplitterAlign::Last,
-                            pos: 200.0,
-                            first: Box::new(DockItem::TabControl {
-                                current: 0,
-                                previous: 0,
-                                tabs: vec![
-                                    DockTab {
-                                        closeable: false,
-                                        title: "Log".to_string(),
-                                        item: Panel::LogList
-                                    },
-                                ]
-                            }),
-                            last: Box::new(DockItem::TabControl {
-                                current: 0,
-                                previous: 0,
-                                tabs: vec![
-                                    DockTab {
-                                        closeable: false,
-                                        title: "WorldSelect".to_string(),
-                                        item: Panel::WorldSelect
-                                    },
-                                    DockTab {
-                                        closeable: false,
-                                        title: "Item".to_string(),
-                                        item: Panel::ItemDisplay
-                                    },
-                                    DockTab {
-                                        closeable: false,
-                                        title: "Keyboard".to_string(),
-                                        item: Panel::Keyboard
-                                    },
-                                    
-                                ]
-                            })
-                        })
-                    })
-                },
+                dock_items: Self::dock_items(200.0, 100.0),
+                dock_layouts: Some(DockLayouts::new(vec![
+                    DockLayout {name: "default".to_string(), items: Self::dock_items(200.0, 100.0)},
+                    DockLayout {name: "editor".to_string(), items: Self::dock_items(200.0, 25.0)},
+                    DockLayout {name: "build".to_string(), items: Self::dock_items(200.0, 400.0)},
+                ]).with_current("default")),
+                dock_floats: None,
             },
             makepad_windows: vec![],
             build_manager: BuildManager::new(cx),
@@ -234,6 +166,115 @@ impl MakepadApp {
         }
     }
     
+    // the dock every layout starts out as, with the width of the file panel and the height of the log
+    pub fn dock_items(sidebar_width: f32, bottom_height: f32) -> DockItem<Panel> {
+        DockItem::Splitter {
+            axis: Axis::Vertical,
+            align: SplitterAlign::First,
+            pos: sidebar_width,
+            
+            first: Box::new(DockItem::Splitter {
+                axis: Axis::Horizontal,
+                align: SplitterAlign::Last,
+                pos: 250.0,
+                first: Box::new(DockItem::TabControl {
+                    current: 0,
+                    previous: 0,
+                    tabs: vec![
+                        DockTab {
+                            closeable: false,
+                            title: "Files".to_string(),
+                            item: Panel::FileTree
+                        },
+                        DockTab {
+                            closeable: false,
+                            title: "".to_string(),
+                            item: Panel::SearchResults
+                        }
+                    ]
+                }),
+                last: Box::new(DockItem::TabControl {
+                    current: 0,
+                    previous: 0,
+                    tabs: vec![
+                        DockTab {
+                            closeable: false,
+                            title: "WorldView".to_string(),
+                            item: Panel::WorldView
+                        },
+                    ],
+                }),
+            }),
+            last: Box::new(DockItem::Splitter {
+                axis: Axis::Horizontal,
+                align: SplitterAlign::Last,
+                pos: bottom_height,
+                first: Box::new(DockItem::TabControl {
+                    current: 1,
+                    previous: 0,
+                    tabs: vec![
+                        DockTab {
+                            closeable: false,
+                            title: "Edit".to_string(),
+                            item: Panel::FileEditorTarget
+                        },
+                        DockTab {
+                            closeable: true,
+                            title: "treeworld.rs".to_string(),
+                            //item: Panel::FileEditor {path: "main/makepad/makepad/app/src/shaderview.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
+                            item: Panel::FileEditor {path: "main/makepad/worlds/src/treeworld.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
+                        },
+                        DockTab {
+                            closeable: true,
+                            title: "skybox.rs".to_string(),
+                            //item: Panel::FileEditor {path: "main/makepad/makepad/app/src/shaderview.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 2}
+                            item: Panel::FileEditor {path: "main/makepad/worlds/src/skybox.rs".to_string(), scroll_pos: Vec2::default(), editor_id: 3}
+                        },
+                    ],
+                }),
+                
+                last: Box::new(DockItem::Splitter {
+                    axis: Axis::Vertical,
+                    align: SplitterAlign::Last,
+                    pos: 200.0,
+                    first: Box::new(DockItem::TabControl {
+                        current: 0,
+                        previous: 0,
+                        tabs: vec![
+                            DockTab {
+                                closeable: false,
+                                title: "Log".to_string(),
+                                item: Panel::LogList
+                            },
+                        ]
+                    }),
+                    last: Box::new(DockItem::TabControl {
+                        current: 0,
+                        previous: 0,
+                        tabs: vec![
+                            DockTab {
+                                closeable: false,
+                                title: "WorldSelect".to_string(),
+                                item: Panel::WorldSelect
+                            },
+                            DockTab {
+                                closeable: false,
+                                title: "Item".to_string(),
+                                item: Panel::ItemDisplay
+                            },
+                            DockTab {
+                                closeable: false,
+                                title: "Keyboard".to_string(),
+                                item: Panel::Keyboard
+                            },
+                            
+                        ]
+                    })
+                })
+            })
+        }
+    }
+    
     pub fn style(cx: &mut Cx) {
         set_widget_style(cx);
         set_makepad_style(cx);
@@ -265,41 +306,39 @@ impl MakepadApp {
             Event::WebSocketMessage(wm) => {
                 self.makepad_storage.handle_websocket_message(cx, &mut self.build_manager, wm);
             }
-            Event::KeyDown(ke) => match ke.key_code {
-                KeyCode::KeyD => if ke.modifiers.logo || ke.modifiers.control {
-                    //let size = self.build_manager.search_index.calc_total_size();
-                    //println!("Text Index size {}", size);
-                },
-                KeyCode::KeyR => if ke.modifiers.logo || ke.modifiers.control {
-                    self.makepad_storage.reload_builders();
-                },
-                KeyCode::Key0 => if ke.modifiers.logo || ke.modifiers.control {
-                    cx.reset_font_atlas_and_redraw();
-                    println!("IMPLEMENT SCALE");
-                    //self.storage.settings.style_options.scale = 1.0;
-                    //self.reload_style(cx);
-                    //cx.reset_font_atlas_and_redraw();
-                    //self.storage.save_settings(cx);
-                },
-                KeyCode::Equals => if ke.modifiers.logo || ke.modifiers.control {
-                    cx.reset_font_atlas_and_redraw();
-                    println!("IMPLEMENT SCALE");
-                    //let scale = self.storage.settings.style_options.scale * 1.1;
-                    // self.storage.settings.style_options.scale = scale.min(3.0).max(0.3);
-                    //self.reload_style(cx);
-                    //cx.reset_font_atlas_and_redraw();
-                    //self.storage.save_settings(cx);
-                },
-                KeyCode::Minus => if ke.modifiers.logo || ke.modifiers.control {
-                    cx.reset_font_atlas_and_redraw();
-                    println!("IMPLEMENT SCALE");
-                    //let scale = self.storage.settings.style_options.scale / 1.1;
-                    //self.storage.settings.style_options.scale = scale.min(3.0).max(0.3);
-                    //self.reload_style(cx);
-                    //cx.reset_font_atlas_and_redraw();
-                    //self.storage.save_settings(cx);
-                },
-                _ => ()
+            Event::KeyDown(ke) => if let Some(command) = cx.keymap_command(KEYMAP_GLOBAL, ke) {
+                match command.as_str() {
+                    "reload_builders" => {
+                        self.makepad_storage.reload_builders();
+                    },
+                    "reset_zoom" => {
+                        cx.reset_font_atlas_and_redraw();
+                        println!("IMPLEMENT SCALE");
+                        //self.storage.settings.style_options.scale = 1.0;
+                        //self.reload_style(cx);
+                        //cx.reset_font_atlas_and_redraw();
+                        //self.storage.save_settings(cx);
+                    },
+                    "zoom_in" => {
+                        cx.reset_font_atlas_and_redraw();
+                        println!("IMPLEMENT SCALE");
+                        //let scale = self.storage.settings.style_options.scale * 1.1;
+                        // self.storage.settings.style_options.scale = scale.min(3.0).max(0.3);
+                        //self.reload_style(cx);
+                        //cx.reset_font_atlas_and_redraw();
+                        //self.storage.save_settings(cx);
+                    },
+                    "zoom_out" => {
+                        cx.reset_font_atlas_and_redraw();
+                        println!("IMPLEMENT SCALE");
+                        //let scale = self.storage.settings.style_options.scale / 1.1;
+                        //self.storage.settings.style_options.scale = scale.min(3.0).max(0.3);
+                        //self.reload_style(cx);
+                        //cx.reset_font_atlas_and_redraw();
+                        //self.storage.save_settings(cx);
+                    },
+                    _ => ()
+                }
             },
             Event::Signal(se) => {
                 // process network messages for hub_ui
@@ -363,6 +402,12 @@ impl MakepadApp {
                     if let Ok(utf8_data) = utf8_data {
                         if let Ok(app_state) = DeRon::deserialize_ron(utf8_data) {
                             self.makepad_state = app_state;
+                            // state saved before there were layouts gets the default ones
+                            for window_state in &mut self.makepad_state.windows {
+                                if window_state.dock_layouts.is_none() {
+                                    window_state.dock_layouts = self.makepad_window_state_template.dock_layouts.clone();
+                                }
+                            }
                             self.makepad_windows.truncate(0);
                             // create our windows with the serialized positions/size
                             for window_state in &self.makepad_state.windows {
@@ -414,6 +459,14 @@ impl MakepadApp {
                         self.makepad_storage.load_settings(cx, &ron);
                     }
                 }
+                else if let Some(utf8_data) = self.makepad_storage.keymap_file_read.resolve_utf8(fr) {
+                    // no keymap file means the default keys
+                    if let Ok(utf8_data) = utf8_data {
+                        if let Err(e) = cx.load_keymap_ron("user", utf8_data) {
+                            println!("Cannot load makepad_keymap.ron {}", e);
+                        }
+                    }
+                }
                 else {
                     self.makepad_storage.text_buffer_handle_file_read(cx, fr);
                 }
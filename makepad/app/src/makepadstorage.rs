@@ -20,7 +20,9 @@ pub struct MakepadSettings {
     pub hub_server: HubServerConfig,
     pub builders: HashMap<String, HubBuilderConfig>,
     pub builds: Vec<BuildTarget>,
+    pub build_profiles: Option<HashMap<String, HubBuildProfile >>,
     pub sync: HashMap<String, Vec<String >>,
+    pub sync_dry_run: Option<bool>,
 }
 
 impl Default for MakepadSettings {
@@ -33,6 +35,8 @@ impl Default for MakepadSettings {
             hub_server: HubServerConfig::Offline,
             builders: HashMap::new(),
             sync: HashMap::new(),
+            sync_dry_run: None,
+            build_profiles: None,
             builds: vec![]
         }
     }
@@ -53,7 +57,8 @@ impl MakepadSettings {
                         let mut workspace = HashMap::new();
                         workspace.insert("makepad".to_string(), ".".to_string());
                         workspace
-                    }
+                    },
+                    build_profiles: None
                 });
                 cfg
             },
@@ -62,6 +67,8 @@ impl MakepadSettings {
                 //sync.insert("main/makepad".to_string(), vec!["windows/makepad".to_string()]);
                 sync
             },
+            sync_dry_run: None,
+            build_profiles: None,
             builds: vec![
                 BuildTarget {
                     builder: "main".to_string(),
@@ -72,6 +79,20 @@ impl MakepadSettings {
             ]
         }
     }
+    
+    // profiles defined at the settings level are shared by all builders, a builder can override them by name
+    pub fn builder_config(&self, builder: &str) -> Option<HubBuilderConfig> {
+        let mut config = self.builders.get(builder) ?.clone();
+        if let Some(shared) = &self.build_profiles {
+            let profiles = config.build_profiles.get_or_insert_with( || HashMap::new());
+            for (name, profile) in shared {
+                if !profiles.contains_key(name) {
+                    profiles.insert(name.clone(), profile.clone());
+                }
+            }
+        }
+        Some(config)
+    }
 }
 
 #[derive(Clone, Debug, SerRon, DeRon, PartialEq)]
@@ -98,9 +119,12 @@ pub struct MakepadStorage {
     pub file_tree_file_read: FileRead,
     pub state_file_read: FileRead,
     pub settings_file_read: FileRead,
+    pub keymap_file_read: FileRead,
     pub text_buffer_path_to_id: HashMap<String, MakepadTextBufferId>,
     pub text_buffer_id_to_path: HashMap<MakepadTextBufferId, String>,
     pub text_buffers: Vec<MakepadTextBuffer>,
+    pub file_sync_jobs: Vec<FileSyncJob>,
+    pub file_sync_builders: Vec<String>,
     pub xr_channel: XRChannel,
 }
 
@@ -152,9 +176,12 @@ impl MakepadStorage {
             text_buffer_path_to_id: HashMap::new(),
             text_buffer_id_to_path: HashMap::new(),
             text_buffers: Vec::new(),
+            file_sync_jobs: Vec::new(),
+            file_sync_builders: Vec::new(),
             file_tree_file_read: FileRead::default(),
             state_file_read: FileRead::default(),
             settings_file_read: FileRead::default(),
+            keymap_file_read: FileRead::default(),
             xr_channel: XRChannel::default(),
         }
     }
@@ -167,6 +194,7 @@ impl MakepadStorage {
             
             self.state_file_read = cx.file_read("makepad_state.ron");
             self.settings_file_read = cx.file_read("makepad_settings.ron");
+            self.keymap_file_read = cx.file_read("makepad_keymap.ron");
             
             // lets start the router
             let mut hub_router = HubRouter::start_hub_router(HubLog::None);
@@ -611,6 +639,65 @@ impl MakepadStorage {
     }
     
     
+    pub fn start_file_sync(&mut self, builders: &Vec<String>) {
+        let hub_ui = self.hub_ui.as_mut().unwrap();
+        let dry_run = self.settings.sync_dry_run.unwrap_or(false);
+        let has_builder = | path: &str | builders.iter().any( | b | path.starts_with(&format!("{}/", b)));
+        // builders that just connected get fresh jobs, syncs between builders that were already there keep running
+        let old_builders = std::mem::replace(&mut self.file_sync_builders, builders.clone());
+        let is_new = | builder: &str | !old_builders.iter().any( | b | b == builder);
+        self.file_sync_jobs.retain( | job | {
+            has_builder(&format!("{}/", job.source.0)) && has_builder(&format!("{}/", job.target.0))
+                && !is_new(&job.source.0) && !is_new(&job.target.0)
+        });
+        for (source, targets) in &self.settings.sync {
+            if !has_builder(source) {
+                continue
+            }
+            for target in targets {
+                let running = self.file_sync_jobs.iter().any( | job | {
+                    format!("{}/{}", job.source.0, job.source.1) == *source && format!("{}/{}", job.target.0, job.target.1) == *target
+                });
+                if has_builder(target) && !running {
+                    let uid = hub_ui.route_send.alloc_uid();
+                    let mut out = Vec::new();
+                    self.file_sync_jobs.push(FileSyncJob::start(uid, source, target, dry_run, &mut out));
+                    for msg in out {
+                        hub_ui.route_send.send(msg);
+                    }
+                }
+            }
+        }
+    }
+    
+    pub fn handle_file_sync_msg(&mut self, cx: &mut Cx, htc: &FromHubMsg, build_manager: &mut BuildManager) -> bool {
+        let hub_ui = self.hub_ui.as_mut().unwrap();
+        let mut consumed = false;
+        let mut out = Vec::new();
+        for job in &mut self.file_sync_jobs {
+            if job.handle_hub_msg(htc, &mut out) {
+                consumed = true;
+                break;
+            }
+        }
+        for msg in out {
+            hub_ui.route_send.send(msg);
+        }
+        let mut i = 0;
+        while i < self.file_sync_jobs.len() {
+            if self.file_sync_jobs[i].is_done() {
+                let job = self.file_sync_jobs.remove(i);
+                for line in job.report {
+                    build_manager.add_log_message(cx, line);
+                }
+            }
+            else {
+                i += 1;
+            }
+        }
+        consumed
+    }
+    
     pub fn handle_hub_msg(
         &mut self,
         cx: &mut Cx,
@@ -619,6 +706,9 @@ impl MakepadStorage {
         makepad_state: &MakepadState,
         build_manager: &mut BuildManager
     ) {
+        if self.handle_file_sync_msg(cx, htc, build_manager) {
+            return
+        }
         let hub_ui = self.hub_ui.as_mut().unwrap();
         // only in ConnectUI of ourselves do we list the workspaces
         match &htc.msg {
@@ -638,10 +728,10 @@ impl MakepadStorage {
                 for builder in builders {
                     // lets look up a workspace and configure it!
                     // lets config it
-                    if let Some(builder_config) = self.settings.builders.get(builder) {
+                    if let Some(builder_config) = self.settings.builder_config(builder) {
                         hub_ui.route_send.send(ToHubMsg {
                             to: HubMsgTo::Builder(builder.clone()),
-                            msg: HubMsg::BuilderConfig {uid: uid, config: builder_config.clone()}
+                            msg: HubMsg::BuilderConfig {uid: uid, config: builder_config}
                         });
                     }
                     hub_ui.route_send.send(ToHubMsg {
@@ -680,6 +770,7 @@ impl MakepadStorage {
                         hub_ui.route_send.send(cth_msg.clone())
                     }
                 }
+                self.start_file_sync(builders);
             },
             HubMsg::BuilderFileTreeResponse {uid, tree} => if *uid == self.builders_request_uid {
                 // replace a workspace node
@@ -7,8 +7,6 @@ use crate::codeicon::*;
 use crate::searchresults::*;
 use crate::itemdisplay::*;
 use crate::liveitems::*;
-use crate::colorpicker::*;
-use crate::floatslider::*;
 
 pub fn set_makepad_style(cx: &mut Cx) {
     CodeIcon::style(cx);
@@ -17,7 +15,5 @@ pub fn set_makepad_style(cx: &mut Cx) {
     LogList::style(cx);
     SearchResults::style(cx);
     ItemDisplay::style(cx);
-    ColorPicker::style(cx);
-    FloatSlider::style(cx);
     LiveItemsView::style(cx);
 }
@@ -48,6 +48,8 @@ pub struct MakepadWindow {
     pub world_view: WorldView,
     pub close_buttons: Elements<u64, TabClose, TabClose>,
     pub dock: Dock<Panel>,
+    // one for each of the dock floats in the window state
+    pub float_windows: Vec<DockFloatWindow<Panel>>,
 }
 
 #[derive(Clone, SerRon, DeRon)]
@@ -56,6 +58,9 @@ pub struct MakepadWindowState {
     pub window_position: Vec2,
     pub window_inner_size: Vec2,
     pub dock_items: DockItem<Panel>,
+    // these are optional so state files from before them still load
+    pub dock_layouts: Option<DockLayouts<Panel>>,
+    pub dock_floats: Option<Vec<DockFloat<Panel>>>,
 }
 
 #[derive(Default, Clone, SerRon, DeRon)]
@@ -85,7 +90,9 @@ impl MakepadWindow {
             xr_control: XRControl::new(cx),
             world_view: WorldView::new(cx),
             close_buttons: Elements::new(TabClose::new(cx)),
-            dock: Dock ::new(cx),
+            dock: Dock ::new(cx)
+                .with_float_tabs(true),
+            float_windows: Vec::new(),
         }
     }
     
@@ -98,10 +105,45 @@ impl MakepadWindow {
         makepad_storage: &mut MakepadStorage,
         build_manager: &mut BuildManager
     ) {
-       
+        
+        // the windows holding tabs floated off the dock, they come from the saved state as well
+        let floats = makepad_state.windows[window_index].dock_floats.get_or_insert_with(Vec::new);
+        self.float_windows.truncate(floats.len());
+        while self.float_windows.len() < floats.len() {
+            self.float_windows.push(DockFloatWindow::new(cx));
+        }
+        let mut for_float = false;
+        let mut float_geom_change = false;
+        let mut closed_float = None;
+        for (index, (float_window, float)) in self.float_windows.iter_mut().zip(floats.iter_mut()).enumerate() {
+            match float_window.handle_dock_float(cx, event, float) {
+                DesktopWindowEvent::EventForOtherWindow => (),
+                DesktopWindowEvent::WindowClosed => closed_float = Some(index),
+                DesktopWindowEvent::WindowGeomChange(_) => {
+                    float_geom_change = true;
+                    for_float = true;
+                },
+                _ => for_float = true
+            }
+        }
+        // closing a float puts its tabs back into the dock
+        if let Some(index) = closed_float {
+            self.float_windows.remove(index);
+            let float = floats.remove(index);
+            self.dock.dock_float(cx, &mut makepad_state.windows[window_index].dock_items, &float);
+            self.ensure_unique_tab_title_for_file_editors(cx, window_index, makepad_state);
+            makepad_storage.save_state(cx, makepad_state);
+            return
+        }
+        if float_geom_change && !makepad_storage.state_file_read.is_pending() {
+            makepad_storage.save_state(cx, makepad_state);
+        }
+        
         match self.desktop_window.handle_desktop_window(cx, event) {
             DesktopWindowEvent::EventForOtherWindow => {
-                return
+                if !for_float {
+                    return
+                }
             }
             DesktopWindowEvent::WindowClosed => {
                 return
@@ -158,8 +200,13 @@ impl MakepadWindow {
                     }
                 }
             },
-            Event::KeyDown(ke) => match ke.key_code {
-                KeyCode::Backtick => if ke.modifiers.logo || ke.modifiers.control {
+            Event::KeyDown(ke) => match cx.keymap_command("build", ke).as_deref() {
+                Some("build.test") => {
+                    build_manager.tail_log_items = true;
+                    build_manager.restart_tests(cx, makepad_storage);
+                    self.show_log_tab(cx, window_index, makepad_state);
+                }
+                Some("build.run") => {
                     if build_manager.active_builds.len() == 0 {
                         build_manager.restart_build(cx, makepad_storage);
                     }
@@ -181,14 +228,23 @@ impl MakepadWindow {
             _ => ()
         }
         
+        // layout.<name> switches the dock to the layout of that name
+        if let Event::KeyDown(ke) = event {
+            if let Some(command) = cx.keymap_command("layout", ke) {
+                if let Some(name) = command.strip_prefix("layout.") {
+                    if self.switch_layout(cx, window_index, makepad_state, name) {
+                        makepad_storage.save_state(cx, makepad_state);
+                    }
+                }
+            }
+        }
+        
         if self.search_results.handle_search_input(cx, event, &mut build_manager.search_index, makepad_storage) {
             self.show_search_tab(cx, window_index, makepad_state);
         }
         
         self.world_view.handle_world_view(cx, event);
         
-        let dock_items = &mut makepad_state.windows[window_index].dock_items;
-        let mut dock_walker = self.dock.walker(dock_items);
         let mut file_tree_event = FileTreeEvent::None;
         //let mut text_editor_event = TextEditorEvent::None;
         let mut set_last_cursor = None;
@@ -198,122 +254,136 @@ impl MakepadWindow {
         
         let mut do_close_tab = None;
         
-        while let Some((item, dock_tab_ident)) = dock_walker.walk_handle_dock(cx, event) {
-            match item {
-                Panel::LogList => {
-                    match self.log_list.handle_log_list(cx, event, makepad_storage, build_manager) {
-                        LogListEvent::SelectLocMessage {loc_message, jump_to_offset} => {
-                            // just make it open an editor
-                            if loc_message.path.len()>0 {
-                                // ok so. lets lookup the path in our remap list
-                                //println!("TRYING TO SELECT FILE {} ")
-                                file_tree_event = FileTreeEvent::SelectFile {path: makepad_storage.remap_sync_path(&loc_message.path)};
+        // the panels are walked in the dock and in every float, a panel is in only one of them
+        let window_state = &mut makepad_state.windows[window_index];
+        let mut docks = vec![(&mut self.dock, &mut window_state.dock_items)];
+        for (float_window, float) in self.float_windows.iter_mut().zip(window_state.dock_floats.iter_mut().flatten()) {
+            docks.push((&mut float_window.dock, &mut float.items));
+        }
+        for (dock_index, (dock, dock_items)) in docks.into_iter().enumerate() {
+            let mut dock_walker = dock.walker(dock_items);
+            while let Some((item, dock_tab_ident)) = dock_walker.walk_handle_dock(cx, event) {
+                match item {
+                    Panel::LogList => {
+                        match self.log_list.handle_log_list(cx, event, makepad_storage, build_manager) {
+                            LogListEvent::SelectLocMessage {loc_message, jump_to_offset} => {
+                                // just make it open an editor
+                                if loc_message.path.len()>0 {
+                                    // ok so. lets lookup the path in our remap list
+                                    //println!("TRYING TO SELECT FILE {} ")
+                                    file_tree_event = FileTreeEvent::SelectFile {path: makepad_storage.remap_sync_path(&loc_message.path)};
+                                }
+                                self.item_display.display_message(cx, &loc_message);
+                                set_last_cursor = Some((jump_to_offset, jump_to_offset));
+                                show_item_display_tab = true;
+                            },
+                            LogListEvent::SelectMessages {items} => {
+                                self.item_display.display_plain_text(cx, &items);
+                                show_item_display_tab = true;
                             }
-                            self.item_display.display_message(cx, &loc_message);
-                            set_last_cursor = Some((jump_to_offset, jump_to_offset));
-                            show_item_display_tab = true;
-                        },
-                        LogListEvent::SelectMessages {items} => {
-                            self.item_display.display_plain_text(cx, &items);
-                            show_item_display_tab = true;
+                            _ => ()
                         }
-                        _ => ()
                     }
-                }
-                Panel::WorldView => {
+                    Panel::WorldView => {
                     
-                },
-                Panel::WorldSelect => {
-                    self.world_view.handle_world_select(cx, event);
-                },
-                Panel::ItemDisplay => {
-                    self.item_display.handle_item_display(cx, event);
-                }
-                Panel::SearchResults => {
-                    match self.search_results.handle_search_results(cx, event, &mut build_manager.search_index, makepad_storage) {
-                        SearchResultEvent::DisplayFile {text_buffer_id, cursor} => {
-                            set_last_cursor = Some(cursor);
-                            do_display_rust_file = Some(text_buffer_id);
-                        },
-                        SearchResultEvent::OpenFile {text_buffer_id, cursor} => {
-                            let path = makepad_storage.text_buffer_id_to_path.get(&text_buffer_id).expect("Path not found").clone();
-                            file_tree_event = FileTreeEvent::SelectFile {path: path};
-                            set_last_cursor = Some(cursor);
-                        },
-                        _ => ()
-                    }
-                }
-                Panel::Keyboard => {
-                    self.keyboard.handle_keyboard(cx, event, makepad_storage);
-                }
-                Panel::FileEditorTarget => {
-                    self.home_page.handle_home_page(cx, event);
-                }
-                Panel::FileTree => {
-                    file_tree_event = self.file_panel.handle_file_panel(cx, event);
-                }
-                Panel::FileEditor {path, scroll_pos, editor_id} => {
-                    if let Some(db) = self.close_buttons.get_mut(*editor_id){
-                        if db.handle_tab_close(cx, event) == ButtonEvent::Down{
-                            do_close_tab = Some(dock_tab_ident)
-                        };
+                    },
+                    Panel::WorldSelect => {
+                        self.world_view.handle_world_select(cx, event);
+                    },
+                    Panel::ItemDisplay => {
+                        self.item_display.handle_item_display(cx, event);
                     }
-
-                    
-                    if let Some(file_editor) = &mut self.file_editors.editors.get_mut(editor_id) {
-                        
-                        let mtb = makepad_storage.text_buffer_from_path(cx, path);
-                        let mut temporary_change_hook = false;
-                        
-                        match file_editor.handle_file_editor(cx, event, mtb, Some(&mut build_manager.search_index)) {
-                            TextEditorEvent::Search(search) => {
-                                do_search = Some((Some(search), mtb.text_buffer_id, true, false));
-                            }
-                            TextEditorEvent::Decl(search) => {
-                                do_search = Some((Some(search), mtb.text_buffer_id, false, false));
-                            }
-                            TextEditorEvent::Escape => {
-                                do_search = Some((Some("".to_string()), mtb.text_buffer_id, false, true));
-                            }
-                            TextEditorEvent::Change => {
-                                // lets post a new file to our local thing
-                                temporary_change_hook = true;
-                                // and send over the cursor change
-                                //do_search = Some((None, MakepadTextBufferId(0), false, false));
-                            }
-                            TextEditorEvent::LagChange => {
-                                makepad_storage.text_buffer_file_write(cx, path);
-                                if makepad_storage.settings.build_on_save {
-                                    build_manager.restart_build(cx, makepad_storage);
-                                }
+                    Panel::SearchResults => {
+                        match self.search_results.handle_search_results(cx, event, &mut build_manager.search_index, makepad_storage) {
+                            SearchResultEvent::DisplayFile {text_buffer_id, cursor} => {
+                                set_last_cursor = Some(cursor);
+                                do_display_rust_file = Some(text_buffer_id);
                             },
-                            TextEditorEvent::CursorMove => {
-                                // lets send over the cursor set.
-                                temporary_change_hook = true;
+                            SearchResultEvent::OpenFile {text_buffer_id, cursor} => {
+                                let path = makepad_storage.text_buffer_id_to_path.get(&text_buffer_id).expect("Path not found").clone();
+                                file_tree_event = FileTreeEvent::SelectFile {path: path};
+                                set_last_cursor = Some(cursor);
                             },
                             _ => ()
                         }
-                        *scroll_pos = file_editor.get_scroll_pos(cx);
-                        if temporary_change_hook {
-                            let mtb = makepad_storage.text_buffer_from_path(cx, path);
-                            let mm = MakepadChannelMessage::ChangeAll {
-                                path: path.to_string(),
-                                code: mtb.text_buffer.get_as_string(),
-                                cursors: file_editor.get_text_editor().cursors.clone()
+                    }
+                    Panel::Keyboard => {
+                        self.keyboard.handle_keyboard(cx, event, makepad_storage);
+                    }
+                    Panel::FileEditorTarget => {
+                        self.home_page.handle_home_page(cx, event);
+                    }
+                    Panel::FileTree => {
+                        file_tree_event = self.file_panel.handle_file_panel(cx, event);
+                    }
+                    Panel::FileEditor {path, scroll_pos, editor_id} => {
+                        if let Some(db) = self.close_buttons.get_mut(*editor_id){
+                            if db.handle_tab_close(cx, event) == ButtonEvent::Down{
+                                do_close_tab = Some((dock_index, dock_tab_ident))
                             };
+                        }
+                        
+                    
+                        if let Some(file_editor) = &mut self.file_editors.editors.get_mut(editor_id) {
+                        
+                            let mtb = makepad_storage.text_buffer_from_path(cx, path);
+                            let mut temporary_change_hook = false;
+                        
+                            match file_editor.handle_file_editor(cx, event, mtb, Some(&mut build_manager.search_index)) {
+                                TextEditorEvent::Search(search) => {
+                                    do_search = Some((Some(search), mtb.text_buffer_id, true, false));
+                                }
+                                TextEditorEvent::Decl(search) => {
+                                    do_search = Some((Some(search), mtb.text_buffer_id, false, false));
+                                }
+                                TextEditorEvent::Escape => {
+                                    do_search = Some((Some("".to_string()), mtb.text_buffer_id, false, true));
+                                }
+                                TextEditorEvent::Change => {
+                                    // lets post a new file to our local thing
+                                    temporary_change_hook = true;
+                                    // and send over the cursor change
+                                    //do_search = Some((None, MakepadTextBufferId(0), false, false));
+                                }
+                                TextEditorEvent::LagChange => {
+                                    makepad_storage.text_buffer_file_write(cx, path);
+                                    if makepad_storage.settings.build_on_save {
+                                        build_manager.restart_build(cx, makepad_storage);
+                                    }
+                                },
+                                TextEditorEvent::CursorMove => {
+                                    // lets send over the cursor set.
+                                    temporary_change_hook = true;
+                                },
+                                _ => ()
+                            }
+                            *scroll_pos = file_editor.get_scroll_pos(cx);
+                            if temporary_change_hook {
+                                let mtb = makepad_storage.text_buffer_from_path(cx, path);
+                                let mm = MakepadChannelMessage::ChangeAll {
+                                    path: path.to_string(),
+                                    code: mtb.text_buffer.get_as_string(),
+                                    cursors: file_editor.get_text_editor().cursors.clone()
+                                };
                             
-                            makepad_storage.websocket_channels.send_directly(
-                                "/channel/index.html",
-                                mm.serialize_bin()
-                            );
+                                makepad_storage.websocket_channels.send_directly(
+                                    "/channel/index.html",
+                                    mm.serialize_bin()
+                                );
+                            }
                         }
                     }
                 }
             }
         }
         
-        if let Some(dock_tab_ident) = do_close_tab{
-            self.dock.close_tab(cx, dock_tab_ident);
+        if let Some((dock_index, dock_tab_ident)) = do_close_tab {
+            if dock_index == 0 {
+                self.dock.close_tab(cx, dock_tab_ident);
+            }
+            else {
+                self.float_windows[dock_index - 1].dock.close_tab(cx, dock_tab_ident);
+            }
         }
         
         if let Some((search, first_tbid, focus, escape)) = do_search {
@@ -381,40 +451,72 @@ impl MakepadWindow {
             _ => {}
         }
         
-        let dock_items = &mut makepad_state.windows[window_index].dock_items;
-        match self.dock.handle_dock(cx, event, dock_items) {
-            DockEvent::DockChanged => { // thats a bit bland event. lets let the thing know which file closed
-                makepad_storage.save_state(cx, makepad_state);
-            }
-            DockEvent::DockTabClosed => {
-                self.ensure_unique_tab_title_for_file_editors(cx, window_index, makepad_state);
-                makepad_storage.save_state(cx, makepad_state);
-            }
-            DockEvent::DockTabCloned {tab_control_id, tab_id} => {
-                // lets change up our editor_id
-                let max_id = self.file_editors.highest_file_editor_id();
-                let mut dock_walker = self.dock.walker(dock_items);
-                while let Some((ctrl_id, dock_item)) = dock_walker.walk_dock_item() {
-                    match dock_item {
-                        DockItem::TabControl {tabs, ..} => if ctrl_id == tab_control_id {
-                            if let Some(tab) = tabs.get_mut(tab_id) {
-                                match &mut tab.item {
-                                    Panel::FileEditor {editor_id, ..} => {
-                                        // we need to make a new editor_id here.
-                                        *editor_id = max_id + 1;
-                                        break;
-                                        // and now it needs to scroll the new one....
-                                    },
-                                    _ => ()
+        let window_state = &mut makepad_state.windows[window_index];
+        let mut docks = vec![(&mut self.dock, &mut window_state.dock_items)];
+        for (float_window, float) in self.float_windows.iter_mut().zip(window_state.dock_floats.iter_mut().flatten()) {
+            docks.push((&mut float_window.dock, &mut float.items));
+        }
+        let max_id = self.file_editors.highest_file_editor_id();
+        let mut dock_changed = false;
+        let mut tab_closed = false;
+        let mut tab_floated = false;
+        let mut empty_floats = Vec::new();
+        for (dock_index, (dock, dock_items)) in docks.into_iter().enumerate() {
+            match dock.handle_dock(cx, event, dock_items) {
+                DockEvent::DockChanged => { // thats a bit bland event. lets let the thing know which file closed
+                    dock_changed = true;
+                }
+                DockEvent::DockTabClosed => {
+                    // a float goes when its last tab does
+                    if dock_index > 0 && dock_items.tabs().len() == 0 {
+                        empty_floats.push(dock_index - 1);
+                    }
+                    tab_closed = true;
+                }
+                DockEvent::DockTabCloned {tab_control_id, tab_id} => {
+                    // lets change up our editor_id
+                    let mut dock_walker = dock.walker(dock_items);
+                    while let Some((ctrl_id, dock_item)) = dock_walker.walk_dock_item() {
+                        match dock_item {
+                            DockItem::TabControl {tabs, ..} => if ctrl_id == tab_control_id {
+                                if let Some(tab) = tabs.get_mut(tab_id) {
+                                    match &mut tab.item {
+                                        Panel::FileEditor {editor_id, ..} => {
+                                            // we need to make a new editor_id here.
+                                            *editor_id = max_id + 1;
+                                            break;
+                                            // and now it needs to scroll the new one....
+                                        },
+                                        _ => ()
+                                    }
                                 }
-                            }
-                        },
-                        _ => ()
+                            },
+                            _ => ()
+                        }
                     }
+                    dock_changed = true;
+                },
+                DockEvent::DockTabFloated => {
+                    tab_floated = true;
                 }
-                makepad_storage.save_state(cx, makepad_state);
-            },
-            _ => ()
+                _ => ()
+            }
+        }
+        if tab_floated {
+            if let Some(float) = self.dock.take_floated() {
+                makepad_state.windows[window_index].dock_floats.get_or_insert_with(Vec::new).push(float);
+                self.float_windows.push(DockFloatWindow::new(cx));
+                cx.redraw_child_area(Area::All);
+            }
+        }
+        for index in empty_floats {
+            self.float_windows[index].desktop_window.window.close_window(cx);
+        }
+        if tab_closed {
+            self.ensure_unique_tab_title_for_file_editors(cx, window_index, makepad_state);
+        }
+        if dock_changed || tab_closed || tab_floated {
+            makepad_storage.save_state(cx, makepad_state);
         }
     }
     
@@ -427,88 +529,117 @@ impl MakepadWindow {
         makepad_storage: &mut MakepadStorage,
         build_manager: &mut BuildManager
     ) {
-        if self.desktop_window.begin_desktop_window(cx, Some(menu)).is_err() {return}
-        
-        self.dock.draw_dock(cx);
-        
-        let dock_items = &mut makepad_state.windows[window_index].dock_items;
-        let mut dock_walker = self.dock.walker(dock_items);
-        let file_panel = &mut self.file_panel;
-        let search_results = &mut self.search_results;
-        let item_display = &mut self.item_display;
-        while let Some(item) = dock_walker.walk_draw_dock(cx, | cx, tab_control, tab, selected | {
-            // this draws the tabs, so we can customimze it
-            match tab.item {
-                Panel::FileTree => {
-                    let tab = tab_control.get_draw_tab(cx, &tab.title, selected/*, tab.closeable*/);
-                    if tab.begin_tab(cx).is_ok() {
-                        file_panel.draw_file_panel_tab(cx);
-                        tab.end_tab(cx);
-                    };
-                }
-                Panel::SearchResults => {
-                    let tab = tab_control.get_draw_tab(cx, &tab.title, selected/*, tab.closeable*/);
-                    if tab.begin_tab(cx).is_ok() {
-                        search_results.draw_search_result_tab(cx, &build_manager.search_index);
-                        tab.end_tab(cx);
-                    };
-                }
-                _ => tab_control.draw_tab(cx, &tab.title, selected/*, tab.closeable*/)
+        // the main window and then the floats, each with its own dock over the same panels
+        let window_state = &mut makepad_state.windows[window_index];
+        let floats = window_state.dock_floats.get_or_insert_with(Vec::new);
+        for dock_index in 0..self.float_windows.len().min(floats.len()) + 1 {
+            let (dock, dock_items) = if dock_index == 0 {
+                if self.desktop_window.begin_desktop_window(cx, Some(menu)).is_err() {continue}
+                (&mut self.dock, &mut window_state.dock_items)
             }
-        }) {
-            match item {
-                Panel::WorldView => {
-                    self.world_view.draw_world_view_2d(cx);
-                },
-                Panel::WorldSelect => {
-                    self.world_view.draw_world_select(cx);
-                }
-                Panel::LogList => {
-                    self.log_list.draw_log_list(cx, build_manager);
-                }
-                Panel::SearchResults => {
-                    search_results.draw_search_results(cx, makepad_storage);
-                }
-                Panel::ItemDisplay => {
-                    item_display.draw_item_display(cx);
-                }
-                Panel::Keyboard => {
-                    self.keyboard.draw_keyboard(cx);
-                }
-                Panel::FileEditorTarget => {
-                    self.home_page.draw_home_page(cx);
-                }
-                Panel::FileTree => {
-                    file_panel.draw_file_panel(cx);
-                }
-                Panel::FileEditor {path, scroll_pos, editor_id} => {
+            else {
+                let float_window = &mut self.float_windows[dock_index - 1];
+                let float = &mut floats[dock_index - 1];
+                if float_window.begin_dock_float(cx, float).is_err() {continue}
+                (&mut float_window.dock, &mut float.items)
+            };
+            
+            dock.draw_dock(cx);
+            
+            let mut dock_walker = dock.walker(dock_items);
+            let file_panel = &mut self.file_panel;
+            let search_results = &mut self.search_results;
+            let item_display = &mut self.item_display;
+            while let Some(item) = dock_walker.walk_draw_dock(cx, | cx, tab_control, tab, selected | {
+                // this draws the tabs, so we can customimze it
+                match tab.item {
+                    Panel::FileTree => {
+                        let tab = tab_control.get_draw_tab(cx, &tab.title, selected/*, tab.closeable*/);
+                        if tab.begin_tab(cx).is_ok() {
+                            file_panel.draw_file_panel_tab(cx);
+                            tab.end_tab(cx);
+                        };
+                    }
+                    Panel::SearchResults => {
+                        let tab = tab_control.get_draw_tab(cx, &tab.title, selected/*, tab.closeable*/);
+                        if tab.begin_tab(cx).is_ok() {
+                            search_results.draw_search_result_tab(cx, &build_manager.search_index);
+                            tab.end_tab(cx);
+                        };
+                    }
+                    _ => tab_control.draw_tab(cx, &tab.title, selected/*, tab.closeable*/)
+                }
+            }) {
+                match item {
+                    Panel::WorldView => {
+                        self.world_view.draw_world_view_2d(cx);
+                    },
+                    Panel::WorldSelect => {
+                        self.world_view.draw_world_select(cx);
+                    }
+                    Panel::LogList => {
+                        self.log_list.draw_log_list(cx, build_manager);
+                    }
+                    Panel::SearchResults => {
+                        search_results.draw_search_results(cx, makepad_storage);
+                    }
+                    Panel::ItemDisplay => {
+                        item_display.draw_item_display(cx);
+                    }
+                    Panel::Keyboard => {
+                        self.keyboard.draw_keyboard(cx);
+                    }
+                    Panel::FileEditorTarget => {
+                        self.home_page.draw_home_page(cx);
+                    }
+                    Panel::FileTree => {
+                        file_panel.draw_file_panel(cx);
+                    }
+                    Panel::FileEditor {path, scroll_pos, editor_id} => {
                     
-                    let text_buffer = makepad_storage.text_buffer_from_path(cx, path);
-                    let (file_editor, is_new) = self.file_editors.get_file_editor_for_path(path, *editor_id);
-                    if is_new {
-                        file_editor.set_scroll_pos_on_load(*scroll_pos);
+                        let text_buffer = makepad_storage.text_buffer_from_path(cx, path);
+                        let (file_editor, is_new) = self.file_editors.get_file_editor_for_path(path, *editor_id);
+                        if is_new {
+                            file_editor.set_scroll_pos_on_load(*scroll_pos);
+                        }
+                        file_editor.draw_file_editor(cx, text_buffer, &mut build_manager.search_index);
+                        
+                        // draw the little editor close button over it
+                        self.close_buttons.get_draw(cx, *editor_id, |_,t| t.clone()).draw_tab_close(cx);
                     }
-                    file_editor.draw_file_editor(cx, text_buffer, &mut build_manager.search_index);
-
-                    // draw the little editor close button over it
-                    self.close_buttons.get_draw(cx, *editor_id, |_,t| t.clone()).draw_tab_close(cx);
                 }
             }
+            
+            if dock_index == 0 {
+                if self.desktop_window.window.xr_is_presenting(cx) {
+                    self.world_view.draw_world_view_3d(cx);
+                    self.xr_control.draw_xr_control(cx);
+                }
+                /*
+                let mut di = DrawImage::new(cx, default_shader!()).with_draw_depth(100.);
+                di.texture = Texture2D(Some(cx.fonts_atlas.texture_id));
+                di.alpha = 1.0;
+                di.pt1 = vec2(0.,0.);
+                di.pt2 = vec2(1.,0.2);
+                di.draw_quad_abs(cx, Rect{pos:vec2(100.,100.), size:cx.get_turtle_rect().size*0.5});
+                */
+                self.desktop_window.end_desktop_window(cx);
+            }
+            else {
+                self.float_windows[dock_index - 1].end_dock_float(cx);
+            }
         }
-        
-        if self.desktop_window.window.xr_is_presenting(cx) {
-            self.world_view.draw_world_view_3d(cx);
-            self.xr_control.draw_xr_control(cx);
-        }
-        /*
-        let mut di = DrawImage::new(cx, default_shader!()).with_draw_depth(100.);
-        di.texture = Texture2D(Some(cx.fonts_atlas.texture_id));
-        di.alpha = 1.0;
-        di.pt1 = vec2(0.,0.);
-        di.pt2 = vec2(1.,0.2);
-        di.draw_quad_abs(cx, Rect{pos:vec2(100.,100.), size:cx.get_turtle_rect().size*0.5});
-        */
-        self.desktop_window.end_desktop_window(cx);
+    }
+    
+    // switches the dock to a named layout, returns false if the window has no layout of that name
+    pub fn switch_layout(&mut self, cx: &mut Cx, window_index: usize, makepad_state: &mut MakepadState, name: &str) -> bool {
+        let window_state = &mut makepad_state.windows[window_index];
+        let dock_layouts = if let Some(dock_layouts) = &mut window_state.dock_layouts {dock_layouts} else {return false};
+        if !self.dock.switch_to_layout(cx, &mut window_state.dock_items, dock_layouts, name) {
+            return false
+        }
+        self.ensure_unique_tab_title_for_file_editors(cx, window_index, makepad_state);
+        true
     }
     
     pub fn ensure_unique_tab_title_for_file_editors(&mut self, cx: &mut Cx, window_index: usize, makepad_state: &mut MakepadState) {
@@ -190,17 +190,17 @@ impl SearchResults {
         // global key handle
         match event {
             Event::KeyDown(ke) => if self.search_input.text_editor.has_key_focus(cx) {
-                match ke.key_code {
-                    KeyCode::ArrowDown => {
+                match cx.keymap_command(KEYMAP_EDITOR, ke).as_deref() {
+                    Some("editor.move_down") => {
                         select = self.list.get_next_single_selection();
                         self.list.scroll_item_in_view = select.item_index();
                     },
-                    KeyCode::ArrowUp => {
+                    Some("editor.move_up") => {
                         // lets find the
                         select = self.list.get_prev_single_selection();
                         self.list.scroll_item_in_view = select.item_index();
                     },
-                    KeyCode::Return => {
+                    Some("editor.newline") => {
                         if self.list.selection.len()>0 {
                             select = ListSelect::Single(self.list.selection[0]);
                             dblclick = true;
@@ -370,9 +370,11 @@ impl SearchResultDraw {
                     bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
                     ease: Ease::Lin,
                     keys: vec![(1.0, default_color)],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -396,9 +398,11 @@ impl SearchResultDraw {
                     bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
                     ease: Ease::Lin,
                     keys: vec![(0.0, over_color)],
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 }
-            ]
+            ],
+            events: vec![]
         }
     }
     
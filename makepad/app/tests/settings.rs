@@ -0,0 +1,36 @@
+// the config a builder gets from the settings, with the build profiles shared by every builder merged in
+use makepad_app::makepadstorage::*;
+use makepad_hub::*;
+use std::collections::HashMap;
+
+fn profiles(list: &[(&str, HubBuildProfile)]) -> HashMap<String, HubBuildProfile> {
+    list.iter().map( | (name, profile) | (name.to_string(), profile.clone())).collect()
+}
+
+#[test]
+fn builder_config() {
+    let release = HubBuildProfile {release: Some(true), ..HubBuildProfile::new()};
+    let check = HubBuildProfile {check: Some(true), ..HubBuildProfile::new()};
+    let mut settings = MakepadSettings::initial();
+    
+    // a builder that isn't in the settings has no config
+    assert_eq!(settings.builder_config("nothing"), None);
+    // without shared profiles the builder config goes out as it is
+    assert_eq!(settings.builder_config("main"), Some(settings.builders["main"].clone()));
+    assert_eq!(settings.builder_config("main").unwrap().build_profiles, None);
+    
+    // a builder without profiles of its own gets the shared ones
+    settings.build_profiles = Some(profiles(&[("fast", release.clone()), ("lint", check.clone())]));
+    assert_eq!(
+        settings.builder_config("main").unwrap().build_profiles,
+        Some(profiles(&[("fast", release.clone()), ("lint", check.clone())]))
+    );
+    
+    // and one with its own keeps them over the shared ones of the same name
+    settings.builders.get_mut("main").unwrap().build_profiles = Some(profiles(&[("fast", HubBuildProfile::new())]));
+    let config = settings.builder_config("main").unwrap();
+    assert_eq!(config.build_profiles, Some(profiles(&[("fast", HubBuildProfile::new()), ("lint", check.clone())])));
+    assert_eq!(config.workspaces, settings.builders["main"].workspaces);
+    // the settings themselves are left alone
+    assert_eq!(settings.builders["main"].build_profiles, Some(profiles(&[("fast", HubBuildProfile::new())])));
+}
@@ -0,0 +1,117 @@
+// the job queue of a builder daemon. builds run one at a time, identical requests
+// share a job, and a running job that got its sources written to is restarted
+// by the next identical request instead of being joined
+use crate::hubmsg::*;
+
+pub struct BuildQueueJob {
+    pub from: HubAddr,
+    pub job: HubBuildJob,
+    pub stale: bool,
+}
+
+pub enum BuildQueueAction {
+    Queued,
+    Merged {uid: HubUid},
+    Superseded {kill_uid: HubUid},
+}
+
+pub enum BuildQueueCancel {
+    NotQueued,
+    Detached,
+    Removed,
+    Kill {uid: HubUid},
+}
+
+#[derive(Default)]
+pub struct BuildQueue {
+    pub jobs: Vec<BuildQueueJob>,
+}
+
+impl BuildQueue {
+
+    pub fn push(&mut self, from: HubAddr, uid: HubUid, workspace: &str, package: &str, config: &str) -> BuildQueueAction {
+        let same = self.jobs.iter().position( | j | j.job.workspace == workspace && j.job.package == package && j.job.config == config);
+        let mut uids = vec![uid];
+        let mut action = BuildQueueAction::Queued;
+        if let Some(index) = same {
+            let job = &mut self.jobs[index];
+            if !job.job.running || !job.stale {
+                job.job.uids.push(uid);
+                return BuildQueueAction::Merged {uid: job.job.uid}
+            }
+            // the running build is compiling old sources, everyone waiting on it moves over
+            let old = self.jobs.remove(index);
+            let kill_uid = old.job.uid;
+            uids.extend(old.job.uids.into_iter().filter( | u | *u != kill_uid));
+            action = BuildQueueAction::Superseded {kill_uid: kill_uid};
+        }
+        self.jobs.push(BuildQueueJob {
+            from: from,
+            job: HubBuildJob {
+                uid: uid,
+                uids: uids,
+                workspace: workspace.to_string(),
+                package: package.to_string(),
+                config: config.to_string(),
+                running: false
+            },
+            stale: false
+        });
+        action
+    }
+
+    pub fn cancel(&mut self, uid: HubUid) -> BuildQueueCancel {
+        let index = if let Some(index) = self.jobs.iter().position( | j | j.job.uids.contains(&uid)) {index} else {
+            return BuildQueueCancel::NotQueued
+        };
+        let job = &mut self.jobs[index];
+        job.job.uids.retain( | u | *u != uid);
+        if job.job.uids.len() > 0 {
+            return BuildQueueCancel::Detached
+        }
+        if job.job.running {
+            // the job is removed when its build returns, until then it is stale so the
+            // next identical request gets a job of its own instead of joining one that dies
+            job.stale = true;
+            return BuildQueueCancel::Kill {uid: job.job.uid}
+        }
+        self.jobs.remove(index);
+        BuildQueueCancel::Removed
+    }
+
+    // a file in the workspace changed, running builds of it are out of date
+    pub fn mark_stale(&mut self, workspace: &str) {
+        for job in &mut self.jobs {
+            if job.job.running && job.job.workspace == workspace {
+                job.stale = true;
+            }
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.jobs.iter().any( | j | j.job.running)
+    }
+
+    pub fn start_next(&mut self) -> Option<(HubAddr, HubBuildJob)> {
+        if self.is_running() {
+            return None
+        }
+        if let Some(job) = self.jobs.iter_mut().find( | j | !j.job.running) {
+            job.job.running = true;
+            return Some((job.from, job.job.clone()))
+        }
+        None
+    }
+
+    // returns every uid that was waiting on the job
+    pub fn finish(&mut self, uid: HubUid) -> Vec<HubUid> {
+        if let Some(index) = self.jobs.iter().position( | j | j.job.running && j.job.uid == uid) {
+            return self.jobs.remove(index).job.uids
+        }
+        Vec::new()
+    }
+
+    pub fn state(&self) -> Vec<HubBuildJob> {
+        self.jobs.iter().map( | j | j.job.clone()).collect()
+    }
+}
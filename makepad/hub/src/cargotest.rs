@@ -0,0 +1,161 @@
+// parses the human readable output of libtest as produced by cargo test
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CargoTestResult {
+    Ok {suite: String, name: String},
+    Ignored {suite: String, name: String},
+    Failed {suite: String, name: String, panic: Option<CargoTestPanic>, output: Vec<String>}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CargoTestPanic {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CargoTestParser {
+    pub suite: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    failed_names: Vec<String>,
+    capture: Option<(String, Vec<String >) >,
+}
+
+impl CargoTestParser {
+
+    // cargo prints the test binary it is about to run on stderr
+    pub fn parse_stderr_line(&mut self, line: &str) -> bool {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Running ") {
+            let rest = &trimmed["Running ".len()..];
+            // newer cargo: Running unittests src/lib.rs (target/debug/deps/name-hash)
+            self.suite = if let Some(open) = rest.find(" (") {
+                rest[0..open].to_string()
+            }
+            else {
+                rest.to_string()
+            };
+            return true
+        }
+        if trimmed.starts_with("Doc-tests ") {
+            self.suite = trimmed.to_string();
+            return true
+        }
+        false
+    }
+
+    pub fn parse_stdout_line(&mut self, line: &str, results: &mut Vec<CargoTestResult>) {
+        let line = line.trim_end_matches( | c | c == '\n' || c == '\r');
+
+        // captured output of a failed test
+        if line.starts_with("---- ") && line.ends_with(" ----") {
+            self.flush_capture(results);
+            let inner = &line[5..line.len() - 5];
+            let name = if let Some(pos) = inner.rfind(' ') {&inner[0..pos]}else {inner};
+            self.capture = Some((name.to_string(), Vec::new()));
+            return
+        }
+        if line == "failures:" || line == "successes:" || line.starts_with("test result:") {
+            self.flush_capture(results);
+            if line.starts_with("test result:") {
+                self.flush_failed(results);
+            }
+            return
+        }
+        if let Some((_, output)) = &mut self.capture {
+            output.push(line.to_string());
+            return
+        }
+
+        if line.starts_with("test ") {
+            let rest = &line[5..];
+            if let Some(pos) = rest.rfind(" ... ") {
+                let name = rest[0..pos].to_string();
+                let status = &rest[pos + 5..];
+                if status == "ok" {
+                    self.passed += 1;
+                    results.push(CargoTestResult::Ok {suite: self.suite.clone(), name: name});
+                }
+                else if status.starts_with("ignored") {
+                    self.ignored += 1;
+                    results.push(CargoTestResult::Ignored {suite: self.suite.clone(), name: name});
+                }
+                else if status.starts_with("FAILED") {
+                    self.failed += 1;
+                    self.failed_names.push(name);
+                }
+            }
+        }
+    }
+
+    // flushes anything left over when the process terminates
+    pub fn finish(&mut self, results: &mut Vec<CargoTestResult>) {
+        self.flush_capture(results);
+        self.flush_failed(results);
+    }
+
+    fn flush_capture(&mut self, results: &mut Vec<CargoTestResult>) {
+        if let Some((name, output)) = self.capture.take() {
+            if let Some(index) = self.failed_names.iter().position( | n | *n == name) {
+                self.failed_names.remove(index);
+                results.push(CargoTestResult::Failed {
+                    suite: self.suite.clone(),
+                    panic: parse_panic(&name, &output),
+                    name: name,
+                    output: output
+                });
+            }
+        }
+    }
+
+    // failed tests that didnt produce a captured output block
+    fn flush_failed(&mut self, results: &mut Vec<CargoTestResult>) {
+        for name in self.failed_names.drain(..) {
+            results.push(CargoTestResult::Failed {
+                suite: self.suite.clone(),
+                name: name,
+                panic: None,
+                output: Vec::new()
+            });
+        }
+    }
+}
+
+fn parse_location(loc: &str) -> Option<(String, usize, usize)> {
+    let mut parts = loc.rsplitn(3, ':');
+    let column = parts.next()?.parse::<usize>().ok()?;
+    let line = parts.next()?.parse::<usize>().ok()?;
+    let path = parts.next()?;
+    Some((path.replace("\\", "/"), line, column))
+}
+
+fn parse_panic(name: &str, output: &Vec<String>) -> Option<CargoTestPanic> {
+    let prefix = format!("thread '{}'", name);
+    for (index, line) in output.iter().enumerate() {
+        if !line.starts_with(&prefix) {
+            continue
+        }
+        // newer toolchains put the thread id between the name and 'panicked at'
+        let rest = if let Some(pos) = line.find(" panicked at ") {&line[pos + " panicked at ".len()..]}else {continue};
+        // old style: panicked at 'message', src/lib.rs:10:5
+        if rest.starts_with("'") {
+            if let Some(end) = rest.rfind("', ") {
+                if let Some((path, line, column)) = parse_location(&rest[end + 3..]) {
+                    return Some(CargoTestPanic {path, line, column, message: rest[1..end].to_string()})
+                }
+            }
+        }
+        // new style: panicked at src/lib.rs:10:5:\nmessage
+        else if let Some((path, line, column)) = parse_location(rest.trim_end_matches(':')) {
+            let message = output[index + 1..].iter()
+                .take_while( | l | !l.starts_with("note: ") && !l.starts_with("stack backtrace:"))
+                .cloned().collect::<Vec<String >> ().join("\n");
+            return Some(CargoTestPanic {path, line, column, message})
+        }
+    }
+    None
+}
@@ -0,0 +1,389 @@
+use makepad_microserde::*;
+use crate::hubmsg::*;
+use crate::hubclient::*;
+use std::collections::HashMap;
+
+pub const SYNC_BLOCK_SIZE: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileSyncOp {
+    Create {path: String},
+    Change {path: String},
+    Delete {path: String},
+    Rename {path: String, new_path: String},
+}
+
+impl FileSyncOp {
+    pub fn describe(&self) -> String {
+        match self {
+            FileSyncOp::Create {path} => format!("create {}", path),
+            FileSyncOp::Change {path} => format!("change {}", path),
+            FileSyncOp::Delete {path} => format!("delete {}", path),
+            FileSyncOp::Rename {path, new_path} => format!("rename {} -> {}", path, new_path),
+        }
+    }
+}
+
+// 256 bits of the digest of a block, with its length mixed in so trailing zeros count
+#[derive(Clone, Copy, Debug, Default, PartialEq, SerBin, DeBin)]
+pub struct BlockDigest {
+    pub buf: [u64; 4]
+}
+
+impl BlockDigest {
+    pub fn from_block(block: &[u8]) -> BlockDigest {
+        let mut digest = Digest::default();
+        digest.digest_buffer(block);
+        digest.digest_buffer(&(block.len() as u64).to_le_bytes());
+        BlockDigest {buf: [digest.buf[0], digest.buf[1], digest.buf[2], digest.buf[3]]}
+    }
+}
+
+pub fn file_block_digests(data: &[u8], block_size: usize) -> Vec<BlockDigest> {
+    data.chunks(block_size).map( | block | BlockDigest::from_block(block)).collect()
+}
+
+pub fn file_content_digest(data: &[u8]) -> Digest {
+    let mut digest = Digest::default();
+    digest.digest_buffer(data);
+    digest
+}
+
+// returns the blocks of data the target doesnt have yet
+pub fn file_patch_blocks(data: &[u8], block_size: usize, target_digests: &[BlockDigest]) -> Vec<(usize, Vec<u8>)> {
+    let mut blocks = Vec::new();
+    for (index, block) in data.chunks(block_size).enumerate() {
+        if index >= target_digests.len() || target_digests[index] != BlockDigest::from_block(block) {
+            blocks.push((index, block.to_vec()));
+        }
+    }
+    blocks
+}
+
+pub fn apply_file_patch(old: &[u8], len: usize, block_size: usize, blocks: &[(usize, Vec<u8>)]) -> Vec<u8> {
+    let mut data = old.to_vec();
+    data.resize(len, 0);
+    for (index, block) in blocks {
+        let start = index * block_size;
+        let end = (start + block.len()).min(len);
+        if start < end {
+            data[start..end].copy_from_slice(&block[0..end - start]);
+        }
+    }
+    data
+}
+
+fn flatten_file_tree(node: &BuilderFileTreeNode, base: &str, out: &mut HashMap<String, Option<Digest >>) {
+    match node {
+        BuilderFileTreeNode::File {name, digest} => {
+            out.insert(format!("{}{}", base, name), digest.as_ref().map( | d | *d.clone()));
+        },
+        BuilderFileTreeNode::Folder {name, folder, ..} => {
+            let base = format!("{}{}/", base, name);
+            for node in folder {
+                flatten_file_tree(node, &base, out);
+            }
+        }
+    }
+}
+
+// diffs two workspace trees, both need to be created with digests
+pub fn diff_file_trees(source: &BuilderFileTreeNode, target: &BuilderFileTreeNode) -> Vec<FileSyncOp> {
+    let mut source_files = HashMap::new();
+    let mut target_files = HashMap::new();
+    if let BuilderFileTreeNode::Folder {folder, ..} = source {
+        for node in folder {flatten_file_tree(node, "", &mut source_files)}
+    }
+    if let BuilderFileTreeNode::Folder {folder, ..} = target {
+        for node in folder {flatten_file_tree(node, "", &mut target_files)}
+    }
+
+    let mut created = Vec::new();
+    let mut ops = Vec::new();
+    for (path, digest) in &source_files {
+        match target_files.get(path) {
+            None => created.push(path.clone()),
+            Some(target_digest) => if digest.is_none() || digest != target_digest {
+                ops.push(FileSyncOp::Change {path: path.clone()})
+            }
+        }
+    }
+    let mut deleted: Vec<String> = target_files.keys().filter( | path | !source_files.contains_key(*path)).cloned().collect();
+    created.sort();
+    deleted.sort();
+
+    // a deleted file with the same content as a created one is a rename
+    for path in created {
+        let digest = &source_files[&path];
+        let renamed = if digest.is_some() {
+            deleted.iter().position( | old | target_files[old] == *digest)
+        } else {None};
+        if let Some(index) = renamed {
+            ops.push(FileSyncOp::Rename {path: deleted.remove(index), new_path: path});
+        }
+        else {
+            ops.push(FileSyncOp::Create {path})
+        }
+    }
+    for path in deleted {
+        ops.push(FileSyncOp::Delete {path})
+    }
+    ops.sort_by( | a, b | a.describe().cmp(&b.describe()));
+    ops
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileSyncState {
+    WaitTrees,
+    Transfer,
+    Done
+}
+
+#[derive(Default)]
+struct FileSyncPending {
+    data: Option<Vec<u8 >>,
+    digests: Option<Vec<BlockDigest >>,
+}
+
+// syncs one builder workspace to another, driven by hub messages.
+pub struct FileSyncJob {
+    pub uid: HubUid,
+    pub source: (String, String),
+    pub target: (String, String),
+    pub dry_run: bool,
+    pub state: FileSyncState,
+    pub ops: Vec<FileSyncOp>,
+    pub report: Vec<String>,
+    pub bytes_total: usize,
+    pub bytes_sent: usize,
+    source_tree: Option<BuilderFileTreeNode>,
+    target_tree: Option<BuilderFileTreeNode>,
+    pending: HashMap<String, FileSyncPending>,
+    unconfirmed: usize,
+}
+
+fn split_builder_path(path: &str) -> (String, String) {
+    if let Some(pos) = path.find('/') {
+        (path[0..pos].to_string(), path[pos + 1..].to_string())
+    }
+    else {
+        (path.to_string(), "".to_string())
+    }
+}
+
+impl FileSyncJob {
+    // source and target are builder/workspace paths like the keys in MakepadSettings.sync
+    pub fn start(uid: HubUid, source: &str, target: &str, dry_run: bool, out: &mut Vec<ToHubMsg>) -> FileSyncJob {
+        let job = FileSyncJob {
+            uid: uid,
+            source: split_builder_path(source),
+            target: split_builder_path(target),
+            dry_run: dry_run,
+            state: FileSyncState::WaitTrees,
+            ops: Vec::new(),
+            report: Vec::new(),
+            bytes_total: 0,
+            bytes_sent: 0,
+            source_tree: None,
+            target_tree: None,
+            pending: HashMap::new(),
+            unconfirmed: 0,
+        };
+        for builder in &[&job.source.0, &job.target.0] {
+            out.push(ToHubMsg {
+                to: HubMsgTo::Builder(builder.to_string()),
+                msg: HubMsg::BuilderFileTreeRequest {uid: uid, create_digest: true}
+            });
+        }
+        job
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state == FileSyncState::Done
+    }
+
+    fn finish(&mut self, msg: String) {
+        self.report.push(msg);
+        self.state = FileSyncState::Done;
+    }
+
+    fn source_path(&self, path: &str) -> String {format!("{}/{}", self.source.1, path)}
+    fn target_path(&self, path: &str) -> String {format!("{}/{}", self.target.1, path)}
+
+    fn strip_workspace<'a>(workspace: &str, path: &'a str) -> Option<&'a str> {
+        if path.len() > workspace.len() && path.starts_with(workspace) && &path[workspace.len()..workspace.len() + 1] == "/" {
+            Some(&path[workspace.len() + 1..])
+        }
+        else {
+            None
+        }
+    }
+
+    fn find_workspace(tree: &BuilderFileTreeNode, workspace: &str) -> Option<BuilderFileTreeNode> {
+        if let BuilderFileTreeNode::Folder {folder, ..} = tree {
+            for node in folder {
+                if let BuilderFileTreeNode::Folder {name, ..} = node {
+                    if name == workspace {
+                        return Some(node.clone())
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // returns true if the message belonged to this job
+    pub fn handle_hub_msg(&mut self, htc: &FromHubMsg, out: &mut Vec<ToHubMsg>) -> bool {
+        match &htc.msg {
+            HubMsg::BuilderFileTreeResponse {uid, tree} => if *uid == self.uid {
+                if let BuilderFileTreeNode::Folder {name, ..} = tree {
+                    if *name == self.source.0 {
+                        self.source_tree = Self::find_workspace(tree, &self.source.1);
+                        if self.source_tree.is_none() {
+                            self.finish(format!("Sync: workspace {}/{} not found", self.source.0, self.source.1));
+                        }
+                    }
+                    if *name == self.target.0 {
+                        self.target_tree = Self::find_workspace(tree, &self.target.1);
+                        if self.target_tree.is_none() {
+                            self.finish(format!("Sync: workspace {}/{} not found", self.target.0, self.target.1));
+                        }
+                    }
+                }
+                if self.state == FileSyncState::WaitTrees && self.source_tree.is_some() && self.target_tree.is_some() {
+                    self.start_transfer(out);
+                }
+                return true
+            },
+            HubMsg::FileReadResponse {uid, path, data} => if *uid == self.uid {
+                if let Some(path) = Self::strip_workspace(&self.source.1, path) {
+                    let path = path.to_string();
+                    // a source that can't be read is left alone on the target, not emptied
+                    if data.is_none() {
+                        if self.pending.remove(&path).is_some() {
+                            self.report.push(format!("Sync: failed to read {}/{}, skipped", self.source.0, self.source_path(&path)));
+                        }
+                        self.check_done();
+                        return true
+                    }
+                    if let Some(pending) = self.pending.get_mut(&path) {
+                        pending.data = data.clone();
+                    }
+                    self.send_patch(&path, out);
+                }
+                return true
+            },
+            HubMsg::FileBlockDigestsResponse {uid, path, digests} => if *uid == self.uid {
+                if let Some(path) = Self::strip_workspace(&self.target.1, path) {
+                    let path = path.to_string();
+                    if let Some(pending) = self.pending.get_mut(&path) {
+                        pending.digests = Some(if let Some(digests) = digests {digests.clone()}else {Vec::new()});
+                    }
+                    self.send_patch(&path, out);
+                }
+                return true
+            },
+            HubMsg::FileWriteResponse {uid, path, done} => if *uid == self.uid {
+                if !done {
+                    self.report.push(format!("Sync: failed to update {}/{}", self.target.0, path));
+                }
+                self.unconfirmed = self.unconfirmed.max(1) - 1;
+                self.check_done();
+                return true
+            },
+            _ => ()
+        }
+        false
+    }
+    
+    fn check_done(&mut self) {
+        if self.unconfirmed == 0 && self.pending.len() == 0 && self.state == FileSyncState::Transfer {
+            self.finish(format!(
+                "Sync {}/{} -> {}/{} done: {} operations, sent {} of {} bytes",
+                self.source.0,
+                self.source.1,
+                self.target.0,
+                self.target.1,
+                self.ops.len(),
+                self.bytes_sent,
+                self.bytes_total
+            ));
+        }
+    }
+
+    fn start_transfer(&mut self, out: &mut Vec<ToHubMsg>) {
+        self.ops = diff_file_trees(self.source_tree.as_ref().unwrap(), self.target_tree.as_ref().unwrap());
+        if self.dry_run {
+            for op in &self.ops {
+                self.report.push(format!("Sync dry run: {}", op.describe()));
+            }
+            let msg = format!("Sync dry run {}/{} -> {}/{}: {} operations", self.source.0, self.source.1, self.target.0, self.target.1, self.ops.len());
+            return self.finish(msg);
+        }
+        if self.ops.len() == 0 {
+            return self.finish(format!("Sync {}/{} -> {}/{}: up to date", self.source.0, self.source.1, self.target.0, self.target.1));
+        }
+        self.state = FileSyncState::Transfer;
+        let target_builder = HubMsgTo::Builder(self.target.0.clone());
+        for op in self.ops.clone() {
+            let is_create = if let FileSyncOp::Create {..} = op {true} else {false};
+            match op {
+                FileSyncOp::Create {path} | FileSyncOp::Change {path} => {
+                    out.push(ToHubMsg {
+                        to: HubMsgTo::Builder(self.source.0.clone()),
+                        msg: HubMsg::FileReadRequest {uid: self.uid, path: self.source_path(&path)}
+                    });
+                    if !is_create {
+                        out.push(ToHubMsg {
+                            to: target_builder.clone(),
+                            msg: HubMsg::FileBlockDigestsRequest {uid: self.uid, path: self.target_path(&path), block_size: SYNC_BLOCK_SIZE}
+                        });
+                    }
+                    self.pending.insert(path, FileSyncPending {
+                        data: None,
+                        digests: if is_create {Some(Vec::new())} else {None}
+                    });
+                },
+                FileSyncOp::Delete {path} => {
+                    self.unconfirmed += 1;
+                    out.push(ToHubMsg {
+                        to: target_builder.clone(),
+                        msg: HubMsg::FileDeleteRequest {uid: self.uid, path: self.target_path(&path)}
+                    });
+                },
+                FileSyncOp::Rename {path, new_path} => {
+                    self.unconfirmed += 1;
+                    out.push(ToHubMsg {
+                        to: target_builder.clone(),
+                        msg: HubMsg::FileRenameRequest {uid: self.uid, path: self.target_path(&path), new_path: self.target_path(&new_path)}
+                    });
+                }
+            }
+        }
+    }
+
+    fn send_patch(&mut self, path: &str, out: &mut Vec<ToHubMsg>) {
+        let ready = if let Some(pending) = self.pending.get(path) {
+            pending.data.is_some() && pending.digests.is_some()
+        } else {false};
+        if !ready {
+            return
+        }
+        let pending = self.pending.remove(path).unwrap();
+        let data = pending.data.unwrap();
+        let blocks = file_patch_blocks(&data, SYNC_BLOCK_SIZE, &pending.digests.unwrap());
+        self.bytes_total += data.len();
+        self.bytes_sent += blocks.iter().map( | (_, block) | block.len()).sum::<usize>();
+        self.unconfirmed += 1;
+        out.push(ToHubMsg {
+            to: HubMsgTo::Builder(self.target.0.clone()),
+            msg: HubMsg::FilePatchRequest {
+                uid: self.uid,
+                path: self.target_path(path),
+                len: data.len(),
+                block_size: SYNC_BLOCK_SIZE,
+                blocks: blocks
+            }
+        });
+    }
+}
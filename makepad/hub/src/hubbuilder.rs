@@ -2,8 +2,12 @@ use crate::process::*;
 use crate::hubmsg::*;
 use crate::hubrouter::*;
 use crate::hubclient::*;
+use crate::hubserver::*;
 use crate::httpserver::*;
 use crate::wasmstrip::*;
+use crate::buildqueue::*;
+use crate::cargotest::*;
+use crate::filesync::*;
 
 use makepad_microserde::*;
 use makepad_http::channel::*;
@@ -19,10 +23,12 @@ pub struct HubBuilder {
     pub route_send: HubRouteSend,
     pub http_server: Arc<Mutex<Option<HttpServer >> >,
     pub workspaces: Arc<Mutex<HashMap<String, String >> >,
+    pub build_profiles: Arc<Mutex<HashMap<String, HubBuildProfile >> >,
     pub websocket_channels: WebSocketChannels,
     pub builder: String,
     pub abs_cwd_path: String,
     pub processes: Arc<Mutex<Vec<HubProcess >> >,
+    pub cancelled: Arc<Mutex<Vec<HubUid >> >,
 }
 
 pub struct HubWorkspace {
@@ -54,9 +60,11 @@ impl HubBuilder {
     ) -> HubRouteSend
     where F: Fn(&mut HubBuilder, FromHubMsg) -> Result<(), HubWsError> + Clone + Send + 'static {
         let workspaces = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+        let build_profiles = Arc::new(Mutex::new(HashMap::<String, HubBuildProfile>::new()));
         let http_server = Arc::new(Mutex::new(None));
         let abs_cwd_path = format!("{}", std::env::current_dir().unwrap().display());
         let processes = Arc::new(Mutex::new(Vec::<HubProcess>::new()));
+        let cancelled = Arc::new(Mutex::new(Vec::<HubUid>::new()));
         
         let (tx_write, rx_write) = mpsc::channel::<FromHubMsg>();
         
@@ -82,7 +90,9 @@ impl HubBuilder {
                             websocket_channels: websocket_channels.clone(),
                             http_server: Arc::clone(&http_server),
                             workspaces: Arc::clone(&workspaces),
+                            build_profiles: Arc::clone(&build_profiles),
                             processes: Arc::clone(&processes),
+                            cancelled: Arc::clone(&cancelled),
                             builder: builder.to_string(),
                             abs_cwd_path: abs_cwd_path.clone(),
                         };
@@ -92,6 +102,7 @@ impl HubBuilder {
                             let result = event_handler(&mut hub_builder, htc);
                             
                             if let Some(is_build_uid) = is_build_uid {
+                                hub_builder.clear_cancelled(is_build_uid);
                                 if result.is_ok() {
                                     hub_builder.route_send.send(ToHubMsg {
                                         to: HubMsgTo::UI,
@@ -120,9 +131,11 @@ impl HubBuilder {
     where F: Fn(&mut HubBuilder, FromHubMsg) -> Result<(), HubWsError> + Clone + Send + 'static {
         
         let workspaces = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+        let build_profiles = Arc::new(Mutex::new(HashMap::<String, HubBuildProfile>::new()));
         let http_server = Arc::new(Mutex::new(None));
         let abs_cwd_path = format!("{}", std::env::current_dir().unwrap().display());
         let processes = Arc::new(Mutex::new(Vec::<HubProcess>::new()));
+        let cancelled = Arc::new(Mutex::new(Vec::<HubUid>::new()));
         
         loop {
             
@@ -164,7 +177,9 @@ impl HubBuilder {
                         websocket_channels: WebSocketChannels::default(),
                         http_server: Arc::clone(&http_server),
                         workspaces: Arc::clone(&workspaces),
+                        build_profiles: Arc::clone(&build_profiles),
                         processes: Arc::clone(&processes),
+                        cancelled: Arc::clone(&cancelled),
                         builder: builder.to_string(),
                         abs_cwd_path: abs_cwd_path.clone(),
                     };
@@ -174,6 +189,7 @@ impl HubBuilder {
                         let result = event_handler(&mut hub_builder, htc);
                         
                         if let Some(is_build_uid) = is_build_uid {
+                            hub_builder.clear_cancelled(is_build_uid);
                             if result.is_ok() {
                                 hub_builder.route_send.send(ToHubMsg {
                                     to: HubMsgTo::UI,
@@ -196,6 +212,184 @@ impl HubBuilder {
         }
     }
     
+    // a long running builder with its own hub server, builds are queued so several UIs can share it
+    pub fn run_builder_daemon<F>(digest: Digest, config: HubServerConfig, builder: &str, path: &str, event_handler: F)
+    where F: Fn(&mut HubBuilder, FromHubMsg) -> Result<(), HubWsError> + Clone + Send + 'static {
+        
+        let workspaces = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+        let build_profiles = Arc::new(Mutex::new(HashMap::<String, HubBuildProfile>::new()));
+        let http_server = Arc::new(Mutex::new(None));
+        let abs_cwd_path = format!("{}", std::env::current_dir().unwrap().display());
+        let processes = Arc::new(Mutex::new(Vec::<HubProcess>::new()));
+        let cancelled = Arc::new(Mutex::new(Vec::<HubUid>::new()));
+        let build_queue = Arc::new(Mutex::new(BuildQueue::default()));
+        
+        if let Ok(mut workspaces) = workspaces.lock() {
+            workspaces.insert(
+                "main".to_string(),
+                rel_to_abs_path(&abs_cwd_path, &path)
+            );
+        };
+        
+        let mut hub_router = HubRouter::start_hub_router(HubLog::None);
+        let _hub_server = if let Some(hub_server) = HubServer::start_hub_server(digest, &config, &hub_router) {
+            hub_server
+        }
+        else {
+            println!("Builder daemon cannot start hub server {:?}", config);
+            return
+        };
+        
+        let (tx_write, rx_write) = mpsc::channel::<FromHubMsg>();
+        let route_send = hub_router.connect_direct(HubRouteType::Builder(builder.to_string()), tx_write);
+        
+        route_send.send(ToHubMsg {
+            to: HubMsgTo::All,
+            msg: HubMsg::ConnectBuilder(builder.to_string())
+        });
+        
+        let new_hub_builder = {
+            let builder = builder.to_string();
+            let route_send = route_send.clone();
+            move || HubBuilder {
+                route_send: route_send.clone(),
+                websocket_channels: WebSocketChannels::default(),
+                http_server: Arc::clone(&http_server),
+                workspaces: Arc::clone(&workspaces),
+                build_profiles: Arc::clone(&build_profiles),
+                processes: Arc::clone(&processes),
+                cancelled: Arc::clone(&cancelled),
+                builder: builder.clone(),
+                abs_cwd_path: abs_cwd_path.clone(),
+            }
+        };
+        
+        let send_queue_state = {
+            let builder = builder.to_string();
+            move | route_send: &HubRouteSend, build_queue: &BuildQueue | {
+                route_send.send(ToHubMsg {
+                    to: HubMsgTo::UI,
+                    msg: HubMsg::BuildQueueState {
+                        builder: builder.clone(),
+                        jobs: build_queue.state()
+                    }
+                });
+            }
+        };
+        
+        // the build thread runs one queued job at a time
+        let (tx_wake, rx_wake) = mpsc::channel::<()>();
+        let _build_thread = {
+            let event_handler = event_handler.clone();
+            let build_queue = Arc::clone(&build_queue);
+            let send_queue_state = send_queue_state.clone();
+            let mut hub_builder = new_hub_builder();
+            std::thread::spawn(move || {
+                while let Ok(_) = rx_wake.recv() {
+                    loop {
+                        let next = if let Ok(mut build_queue) = build_queue.lock() {
+                            let next = build_queue.start_next();
+                            send_queue_state(&hub_builder.route_send, &build_queue);
+                            next
+                        }
+                        else {None};
+                        
+                        let (from, job) = if let Some(next) = next {next} else {break};
+                        
+                        let result = event_handler(&mut hub_builder, FromHubMsg {
+                            from: from,
+                            msg: HubMsg::Build {
+                                uid: job.uid,
+                                workspace: job.workspace.clone(),
+                                package: job.package.clone(),
+                                config: job.config.clone()
+                            }
+                        });
+                        
+                        hub_builder.clear_cancelled(job.uid);
+                        let mut uids = if let Ok(mut build_queue) = build_queue.lock() {
+                            let uids = build_queue.finish(job.uid);
+                            send_queue_state(&hub_builder.route_send, &build_queue);
+                            uids
+                        }
+                        else {Vec::new()};
+                        if !uids.contains(&job.uid) {
+                            uids.push(job.uid);
+                        }
+                        for uid in uids {
+                            hub_builder.route_send.send(ToHubMsg {
+                                to: HubMsgTo::UI,
+                                msg: if result.is_ok() {
+                                    HubMsg::BuildSuccess {uid: uid}
+                                }
+                                else {
+                                    HubMsg::BuildFailure {uid: uid}
+                                }
+                            });
+                        }
+                    }
+                }
+            })
+        };
+        
+        println!("Builder daemon {} serving {} on {:?}", builder, path, config);
+        
+        while let Ok(htc) = rx_write.recv() {
+            // builds go through the queue, everything else is handled as usual
+            match &htc.msg {
+                HubMsg::Build {uid, workspace, package, config} => {
+                    if let Ok(mut build_queue) = build_queue.lock() {
+                        match build_queue.push(htc.from, *uid, workspace, package, config) {
+                            BuildQueueAction::Superseded {kill_uid} => new_hub_builder().process_kill(kill_uid),
+                            BuildQueueAction::Merged {..} | BuildQueueAction::Queued => ()
+                        }
+                        send_queue_state(&route_send, &build_queue);
+                    };
+                    let _ = tx_wake.send(());
+                    continue;
+                },
+                HubMsg::BuildKill {uid} => {
+                    if let Ok(mut build_queue) = build_queue.lock() {
+                        match build_queue.cancel(*uid) {
+                            BuildQueueCancel::NotQueued => (),
+                            BuildQueueCancel::Kill {uid} => {
+                                new_hub_builder().process_kill(uid);
+                                send_queue_state(&route_send, &build_queue);
+                                continue;
+                            },
+                            BuildQueueCancel::Detached | BuildQueueCancel::Removed => {
+                                send_queue_state(&route_send, &build_queue);
+                                continue;
+                            }
+                        }
+                    };
+                },
+                HubMsg::FileWriteRequest {path, ..} |
+                HubMsg::FilePatchRequest {path, ..} |
+                HubMsg::FileDeleteRequest {path, ..} |
+                HubMsg::FileRenameRequest {path, ..} => {
+                    if let Some(workspace_pos) = path.find("/") {
+                        if let Ok(mut build_queue) = build_queue.lock() {
+                            build_queue.mark_stale(&path[0..workspace_pos]);
+                        };
+                    }
+                },
+                _ => ()
+            }
+            let is_blocking = htc.msg.is_blocking();
+            let thread = {
+                let event_handler = event_handler.clone();
+                let mut hub_builder = new_hub_builder();
+                std::thread::spawn(move || {
+                    let _ = event_handler(&mut hub_builder, htc);
+                })
+            };
+            if is_blocking {
+                let _ = thread.join();
+            }
+        }
+    }
+    
     pub fn run_builder_commandline<F>(args: Vec<String>, event_handler: F)
     where F: Fn(&mut HubBuilder, FromHubMsg) -> Result<(), HubWsError> + Clone + Send + 'static {
         
@@ -205,10 +399,22 @@ impl HubBuilder {
             println!("cargo run -p builder -- connect <ip>:<port> <key.ron> <workspace>");
             println!("example: cargo run -p builder -- connect 127.0.0.1:7243 key.ron windows");
             println!("");
+            println!("Run a local builder daemon with its own hub server that queues builds");
+            println!("cargo run -p builder -- daemon <port> <key.ron> <builder> <path>");
+            println!("example: cargo run -p builder -- daemon 7243 key.ron main edit_repo");
+            println!("");
+            println!("Inspect or post-process a wasm file, command is info, strip, strip-keep-names or validate");
+            println!("cargo run -p builder -- wasm <command> <file.wasm>");
+            println!("example: cargo run -p builder -- wasm info target/wasm32-unknown-unknown/release/webgl_example_wasm.wasm");
+            println!("");
             println!("Build a specific package");
             println!("cargo run -p builder -- build <path> <package> <config>");
             println!("example: cargo run -p builder -- build edit_repo makepad release");
             println!("");
+            println!("Run the tests of a package");
+            println!("cargo run -p builder -- test <path> <package> [filter]");
+            println!("example: cargo run -p builder -- test edit_repo makepad-hub");
+            println!("");
             println!("List packages");
             println!("cargo run -p builder -- list <path>");
             println!("example: cargo run -p builder -- list edit_repo");
@@ -237,6 +443,24 @@ impl HubBuilder {
                 Self::run_builder_networked(digest, addr, &builder, HubLog::None, event_handler);
                 return
             },
+            "daemon" => {
+                if args.len() != 6 {
+                    return print_help();
+                }
+                let port = args[2].parse().expect("cant parse port");
+                let key_file = args[3].to_string();
+                let builder = args[4].to_string();
+                let utf8_data = std::fs::read_to_string(key_file).expect("Can't read key file");
+                let digest: Digest = DeRon::deserialize_ron(&utf8_data).expect("Can't load key file");
+                Self::run_builder_daemon(digest, HubServerConfig::Localhost(port), &builder, &args[5], event_handler);
+                return
+            },
+            "wasm" => {
+                if args.len() != 4 {
+                    return print_help();
+                }
+                return wasm_commandline(&args[2], &args[3]);
+            },
             "list" => {
                 if args.len() != 3 {
                     return print_help();
@@ -256,6 +480,17 @@ impl HubBuilder {
                     config: args[4].clone()
                 }, args[2].clone(), None)
             },
+            "test" => {
+                if args.len() != 4 && args.len() != 5 {
+                    return print_help();
+                }
+                (HubMsg::TestRun {
+                    uid: HubUid::zero(),
+                    workspace: "main".to_string(),
+                    package: args[3].clone(),
+                    filter: if args.len() == 5 {args[4].clone()}else {"".to_string()}
+                }, args[2].clone(), None)
+            },
             "index" => {
                 if args.len() != 4 {
                     return print_help();
@@ -271,8 +506,10 @@ impl HubBuilder {
         };
         
         let workspaces = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+        let build_profiles = Arc::new(Mutex::new(HashMap::<String, HubBuildProfile>::new()));
         let http_server = Arc::new(Mutex::new(None));
         let processes = Arc::new(Mutex::new(Vec::<HubProcess>::new()));
+        let cancelled = Arc::new(Mutex::new(Vec::<HubUid>::new()));
         let abs_cwd_path = format!("{}", std::env::current_dir().unwrap().display());
         
         if let Ok(mut workspaces) = workspaces.lock() {
@@ -294,7 +531,9 @@ impl HubBuilder {
             http_server: Arc::clone(&http_server),
             builder: "".to_string(),
             processes: Arc::clone(&processes),
+            cancelled: Arc::clone(&cancelled),
             workspaces: Arc::clone(&workspaces),
+            build_profiles: Arc::clone(&build_profiles),
             abs_cwd_path: abs_cwd_path.clone()
         };
         
@@ -344,6 +583,9 @@ impl HubBuilder {
                     HubMsg::CargoEnd {build_result, ..} => {
                         println!("CargoEnd {:?}", build_result);
                     }
+                    HubMsg::TestEnd {passed, failed, ignored, ..} => {
+                        println!("TestEnd passed: {} failed: {} ignored: {}", passed, failed, ignored);
+                    }
                     HubMsg::BuildSuccess {..} => {
                         println!("Success!");
                         return
@@ -385,6 +627,10 @@ impl HubBuilder {
             }
         };
         
+        if let Ok(mut build_profiles) = self.build_profiles.lock() {
+            *build_profiles = config.build_profiles.unwrap_or(HashMap::new());
+        };
+        
         let workspaces = Arc::clone(&self.workspaces);
         
         if let Ok(mut http_server) = self.http_server.lock() {
@@ -429,6 +675,25 @@ impl HubBuilder {
                 ws.file_write(htc.from, uid, &path, data);
                 Ok(())
             },
+            HubMsg::FileBlockDigestsRequest {uid, path, block_size} => {
+                ws.file_block_digests(htc.from, uid, &path, block_size);
+                Ok(())
+            },
+            HubMsg::FilePatchRequest {uid, path, len, block_size, blocks} => {
+                ws.file_patch(htc.from, uid, &path, len, block_size, &blocks);
+                Ok(())
+            },
+            HubMsg::FileDeleteRequest {uid, path} => {
+                ws.file_delete(htc.from, uid, &path);
+                Ok(())
+            },
+            HubMsg::FileRenameRequest {uid, path, new_path} => {
+                ws.file_rename(htc.from, uid, &path, &new_path);
+                Ok(())
+            },
+            HubMsg::TestRun {uid, workspace, package, filter} => {
+                ws.cargo_test(uid, &workspace, &package, &filter)
+            },
             HubMsg::BuildKill {uid} => {
                 ws.process_kill(uid);
                 Ok(())
@@ -446,8 +711,14 @@ impl HubBuilder {
         }
     }
     
+    // a build can be killed before it started cargo, the flag stops it at its next step
     pub fn process_kill(&mut self, uid: HubUid) {
         if let Ok(mut procs) = self.processes.lock() {
+            if let Ok(mut cancelled) = self.cancelled.lock() {
+                if !cancelled.contains(&uid) {
+                    cancelled.push(uid);
+                }
+            };
             for proc in procs.iter_mut() {
                 if proc.uid == uid {
                     proc.process.kill();
@@ -456,6 +727,26 @@ impl HubBuilder {
         };
     }
     
+    pub fn is_cancelled(&self, uid: HubUid) -> bool {
+        if let Ok(cancelled) = self.cancelled.lock() {
+            return cancelled.contains(&uid)
+        }
+        false
+    }
+    
+    pub fn clear_cancelled(&mut self, uid: HubUid) {
+        if let Ok(mut cancelled) = self.cancelled.lock() {
+            cancelled.retain( | u | *u != uid);
+        };
+    }
+    
+    pub fn check_cancelled(&mut self, uid: HubUid) -> Result<(), HubWsError> {
+        if self.is_cancelled(uid) {
+            return Err(self.error(uid, format!("Builder {} build cancelled", self.builder)))
+        }
+        Ok(())
+    }
+    
     pub fn workspace_split_from_path(&mut self, uid: HubUid, path: &str) -> Result<(String, String, String), HubWsError> {
         if let Some(workspace_pos) = path.find("/") {
             let (workspace, rest) = path.split_at(workspace_pos);
@@ -666,13 +957,18 @@ impl HubBuilder {
         
         let mut extargs = args.to_vec();
         extargs.push("--message-format=json");
+        self.check_cancelled(uid) ?;
         let mut process = Process::start("cargo", &extargs, &abs_root_path, env).expect("Cannot start process");
         
         let route_send = self.route_send.clone();
         
         let rx_line = process.rx_line.take().unwrap();
         
+        // checked again under the process lock, a kill in between would miss the new process
         if let Ok(mut processes) = self.processes.lock() {
+            if self.is_cancelled(uid) {
+                process.kill();
+            }
             processes.push(HubProcess {
                 uid: uid,
                 process: process,
@@ -705,8 +1001,8 @@ impl HubBuilder {
                     continue
                 }
                 
-                let mut parsed: Result<RustcCompilerMessage, DeJsonErr> = DeJson::deserialize_json(&line);
-                match &mut parsed {
+                let parsed: Result<RustcCompilerMessage, DeJsonErr> = DeJson::deserialize_json(&line);
+                match &parsed {
                     Err(_e) => {
                         if line != "\n" && line != "\r\n" && !line.contains("--verbose"){
                             route_send.send(ToHubMsg {
@@ -721,73 +1017,8 @@ impl HubBuilder {
                     },
                     Ok(parsed) => {
                         
-                        if let Some(message) = &mut parsed.message { //.spans;
-                            let spans = &message.spans;
-                            //println!("{:#?}", spans);
-                            for i in 0..spans.len() {
-                                let mut span = spans[i].clone();
-                                if !span.is_primary {
-                                    continue
-                                }
-                                if let Some(exp) = span.expansion{
-                                    if let Some(ispan) = exp.span{
-                                        span = ispan
-                                    }
-                                }
-                                
-                                let mut msg = message.message.clone();
-                                
-                                for child in &message.children {
-                                    msg.push_str(" - ");
-                                    msg.push_str(&child.message);
-                                }
-                                msg = msg.replace("\n", "");
-                                // lets try to pull path out of rendered, this fixes some rust bugs
-
-                                //println!("{:#?}", span);
-
-                                let path = span.file_name;
-
-                                let line = span.line_start as usize;
-                                let column = span.column_start as usize;/*
-                                if let Some(rendered) = &message.rendered {
-                                    let lines: Vec<&str> = rendered.split('\n').collect();
-                                    if lines.len() > 1 {
-                                        if let Some(start) = lines[1].find("--> ") {
-                                            if let Some(end) = lines[1].find(":") {
-                                                path = lines[1].get((start + 4)..end).unwrap().to_string();
-                                                // TODO parse row/col from this line
-                                                
-                                            }
-                                        }
-                                    }
-                                }*/
-                                let loc_message = LocMessage {
-                                    path: format!("{}/{}/{}", builder, workspace, project_rel_path(&abs_root_path, &path)).replace("\\", "/"),
-                                    line: line,
-                                    column: column,
-                                    range: Some((span.byte_start as usize, span.byte_end as usize)),
-                                    body: msg,
-                                    rendered: message.rendered.clone(),
-                                    explanation: if let Some(code) = &message.code {code.explanation.clone()}else {None},
-                                };
-                                //println!("{:?}", loc_message);
-                                let item = match message.level.as_ref() {
-                                    "error" => {
-                                        errors.push(loc_message.clone());
-                                        HubLogItem::LocError(loc_message)
-                                    },
-                                    _ => HubLogItem::LocWarning(loc_message),
-                                };
-                                
-                                route_send.send(ToHubMsg {
-                                    to: HubMsgTo::UI,
-                                    msg: HubMsg::LogItem {
-                                        uid: uid,
-                                        item: item
-                                    }
-                                });
-                            }
+                        if let Some(message) = &parsed.message {
+                            send_rustc_message(uid, &builder, workspace, &abs_root_path, message, &mut errors, &route_send);
                         }
                         else {
                             if parsed.package_id.is_none(){
@@ -855,12 +1086,297 @@ impl HubBuilder {
                 processes.remove(index);
             }
         };
+        self.check_cancelled(uid) ?;
         if let BuildResult::Error = build_result {
             return Err(HubWsError::LocErrors(errors))
         }
         return Ok(build_result);
     }
     
+    pub fn cargo_test(&mut self, uid: HubUid, workspace: &str, package: &str, filter: &str) -> Result<(), HubWsError> {
+        
+        let abs_root_path = self.get_workspace_abs(uid, workspace) ?;
+        
+        let mut args = vec!["test", "-p", package, "--message-format=json"];
+        if filter.len() > 0 {
+            args.push(filter);
+        }
+        let mut process = match Process::start("cargo", &args, &abs_root_path, &[("RUST_BACKTRACE", "0")]) {
+            Ok(process) => process,
+            Err(e) => return Err(self.error(uid, format!("Builder {} cannot start cargo: {:?}", self.builder, e)))
+        };
+        
+        let route_send = self.route_send.clone();
+        
+        let rx_line = process.rx_line.take().unwrap();
+        
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.push(HubProcess {
+                uid: uid,
+                process: process,
+            });
+        };
+        
+        route_send.send(ToHubMsg {
+            to: HubMsgTo::UI,
+            msg: HubMsg::CargoBegin {uid: uid}
+        });
+        
+        let builder = self.builder.clone();
+        
+        fn send_test_results(uid: HubUid, builder: &str, workspace: &str, results: &mut Vec<CargoTestResult>, route_send: &HubRouteSend) {
+            for result in results.drain(..) {
+                let mut items = Vec::new();
+                match result {
+                    CargoTestResult::Ok {suite, name} => items.push(HubLogItem::TestOk(TestMessage {suite, name, loc: None})),
+                    CargoTestResult::Ignored {suite, name} => items.push(HubLogItem::TestIgnored(TestMessage {suite, name, loc: None})),
+                    CargoTestResult::Failed {suite, name, panic: Some(panic), output} => {
+                        let loc = LocMessage {
+                            path: format!("{}/{}/{}", builder, workspace, panic.path),
+                            line: panic.line,
+                            column: panic.column,
+                            range: None,
+                            body: panic.message,
+                            rendered: Some(output.join("\n")),
+                            explanation: None
+                        };
+                        items.push(HubLogItem::TestFailed(TestMessage {suite, name, loc: Some(loc)}));
+                    },
+                    // without a panic location there is no file to point at, the output follows as plain text
+                    CargoTestResult::Failed {suite, name, panic: None, output} => {
+                        items.push(HubLogItem::TestFailed(TestMessage {suite, name, loc: None}));
+                        if output.len() > 0 {
+                            items.push(HubLogItem::Error(output.join("\n")));
+                        }
+                    }
+                };
+                for item in items {
+                    route_send.send(ToHubMsg {
+                        to: HubMsgTo::UI,
+                        msg: HubMsg::LogItem {
+                            uid: uid,
+                            item: item
+                        }
+                    });
+                }
+            }
+        }
+        
+        let mut errors = Vec::new();
+        let mut parser = CargoTestParser::default();
+        let mut results = Vec::new();
+        while let Ok(line) = rx_line.recv() {
+            if let Some((is_stderr, line)) = line {
+                if is_stderr {
+                    if parser.parse_stderr_line(&line) {
+                        continue
+                    }
+                    if line != "\n"
+                        && !line.contains("Finished")
+                        && !line.contains("Blocking")
+                        && !line.contains("Compiling")
+                        && !line.contains("--verbose") {
+                        route_send.send(ToHubMsg {
+                            to: HubMsgTo::UI,
+                            msg: HubMsg::LogItem {
+                                uid: uid,
+                                item: HubLogItem::Error(line.clone())
+                            }
+                        });
+                    }
+                    continue
+                }
+                // compiler messages come in as json, the test binaries print plain text
+                if line.starts_with("{") {
+                    let parsed: Result<RustcCompilerMessage, DeJsonErr> = DeJson::deserialize_json(&line);
+                    if let Ok(parsed) = parsed {
+                        if let Some(message) = &parsed.message {
+                            send_rustc_message(uid, &builder, workspace, &abs_root_path, message, &mut errors, &route_send);
+                        }
+                        continue
+                    }
+                }
+                parser.parse_stdout_line(&line, &mut results);
+                send_test_results(uid, &builder, workspace, &mut results, &route_send);
+            }
+            else { // process terminated
+                break;
+            }
+        }
+        parser.finish(&mut results);
+        send_test_results(uid, &builder, workspace, &mut results, &route_send);
+        
+        route_send.send(ToHubMsg {
+            to: HubMsgTo::UI,
+            msg: HubMsg::TestEnd {
+                uid: uid,
+                passed: parser.passed,
+                failed: parser.failed,
+                ignored: parser.ignored
+            }
+        });
+        
+        if let Ok(mut processes) = self.processes.lock() {
+            if let Some(index) = processes.iter().position( | p | p.uid == uid) {
+                processes[index].process.wait();
+                processes.remove(index);
+            }
+        };
+        if errors.len() > 0 {
+            return Err(HubWsError::LocErrors(errors))
+        }
+        if parser.failed > 0 {
+            return Err(HubWsError::Error(format!("{} tests failed", parser.failed)))
+        }
+        Ok(())
+    }
+    
+    pub fn build_profile(&mut self, config: &str) -> Option<HubBuildProfile> {
+        if let Ok(build_profiles) = self.build_profiles.lock() {
+            if let Some(profile) = build_profiles.get(config) {
+                return Some(profile.clone())
+            }
+        }
+        HubBuildProfile::builtin(config)
+    }
+    
+    pub fn build_profile_names(&mut self) -> Vec<String> {
+        let mut names = HubBuildProfile::builtin_names();
+        if let Ok(build_profiles) = self.build_profiles.lock() {
+            let mut extra: Vec<String> = build_profiles.keys().filter( | k | !names.contains(k)).cloned().collect();
+            extra.sort();
+            names.extend(extra);
+        }
+        names
+    }
+    
+    pub fn cargo_profile(&mut self, uid: HubUid, workspace: &str, package: &str, profile: &HubBuildProfile) -> Result<BuildResult, HubWsError> {
+        let no_output = BuildResult::NoOutput;
+        if let Some(pre_steps) = &profile.pre_steps {
+            for step in pre_steps {
+                self.check_cancelled(uid) ?;
+                self.build_step(uid, workspace, step, &no_output) ?;
+            }
+        }
+        
+        let args = profile.cargo_args(package);
+        let args: Vec<&str> = args.iter().map( | v | v.as_ref()).collect();
+        let env = profile.cargo_env();
+        let env: Vec<(&str, &str)> = env.iter().map( | (k, v) | (k.as_ref(), v.as_ref())).collect();
+        let build_result = self.cargo(uid, workspace, &args, &env) ?;
+        
+        if let Some(post_steps) = &profile.post_steps {
+            for step in post_steps {
+                self.check_cancelled(uid) ?;
+                self.build_step(uid, workspace, step, &build_result) ?;
+            }
+        }
+        Ok(build_result)
+    }
+    
+    pub fn build_step(&mut self, uid: HubUid, workspace: &str, step: &HubBuildStep, build_result: &BuildResult) -> Result<(), HubWsError> {
+        let artifact = match build_result {
+            BuildResult::Executable {path} => Some(path.clone()),
+            BuildResult::Wasm {path} => Some(path.clone()),
+            _ => None
+        };
+        match step {
+            HubBuildStep::WasmStripDebug | HubBuildStep::WasmStrip {..} | HubBuildStep::WasmReport {..} | HubBuildStep::WasmValidate => {
+                let path = if let BuildResult::Wasm {path} = build_result {path} else {
+                    self.message(uid, format!("Step: {} skipped, build produced no wasm file", step.describe()));
+                    return Ok(())
+                };
+                self.message(uid, format!("Step: {} {}", step.describe(), path));
+                match step {
+                    HubBuildStep::WasmStripDebug => {self.wasm_strip_debug(uid, path) ?;},
+                    HubBuildStep::WasmStrip {keep_names} => {self.wasm_strip(uid, path, *keep_names) ?;},
+                    HubBuildStep::WasmReport {functions} => self.wasm_report(uid, path, *functions) ?,
+                    _ => self.wasm_validate(uid, path) ?
+                }
+                Ok(())
+            },
+            HubBuildStep::CopyArtifact {to} => {
+                let artifact = if let Some(artifact) = artifact {artifact} else {
+                    return Err(self.error(uid, format!("Step: copy artifact to {} failed, build produced no artifact", to)))
+                };
+                self.message(uid, format!("Step: copy {} to {}", artifact, to));
+                let (abs_root_path, _workspace, sub_path) = self.workspace_split_from_path(uid, &artifact) ?;
+                let src = format!("{}/{}", abs_root_path, sub_path);
+                let abs_workspace = self.get_workspace_abs(uid, workspace) ?;
+                let mut dst = rel_to_abs_path(&abs_workspace, to);
+                // copying into a directory keeps the artifact filename
+                if to.ends_with("/") || std::path::Path::new(&dst).is_dir() {
+                    let file_name = sub_path.rsplit("/").next().unwrap_or("");
+                    dst = format!("{}/{}", dst.trim_end_matches('/'), file_name);
+                }
+                if let Some(parent) = std::path::Path::new(&dst).parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                match fs::copy(&src, &dst) {
+                    Ok(bytes) => {
+                        self.message(uid, format!("Copied {} bytes to {}", bytes, dst));
+                        Ok(())
+                    },
+                    Err(e) => Err(self.error(uid, format!("Step: cannot copy {} to {}: {:?}", src, dst, e)))
+                }
+            },
+            HubBuildStep::Command {cmd, args} => {
+                self.message(uid, format!("Step: {} {}", cmd, args.join(" ")));
+                let args: Vec<&str> = args.iter().map( | v | v.as_ref()).collect();
+                self.command_run(uid, workspace, cmd, &args)
+            }
+        }
+    }
+    
+    pub fn command_run(&mut self, uid: HubUid, workspace: &str, cmd: &str, args: &[&str]) -> Result<(), HubWsError> {
+        let abs_root_path = self.get_workspace_abs(uid, workspace) ?;
+        
+        let mut process = match Process::start(cmd, args, &abs_root_path, &[]) {
+            Ok(process) => process,
+            Err(e) => return Err(self.error(uid, format!("Builder {} cannot start {}: {:?}", self.builder, cmd, e)))
+        };
+        
+        let rx_line = process.rx_line.take().unwrap();
+        
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.push(HubProcess {
+                uid: uid,
+                process: process,
+            });
+        };
+        
+        while let Ok(line) = rx_line.recv() {
+            if let Some((is_stderr, line)) = line {
+                let line = line.trim_end().to_string();
+                if line.len() == 0 {
+                    continue
+                }
+                self.route_send.send(ToHubMsg {
+                    to: HubMsgTo::UI,
+                    msg: HubMsg::LogItem {
+                        uid: uid,
+                        item: if is_stderr {HubLogItem::Warning(line)} else {HubLogItem::Message(line)}
+                    }
+                });
+            }
+            else { // process terminated
+                break;
+            }
+        }
+        
+        let mut exit_code = None;
+        if let Ok(mut processes) = self.processes.lock() {
+            if let Some(index) = processes.iter().position( | p | p.uid == uid) {
+                exit_code = processes[index].process.wait_exit_code();
+                processes.remove(index);
+            }
+        };
+        if exit_code != Some(0) {
+            return Err(self.error(uid, format!("Step: {} failed with exit code {:?}", cmd, exit_code)))
+        }
+        Ok(())
+    }
+    
     pub fn packages_response(&mut self, from: HubAddr, uid: HubUid, packages: Vec<HubPackage>) {
         
         self.route_send.send(ToHubMsg {
@@ -889,13 +1405,21 @@ impl HubBuilder {
     }
     
     pub fn wasm_strip_debug(&mut self, uid: HubUid, path: &str) -> Result<BuildResult, HubWsError> {
+        self.wasm_strip(uid, path, false)
+    }
+    
+    pub fn wasm_strip(&mut self, uid: HubUid, path: &str, keep_names: bool) -> Result<BuildResult, HubWsError> {
         
         let (abs_root_path, _project, sub_path) = self.workspace_split_from_path(uid, path) ?;
         
         let filepath = format!("{}/{}", abs_root_path, sub_path);
         
         if let Ok(data) = fs::read(&filepath) {
-            if let Ok(strip) = wasm_strip_debug(&data) {
+            if let Ok(strip) = wasm_strip(&data, keep_names) {
+                
+                if let Err(e) = wasm_validate(&strip) {
+                    return Err(self.error(uid, format!("Stripped wasm {} is invalid: {}", filepath, e)));
+                }
                 
                 let uncomp_len = strip.len();
                 //let mut enc = snap::Encoder::new();
@@ -923,6 +1447,38 @@ impl HubBuilder {
         Err(self.error(uid, format!("Cannot read wasm {}", filepath)))
     }
     
+    pub fn wasm_report(&mut self, uid: HubUid, path: &str, functions: usize) -> Result<(), HubWsError> {
+        let (abs_root_path, _project, sub_path) = self.workspace_split_from_path(uid, path) ?;
+        let filepath = format!("{}/{}", abs_root_path, sub_path);
+        let data = if let Ok(data) = fs::read(&filepath) {data} else {
+            return Err(self.error(uid, format!("Cannot read wasm {}", filepath)))
+        };
+        match wasm_report(&data, functions) {
+            Ok(lines) => {
+                for line in lines {
+                    self.message(uid, line);
+                }
+                Ok(())
+            },
+            Err(_) => Err(self.error(uid, format!("Cannot parse wasm {}", filepath)))
+        }
+    }
+    
+    pub fn wasm_validate(&mut self, uid: HubUid, path: &str) -> Result<(), HubWsError> {
+        let (abs_root_path, _project, sub_path) = self.workspace_split_from_path(uid, path) ?;
+        let filepath = format!("{}/{}", abs_root_path, sub_path);
+        let data = if let Ok(data) = fs::read(&filepath) {data} else {
+            return Err(self.error(uid, format!("Cannot read wasm {}", filepath)))
+        };
+        match wasm_validate(&data) {
+            Ok(()) => {
+                self.message(uid, format!("Wasm {} is valid", path));
+                Ok(())
+            },
+            Err(e) => Err(self.error(uid, format!("Wasm {} is invalid: {}", filepath, e)))
+        }
+    }
+    
     pub fn read_packages(&mut self, uid: HubUid) -> Vec<(String, String)> {
         
         let mut packages = Vec::new();
@@ -1035,6 +1591,91 @@ impl HubBuilder {
         }
     }
     
+    pub fn file_block_digests(&mut self, from: HubAddr, uid: HubUid, path: &str, block_size: usize) {
+        if let Ok((abs_dir, _workspace, sub_path)) = self.workspace_split_from_path(uid, path) {
+            if path.contains("..") || block_size == 0 {
+                self.error(uid, format!("file_block_digests got invalid request, ignoring {}", path));
+                return
+            }
+            let digests = if let Ok(data) = std::fs::read(format!("{}/{}", abs_dir, sub_path)) {
+                Some(file_block_digests(&data, block_size))
+            }
+            else {
+                None
+            };
+            self.route_send.send(ToHubMsg {
+                to: HubMsgTo::Client(from),
+                msg: HubMsg::FileBlockDigestsResponse {
+                    uid: uid,
+                    path: path.to_string(),
+                    digests: digests
+                }
+            });
+        }
+    }
+    
+    pub fn file_patch(&mut self, from: HubAddr, uid: HubUid, path: &str, len: usize, block_size: usize, blocks: &[(usize, Vec<u8>)]) {
+        if let Ok((abs_dir, _workspace, sub_path)) = self.workspace_split_from_path(uid, path) {
+            if path.contains("..") {
+                self.error(uid, format!("file_patch got relative path, ignoring {}", path));
+                return
+            }
+            let file_path = format!("{}/{}", abs_dir, sub_path);
+            let old = std::fs::read(&file_path).unwrap_or(Vec::new());
+            let data = apply_file_patch(&old, len, block_size, blocks);
+            if let Some(parent) = std::path::Path::new(&file_path).parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let done = std::fs::write(&file_path, &data).is_ok();
+            
+            if let Ok(mut http_server) = self.http_server.lock() {
+                if let Some(http_server) = &mut *http_server {
+                    http_server.send_file_change(path);
+                }
+            };
+            self.file_write_response(from, uid, path, done);
+        }
+    }
+    
+    pub fn file_delete(&mut self, from: HubAddr, uid: HubUid, path: &str) {
+        if let Ok((abs_dir, _workspace, sub_path)) = self.workspace_split_from_path(uid, path) {
+            if path.contains("..") {
+                self.error(uid, format!("file_delete got relative path, ignoring {}", path));
+                return
+            }
+            let done = std::fs::remove_file(format!("{}/{}", abs_dir, sub_path)).is_ok();
+            self.file_write_response(from, uid, path, done);
+        }
+    }
+    
+    pub fn file_rename(&mut self, from: HubAddr, uid: HubUid, path: &str, new_path: &str) {
+        if let Ok((abs_dir, _workspace, sub_path)) = self.workspace_split_from_path(uid, path) {
+            if let Ok((new_abs_dir, _workspace, new_sub_path)) = self.workspace_split_from_path(uid, new_path) {
+                if path.contains("..") || new_path.contains("..") {
+                    self.error(uid, format!("file_rename got relative path, ignoring {}", path));
+                    return
+                }
+                let new_file_path = format!("{}/{}", new_abs_dir, new_sub_path);
+                if let Some(parent) = std::path::Path::new(&new_file_path).parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                let done = std::fs::rename(format!("{}/{}", abs_dir, sub_path), new_file_path).is_ok();
+                self.file_write_response(from, uid, new_path, done);
+            }
+        }
+    }
+    
+    fn file_write_response(&mut self, from: HubAddr, uid: HubUid, path: &str, done: bool) {
+        self.route_send.send(ToHubMsg {
+            to: HubMsgTo::Client(from),
+            msg: HubMsg::FileWriteResponse {
+                uid: uid,
+                path: path.to_string(),
+                done: done
+            }
+        });
+    }
+    
     pub fn workspace_file_tree(&mut self, create_digest: bool, ext_inc: &[&str], file_ex: &[&str], dir_ex: &[&str]) -> BuilderFileTreeNode {
         fn digest_folder(create_digest: bool, name: &str, folder: &Vec<BuilderFileTreeNode>) -> Option<Box<Digest >> {
             if !create_digest {
@@ -1082,11 +1723,19 @@ impl HubBuilder {
                                         continue
                                     }
                                     if ext_inc.iter().find( | ext | name.ends_with(*ext)).is_some() {
-                                        if create_digest {
-                                            
+                                        let digest = if create_digest {
+                                            if let Ok(data) = fs::read(&format!("{}/{}", path, name)) {
+                                                Some(Box::new(file_content_digest(&data)))
+                                            }
+                                            else {
+                                                None
+                                            }
                                         }
+                                        else {
+                                            None
+                                        };
                                         ret.push(BuilderFileTreeNode::File {
-                                            digest: None,
+                                            digest: digest,
                                             name: name
                                         });
                                     }
@@ -1128,6 +1777,35 @@ impl HubBuilder {
     }
 }
 
+fn wasm_commandline(command: &str, path: &str) {
+    let data = if let Ok(data) = fs::read(path) {data} else {
+        return println!("Cannot read wasm {}", path)
+    };
+    match command {
+        "info" => match wasm_report(&data, 20) {
+            Ok(lines) => for line in lines {println!("{}", line)},
+            Err(_) => println!("Cannot parse wasm {}", path)
+        },
+        "strip" | "strip-keep-names" => {
+            let strip = if let Ok(strip) = wasm_strip(&data, command == "strip-keep-names") {strip} else {
+                return println!("Cannot parse wasm {}", path)
+            };
+            if let Err(e) = wasm_validate(&strip) {
+                return println!("Stripped wasm {} is invalid: {}", path, e)
+            }
+            if let Err(_) = fs::write(path, &strip) {
+                return println!("Cannot write stripped wasm {}", path)
+            }
+            println!("Stripped {} from {} to {} bytes", path, data.len(), strip.len());
+        },
+        "validate" => match wasm_validate(&data) {
+            Ok(()) => println!("Wasm {} is valid", path),
+            Err(e) => println!("Wasm {} is invalid: {}", path, e)
+        },
+        _ => println!("Unknown wasm command {}, use info, strip, strip-keep-names or validate", command)
+    }
+}
+
 fn rel_to_abs_path(abs_root: &str, path: &str) -> String {
     if path.starts_with("/") {
         return path.to_string();
@@ -1159,6 +1837,83 @@ fn de_relativize_path( path: &str) -> String {
     out.join("/")
 }
 
+fn send_rustc_message(
+    uid: HubUid,
+    builder: &str,
+    workspace: &str,
+    abs_root_path: &str,
+    message: &RustcMessage,
+    errors: &mut Vec<LocMessage>,
+    route_send: &HubRouteSend
+) {
+    let spans = &message.spans;
+    //println!("{:#?}", spans);
+    for i in 0..spans.len() {
+        let mut span = spans[i].clone();
+        if !span.is_primary {
+            continue
+        }
+        if let Some(exp) = span.expansion{
+            if let Some(ispan) = exp.span{
+                span = ispan
+            }
+        }
+        
+        let mut msg = message.message.clone();
+        
+        for child in &message.children {
+            msg.push_str(" - ");
+            msg.push_str(&child.message);
+        }
+        msg = msg.replace("\n", "");
+        // lets try to pull path out of rendered, this fixes some rust bugs
+
+        //println!("{:#?}", span);
+
+        let path = span.file_name;
+
+        let line = span.line_start as usize;
+        let column = span.column_start as usize;/*
+        if let Some(rendered) = &message.rendered {
+            let lines: Vec<&str> = rendered.split('\n').collect();
+            if lines.len() > 1 {
+                if let Some(start) = lines[1].find("--> ") {
+                    if let Some(end) = lines[1].find(":") {
+                        path = lines[1].get((start + 4)..end).unwrap().to_string();
+                        // TODO parse row/col from this line
+                        
+                    }
+                }
+            }
+        }*/
+        let loc_message = LocMessage {
+            path: format!("{}/{}/{}", builder, workspace, project_rel_path(abs_root_path, &path)).replace("\\", "/"),
+            line: line,
+            column: column,
+            range: Some((span.byte_start as usize, span.byte_end as usize)),
+            body: msg,
+            rendered: message.rendered.clone(),
+            explanation: if let Some(code) = &message.code {code.explanation.clone()}else {None},
+        };
+        //println!("{:?}", loc_message);
+        let item = match message.level.as_ref() {
+            "error" => {
+                errors.push(loc_message.clone());
+                HubLogItem::LocError(loc_message)
+            },
+            _ => HubLogItem::LocWarning(loc_message),
+        };
+        
+        route_send.send(ToHubMsg {
+            to: HubMsgTo::UI,
+            msg: HubMsg::LogItem {
+                uid: uid,
+                item: item
+            }
+        });
+    }
+}
+
 
 // rust compiler output json structs
 #[derive(Clone, DeJson, Debug, Default)]
@@ -4,6 +4,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use crate::httpserver::*;
 use crate::hubclient::*;
+use crate::filesync::*;
 
 #[derive(Clone, Debug, SerBin, DeBin)]
 pub enum HubMsg {
@@ -43,6 +44,11 @@ pub enum HubMsg {
         uid: HubUid
     },
     
+    BuildQueueState {
+        builder: String,
+        jobs: Vec<HubBuildJob>
+    },
+    
     CargoBegin {
         uid: HubUid,
     },
@@ -63,6 +69,20 @@ pub enum HubMsg {
         build_result: BuildResult
     },
     
+    TestRun {
+        uid: HubUid,
+        workspace: String,
+        package: String,
+        filter: String
+    },
+    
+    TestEnd {
+        uid: HubUid,
+        passed: usize,
+        failed: usize,
+        ignored: usize
+    },
+    
     ListPackagesRequest {
         uid: HubUid
     },
@@ -131,6 +151,38 @@ pub enum HubMsg {
         path: String,
         done: bool
     },
+    
+    FileBlockDigestsRequest {
+        uid: HubUid,
+        path: String,
+        block_size: usize
+    },
+    
+    FileBlockDigestsResponse {
+        uid: HubUid,
+        path: String,
+        digests: Option<Vec<BlockDigest >>
+    },
+    
+    // answered with a FileWriteResponse
+    FilePatchRequest {
+        uid: HubUid,
+        path: String,
+        len: usize,
+        block_size: usize,
+        blocks: Vec<(usize, Vec<u8 >) >
+    },
+    
+    FileDeleteRequest {
+        uid: HubUid,
+        path: String
+    },
+    
+    FileRenameRequest {
+        uid: HubUid,
+        path: String,
+        new_path: String
+    },
 }
 
 impl HubMsg{
@@ -138,6 +190,9 @@ impl HubMsg{
         match self{
             HubMsg::BuilderConfig{..}=>true,
             HubMsg::FileWriteRequest{..}=>true,
+            HubMsg::FilePatchRequest{..}=>true,
+            HubMsg::FileDeleteRequest{..}=>true,
+            HubMsg::FileRenameRequest{..}=>true,
             _=>false
         }
     }
@@ -210,10 +265,125 @@ impl HubPackage {
 }
 
 
+// a build as seen by a queueing builder, identical requests are folded into one job
+#[derive(Debug, Clone, PartialEq, SerBin, DeBin)]
+pub struct HubBuildJob {
+    pub uid: HubUid,
+    pub uids: Vec<HubUid>,
+    pub workspace: String,
+    pub package: String,
+    pub config: String,
+    pub running: bool,
+}
+
 #[derive(Debug, Clone, SerBin, DeBin, PartialEq, SerRon, DeRon)]
 pub struct HubBuilderConfig {
     pub http_server: HttpServerConfig,
     pub workspaces: HashMap<String, String>,
+    pub build_profiles: Option<HashMap<String, HubBuildProfile>>,
+}
+
+#[derive(Debug, Clone, SerBin, DeBin, PartialEq, SerRon, DeRon)]
+pub struct HubBuildProfile {
+    pub check: Option<bool>,
+    pub release: Option<bool>,
+    pub features: Option<Vec<String>>,
+    pub target: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+    pub pre_steps: Option<Vec<HubBuildStep>>,
+    pub post_steps: Option<Vec<HubBuildStep>>,
+}
+
+#[derive(Debug, Clone, SerBin, DeBin, PartialEq, SerRon, DeRon)]
+pub enum HubBuildStep {
+    WasmStripDebug,
+    WasmStrip {keep_names: bool},
+    WasmReport {functions: usize},
+    WasmValidate,
+    CopyArtifact {to: String},
+    Command {cmd: String, args: Vec<String>},
+}
+
+impl HubBuildStep {
+    pub fn describe(&self) -> String {
+        match self {
+            HubBuildStep::WasmStripDebug => "wasm strip debug".to_string(),
+            HubBuildStep::WasmStrip {keep_names} => if *keep_names {"wasm strip keeping names"} else {"wasm strip"}.to_string(),
+            HubBuildStep::WasmReport {..} => "wasm report".to_string(),
+            HubBuildStep::WasmValidate => "wasm validate".to_string(),
+            HubBuildStep::CopyArtifact {to} => format!("copy artifact to {}", to),
+            HubBuildStep::Command {cmd, args} => format!("{} {}", cmd, args.join(" ")),
+        }
+    }
+}
+
+impl HubBuildProfile {
+    pub fn new() -> HubBuildProfile {
+        HubBuildProfile {
+            check: None,
+            release: None,
+            features: None,
+            target: None,
+            env: None,
+            pre_steps: None,
+            post_steps: None,
+        }
+    }
+    
+    // the configs every builder knows about, settings can override them by name
+    pub fn builtin(config: &str) -> Option<HubBuildProfile> {
+        match config {
+            "check" => Some(HubBuildProfile {check: Some(true), ..HubBuildProfile::new()}),
+            "debug" => Some(HubBuildProfile::new()),
+            "release" => Some(HubBuildProfile {release: Some(true), ..HubBuildProfile::new()}),
+            "small" => Some(HubBuildProfile {
+                release: Some(true),
+                env: Some({
+                    let mut env = HashMap::new();
+                    env.insert("RUSTFLAGS".to_string(), "-C opt-level=z -C panic=abort -C codegen-units=1".to_string());
+                    env
+                }),
+                post_steps: Some(vec![HubBuildStep::WasmStripDebug]),
+                ..HubBuildProfile::new()
+            }),
+            _ => None
+        }
+    }
+    
+    pub fn builtin_names() -> Vec<String> {
+        vec!["check".to_string(), "debug".to_string(), "release".to_string(), "small".to_string()]
+    }
+    
+    pub fn cargo_args(&self, package: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        args.push(if self.check == Some(true) {"check"} else {"build"}.to_string());
+        if self.release == Some(true) {
+            args.push("--release".to_string());
+        }
+        args.push("-p".to_string());
+        args.push(package.to_string());
+        if let Some(features) = &self.features {
+            if features.len() > 0 {
+                args.push("--features".to_string());
+                args.push(features.join(","));
+            }
+        }
+        if let Some(target) = &self.target {
+            args.push(format!("--target={}", target));
+        }
+        args
+    }
+    
+    pub fn cargo_env(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if let Some(vars) = &self.env {
+            for (key, value) in vars {
+                env.push((key.clone(), value.clone()));
+            }
+        }
+        env.sort();
+        env
+    }
 }
 
 
@@ -228,12 +398,22 @@ pub struct LocMessage {
     pub explanation: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, SerBin, DeBin)]
+pub struct TestMessage {
+    pub suite: String,
+    pub name: String,
+    pub loc: Option<LocMessage>,
+}
+
 #[derive(Debug, Clone, PartialEq, SerBin, DeBin)]
 pub enum HubLogItem {
     LocPanic(LocMessage),
     LocError(LocMessage),
     LocWarning(LocMessage),
     LocMessage(LocMessage),
+    TestOk(TestMessage),
+    TestIgnored(TestMessage),
+    TestFailed(TestMessage),
     Error(String),
     Warning(String),
     Message(String)
@@ -246,6 +426,9 @@ impl HubLogItem {
             HubLogItem::LocError(msg) => Some(msg),
             HubLogItem::LocWarning(msg) => Some(msg),
             HubLogItem::LocMessage(msg) => Some(msg),
+            HubLogItem::TestOk(_) => None,
+            HubLogItem::TestIgnored(_) => None,
+            HubLogItem::TestFailed(msg) => msg.loc.as_ref(),
             HubLogItem::Error(_) => None,
             HubLogItem::Warning(_) => None,
             HubLogItem::Message(_) => None
@@ -257,6 +440,9 @@ impl HubLogItem {
             HubLogItem::LocError(msg) => &msg.body,
             HubLogItem::LocWarning(msg) => &msg.body,
             HubLogItem::LocMessage(msg) => &msg.body,
+            HubLogItem::TestOk(msg) => &msg.name,
+            HubLogItem::TestIgnored(msg) => &msg.name,
+            HubLogItem::TestFailed(msg) => &msg.name,
             HubLogItem::Error(body) => body,
             HubLogItem::Warning(body) => body,
             HubLogItem::Message(body) => body
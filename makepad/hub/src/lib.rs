@@ -22,5 +22,14 @@ pub use crate::hubmsg::*;
 mod httpserver;
 pub use crate::httpserver::*;
 
+mod buildqueue;
+pub use crate::buildqueue::*;
+
+mod cargotest;
+pub use crate::cargotest::*;
+
+mod filesync;
+pub use crate::filesync::*;
+
 mod wasmstrip;
 pub use crate::wasmstrip::*;
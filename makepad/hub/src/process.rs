@@ -106,6 +106,16 @@ impl Process {
         }
     }
     
+    // returns None when the process was killed or never got an exit code
+    pub fn wait_exit_code(&mut self) -> Option<i32> {
+        if let Some(mut child) = self.child.take() {
+            if let Ok(status) = child.wait() {
+                return status.code()
+            }
+        }
+        None
+    }
+    
     pub fn kill(&mut self) {
         if let Some(child) = &mut self.child {
             let _ = child.kill();
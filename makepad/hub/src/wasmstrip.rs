@@ -46,6 +46,33 @@ impl<'a> Reader<'a> {
         Ok(u32::from_le_bytes(bytes))
     }
     
+    fn read_slice(&mut self, count: usize) -> Result<&'a [u8],WasmParseError> {
+        if count > self.bytes.len() {
+            return Err(WasmParseError);
+        }
+        let slice = &self.bytes[..count];
+        self.bytes = &self.bytes[count..];
+        self.offset += count;
+        Ok(slice)
+    }
+    
+    fn read_name(&mut self) -> Result<String,WasmParseError> {
+        let len = self.read_var_u32()? as usize;
+        if let Ok(name) = std::str::from_utf8(self.read_slice(len)?){
+            return Ok(name.to_string())
+        }
+        Err(WasmParseError)
+    }
+    
+    fn read_limits(&mut self) -> Result<(),WasmParseError> {
+        let flags = self.read_u8()?;
+        self.read_var_u32()?;
+        if flags & 1 != 0{
+            self.read_var_u32()?;
+        }
+        Ok(())
+    }
+    
     fn read_var_u32(&mut self) -> Result<u32,WasmParseError>{
         let byte = self.read_u8()? as  u32;
         if byte&0x80 == 0{
@@ -71,14 +98,50 @@ impl<'a> Reader<'a> {
 
 }
 
-struct WasmSection{
+pub struct WasmSection{
     pub type_id: u8,
     pub start: usize,
     pub end: usize,
     pub name: String
 }
 
-fn read_wasm_sections(buf:&[u8])->Result<Vec<WasmSection>,WasmParseError>{
+impl WasmSection{
+    pub fn len(&self)->usize{
+        self.end - self.start
+    }
+    
+    pub fn kind(&self)->&'static str{
+        match self.type_id{
+            0=>"custom",
+            1=>"type",
+            2=>"import",
+            3=>"function",
+            4=>"table",
+            5=>"memory",
+            6=>"global",
+            7=>"export",
+            8=>"start",
+            9=>"element",
+            10=>"code",
+            11=>"data",
+            12=>"datacount",
+            13=>"tag",
+            _=>"unknown"
+        }
+    }
+    
+    // position of the section in the order the spec requires, custom sections can go anywhere
+    fn order(&self)->Option<usize>{
+        match self.type_id{
+            1..=11=>Some(self.type_id as usize * 2),
+            12=>Some(19), // datacount goes between element and code
+            13=>Some(11), // tags go between memory and global
+            _=>None
+        }
+    }
+}
+
+pub fn read_wasm_sections(buf:&[u8])->Result<Vec<WasmSection>,WasmParseError>{
     let mut sections = Vec::new();
     let mut reader = Reader::new(&buf);
     if reader.read_u32()? != 0x6d736100{
@@ -95,19 +158,17 @@ fn read_wasm_sections(buf:&[u8])->Result<Vec<WasmSection>,WasmParseError>{
             let payload_len = reader.read_var_u32()? as usize;
             let start = reader.offset;
             if type_id == 0{
-                let name_len = reader.read_var_u32()? as usize;
-                if let Ok(name) = std::str::from_utf8(&reader.bytes[0..name_len]){
-                    sections.push(WasmSection{
-                        start: offset,
-                        type_id: type_id,
-                        end: offset + payload_len + (start-offset),
-                        name: name.to_string()
-                    })
-                }
-                else{
+                let name = reader.read_name()?;
+                let end = reader.offset;
+                if end - start > payload_len{
                     return Err(WasmParseError);
                 }
-                let end = reader.offset;
+                sections.push(WasmSection{
+                    start: offset,
+                    type_id: type_id,
+                    end: offset + payload_len + (start-offset),
+                    name: name
+                });
                 reader.skip(payload_len - (end-start))?;
             }
             else{
@@ -127,16 +188,181 @@ fn read_wasm_sections(buf:&[u8])->Result<Vec<WasmSection>,WasmParseError>{
     return Ok(sections);
 }
 
-pub fn wasm_strip_debug(buf: &[u8])->Result<Vec<u8>,WasmParseError>{
+// the payload of a section without its id, length and custom section name
+fn section_payload<'a>(buf:&'a [u8], section:&WasmSection)->Result<Reader<'a>,WasmParseError>{
+    let mut reader = Reader::new(&buf[section.start..section.end]);
+    reader.read_u8()?;
+    reader.read_var_u32()?;
+    if section.type_id == 0{
+        reader.read_name()?;
+    }
+    Ok(reader)
+}
+
+fn count_imported_functions(buf:&[u8], section:&WasmSection)->Result<usize,WasmParseError>{
+    let mut reader = section_payload(buf, section)?;
+    let count = reader.read_var_u32()?;
+    let mut functions = 0;
+    for _ in 0..count{
+        reader.read_name()?;
+        reader.read_name()?;
+        match reader.read_u8()?{
+            0=>{functions += 1; reader.read_var_u32()?;},
+            1=>{reader.read_u8()?; reader.read_limits()?;},
+            2=>{reader.read_limits()?;},
+            3=>{reader.read_u8()?; reader.read_u8()?;},
+            4=>{reader.read_u8()?; reader.read_var_u32()?;},
+            _=>return Err(WasmParseError)
+        }
+    }
+    Ok(functions)
+}
+
+fn read_function_names(buf:&[u8], section:&WasmSection)->Result<Vec<(usize, String)>,WasmParseError>{
+    let mut reader = section_payload(buf, section)?;
+    let mut names = Vec::new();
+    while reader.bytes.len() > 0{
+        let sub_id = reader.read_u8()?;
+        let sub_len = reader.read_var_u32()? as usize;
+        let mut sub = Reader::new(reader.read_slice(sub_len)?);
+        if sub_id == 1{
+            let count = sub.read_var_u32()?;
+            for _ in 0..count{
+                let index = sub.read_var_u32()? as usize;
+                names.push((index, sub.read_name()?));
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn read_vec_count(buf:&[u8], section:&WasmSection)->Result<usize,WasmParseError>{
+    Ok(section_payload(buf, section)?.read_var_u32()? as usize)
+}
+
+#[derive(Clone, Debug)]
+pub struct WasmFunctionSize{
+    pub index: usize,
+    pub name: Option<String>,
+    pub size: usize
+}
+
+// function bodies sorted by size, biggest first. names come from the name section if there is one
+pub fn wasm_function_sizes(buf: &[u8])->Result<Vec<WasmFunctionSize>,WasmParseError>{
+    let sections = read_wasm_sections(&buf)?;
+    let mut imported = 0;
+    let mut names = Vec::new();
+    for section in &sections{
+        if section.type_id == 2{
+            imported = count_imported_functions(buf, section)?;
+        }
+        if section.type_id == 0 && section.name == "name"{
+            names = read_function_names(buf, section)?;
+        }
+    }
+    let mut functions = Vec::new();
+    if let Some(code) = sections.iter().find(|s| s.type_id == 10){
+        let mut reader = section_payload(buf, code)?;
+        let count = reader.read_var_u32()? as usize;
+        for i in 0..count{
+            let size = reader.read_var_u32()? as usize;
+            reader.skip(size)?;
+            let index = imported + i;
+            functions.push(WasmFunctionSize{
+                index: index,
+                name: names.iter().find(|(n, _)| *n == index).map(|(_, name)| name.clone()),
+                size: size
+            });
+        }
+    }
+    functions.sort_by(|a, b| b.size.cmp(&a.size));
+    Ok(functions)
+}
+
+// checks the module structure, not the function bodies
+pub fn wasm_validate(buf: &[u8])->Result<(),String>{
+    let sections = if let Ok(sections) = read_wasm_sections(&buf){sections} else{
+        return Err("cannot parse wasm sections".to_string())
+    };
+    let mut last_order = 0;
+    for section in &sections{
+        if let Some(order) = section.order(){
+            if order <= last_order{
+                return Err(format!("{} section out of order or duplicated", section.kind()))
+            }
+            last_order = order;
+        }
+        else if section.type_id != 0{
+            return Err(format!("unknown section id {}", section.type_id))
+        }
+    }
+    let count = |type_id:u8|->Result<Option<usize>,String>{
+        if let Some(section) = sections.iter().find(|s| s.type_id == type_id){
+            return match read_vec_count(buf, section){
+                Ok(count)=>Ok(Some(count)),
+                Err(_)=>Err(format!("cannot read {} section", section.kind()))
+            }
+        }
+        Ok(None)
+    };
+    let functions = count(3)?.unwrap_or(0);
+    let bodies = count(10)?.unwrap_or(0);
+    if functions != bodies{
+        return Err(format!("function section declares {} functions but code section has {} bodies", functions, bodies))
+    }
+    if let Some(data_count) = count(12)?{
+        let data = count(11)?.unwrap_or(0);
+        if data_count != data{
+            return Err(format!("datacount section says {} segments but data section has {}", data_count, data))
+        }
+    }
+    if let Err(_) = wasm_function_sizes(buf){
+        return Err("cannot read code or name section".to_string())
+    }
+    Ok(())
+}
+
+// human readable section and function size listing, used by the builder log and commandline
+pub fn wasm_report(buf: &[u8], functions: usize)->Result<Vec<String>,WasmParseError>{
+    let sections = read_wasm_sections(&buf)?;
+    let mut lines = Vec::new();
+    lines.push(format!("Wasm size {} bytes, {} sections", buf.len(), sections.len()));
+    for section in &sections{
+        if section.type_id == 0{
+            lines.push(format!("  {:>10} {} \"{}\"", section.len(), section.kind(), section.name));
+        }
+        else{
+            lines.push(format!("  {:>10} {}", section.len(), section.kind()));
+        }
+    }
+    let sizes = wasm_function_sizes(buf)?;
+    if functions > 0 && sizes.len() > 0{
+        lines.push(format!("Biggest {} of {} functions", functions.min(sizes.len()), sizes.len()));
+        for function in sizes.iter().take(functions){
+            if let Some(name) = &function.name{
+                lines.push(format!("  {:>10} {}", function.size, name));
+            }
+            else{
+                lines.push(format!("  {:>10} func[{}]", function.size, function.index));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+// drops all custom sections, optionally keeping the name section for readable stacktraces
+pub fn wasm_strip(buf: &[u8], keep_names: bool)->Result<Vec<u8>,WasmParseError>{
     let mut strip = Vec::new();
     strip.extend_from_slice(&[0, 97, 115, 109, 1, 0, 0, 0]);
     let sections = read_wasm_sections(&buf)?;
-    // lets rewrite it
     for section in &sections{
-        if section.type_id != 0{// !section.name.starts_with(".debug"){
+        if section.type_id != 0 || keep_names && section.name == "name"{
             strip.extend_from_slice(&buf[section.start..section.end]);
         }
-        
     }
     Ok(strip)
 }
+
+pub fn wasm_strip_debug(buf: &[u8])->Result<Vec<u8>,WasmParseError>{
+    wasm_strip(buf, false)
+}
@@ -0,0 +1,73 @@
+// the cargo command line and environment a build profile turns into, and the profiles every builder knows
+use makepad_hub::*;
+use makepad_microserde::*;
+use std::collections::HashMap;
+
+fn args(profile: &HubBuildProfile) -> Vec<String> {
+    profile.cargo_args("app")
+}
+
+fn strings(v: &[&str]) -> Vec<String> {
+    v.iter().map( | s | s.to_string()).collect()
+}
+
+#[test]
+fn cargo_args() {
+    assert_eq!(args(&HubBuildProfile::new()), strings(&["build", "-p", "app"]));
+    assert_eq!(args(&HubBuildProfile {check: Some(true), ..HubBuildProfile::new()}), strings(&["check", "-p", "app"]));
+    // an explicit false is the same as leaving it out
+    assert_eq!(args(&HubBuildProfile {check: Some(false), release: Some(false), ..HubBuildProfile::new()}), strings(&["build", "-p", "app"]));
+    assert_eq!(
+        args(&HubBuildProfile {
+            release: Some(true),
+            features: Some(strings(&["a", "b"])),
+            target: Some("wasm32-unknown-unknown".to_string()),
+            ..HubBuildProfile::new()
+        }),
+        strings(&["build", "--release", "-p", "app", "--features", "a,b", "--target=wasm32-unknown-unknown"])
+    );
+    // no features is no flag at all
+    assert_eq!(args(&HubBuildProfile {features: Some(Vec::new()), ..HubBuildProfile::new()}), strings(&["build", "-p", "app"]));
+}
+
+#[test]
+fn cargo_env() {
+    assert_eq!(HubBuildProfile::new().cargo_env(), vec![]);
+    let mut env = HashMap::new();
+    env.insert("RUSTFLAGS".to_string(), "-C opt-level=z".to_string());
+    env.insert("CARGO_INCREMENTAL".to_string(), "0".to_string());
+    env.insert("A".to_string(), "1".to_string());
+    // sorted, so the same profile always starts cargo the same way
+    assert_eq!(HubBuildProfile {env: Some(env), ..HubBuildProfile::new()}.cargo_env(), vec![
+        ("A".to_string(), "1".to_string()),
+        ("CARGO_INCREMENTAL".to_string(), "0".to_string()),
+        ("RUSTFLAGS".to_string(), "-C opt-level=z".to_string()),
+    ]);
+}
+
+#[test]
+fn builtin_profiles() {
+    for name in HubBuildProfile::builtin_names() {
+        assert!(HubBuildProfile::builtin(&name).is_some(), "{}", name);
+    }
+    assert_eq!(HubBuildProfile::builtin("nothing"), None);
+    assert_eq!(args(&HubBuildProfile::builtin("check").unwrap()), strings(&["check", "-p", "app"]));
+    assert_eq!(args(&HubBuildProfile::builtin("debug").unwrap()), strings(&["build", "-p", "app"]));
+    assert_eq!(args(&HubBuildProfile::builtin("release").unwrap()), strings(&["build", "--release", "-p", "app"]));
+    
+    let small = HubBuildProfile::builtin("small").unwrap();
+    assert_eq!(args(&small), strings(&["build", "--release", "-p", "app"]));
+    assert_eq!(small.cargo_env(), vec![("RUSTFLAGS".to_string(), "-C opt-level=z -C panic=abort -C codegen-units=1".to_string())]);
+    assert_eq!(small.post_steps, Some(vec![HubBuildStep::WasmStripDebug]));
+}
+
+#[test]
+fn profile_from_settings() {
+    // fields left out of the settings file are None and change nothing
+    let profile: HubBuildProfile = DeRon::deserialize_ron(
+        "(release:true,features:[\"simd\"],post_steps:[WasmStrip(keep_names:true),Command(cmd:\"ls\",args:[\"-l\"])])"
+    ).unwrap();
+    assert_eq!(args(&profile), strings(&["build", "--release", "-p", "app", "--features", "simd"]));
+    assert_eq!(profile.cargo_env(), vec![]);
+    assert_eq!(profile.post_steps.unwrap().iter().map( | step | step.describe()).collect::<Vec<_>>(), strings(&["wasm strip keeping names", "ls -l"]));
+}
@@ -0,0 +1,131 @@
+// the queueing rules of a builder daemon, and a build that gets killed before cargo runs
+use makepad_hub::*;
+use makepad_http::channel::*;
+use std::sync::{Arc, Mutex, mpsc};
+use std::collections::HashMap;
+
+fn uid(id: u64) -> HubUid {
+    HubUid {addr: HubAddr::None, id: id}
+}
+
+fn push(queue: &mut BuildQueue, id: u64, package: &str) -> BuildQueueAction {
+    queue.push(HubAddr::None, uid(id), "main", package, "release")
+}
+
+#[test]
+fn identical_builds_share_a_job() {
+    let mut queue = BuildQueue::default();
+    assert!(matches!(push(&mut queue, 1, "a"), BuildQueueAction::Queued));
+    assert!(matches!(push(&mut queue, 2, "a"), BuildQueueAction::Merged {uid} if uid == self::uid(1)));
+    assert!(matches!(push(&mut queue, 3, "b"), BuildQueueAction::Queued));
+    assert!(matches!(queue.push(HubAddr::None, uid(4), "main", "a", "debug"), BuildQueueAction::Queued));
+    assert_eq!(queue.jobs.len(), 3);
+    
+    // one at a time, in order
+    let (_, job) = queue.start_next().unwrap();
+    assert_eq!(job.uids, vec![uid(1), uid(2)]);
+    assert!(queue.start_next().is_none());
+    // joining a running build that is up to date
+    assert!(matches!(push(&mut queue, 5, "a"), BuildQueueAction::Merged {..}));
+    assert_eq!(queue.finish(uid(1)), vec![uid(1), uid(2), uid(5)]);
+    assert_eq!(queue.start_next().unwrap().1.uid, uid(3));
+    assert_eq!(queue.finish(uid(3)), vec![uid(3)]);
+    assert_eq!(queue.start_next().unwrap().1.uid, uid(4));
+}
+
+#[test]
+fn stale_running_build_is_superseded() {
+    let mut queue = BuildQueue::default();
+    push(&mut queue, 1, "a");
+    push(&mut queue, 2, "a");
+    queue.start_next();
+    // writes to another workspace or to queued builds dont matter
+    queue.mark_stale("other");
+    assert!(matches!(push(&mut queue, 3, "a"), BuildQueueAction::Merged {..}));
+    
+    queue.mark_stale("main");
+    match push(&mut queue, 4, "a") {
+        BuildQueueAction::Superseded {kill_uid} => assert_eq!(kill_uid, uid(1)),
+        _ => panic!("expected the running build to be superseded")
+    }
+    // everyone that waited on the old build waits on the new one
+    assert_eq!(queue.jobs.len(), 1);
+    assert_eq!(queue.jobs[0].job.uids, vec![uid(4), uid(2), uid(3)]);
+    assert!(!queue.jobs[0].job.running);
+    assert!(!queue.is_running());
+    // the killed build returning doesnt take the new job with it
+    assert_eq!(queue.finish(uid(1)), vec![]);
+    assert_eq!(queue.start_next().unwrap().1.uid, uid(4));
+}
+
+#[test]
+fn cancel() {
+    let mut queue = BuildQueue::default();
+    assert!(matches!(queue.cancel(uid(9)), BuildQueueCancel::NotQueued));
+    push(&mut queue, 1, "a");
+    push(&mut queue, 2, "a");
+    push(&mut queue, 3, "b");
+    queue.start_next();
+    assert!(matches!(queue.cancel(uid(2)), BuildQueueCancel::Detached));
+    assert!(matches!(queue.cancel(uid(3)), BuildQueueCancel::Removed));
+    assert!(matches!(queue.cancel(uid(1)), BuildQueueCancel::Kill {uid} if uid == self::uid(1)));
+    // a killed job stays until its build returns
+    assert_eq!(queue.jobs.len(), 1);
+    assert_eq!(queue.finish(uid(1)), vec![]);
+    assert_eq!(queue.jobs.len(), 0);
+}
+
+#[test]
+fn restart_after_kill() {
+    // what a restart does, kill the running build and ask for the same one again
+    let mut queue = BuildQueue::default();
+    push(&mut queue, 1, "a");
+    queue.start_next();
+    assert!(matches!(queue.cancel(uid(1)), BuildQueueCancel::Kill {..}));
+    match push(&mut queue, 2, "a") {
+        BuildQueueAction::Superseded {kill_uid} => assert_eq!(kill_uid, uid(1)),
+        _ => panic!("expected a job of its own, not one that is being killed")
+    }
+    assert_eq!(queue.jobs.len(), 1);
+    assert_eq!(queue.jobs[0].job.uids, vec![uid(2)]);
+    // the killed build returning fails nobody else
+    assert_eq!(queue.finish(uid(1)), vec![]);
+    assert_eq!(queue.start_next().unwrap().1.uid, uid(2));
+    assert_eq!(queue.finish(uid(2)), vec![uid(2)]);
+}
+
+#[test]
+fn killed_before_cargo_starts() {
+    let (tx_pump, rx_pump) = mpsc::channel();
+    let mut workspaces = HashMap::new();
+    workspaces.insert("main".to_string(), std::env::temp_dir().display().to_string());
+    let mut hub_builder = HubBuilder {
+        route_send: HubRouteSend::Direct {uid_alloc: Arc::new(Mutex::new(0)), tx_pump: tx_pump, own_addr: HubAddr::None},
+        http_server: Arc::new(Mutex::new(None)),
+        workspaces: Arc::new(Mutex::new(workspaces)),
+        build_profiles: Arc::new(Mutex::new(HashMap::new())),
+        websocket_channels: WebSocketChannels::default(),
+        builder: "test".to_string(),
+        abs_cwd_path: "".to_string(),
+        processes: Arc::new(Mutex::new(Vec::new())),
+        cancelled: Arc::new(Mutex::new(Vec::new())),
+    };
+    // superseded while it was still running its pre-steps, no process exists yet
+    hub_builder.process_kill(uid(1));
+    let profile = HubBuildProfile {
+        check: Some(true),
+        release: None,
+        features: None,
+        target: None,
+        env: None,
+        pre_steps: Some(vec![HubBuildStep::WasmValidate]),
+        post_steps: None
+    };
+    assert!(hub_builder.cargo_profile(uid(1), "main", "nothing", &profile).is_err());
+    assert_eq!(hub_builder.processes.lock().unwrap().len(), 0);
+    let cargo_began = rx_pump.try_iter().any( | (_, msg) | if let HubMsg::CargoBegin {..} = msg.msg {true} else {false});
+    assert!(!cargo_began);
+    
+    hub_builder.clear_cancelled(uid(1));
+    assert!(!hub_builder.is_cancelled(uid(1)));
+}
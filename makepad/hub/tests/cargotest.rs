@@ -0,0 +1,124 @@
+// feeds captured cargo test output through the parser and checks the results that come out
+use makepad_hub::*;
+
+fn parse(stderr_then_stdout: &[(bool, &str)]) -> (CargoTestParser, Vec<CargoTestResult>) {
+    let mut parser = CargoTestParser::default();
+    let mut results = Vec::new();
+    for (is_stderr, line) in stderr_then_stdout {
+        if *is_stderr {
+            parser.parse_stderr_line(line);
+        }
+        else {
+            parser.parse_stdout_line(line, &mut results);
+        }
+    }
+    parser.finish(&mut results);
+    (parser, results)
+}
+
+fn ok(suite: &str, name: &str) -> CargoTestResult {
+    CargoTestResult::Ok {suite: suite.to_string(), name: name.to_string()}
+}
+
+#[test]
+fn new_style_panics() {
+    let (parser, results) = parse(&[
+        (true, "   Compiling mycrate v0.1.0 (/work/mycrate)"),
+        (true, "    Finished `test` profile [unoptimized + debuginfo] target(s) in 0.52s"),
+        (true, "     Running unittests src/lib.rs (target/debug/deps/mycrate-1f2e3d4c5b6a7988)"),
+        (false, ""),
+        (false, "running 3 tests"),
+        (false, "test tests::adds ... ok"),
+        (false, "test tests::slow ... ignored, takes a minute"),
+        (false, "test tests::breaks ... FAILED"),
+        (false, ""),
+        (false, "failures:"),
+        (false, ""),
+        (false, "---- tests::breaks stdout ----"),
+        (false, "some println"),
+        (false, ""),
+        (false, "thread 'tests::breaks' (12345) panicked at src/lib.rs:14:9:"),
+        (false, "assertion `left == right` failed"),
+        (false, "  left: 1"),
+        (false, " right: 2"),
+        (false, "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace"),
+        (false, ""),
+        (false, ""),
+        (false, "failures:"),
+        (false, "    tests::breaks"),
+        (false, ""),
+        (false, "test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s"),
+    ]);
+    assert_eq!((parser.passed, parser.failed, parser.ignored), (1, 1, 1));
+    let suite = "unittests src/lib.rs";
+    assert_eq!(results[0], ok(suite, "tests::adds"));
+    assert_eq!(results[1], CargoTestResult::Ignored {suite: suite.to_string(), name: "tests::slow".to_string()});
+    match &results[2] {
+        CargoTestResult::Failed {suite: s, name, panic: Some(panic), output} => {
+            assert_eq!((s.as_str(), name.as_str()), (suite, "tests::breaks"));
+            assert_eq!((panic.path.as_str(), panic.line, panic.column), ("src/lib.rs", 14, 9));
+            assert_eq!(panic.message, "assertion `left == right` failed\n  left: 1\n right: 2");
+            assert_eq!(output[0], "some println");
+        },
+        other => panic!("{:?}", other)
+    }
+    assert_eq!(results.len(), 3);
+}
+
+#[test]
+fn old_style_panics_and_suites() {
+    let (_, results) = parse(&[
+        (true, "     Running target/debug/deps/other-0a1b2c3d"),
+        (false, "running 1 test"),
+        (false, "test it_works ... FAILED"),
+        (false, "failures:"),
+        (false, "---- it_works stdout ----"),
+        (false, "thread 'it_works' panicked at 'oh no, it broke', tests\\it.rs:3:5"),
+        (false, "failures:"),
+        (false, "    it_works"),
+        (false, "test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out"),
+        (true, "   Doc-tests other"),
+        (false, "running 1 test"),
+        (false, "test src/lib.rs - add (line 3) ... ok"),
+        (false, "test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out"),
+    ]);
+    match &results[0] {
+        CargoTestResult::Failed {suite, panic: Some(panic), ..} => {
+            assert_eq!(suite, "target/debug/deps/other-0a1b2c3d");
+            assert_eq!((panic.path.as_str(), panic.line, panic.column), ("tests/it.rs", 3, 5));
+            assert_eq!(panic.message, "oh no, it broke");
+        },
+        other => panic!("{:?}", other)
+    }
+    assert_eq!(results[1], ok("Doc-tests other", "src/lib.rs - add (line 3)"));
+}
+
+#[test]
+fn failures_without_a_location() {
+    // a test returning Err prints no panic location, a killed run prints no output block at all
+    let (_, results) = parse(&[
+        (true, "     Running tests/api.rs (target/debug/deps/api-99aa)"),
+        (false, "running 2 tests"),
+        (false, "test returns_err ... FAILED"),
+        (false, "test killed ... FAILED"),
+        (false, "failures:"),
+        (false, "---- returns_err stdout ----"),
+        (false, "Error: \"nope\""),
+        (false, "failures:"),
+    ]);
+    assert_eq!(results.len(), 2);
+    match &results[0] {
+        CargoTestResult::Failed {name, panic: None, output, ..} => {
+            assert_eq!(name, "returns_err");
+            assert_eq!(output[0], "Error: \"nope\"");
+        },
+        other => panic!("{:?}", other)
+    }
+    match &results[1] {
+        CargoTestResult::Failed {name, panic: None, output, ..} => {
+            assert_eq!(name, "killed");
+            assert!(output.is_empty());
+        },
+        other => panic!("{:?}", other)
+    }
+}
@@ -0,0 +1,100 @@
+// diffs workspace trees and patches files the way a sync between two builders does
+use makepad_hub::*;
+
+fn file(name: &str, data: &[u8]) -> BuilderFileTreeNode {
+    BuilderFileTreeNode::File {name: name.to_string(), digest: Some(Box::new(file_content_digest(data)))}
+}
+
+fn folder(name: &str, folder: Vec<BuilderFileTreeNode>) -> BuilderFileTreeNode {
+    BuilderFileTreeNode::Folder {name: name.to_string(), digest: None, folder: folder}
+}
+
+fn path(path: &str) -> String {
+    path.to_string()
+}
+
+#[test]
+fn diff_trees() {
+    let source = folder("ws", vec![
+        folder("src", vec![file("main.rs", b"fn main(){}"), file("lib.rs", b"pub mod a;"), file("moved.rs", b"moved")]),
+        file("new.txt", b"new"),
+        file("same.txt", b"same"),
+    ]);
+    let target = folder("ws", vec![
+        folder("src", vec![file("main.rs", b"fn main(){ }"), file("lib.rs", b"pub mod a;")]),
+        file("old.rs", b"moved"),
+        file("gone.txt", b"gone"),
+        file("same.txt", b"same"),
+    ]);
+    assert_eq!(diff_file_trees(&source, &target), vec![
+        FileSyncOp::Change {path: path("src/main.rs")},
+        FileSyncOp::Create {path: path("new.txt")},
+        FileSyncOp::Delete {path: path("gone.txt")},
+        FileSyncOp::Rename {path: path("old.rs"), new_path: path("src/moved.rs")},
+    ]);
+    assert_eq!(diff_file_trees(&source, &source), vec![]);
+    
+    // files without a digest are always sent
+    let undigested = folder("ws", vec![BuilderFileTreeNode::File {name: path("a"), digest: None}]);
+    assert_eq!(diff_file_trees(&undigested, &undigested), vec![FileSyncOp::Change {path: path("a")}]);
+}
+
+fn patch_round_trip(old: &[u8], new: &[u8], block_size: usize) -> usize {
+    let blocks = file_patch_blocks(new, block_size, &file_block_digests(old, block_size));
+    assert_eq!(apply_file_patch(old, new.len(), block_size, &blocks), new);
+    blocks.len()
+}
+
+#[test]
+fn patch_files() {
+    let old: Vec<u8> = (0..1000u32).map( | i | (i * 7) as u8).collect();
+    
+    let mut one_byte = old.clone();
+    one_byte[500] ^= 0xff;
+    assert_eq!(patch_round_trip(&old, &one_byte, 64), 1);
+    assert_eq!(patch_round_trip(&old, &old, 64), 0);
+    
+    let mut grown = old.clone();
+    grown.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(patch_round_trip(&old, &grown, 64), 1);
+    assert_eq!(patch_round_trip(&old, &old[0..900], 64), 1);
+    assert_eq!(patch_round_trip(&old, &[], 64), 0);
+    assert_eq!(patch_round_trip(&[], &old, 64), 16);
+    
+    // a shorter last block that only differs by trailing zeros still changes
+    let mut zeros = old[0..990].to_vec();
+    zeros.extend_from_slice(&[0; 10]);
+    assert_eq!(patch_round_trip(&zeros, &zeros[0..995], 64), 1);
+    
+    // a change far into the block is seen, not just its first 8 bytes of digest state
+    let mut deep = old.clone();
+    deep[63] ^= 1;
+    assert_eq!(patch_round_trip(&old, &deep, 64), 1);
+}
+
+fn from(msg: HubMsg) -> FromHubMsg {
+    FromHubMsg {from: HubAddr::None, msg: msg}
+}
+
+#[test]
+fn unreadable_source_is_skipped() {
+    let uid = HubUid {addr: HubAddr::None, id: 1};
+    let mut out = Vec::new();
+    let mut job = FileSyncJob::start(uid, "a/ws", "b/ws", false, &mut out);
+    assert_eq!(out.len(), 2);
+    
+    let tree = | builder: &str, data: &[u8] | folder(builder, vec![folder("ws", vec![file("f.txt", data)])]);
+    out.truncate(0);
+    job.handle_hub_msg(&from(HubMsg::BuilderFileTreeResponse {uid: uid, tree: tree("a", b"new")}), &mut out);
+    job.handle_hub_msg(&from(HubMsg::BuilderFileTreeResponse {uid: uid, tree: tree("b", b"old")}), &mut out);
+    assert_eq!(job.ops, vec![FileSyncOp::Change {path: path("f.txt")}]);
+    assert_eq!(job.state, FileSyncState::Transfer);
+    
+    out.truncate(0);
+    job.handle_hub_msg(&from(HubMsg::FileBlockDigestsResponse {uid: uid, path: path("ws/f.txt"), digests: Some(file_block_digests(b"old", SYNC_BLOCK_SIZE))}), &mut out);
+    job.handle_hub_msg(&from(HubMsg::FileReadResponse {uid: uid, path: path("ws/f.txt"), data: None}), &mut out);
+    // nothing gets written to the target
+    assert_eq!(out.len(), 0);
+    assert!(job.is_done());
+    assert_eq!(job.report[0], "Sync: failed to read a/ws/f.txt, skipped");
+}
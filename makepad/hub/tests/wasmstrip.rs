@@ -0,0 +1,211 @@
+// parses, validates, reports and strips small wasm modules put together by hand
+use makepad_hub::*;
+
+fn leb(mut value: usize, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn name(name: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    leb(name.len(), &mut out);
+    out.extend_from_slice(name.as_bytes());
+    out
+}
+
+fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![id];
+    leb(payload.len(), &mut out);
+    out.extend_from_slice(payload);
+    out
+}
+
+fn custom(section_name: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = name(section_name);
+    body.extend_from_slice(payload);
+    section(0, &body)
+}
+
+fn module(sections: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = vec![0, 97, 115, 109, 1, 0, 0, 0];
+    for section in sections {
+        out.extend_from_slice(section);
+    }
+    out
+}
+
+fn types() -> Vec<u8> {
+    // one type, a function without params or results
+    section(1, &[1, 0x60, 0, 0])
+}
+
+fn imports() -> Vec<u8> {
+    // env.f, a function of type 0
+    let mut payload = vec![1];
+    payload.extend(name("env"));
+    payload.extend(name("f"));
+    payload.extend(&[0, 0]);
+    section(2, &payload)
+}
+
+fn functions(count: u8) -> Vec<u8> {
+    let mut payload = vec![count];
+    payload.extend((0..count).map( | _ | 0));
+    section(3, &payload)
+}
+
+fn code() -> Vec<u8> {
+    // a body of 2 bytes, no locals and end, and one of 5 with three nops
+    section(10, &[2, 2, 0, 0x0b, 5, 0, 1, 1, 1, 0x0b])
+}
+
+fn names() -> Vec<u8> {
+    // function names, index 0 is the import
+    let mut sub = vec![2, 1];
+    sub.extend(name("small"));
+    sub.push(2);
+    sub.extend(name("big"));
+    let mut payload = vec![1];
+    leb(sub.len(), &mut payload);
+    payload.extend(sub);
+    custom("name", &payload)
+}
+
+fn full() -> Vec<u8> {
+    module(&[types(), imports(), functions(2), code(), custom(".debug_info", &[1, 2, 3]), names(), custom("producers", &[0])])
+}
+
+fn kinds(buf: &[u8]) -> Vec<String> {
+    read_wasm_sections(buf).unwrap().iter().map( | s | if s.type_id == 0 {format!("custom {}", s.name)} else {s.kind().to_string()}).collect()
+}
+
+fn strings(v: &[&str]) -> Vec<String> {
+    v.iter().map( | s | s.to_string()).collect()
+}
+
+#[test]
+fn sections() {
+    let buf = full();
+    assert_eq!(kinds(&buf), strings(&["type", "import", "function", "code", "custom .debug_info", "custom name", "custom producers"]));
+    // the sections cover the module after the header
+    let sections = read_wasm_sections(&buf).unwrap();
+    assert_eq!(sections[0].start, 8);
+    for pair in sections.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+    assert_eq!(sections.last().unwrap().end, buf.len());
+    assert_eq!(sections[3].len(), code().len());
+    assert_eq!(wasm_validate(&buf), Ok(()));
+    // the empty module is fine
+    assert_eq!(wasm_validate(&module(&[])), Ok(()));
+}
+
+#[test]
+fn bad_modules() {
+    let buf = full();
+    let mut magic = buf.clone();
+    magic[1] = b'b';
+    assert!(read_wasm_sections(&magic).is_err());
+    assert_eq!(wasm_validate(&magic), Err("cannot parse wasm sections".to_string()));
+    assert!(wasm_strip(&magic, false).is_err());
+    assert!(wasm_report(&magic, 10).is_err());
+    let mut version = buf.clone();
+    version[4] = 2;
+    assert!(read_wasm_sections(&version).is_err());
+    
+    // cut anywhere but between sections, it doesn't parse
+    let ends: Vec<usize> = read_wasm_sections(&buf).unwrap().iter().map( | s | s.end).collect();
+    for len in 0..buf.len() {
+        if len != 8 && !ends.contains(&len) {
+            assert!(read_wasm_sections(&buf[..len]).is_err(), "cut at {}", len);
+            assert!(wasm_validate(&buf[..len]).is_err(), "cut at {}", len);
+            assert!(wasm_strip(&buf[..len], true).is_err(), "cut at {}", len);
+        }
+    }
+    // and cut between sections a missing code section still shows
+    let cut = &buf[..ends[2]];
+    assert_eq!(wasm_validate(cut), Err("function section declares 2 functions but code section has 0 bodies".to_string()));
+    
+    // a custom section name running past the section
+    let mut long_name = module(&[custom("name", &[])]);
+    long_name[9] = 1;
+    assert!(read_wasm_sections(&long_name).is_err());
+}
+
+#[test]
+fn validate() {
+    assert_eq!(
+        wasm_validate(&module(&[types(), functions(1), code()])),
+        Err("function section declares 1 functions but code section has 2 bodies".to_string())
+    );
+    assert_eq!(wasm_validate(&module(&[types(), functions(2), code(), types()])), Err("type section out of order or duplicated".to_string()));
+    assert_eq!(wasm_validate(&module(&[imports(), types()])), Err("type section out of order or duplicated".to_string()));
+    assert_eq!(wasm_validate(&module(&[section(14, &[])])), Err("unknown section id 14".to_string()));
+    // datacount comes before code and has to match the data section
+    assert_eq!(wasm_validate(&module(&[section(12, &[1]), section(11, &[0])])), Err("datacount section says 1 segments but data section has 0".to_string()));
+    assert_eq!(wasm_validate(&module(&[section(12, &[0]), section(11, &[0])])), Ok(()));
+    // a count that can't be read
+    assert_eq!(wasm_validate(&module(&[section(3, &[])])), Err("cannot read function section".to_string()));
+}
+
+#[test]
+fn function_sizes() {
+    // biggest first, indices count the imported function
+    let sizes = wasm_function_sizes(&full()).unwrap();
+    let sizes: Vec<(usize, Option<String>, usize)> = sizes.into_iter().map( | f | (f.index, f.name, f.size)).collect();
+    assert_eq!(sizes, vec![(2, Some("big".to_string()), 5), (1, Some("small".to_string()), 2)]);
+    // the sizes add up to the code section, with a length byte per body and one for the count
+    assert_eq!(sizes.iter().map( | f | f.2 + 1).sum::<usize>() + 1 + 2, code().len());
+    
+    // without imports and names
+    let sizes = wasm_function_sizes(&module(&[types(), functions(2), code()])).unwrap();
+    let sizes: Vec<(usize, Option<String>, usize)> = sizes.into_iter().map( | f | (f.index, f.name, f.size)).collect();
+    assert_eq!(sizes, vec![(1, None, 5), (0, None, 2)]);
+    assert_eq!(wasm_function_sizes(&module(&[types()])).unwrap().len(), 0);
+}
+
+#[test]
+fn strip() {
+    let buf = full();
+    let stripped = wasm_strip(&buf, false).unwrap();
+    assert_eq!(kinds(&stripped), strings(&["type", "import", "function", "code"]));
+    assert_eq!(stripped, module(&[types(), imports(), functions(2), code()]));
+    assert_eq!(wasm_strip_debug(&buf).unwrap(), stripped);
+    assert_eq!(wasm_validate(&stripped), Ok(()));
+    assert!(wasm_function_sizes(&stripped).unwrap().iter().all( | f | f.name.is_none()));
+    
+    // keeping names drops the other custom sections but not the name section
+    let named = wasm_strip(&buf, true).unwrap();
+    assert_eq!(kinds(&named), strings(&["type", "import", "function", "code", "custom name"]));
+    assert_eq!(named, module(&[types(), imports(), functions(2), code(), names()]));
+    assert_eq!(wasm_function_sizes(&named).unwrap()[0].name, Some("big".to_string()));
+    
+    // stripping again changes nothing
+    assert_eq!(wasm_strip(&named, true).unwrap(), named);
+}
+
+#[test]
+fn report() {
+    let buf = wasm_strip(&full(), true).unwrap();
+    assert_eq!(wasm_report(&buf, 1).unwrap(), vec![
+        format!("Wasm size {} bytes, 5 sections", buf.len()),
+        format!("  {:>10} type", types().len()),
+        format!("  {:>10} import", imports().len()),
+        format!("  {:>10} function", functions(2).len()),
+        format!("  {:>10} code", code().len()),
+        format!("  {:>10} custom \"name\"", names().len()),
+        "Biggest 1 of 2 functions".to_string(),
+        format!("  {:>10} big", 5),
+    ]);
+    // unnamed functions go by index, and asking for none leaves the list out
+    let lines = wasm_report(&module(&[types(), functions(2), code()]), 5).unwrap();
+    assert_eq!(lines[lines.len() - 3..].to_vec(), vec!["Biggest 2 of 2 functions".to_string(), format!("  {:>10} func[1]", 5), format!("  {:>10} func[0]", 2)]);
+    assert_eq!(wasm_report(&buf, 0).unwrap().len(), 6);
+}
@@ -6,7 +6,7 @@ use crate::lit::Lit;
 use crate::ty::TyLit;
 use crate::math::*;
 use crate::livestyles::{LiveStyles, LiveStyle};
-use crate::livetypes::{Font, LiveItemId, Play, Anim, Ease, Track};
+use crate::livetypes::{Font, LiveItemId, Play, Anim, Ease, Track, KeyCurve, TrackValue, GridTemplate, GridTrack, GRID_TEMPLATE_MAX};
 use std::iter::Cloned;
 use std::slice::Iter;
 
@@ -368,6 +368,22 @@ impl<T> DeTok for Option<T> where T: DeTok {
     }
 }
 
+impl DeTok for GridTemplate {
+    fn de_tok(p: &mut dyn DeTokParser) -> Result<GridTemplate, LiveError> {
+        p.expect_token(Token::LeftBracket) ?;
+        let mut template = GridTemplate::default();
+        while !p.accept_token(Token::RightBracket) {
+            if template.len == GRID_TEMPLATE_MAX {
+                return Err(p.error(format!("Grid template has more than {} tracks", GRID_TEMPLATE_MAX)));
+            }
+            template.tracks[template.len] = GridTrack::de_tok(p) ?;
+            template.len += 1;
+            p.accept_token(Token::Comma);
+        }
+        Ok(template)
+    }
+}
+
 impl DeTok for Vec2 {
     fn de_tok(p: &mut dyn DeTokParser) -> Result<Vec2, LiveError> {
        p.expect_token(Token::TyLit(TyLit::Vec2)) ?;
@@ -466,6 +482,43 @@ fn parse_track_rhs(p: &mut dyn DeTokParser, time: f64, track: &mut Track) -> Res
     Ok(())
 }
 
+fn parse_track_value(p: &mut dyn DeTokParser, track: &Track) -> Result<Vec4, LiveError> {
+    Ok(match track {
+        Track::Float {..} => f32::de_tok(p) ?.to_vec4(),
+        Track::Vec2 {..} => Vec2::de_tok(p) ?.to_vec4(),
+        Track::Vec3 {..} => Vec3::de_tok(p) ?.to_vec4(),
+        Track::Vec4 {..} => Vec4::de_tok(p) ?,
+    })
+}
+
+// an optional curve after a key value: ease InQuad, step, hermite(out, in) or bezier(out, in)
+fn parse_key_curve(p: &mut dyn DeTokParser, track: &mut Track) -> Result<(), LiveError> {
+    let index = track.key_count() - 1;
+    if p.accept_token(Token::Ident(Ident::new("ease"))) {
+        track.set_key_curve(index, KeyCurve::Ease(Ease::de_tok(p) ?));
+    }
+    else if p.accept_token(Token::Ident(Ident::new("step"))) {
+        track.set_key_curve(index, KeyCurve::Step);
+    }
+    else if p.accept_token(Token::Ident(Ident::new("hermite"))) {
+        p.expect_token(Token::LeftParen) ?;
+        let out_tangent = parse_track_value(p, track) ?;
+        p.expect_token(Token::Comma) ?;
+        let in_tangent = parse_track_value(p, track) ?;
+        p.expect_token(Token::RightParen) ?;
+        track.set_key_curve(index, KeyCurve::Hermite {out_tangent, in_tangent});
+    }
+    else if p.accept_token(Token::Ident(Ident::new("bezier"))) {
+        p.expect_token(Token::LeftParen) ?;
+        let out_handle = parse_track_value(p, track) ?;
+        p.expect_token(Token::Comma) ?;
+        let in_handle = parse_track_value(p, track) ?;
+        p.expect_token(Token::RightParen) ?;
+        track.set_key_curve(index, KeyCurve::Bezier {out_handle, in_handle});
+    }
+    Ok(())
+}
+
 fn parse_track(p: &mut dyn DeTokParser, track: &mut Track) -> Result<(), LiveError> {
     p.expect_token(Token::LeftBrace) ?;
     loop {
@@ -497,11 +550,13 @@ fn parse_track(p: &mut dyn DeTokParser, track: &mut Track) -> Result<(), LiveErr
                                 p.expect_token(Token::Colon) ?;
                                 // now lets parse the RHS
                                 parse_track_rhs(p, i as f64, track) ?;
+                                parse_key_curve(p, track) ?;
                             },
                             Token::Lit(Lit::Float(f)) => { // float time
                                 p.skip_token();
                                 p.expect_token(Token::Colon) ?;
                                 parse_track_rhs(p, f as f64, track) ?;
+                                parse_key_curve(p, track) ?;
                             },
                             Token::RightBrace => {
                                 p.skip_token();
@@ -570,13 +625,15 @@ impl DeTok for Anim {
         p.expect_token(Token::LeftBrace) ?;
         let mut play = Play::Cut {duration: 1.0};
         let mut tracks = Vec::new();
+        let mut events = Vec::new();
         // parse all the crap in it.
         loop {
             
             if p.accept_token(Token::RightBrace) {
                 return Ok(Anim {
                     play: play,
-                    tracks: tracks
+                    tracks: tracks,
+                    events: events
                 })
             }
             
@@ -586,6 +643,29 @@ impl DeTok for Anim {
                 p.expect_token(Token::Colon) ?;
                 play = Play::de_tok(p) ?;
             }
+            else if ident == Ident::new("events") {
+                p.expect_token(Token::Colon) ?;
+                p.expect_token(Token::LeftBrace) ?;
+                loop {
+                    let span = p.begin_span();
+                    let time = match p.peek_token() {
+                        Token::Lit(Lit::Int(i)) => i as f64,
+                        Token::Lit(Lit::Float(f)) => f as f64,
+                        Token::RightBrace => {
+                            p.skip_token();
+                            break;
+                        },
+                        token => {
+                            return Err(span.error(p, format!("Unexpected token in anim events {}", token)));
+                        }
+                    };
+                    p.skip_token();
+                    p.expect_token(Token::Colon) ?;
+                    let ident_path = p.parse_ident_path() ?;
+                    events.push((time, p.qualify_ident_path(&ident_path).to_live_item_id()));
+                    p.accept_token(Token::Comma);
+                }
+            }
             else if ident == Ident::new("tracks") {
                 p.expect_token(Token::Colon) ?;
                 p.expect_token(Token::LeftBracket) ?;
@@ -605,7 +685,8 @@ impl DeTok for Anim {
                             bind_to: LiveItemId(tracks.len() as u64),
                             ease: Ease::Lin,
                             cut_init: None,
-                            keys: Vec::new()
+                            keys: Vec::new(),
+                            curves: Vec::new()
                         };
                         parse_track(p, &mut track) ?;
                         tracks.push(track);
@@ -615,7 +696,8 @@ impl DeTok for Anim {
                             bind_to: LiveItemId(tracks.len() as u64),
                             ease: Ease::Lin,
                             cut_init: None,
-                            keys: Vec::new()
+                            keys: Vec::new(),
+                            curves: Vec::new()
                         };
                         parse_track(p, &mut track) ?;
                         tracks.push(track);
@@ -625,7 +707,8 @@ impl DeTok for Anim {
                             bind_to: LiveItemId(tracks.len() as u64),
                             ease: Ease::Lin,
                             cut_init: None,
-                            keys: Vec::new()
+                            keys: Vec::new(),
+                            curves: Vec::new()
                         };
                         parse_track(p, &mut track) ?;
                         tracks.push(track);
@@ -635,7 +718,8 @@ impl DeTok for Anim {
                             bind_to: LiveItemId(tracks.len() as u64),
                             ease: Ease::Lin,
                             cut_init: None,
-                            keys: Vec::new()
+                            keys: Vec::new(),
+                            curves: Vec::new()
                         };
                         parse_track(p, &mut track) ?;
                         tracks.push(track);
@@ -153,6 +153,9 @@ pub struct Layout {
     pub abs_origin: Option<Vec2>,
     pub abs_size: Option<Vec2>,
     pub walk: Walk,
+    // space between walked items and wrapped lines, or between grid columns and rows
+    pub gap: Vec2,
+    pub grid: Option<Grid>,
 }
 
 #[derive(Copy, Clone, Default, Debug, DeTokSplat, DeTok)]
@@ -179,6 +182,94 @@ impl Layout {
             ..Default::default()
         }
     }
+    
+    pub fn grid(columns: &[GridTrack], rows: &[GridTrack], gap: Vec2) -> Self {
+        Layout {
+            gap,
+            grid: Some(Grid {columns: GridTemplate::new(columns), rows: GridTemplate::new(rows)}),
+            ..Default::default()
+        }
+    }
+    
+    // flex is a grid with a single row, or a single column going down
+    pub fn flex(direction: Direction, tracks: &[GridTrack], gap: f32) -> Self {
+        match direction {
+            Direction::Down | Direction::Up => Self::grid(&[GridTrack::Weight(1.0)], tracks, vec2(0.0, gap)),
+            _ => Self::grid(tracks, &[GridTrack::Weight(1.0)], vec2(gap, 0.0)),
+        }
+    }
+}
+
+// the size of a grid column or row. Weights share what the fixed tracks and gaps leave,
+// auto rows are as high as their tallest item
+#[derive(Copy, Clone, Debug, PartialEq, DeTok)]
+pub enum GridTrack {
+    Fix(f32),
+    Weight(f32),
+    Auto,
+}
+
+impl Default for GridTrack {
+    fn default() -> Self {
+        GridTrack::Weight(1.0)
+    }
+}
+
+pub const GRID_TEMPLATE_MAX: usize = 16;
+
+// a fixed size list of tracks so Layout stays Copy, in live styles it reads as [Fix(100), Weight(1)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct GridTemplate {
+    pub len: usize,
+    pub tracks: [GridTrack; GRID_TEMPLATE_MAX],
+}
+
+impl GridTemplate {
+    pub fn new(tracks: &[GridTrack]) -> Self {
+        let mut template = Self::default();
+        for track in tracks.iter().take(GRID_TEMPLATE_MAX) {
+            template.tracks[template.len] = *track;
+            template.len += 1;
+        }
+        template
+    }
+    
+    pub fn as_slice(&self) -> &[GridTrack] {
+        &self.tracks[0..self.len]
+    }
+    
+    // offset and size of every track in a total size, auto tracks and unknown totals give NaN sizes
+    pub fn resolve(&self, total: f32, gap: f32) -> Vec<(f32, f32)> {
+        let tracks = self.as_slice();
+        let mut fixed = gap * (tracks.len().max(1) - 1) as f32;
+        let mut weights = 0.0;
+        for track in tracks {
+            match track {
+                GridTrack::Fix(v) => fixed += v,
+                GridTrack::Weight(w) => weights += w,
+                GridTrack::Auto => ()
+            }
+        }
+        let unit = if total.is_nan() {std::f32::NAN} else if weights > 0.0 {(total - fixed).max(0.0) / weights} else {0.0};
+        let mut pos = 0.0;
+        let mut out = Vec::new();
+        for track in tracks {
+            let size = match track {
+                GridTrack::Fix(v) => *v,
+                GridTrack::Weight(w) => w * unit,
+                GridTrack::Auto => std::f32::NAN
+            };
+            out.push((pos, size));
+            pos += if size.is_nan() {0.0} else {size} + gap;
+        }
+        out
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, DeTokSplat, DeTok)]
+pub struct Grid {
+    pub columns: GridTemplate,
+    pub rows: GridTemplate,
 }
 
 #[derive(Clone, Copy, Default, Debug, DeTokSplat, DeTok)]
@@ -339,7 +430,7 @@ impl Height {
 }
 
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, SerRon, DeRon, DeTokSplat, DeTok)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, SerRon, DeRon, SerBin, DeBin, DeTokSplat, DeTok)]
 pub struct Rect {
     pub pos: Vec2,
     pub size: Vec2,
@@ -389,12 +480,14 @@ impl Rect {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Anim {
     pub play: Play,
-    pub tracks: Vec<Track>
+    pub tracks: Vec<Track>,
+    // named markers at key times, they come out of event.is_animate when playback crosses them
+    pub events: Vec<(f64, LiveItemId)>
 }
 
 impl Default for Anim {
     fn default() -> Anim {
-        Anim {play: Play::Cut {duration: 0.0}, tracks: Vec::new()}
+        Anim {play: Play::Cut {duration: 0.0}, tracks: Vec::new(), events: Vec::new()}
     }
 }
 
@@ -781,145 +874,155 @@ impl Ease {
     }
 }
 
+// how a key moves on to the next one, keys without a curve use the track ease
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyCurve {
+    Default,
+    Ease(Ease),
+    // hold the value until the next key
+    Step,
+    // tangents are in value per unit of key time, leaving this key and arriving at the next
+    Hermite {out_tangent: Vec4, in_tangent: Vec4},
+    // the two inner control points of a cubic bezier between this key and the next
+    Bezier {out_handle: Vec4, in_handle: Vec4},
+}
+
+impl Default for KeyCurve {
+    fn default() -> Self {
+        KeyCurve::Default
+    }
+}
+
+impl KeyCurve {
+    pub fn interpolate(&self, ease: &Ease, t0: f64, v0: Vec4, t1: f64, v1: Vec4, time: f64) -> Vec4 {
+        let s = ((time - t0) / (t1 - t0)).max(0.0).min(1.0);
+        match self {
+            KeyCurve::Default => {
+                let f = ease.map(s) as f32;
+                v0 * (1.0 - f) + v1 * f
+            },
+            KeyCurve::Ease(ease) => {
+                let f = ease.map(s) as f32;
+                v0 * (1.0 - f) + v1 * f
+            },
+            KeyCurve::Step => {
+                if s >= 1.0 {v1} else {v0}
+            },
+            KeyCurve::Hermite {out_tangent, in_tangent} => {
+                let s = s as f32;
+                let dt = (t1 - t0) as f32;
+                let s2 = s * s;
+                let s3 = s2 * s;
+                v0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+                    + *out_tangent * ((s3 - 2.0 * s2 + s) * dt)
+                    + v1 * (-2.0 * s3 + 3.0 * s2)
+                    + *in_tangent * ((s3 - s2) * dt)
+            },
+            KeyCurve::Bezier {out_handle, in_handle} => {
+                let s = s as f32;
+                let n = 1.0 - s;
+                v0 * (n * n * n) + *out_handle * (3.0 * n * n * s) + *in_handle * (3.0 * n * s * s) + v1 * (s * s * s)
+            }
+        }
+    }
+}
+
+// the value types a track can hold, they are interpolated as a vec4
+pub trait TrackValue: Copy {
+    fn to_vec4(self) -> Vec4;
+    fn from_vec4(v: Vec4) -> Self;
+}
+
+impl TrackValue for f32 {
+    fn to_vec4(self) -> Vec4 {Vec4 {x: self, y: 0., z: 0., w: 0.}}
+    fn from_vec4(v: Vec4) -> Self {v.x}
+}
+
+impl TrackValue for Vec2 {
+    fn to_vec4(self) -> Vec4 {Vec4 {x: self.x, y: self.y, z: 0., w: 0.}}
+    fn from_vec4(v: Vec4) -> Self {Vec2 {x: v.x, y: v.y}}
+}
+
+impl TrackValue for Vec3 {
+    fn to_vec4(self) -> Vec4 {Vec4 {x: self.x, y: self.y, z: self.z, w: 0.}}
+    fn from_vec4(v: Vec4) -> Self {Vec3 {x: v.x, y: v.y, z: v.z}}
+}
+
+impl TrackValue for Vec4 {
+    fn to_vec4(self) -> Vec4 {self}
+    fn from_vec4(v: Vec4) -> Self {v}
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Track {
     Float {
         bind_to: LiveItemId,
         ease: Ease,
         cut_init: Option<f32>,
-        keys: Vec<(f64, f32)>
+        keys: Vec<(f64, f32)>,
+        curves: Vec<KeyCurve>
     },
     Vec2 {
         bind_to: LiveItemId,
         ease: Ease,
         cut_init: Option<Vec2>,
-        keys: Vec<(f64, Vec2)>
+        keys: Vec<(f64, Vec2)>,
+        curves: Vec<KeyCurve>
     },
     Vec3 {
         bind_to: LiveItemId,
         ease: Ease,
         cut_init: Option<Vec3>,
-        keys: Vec<(f64, Vec3)>
+        keys: Vec<(f64, Vec3)>,
+        curves: Vec<KeyCurve>
     },
     Vec4 {
         bind_to: LiveItemId,
         ease: Ease,
         cut_init: Option<Vec4>,
-        keys: Vec<(f64, Vec4)>
+        keys: Vec<(f64, Vec4)>,
+        curves: Vec<KeyCurve>
     },
 }
 
 impl Track {
     
-    pub fn compute_track_float(time: f64, track: &Vec<(f64, f32)>, cut_init: &mut Option<f32>, init: f32, ease: &Ease) -> f32 {
+    // curves run parallel to keys, a missing entry is KeyCurve::Default
+    pub fn compute_track<T: TrackValue>(time: f64, track: &Vec<(f64, T)>, curves: &Vec<KeyCurve>, cut_init: &mut Option<T>, init: T, ease: &Ease) -> T {
         if track.is_empty() {return init}
-        fn lerp(a: f32, b: f32, f: f32) -> f32 {
-            return a * (1.0 - f) + b * f;
-        }
-        // find the 2 keys we want
-        for i in 0..track.len() {
-            if time >= track[i].0 { // we found the left key
-                let val1 = &track[i];
-                if i == track.len() - 1 { // last key
-                    return val1.1.clone()
-                }
-                let val2 = &track[i + 1];
-                // lerp it
-                let f = ease.map((time - val1.0) / (val2.0 - val1.0)) as f32;
-                return lerp(val1.1, val2.1, f);
+        // the first key we are past is the left key, like it always was
+        if let Some(i) = track.iter().position( | key | time >= key.0) {
+            let val1 = &track[i];
+            if i == track.len() - 1 { // last key
+                return val1.1
             }
+            let val2 = &track[i + 1];
+            let curve = curves.get(i).unwrap_or(&KeyCurve::Default);
+            return T::from_vec4(curve.interpolate(ease, val1.0, val1.1.to_vec4(), val2.0, val2.1.to_vec4(), time))
         }
+        // before the first key we come from wherever the value was when the anim started
         if cut_init.is_none() {
             *cut_init = Some(init);
         }
         let val2 = &track[0];
-        let val1 = cut_init.as_mut().unwrap();
-        let f = ease.map(time / val2.0) as f32;
-        return lerp(*val1, val2.1, f)
+        let val1 = cut_init.unwrap();
+        T::from_vec4(KeyCurve::Default.interpolate(ease, 0.0, val1.to_vec4(), val2.0, val2.1.to_vec4(), time))
     }
     
-    pub fn compute_track_vec2(time: f64, track: &Vec<(f64, Vec2)>, cut_init: &mut Option<Vec2>, init: Vec2, ease: &Ease) -> Vec2 {
-        if track.is_empty() {return init}
-        fn lerp(a: Vec2, b: Vec2, f: f32) -> Vec2 {
-            let nf = 1.0 - f;
-            return Vec2 {x: a.x * nf + b.x * f, y: a.y * nf + b.y * f}
-        }
-        // find the 2 keys we want
-        for i in 0..track.len() {
-            if time >= track[i].0 { // we found the left key
-                let val1 = &track[i];
-                if i == track.len() - 1 { // last key
-                    return val1.1.clone()
-                }
-                let val2 = &track[i + 1];
-                // lerp it
-                let f = ease.map((time - val1.0) / (val2.0 - val1.0)) as f32;
-                return lerp(val1.1, val2.1, f);
-            }
-        }
-        if cut_init.is_none() {
-            *cut_init = Some(init);
-        }
-        let val2 = &track[0];
-        let val1 = cut_init.as_mut().unwrap();
-        let f = ease.map(time / val2.0) as f32;
-        return lerp(*val1, val2.1, f)
+    pub fn compute_track_float(time: f64, track: &Vec<(f64, f32)>, curves: &Vec<KeyCurve>, cut_init: &mut Option<f32>, init: f32, ease: &Ease) -> f32 {
+        Self::compute_track(time, track, curves, cut_init, init, ease)
     }
     
-    pub fn compute_track_vec3(time: f64, track: &Vec<(f64, Vec3)>, cut_init: &mut Option<Vec3>, init: Vec3, ease: &Ease) -> Vec3 {
-        if track.is_empty() {return init}
-        fn lerp(a: Vec3, b: Vec3, f: f32) -> Vec3 {
-            let nf = 1.0 - f;
-            return Vec3 {x: a.x * nf + b.x * f, y: a.y * nf + b.y * f, z: a.z * nf + b.z * f}
-        }
-        // find the 2 keys we want
-        for i in 0..track.len() {
-            if time >= track[i].0 { // we found the left key
-                let val1 = &track[i];
-                if i == track.len() - 1 { // last key
-                    return val1.1.clone()
-                }
-                let val2 = &track[i + 1];
-                // lerp it
-                let f = ease.map((time - val1.0) / (val2.0 - val1.0)) as f32;
-                return lerp(val1.1, val2.1, f);
-            }
-        }
-        if cut_init.is_none() {
-            *cut_init = Some(init);
-        }
-        let val2 = &track[0];
-        let val1 = cut_init.as_mut().unwrap();
-        let f = ease.map(time / val2.0) as f32;
-        return lerp(*val1, val2.1, f)
+    pub fn compute_track_vec2(time: f64, track: &Vec<(f64, Vec2)>, curves: &Vec<KeyCurve>, cut_init: &mut Option<Vec2>, init: Vec2, ease: &Ease) -> Vec2 {
+        Self::compute_track(time, track, curves, cut_init, init, ease)
     }
     
-    pub fn compute_track_vec4(time: f64, track: &Vec<(f64, Vec4)>, cut_init: &mut Option<Vec4>, init: Vec4, ease: &Ease) -> Vec4 {
-        if track.is_empty() {return init}
-        fn lerp(a: Vec4, b: Vec4, f: f32) -> Vec4 {
-            let nf = 1.0 - f;
-            return Vec4 {x: a.x * nf + b.x * f, y: a.y * nf + b.y * f, z: a.z * nf + b.z * f, w: a.w * nf + b.w * f}
-        }
-        // find the 2 keys we want
-        for i in 0..track.len() {
-            if time >= track[i].0 { // we found the left key
-                let val1 = &track[i];
-                if i == track.len() - 1 { // last key
-                    return val1.1.clone()
-                }
-                let val2 = &track[i + 1];
-                // lerp it
-                let f = ease.map((time - val1.0) / (val2.0 - val1.0)) as f32;
-                return lerp(val1.1, val2.1, f);
-            }
-        }
-        if cut_init.is_none() {
-            *cut_init = Some(init);
-        }
-        let val2 = &track[0];
-        let val1 = cut_init.as_mut().unwrap();
-        let f = ease.map(time / val2.0) as f32;
-        return lerp(*val1, val2.1, f)
+    pub fn compute_track_vec3(time: f64, track: &Vec<(f64, Vec3)>, curves: &Vec<KeyCurve>, cut_init: &mut Option<Vec3>, init: Vec3, ease: &Ease) -> Vec3 {
+        Self::compute_track(time, track, curves, cut_init, init, ease)
+    }
+    
+    pub fn compute_track_vec4(time: f64, track: &Vec<(f64, Vec4)>, curves: &Vec<KeyCurve>, cut_init: &mut Option<Vec4>, init: Vec4, ease: &Ease) -> Vec4 {
+        Self::compute_track(time, track, curves, cut_init, init, ease)
     }
     
     pub fn bind_id(&self) -> LiveItemId {
@@ -939,6 +1042,28 @@ impl Track {
         }
     }
     
+    pub fn key_count(&self) -> usize {
+        match self {
+            Track::Float {keys, ..} => keys.len(),
+            Track::Vec2 {keys, ..} => keys.len(),
+            Track::Vec3 {keys, ..} => keys.len(),
+            Track::Vec4 {keys, ..} => keys.len(),
+        }
+    }
+    
+    pub fn set_key_curve(&mut self, index: usize, curve: KeyCurve) {
+        let curves = match self {
+            Track::Float {curves, ..} => curves,
+            Track::Vec2 {curves, ..} => curves,
+            Track::Vec3 {curves, ..} => curves,
+            Track::Vec4 {curves, ..} => curves,
+        };
+        if curves.len() <= index {
+            curves.resize(index + 1, KeyCurve::Default);
+        }
+        curves[index] = curve;
+    }
+    
     pub fn reset_cut_init(&mut self) {
         match self {
             Track::Vec4 {cut_init, ..} => {
@@ -1013,7 +1138,8 @@ impl Anim {
     pub fn empty() -> Anim {
         Anim {
             play: Play::Cut {duration: 0.},
-            tracks: vec![]
+            tracks: vec![],
+            events: vec![]
         }
     }
 }
@@ -1030,6 +1156,8 @@ pub enum Play {
     LoopForever {duration: f64, cut: bool, term: bool, end: f64},
     ReverseForever {duration: f64, cut: bool, term: bool, end: f64},
     BounceForever {duration: f64, cut: bool, term: bool, end: f64},
+    // the tracks last keys become spring targets, it plays until the springs settle
+    Spring {stiffness: f64, damping: f64},
 }
 
 impl Play {
@@ -1045,6 +1173,7 @@ impl Play {
             Play::Forever {duration, ..} => *duration,
             Play::LoopForever {duration, ..} => *duration,
             Play::ReverseForever {duration, ..} => *duration,
+            Play::Spring {..} => 0.0,
         }
     }
     pub fn total_time(&self) -> f64 {
@@ -1059,6 +1188,7 @@ impl Play {
             Play::Forever {..} => std::f64::INFINITY,
             Play::LoopForever {..} => std::f64::INFINITY,
             Play::ReverseForever {..} => std::f64::INFINITY,
+            Play::Spring {..} => std::f64::INFINITY,
         }
     }
     
@@ -1074,6 +1204,7 @@ impl Play {
             Play::Forever {cut, ..} => *cut,
             Play::LoopForever {cut, ..} => *cut,
             Play::ReverseForever {cut, ..} => *cut,
+            Play::Spring {..} => true,
         }
     }
     
@@ -1089,6 +1220,14 @@ impl Play {
             Play::Forever {..} => std::f64::INFINITY,
             Play::LoopForever {..} => std::f64::INFINITY,
             Play::ReverseForever {..} => std::f64::INFINITY,
+            Play::Spring {..} => 1.0,
+        }
+    }
+    
+    pub fn is_spring(&self) -> bool {
+        match self {
+            Play::Spring {..} => true,
+            _ => false
         }
     }
     
@@ -1104,6 +1243,7 @@ impl Play {
             Play::Forever {term, ..} => *term,
             Play::LoopForever {term, ..} => *term,
             Play::ReverseForever {term, ..} => *term,
+            Play::Spring {..} => false,
         }
     }
     
@@ -1150,7 +1290,47 @@ impl Play {
                 let local_time = end - (time / duration) % end;
                 local_time
             },
+            Play::Spring {..} => 1.0,
+        }
+    }
+    
+    // how often playback passes key_time between elapsed times from (exclusive) and to (inclusive)
+    pub fn key_crossings(&self, key_time: f64, from: f64, to: f64) -> usize {
+        let duration = self.duration();
+        let to = to.min(self.total_time());
+        if duration <= 0.0 || to <= from {
+            return 0
+        }
+        // where the key falls inside one period of the play mode, in key time
+        let (offsets, period) = match self {
+            Play::Cut {..} | Play::Chain {..} | Play::Single {..} | Play::Forever {..} | Play::Spring {..} => (vec![key_time], 0.0),
+            Play::Loop {end, ..} | Play::LoopForever {end, ..} => (vec![key_time], *end),
+            Play::Reverse {end, ..} | Play::ReverseForever {end, ..} => (vec![end - key_time], *end),
+            Play::Bounce {end, ..} | Play::BounceForever {end, ..} => (vec![key_time, 2.0 * end - key_time], 2.0 * end),
+        };
+        let mut count = 0;
+        for offset in offsets {
+            if period <= 0.0 {
+                let at = offset * duration;
+                if at > from && at <= to {
+                    count += 1;
+                }
+                continue;
+            }
+            let span = period * duration;
+            let mut cycle = ((from - offset * duration) / span).floor().max(0.0);
+            loop {
+                let at = (offset + cycle * period) * duration;
+                if at > to {
+                    break;
+                }
+                if at > from {
+                    count += 1;
+                }
+                cycle += 1.0;
+            }
         }
+        count
     }
 }
 
@@ -54,7 +54,7 @@ impl Transform {
     }
 }
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, SerRon, DeRon)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, SerBin, DeBin, SerRon, DeRon)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
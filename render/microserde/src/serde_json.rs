@@ -376,13 +376,30 @@ impl DeJsonState {
                     self.numbuf.push(self.cur);
                     self.next(i);
                 }
+                let mut is_float = false;
                 if self.cur == '.' {
+                    is_float = true;
                     self.numbuf.push(self.cur);
                     self.next(i);
                     while self.cur >= '0' && self.cur <= '9' {
                         self.numbuf.push(self.cur);
                         self.next(i);
                     }
+                }
+                if self.cur == 'e' || self.cur == 'E' {
+                    is_float = true;
+                    self.numbuf.push(self.cur);
+                    self.next(i);
+                    if self.cur == '-' || self.cur == '+' {
+                        self.numbuf.push(self.cur);
+                        self.next(i);
+                    }
+                    while self.cur >= '0' && self.cur <= '9' {
+                        self.numbuf.push(self.cur);
+                        self.next(i);
+                    }
+                }
+                if is_float {
                     if let Ok(num) = self.numbuf.parse() {
                         self.tok = DeJsonTok::F64(num);
                         return Ok(())
@@ -444,6 +461,19 @@ impl DeJsonState {
                             'r'=>self.strbuf.push('\r'),
                             't'=>self.strbuf.push('\t'),
                             '0'=>self.strbuf.push('\0'),
+                            'u'=>{
+                                let mut code = 0;
+                                for _ in 0..4 {
+                                    self.next(i);
+                                    if let Some(digit) = self.cur.to_digit(16) {
+                                        code = code * 16 + digit;
+                                    }
+                                    else {
+                                        return Err(self.err_parse("string"));
+                                    }
+                                }
+                                self.strbuf.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                            },
                             '\0'=>{
                                 return Err(self.err_parse("string"));
                             },
@@ -813,4 +843,97 @@ impl<T> DeJson for Box<T> where T: DeJson {
     fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Box<T>, DeJsonErr> {
         Ok(Box::new(DeJson::de_json(s, i) ?))
     }
-}
\ No newline at end of file
+}
+// any json value, for documents that don't map onto a derived struct
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Array(Vec<JsonValue>),
+    Object(HashMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        if let JsonValue::Object(h) = self {
+            return h.get(key)
+        }
+        None
+    }
+    
+    pub fn index(&self, index: usize) -> Option<&JsonValue> {
+        if let JsonValue::Array(v) = self {
+            return v.get(index)
+        }
+        None
+    }
+    
+    pub fn as_f64(&self) -> Option<f64> {
+        if let JsonValue::Num(n) = self {
+            return Some(*n)
+        }
+        None
+    }
+    
+    pub fn as_usize(&self) -> Option<usize> {
+        if let JsonValue::Num(n) = self {
+            if *n >= 0.0 && n.fract() == 0.0 {
+                return Some(*n as usize)
+            }
+        }
+        None
+    }
+    
+    pub fn as_str(&self) -> Option<&str> {
+        if let JsonValue::Str(v) = self {
+            return Some(v)
+        }
+        None
+    }
+    
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        if let JsonValue::Array(v) = self {
+            return Some(v)
+        }
+        None
+    }
+}
+
+impl SerJson for JsonValue {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        match self {
+            JsonValue::Null => s.out.push_str("null"),
+            JsonValue::Bool(v) => v.ser_json(d, s),
+            JsonValue::Num(v) => v.ser_json(d, s),
+            JsonValue::Str(v) => v.ser_json(d, s),
+            JsonValue::Array(v) => v.ser_json(d, s),
+            JsonValue::Object(v) => {
+                if v.len() == 0 {
+                    s.out.push_str("{}");
+                }
+                else {
+                    v.ser_json(d, s)
+                }
+            }
+        }
+    }
+}
+
+impl DeJson for JsonValue {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<JsonValue, DeJsonErr> {
+        match s.tok {
+            DeJsonTok::Null => {
+                s.next_tok(i) ?;
+                Ok(JsonValue::Null)
+            },
+            DeJsonTok::Bool(_) => Ok(JsonValue::Bool(DeJson::de_json(s, i) ?)),
+            DeJsonTok::U64(_) | DeJsonTok::I64(_) | DeJsonTok::F64(_) => Ok(JsonValue::Num(DeJson::de_json(s, i) ?)),
+            DeJsonTok::Str => Ok(JsonValue::Str(DeJson::de_json(s, i) ?)),
+            DeJsonTok::BlockOpen => Ok(JsonValue::Array(DeJson::de_json(s, i) ?)),
+            DeJsonTok::CurlyOpen => Ok(JsonValue::Object(DeJson::de_json(s, i) ?)),
+            _ => Err(s.err_token("json value"))
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::cx::*;
+use makepad_microserde::*;
+use std::collections::{HashMap, HashSet};
+
+// what a node is to a screen reader
+#[derive(Clone, Copy, Debug, PartialEq, SerRon, DeRon)]
+pub enum AccessRole {
+    Window,
+    Group,
+    Label,
+    Button,
+    TextInput,
+    TabList,
+    Tab,
+    Tree,
+    TreeItem,
+    ScrollBar,
+    Table,
+    ColumnHeader,
+    Menu,
+    MenuItem,
+    ComboBox,
+    Tooltip,
+    Dialog,
+    CheckBox,
+    Switch,
+    RadioButton,
+    Slider,
+    SpinButton,
+    ColorWell,
+}
+
+// what a widget says about itself while it draws, the bounds are read from the area when a snapshot is taken
+#[derive(Clone, Debug)]
+pub struct AccessNode {
+    pub role: AccessRole,
+    pub name: String,
+    pub value: String,
+    pub area: Area,
+    pub focused: bool,
+    pub selected: bool,
+    pub expanded: Option<bool>,
+}
+
+impl AccessNode {
+    pub fn new(role: AccessRole, area: Area) -> Self {
+        Self {
+            role,
+            name: String::new(),
+            value: String::new(),
+            area,
+            focused: false,
+            selected: false,
+            expanded: None
+        }
+    }
+    
+    pub fn with_name(self, name: &str) -> Self {Self {name: name.to_string(), ..self}}
+    pub fn with_value(self, value: &str) -> Self {Self {value: value.to_string(), ..self}}
+    pub fn with_focused(self, focused: bool) -> Self {Self {focused, ..self}}
+    pub fn with_selected(self, selected: bool) -> Self {Self {selected, ..self}}
+    pub fn with_expanded(self, expanded: bool) -> Self {Self {expanded: Some(expanded), ..self}}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AccessNodeId(pub u64);
+
+#[derive(Clone, Debug)]
+pub struct CxAccessNode {
+    pub id: AccessNodeId,
+    pub parent: Option<AccessNodeId>,
+    // nodes live as long as the draw of the view they were added in
+    pub view_id: Option<usize>,
+    pub redraw_id: u64,
+    pub node: AccessNode,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CxAccess {
+    pub nodes: Vec<CxAccessNode>,
+    pub stack: Vec<(AccessNodeId, Option<usize>)>,
+    // the node a view was drawn under, so a view that redraws by itself keeps its place in the tree
+    pub view_parents: HashMap<usize, AccessNodeId>,
+    pub id_alloc: u64,
+}
+
+// the serializable snapshot handed to a platform bridge or a test
+#[derive(Clone, Debug, Default, PartialEq, SerRon, DeRon)]
+pub struct AccessTree {
+    pub children: Vec<AccessTreeNode>
+}
+
+#[derive(Clone, Debug, PartialEq, SerRon, DeRon)]
+pub struct AccessTreeNode {
+    pub role: AccessRole,
+    pub name: String,
+    pub value: String,
+    pub rect: Rect,
+    pub focused: bool,
+    pub selected: bool,
+    pub expanded: Option<bool>,
+    pub children: Vec<AccessTreeNode>,
+}
+
+impl AccessTree {
+    // depth first search on role and name
+    pub fn find(&self, role: AccessRole, name: &str) -> Option<&AccessTreeNode> {
+        fn find_in<'a>(nodes: &'a [AccessTreeNode], role: AccessRole, name: &str) -> Option<&'a AccessTreeNode> {
+            for node in nodes {
+                if node.role == role && node.name == name {
+                    return Some(node)
+                }
+                if let Some(found) = find_in(&node.children, role, name) {
+                    return Some(found)
+                }
+            }
+            None
+        }
+        find_in(&self.children, role, name)
+    }
+    
+    pub fn focused(&self) -> Option<&AccessTreeNode> {
+        fn focused_in(nodes: &[AccessTreeNode]) -> Option<&AccessTreeNode> {
+            for node in nodes {
+                if node.focused {
+                    return Some(node)
+                }
+                if let Some(found) = focused_in(&node.children) {
+                    return Some(found)
+                }
+            }
+            None
+        }
+        focused_in(&self.children)
+    }
+}
+
+impl Cx {
+    
+    pub fn add_access_node(&mut self, node: AccessNode) -> AccessNodeId {
+        self.access.id_alloc += 1;
+        let id = AccessNodeId(self.access.id_alloc);
+        let view_id = self.view_stack.last().cloned();
+        let redraw_id = if let Some(view_id) = view_id {self.views[view_id].redraw_id} else {self.redraw_id};
+        let parent = if let Some((parent, parent_view_id)) = self.access.stack.last().cloned() {
+            if let Some(view_id) = view_id {
+                if parent_view_id != Some(view_id) {
+                    self.access.view_parents.insert(view_id, parent);
+                }
+            }
+            Some(parent)
+        }
+        else if let Some(view_id) = view_id {
+            self.access.view_parents.get(&view_id).cloned()
+        }
+        else {
+            None
+        };
+        self.access.nodes.push(CxAccessNode {id, parent, view_id, redraw_id, node});
+        id
+    }
+    
+    // nodes added until end_access_node become children of this one
+    pub fn begin_access_node(&mut self, node: AccessNode) -> AccessNodeId {
+        let id = self.add_access_node(node);
+        let view_id = self.view_stack.last().cloned();
+        self.access.stack.push((id, view_id));
+        id
+    }
+    
+    pub fn end_access_node(&mut self) {
+        if self.access.stack.pop().is_none() {
+            panic!("end_access_node without begin_access_node");
+        }
+    }
+    
+    // views that are still drawn somewhere, hidden views keep their redraw_id but drop out of here
+    fn live_access_views(&self) -> Vec<bool> {
+        let mut live = vec![false; self.views.len()];
+        let mut stack: Vec<usize> = self.passes.iter().filter_map( | pass | pass.main_view_id).collect();
+        while let Some(view_id) = stack.pop() {
+            if view_id >= live.len() || live[view_id] {
+                continue
+            }
+            live[view_id] = true;
+            let cxview = &self.views[view_id];
+            for draw_call in &cxview.draw_calls[0..cxview.draw_calls_len] {
+                if draw_call.sub_view_id != 0 {
+                    stack.push(draw_call.sub_view_id);
+                }
+            }
+        }
+        live
+    }
+    
+    fn is_access_node_current(&self, node: &CxAccessNode, live: &[bool]) -> bool {
+        if let Some(view_id) = node.view_id {
+            live[view_id] && self.views[view_id].redraw_id == node.redraw_id
+        }
+        else {
+            self.redraw_id == node.redraw_id
+        }
+    }
+    
+    // drops the nodes of views that redrew or went away, called after every draw
+    pub fn prune_access_nodes(&mut self) {
+        let live = self.live_access_views();
+        let keep: Vec<bool> = self.access.nodes.iter().map( | node | self.is_access_node_current(node, &live)).collect();
+        let mut index = 0;
+        self.access.nodes.retain( | _ | {index += 1; keep[index - 1]});
+        let ids: HashSet<AccessNodeId> = self.access.nodes.iter().map( | node | node.id).collect();
+        self.access.view_parents.retain( | _, parent | ids.contains(parent));
+    }
+    
+    // a snapshot of the current tree with bounds and focus, take it outside of a draw
+    pub fn access_tree(&self) -> AccessTree {
+        let live = self.live_access_views();
+        let nodes: Vec<&CxAccessNode> = self.access.nodes.iter().filter( | node | self.is_access_node_current(node, &live)).collect();
+        let ids: HashSet<AccessNodeId> = nodes.iter().map( | node | node.id).collect();
+        let mut children: HashMap<Option<AccessNodeId>, Vec<usize>> = HashMap::new();
+        for (index, node) in nodes.iter().enumerate() {
+            // a node whose parent went away hangs off the root
+            let parent = node.parent.filter( | parent | ids.contains(parent));
+            children.entry(parent).or_insert_with(Vec::new).push(index);
+        }
+        
+        fn build(cx: &Cx, nodes: &[&CxAccessNode], children: &HashMap<Option<AccessNodeId>, Vec<usize>>, parent: Option<AccessNodeId>) -> Vec<AccessTreeNode> {
+            let mut out = Vec::new();
+            if let Some(indices) = children.get(&parent) {
+                for index in indices {
+                    let node = &nodes[*index].node;
+                    out.push(AccessTreeNode {
+                        role: node.role,
+                        name: node.name.clone(),
+                        value: node.value.clone(),
+                        rect: if node.area.is_empty() {Rect::default()} else {node.area.get_rect(cx)},
+                        focused: node.focused || (!node.area.is_empty() && node.area == cx.key_focus),
+                        selected: node.selected,
+                        expanded: node.expanded,
+                        children: build(cx, nodes, children, Some(nodes[*index].id))
+                    });
+                }
+            }
+            out
+        }
+        AccessTree {children: build(self, &nodes, &children, None)}
+    }
+}
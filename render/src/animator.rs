@@ -10,21 +10,83 @@ impl AnimatorId{
 #[derive(Clone)]
 pub struct AnimInfo {
     pub start_time: f64,
-    pub total_time: f64
+    // time left in the main anim and the queued one, total_time also covers the layers
+    pub anim_time: f64,
+    pub total_time: f64,
+    // up to where is_animate reported anim events
+    pub event_time: f64
 }
 
 #[derive(Debug, Clone)]
 pub enum AnimLastValue {
-    Float(f32), 
-    Vec2(Vec2), 
+    Float(f32),
+    Vec2(Vec2),
     Vec3(Vec3),
     Vec4(Vec4),
 }
 
+impl AnimLastValue {
+    pub fn to_vec4(&self) -> Vec4 {
+        match self {
+            AnimLastValue::Float(v) => v.to_vec4(),
+            AnimLastValue::Vec2(v) => v.to_vec4(),
+            AnimLastValue::Vec3(v) => v.to_vec4(),
+            AnimLastValue::Vec4(v) => *v,
+        }
+    }
+}
+
+// an anim played on top of the main one, its track values are added to it scaled by the weight
+#[derive(Debug, Clone)]
+pub struct AnimLayer {
+    pub layer_id: LiveItemId,
+    pub anim: Anim,
+    pub start_time: f64,
+    pub weight: f32,
+    pub fade_from: f32,
+    pub fade_start: f64,
+    pub fade_duration: f64,
+    pub stopping: bool,
+    pub ended: bool,
+}
+
+impl AnimLayer {
+    pub fn weight_at(&self, time: f64) -> f32 {
+        if self.fade_duration <= 0.0 {
+            return self.weight
+        }
+        let start = if self.fade_start.is_nan() {time} else {self.fade_start};
+        let f = ((time - start) / self.fade_duration).max(0.0).min(1.0) as f32;
+        self.fade_from + (self.weight - self.fade_from) * f
+    }
+    
+    pub fn end_time(&self) -> f64 {
+        if self.start_time.is_nan() {
+            return std::f64::INFINITY
+        }
+        let end = self.start_time + self.anim.play.total_time();
+        if self.stopping && !self.fade_start.is_nan() {
+            return end.min(self.fade_start + self.fade_duration)
+        }
+        end
+    }
+    
+    // fades start from the weight at time, the clock starts at the next frame
+    fn fade_to(&mut self, weight: f32, fade: f64, time: f64) {
+        self.fade_from = self.weight_at(time);
+        self.weight = weight;
+        self.fade_start = std::f64::NAN;
+        self.fade_duration = fade;
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct Animator {
     current: Option<Anim>,
     next: Option<Anim>,
+    layers: Vec<AnimLayer>,
+    springs: Vec<(LiveItemId, Spring)>,
+    frame_time: f64,
     pub animator_id: AnimatorId,
     pub live_update_id: u64,
     pub last_values: Vec<(LiveItemId, AnimLastValue)>,
@@ -38,6 +100,53 @@ impl Cx{
     }
 }
 
+// ties the track value types to their track variant and last value
+trait AnimValue: TrackValue {
+    fn track_keys(track: &mut Track) -> Option<(&Vec<(f64, Self)>, &Vec<KeyCurve>, &mut Option<Self>, &Ease)>;
+    fn from_last(value: &AnimLastValue) -> Option<Self>;
+    fn into_last(self) -> AnimLastValue;
+}
+
+impl AnimValue for f32 {
+    fn track_keys(track: &mut Track) -> Option<(&Vec<(f64, Self)>, &Vec<KeyCurve>, &mut Option<Self>, &Ease)> {
+        if let Track::Float {keys, curves, cut_init, ease, ..} = track {Some((keys, curves, cut_init, ease))} else {None}
+    }
+    fn from_last(value: &AnimLastValue) -> Option<Self> {
+        if let AnimLastValue::Float(value) = value {Some(*value)} else {None}
+    }
+    fn into_last(self) -> AnimLastValue {AnimLastValue::Float(self)}
+}
+
+impl AnimValue for Vec2 {
+    fn track_keys(track: &mut Track) -> Option<(&Vec<(f64, Self)>, &Vec<KeyCurve>, &mut Option<Self>, &Ease)> {
+        if let Track::Vec2 {keys, curves, cut_init, ease, ..} = track {Some((keys, curves, cut_init, ease))} else {None}
+    }
+    fn from_last(value: &AnimLastValue) -> Option<Self> {
+        if let AnimLastValue::Vec2(value) = value {Some(*value)} else {None}
+    }
+    fn into_last(self) -> AnimLastValue {AnimLastValue::Vec2(self)}
+}
+
+impl AnimValue for Vec3 {
+    fn track_keys(track: &mut Track) -> Option<(&Vec<(f64, Self)>, &Vec<KeyCurve>, &mut Option<Self>, &Ease)> {
+        if let Track::Vec3 {keys, curves, cut_init, ease, ..} = track {Some((keys, curves, cut_init, ease))} else {None}
+    }
+    fn from_last(value: &AnimLastValue) -> Option<Self> {
+        if let AnimLastValue::Vec3(value) = value {Some(*value)} else {None}
+    }
+    fn into_last(self) -> AnimLastValue {AnimLastValue::Vec3(self)}
+}
+
+impl AnimValue for Vec4 {
+    fn track_keys(track: &mut Track) -> Option<(&Vec<(f64, Self)>, &Vec<KeyCurve>, &mut Option<Self>, &Ease)> {
+        if let Track::Vec4 {keys, curves, cut_init, ease, ..} = track {Some((keys, curves, cut_init, ease))} else {None}
+    }
+    fn from_last(value: &AnimLastValue) -> Option<Self> {
+        if let AnimLastValue::Vec4(value) = value {Some(*value)} else {None}
+    }
+    fn into_last(self) -> AnimLastValue {AnimLastValue::Vec4(self)}
+}
+
 impl Animator {
     
     pub fn need_init(&mut self, cx: &mut Cx)->bool{
@@ -47,10 +156,13 @@ impl Animator {
     pub fn init(&mut self, cx: &mut Cx, def_anim:Anim){
         self.live_update_id = cx.live_update_id;
         // lets stop all animations if we had any
+        self.layers.clear();
+        self.springs.clear();
         if self.animator_id.is_empty() {
             self.animator_id = cx.new_animator_id();
         }
         else if let Some(anim_area) = cx.playing_animator_ids.get_mut(&self.animator_id) {
+            anim_area.anim_time = 0.;
             anim_area.total_time = 0.;
         }
         self.set_anim_as_last_values(&def_anim);
@@ -91,7 +203,7 @@ impl Animator {
                 Track::Float{keys,..} => {
                     let val = if keys.len()>0 {keys.last().unwrap().1}else {0.};
                     if let Some((_name, value)) = self.last_values.iter_mut().find( | (name, _) | *name == bind_id) {
-                        *value = AnimLastValue::Float(val); 
+                        *value = AnimLastValue::Float(val);
                     }
                     else {
                         self.last_values.push((bind_id, AnimLastValue::Float(val)));
@@ -105,10 +217,14 @@ impl Animator {
         if let Some(current) = self.current.take() {
             self.set_anim_as_last_values(&current);
         }
+        for (bind_id, spring) in std::mem::replace(&mut self.springs, Vec::new()) {
+            self.set_last_value(bind_id, spring.target::<Vec4>());
+        }
     }
     
     pub fn end_and_set(&mut self, anim: Anim) {
         self.current = None;
+        self.springs.clear();
         self.set_anim_as_last_values(&anim);
     }
     
@@ -127,39 +243,225 @@ impl Animator {
                 return
             }
         }
-
+        
         if self.animator_id.is_empty() {
             self.animator_id = cx.new_animator_id();
         }
-
+        
+        if let Play::Spring {stiffness, damping} = anim.play {
+            self.play_springs(cx, &anim, stiffness, damping);
+            return
+        }
+        // keyframes take over from the springs on the same values
+        self.springs.retain( | (bind_id, _) | anim.tracks.iter().all( | t | t.bind_id() != *bind_id));
+        
         // alright first we find area, it already exists
         if let Some(anim_info) = cx.playing_animator_ids.get_mut(&self.animator_id){
-
+            
             if anim.play.cut() || self.current.is_none() {
                 self.current = Some(anim);
                 anim_info.start_time = std::f64::NAN;
                 self.next = None;
-                anim_info.total_time = self.current.as_ref().unwrap().play.total_time();
+                anim_info.anim_time = self.current.as_ref().unwrap().play.total_time();
             }
             else { // queue it
                 self.next = Some(anim);
                 // lets ask an animation anim how long it is
-                anim_info.total_time = self.current.as_ref().unwrap().play.total_time() + self.next.as_ref().unwrap().play.total_time()
+                anim_info.anim_time = self.current.as_ref().unwrap().play.total_time() + self.next.as_ref().unwrap().play.total_time()
             }
+            self.sync_total_time(anim_info);
         }
         else{
             self.current = Some(anim);
             self.next = None;
+            let anim_time = self.current.as_ref().unwrap().play.total_time();
             cx.playing_animator_ids.insert(self.animator_id, AnimInfo {
                 start_time: std::f64::NAN,
-                total_time: self.current.as_ref().unwrap().play.total_time()
+                anim_time: anim_time,
+                total_time: anim_time,
+                event_time: std::f64::NAN
             });
         }
     }
     
+    // every track springs from where its value is now to its last key. Springs that are already
+    // moving keep their velocity, a keyframe anim that gets interrupted hands over its own
+    fn play_springs(&mut self, cx: &mut Cx, anim: &Anim, stiffness: f64, damping: f64) {
+        let time = self.frame_time;
+        let mut handover = Vec::new();
+        if let (Some(current), Some(anim_info)) = (&self.current, cx.playing_animator_ids.get(&self.animator_id)) {
+            let elapsed = time - anim_info.start_time;
+            if elapsed >= 0.0 && elapsed < current.play.total_time() {
+                let h = 1.0 / 240.0;
+                let (t0, t1) = (current.play.compute_time(elapsed), current.play.compute_time(elapsed + h));
+                for track in &current.tracks {
+                    let last = self.last_values.iter().find( | v | v.0 == track.bind_id()).map( | v | v.1.to_vec4()).unwrap_or(Vec4::all(0.0));
+                    let v0 = Self::track_vec4_at(track, t0, last);
+                    let v1 = Self::track_vec4_at(track, t1, last);
+                    handover.push((track.bind_id(), (v1 - v0) * (1.0 / h) as f32));
+                }
+            }
+        }
+        self.current = None;
+        self.next = None;
+        
+        for track in &anim.tracks {
+            let bind_id = track.bind_id();
+            let target = if let Some(target) = Self::track_target(track) {target} else {continue};
+            if let Some((_, spring)) = self.springs.iter_mut().find( | (id, _) | *id == bind_id) {
+                spring.stiffness = stiffness;
+                spring.damping = damping;
+                spring.set_target(target);
+                continue;
+            }
+            let mut spring = Spring::new(stiffness, damping);
+            if let Some((_, last)) = self.last_values.iter().find( | v | v.0 == bind_id) {
+                spring.set_value(last.to_vec4());
+            }
+            else {
+                spring.set_value(target);
+            }
+            if let Some((_, velocity)) = handover.iter().find( | (id, _) | *id == bind_id) {
+                spring.velocity = *velocity;
+            }
+            spring.set_target(target);
+            self.springs.push((bind_id, spring));
+        }
+        if let Some(anim_info) = cx.playing_animator_ids.get_mut(&self.animator_id) {
+            anim_info.anim_time = 0.0;
+        }
+        self.ensure_playing(cx);
+    }
+    
+    fn track_target(track: &Track) -> Option<Vec4> {
+        match track {
+            Track::Float {keys, ..} => keys.last().map( | k | k.1.to_vec4()),
+            Track::Vec2 {keys, ..} => keys.last().map( | k | k.1.to_vec4()),
+            Track::Vec3 {keys, ..} => keys.last().map( | k | k.1.to_vec4()),
+            Track::Vec4 {keys, ..} => keys.last().map( | k | k.1),
+        }
+    }
+    
+    fn track_vec4_at(track: &Track, time: f64, init: Vec4) -> Vec4 {
+        let mut track = track.clone();
+        match &mut track {
+            Track::Float {keys, curves, cut_init, ease, ..} => Track::compute_track(time, keys, curves, cut_init, f32::from_vec4(init), ease).to_vec4(),
+            Track::Vec2 {keys, curves, cut_init, ease, ..} => Track::compute_track(time, keys, curves, cut_init, Vec2::from_vec4(init), ease).to_vec4(),
+            Track::Vec3 {keys, curves, cut_init, ease, ..} => Track::compute_track(time, keys, curves, cut_init, Vec3::from_vec4(init), ease).to_vec4(),
+            Track::Vec4 {keys, curves, cut_init, ease, ..} => Track::compute_track(time, keys, curves, cut_init, init, ease),
+        }
+    }
+    
+    // plays an anim as a named additive layer, its weight fades in from 0 over fade seconds.
+    // Playing a layer that is already there restarts it and fades from its current weight
+    pub fn play_layer(&mut self, cx: &mut Cx, layer_id: LiveItemId, anim: Anim, fade: f64) {
+        let time = self.frame_time;
+        if let Some(layer) = self.layers.iter_mut().find( | l | l.layer_id == layer_id && !l.ended) {
+            layer.anim = anim;
+            layer.start_time = std::f64::NAN;
+            layer.stopping = false;
+            layer.fade_to(1.0, fade, time);
+        }
+        else {
+            self.layers.push(AnimLayer {
+                layer_id,
+                anim,
+                start_time: std::f64::NAN,
+                weight: 1.0,
+                fade_from: 0.0,
+                fade_start: std::f64::NAN,
+                fade_duration: fade,
+                stopping: false,
+                ended: false
+            });
+        }
+        self.ensure_playing(cx);
+    }
+    
+    pub fn set_layer_weight(&mut self, cx: &mut Cx, layer_id: LiveItemId, weight: f32, fade: f64) {
+        let time = self.frame_time;
+        if let Some(layer) = self.layers.iter_mut().find( | l | l.layer_id == layer_id && !l.ended) {
+            layer.fade_to(weight, fade, time);
+            self.ensure_playing(cx);
+        }
+    }
+    
+    // fades the layer out and drops it
+    pub fn stop_layer(&mut self, cx: &mut Cx, layer_id: LiveItemId, fade: f64) {
+        let time = self.frame_time;
+        if let Some(layer) = self.layers.iter_mut().find( | l | l.layer_id == layer_id && !l.ended) {
+            layer.fade_to(0.0, fade, time);
+            layer.stopping = true;
+            self.ensure_playing(cx);
+        }
+    }
+    
+    pub fn has_layer(&self, layer_id: LiveItemId) -> bool {
+        self.layers.iter().any( | l | l.layer_id == layer_id && !l.ended)
+    }
+    
+    fn ensure_playing(&mut self, cx: &mut Cx) {
+        self.live_update_id = cx.live_update_id;
+        if self.animator_id.is_empty() {
+            self.animator_id = cx.new_animator_id();
+        }
+        let anim_info = cx.playing_animator_ids.entry(self.animator_id).or_insert(AnimInfo {
+            start_time: std::f64::NAN,
+            anim_time: 0.0,
+            total_time: 0.0,
+            event_time: std::f64::NAN
+        });
+        self.sync_total_time(anim_info);
+    }
+    
+    fn layers_end_time(&self) -> f64 {
+        self.layers.iter().filter( | l | !l.ended).map( | l | l.end_time()).fold(std::f64::NEG_INFINITY, f64::max)
+    }
+    
+    // keeps the animator playing until the main anim, the springs and all the layers are done
+    fn sync_total_time(&self, anim_info: &mut AnimInfo) {
+        anim_info.total_time = if self.springs.iter().any( | (_, s) | !s.is_settled()) {
+            std::f64::INFINITY
+        }
+        else if self.layers.is_empty() {
+            anim_info.anim_time
+        }
+        else if anim_info.start_time.is_nan() {
+            std::f64::INFINITY
+        }
+        else {
+            anim_info.anim_time.max(self.layers_end_time() - anim_info.start_time)
+        };
+    }
+    
+    // once per frame: start new layers, step the springs and drop the layers and springs
+    // that ended the frame before, so their last values still get written once
+    fn update_frame(&mut self, time: f64) {
+        if time == self.frame_time {
+            return
+        }
+        self.frame_time = time;
+        self.layers.retain( | l | !l.ended);
+        for layer in &mut self.layers {
+            if layer.start_time.is_nan() {
+                layer.start_time = time;
+            }
+            if layer.fade_start.is_nan() {
+                layer.fade_start = time;
+            }
+            if time >= layer.end_time() {
+                layer.ended = true;
+            }
+        }
+        self.springs.retain( | (_, s) | !s.is_settled());
+        for (_, spring) in &mut self.springs {
+            spring.step(time);
+        }
+    }
+    
     pub fn handle_end(&mut self, cx: &Cx, time: f64)->bool{
         if let Some(anim_info) = cx.playing_animator_ids.get(&self.animator_id){
-            if anim_info.start_time.is_nan() || time - anim_info.start_time < anim_info.total_time{
+            if anim_info.start_time.is_nan() || time - anim_info.start_time < anim_info.anim_time{
                 return false;
             }
         }
@@ -170,11 +472,12 @@ impl Animator {
     }
     
     pub fn update_anim_track(&mut self, cx: &mut Cx, time: f64) -> Option<f64> {
-
+        
         // alright first we find area in running animations
-
+        self.update_frame(time);
+        
         // fetch current anim
-        if self.current.is_none() { // remove anim
+        if self.current.is_none() && self.layers.is_empty() && self.springs.is_empty() { // remove anim
             cx.playing_animator_ids.remove(&self.animator_id);
             return None
         }
@@ -184,28 +487,56 @@ impl Animator {
                 anim_info.start_time = time;
             }
             
-            let current_total_time = self.current.as_ref().unwrap().play.total_time();
-        
+            let current_total_time = self.current.as_ref().map(|c| c.play.total_time());
+            
             // process queueing
-            if time - anim_info.start_time >= current_total_time && !self.next.is_none() {
-                self.current = self.next.clone();
-                self.next = None;
-                // update animation slot
-                anim_info.start_time += current_total_time;
-                anim_info.total_time -= current_total_time;
-
-                Some(self.current.as_ref().unwrap().play.compute_time(time - anim_info.start_time))
+            if let Some(current_total_time) = current_total_time {
+                if time - anim_info.start_time >= current_total_time && !self.next.is_none() {
+                    self.current = self.next.clone();
+                    self.next = None;
+                    // update animation slot
+                    anim_info.start_time += current_total_time;
+                    anim_info.anim_time -= current_total_time;
+                }
             }
             else {
-                Some(self.current.as_ref().unwrap().play.compute_time(time - anim_info.start_time))
+                anim_info.anim_time = 0.0;
             }
+            self.sync_total_time(anim_info);
             
+            self.current.as_ref().map( | current | current.play.compute_time(time - anim_info.start_time))
         }
         else{
             return None
         }
     }
     
+    // the anim events the main anim and the layers passed since the last call
+    pub fn crossed_timeline_events(&self, cx: &mut Cx, time: f64) -> Vec<TimelineEvent> {
+        let mut events = Vec::new();
+        if let Some(anim_info) = cx.playing_animator_ids.get_mut(&self.animator_id) {
+            let last_time = anim_info.event_time;
+            anim_info.event_time = time;
+            let mut crossed = | layer: Option<LiveItemId>, anim: &Anim, start_time: f64 | {
+                // not started yet means it starts this frame
+                let start_time = if start_time.is_nan() {time} else {start_time};
+                let from = if last_time.is_nan() || last_time < start_time {std::f64::NEG_INFINITY} else {last_time - start_time};
+                for (key_time, event) in &anim.events {
+                    for _ in 0..anim.play.key_crossings(*key_time, from, time - start_time) {
+                        events.push(TimelineEvent {layer, event: *event, key_time: *key_time});
+                    }
+                }
+            };
+            if let Some(current) = &self.current {
+                crossed(None, current, anim_info.start_time);
+            }
+            for layer in self.layers.iter().filter( | l | !l.ended) {
+                crossed(Some(layer.layer_id), &layer.anim, layer.start_time);
+            }
+        }
+        events
+    }
+    
     pub fn find_track_index(&mut self, bind_id: LiveItemId) -> Option<usize> {
         // find our track
         for (track_index, track) in &mut self.current.as_ref().unwrap().tracks.iter().enumerate() {
@@ -216,131 +547,110 @@ impl Animator {
         None
     }
     
-    pub fn calc_float(&mut self, cx: &mut Cx, bind_id: LiveItemId, time: f64) -> Option<f32> {
-        let last = self.last_float(bind_id);
-        if let Some(time) = self.update_anim_track(cx, time) {
+    // the weighted sum of what the layers add to a value, None if no layer animates it
+    fn layer_offset<T: AnimValue>(&mut self, bind_id: LiveItemId, time: f64) -> Option<Vec4> {
+        let mut offset = None;
+        for layer in &mut self.layers {
+            let weight = layer.weight_at(time);
+            let start_time = if layer.start_time.is_nan() {time} else {layer.start_time};
+            let local_time = layer.anim.play.compute_time((time - start_time).min(layer.anim.play.total_time()));
+            if let Some(track) = layer.anim.tracks.iter_mut().find( | t | t.bind_id() == bind_id) {
+                if let Some((keys, curves, cut_init, ease)) = T::track_keys(track) {
+                    let zero = T::from_vec4(Vec4::all(0.0));
+                    let value = Track::compute_track(local_time, keys, curves, cut_init, zero, ease);
+                    offset = Some(offset.unwrap_or(Vec4::all(0.0)) + value.to_vec4() * weight);
+                }
+            }
+        }
+        offset
+    }
+    
+    fn calc_value<T: AnimValue>(&mut self, cx: &mut Cx, bind_id: LiveItemId, time: f64) -> Option<T> {
+        let last = self.last_value::<T>(bind_id);
+        let mut base = None;
+        if let Some(anim_time) = self.update_anim_track(cx, time) {
             if let Some(track_index) = self.find_track_index(bind_id) {
-                if let Track::Float{keys, cut_init, ease, ..} = &mut self.current.as_mut().unwrap().tracks[track_index] {
-                    let ret = Track::compute_track_float(time, keys, cut_init, last.unwrap_or(0.0), ease);
-                    self.set_last_float(bind_id, ret);
-                    return Some(ret)
+                if let Some((keys, curves, cut_init, ease)) = T::track_keys(&mut self.current.as_mut().unwrap().tracks[track_index]) {
+                    let ret = Track::compute_track(anim_time, keys, curves, cut_init, last.unwrap_or(T::from_vec4(Vec4::all(0.0))), ease);
+                    self.set_last_value(bind_id, ret);
+                    base = Some(ret);
                 }
             }
         }
-        None
+        if let Some((_, spring)) = self.springs.iter().find( | (id, _) | *id == bind_id) {
+            let value = spring.value::<T>();
+            self.set_last_value(bind_id, value);
+            base = Some(value);
+        }
+        // layers add on top of the main anim, the last values stay without them
+        if let Some(offset) = self.layer_offset::<T>(bind_id, time) {
+            let base = base.or(last).unwrap_or(T::from_vec4(Vec4::all(0.0)));
+            return Some(T::from_vec4(base.to_vec4() + offset))
+        }
+        base
     }
     
-    pub fn last_float(&self, bind_id: LiveItemId) -> Option<f32> {
+    fn last_value<T: AnimValue>(&self, bind_id: LiveItemId) -> Option<T> {
         if let Some((_, value)) = self.last_values.iter().find( | v | v.0 == bind_id) {
-            if let AnimLastValue::Float(value) = value {
-                return Some(*value)
-            }
+            return T::from_last(value)
         }
         None
     }
     
-    pub fn set_last_float(&mut self, bind_id: LiveItemId, value: f32) {
+    fn set_last_value<T: AnimValue>(&mut self, bind_id: LiveItemId, value: T) {
         if let Some((_, last)) = self.last_values.iter_mut().find( | v | v.0 == bind_id) {
-            *last = AnimLastValue::Float(value);
+            *last = value.into_last();
         }
         else {
-            self.last_values.push((bind_id, AnimLastValue::Float(value)))
+            self.last_values.push((bind_id, value.into_last()))
         }
     }
     
+    pub fn calc_float(&mut self, cx: &mut Cx, bind_id: LiveItemId, time: f64) -> Option<f32> {
+        self.calc_value(cx, bind_id, time)
+    }
+    
+    pub fn last_float(&self, bind_id: LiveItemId) -> Option<f32> {
+        self.last_value(bind_id)
+    }
+    
+    pub fn set_last_float(&mut self, bind_id: LiveItemId, value: f32) {
+        self.set_last_value(bind_id, value)
+    }
+    
     pub fn calc_vec2(&mut self, cx: &mut Cx, bind_id: LiveItemId, time: f64) -> Option<Vec2> {
-        let last = self.last_vec2(bind_id);
-        if let Some(time) = self.update_anim_track(cx, time) {
-            if let Some(track_index) = self.find_track_index(bind_id) {
-                if let Track::Vec2{keys, cut_init, ease, ..} = &mut self.current.as_mut().unwrap().tracks[track_index] {
-                    let ret = Track::compute_track_vec2(time, keys, cut_init, last.unwrap_or(vec2(0.,0.)), ease);
-                    self.set_last_vec2(bind_id, ret);
-                    return Some(ret);
-                }
-            }
-        }
-        None
+        self.calc_value(cx, bind_id, time)
     }
     
     pub fn last_vec2(&self, live_item_id: LiveItemId) -> Option<Vec2> {
-        if let Some((_, value)) = self.last_values.iter().find( | v | v.0 == live_item_id) {
-            if let AnimLastValue::Vec2(value) = value {
-                return Some(*value)
-            }
-        }
-        return None
+        self.last_value(live_item_id)
     }
     
     pub fn set_last_vec2(&mut self, live_item_id: LiveItemId, value: Vec2) {
-        if let Some((_, last)) = self.last_values.iter_mut().find( | v | v.0 == live_item_id) {
-            *last = AnimLastValue::Vec2(value);
-        }
-        else {
-            self.last_values.push((live_item_id, AnimLastValue::Vec2(value)))
-        }
+        self.set_last_value(live_item_id, value)
     }
     
     pub fn calc_vec3(&mut self, cx: &mut Cx, live_item_id: LiveItemId, time: f64) -> Option<Vec3> {
-        let last = self.last_vec3(live_item_id);
-        if let Some(time) = self.update_anim_track(cx, time) {
-            if let Some(track_index) = self.find_track_index(live_item_id) {
-                if let Track::Vec3{keys, cut_init, ease, ..} = &mut self.current.as_mut().unwrap().tracks[track_index] {
-                    let ret = Track::compute_track_vec3(time, keys, cut_init, last.unwrap_or(Vec3::all(0.)), ease);
-                    self.set_last_vec3(live_item_id, ret);
-                    return Some(ret)
-                }
-            }
-        }
-        None
+        self.calc_value(cx, live_item_id, time)
     }
     
     pub fn last_vec3(&self, live_item_id: LiveItemId) -> Option<Vec3> {
-        if let Some((_, value)) = self.last_values.iter().find( | v | v.0 == live_item_id) {
-            if let AnimLastValue::Vec3(value) = value {
-                return Some(*value)
-            }
-        }
-        None
+        self.last_value(live_item_id)
     }
+    
     pub fn set_last_vec3(&mut self, live_item_id: LiveItemId, value: Vec3) {
-        if let Some((_, last)) = self.last_values.iter_mut().find( | v | v.0 == live_item_id) {
-            *last = AnimLastValue::Vec3(value);
-        }
-        else {
-            self.last_values.push((live_item_id, AnimLastValue::Vec3(value)))
-        }
+        self.set_last_value(live_item_id, value)
     }
     
     pub fn calc_vec4(&mut self, cx: &mut Cx, live_item_id: LiveItemId, time: f64) -> Option<Vec4> {
-        let last = self.last_vec4(live_item_id);
-        if let Some(time) = self.update_anim_track(cx, time) {
-            if let Some(track_index) = self.find_track_index(live_item_id) {
-                if let Track::Vec4{keys, cut_init, ease, ..} = &mut self.current.as_mut().unwrap().tracks[track_index] {
-                    let ret = Track::compute_track_vec4(time, keys, cut_init, last.unwrap_or(Vec4::all(0.0)), ease);
-                    self.set_last_vec4(live_item_id, ret);
-                    return Some(ret)
-                }
-            }
-        }
-        return None
+        self.calc_value(cx, live_item_id, time)
     }
-
+    
     pub fn last_vec4(&self, live_item_id: LiveItemId) -> Option<Vec4> {
-        if let Some((_, value)) = self.last_values.iter().find( | v | v.0 == live_item_id) {
-            if let AnimLastValue::Vec4(value) = value {
-                return Some(*value)
-            }
-        }
-        None
+        self.last_value(live_item_id)
     }
     
     pub fn set_last_vec4(&mut self, live_item_id: LiveItemId, value: Vec4) {
-        if let Some((_, last)) = self.last_values.iter_mut().find( | v | v.0 == live_item_id) {
-            *last = AnimLastValue::Vec4(value);
-        }
-        else {
-            self.last_values.push((live_item_id, AnimLastValue::Vec4(value)))
-        }
+        self.set_last_value(live_item_id, value)
     }
 }
-
@@ -1,7 +1,8 @@
 use crate::cx::*; 
+use makepad_microserde::*;
 use makepad_live_compiler::ty::Ty;
 
-#[derive(Clone, Default, Hash, Ord, PartialOrd, Eq,Debug, PartialEq, Copy)]
+#[derive(Clone, Default, Hash, Ord, PartialOrd, Eq,Debug, PartialEq, Copy, SerBin, DeBin)]
 pub struct InstanceArea{
     pub view_id:usize,
     pub draw_call_id:usize,
@@ -10,13 +11,13 @@ pub struct InstanceArea{
     pub redraw_id:u64
 }
 
-#[derive(Clone, Default, Hash, Ord, PartialOrd, Eq,Debug, PartialEq, Copy)]
+#[derive(Clone, Default, Hash, Ord, PartialOrd, Eq,Debug, PartialEq, Copy, SerBin, DeBin)]
 pub struct ViewArea{
     pub view_id:usize,
     pub redraw_id:u64 
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Ord, PartialOrd, Eq, Copy)]
+#[derive(Clone, Debug, Hash, PartialEq, Ord, PartialOrd, Eq, Copy, SerBin, DeBin)]
 pub enum Area{
     Empty,
     All,
@@ -0,0 +1,390 @@
+use crate::cx::*;
+use crate::drawimage::DrawImage;
+
+// shelf packing texture atlas. Items go on horizontal shelves of similar height,
+// freed space on a shelf is reused and the atlas can grow up to max_size.
+// all sizes are in pixels
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AtlasRect {
+    pub x: usize,
+    pub y: usize,
+    pub w: usize,
+    pub h: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct AtlasId(pub usize);
+
+// returned by defragment so the caller can move the pixels along
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasMove {
+    pub id: AtlasId,
+    pub from: AtlasRect,
+    pub to: AtlasRect,
+}
+
+#[derive(Clone, Debug)]
+struct AtlasShelf {
+    y: usize,
+    height: usize,
+    used: usize,
+    // free spans as (x, width), sorted on x and never touching
+    free: Vec<(usize, usize)>,
+}
+
+impl AtlasShelf {
+    fn new(y: usize, height: usize, width: usize) -> Self {
+        Self {y, height, used: 0, free: vec![(0, width)]}
+    }
+
+    fn alloc(&mut self, w: usize) -> Option<usize> {
+        let index = self.free.iter().position( | (_, fw) | *fw >= w) ?;
+        let (x, fw) = self.free[index];
+        if fw == w {
+            self.free.remove(index);
+        }
+        else {
+            self.free[index] = (x + w, fw - w);
+        }
+        self.used += 1;
+        Some(x)
+    }
+
+    fn free(&mut self, x: usize, w: usize) {
+        self.used -= 1;
+        let index = self.free.iter().position( | (fx, _) | *fx > x).unwrap_or(self.free.len());
+        self.free.insert(index, (x, w));
+        if index + 1 < self.free.len() && x + w == self.free[index + 1].0 {
+            self.free[index].1 += self.free[index + 1].1;
+            self.free.remove(index + 1);
+        }
+        if index > 0 && self.free[index - 1].0 + self.free[index - 1].1 == x {
+            self.free[index - 1].1 += self.free[index].1;
+            self.free.remove(index);
+        }
+    }
+
+    fn widen(&mut self, old_width: usize, new_width: usize) {
+        if let Some(last) = self.free.last_mut() {
+            if last.0 + last.1 == old_width {
+                last.1 += new_width - old_width;
+                return
+            }
+        }
+        self.free.push((old_width, new_width - old_width));
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct AtlasSlot {
+    rect: AtlasRect,
+    shelf: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TextureAtlas {
+    pub width: usize,
+    pub height: usize,
+    pub max_size: usize,
+    // empty pixels kept right and below every item so filtering doesn't bleed
+    pub padding: usize,
+    shelves: Vec<AtlasShelf>,
+    slots: Vec<Option<AtlasSlot>>,
+    free_slots: Vec<usize>,
+}
+
+impl TextureAtlas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            max_size: width.max(height),
+            padding: 1,
+            shelves: Vec::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+
+    pub fn with_max_size(self, max_size: usize) -> Self {
+        Self {max_size: max_size.max(self.width).max(self.height), ..self}
+    }
+
+    pub fn with_padding(self, padding: usize) -> Self {
+        Self {padding, ..self}
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2 {x: self.width as f32, y: self.height as f32}
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.shelves.truncate(0);
+        self.slots.truncate(0);
+        self.free_slots.truncate(0);
+    }
+
+    // grows the atlas when needed, returns None when it can't fit even at max_size
+    pub fn alloc(&mut self, w: usize, h: usize) -> Option<AtlasId> {
+        let pw = w + self.padding;
+        let ph = h + self.padding;
+        if pw > self.max_size || ph > self.max_size {
+            return None
+        }
+        loop {
+            if let Some(slot) = self.alloc_slot(w, h, pw, ph) {
+                let id = if let Some(id) = self.free_slots.pop() {
+                    self.slots[id] = Some(slot);
+                    id
+                }
+                else {
+                    self.slots.push(Some(slot));
+                    self.slots.len() - 1
+                };
+                return Some(AtlasId(id))
+            }
+            if !self.grow() {
+                return None
+            }
+        }
+    }
+
+    fn alloc_slot(&mut self, w: usize, h: usize, pw: usize, ph: usize) -> Option<AtlasSlot> {
+        // the lowest shelf that fits and doesn't waste more than half the item height,
+        // an empty shelf takes anything that fits
+        let mut best: Option<usize> = None;
+        for (index, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height < ph || (shelf.used > 0 && shelf.height > ph + ph / 2) {
+                continue;
+            }
+            if !shelf.free.iter().any( | (_, fw) | *fw >= pw) {
+                continue;
+            }
+            if best.map_or(true, | b | self.shelves[b].height > shelf.height) {
+                best = Some(index);
+            }
+        }
+        let shelf_index = if let Some(index) = best {
+            index
+        }
+        else {
+            let y = self.shelves.last().map_or(0, | s | s.y + s.height);
+            if y + ph > self.height || pw > self.width {
+                return None
+            }
+            self.shelves.push(AtlasShelf::new(y, ph, self.width));
+            self.shelves.len() - 1
+        };
+        let shelf = &mut self.shelves[shelf_index];
+        let x = shelf.alloc(pw) ?;
+        Some(AtlasSlot {
+            rect: AtlasRect {x, y: shelf.y, w, h},
+            shelf: shelf_index
+        })
+    }
+
+    // doubles the smaller side, returns false at max_size
+    fn grow(&mut self) -> bool {
+        if self.height <= self.width && self.height * 2 <= self.max_size {
+            self.height *= 2;
+            return true
+        }
+        if self.width * 2 <= self.max_size {
+            let old_width = self.width;
+            self.width *= 2;
+            for shelf in &mut self.shelves {
+                shelf.widen(old_width, self.width);
+            }
+            return true
+        }
+        if self.height * 2 <= self.max_size {
+            self.height *= 2;
+            return true
+        }
+        false
+    }
+
+    pub fn free(&mut self, id: AtlasId) {
+        if let Some(slot) = self.slots.get_mut(id.0).and_then( | slot | slot.take()) {
+            self.shelves[slot.shelf].free(slot.rect.x, slot.rect.w + self.padding);
+            self.free_slots.push(id.0);
+            // give back empty shelves at the bottom so other heights can use the space
+            while let Some(shelf) = self.shelves.last() {
+                if shelf.used != 0 {
+                    break;
+                }
+                self.shelves.pop();
+            }
+        }
+    }
+
+    pub fn rect(&self, id: AtlasId) -> Option<AtlasRect> {
+        self.slots.get(id.0).and_then( | slot | slot.map( | slot | slot.rect))
+    }
+
+    // texture coordinates for DrawImage pt1 and pt2, these change when the atlas grows
+    pub fn uv(&self, id: AtlasId) -> Option<(Vec2, Vec2)> {
+        let rect = self.rect(id) ?;
+        let (w, h) = (self.width as f32, self.height as f32);
+        Some((
+            Vec2 {x: rect.x as f32 / w, y: rect.y as f32 / h},
+            Vec2 {x: (rect.x + rect.w) as f32 / w, y: (rect.y + rect.h) as f32 / h}
+        ))
+    }
+
+    // repacks everything tallest first, ids stay the same.
+    // when the new packing doesn't fit in max_size the old layout is kept and nothing moves
+    pub fn defragment(&mut self) -> Vec<AtlasMove> {
+        let mut live: Vec<(usize, AtlasRect)> = self.slots.iter().enumerate().filter_map( | (id, slot) | slot.map( | slot | (id, slot.rect))).collect();
+        live.sort_by( | a, b | b.1.h.cmp(&a.1.h).then(b.1.w.cmp(&a.1.w)));
+        let old = (self.width, self.height, self.shelves.clone(), self.slots.clone());
+        self.shelves.truncate(0);
+        let mut moves = Vec::new();
+        for (id, from) in live {
+            let (pw, ph) = (from.w + self.padding, from.h + self.padding);
+            let slot = loop {
+                if let Some(slot) = self.alloc_slot(from.w, from.h, pw, ph) {
+                    break Some(slot)
+                }
+                if !self.grow() {
+                    break None
+                }
+            };
+            let slot = if let Some(slot) = slot {slot} else {
+                let (width, height, shelves, slots) = old;
+                self.width = width;
+                self.height = height;
+                self.shelves = shelves;
+                self.slots = slots;
+                return Vec::new()
+            };
+            if slot.rect != from {
+                moves.push(AtlasMove {id: AtlasId(id), from, to: slot.rect});
+            }
+            self.slots[id] = Some(slot);
+        }
+        moves
+    }
+}
+
+// many small images in one texture, drawn with DrawImage pt1/pt2 so they
+// share a single texture and end up in the same draw call
+pub struct SpriteSheet {
+    pub texture: Texture,
+    pub atlas: TextureAtlas,
+}
+
+impl SpriteSheet {
+    pub fn new(cx: &mut Cx, atlas: TextureAtlas) -> Self {
+        let texture = Texture::new(cx);
+        let cxtexture = &mut cx.textures[texture.texture_id as usize];
+        cxtexture.desc = TextureDesc {
+            format: TextureFormat::ImageBGRA,
+            width: Some(atlas.width),
+            height: Some(atlas.height),
+            multisample: None
+        };
+        cxtexture.image_u32 = vec![0; atlas.width * atlas.height];
+        cxtexture.update_image = true;
+        Self {texture, atlas}
+    }
+
+    // copies the pixels in, returns None when the sheet is at its max size
+    pub fn add_sprite(&mut self, cx: &mut Cx, image: &DecodedImage) -> Option<AtlasId> {
+        let (old_width, old_height) = (self.atlas.width, self.atlas.height);
+        let id = self.atlas.alloc(image.width, image.height) ?;
+        let rect = self.atlas.rect(id).unwrap();
+        let cxtexture = &mut cx.textures[self.texture.texture_id as usize];
+        if (old_width, old_height) != (self.atlas.width, self.atlas.height) {
+            let mut pixels = vec![0u32; self.atlas.width * self.atlas.height];
+            for y in 0..old_height {
+                pixels[y * self.atlas.width..y * self.atlas.width + old_width].copy_from_slice(&cxtexture.image_u32[y * old_width..(y + 1) * old_width]);
+            }
+            cxtexture.image_u32 = pixels;
+            cxtexture.desc.width = Some(self.atlas.width);
+            cxtexture.desc.height = Some(self.atlas.height);
+        }
+        for y in 0..rect.h {
+            let dst = (rect.y + y) * self.atlas.width + rect.x;
+            cxtexture.image_u32[dst..dst + rect.w].copy_from_slice(&image.pixels[y * image.width..(y + 1) * image.width]);
+        }
+        cxtexture.update_image = true;
+        Some(id)
+    }
+
+    pub fn remove_sprite(&mut self, cx: &mut Cx, id: AtlasId) {
+        if let Some(rect) = self.atlas.rect(id) {
+            let cxtexture = &mut cx.textures[self.texture.texture_id as usize];
+            for y in 0..rect.h {
+                let dst = (rect.y + y) * self.atlas.width + rect.x;
+                for pixel in &mut cxtexture.image_u32[dst..dst + rect.w] {
+                    *pixel = 0;
+                }
+            }
+            cxtexture.update_image = true;
+            self.atlas.free(id);
+        }
+    }
+
+    pub fn defragment(&mut self, cx: &mut Cx) {
+        let (old_width, old_height) = (self.atlas.width, self.atlas.height);
+        let moves = self.atlas.defragment();
+        if moves.len() == 0 && (old_width, old_height) == (self.atlas.width, self.atlas.height) {
+            return
+        }
+        let width = self.atlas.width;
+        let cxtexture = &mut cx.textures[self.texture.texture_id as usize];
+        let mut pixels = vec![0u32; width * self.atlas.height];
+        for id in 0..self.atlas.slots.len() {
+            if let Some(to) = self.atlas.rect(AtlasId(id)) {
+                let from = moves.iter().find( | m | m.id.0 == id).map_or(to, | m | m.from);
+                for y in 0..to.h {
+                    let src = (from.y + y) * old_width + from.x;
+                    let dst = (to.y + y) * width + to.x;
+                    pixels[dst..dst + to.w].copy_from_slice(&cxtexture.image_u32[src..src + to.w]);
+                }
+            }
+        }
+        cxtexture.image_u32 = pixels;
+        cxtexture.desc.width = Some(width);
+        cxtexture.desc.height = Some(self.atlas.height);
+        cxtexture.update_image = true;
+    }
+
+    pub fn sprite_size(&self, id: AtlasId) -> Vec2 {
+        self.atlas.rect(id).map_or(Vec2::default(), | rect | Vec2 {x: rect.w as f32, y: rect.h as f32})
+    }
+
+    // points a DrawImage at a sprite, draw it afterwards with any of the draw_quad calls
+    pub fn set_sprite(&self, image: &mut DrawImage, id: AtlasId) {
+        image.texture = self.texture.into();
+        if let Some((pt1, pt2)) = self.atlas.uv(id) {
+            image.pt1 = pt1;
+            image.pt2 = pt2;
+        }
+    }
+
+    pub fn draw_sprite_walk(&self, cx: &mut Cx, image: &mut DrawImage, id: AtlasId, walk: Walk) {
+        self.set_sprite(image, id);
+        image.draw_quad_walk(cx, walk);
+    }
+
+    pub fn draw_sprite_rel(&self, cx: &mut Cx, image: &mut DrawImage, id: AtlasId, pos: Vec2) {
+        self.set_sprite(image, id);
+        image.draw_quad_rel(cx, Rect {pos, size: self.sprite_size(id)});
+    }
+
+    pub fn draw_sprite_abs(&self, cx: &mut Cx, image: &mut DrawImage, id: AtlasId, rect: Rect) {
+        self.set_sprite(image, id);
+        image.draw_quad_abs(cx, rect);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::cx::*;
+use makepad_microserde::*;
+
+// what apps exchange plain text under, the other formats have their own mime
+pub const CLIPBOARD_MIME_TEXT: &str = "text/plain";
+pub const CLIPBOARD_MIME_HTML: &str = "text/html";
+
+// one representation of what is on the clipboard
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub enum ClipboardFormat {
+    Text(String),
+    Html(String),
+    // app defined data, the mime should be specific like application/x-makepad-file-path
+    Custom {mime: String, data: Vec<u8>},
+    // straight alpha, platforms exchange it as a bitmap
+    Image(DecodedImage),
+}
+
+// the same content in every format the source could offer, the receiver picks the richest it understands
+#[derive(Clone, Debug, Default, PartialEq, SerBin, DeBin)]
+pub struct ClipboardData {
+    pub formats: Vec<ClipboardFormat>
+}
+
+// the clipboard arrived, sent to the key focus instead of a TextInput
+#[derive(Clone, Debug, Default, PartialEq, SerBin, DeBin)]
+pub struct PasteEvent {
+    pub data: ClipboardData
+}
+
+impl ClipboardFormat {
+    pub fn mime(&self) -> &str {
+        match self {
+            ClipboardFormat::Text(_) => CLIPBOARD_MIME_TEXT,
+            ClipboardFormat::Html(_) => CLIPBOARD_MIME_HTML,
+            ClipboardFormat::Custom {mime, ..} => mime,
+            ClipboardFormat::Image(_) => "image/bmp",
+        }
+    }
+}
+
+impl ClipboardData {
+    pub fn from_text(text: &str) -> Self {
+        Self::default().with_text(text)
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.formats.len() == 0
+    }
+    
+    // adding a format twice replaces the earlier one
+    pub fn with_format(mut self, format: ClipboardFormat) -> Self {
+        self.formats.retain( | f | f.mime() != format.mime());
+        self.formats.push(format);
+        self
+    }
+    
+    pub fn with_text(self, text: &str) -> Self {self.with_format(ClipboardFormat::Text(text.to_string()))}
+    pub fn with_html(self, html: &str) -> Self {self.with_format(ClipboardFormat::Html(html.to_string()))}
+    pub fn with_custom(self, mime: &str, data: &[u8]) -> Self {self.with_format(ClipboardFormat::Custom {mime: mime.to_string(), data: data.to_vec()})}
+    pub fn with_image(self, image: DecodedImage) -> Self {self.with_format(ClipboardFormat::Image(image))}
+    
+    pub fn text(&self) -> Option<&str> {
+        self.formats.iter().find_map( | f | if let ClipboardFormat::Text(text) = f {Some(text.as_str())} else {None})
+    }
+    
+    pub fn html(&self) -> Option<&str> {
+        self.formats.iter().find_map( | f | if let ClipboardFormat::Html(html) = f {Some(html.as_str())} else {None})
+    }
+    
+    pub fn custom(&self, mime: &str) -> Option<&[u8]> {
+        self.formats.iter().find_map( | f | match f {
+            ClipboardFormat::Custom {mime: m, data} if m == mime => Some(data.as_slice()),
+            _ => None
+        })
+    }
+    
+    pub fn image(&self) -> Option<&DecodedImage> {
+        self.formats.iter().find_map( | f | if let ClipboardFormat::Image(image) = f {Some(image)} else {None})
+    }
+    
+    pub fn mimes(&self) -> Vec<&str> {
+        self.formats.iter().map( | f | f.mime()).collect()
+    }
+}
+
+impl Cx {
+    
+    // puts data on the system clipboard once the current event is handled. Answering a TextCopy
+    // with a string ends up here as well, so a widget can also call this from its copy handler
+    pub fn set_clipboard(&mut self, data: ClipboardData) {
+        self.clipboard_offer = Some(data);
+    }
+    
+    // turns the string answer to a TextCopy into an offer, called after the event went through the app
+    pub fn process_text_copy_response(&mut self, event: &Event) {
+        if let Event::TextCopy(req) = event {
+            if let Some(response) = &req.response {
+                if self.clipboard_offer.is_none() {
+                    self.clipboard_offer = Some(ClipboardData::from_text(response));
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::cx::*;
+use makepad_microserde::*;
 
-#[derive(Clone, Debug, Hash, PartialEq)]
+#[derive(Clone, Debug, Hash, PartialEq, SerBin, DeBin)]
 pub enum MouseCursor {
     // don't show the cursor
     Hidden,
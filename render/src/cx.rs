@@ -19,38 +19,61 @@ pub use crate::view::*;
 pub use crate::pass::*;
 pub use crate::geometry::*;
 pub use crate::texture::*;
+pub use crate::atlas::*;
+pub use crate::image::*;
+pub use crate::imagepng::*;
+pub use crate::imagejpeg::*;
+pub use crate::imageqoi::*;
+pub use crate::imagebmp::*;
 pub use crate::livemacros::*;
 pub use crate::events::*;
 pub use crate::animator::*;
+pub use crate::spring::*;
 pub use crate::area::*;
 pub use crate::menu::*;
+pub use crate::keymap::*;
+pub use crate::accessibility::*;
+pub use crate::gesture::*;
+pub use crate::clipboard::*;
 pub use crate::shader::*;
 pub use crate::livemacros::*;
 pub use crate::geometrygen::*;
+pub use crate::meshload::*;
 pub use crate::gpuinfo::*;
+pub use crate::ipc::*;
 pub use crate::uid;
 
-#[cfg(all(not(feature = "ipc"), target_os = "linux"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "linux"))]
 pub use crate::cx_linux::*;
-#[cfg(all(not(feature = "ipc"), target_os = "linux"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "linux"))]
 pub use crate::cx_opengl::*;
 
-#[cfg(all(not(feature = "ipc"), target_os = "macos"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "macos"))]
 pub use crate::cx_macos::*;
-#[cfg(all(not(feature = "ipc"), target_os = "macos"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "macos"))]
 pub use crate::cx_metal::*;
 
-#[cfg(all(not(feature = "ipc"), target_os = "windows"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "windows"))]
 pub use crate::cx_windows::*;
-#[cfg(all(not(feature = "ipc"), target_os = "windows"))]
+#[cfg(all(not(any(feature = "ipc", feature = "headless")), target_os = "windows"))]
 pub use crate::cx_dx11::*;
 
 #[cfg(all(not(feature = "ipc"), target_arch = "wasm32"))]
 pub use crate::cx_webgl::*;
 
-#[cfg(all(not(feature = "ipc"), any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+#[cfg(all(feature = "headless", not(feature = "ipc"), any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub use crate::cx_headless::*;
+#[cfg(all(any(feature = "headless", feature = "ipc"), any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub use crate::cx_software::*;
+#[cfg(all(any(feature = "headless", feature = "ipc"), any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub use crate::cx_software_shader::*;
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 pub use crate::cx_desktop::*;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use crate::cx_record::*;
+
 #[cfg(all(not(feature = "ipc"), target_arch = "wasm32"))]
 pub use crate::cx_wasm32::*;
 
@@ -115,6 +138,7 @@ pub struct Cx {
     pub pass_stack: Vec<usize>,
     pub view_stack: Vec<usize>,
     pub turtles: Vec<Turtle>,
+    pub measures: Vec<CxMeasure>,
     pub align_list: Vec<Area>,
     
     pub redraw_child_areas: Vec<Area>,
@@ -158,6 +182,9 @@ pub struct Cx {
     pub live_styles: LiveStyles,
     
     pub command_settings: HashMap<CommandId, CxCommandSetting>,
+    pub keymap: Keymap,
+    pub access: CxAccess,
+    pub clipboard_offer: Option<ClipboardData>,
     
     pub panic_now: bool,
     pub panic_redraw: bool,
@@ -241,6 +268,7 @@ impl Default for Cx {
             pass_stack: Vec::new(),
             view_stack: Vec::new(),
             turtles: Vec::new(),
+            measures: Vec::new(),
             align_list: Vec::new(),
             
             redraw_parent_areas: Vec::new(),
@@ -274,6 +302,9 @@ impl Default for Cx {
             live_styles: LiveStyles::new(),
             
             command_settings: HashMap::new(),
+            keymap: Keymap::default(),
+            access: CxAccess::default(),
+            clipboard_offer: None,
             
             playing_animator_ids: BTreeMap::new(),
             
@@ -698,15 +729,23 @@ impl Cx {
 
         let event_handler = self.event_handler.unwrap();
         
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        self.record_delivered_event(event);
+        
         unsafe{(*event_handler)(self, event);}
+        
+        self.process_text_copy_response(event);
 
         if self.next_key_focus != self.key_focus {
             self.prev_key_focus = self.key_focus;
             self.key_focus = self.next_key_focus;
-            unsafe{(*event_handler)(self,  &mut Event::KeyFocus(KeyFocusEvent {
+            let mut event = Event::KeyFocus(KeyFocusEvent {
                 prev: self.prev_key_focus,
                 focus: self.key_focus
-            }));}
+            });
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            self.record_delivered_event(&event);
+            unsafe{(*event_handler)(self, &mut event);}
         }
     }
     
@@ -738,12 +777,16 @@ impl Cx {
         if self.turtles.len()>0 {
             panic!("Turtle stack disaligned, forgot an end_turtle()");
         }
+        if self.access.stack.len()>0 {
+            panic!("Access stack disaligned, forgot an end_access_node()");
+        }
+        self.prune_access_nodes();
         //self.profile();
     }
     
     pub fn call_animate_event(&mut self, time: f64)
     {
-        self.call_event_handler(&mut Event::Animate(AnimateEvent {time: time, frame: self.repaint_id}));
+        self.call_event_handler(&mut Event::Animate(AnimateEvent {time: time, frame: self.repaint_id, timeline_events: Vec::new()}));
         self.check_ended_animator_ids(time);
     }
     
@@ -11,6 +11,7 @@ pub use makepad_objc_sys::{Encode, Encoding};
 
 pub type id = *mut makepad_objc_sys::runtime::Object;
 pub const nil: id = 0 as id;
+pub const NSBitmapImageFileTypePNG: u64 = 4;
 
 
 #[link(name = "Foundation", kind = "framework")]
@@ -23,6 +24,8 @@ extern {
 extern {
     pub static NSStringPboardType: id;
     pub static NSPasteboardTypeURL: id;
+    pub static NSPasteboardTypeHTML: id;
+    pub static NSPasteboardTypeTIFF: id;
 }
 
 #[link(name = "CoreGraphics", kind = "framework")]
@@ -97,6 +100,23 @@ pub fn str_to_nsstring(val: &str) -> id {
     }
 }
 
+pub fn bytes_to_nsdata(bytes: &[u8]) -> id {
+    unsafe {
+        msg_send![class!(NSData), dataWithBytes: bytes.as_ptr() length: bytes.len()]
+    }
+}
+
+pub fn nsdata_to_bytes(data: id) -> Vec<u8> {
+    unsafe {
+        let bytes: *const u8 = msg_send![data, bytes];
+        let len: usize = msg_send![data, length];
+        if bytes.is_null() {
+            return Vec::new()
+        }
+        std::slice::from_raw_parts(bytes, len).to_vec()
+    }
+}
+
 pub fn load_native_cursor(cursor_name: &str) -> id {
     let sel = Sel::register(cursor_name);
     let id: id = unsafe {msg_send![class!(NSCursor), performSelector: sel]};
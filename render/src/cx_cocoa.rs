@@ -332,34 +332,20 @@ impl CocoaApp {
                     match key_code {
                         KeyCode::KeyV => if modifiers.logo || modifiers.control {
                             // was a paste
-                            let nsstring: id = msg_send![self.pasteboard, stringForType: NSStringPboardType];
-                            let string = nsstring_to_string(nsstring);
-                            self.do_callback(&mut vec![
-                                Event::TextInput(TextInputEvent {
-                                    input: string,
-                                    was_paste: true,
-                                    replace_last: false
-                                })
-                            ]);
+                            let data = self.get_clipboard();
+                            if !data.is_empty() {
+                                self.do_callback(&mut vec![
+                                    Event::Paste(PasteEvent {data})
+                                ]);
+                            }
                         },
                         KeyCode::KeyX | KeyCode::KeyC => if modifiers.logo || modifiers.control {
-                            // cut or copy.
-                            let mut events = vec![
+                            // cut or copy, the answer comes back through set_clipboard
+                            self.do_callback(&mut vec![
                                 Event::TextCopy(TextCopyEvent {
                                     response: None
                                 })
-                            ];
-                            self.do_callback(&mut events);
-                            match &events[0] {
-                                Event::TextCopy(req) => if let Some(response) = &req.response {
-                                    // plug it into the apple clipboard
-                                    let nsstring: id = str_to_nsstring(&response);
-                                    let array: id = msg_send![class!(NSArray), arrayWithObject: NSStringPboardType];
-                                    let () = msg_send![self.pasteboard, declareTypes: array owner: nil];
-                                    let () = msg_send![self.pasteboard, setString: nsstring forType: NSStringPboardType];
-                                },
-                                _ => ()
-                            };
+                            ]);
                         },
                         _ => {}
                     }
@@ -497,6 +483,75 @@ impl CocoaApp {
         }
     }
     
+    // plug it into the apple clipboard, custom mimes are used as pasteboard types as they are
+    pub fn set_clipboard(&mut self, data: ClipboardData) {
+        unsafe {
+            let () = msg_send![self.pasteboard, clearContents];
+            for format in &data.formats {
+                match format {
+                    ClipboardFormat::Text(text) => {
+                        let () = msg_send![self.pasteboard, setString: str_to_nsstring(text) forType: NSStringPboardType];
+                    },
+                    ClipboardFormat::Html(html) => {
+                        let () = msg_send![self.pasteboard, setString: str_to_nsstring(html) forType: NSPasteboardTypeHTML];
+                    },
+                    ClipboardFormat::Custom {mime, data} => {
+                        let () = msg_send![self.pasteboard, setData: bytes_to_nsdata(data) forType: str_to_nsstring(mime)];
+                    },
+                    ClipboardFormat::Image(image) => {
+                        // other apps look for tiff, the image rep converts our bitmap
+                        let rep: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: bytes_to_nsdata(&encode_bmp(image))];
+                        if rep != nil {
+                            let tiff: id = msg_send![rep, TIFFRepresentation];
+                            let () = msg_send![self.pasteboard, setData: tiff forType: NSPasteboardTypeTIFF];
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    pub fn get_clipboard(&mut self) -> ClipboardData {
+        let mut data = ClipboardData::default();
+        unsafe {
+            let text: id = msg_send![self.pasteboard, stringForType: NSStringPboardType];
+            if text != nil {
+                data = data.with_text(&nsstring_to_string(text));
+            }
+            let html: id = msg_send![self.pasteboard, stringForType: NSPasteboardTypeHTML];
+            if html != nil {
+                data = data.with_html(&nsstring_to_string(html));
+            }
+            // anything the image rep can read comes out as png, which we decode ourselves
+            let image_types: id = msg_send![class!(NSBitmapImageRep), imageTypes];
+            let image_type: id = msg_send![self.pasteboard, availableTypeFromArray: image_types];
+            if image_type != nil {
+                let image_data: id = msg_send![self.pasteboard, dataForType: image_type];
+                let rep: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: image_data];
+                if rep != nil {
+                    let dict: id = msg_send![class!(NSDictionary), dictionary];
+                    let png: id = msg_send![rep, representationUsingType: NSBitmapImageFileTypePNG properties: dict];
+                    if let Ok(image) = decode_png(&nsdata_to_bytes(png)) {
+                        data = data.with_image(image);
+                    }
+                }
+            }
+            let types: id = msg_send![self.pasteboard, types];
+            let count: usize = msg_send![types, count];
+            for i in 0..count {
+                let ty: id = msg_send![types, objectAtIndex: i];
+                let mime = nsstring_to_string(ty);
+                if mime.starts_with("application/x-") {
+                    let custom: id = msg_send![self.pasteboard, dataForType: ty];
+                    if custom != nil {
+                        data = data.with_custom(&mime, &nsdata_to_bytes(custom));
+                    }
+                }
+            }
+        }
+        data
+    }
+    
     pub fn terminate_event_loop(&mut self) {
         self.event_loop_running = false;
     }
@@ -1082,6 +1137,10 @@ impl CocoaWindow {
             replace_last: replace_last
         })])
     }
+    
+    pub fn send_text_ime(&mut self, ime: TextImeEvent) {
+        self.do_callback(&mut vec![Event::TextIme(ime)])
+    }
 }
 
 fn get_event_char(event: id) -> char {
@@ -1832,19 +1891,33 @@ pub fn define_cocoa_view_class() -> *const Class {
         }
     }
     
-    extern fn set_marked_text(this: &mut Object, _sel: Sel, string: id, _selected_range: NSRange, _replacement_range: NSRange) {
-        unsafe {
+    extern fn set_marked_text(this: &mut Object, _sel: Sel, string: id, selected_range: NSRange, _replacement_range: NSRange) {
+        let preedit = unsafe {
             let marked_text_ref: &mut id = this.get_mut_ivar("markedText");
             let _: () = msg_send![(*marked_text_ref), release];
             let marked_text = NSMutableAttributedString::alloc(nil);
             let has_attr = msg_send![string, isKindOfClass: class!(NSAttributedString)];
-            if has_attr {
+            let characters = if has_attr {
                 marked_text.init_with_attributed_string(string);
+                msg_send![string, string]
             } else {
                 marked_text.init_with_string(string);
+                string
             };
             *marked_text_ref = marked_text;
-        }
+            nsstring_to_string(characters)
+        };
+        // the selected range is the clause being converted, the rest is still input
+        let len = preedit.chars().count();
+        let start = (selected_range.location as usize).min(len);
+        let end = (start + selected_range.length as usize).min(len);
+        let styles: Vec<TextImeStyle> = (0..len).map( | i | if i >= start && i < end {TextImeStyle::Target} else {TextImeStyle::Input}).collect();
+        let cw = get_cocoa_window(this);
+        cw.send_text_ime(TextImeEvent {
+            preedit: preedit,
+            cursor: end,
+            segments: TextImeEvent::segments_from_styles(&styles)
+        });
     }
     
     extern fn unmark_text(this: &Object, _sel: Sel) {
@@ -1852,6 +1925,9 @@ pub fn define_cocoa_view_class() -> *const Class {
         unsafe {
             let cocoa_app = &(*cw.cocoa_app);
             let marked_text: id = *this.get_ivar("markedText");
+            if marked_text.length() > 0 {
+                cw.send_text_ime(TextImeEvent::default());
+            }
             let mutable_string = marked_text.mutable_string();
             let _: () = msg_send![mutable_string, setString: cocoa_app.const_empty_string];
             let input_context: id = msg_send![this, inputContext];
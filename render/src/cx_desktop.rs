@@ -1,5 +1,6 @@
 use crate::cx::*;
 use std::io::prelude::*;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::net::TcpStream;
@@ -18,6 +19,8 @@ pub struct CxDesktop {
     pub file_read_id: u64,
     pub file_reads: Vec<FileRead>,
     pub profiler_start: Option<u64>,
+    pub event_recorder: Option<EventRecorder>,
+    pub replay_file_reads: Option<HashMap<u64, FileReadEvent>>,
 }
 
 impl Default for CxDesktop {
@@ -27,6 +30,8 @@ impl Default for CxDesktop {
             file_read_id: 1,
             file_reads: Vec::new(),
             profiler_start: None,
+            event_recorder: EventRecorder::from_env(),
+            replay_file_reads: None,
         }
     }
 }
@@ -66,6 +71,8 @@ impl Cx {
     
     pub fn process_desktop_pre_event(&mut self, event: &mut Event)
     {
+        self.record_input_event(event);
+        
         match event {
             Event::FingerHover(fe) => {
                 self.fingers[fe.digit].over_last = Area::Empty;
@@ -128,6 +135,8 @@ impl Cx {
     
     pub fn process_desktop_paint_callbacks(&mut self, time: f64) -> bool
     {
+        self.record_paint(time);
+        
         if self.playing_animator_ids.len() != 0 {
             self.call_animate_event(time);
         }
@@ -168,29 +177,34 @@ impl Cx {
         self.platform.desktop.file_reads.truncate(0);
         
         for read_req in file_read_requests {
-            let file_result = File::open(&read_req.path);
-            if let Ok(mut file) = file_result {
+            // a replay answers from the log, so it sees what the recording saw
+            let data = if let Some(replay_file_reads) = &mut self.platform.desktop.replay_file_reads {
+                if let Some(fr) = replay_file_reads.remove(&read_req.read_id) {
+                    fr.data
+                }
+                else {
+                    Err(format!("Failed to replay {}", read_req.path))
+                }
+            }
+            else if let Ok(mut file) = File::open(&read_req.path) {
                 let mut buffer = Vec::new();
                 // read the whole file
                 if file.read_to_end(&mut buffer).is_ok() {
-                    self.call_event_handler(&mut Event::FileRead(FileReadEvent {
-                        read_id: read_req.read_id,
-                        data: Ok(buffer)
-                    }))
+                    Ok(buffer)
                 }
                 else {
-                    self.call_event_handler(&mut Event::FileRead(FileReadEvent {
-                        read_id: read_req.read_id,
-                        data: Err(format!("Failed to read {}", read_req.path))
-                    }))
+                    Err(format!("Failed to read {}", read_req.path))
                 }
             }
             else {
-                self.call_event_handler(&mut Event::FileRead(FileReadEvent {
-                    read_id: read_req.read_id,
-                    data: Err(format!("Failed to open {}", read_req.path))
-                }))
-            }
+                Err(format!("Failed to open {}", read_req.path))
+            };
+            let event = FileReadEvent {
+                read_id: read_req.read_id,
+                data
+            };
+            self.record_file_read(&read_req.path, &event);
+            self.call_event_handler(&mut Event::FileRead(event))
         }
         
         if self.platform.desktop.file_reads.len() != 0 {
@@ -1320,7 +1320,7 @@ impl D3d11Cx {
             Height: height as u32,
             MipLevels: 1,
             ArraySize: 1,
-            Format: dxgiformat::DXGI_FORMAT_R8G8B8A8_UNORM,
+            Format: dxgiformat::DXGI_FORMAT_B8G8R8A8_UNORM,
             SampleDesc: dxgitype::DXGI_SAMPLE_DESC {
                 Count: 1,
                 Quality: 0
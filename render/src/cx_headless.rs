@@ -0,0 +1,331 @@
+use crate::cx::*;
+use std::collections::{HashMap, BTreeSet, VecDeque};
+use std::sync::{Mutex, Once};
+
+// signals can be posted from any thread, the loop drains them every frame
+static mut GLOBAL_HEADLESS_SIGNALS: *mut Mutex<Vec<(Signal, StatusId)>> = 0 as *mut _;
+static GLOBAL_HEADLESS_SIGNALS_INIT: Once = Once::new();
+
+fn headless_signals() -> &'static Mutex<Vec<(Signal, StatusId)>> {
+    unsafe {
+        GLOBAL_HEADLESS_SIGNALS_INIT.call_once( || {
+            GLOBAL_HEADLESS_SIGNALS = Box::into_raw(Box::new(Mutex::new(Vec::new())));
+        });
+        &*GLOBAL_HEADLESS_SIGNALS
+    }
+}
+
+impl Cx {
+    pub fn event_loop<F>(&mut self, mut event_handler: F)
+    where F: FnMut(&mut Cx, &mut Event),
+    {
+        self.event_handler = Some(&mut event_handler as *const dyn FnMut(&mut Cx, &mut Event) as *mut dyn FnMut(&mut Cx, &mut Event));
+        self.event_loop_core();
+        self.event_handler = None;
+    }
+
+    // runs on a simulated clock, one step per frame_time, until nothing
+    // is left to do or max_frames is reached
+    pub fn event_loop_core(&mut self) {
+        self.platform_type = PlatformType::Linux {custom_window_chrome: true};
+        self.gpu_info.performance = GpuPerformance::Tier1;
+        self.default_dpi_factor = self.platform.headless.dpi_factor;
+
+        self.software_compile_all_shaders();
+
+        self.load_all_fonts();
+
+        self.call_event_handler(&mut Event::Construct);
+
+        self.redraw_child_area(Area::All);
+
+        let mut passes_todo = Vec::new();
+
+        loop {
+            let time = self.platform.headless.time;
+
+            self.headless_fire_timers(time);
+
+            let signals = if let Ok(mut signals) = headless_signals().lock() {
+                std::mem::replace(&mut *signals, Vec::new())
+            } else {
+                Vec::new()
+            };
+            if signals.len() > 0 {
+                let mut event = SignalEvent {signals: HashMap::new()};
+                for (signal, status) in signals {
+                    event.signals.entry(signal).or_insert_with( || BTreeSet::new()).insert(status);
+                }
+                self.headless_event(Event::Signal(event));
+            }
+
+            // injected input goes in one event per frame, so every event sees the ui drawn
+            if let Some(event) = self.platform.headless.events.pop_front() {
+                self.headless_event(event);
+            }
+
+            self.headless_paint(time, &mut passes_todo);
+
+            if self.live_styles.changed_live_bodies.len()>0 || self.live_styles.changed_deps.len()>0 {
+                let changed_live_bodies = self.live_styles.changed_live_bodies.clone();
+                let mut errors = self.process_live_styles_changes();
+                self.software_update_all_shaders(&mut errors);
+                self.call_live_recompile_event(changed_live_bodies, errors);
+            }
+
+            self.process_live_style_errors();
+
+            self.platform.headless.frame += 1;
+            self.platform.headless.time += self.platform.headless.frame_time;
+
+            if self.platform.headless.terminate || self.platform.headless.frame >= self.platform.headless.max_frames {
+                break;
+            }
+            let windows_open = self.windows.iter().any( | window | match window.window_state {
+                CxWindowState::Create {..} | CxWindowState::Created | CxWindowState::Close => true,
+                CxWindowState::Closed => false
+            });
+            if !windows_open && self.windows.len() > 0 {
+                break;
+            }
+            let signals_pending = if let Ok(signals) = headless_signals().lock() {signals.len() > 0} else {false};
+            if self.platform.headless.events.len() == 0
+                && !signals_pending
+                && self.playing_animator_ids.len() == 0
+                && self.redraw_parent_areas.len() == 0
+                && self.redraw_child_areas.len() == 0
+                && self.next_frames.len() == 0
+                && !self.passes.iter().any( | pass | pass.paint_dirty) {
+                break;
+            }
+        }
+    }
+
+    fn headless_event(&mut self, mut event: Event) {
+        self.process_desktop_pre_event(&mut event);
+        match &event {
+            Event::WindowSetHoverCursor(mc) => {
+                self.set_hover_mouse_cursor(mc.clone());
+            },
+            Event::WindowGeomChange(re) => {
+                self.windows[re.window_id].window_geom = re.new_geom.clone();
+                if re.old_geom.inner_size != re.new_geom.inner_size {
+                    if let Some(main_pass_id) = self.windows[re.window_id].main_pass_id {
+                        self.redraw_pass_and_sub_passes(main_pass_id);
+                    }
+                }
+                self.call_event_handler(&mut event);
+            },
+            Event::WindowClosed(wc) => {
+                self.windows[wc.window_id].window_state = CxWindowState::Closed;
+                self.windows_free.push(wc.window_id);
+                self.call_event_handler(&mut event);
+            },
+            Event::Signal {..} => {
+                self.call_event_handler(&mut event);
+                self.call_signals_and_triggers();
+            },
+            Event::None | Event::Paint => {
+            },
+            _ => {
+                self.call_event_handler(&mut event);
+            }
+        }
+        if self.process_desktop_post_event(&mut event) {
+            self.platform.headless.terminate = true;
+        }
+    }
+
+    fn headless_fire_timers(&mut self, time: f64) {
+        while self.platform.start_timer.len() > 0 {
+            let (timer_id, interval, repeats) = self.platform.start_timer.remove(0);
+            self.platform.headless.timers.push(HeadlessTimer {
+                timer_id,
+                interval,
+                repeats,
+                fire_at: time + interval
+            });
+        }
+        while self.platform.stop_timer.len() > 0 {
+            let timer_id = self.platform.stop_timer.pop().unwrap();
+            self.platform.headless.timers.retain( | timer | timer.timer_id != timer_id);
+        }
+        let mut fired = Vec::new();
+        for timer in &mut self.platform.headless.timers {
+            if timer.fire_at <= time {
+                fired.push(timer.timer_id);
+                timer.fire_at = time + timer.interval.max(self.platform.headless.frame_time);
+            }
+        }
+        self.platform.headless.timers.retain( | timer | timer.repeats || !fired.contains(&timer.timer_id));
+        for timer_id in fired {
+            self.headless_event(Event::Timer(TimerEvent {timer_id}));
+        }
+    }
+
+    fn headless_paint(&mut self, time: f64, passes_todo: &mut Vec<usize>) {
+        self.process_desktop_paint_callbacks(time);
+
+        let dpi_factor = self.platform.headless.dpi_factor;
+        let window_size = self.platform.headless.window_size;
+        let mut closed = Vec::new();
+        let mut resized = Vec::new();
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            window.window_state = match &window.window_state {
+                CxWindowState::Create {inner_size, position, ..} => {
+                    // the first draw used the requested size, redraw at the real one
+                    if let Some(window_size) = window_size {
+                        if window_size != *inner_size {
+                            resized.extend(window.main_pass_id);
+                        }
+                    }
+                    let inner_size = window_size.unwrap_or(*inner_size);
+                    window.window_geom = WindowGeom {
+                        dpi_factor,
+                        inner_size,
+                        outer_size: inner_size,
+                        position: position.unwrap_or(Vec2::default()),
+                        ..WindowGeom::default()
+                    };
+                    CxWindowState::Created
+                },
+                CxWindowState::Close => {
+                    closed.push(index);
+                    CxWindowState::Close
+                },
+                CxWindowState::Created => CxWindowState::Created,
+                CxWindowState::Closed => CxWindowState::Closed
+            };
+            window.window_command = CxWindowCmd::None;
+        }
+        for window_id in closed {
+            self.headless_event(Event::WindowClosed(WindowClosedEvent {window_id}));
+        }
+        for pass_id in resized {
+            self.redraw_pass_and_sub_passes(pass_id);
+        }
+
+        if let Some(size) = self.platform.set_window_outer_size.take() {
+            for window_id in 0..self.windows.len() {
+                if let CxWindowState::Created = self.windows[window_id].window_state {
+                    let old_geom = self.windows[window_id].window_geom.clone();
+                    let new_geom = WindowGeom {inner_size: size, outer_size: size, ..old_geom.clone()};
+                    self.headless_event(Event::WindowGeomChange(WindowGeomChangeEvent {window_id, old_geom, new_geom}));
+                }
+            }
+        }
+        self.platform.set_window_position = None;
+        self.platform.set_ime_position = None;
+
+        let mut windows_need_repaint = 0;
+        self.compute_passes_to_repaint(passes_todo, &mut windows_need_repaint);
+
+        for pass_id in passes_todo.iter() {
+            match self.passes[*pass_id].dep_of.clone() {
+                CxPassDepOf::Window(window_id) => {
+                    let dpi_factor = self.windows[window_id].window_geom.dpi_factor;
+                    self.passes[*pass_id].paint_dirty = false;
+                    self.draw_pass_to_framebuffer(*pass_id, dpi_factor);
+                }
+                CxPassDepOf::Pass(parent_pass_id) => {
+                    let dpi_factor = self.get_delegated_dpi_factor(parent_pass_id);
+                    self.draw_pass_to_texture(*pass_id, dpi_factor);
+                },
+                CxPassDepOf::None => {
+                    self.draw_pass_to_texture(*pass_id, 1.0);
+                }
+            }
+        }
+    }
+
+    // queue an event for the loop, delivered one per frame in order
+    pub fn headless_push_event(&mut self, event: Event) {
+        self.platform.headless.events.push_back(event);
+    }
+
+    pub fn show_text_ime(&mut self, x: f32, y: f32) {
+        self.platform.set_ime_position = Some(Vec2 {x: x, y: y});
+    }
+
+    pub fn hide_text_ime(&mut self) {
+    }
+
+    pub fn set_window_outer_size(&mut self, size: Vec2) {
+        self.platform.set_window_outer_size = Some(size);
+    }
+
+    pub fn set_window_position(&mut self, pos: Vec2) {
+        self.platform.set_window_position = Some(pos);
+    }
+
+    pub fn start_timer(&mut self, interval: f64, repeats: bool) -> Timer {
+        self.timer_id += 1;
+        self.platform.start_timer.push((self.timer_id, interval, repeats));
+        Timer {timer_id: self.timer_id}
+    }
+
+    pub fn stop_timer(&mut self, timer: &mut Timer) {
+        if timer.timer_id != 0 {
+            self.platform.stop_timer.push(timer.timer_id);
+            timer.timer_id = 0;
+        }
+    }
+
+    pub fn post_signal(signal: Signal, status: StatusId) {
+        if let Ok(mut signals) = headless_signals().lock() {
+            signals.push((signal, status));
+        }
+    }
+
+    pub fn update_menu(&mut self, _menu: &Menu) {
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HeadlessTimer {
+    pub timer_id: u64,
+    pub interval: f64,
+    pub repeats: bool,
+    pub fire_at: f64,
+}
+
+#[derive(Clone)]
+pub struct CxHeadless {
+    pub time: f64,
+    pub frame_time: f64,
+    pub frame: u64,
+    pub max_frames: u64,
+    pub dpi_factor: f32,
+    pub window_size: Option<Vec2>,
+    pub events: VecDeque<Event>,
+    pub timers: Vec<HeadlessTimer>,
+    pub terminate: bool,
+}
+
+impl Default for CxHeadless {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            frame_time: 1.0 / 60.0,
+            frame: 0,
+            max_frames: 600,
+            dpi_factor: 1.0,
+            window_size: None,
+            events: VecDeque::new(),
+            timers: Vec::new(),
+            terminate: false,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CxPlatform {
+    pub set_window_position: Option<Vec2>,
+    pub set_window_outer_size: Option<Vec2>,
+    pub set_ime_position: Option<Vec2>,
+    pub start_timer: Vec<(u64, f64, bool)>,
+    pub stop_timer: Vec<u64>,
+    pub text_clipboard_response: Option<String>,
+    pub desktop: CxDesktop,
+    pub headless: CxHeadless,
+}
@@ -0,0 +1,447 @@
+use crate::cx::*;
+use std::collections::{HashMap, HashSet, BTreeSet};
+use std::time::{Duration, Instant};
+
+impl Cx {
+    pub fn event_loop<F>(&mut self, mut event_handler: F)
+    where F: FnMut(&mut Cx, &mut Event),
+    {
+        self.event_handler = Some(&mut event_handler as *const dyn FnMut(&mut Cx, &mut Event) as *mut dyn FnMut(&mut Cx, &mut Event));
+        self.event_loop_core();
+        self.event_handler = None;
+    }
+    
+    // the app ships the draw lists of its windows to the parent, which draws them with
+    // its own shaders. input comes back over the same socket
+    pub fn event_loop_core(&mut self) {
+        self.platform_type = PlatformType::Linux {custom_window_chrome: false};
+        self.gpu_info.performance = GpuPerformance::Tier1;
+        
+        let mut transport = match IpcTransport::connect_from_env() {
+            Ok(transport) => transport,
+            Err(err) => {
+                eprintln!("{}", err);
+                return
+            }
+        };
+        
+        self.software_compile_all_shaders();
+        
+        self.load_all_fonts();
+        
+        self.call_event_handler(&mut Event::Construct);
+        
+        self.redraw_child_area(Area::All);
+        
+        let start = Instant::now();
+        let mut passes_todo = Vec::new();
+        let mut running = true;
+        
+        while running {
+            let busy = self.playing_animator_ids.len() != 0
+                || self.redraw_parent_areas.len() != 0
+                || self.redraw_child_areas.len() != 0
+                || self.next_frames.len() != 0
+                || self.platform.start_timer.len() != 0
+                || self.passes.iter().any( | pass | pass.paint_dirty);
+            
+            let timeout = if busy {
+                Some(Duration::from_millis(16))
+            }
+            else {
+                self.ipc_next_timer(start)
+            };
+            
+            let mut inputs = Vec::new();
+            if let Some(input) = transport.recv(timeout) {
+                inputs.push(input);
+            }
+            while let Some(input) = transport.try_recv() {
+                inputs.push(input);
+            }
+            
+            let mut signals = HashMap::new();
+            for input in inputs {
+                match input {
+                    IpcChildInput::Parent(IpcParentMsg::Close) | IpcChildInput::Disconnected => {
+                        running = false;
+                    },
+                    IpcChildInput::Parent(IpcParentMsg::Resize {size, dpi_factor}) => {
+                        self.ipc_resize(size, dpi_factor);
+                    },
+                    IpcChildInput::Parent(msg) => {
+                        let window_id = self.platform.ipc.window_id;
+                        let size = self.platform.ipc.size.unwrap_or(Vec2::default());
+                        if self.ipc_event(msg.to_event(window_id, size)) {
+                            running = false;
+                        }
+                    },
+                    IpcChildInput::Signal(signal, status) => {
+                        signals.entry(signal).or_insert_with( || BTreeSet::new()).insert(status);
+                    }
+                }
+            }
+            if signals.len() > 0 {
+                self.ipc_event(Event::Signal(SignalEvent {signals}));
+            }
+            if !running {
+                break;
+            }
+            
+            let time = start.elapsed().as_secs_f64();
+            self.ipc_fire_timers(time);
+            
+            if !self.ipc_paint(time, &mut passes_todo, &mut transport) {
+                break;
+            }
+            
+            if self.live_styles.changed_live_bodies.len()>0 || self.live_styles.changed_deps.len()>0 {
+                let changed_live_bodies = self.live_styles.changed_live_bodies.clone();
+                let mut errors = self.process_live_styles_changes();
+                self.software_update_all_shaders(&mut errors);
+                self.call_live_recompile_event(changed_live_bodies, errors);
+            }
+            
+            self.process_live_style_errors();
+        }
+        
+        for window_id in 0..self.windows.len() {
+            if let CxWindowState::Created = self.windows[window_id].window_state {
+                self.windows[window_id].window_state = CxWindowState::Closed;
+                self.call_event_handler(&mut Event::WindowClosed(WindowClosedEvent {window_id}));
+            }
+        }
+        let _ = transport.send(&IpcChildMsg::Exit);
+    }
+    
+    // returns true if the app wants to quit
+    fn ipc_event(&mut self, mut event: Event) -> bool {
+        self.process_desktop_pre_event(&mut event);
+        match &event {
+            Event::None => (),
+            Event::WindowSetHoverCursor(mc) => {
+                self.set_hover_mouse_cursor(mc.clone());
+            },
+            Event::WindowGeomChange(re) => {
+                self.windows[re.window_id].window_geom = re.new_geom.clone();
+                if re.old_geom.inner_size != re.new_geom.inner_size || re.old_geom.dpi_factor != re.new_geom.dpi_factor {
+                    if let Some(main_pass_id) = self.windows[re.window_id].main_pass_id {
+                        self.redraw_pass_and_sub_passes(main_pass_id);
+                    }
+                }
+                self.call_event_handler(&mut event);
+            },
+            Event::Signal {..} => {
+                self.call_event_handler(&mut event);
+                self.call_signals_and_triggers();
+            },
+            _ => {
+                self.call_event_handler(&mut event);
+            }
+        }
+        self.process_desktop_post_event(&mut event)
+    }
+    
+    fn ipc_resize(&mut self, size: Vec2, dpi_factor: f32) {
+        self.platform.ipc.size = Some(size);
+        self.platform.ipc.dpi_factor = dpi_factor;
+        for window_id in 0..self.windows.len() {
+            if let CxWindowState::Created = self.windows[window_id].window_state {
+                let old_geom = self.windows[window_id].window_geom.clone();
+                let new_geom = WindowGeom {dpi_factor, inner_size: size, outer_size: size, ..old_geom.clone()};
+                if old_geom != new_geom {
+                    self.ipc_event(Event::WindowGeomChange(WindowGeomChangeEvent {window_id, old_geom, new_geom}));
+                }
+            }
+        }
+    }
+    
+    fn ipc_next_timer(&self, start: Instant) -> Option<Duration> {
+        let time = start.elapsed().as_secs_f64();
+        self.platform.ipc.timers.iter().map( | timer | timer.fire_at).fold(None, | next: Option<f64>, at | {
+            Some(next.map_or(at, | next | next.min(at)))
+        }).map( | at | Duration::from_secs_f64((at - time).max(0.0)))
+    }
+    
+    fn ipc_fire_timers(&mut self, time: f64) {
+        while self.platform.start_timer.len() > 0 {
+            let (timer_id, interval, repeats) = self.platform.start_timer.remove(0);
+            self.platform.ipc.timers.push(IpcTimer {
+                timer_id,
+                interval,
+                repeats,
+                fire_at: time + interval
+            });
+        }
+        while self.platform.stop_timer.len() > 0 {
+            let timer_id = self.platform.stop_timer.pop().unwrap();
+            self.platform.ipc.timers.retain( | timer | timer.timer_id != timer_id);
+        }
+        let mut fired = Vec::new();
+        for timer in &mut self.platform.ipc.timers {
+            if timer.fire_at <= time {
+                fired.push(timer.timer_id);
+                timer.fire_at = time + timer.interval;
+            }
+        }
+        self.platform.ipc.timers.retain( | timer | timer.repeats || !fired.contains(&timer.timer_id));
+        for timer_id in fired {
+            self.ipc_event(Event::Timer(TimerEvent {timer_id}));
+        }
+    }
+    
+    // returns false once the app has no window left to show
+    fn ipc_paint(&mut self, time: f64, passes_todo: &mut Vec<usize>, transport: &mut IpcTransport) -> bool {
+        self.process_desktop_paint_callbacks(time);
+        
+        let dpi_factor = self.platform.ipc.dpi_factor;
+        let size = self.platform.ipc.size;
+        let mut resized = Vec::new();
+        let mut closed = Vec::new();
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            window.window_state = match &window.window_state {
+                CxWindowState::Create {inner_size, ..} => {
+                    // the parent decides the size, the first draw may have used another
+                    let inner_size = if let Some(size) = size {
+                        if size != *inner_size {
+                            resized.extend(window.main_pass_id);
+                        }
+                        size
+                    }
+                    else {
+                        *inner_size
+                    };
+                    window.window_geom = WindowGeom {
+                        dpi_factor,
+                        inner_size,
+                        outer_size: inner_size,
+                        ..WindowGeom::default()
+                    };
+                    CxWindowState::Created
+                },
+                CxWindowState::Close => {
+                    closed.push(index);
+                    CxWindowState::Closed
+                },
+                CxWindowState::Created => CxWindowState::Created,
+                CxWindowState::Closed => CxWindowState::Closed
+            };
+            window.window_command = CxWindowCmd::None;
+        }
+        for window_id in closed {
+            self.windows_free.push(window_id);
+            self.call_event_handler(&mut Event::WindowClosed(WindowClosedEvent {window_id}));
+        }
+        for pass_id in resized {
+            self.redraw_pass_and_sub_passes(pass_id);
+        }
+        if let Some(window_id) = self.windows.iter().position( | window | if let CxWindowState::Created = window.window_state {true} else {false}) {
+            self.platform.ipc.window_id = window_id;
+        }
+        else if self.windows.len() > 0 {
+            return false
+        }
+        
+        self.platform.set_window_position = None;
+        self.platform.set_window_outer_size = None;
+        self.platform.set_ime_position = None;
+        
+        let cursor = self.down_mouse_cursor.clone().or(self.hover_mouse_cursor.clone()).unwrap_or(MouseCursor::Default);
+        if self.platform.ipc.cursor.as_ref() != Some(&cursor) {
+            let _ = transport.send(&IpcChildMsg::SetHoverCursor(cursor.clone()));
+            self.platform.ipc.cursor = Some(cursor);
+        }
+        
+        if let Some(data) = self.clipboard_offer.take() {
+            let _ = transport.send(&IpcChildMsg::SetClipboard(data));
+        }
+        
+        // images that changed go over again when a window uses them next
+        for (texture_id, cxtexture) in self.textures.iter().enumerate() {
+            if cxtexture.update_image {
+                self.platform.ipc.sent_textures.remove(&(texture_id as u32));
+            }
+        }
+        
+        let mut windows_need_repaint = 0;
+        self.compute_passes_to_repaint(passes_todo, &mut windows_need_repaint);
+        
+        // sub passes paint here, the windows sample what they painted
+        for pass_id in passes_todo.iter() {
+            let dpi_factor = match self.passes[*pass_id].dep_of.clone() {
+                CxPassDepOf::Window(_) => continue,
+                CxPassDepOf::Pass(parent_pass_id) => self.get_delegated_dpi_factor(parent_pass_id),
+                CxPassDepOf::None => 1.0
+            };
+            self.draw_pass_to_texture(*pass_id, dpi_factor);
+            for color_texture in &self.passes[*pass_id].color_textures {
+                self.platform.ipc.sent_textures.remove(&color_texture.texture_id);
+            }
+        }
+        
+        let shader_ids: HashMap<usize, LiveItemId> = self.live_styles.shader_alloc.iter().map( | (live_id, shader) | (shader.shader_id, *live_id)).collect();
+        for pass_id in passes_todo.iter() {
+            let window_id = if let CxPassDepOf::Window(window_id) = self.passes[*pass_id].dep_of {window_id} else {continue};
+            self.passes[*pass_id].paint_dirty = false;
+            let view_id = if let Some(view_id) = self.passes[*pass_id].main_view_id {view_id} else {continue};
+            
+            let mut textures = BTreeSet::new();
+            let root = self.ipc_draw_list(view_id, &shader_ids, &mut textures);
+            for texture_id in textures {
+                if self.platform.ipc.sent_textures.insert(texture_id) {
+                    if let Some(msg) = self.ipc_texture_msg(texture_id) {
+                        let _ = transport.send(&msg);
+                    }
+                }
+            }
+            let pass = &self.passes[*pass_id];
+            let clear_color = if let Some(color_texture) = pass.color_textures.get(0) {
+                match color_texture.clear_color {
+                    ClearColor::InitWith(color) => color,
+                    ClearColor::ClearWith(color) => color
+                }
+            }
+            else {
+                pass.clear_color
+            };
+            let _ = transport.send(&IpcChildMsg::Frame(IpcFrame {
+                window_id,
+                size: pass.pass_size,
+                dpi_factor: self.windows[window_id].window_geom.dpi_factor,
+                clear_color,
+                root
+            }));
+        }
+        true
+    }
+    
+    // a view and its sub views as the parent draws them. calls with geometry of their
+    // own are left out, the parent doesn't have it
+    fn ipc_draw_list(&self, view_id: usize, shader_ids: &HashMap<usize, LiveItemId>, textures: &mut BTreeSet<u32>) -> IpcDrawList {
+        let cxview = &self.views[view_id];
+        let mut items = Vec::new();
+        for draw_call in &cxview.draw_calls[0..cxview.draw_calls_len] {
+            if draw_call.sub_view_id != 0 {
+                items.push(IpcDrawItem::DrawList(self.ipc_draw_list(draw_call.sub_view_id, shader_ids, textures)));
+                continue;
+            }
+            if draw_call.geometry.is_some() || draw_call.instances.len() == 0 {
+                continue;
+            }
+            let shader = if let Some(shader) = shader_ids.get(&draw_call.shader.shader_id) {*shader} else {continue};
+            textures.extend(draw_call.textures_2d.iter().cloned());
+            items.push(IpcDrawItem::DrawCall(IpcDrawCall {
+                shader,
+                instance_slots: draw_call.total_instance_slots,
+                instances: draw_call.instances.clone(),
+                user_uniforms: draw_call.user_uniforms.clone(),
+                textures: draw_call.textures_2d.clone(),
+                do_h_scroll: draw_call.do_h_scroll,
+                do_v_scroll: draw_call.do_v_scroll,
+            }));
+        }
+        IpcDrawList {
+            rect: cxview.rect,
+            clipped: cxview.clipped,
+            scroll: cxview.get_local_scroll(),
+            items
+        }
+    }
+    
+    // images go as they are, what a pass painted is read back from its buffer
+    fn ipc_texture_msg(&self, texture_id: u32) -> Option<IpcChildMsg> {
+        let cxtexture = self.textures.get(texture_id as usize) ?;
+        if cxtexture.image_u32.len() > 0 {
+            let width = cxtexture.desc.width.unwrap_or(0);
+            let height = cxtexture.desc.height.unwrap_or(0);
+            return Some(IpcChildMsg::Texture {texture_id, width, height, pixels: cxtexture.image_u32.clone()})
+        }
+        let buffer = &cxtexture.platform.buffer;
+        if buffer.width * buffer.height == 0 || buffer.color.len() != buffer.width * buffer.height {
+            return None
+        }
+        let pixels = buffer.to_rgba8().chunks(4).map( | c | {
+            (c[3] as u32) << 24 | (c[0] as u32) << 16 | (c[1] as u32) << 8 | c[2] as u32
+        }).collect();
+        Some(IpcChildMsg::Texture {texture_id, width: buffer.width, height: buffer.height, pixels})
+    }
+    
+    pub fn show_text_ime(&mut self, x: f32, y: f32) {
+        self.platform.set_ime_position = Some(Vec2 {x: x, y: y});
+    }
+    
+    pub fn hide_text_ime(&mut self) {
+    }
+    
+    pub fn set_window_outer_size(&mut self, size: Vec2) {
+        self.platform.set_window_outer_size = Some(size);
+    }
+    
+    pub fn set_window_position(&mut self, pos: Vec2) {
+        self.platform.set_window_position = Some(pos);
+    }
+    
+    pub fn start_timer(&mut self, interval: f64, repeats: bool) -> Timer {
+        self.timer_id += 1;
+        self.platform.start_timer.push((self.timer_id, interval, repeats));
+        Timer {timer_id: self.timer_id}
+    }
+    
+    pub fn stop_timer(&mut self, timer: &mut Timer) {
+        if timer.timer_id != 0 {
+            self.platform.stop_timer.push(timer.timer_id);
+            timer.timer_id = 0;
+        }
+    }
+    
+    pub fn post_signal(signal: Signal, status: StatusId) {
+        IpcTransport::post_signal(signal, status);
+    }
+    
+    pub fn update_menu(&mut self, _menu: &Menu) {
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IpcTimer {
+    pub timer_id: u64,
+    pub interval: f64,
+    pub repeats: bool,
+    pub fire_at: f64,
+}
+
+#[derive(Clone)]
+pub struct CxIpcChild {
+    pub size: Option<Vec2>,
+    pub dpi_factor: f32,
+    pub window_id: usize,
+    pub cursor: Option<MouseCursor>,
+    pub timers: Vec<IpcTimer>,
+    // child texture ids the parent has the current pixels of
+    pub sent_textures: HashSet<u32>,
+}
+
+impl Default for CxIpcChild {
+    fn default() -> Self {
+        Self {
+            size: None,
+            dpi_factor: 1.0,
+            window_id: 0,
+            cursor: None,
+            timers: Vec::new(),
+            sent_textures: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CxPlatform {
+    pub set_window_position: Option<Vec2>,
+    pub set_window_outer_size: Option<Vec2>,
+    pub set_ime_position: Option<Vec2>,
+    pub start_timer: Vec<(u64, f64, bool)>,
+    pub stop_timer: Vec<u64>,
+    pub text_clipboard_response: Option<String>,
+    pub desktop: CxDesktop,
+    pub ipc: CxIpcChild,
+}
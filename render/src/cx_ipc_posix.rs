@@ -0,0 +1,92 @@
+use crate::cx::*;
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::sync::{mpsc, Mutex, Once};
+use std::time::Duration;
+
+pub enum IpcChildInput {
+    Parent(IpcParentMsg),
+    Signal(Signal, StatusId),
+    Disconnected
+}
+
+// post_signal is static, so the loop's channel lives in a global like the other platforms
+static mut GLOBAL_IPC_CHANNEL: *mut (Mutex<mpsc::Sender<IpcChildInput>>, Mutex<Option<mpsc::Receiver<IpcChildInput>>>) = 0 as *mut _;
+static GLOBAL_IPC_CHANNEL_INIT: Once = Once::new();
+
+fn ipc_channel() -> &'static (Mutex<mpsc::Sender<IpcChildInput>>, Mutex<Option<mpsc::Receiver<IpcChildInput>>>) {
+    unsafe {
+        GLOBAL_IPC_CHANNEL_INIT.call_once( || {
+            let (sender, receiver) = mpsc::channel();
+            GLOBAL_IPC_CHANNEL = Box::into_raw(Box::new((Mutex::new(sender), Mutex::new(Some(receiver)))));
+        });
+        &*GLOBAL_IPC_CHANNEL
+    }
+}
+
+pub struct IpcTransport {
+    stream: UnixStream,
+    receiver: mpsc::Receiver<IpcChildInput>,
+}
+
+impl IpcTransport {
+    // connects to the socket the parent passed in our environment
+    pub fn connect_from_env() -> io::Result<IpcTransport> {
+        let socket_path = std::env::var(IPC_SOCKET_ENV).map_err( | _ | {
+            io::Error::new(io::ErrorKind::NotFound, format!("IpcTransport: {} not set, start this app from a parent window", IPC_SOCKET_ENV))
+        }) ?;
+        let stream = UnixStream::connect(socket_path) ?;
+        let receiver = if let Ok(mut receiver) = ipc_channel().1.lock() {
+            receiver.take()
+        } else {
+            None
+        };
+        let receiver = receiver.ok_or_else( || io::Error::new(io::ErrorKind::Other, "IpcTransport: already connected")) ?;
+
+        let sender = if let Ok(sender) = ipc_channel().0.lock() {sender.clone()} else {
+            return Err(io::Error::new(io::ErrorKind::Other, "IpcTransport: channel poisoned"))
+        };
+        let mut read_stream = stream.try_clone() ?;
+        std::thread::spawn(move || {
+            loop {
+                match read_ipc_msg::<_, IpcParentMsg>(&mut read_stream) {
+                    Ok(msg) => if sender.send(IpcChildInput::Parent(msg)).is_err() {
+                        return
+                    },
+                    Err(_) => {
+                        let _ = sender.send(IpcChildInput::Disconnected);
+                        return
+                    }
+                }
+            }
+        });
+        Ok(IpcTransport {
+            stream,
+            receiver
+        })
+    }
+
+    pub fn send(&mut self, msg: &IpcChildMsg) -> io::Result<()> {
+        write_ipc_msg(&mut self.stream, msg)
+    }
+
+    // blocks until input arrives, or the timeout passes
+    pub fn recv(&self, timeout: Option<Duration>) -> Option<IpcChildInput> {
+        if let Some(timeout) = timeout {
+            self.receiver.recv_timeout(timeout).ok()
+        }
+        else {
+            self.receiver.recv().ok()
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<IpcChildInput> {
+        self.receiver.try_recv().ok()
+    }
+
+    pub fn post_signal(signal: Signal, status: StatusId) {
+        if let Ok(sender) = ipc_channel().0.lock() {
+            let _ = sender.send(IpcChildInput::Signal(signal, status));
+        }
+    }
+}
@@ -249,6 +249,10 @@ impl Cx {
             }
             
             self.process_live_style_errors();
+            
+            if let Some(data) = self.clipboard_offer.take() {
+                xlib_app.set_clipboard(data);
+            }
 
             if paint_dirty 
                 || self.playing_animator_ids.len() != 0
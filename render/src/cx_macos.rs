@@ -257,6 +257,10 @@ impl Cx {
             
             self.process_live_style_errors();
             
+            if let Some(data) = self.clipboard_offer.take() {
+                cocoa_app.set_clipboard(data);
+            }
+            
             if paint_dirty
                 || self.playing_animator_ids.len() != 0
                 || self.redraw_parent_areas.len() != 0
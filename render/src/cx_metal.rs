@@ -510,25 +510,6 @@ impl MetalCx {
                     let tex: id = unsafe {msg_send![self.device, newTextureWithDescriptor: mdesc]};
                     
                     cxtexture.platform.mtl_texture = Some(tex);
-                    
-                    if cxtexture.image_u32.len() != width * height {
-                        println!("update_platform_texture_image2d with wrong buffer_u32 size!");
-                        cxtexture.platform.mtl_texture = None;
-                        return;
-                    }
-                    let region = MTLRegion {
-                        origin: MTLOrigin {x: 0, y: 0, z: 0},
-                        size: MTLSize {width: width as u64, height: height as u64, depth: 1}
-                    };
-                    if let Some(mtl_texture) = cxtexture.platform.mtl_texture {
-                        let () = unsafe {msg_send![
-                            mtl_texture,
-                            replaceRegion: region
-                            mipmapLevel: 0
-                            withBytes: cxtexture.image_u32.as_ptr() as *const std::ffi::c_void
-                            bytesPerRow: (width * std::mem::size_of::<u32>()) as u64
-                        ]};
-                    }
                 },
                 _ => {
                     println!("update_platform_texture_image2d with unsupported format");
@@ -540,6 +521,25 @@ impl MetalCx {
             cxtexture.platform.height = height as u64;
         }
         
+        // the image can change without the descriptor changing, so always upload
+        if cxtexture.image_u32.len() != width * height {
+            println!("update_platform_texture_image2d with wrong buffer_u32 size!");
+            return;
+        }
+        let region = MTLRegion {
+            origin: MTLOrigin {x: 0, y: 0, z: 0},
+            size: MTLSize {width: width as u64, height: height as u64, depth: 1}
+        };
+        if let Some(mtl_texture) = cxtexture.platform.mtl_texture {
+            let () = unsafe {msg_send![
+                mtl_texture,
+                replaceRegion: region
+                mipmapLevel: 0
+                withBytes: cxtexture.image_u32.as_ptr() as *const std::ffi::c_void
+                bytesPerRow: (width * std::mem::size_of::<u32>()) as u64
+            ]};
+        }
+        
         cxtexture.update_image = false;
     }
 }
@@ -1062,7 +1062,20 @@ impl OpenglCx {
                 gl::BindTexture(gl::TEXTURE_2D, gl_texture);
                 gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
                 gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-                gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, width as i32, height as i32, 0, gl::RGBA, gl::UNSIGNED_BYTE, cxtexture.image_u32.as_ptr() as *const _);
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+            }
+        }
+        
+        // the image can change without the descriptor changing, so always upload
+        if cxtexture.image_u32.len() != width * height {
+            println!("update_platform_texture_image2d with wrong buffer_u32 size!");
+            return;
+        }
+        if let Some(gl_texture) = cxtexture.platform.gl_texture {
+            unsafe {
+                // image_u32 is 0xAARRGGBB, which is BGRA in memory
+                gl::BindTexture(gl::TEXTURE_2D, gl_texture);
+                gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA as i32, width as i32, height as i32, 0, gl::BGRA, gl::UNSIGNED_BYTE, cxtexture.image_u32.as_ptr() as *const _);
                 gl::BindTexture(gl::TEXTURE_2D, 0);
             }
         }
@@ -0,0 +1,513 @@
+use crate::cx::*;
+use makepad_microserde::*;
+use makepad_live_compiler::analyse::ShaderCompileOptions;
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::Arc;
+use std::io;
+use std::io::prelude::*;
+use std::time::Instant;
+
+// set this to a path to record every run of an app into it
+pub const EVENT_RECORD_ENV: &str = "MAKEPAD_RECORD_EVENTS";
+
+// an Event as it is stored in a log. statuses and triggers are kept as their ids,
+// which only mean the same thing to the build that recorded them. commands are
+// keyed on TypeIds and file writes and live recompiles are not replayed
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub enum RecordedEvent {
+    None,
+    Construct,
+    Destruct,
+    Draw,
+    Paint,
+    Redraw,
+    AppFocus,
+    AppFocusLost,
+    AnimEnded(AnimateEvent),
+    Animate(AnimateEvent),
+    NextFrame(NextFrameEvent),
+    XRUpdate(XRUpdateEvent),
+    WindowSetHoverCursor(MouseCursor),
+    WindowDragQuery(WindowDragQueryEvent),
+    WindowCloseRequested(WindowCloseRequestedEvent),
+    WindowClosed(WindowClosedEvent),
+    WindowGeomChange(WindowGeomChangeEvent),
+    WindowResizeLoop(WindowResizeLoopEvent),
+    FingerDown(FingerDownEvent),
+    FingerMove(FingerMoveEvent),
+    FingerHover(FingerHoverEvent),
+    FingerUp(FingerUpEvent),
+    FingerScroll(FingerScrollEvent),
+    FileRead(FileReadEvent),
+    FileWrite,
+    Timer(TimerEvent),
+    Signal(Vec<(usize, Vec<u64>)>),
+    Triggers(Vec<(Area, Vec<u64>)>),
+    Trigger(Vec<u64>),
+    Command,
+    KeyFocus(KeyFocusEvent),
+    KeyFocusLost(KeyFocusEvent),
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    TextInput(TextInputEvent),
+    TextIme(TextImeEvent),
+    TextCopy(TextCopyEvent),
+    Paste(PasteEvent),
+    LiveRecompile,
+    WebSocketMessage(WebSocketMessageEvent),
+    FingerDrag(FingerDragEvent),
+}
+
+impl RecordedEvent {
+    pub fn from_event(event: &Event) -> RecordedEvent {
+        match event {
+            Event::None => RecordedEvent::None,
+            Event::Construct => RecordedEvent::Construct,
+            Event::Destruct => RecordedEvent::Destruct,
+            Event::Draw => RecordedEvent::Draw,
+            Event::Paint => RecordedEvent::Paint,
+            Event::Redraw => RecordedEvent::Redraw,
+            Event::AppFocus => RecordedEvent::AppFocus,
+            Event::AppFocusLost => RecordedEvent::AppFocusLost,
+            Event::AnimEnded(e) => RecordedEvent::AnimEnded(e.clone()),
+            Event::Animate(e) => RecordedEvent::Animate(e.clone()),
+            Event::NextFrame(e) => RecordedEvent::NextFrame(e.clone()),
+            Event::XRUpdate(e) => RecordedEvent::XRUpdate(e.clone()),
+            Event::WindowSetHoverCursor(e) => RecordedEvent::WindowSetHoverCursor(e.clone()),
+            Event::WindowDragQuery(e) => RecordedEvent::WindowDragQuery(e.clone()),
+            Event::WindowCloseRequested(e) => RecordedEvent::WindowCloseRequested(e.clone()),
+            Event::WindowClosed(e) => RecordedEvent::WindowClosed(e.clone()),
+            Event::WindowGeomChange(e) => RecordedEvent::WindowGeomChange(e.clone()),
+            Event::WindowResizeLoop(e) => RecordedEvent::WindowResizeLoop(e.clone()),
+            Event::FingerDown(e) => RecordedEvent::FingerDown(e.clone()),
+            Event::FingerMove(e) => RecordedEvent::FingerMove(e.clone()),
+            Event::FingerHover(e) => RecordedEvent::FingerHover(e.clone()),
+            Event::FingerUp(e) => RecordedEvent::FingerUp(e.clone()),
+            Event::FingerScroll(e) => RecordedEvent::FingerScroll(e.clone()),
+            Event::FileRead(e) => RecordedEvent::FileRead(e.clone()),
+            Event::FileWrite(_) => RecordedEvent::FileWrite,
+            Event::Timer(e) => RecordedEvent::Timer(e.clone()),
+            Event::Signal(e) => {
+                let mut signals: Vec<(usize, Vec<u64>)> = e.signals.iter().map( | (signal, statuses) | {
+                    (signal.signal_id, statuses.iter().map( | status | status.0).collect())
+                }).collect();
+                signals.sort();
+                RecordedEvent::Signal(signals)
+            },
+            Event::Triggers(e) => {
+                let mut triggers: Vec<(Area, Vec<u64>)> = e.triggers.iter().map( | (area, triggers) | {
+                    (area.clone(), triggers.iter().map( | trigger | trigger.0).collect())
+                }).collect();
+                triggers.sort();
+                RecordedEvent::Triggers(triggers)
+            },
+            Event::Trigger(e) => RecordedEvent::Trigger(e.triggers.iter().map( | trigger | trigger.0).collect()),
+            Event::Command(_) => RecordedEvent::Command,
+            Event::KeyFocus(e) => RecordedEvent::KeyFocus(e.clone()),
+            Event::KeyFocusLost(e) => RecordedEvent::KeyFocusLost(e.clone()),
+            Event::KeyDown(e) => RecordedEvent::KeyDown(e.clone()),
+            Event::KeyUp(e) => RecordedEvent::KeyUp(e.clone()),
+            Event::TextInput(e) => RecordedEvent::TextInput(e.clone()),
+            Event::TextIme(e) => RecordedEvent::TextIme(e.clone()),
+            Event::TextCopy(e) => RecordedEvent::TextCopy(e.clone()),
+            Event::Paste(e) => RecordedEvent::Paste(e.clone()),
+            Event::LiveRecompile(_) => RecordedEvent::LiveRecompile,
+            Event::WebSocketMessage(e) => RecordedEvent::WebSocketMessage(e.clone()),
+            Event::FingerDrag(e) => RecordedEvent::FingerDrag(e.clone()),
+        }
+    }
+
+    // the event to feed back in
+    pub fn to_event(&self) -> Option<Event> {
+        Some(match self {
+            RecordedEvent::None => Event::None,
+            RecordedEvent::Construct => Event::Construct,
+            RecordedEvent::Destruct => Event::Destruct,
+            RecordedEvent::Draw => Event::Draw,
+            RecordedEvent::Paint => Event::Paint,
+            RecordedEvent::Redraw => Event::Redraw,
+            RecordedEvent::AppFocus => Event::AppFocus,
+            RecordedEvent::AppFocusLost => Event::AppFocusLost,
+            RecordedEvent::AnimEnded(e) => Event::AnimEnded(e.clone()),
+            RecordedEvent::Animate(e) => Event::Animate(e.clone()),
+            RecordedEvent::NextFrame(e) => Event::NextFrame(e.clone()),
+            RecordedEvent::XRUpdate(e) => Event::XRUpdate(e.clone()),
+            RecordedEvent::WindowSetHoverCursor(e) => Event::WindowSetHoverCursor(e.clone()),
+            RecordedEvent::WindowDragQuery(e) => Event::WindowDragQuery(e.clone()),
+            RecordedEvent::WindowCloseRequested(e) => Event::WindowCloseRequested(e.clone()),
+            RecordedEvent::WindowClosed(e) => Event::WindowClosed(e.clone()),
+            RecordedEvent::WindowGeomChange(e) => Event::WindowGeomChange(e.clone()),
+            RecordedEvent::WindowResizeLoop(e) => Event::WindowResizeLoop(e.clone()),
+            RecordedEvent::FingerDown(e) => Event::FingerDown(e.clone()),
+            RecordedEvent::FingerMove(e) => Event::FingerMove(e.clone()),
+            RecordedEvent::FingerHover(e) => Event::FingerHover(e.clone()),
+            RecordedEvent::FingerUp(e) => Event::FingerUp(e.clone()),
+            RecordedEvent::FingerScroll(e) => Event::FingerScroll(e.clone()),
+            RecordedEvent::FileRead(e) => Event::FileRead(e.clone()),
+            RecordedEvent::Timer(e) => Event::Timer(e.clone()),
+            RecordedEvent::Signal(signals) => Event::Signal(SignalEvent {
+                signals: signals.iter().map( | (signal_id, statuses) | {
+                    (Signal {signal_id: *signal_id}, statuses.iter().map( | status | StatusId(*status)).collect())
+                }).collect()
+            }),
+            RecordedEvent::Triggers(triggers) => Event::Triggers(TriggersEvent {
+                triggers: triggers.iter().map( | (area, triggers) | {
+                    (area.clone(), triggers.iter().map( | trigger | TriggerId(*trigger)).collect())
+                }).collect()
+            }),
+            RecordedEvent::Trigger(triggers) => Event::Trigger(TriggerEvent {
+                triggers: triggers.iter().map( | trigger | TriggerId(*trigger)).collect()
+            }),
+            RecordedEvent::KeyFocus(e) => Event::KeyFocus(e.clone()),
+            RecordedEvent::KeyFocusLost(e) => Event::KeyFocusLost(e.clone()),
+            RecordedEvent::KeyDown(e) => Event::KeyDown(e.clone()),
+            RecordedEvent::KeyUp(e) => Event::KeyUp(e.clone()),
+            RecordedEvent::TextInput(e) => Event::TextInput(e.clone()),
+            RecordedEvent::TextIme(e) => Event::TextIme(e.clone()),
+            RecordedEvent::TextCopy(e) => Event::TextCopy(e.clone()),
+            RecordedEvent::Paste(e) => Event::Paste(e.clone()),
+            RecordedEvent::WebSocketMessage(e) => Event::WebSocketMessage(e.clone()),
+            RecordedEvent::FingerDrag(e) => Event::FingerDrag(e.clone()),
+            RecordedEvent::FileWrite
+                | RecordedEvent::Command
+                | RecordedEvent::LiveRecompile => return None
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub enum RecordedWindowState {
+    Create,
+    Created,
+    Close,
+    Closed
+}
+
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub struct RecordedWindow {
+    pub state: RecordedWindowState,
+    pub geom: WindowGeom,
+}
+
+// time is in seconds since the recording started
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub enum EventRecord {
+    // an event the platform fed in, a replay feeds these back
+    Input {time: f64, event: RecordedEvent},
+    // the platform ran its paint callbacks on its own clock, with these windows
+    Paint {time: f64, paint_time: f64, windows: Vec<RecordedWindow>},
+    // a file read finished, a replay answers the read from here instead of the disk
+    FileRead {time: f64, path: String, event: FileReadEvent},
+    // an event that reached the app handler, a replay checks it arrives again
+    Delivered {time: f64, event: RecordedEvent},
+}
+
+// the file holds length prefixed records, so a log cut short by a crash still loads
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventLog {
+    pub records: Vec<EventRecord>
+}
+
+impl EventLog {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for record in &self.records {
+            Self::append_record(&mut bytes, record);
+        }
+        bytes
+    }
+
+    fn append_record(bytes: &mut Vec<u8>, record: &EventRecord) {
+        let data = record.serialize_bin();
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&data);
+    }
+
+    // a truncated last record is dropped, anything else that doesn't parse is an error
+    pub fn from_bytes(bytes: &[u8]) -> Result<EventLog, String> {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while offset + 8 <= bytes.len() {
+            let mut len = [0u8; 8];
+            len.copy_from_slice(&bytes[offset..offset + 8]);
+            let len = u64::from_le_bytes(len) as usize;
+            offset += 8;
+            if offset + len > bytes.len() {
+                break;
+            }
+            let record = EventRecord::deserialize_bin(&bytes[offset..offset + len]).map_err( | err | format!("EventLog: record {} {}", records.len(), err)) ?;
+            records.push(record);
+            offset += len;
+        }
+        Ok(EventLog {records})
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        File::create(path) ?.write_all(&self.to_bytes())
+    }
+
+    pub fn load(path: &str) -> io::Result<EventLog> {
+        let mut bytes = Vec::new();
+        File::open(path) ?.read_to_end(&mut bytes) ?;
+        Self::from_bytes(&bytes).map_err( | err | io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn delivered_events(&self) -> Vec<RecordedEvent> {
+        self.records.iter().filter_map( | record | match record {
+            EventRecord::Delivered {event, ..} => Some(event.clone()),
+            _ => None
+        }).collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct EventRecorder {
+    pub start: Instant,
+    pub log: EventLog,
+    // when set, records are appended to this file every paint and dropped from the log
+    pub path: Option<String>,
+    pub file: Option<Arc<File>>,
+}
+
+impl EventRecorder {
+    pub fn new(path: Option<String>) -> EventRecorder {
+        // the file stays open for the whole recording
+        let file = if let Some(path) = &path {
+            match File::create(path) {
+                Ok(file) => Some(Arc::new(file)),
+                Err(err) => {
+                    println!("EventRecorder: can't create {} {}", path, err);
+                    None
+                }
+            }
+        } else {None};
+        EventRecorder {
+            start: Instant::now(),
+            log: EventLog::default(),
+            path,
+            file
+        }
+    }
+
+    pub fn from_env() -> Option<EventRecorder> {
+        std::env::var(EVENT_RECORD_ENV).ok().map( | path | EventRecorder::new(Some(path)))
+    }
+
+    pub fn time(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(path) = &self.path {
+            if self.log.records.len() == 0 {
+                return
+            }
+            if let Some(file) = &self.file {
+                if let Err(err) = (&**file).write_all(&self.log.to_bytes()) {
+                    println!("EventRecorder: can't write {} {}", path, err);
+                }
+            }
+            self.log.records.truncate(0);
+        }
+    }
+}
+
+// what came out of a replay, mismatch holds the index and both sides of the
+// first delivered event that differs from the recording
+#[derive(Clone, Debug, Default)]
+pub struct EventReplay {
+    pub delivered: Vec<RecordedEvent>,
+    pub mismatch: Option<(usize, Option<RecordedEvent>, Option<RecordedEvent>)>
+}
+
+impl EventReplay {
+    pub fn matches(&self) -> bool {
+        self.mismatch.is_none()
+    }
+}
+
+impl Cx {
+    pub fn start_event_recording(&mut self, path: Option<String>) {
+        self.platform.desktop.event_recorder = Some(EventRecorder::new(path));
+    }
+
+    // returns what wasn't flushed to a file yet
+    pub fn stop_event_recording(&mut self) -> Option<EventLog> {
+        if let Some(mut recorder) = self.platform.desktop.event_recorder.take() {
+            recorder.flush();
+            return Some(recorder.log)
+        }
+        None
+    }
+
+    pub fn record_input_event(&mut self, event: &Event) {
+        if let Some(recorder) = &mut self.platform.desktop.event_recorder {
+            let time = recorder.time();
+            recorder.log.records.push(EventRecord::Input {time, event: RecordedEvent::from_event(event)});
+        }
+    }
+
+    pub fn record_delivered_event(&mut self, event: &Event) {
+        if let Some(recorder) = &mut self.platform.desktop.event_recorder {
+            let time = recorder.time();
+            recorder.log.records.push(EventRecord::Delivered {time, event: RecordedEvent::from_event(event)});
+        }
+    }
+
+    pub fn record_file_read(&mut self, path: &str, event: &FileReadEvent) {
+        if let Some(recorder) = &mut self.platform.desktop.event_recorder {
+            let time = recorder.time();
+            recorder.log.records.push(EventRecord::FileRead {time, path: path.to_string(), event: event.clone()});
+        }
+    }
+
+    pub fn record_paint(&mut self, paint_time: f64) {
+        if self.platform.desktop.event_recorder.is_none() {
+            return
+        }
+        let windows = self.windows.iter().map( | window | RecordedWindow {
+            state: match window.window_state {
+                CxWindowState::Create {..} => RecordedWindowState::Create,
+                CxWindowState::Created => RecordedWindowState::Created,
+                CxWindowState::Close => RecordedWindowState::Close,
+                CxWindowState::Closed => RecordedWindowState::Closed,
+            },
+            geom: window.window_geom.clone()
+        }).collect();
+        let recorder = self.platform.desktop.event_recorder.as_mut().unwrap();
+        let time = recorder.time();
+        recorder.log.records.push(EventRecord::Paint {time, paint_time, windows});
+        recorder.flush();
+    }
+
+    // feeds a log back into an app without a window or a gpu, file reads are answered
+    // from the log. compares what the app gets against what it got when recording
+    pub fn replay_event_log<F>(&mut self, log: &EventLog, mut event_handler: F) -> EventReplay
+    where F: FnMut(&mut Cx, &mut Event),
+    {
+        self.event_handler = Some(&mut event_handler as *const dyn FnMut(&mut Cx, &mut Event) as *mut dyn FnMut(&mut Cx, &mut Event));
+
+        let mut file_reads = HashMap::new();
+        for record in &log.records {
+            if let EventRecord::FileRead {event, ..} = record {
+                file_reads.insert(event.read_id, event.clone());
+            }
+        }
+        let old_recorder = self.platform.desktop.event_recorder.replace(EventRecorder::new(None));
+        self.platform.desktop.replay_file_reads = Some(file_reads);
+
+        self.replay_map_all_shaders();
+
+        self.load_all_fonts();
+
+        self.call_event_handler(&mut Event::Construct);
+
+        self.redraw_child_area(Area::All);
+
+        for record in &log.records {
+            match record {
+                EventRecord::Input {event, ..} => if let Some(event) = event.to_event() {
+                    self.replay_event(event);
+                },
+                EventRecord::Paint {paint_time, windows, ..} => {
+                    self.replay_windows(windows);
+                    self.process_desktop_paint_callbacks(*paint_time);
+                },
+                EventRecord::FileRead {..} | EventRecord::Delivered {..} => ()
+            }
+        }
+
+        self.platform.desktop.replay_file_reads = None;
+        let replay_log = std::mem::replace(&mut self.platform.desktop.event_recorder, old_recorder).unwrap().log;
+        self.event_handler = None;
+
+        let expected = log.delivered_events();
+        let delivered = replay_log.delivered_events();
+        let mut mismatch = None;
+        for index in 0..expected.len().max(delivered.len()) {
+            if expected.get(index) != delivered.get(index) {
+                mismatch = Some((index, expected.get(index).cloned(), delivered.get(index).cloned()));
+                break;
+            }
+        }
+        EventReplay {
+            delivered,
+            mismatch
+        }
+    }
+
+    // nothing gets painted, but drawing needs the instance layouts of the shaders
+    fn replay_map_all_shaders(&mut self) {
+        let options = ShaderCompileOptions {
+            gather_all: false,
+            create_const_table: false,
+            no_const_collapse: false
+        };
+        for (live_id, shader) in &self.live_styles.shader_alloc {
+            match self.live_styles.collect_and_analyse_shader(*live_id, options) {
+                Err(err) => {
+                    eprintln!("{}", err);
+                    panic!()
+                },
+                Ok((shader_ast, default_geometry)) => {
+                    let sh = &mut self.shaders[shader.shader_id];
+                    let mut mapping = CxShaderMapping::from_shader_ast(shader_ast, options, false);
+                    mapping.update_live_uniforms(&self.live_styles);
+                    sh.default_geometry = default_geometry;
+                    sh.mapping = mapping;
+                }
+            }
+        };
+        self.live_styles.changed_shaders.clear();
+    }
+
+    fn replay_windows(&mut self, windows: &Vec<RecordedWindow>) {
+        for (window_id, recorded) in windows.iter().enumerate() {
+            if window_id >= self.windows.len() {
+                break;
+            }
+            let window = &mut self.windows[window_id];
+            window.window_geom = recorded.geom.clone();
+            window.window_state = match (&window.window_state, &recorded.state) {
+                (CxWindowState::Create {..}, RecordedWindowState::Created) => CxWindowState::Created,
+                (_, RecordedWindowState::Closed) => CxWindowState::Closed,
+                (state, _) => state.clone()
+            };
+            window.window_command = CxWindowCmd::None;
+        }
+    }
+
+    fn replay_event(&mut self, mut event: Event) {
+        self.process_desktop_pre_event(&mut event);
+        match &event {
+            Event::WindowSetHoverCursor(mc) => {
+                self.set_hover_mouse_cursor(mc.clone());
+            },
+            Event::WindowGeomChange(re) => {
+                self.windows[re.window_id].window_geom = re.new_geom.clone();
+                if re.old_geom.inner_size != re.new_geom.inner_size {
+                    if let Some(main_pass_id) = self.windows[re.window_id].main_pass_id {
+                        self.redraw_pass_and_sub_passes(main_pass_id);
+                    }
+                }
+                self.call_event_handler(&mut event);
+            },
+            Event::WindowClosed(wc) => {
+                self.windows[wc.window_id].window_state = CxWindowState::Closed;
+                self.windows_free.push(wc.window_id);
+                self.call_event_handler(&mut event);
+            },
+            Event::Signal {..} => {
+                self.call_event_handler(&mut event);
+                self.call_signals_and_triggers();
+            },
+            Event::None | Event::Paint => {
+            },
+            _ => {
+                self.call_event_handler(&mut event);
+            }
+        }
+        self.process_desktop_post_event(&mut event);
+    }
+}
@@ -0,0 +1,657 @@
+use crate::cx::*;
+use makepad_live_compiler::analyse::ShaderCompileOptions;
+use makepad_live_compiler::shaderast::ShaderAst;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+
+impl Cx {
+
+    pub fn render_view(
+        &mut self,
+        pass_id: usize,
+        view_id: usize,
+        scroll: Vec2,
+        clip: (Vec2, Vec2),
+        zbias: &mut f32,
+        zbias_step: f32,
+        target: &mut SoftwareBuffer,
+    ) {
+        let draw_calls_len = self.views[view_id].draw_calls_len;
+        self.views[view_id].uniform_view_transform(&Mat4::identity());
+        self.views[view_id].parent_scroll = scroll;
+        let local_scroll = self.views[view_id].get_local_scroll();
+        let clip = self.views[view_id].intersect_clip(clip);
+        for draw_call_id in 0..draw_calls_len {
+            let sub_view_id = self.views[view_id].draw_calls[draw_call_id].sub_view_id;
+            if sub_view_id != 0 {
+                self.render_view(
+                    pass_id,
+                    sub_view_id,
+                    Vec2 {x: local_scroll.x + scroll.x, y: local_scroll.y + scroll.y},
+                    clip,
+                    zbias,
+                    zbias_step,
+                    target
+                );
+                continue;
+            }
+
+            // upload changed images first, the draw below only borrows textures
+            for texture_id in &self.views[view_id].draw_calls[draw_call_id].textures_2d {
+                let cxtexture = &mut self.textures[*texture_id as usize];
+                if cxtexture.update_image {
+                    cxtexture.update_image = false;
+                    Self::software_update_texture_image2d(cxtexture);
+                }
+            }
+
+            let cxview = &mut self.views[view_id];
+            let draw_call = &mut cxview.draw_calls[draw_call_id];
+            let sh = &self.shaders[draw_call.shader.shader_id];
+            let shp = if let Some(shp) = sh.platform.as_ref() {shp} else {continue};
+
+            draw_call.instance_dirty = false;
+            draw_call.uniforms_dirty = false;
+
+            let geometry_id = if let Some(geometry) = draw_call.geometry {
+                geometry.geometry_id
+            }
+            else if let Some(geometry) = sh.default_geometry {
+                geometry.geometry_id
+            }
+            else {
+                continue
+            };
+
+            draw_call.set_zbias(*zbias);
+            draw_call.set_local_scroll(scroll, local_scroll);
+            draw_call.set_clip(clip);
+            *zbias += zbias_step;
+
+            let instance_slots = sh.mapping.instance_props.total_slots;
+            if instance_slots == 0 {
+                continue
+            }
+            let geometry = &mut self.geometries[geometry_id];
+            geometry.dirty = false;
+            let geometry = &self.geometries[geometry_id];
+
+            let cxtextures = &self.textures;
+            let textures = SoftwareTextures {
+                buffers: draw_call.textures_2d.iter().map( | texture_id | {
+                    &cxtextures[*texture_id as usize].platform.buffer
+                }).collect()
+            };
+
+            let mut run = SwRun::new(
+                &shp.program,
+                &sh.mapping,
+                SwUniforms {
+                    pass: self.passes[pass_id].pass_uniforms.as_slice(),
+                    view: cxview.view_uniforms.as_slice(),
+                    draw: draw_call.draw_uniforms.as_slice(),
+                    user: &draw_call.user_uniforms,
+                    live: &sh.mapping.live_uniforms_buf,
+                },
+                &textures,
+                &self.live_styles
+            );
+
+            let geometry_slots = sh.mapping.geometry_props.total_slots.max(1);
+            let vertex_count = geometry.vertices.len() / geometry_slots;
+            let mut transformed = Vec::with_capacity(vertex_count);
+            for instance in draw_call.instances.chunks(instance_slots) {
+                transformed.truncate(0);
+                for vertex in 0..vertex_count {
+                    let mut varyings = Vec::new();
+                    let pos = run.run_vertex(
+                        &geometry.vertices[vertex * geometry_slots..(vertex + 1) * geometry_slots],
+                        instance,
+                        &mut varyings
+                    );
+                    transformed.push(SoftwareVertex {pos, varyings});
+                }
+                for tri in geometry.indices.chunks(3) {
+                    if tri.len() != 3 || tri.iter().any( | i | *i as usize >= transformed.len()) {
+                        continue;
+                    }
+                    Self::software_draw_triangle(
+                        &mut run,
+                        [&transformed[tri[0] as usize], &transformed[tri[1] as usize], &transformed[tri[2] as usize]],
+                        target
+                    );
+                }
+            }
+        }
+    }
+
+    fn software_draw_triangle(run: &mut SwRun, v: [&SoftwareVertex; 3], target: &mut SoftwareBuffer) {
+        if v.iter().any( | v | v.pos.w <= 0.0) { // no near plane clipping
+            return
+        }
+        let width = target.width as f32;
+        let height = target.height as f32;
+        // to window coordinates, row 0 is the top of the pass
+        let mut p = [(0.0f32, 0.0f32, 0.0f32, 0.0f32); 3];
+        for i in 0..3 {
+            let inv_w = 1.0 / v[i].pos.w;
+            p[i] = (
+                (v[i].pos.x * inv_w * 0.5 + 0.5) * width,
+                (0.5 - v[i].pos.y * inv_w * 0.5) * height,
+                v[i].pos.z * inv_w * 0.5 + 0.5,
+                inv_w
+            );
+        }
+        let edge = | a: (f32, f32, f32, f32), b: (f32, f32, f32, f32), x: f32, y: f32 | {
+            (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0)
+        };
+        let mut order = [0, 1, 2];
+        let mut area = edge(p[0], p[1], p[2].0, p[2].1);
+        if area == 0.0 {
+            return
+        }
+        if area < 0.0 {
+            order = [0, 2, 1];
+            area = -area;
+        }
+        let p = [p[order[0]], p[order[1]], p[order[2]]];
+        let v = [v[order[0]], v[order[1]], v[order[2]]];
+
+        // shared edges belong to exactly one of the two triangles
+        let owns_edge = | a: (f32, f32, f32, f32), b: (f32, f32, f32, f32) | {
+            let dy = b.1 - a.1;
+            dy > 0.0 || (dy == 0.0 && b.0 - a.0 < 0.0)
+        };
+        let edges = [(p[1], p[2]), (p[2], p[0]), (p[0], p[1])];
+        let owns = [owns_edge(p[1], p[2]), owns_edge(p[2], p[0]), owns_edge(p[0], p[1])];
+        // barycentric derivatives are constant over the triangle
+        let mut db = [(0.0f32, 0.0f32); 3];
+        for i in 0..3 {
+            let (a, b) = edges[i];
+            db[i] = (-(b.1 - a.1) / area, (b.0 - a.0) / area);
+        }
+
+        let slots = v[0].varyings.len();
+        let mut weighted = [Vec::new(), Vec::new(), Vec::new()];
+        for i in 0..3 {
+            weighted[i] = v[i].varyings.iter().map( | a | a * p[i].3).collect::<Vec<f32>>();
+        }
+        let mut duals = vec![Dual::default(); slots];
+
+        let min_x = p.iter().fold(width, | m, p | m.min(p.0)).floor().max(0.0) as usize;
+        let max_x = p.iter().fold(0.0f32, | m, p | m.max(p.0)).ceil().min(width) as usize;
+        let min_y = p.iter().fold(height, | m, p | m.min(p.1)).floor().max(0.0) as usize;
+        let max_y = p.iter().fold(0.0f32, | m, p | m.max(p.1)).ceil().min(height) as usize;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let px = x as f32 + 0.5;
+                let py = y as f32 + 0.5;
+                let mut b = [0.0f32; 3];
+                let mut inside = true;
+                for i in 0..3 {
+                    let w = edge(edges[i].0, edges[i].1, px, py);
+                    if w < 0.0 || (w == 0.0 && !owns[i]) {
+                        inside = false;
+                        break;
+                    }
+                    b[i] = w / area;
+                }
+                if !inside {
+                    continue;
+                }
+                let z = b[0] * p[0].2 + b[1] * p[1].2 + b[2] * p[2].2;
+                let index = y * target.width + x;
+                if z < 0.0 || z > 1.0 || z > target.depth[index] {
+                    continue;
+                }
+                // perspective correct varyings with their screen space derivatives
+                let w = b[0] * p[0].3 + b[1] * p[1].3 + b[2] * p[2].3;
+                let wx = db[0].0 * p[0].3 + db[1].0 * p[1].3 + db[2].0 * p[2].3;
+                let wy = db[0].1 * p[0].3 + db[1].1 * p[1].3 + db[2].1 * p[2].3;
+                let inv_w2 = 1.0 / (w * w);
+                for s in 0..slots {
+                    let a = b[0] * weighted[0][s] + b[1] * weighted[1][s] + b[2] * weighted[2][s];
+                    let ax = db[0].0 * weighted[0][s] + db[1].0 * weighted[1][s] + db[2].0 * weighted[2][s];
+                    let ay = db[0].1 * weighted[0][s] + db[1].1 * weighted[1][s] + db[2].1 * weighted[2][s];
+                    duals[s] = Dual {
+                        v: a / w,
+                        dx: (ax * w - a * wx) * inv_w2,
+                        dy: (ay * w - a * wy) * inv_w2
+                    };
+                }
+                let src = run.run_pixel(&duals);
+                // premultiplied blending, ONE, ONE_MINUS_SRC_ALPHA
+                let dst = target.color[index];
+                let ia = 1.0 - src.w;
+                target.color[index] = Vec4 {
+                    x: (src.x + dst.x * ia).max(0.0).min(1.0),
+                    y: (src.y + dst.y * ia).max(0.0).min(1.0),
+                    z: (src.z + dst.z * ia).max(0.0).min(1.0),
+                    w: (src.w + dst.w * ia).max(0.0).min(1.0),
+                };
+                target.depth[index] = z;
+            }
+        }
+    }
+
+    pub fn draw_pass_to_framebuffer(
+        &mut self,
+        pass_id: usize,
+        dpi_factor: f32,
+    ) {
+        let view_id = self.passes[pass_id].main_view_id.unwrap();
+        let pass_size = self.passes[pass_id].pass_size;
+        self.passes[pass_id].set_matrix(Vec2::default(), pass_size);
+        self.passes[pass_id].set_dpi_factor(dpi_factor);
+
+        let clear_color = if self.passes[pass_id].color_textures.len() == 0 {
+            self.passes[pass_id].clear_color
+        }
+        else {
+            match self.passes[pass_id].color_textures[0].clear_color {
+                ClearColor::InitWith(color) => color,
+                ClearColor::ClearWith(color) => color
+            }
+        };
+        let clear_depth = match self.passes[pass_id].clear_depth {
+            ClearDepth::InitWith(depth) => depth,
+            ClearDepth::ClearWith(depth) => depth
+        };
+
+        let mut target = std::mem::replace(&mut self.passes[pass_id].platform.framebuffer, SoftwareBuffer::default());
+        target.resize((pass_size.x * dpi_factor) as usize, (pass_size.y * dpi_factor) as usize);
+        target.clear_color(clear_color);
+        target.clear_depth(clear_depth as f32);
+
+        let mut zbias = 0.0;
+        let zbias_step = self.passes[pass_id].zbias_step;
+        self.render_view(
+            pass_id,
+            view_id,
+            Vec2::default(),
+            (Vec2 {x: -50000., y: -50000.}, Vec2 {x: 50000., y: 50000.}),
+            &mut zbias,
+            zbias_step,
+            &mut target
+        );
+        self.passes[pass_id].platform.framebuffer = target;
+    }
+
+    pub fn draw_pass_to_texture(
+        &mut self,
+        pass_id: usize,
+        inherit_dpi_factor: f32,
+    ) {
+        let pass_size = self.passes[pass_id].pass_size;
+        self.passes[pass_id].set_matrix(Vec2::default(), pass_size);
+        self.passes[pass_id].paint_dirty = false;
+
+        let dpi_factor = if let Some(override_dpi_factor) = self.passes[pass_id].override_dpi_factor {
+            override_dpi_factor
+        }
+        else {
+            inherit_dpi_factor
+        };
+        self.passes[pass_id].set_dpi_factor(dpi_factor);
+
+        // the first color texture decides the size of the target
+        let (width, height) = if let Some(color_texture) = self.passes[pass_id].color_textures.get(0) {
+            let desc = &self.textures[color_texture.texture_id as usize].desc;
+            (
+                desc.width.unwrap_or((pass_size.x * dpi_factor) as usize),
+                desc.height.unwrap_or((pass_size.y * dpi_factor) as usize)
+            )
+        }
+        else {
+            ((pass_size.x * dpi_factor) as usize, (pass_size.y * dpi_factor) as usize)
+        };
+
+        let mut target = std::mem::replace(&mut self.passes[pass_id].platform.framebuffer, SoftwareBuffer::default());
+        let realloc = target.resize(width, height);
+
+        if self.passes[pass_id].depth_texture.is_some() {
+            match self.passes[pass_id].clear_depth {
+                ClearDepth::InitWith(depth) => if realloc {
+                    target.clear_depth(depth as f32);
+                },
+                ClearDepth::ClearWith(depth) => target.clear_depth(depth as f32)
+            }
+        }
+        else {
+            target.clear_depth(1.0);
+        }
+        if let Some(color_texture) = self.passes[pass_id].color_textures.get(0) {
+            match color_texture.clear_color {
+                ClearColor::InitWith(color) => if realloc {
+                    target.clear_color(color);
+                },
+                ClearColor::ClearWith(color) => target.clear_color(color)
+            }
+        }
+
+        let mut zbias = 0.0;
+        let zbias_step = self.passes[pass_id].zbias_step;
+        let view_id = self.passes[pass_id].main_view_id.unwrap();
+
+        self.render_view(
+            pass_id,
+            view_id,
+            Vec2::default(),
+            (Vec2 {x: -50000., y: -50000.}, Vec2 {x: 50000., y: 50000.}),
+            &mut zbias,
+            zbias_step,
+            &mut target
+        );
+
+        // hand the result to the textures so later passes can sample it
+        for color_texture in &self.passes[pass_id].color_textures {
+            let buffer = &mut self.textures[color_texture.texture_id as usize].platform.buffer;
+            buffer.width = target.width;
+            buffer.height = target.height;
+            buffer.color.clone_from(&target.color);
+        }
+        if let Some(depth_texture_id) = self.passes[pass_id].depth_texture {
+            let buffer = &mut self.textures[depth_texture_id as usize].platform.buffer;
+            buffer.width = target.width;
+            buffer.height = target.height;
+            buffer.depth.clone_from(&target.depth);
+        }
+        self.passes[pass_id].platform.framebuffer = target;
+    }
+
+    pub fn software_update_texture_image2d(cxtexture: &mut CxTexture) {
+        let width = cxtexture.desc.width.unwrap_or(0);
+        let height = cxtexture.desc.height.unwrap_or(0);
+        let buffer = &mut cxtexture.platform.buffer;
+        buffer.resize(width, height);
+        for (index, pixel) in cxtexture.image_u32.iter().take(width * height).enumerate() {
+            buffer.color[index] = Vec4 {
+                x: ((pixel >> 16) & 0xff) as f32 / 255.0,
+                y: ((pixel >> 8) & 0xff) as f32 / 255.0,
+                z: (pixel & 0xff) as f32 / 255.0,
+                w: ((pixel >> 24) & 0xff) as f32 / 255.0,
+            };
+        }
+    }
+
+    // the rgba result of the last paint of a pass
+    pub fn pass_framebuffer(&self, pass_id: usize) -> &SoftwareBuffer {
+        &self.passes[pass_id].platform.framebuffer
+    }
+
+    pub fn window_framebuffer(&self, window_id: usize) -> Option<&SoftwareBuffer> {
+        let pass_id = self.windows.get(window_id)?.main_pass_id?;
+        Some(&self.passes[pass_id].platform.framebuffer)
+    }
+
+    pub fn software_compile_all_shaders(&mut self) {
+        let options = ShaderCompileOptions {
+            gather_all: false,
+            create_const_table: false,
+            no_const_collapse: false
+        };
+
+        for (live_id, shader) in &self.live_styles.shader_alloc {
+            match self.live_styles.collect_and_analyse_shader(*live_id, options) {
+                Err(err) => {
+                    eprintln!("{}", err);
+                    panic!()
+                },
+                Ok((shader_ast, default_geometry)) => {
+                    let shader_id = shader.shader_id;
+                    Self::software_compile_shader(
+                        &mut self.shaders[shader_id],
+                        shader_ast,
+                        default_geometry,
+                        options,
+                        &self.live_styles
+                    );
+                }
+            }
+        };
+        self.live_styles.changed_shaders.clear();
+    }
+
+    pub fn software_update_all_shaders(&mut self, errors: &mut Vec<LiveBodyError>) {
+        let options = ShaderCompileOptions {
+            gather_all: true,
+            create_const_table: false,
+            no_const_collapse: false
+        };
+
+        for (live_id, change) in &self.live_styles.changed_shaders {
+            match change {
+                LiveChangeType::Recompile => {
+                    match self.live_styles.collect_and_analyse_shader(*live_id, options) {
+                        Err(err) => {
+                            errors.push(err);
+                        },
+                        Ok((shader_ast, default_geometry)) => {
+                            let shader_id = self.live_styles.shader_alloc.get(&live_id).unwrap().shader_id;
+                            Self::software_compile_shader(
+                                &mut self.shaders[shader_id],
+                                shader_ast,
+                                default_geometry,
+                                options,
+                                &self.live_styles
+                            );
+                        }
+                    }
+                }
+                LiveChangeType::UpdateValue => {
+                    let shader_id = self.live_styles.shader_alloc.get(&live_id).unwrap().shader_id;
+                    self.shaders[shader_id].mapping.update_live_uniforms(&self.live_styles);
+                }
+            }
+        }
+        self.live_styles.changed_shaders.clear();
+    }
+
+    pub fn software_compile_shader(
+        sh: &mut CxShader,
+        shader_ast: ShaderAst,
+        default_geometry: Option<Geometry>,
+        options: ShaderCompileOptions,
+        live_styles: &LiveStyles
+    ) -> ShaderCompileResult {
+        // the interpreter walks the ast, so keep a copy next to the mapping
+        let program_ast = shader_ast.clone();
+        let mut mapping = CxShaderMapping::from_shader_ast(shader_ast, options, false);
+        mapping.update_live_uniforms(live_styles);
+        sh.default_geometry = default_geometry;
+        sh.platform = Some(CxPlatformShader {
+            program: SwShader::new(program_ast, &mapping)
+        });
+        sh.mapping = mapping;
+        ShaderCompileResult::Ok
+    }
+}
+
+struct SoftwareVertex {
+    pos: Vec4,
+    varyings: Vec<f32>,
+}
+
+struct SoftwareTextures<'a> {
+    buffers: Vec<&'a SoftwareBuffer>
+}
+
+impl<'a> SwTextureSource for SoftwareTextures<'a> {
+    fn sample(&self, slot: usize, x: f32, y: f32) -> Vec4 {
+        if let Some(buffer) = self.buffers.get(slot) {
+            return buffer.sample(x, y)
+        }
+        Vec4::default()
+    }
+}
+
+// an rgba color target with a depth buffer. colors are premultiplied floats,
+// clamped like an 8 bit gpu target would
+#[derive(Clone, Default)]
+pub struct SoftwareBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub color: Vec<Vec4>,
+    pub depth: Vec<f32>,
+}
+
+impl SoftwareBuffer {
+    // returns true if the buffer was reallocated
+    pub fn resize(&mut self, width: usize, height: usize) -> bool {
+        if self.width == width && self.height == height && self.color.len() == width * height && self.depth.len() == width * height {
+            return false
+        }
+        self.width = width;
+        self.height = height;
+        self.color = vec![Vec4::default(); width * height];
+        self.depth = vec![1.0; width * height];
+        true
+    }
+
+    pub fn clear_color(&mut self, color: Vec4) {
+        for pixel in &mut self.color {
+            *pixel = color;
+        }
+    }
+
+    pub fn clear_depth(&mut self, depth: f32) {
+        for d in &mut self.depth {
+            *d = depth;
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Vec4 {
+        if x >= self.width || y >= self.height || self.color.len() < self.width * self.height {
+            return Vec4::default()
+        }
+        self.color[y * self.width + x]
+    }
+
+    // bilinear, clamped to the edges, y = 0 is the first row
+    pub fn sample(&self, x: f32, y: f32) -> Vec4 {
+        if self.width == 0 || self.height == 0 || self.color.len() < self.width * self.height {
+            return Vec4::default()
+        }
+        let fx = x * self.width as f32 - 0.5;
+        let fy = y * self.height as f32 - 0.5;
+        let x0 = fx.floor();
+        let y0 = fy.floor();
+        let tx = fx - x0;
+        let ty = fy - y0;
+        let clamp_x = | x: f32 | (x.max(0.0) as usize).min(self.width - 1);
+        let clamp_y = | y: f32 | (y.max(0.0) as usize).min(self.height - 1);
+        let a = self.color[clamp_y(y0) * self.width + clamp_x(x0)];
+        let b = self.color[clamp_y(y0) * self.width + clamp_x(x0 + 1.0)];
+        let c = self.color[clamp_y(y0 + 1.0) * self.width + clamp_x(x0)];
+        let d = self.color[clamp_y(y0 + 1.0) * self.width + clamp_x(x0 + 1.0)];
+        let lerp = | a: f32, b: f32, t: f32 | a + (b - a) * t;
+        Vec4 {
+            x: lerp(lerp(a.x, b.x, tx), lerp(c.x, d.x, tx), ty),
+            y: lerp(lerp(a.y, b.y, tx), lerp(c.y, d.y, tx), ty),
+            z: lerp(lerp(a.z, b.z, tx), lerp(c.z, d.z, tx), ty),
+            w: lerp(lerp(a.w, b.w, tx), lerp(c.w, d.w, tx), ty),
+        }
+    }
+
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.color.len() * 4);
+        for pixel in &self.color {
+            for c in &[pixel.x, pixel.y, pixel.z, pixel.w] {
+                out.push((c.max(0.0).min(1.0) * 255.0 + 0.5) as u8);
+            }
+        }
+        out
+    }
+
+    // an uncompressed png, enough for screenshots of tests
+    pub fn to_png(&self) -> Vec<u8> {
+        fn chunk(out: &mut Vec<u8>, ty: &[u8], data: &[u8]) {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = out.len();
+            out.extend_from_slice(ty);
+            out.extend_from_slice(data);
+            let crc = crc32(&out[start..]);
+            out.extend_from_slice(&crc.to_be_bytes());
+        }
+        fn crc32(data: &[u8]) -> u32 {
+            let mut crc = 0xffff_ffffu32;
+            for byte in data {
+                crc ^= *byte as u32;
+                for _ in 0..8 {
+                    crc = if crc & 1 != 0 {(crc >> 1) ^ 0xedb8_8320} else {crc >> 1};
+                }
+            }
+            !crc
+        }
+
+        let rgba = self.to_rgba8();
+        let mut raw = Vec::with_capacity((self.width * 4 + 1) * self.height);
+        for row in 0..self.height {
+            raw.push(0); // filter type none
+            raw.extend_from_slice(&rgba[row * self.width * 4..(row + 1) * self.width * 4]);
+        }
+        // zlib stream with stored blocks
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(65535).peekable();
+        if blocks.peek().is_none() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            zlib.push(if blocks.peek().is_none() {1} else {0});
+            zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        let (mut a, mut b) = (1u32, 0u32);
+        for byte in &raw {
+            a = (a + *byte as u32) % 65521;
+            b = (b + a) % 65521;
+        }
+        zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bit rgba
+
+        let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        chunk(&mut out, b"IHDR", &ihdr);
+        chunk(&mut out, b"IDAT", &zlib);
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    pub fn write_png(&self, path: &str) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(&self.to_png())
+    }
+}
+
+#[derive(Clone)]
+pub struct CxPlatformShader {
+    pub program: SwShader,
+}
+
+#[derive(Clone, Default)]
+pub struct CxPlatformGeometry {
+}
+
+#[derive(Clone, Default)]
+pub struct CxPlatformView {
+}
+
+#[derive(Default, Clone)]
+pub struct CxPlatformDrawCall {
+}
+
+#[derive(Default, Clone)]
+pub struct CxPlatformTexture {
+    pub buffer: SoftwareBuffer,
+}
+
+#[derive(Default, Clone)]
+pub struct CxPlatformPass {
+    pub framebuffer: SoftwareBuffer,
+}
@@ -0,0 +1,1164 @@
+// a tree walking interpreter for the shader ast, used by the software rasterizer.
+// floats carry their screen space derivatives along so dFdx/dFdy work per pixel
+// without having to run pixels in quads like a gpu does
+use crate::cx::*;
+use makepad_live_compiler::shaderast::*;
+use makepad_live_compiler::ident::{Ident, IdentPath};
+use makepad_live_compiler::env::VarKind;
+use makepad_live_compiler::val::Val;
+use makepad_live_compiler::swizzle::Swizzle;
+use makepad_live_compiler::ty::{Ty, TyLit};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Dual {
+    pub v: f32,
+    pub dx: f32,
+    pub dy: f32
+}
+
+impl Dual {
+    pub fn new(v: f32) -> Dual {
+        Dual {v: v, dx: 0.0, dy: 0.0}
+    }
+
+    fn add(self, o: Dual) -> Dual {Dual {v: self.v + o.v, dx: self.dx + o.dx, dy: self.dy + o.dy}}
+    fn sub(self, o: Dual) -> Dual {Dual {v: self.v - o.v, dx: self.dx - o.dx, dy: self.dy - o.dy}}
+    fn mul(self, o: Dual) -> Dual {
+        Dual {v: self.v * o.v, dx: self.dx * o.v + self.v * o.dx, dy: self.dy * o.v + self.v * o.dy}
+    }
+    fn div(self, o: Dual) -> Dual {
+        let inv = 1.0 / (o.v * o.v);
+        Dual {v: self.v / o.v, dx: (self.dx * o.v - self.v * o.dx) * inv, dy: (self.dy * o.v - self.v * o.dy) * inv}
+    }
+    fn neg(self) -> Dual {Dual {v: -self.v, dx: -self.dx, dy: -self.dy}}
+    // applies f with its derivative df
+    fn chain(self, v: f32, d: f32) -> Dual {Dual {v: v, dx: self.dx * d, dy: self.dy * d}}
+    fn scale(self, s: f32) -> Dual {Dual {v: self.v * s, dx: self.dx * s, dy: self.dy * s}}
+    fn flat(v: f32) -> Dual {Dual::new(v)}
+    fn select(cond: bool, a: Dual, b: Dual) -> Dual {if cond {a} else {b}}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwKind {
+    Bool,
+    Int,
+    Float
+}
+
+#[derive(Clone, Debug)]
+pub enum SwVal {
+    Void,
+    Vec {kind: SwKind, len: usize, c: [Dual; 4]},
+    // column major, like the uniform buffers
+    Mat {n: usize, c: [Dual; 16]},
+    Struct(Vec<SwVal>),
+    Texture(usize),
+}
+
+impl SwVal {
+    pub fn float(v: f32) -> SwVal {
+        SwVal::Vec {kind: SwKind::Float, len: 1, c: [Dual::new(v), Dual::default(), Dual::default(), Dual::default()]}
+    }
+
+    pub fn int(v: i32) -> SwVal {
+        SwVal::Vec {kind: SwKind::Int, len: 1, c: [Dual::new(v as f32), Dual::default(), Dual::default(), Dual::default()]}
+    }
+
+    pub fn bool(v: bool) -> SwVal {
+        SwVal::Vec {kind: SwKind::Bool, len: 1, c: [Dual::new(if v {1.0} else {0.0}), Dual::default(), Dual::default(), Dual::default()]}
+    }
+
+    pub fn vec(kind: SwKind, comps: &[Dual]) -> SwVal {
+        let mut c = [Dual::default(); 4];
+        for (i, d) in comps.iter().take(4).enumerate() {
+            c[i] = *d;
+        }
+        SwVal::Vec {kind: kind, len: comps.len().min(4).max(1), c: c}
+    }
+
+    pub fn from_val(val: &Val) -> SwVal {
+        match val {
+            Val::Bool(v) => SwVal::bool(*v),
+            Val::Int(v) => SwVal::int(*v),
+            Val::Float(v) => SwVal::float(*v),
+            Val::Vec4(v) => SwVal::vec(SwKind::Float, &[Dual::new(v.x), Dual::new(v.y), Dual::new(v.z), Dual::new(v.w)])
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            SwVal::Vec {len, ..} => *len,
+            SwVal::Mat {n, ..} => n * n,
+            _ => 0
+        }
+    }
+
+    pub fn kind(&self) -> SwKind {
+        match self {
+            SwVal::Vec {kind, ..} => *kind,
+            _ => SwKind::Float
+        }
+    }
+
+    // scalars broadcast over every component
+    pub fn comp(&self, i: usize) -> Dual {
+        match self {
+            SwVal::Vec {len, c, ..} => if *len == 1 {c[0]} else if i < *len {c[i]} else {Dual::default()},
+            SwVal::Mat {n, c} => if i < n * n {c[i]} else {Dual::default()},
+            _ => Dual::default()
+        }
+    }
+
+    pub fn comps(&self) -> Vec<Dual> {
+        (0..self.len()).map( | i | self.comp(i)).collect()
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.comp(0).v
+    }
+
+    pub fn to_bool(&self) -> bool {
+        self.comp(0).v != 0.0
+    }
+
+    pub fn to_int(&self) -> i32 {
+        self.comp(0).v as i32
+    }
+
+    pub fn to_vec4(&self) -> Vec4 {
+        Vec4 {x: self.comp(0).v, y: self.comp(1).v, z: self.comp(2).v, w: self.comp(3).v}
+    }
+
+    pub fn zero(ty: &Ty, shader: &SwShader) -> SwVal {
+        match ty {
+            Ty::Struct {ident} => {
+                if let Some(fields) = shader.structs.get(ident) {
+                    SwVal::Struct(fields.iter().map( | (_, ty) | SwVal::zero(ty, shader)).collect())
+                }
+                else {
+                    SwVal::Void
+                }
+            },
+            Ty::Texture2D => SwVal::Texture(0),
+            Ty::Void | Ty::Array {..} => SwVal::Void,
+            _ => SwVal::from_slots(ty, &[])
+        }
+    }
+
+    pub fn from_slots(ty: &Ty, slots: &[f32]) -> SwVal {
+        let slot = | i: usize | Dual::new(if i < slots.len() {slots[i]} else {0.0});
+        match ty {
+            Ty::Mat2 | Ty::Mat3 | Ty::Mat4 => {
+                let n = match ty {Ty::Mat2 => 2, Ty::Mat3 => 3, _ => 4};
+                let mut c = [Dual::default(); 16];
+                for i in 0..n * n {
+                    c[i] = slot(i);
+                }
+                SwVal::Mat {n: n, c: c}
+            },
+            _ => {
+                if let Some((kind, len)) = vec_shape(ty) {
+                    let comps: Vec<Dual> = (0..len).map(slot).collect();
+                    SwVal::vec(kind, &comps)
+                }
+                else {
+                    SwVal::Void
+                }
+            }
+        }
+    }
+
+    pub fn from_duals(ty: &Ty, duals: &[Dual]) -> SwVal {
+        match SwVal::from_slots(ty, &[]) {
+            SwVal::Vec {kind, len, ..} => SwVal::vec(kind, &duals[0..len.min(duals.len())]),
+            SwVal::Mat {n, ..} => {
+                let mut c = [Dual::default(); 16];
+                for i in 0..(n * n).min(duals.len()) {
+                    c[i] = duals[i];
+                }
+                SwVal::Mat {n: n, c: c}
+            },
+            other => other
+        }
+    }
+
+    pub fn write_slots(&self, out: &mut Vec<f32>) {
+        for i in 0..self.len() {
+            out.push(self.comp(i).v);
+        }
+    }
+
+    fn map(&self, f: impl Fn(Dual) -> Dual) -> SwVal {
+        match self {
+            SwVal::Vec {kind, len, c} => {
+                let mut o = *c;
+                for i in 0..*len {
+                    o[i] = f(c[i]);
+                }
+                SwVal::Vec {kind: *kind, len: *len, c: o}
+            },
+            SwVal::Mat {n, c} => {
+                let mut o = *c;
+                for i in 0..n * n {
+                    o[i] = f(c[i]);
+                }
+                SwVal::Mat {n: *n, c: o}
+            },
+            other => other.clone()
+        }
+    }
+
+    // componentwise over two or three values, scalars broadcast
+    fn zip(&self, b: &SwVal, f: impl Fn(Dual, Dual) -> Dual) -> SwVal {
+        let (shape, len) = if self.len() >= b.len() {(self, self.len())} else {(b, b.len())};
+        let comps: Vec<Dual> = (0..len).map( | i | f(self.comp(i), b.comp(i))).collect();
+        match shape {
+            SwVal::Mat {n, ..} => SwVal::from_duals(&mat_ty(*n), &comps),
+            _ => {
+                let kind = if self.len() >= b.len() {self.kind()} else {b.kind()};
+                SwVal::vec(kind, &comps)
+            }
+        }
+    }
+
+    fn zip3(&self, b: &SwVal, c: &SwVal, f: impl Fn(Dual, Dual, Dual) -> Dual) -> SwVal {
+        let len = self.len().max(b.len()).max(c.len());
+        let comps: Vec<Dual> = (0..len).map( | i | f(self.comp(i), b.comp(i), c.comp(i))).collect();
+        SwVal::vec(SwKind::Float, &comps)
+    }
+
+    fn with_kind(&self, kind: SwKind) -> SwVal {
+        match self {
+            SwVal::Vec {len, c, ..} => SwVal::Vec {kind: kind, len: *len, c: *c},
+            other => other.clone()
+        }
+    }
+}
+
+fn vec_shape(ty: &Ty) -> Option<(SwKind, usize)> {
+    Some(match ty {
+        Ty::Bool => (SwKind::Bool, 1),
+        Ty::Int => (SwKind::Int, 1),
+        Ty::Float => (SwKind::Float, 1),
+        Ty::Bvec2 => (SwKind::Bool, 2),
+        Ty::Bvec3 => (SwKind::Bool, 3),
+        Ty::Bvec4 => (SwKind::Bool, 4),
+        Ty::Ivec2 => (SwKind::Int, 2),
+        Ty::Ivec3 => (SwKind::Int, 3),
+        Ty::Ivec4 => (SwKind::Int, 4),
+        Ty::Vec2 => (SwKind::Float, 2),
+        Ty::Vec3 => (SwKind::Float, 3),
+        Ty::Vec4 => (SwKind::Float, 4),
+        _ => return None
+    })
+}
+
+fn mat_ty(n: usize) -> Ty {
+    match n {2 => Ty::Mat2, 3 => Ty::Mat3, _ => Ty::Mat4}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwUniformBlock {
+    Pass,
+    View,
+    Draw,
+    User,
+}
+
+// the per shader tables the interpreter needs, built once at compile time
+#[derive(Clone)]
+pub struct SwShader {
+    pub shader_ast: ShaderAst,
+    pub fns: HashMap<IdentPath, usize>,
+    pub structs: HashMap<Ident, Vec<(Ident, Ty)>>,
+    pub uniforms: HashMap<Ident, (SwUniformBlock, usize, Ty)>,
+    pub textures: HashMap<Ident, usize>,
+    // geometry, instance and varying decls live in globals, in that order
+    pub globals: Vec<(Ident, Ty, usize)>,
+    pub global_map: HashMap<Ident, usize>,
+    pub global_slots: usize,
+    // (global index, slot offset in the vertex or instance buffer)
+    pub geometry_globals: Vec<(usize, usize)>,
+    pub instance_globals: Vec<(usize, usize)>,
+}
+
+impl SwShader {
+    pub fn new(shader_ast: ShaderAst, mapping: &CxShaderMapping) -> SwShader {
+        let mut fns = HashMap::new();
+        let mut structs = HashMap::new();
+        for (index, decl) in shader_ast.decls.iter().enumerate() {
+            match decl {
+                Decl::Fn(decl) => {
+                    // later decls win, same as find_fn_decl
+                    fns.insert(decl.ident_path, index);
+                },
+                Decl::Struct(decl) => {
+                    structs.insert(decl.ident, decl.fields.iter().map( | f | {
+                        (f.ident, f.ty_expr.ty.borrow().clone().unwrap_or(Ty::Void))
+                    }).collect());
+                },
+                _ => ()
+            }
+        }
+
+        let mut uniforms = HashMap::new();
+        // same packing as the uniform buffer upload of the gpu backends
+        let mut block_offsets = | block: SwUniformBlock, props: &Vec<PropDef> | {
+            let mut o = 0;
+            for prop in props {
+                let size = prop.ty.size();
+                if (o & 3) != 0 && (o & 3) + size > 4 {
+                    o += 4 - (o & 3);
+                }
+                uniforms.insert(Ident::new(&prop.name), (block, o, prop.ty.clone()));
+                o += size;
+            }
+        };
+        block_offsets(SwUniformBlock::Pass, &mapping.pass_uniforms);
+        block_offsets(SwUniformBlock::View, &mapping.view_uniforms);
+        block_offsets(SwUniformBlock::Draw, &mapping.draw_uniforms);
+        for prop in &mapping.user_uniform_props.props {
+            uniforms.insert(Ident::new(&prop.name), (SwUniformBlock::User, prop.offset, prop.ty.clone()));
+        }
+
+        let mut textures = HashMap::new();
+        for (index, prop) in mapping.textures.iter().enumerate() {
+            textures.insert(Ident::new(&prop.name), index);
+        }
+
+        // the buffer layout comes from the mapping, not from the decl order
+        let buffer_offset = | props: &InstanceProps, ident: Ident | {
+            props.props.iter().find( | prop | Ident::new(&prop.name) == ident).map( | prop | prop.offset).unwrap_or(0)
+        };
+        let mut globals = Vec::new();
+        let mut geometry_globals = Vec::new();
+        let mut instance_globals = Vec::new();
+        let mut slot = 0;
+        for pass in 0..3 {
+            for decl in &shader_ast.decls {
+                let (ident, ty) = match (pass, decl) {
+                    (0, Decl::Geometry(decl)) => {
+                        let offset = buffer_offset(&mapping.geometry_props, decl.ident);
+                        geometry_globals.push((globals.len(), offset));
+                        (decl.ident, decl.ty_expr.ty.borrow().clone().unwrap())
+                    },
+                    (1, Decl::Instance(decl)) => {
+                        let offset = buffer_offset(&mapping.instance_props, decl.ident);
+                        instance_globals.push((globals.len(), offset));
+                        (decl.ident, decl.ty_expr.ty.borrow().clone().unwrap())
+                    },
+                    (2, Decl::Varying(decl)) => (decl.ident, decl.ty_expr.ty.borrow().clone().unwrap()),
+                    _ => continue
+                };
+                let size = ty.size();
+                globals.push((ident, ty, slot));
+                slot += size;
+            }
+        }
+        let global_map = globals.iter().enumerate().map( | (i, g) | (g.0, i)).collect();
+
+        SwShader {
+            shader_ast,
+            fns,
+            structs,
+            uniforms,
+            textures,
+            globals,
+            global_map,
+            global_slots: slot,
+            geometry_globals,
+            instance_globals,
+        }
+    }
+}
+
+pub trait SwTextureSource {
+    fn sample(&self, slot: usize, x: f32, y: f32) -> Vec4;
+}
+
+pub struct SwUniforms<'a> {
+    pub pass: &'a [f32],
+    pub view: &'a [f32],
+    pub draw: &'a [f32],
+    pub user: &'a [f32],
+    pub live: &'a [f32],
+}
+
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(SwVal)
+}
+
+pub struct SwRun<'a> {
+    pub shader: &'a SwShader,
+    pub mapping: &'a CxShaderMapping,
+    pub uniforms: SwUniforms<'a>,
+    pub textures: &'a dyn SwTextureSource,
+    pub live_styles: &'a LiveStyles,
+    pub globals: Vec<SwVal>,
+    locals: Vec<(Ident, SwVal)>,
+    frame_base: usize,
+    live_cache: HashMap<(usize, IdentPath), Option<(usize, Ty)>>,
+}
+
+impl<'a> SwRun<'a> {
+    pub fn new(
+        shader: &'a SwShader,
+        mapping: &'a CxShaderMapping,
+        uniforms: SwUniforms<'a>,
+        textures: &'a dyn SwTextureSource,
+        live_styles: &'a LiveStyles
+    ) -> Self {
+        let globals = shader.globals.iter().map( | (_, ty, _) | SwVal::zero(ty, shader)).collect();
+        SwRun {
+            shader,
+            mapping,
+            uniforms,
+            textures,
+            live_styles,
+            globals,
+            locals: Vec::new(),
+            frame_base: 0,
+            live_cache: HashMap::new(),
+        }
+    }
+
+    // runs the vertex function for one vertex of one instance, returns the clip position
+    pub fn run_vertex(&mut self, geometry: &[f32], instance: &[f32], varyings_out: &mut Vec<f32>) -> Vec4 {
+        let shader = self.shader;
+        for (index, global) in shader.globals.iter().enumerate() {
+            self.globals[index] = SwVal::zero(&global.1, shader);
+        }
+        for (index, offset) in &shader.geometry_globals {
+            let ty = &shader.globals[*index].1;
+            self.globals[*index] = SwVal::from_slots(ty, slice_at(geometry, *offset, ty.size()));
+        }
+        for (index, offset) in &shader.instance_globals {
+            let ty = &shader.globals[*index].1;
+            self.globals[*index] = SwVal::from_slots(ty, slice_at(instance, *offset, ty.size()));
+        }
+        let pos = self.call_entry("vertex").to_vec4();
+        varyings_out.truncate(0);
+        for global in &self.globals {
+            global.write_slots(varyings_out);
+        }
+        pos
+    }
+
+    // runs the pixel function with the interpolated globals and their derivatives
+    pub fn run_pixel(&mut self, varyings: &[Dual]) -> Vec4 {
+        let shader = self.shader;
+        for (index, (_, ty, slot)) in shader.globals.iter().enumerate() {
+            self.globals[index] = SwVal::from_duals(ty, slice_at(varyings, *slot, ty.size()));
+        }
+        self.call_entry("pixel").to_vec4()
+    }
+
+    fn call_entry(&mut self, name: &str) -> SwVal {
+        self.locals.truncate(0);
+        self.frame_base = 0;
+        let decl = if let Some(index) = self.shader.fns.get(&IdentPath::from_str(name)) {
+            if let Decl::Fn(decl) = &self.shader.shader_ast.decls[*index] {decl} else {return SwVal::Void}
+        }
+        else {
+            return SwVal::Void
+        };
+        match self.exec_block(&decl.block) {
+            Flow::Return(val) => val,
+            _ => SwVal::Void
+        }
+    }
+
+    fn exec_block(&mut self, block: &Block) -> Flow {
+        let mark = self.locals.len();
+        for stmt in &block.stmts {
+            let flow = self.exec_stmt(stmt);
+            if let Flow::Normal = flow {
+                continue
+            }
+            self.locals.truncate(mark);
+            return flow
+        }
+        self.locals.truncate(mark);
+        Flow::Normal
+    }
+
+    fn exec_stmt(&mut self, stmt: &Stmt) -> Flow {
+        match stmt {
+            Stmt::Break {..} => Flow::Break,
+            Stmt::Continue {..} => Flow::Continue,
+            Stmt::For {ident, from_expr, to_expr, step_expr, block, ..} => {
+                let from = self.eval(from_expr).to_int();
+                let to = self.eval(to_expr).to_int();
+                let step = if let Some(step_expr) = step_expr {
+                    self.eval(step_expr).to_int()
+                } else if from < to {1} else {-1};
+                // same bounds as the generated glsl loop
+                let mut i = if from <= to {from} else {from - 1};
+                while (from <= to && i < to) || (from > to && i >= to) {
+                    self.locals.push((*ident, SwVal::int(i)));
+                    let flow = self.exec_block(block);
+                    self.locals.pop();
+                    match flow {
+                        Flow::Break => break,
+                        Flow::Return(val) => return Flow::Return(val),
+                        _ => ()
+                    }
+                    if step == 0 {
+                        break;
+                    }
+                    i += if from <= to {step.abs()} else {-step.abs()};
+                }
+                Flow::Normal
+            },
+            Stmt::If {expr, block_if_true, block_if_false, ..} => {
+                if self.eval(expr).to_bool() {
+                    self.exec_block(block_if_true)
+                }
+                else if let Some(block_if_false) = block_if_false {
+                    self.exec_block(block_if_false)
+                }
+                else {
+                    Flow::Normal
+                }
+            },
+            Stmt::Let {ty, ident, expr, ..} => {
+                let val = if let Some(expr) = expr {
+                    self.eval(expr)
+                }
+                else {
+                    SwVal::zero(ty.borrow().as_ref().unwrap_or(&Ty::Void), self.shader)
+                };
+                self.locals.push((*ident, val));
+                Flow::Normal
+            },
+            Stmt::Return {expr, ..} => {
+                Flow::Return(if let Some(expr) = expr {self.eval(expr)} else {SwVal::Void})
+            },
+            Stmt::Block {block, ..} => self.exec_block(block),
+            Stmt::Expr {expr, ..} => {
+                self.eval(expr);
+                Flow::Normal
+            }
+        }
+    }
+
+    fn find_local(&self, ident: Ident) -> Option<usize> {
+        for index in (self.frame_base..self.locals.len()).rev() {
+            if self.locals[index].0 == ident {
+                return Some(index)
+            }
+        }
+        None
+    }
+
+    pub fn eval(&mut self, expr: &Expr) -> SwVal {
+        if let Some(Some(val)) = expr.const_val.borrow().as_ref() {
+            return SwVal::from_val(val)
+        }
+        match &expr.kind {
+            ExprKind::Cond {expr, expr_if_true, expr_if_false, ..} => {
+                if self.eval(expr).to_bool() {
+                    self.eval(expr_if_true)
+                }
+                else {
+                    self.eval(expr_if_false)
+                }
+            },
+            ExprKind::Bin {op, left_expr, right_expr, ..} => self.eval_bin(*op, left_expr, right_expr),
+            ExprKind::Un {op, expr, ..} => {
+                let val = self.eval(expr);
+                match op {
+                    UnOp::Not => val.map( | d | Dual::flat(if d.v == 0.0 {1.0} else {0.0})),
+                    UnOp::Neg => val.map( | d | d.neg())
+                }
+            },
+            ExprKind::MethodCall {ident, arg_exprs, ..} => {
+                let struct_ident = match arg_exprs[0].ty.borrow().as_ref() {
+                    Some(Ty::Struct {ident}) => *ident,
+                    _ => return SwVal::Void
+                };
+                self.call_fn(IdentPath::from_two(struct_ident, *ident), arg_exprs)
+            },
+            ExprKind::Field {expr: base, field_ident, ..} => {
+                let val = self.eval(base);
+                self.field(&val, base, *field_ident)
+            },
+            ExprKind::Index {expr: base, index_expr, ..} => {
+                let val = self.eval(base);
+                let index = self.eval(index_expr).to_int().max(0) as usize;
+                match val {
+                    SwVal::Mat {n, c} => SwVal::vec(SwKind::Float, &c[index.min(n - 1) * n..index.min(n - 1) * n + n]),
+                    SwVal::Vec {kind, ..} => SwVal::vec(kind, &[val.comp(index)]),
+                    _ => SwVal::Void
+                }
+            },
+            ExprKind::Call {ident_path, arg_exprs, ..} => {
+                if self.shader.fns.contains_key(ident_path) {
+                    self.call_fn(*ident_path, arg_exprs)
+                }
+                else if let Some(ident) = ident_path.get_single() {
+                    let args: Vec<SwVal> = arg_exprs.iter().map( | e | self.eval(e)).collect();
+                    ident.with( | name | self.builtin(name, &args))
+                }
+                else {
+                    SwVal::Void
+                }
+            },
+            ExprKind::MacroCall {..} => SwVal::Void,
+            ExprKind::ConsCall {ty_lit, arg_exprs, ..} => {
+                let args: Vec<SwVal> = arg_exprs.iter().map( | e | self.eval(e)).collect();
+                construct(*ty_lit, &args)
+            },
+            ExprKind::Var {span, kind, ident_path} => self.eval_var(*span, kind.get(), *ident_path),
+            ExprKind::Lit {lit, ..} => SwVal::from_val(&lit.to_val()),
+        }
+    }
+
+    fn eval_var(&mut self, span: makepad_live_compiler::span::Span, kind: Option<VarKind>, ident_path: IdentPath) -> SwVal {
+        let ident = ident_path.get_single();
+        match kind {
+            Some(VarKind::Geometry) | Some(VarKind::Instance) | Some(VarKind::Varying) => {
+                if let Some(index) = ident.and_then( | i | self.shader.global_map.get(&i)) {
+                    return self.globals[*index].clone()
+                }
+            },
+            Some(VarKind::Uniform) => {
+                if let Some((block, offset, ty)) = ident.and_then( | i | self.shader.uniforms.get(&i)) {
+                    let buf = match block {
+                        SwUniformBlock::Pass => self.uniforms.pass,
+                        SwUniformBlock::View => self.uniforms.view,
+                        SwUniformBlock::Draw => self.uniforms.draw,
+                        SwUniformBlock::User => self.uniforms.user,
+                    };
+                    return SwVal::from_slots(ty, slice_at(buf, *offset, ty.size()))
+                }
+            },
+            Some(VarKind::Texture) => {
+                if let Some(slot) = ident.and_then( | i | self.shader.textures.get(&i)) {
+                    return SwVal::Texture(*slot)
+                }
+            },
+            Some(VarKind::Const) => {
+                if let Some(decl) = ident.and_then( | i | self.shader.shader_ast.find_const_decl(i)) {
+                    return self.eval(&decl.expr)
+                }
+            },
+            Some(VarKind::LiveStyle) => {
+                let live_styles = self.live_styles;
+                let mapping = self.mapping;
+                let key = (span.live_body_id.0, ident_path);
+                let entry = self.live_cache.entry(key).or_insert_with( || {
+                    let module_path = &live_styles.live_bodies[span.live_body_id.0].module_path;
+                    let live_item_id = ident_path.qualify(module_path).to_live_item_id();
+                    mapping.live_uniform_props.prop_map.get(&live_item_id).map( | index | {
+                        let prop = &mapping.live_uniform_props.props[*index];
+                        (prop.offset, prop.ty.clone())
+                    })
+                });
+                if let Some((offset, ty)) = entry.clone() {
+                    return SwVal::from_slots(&ty, slice_at(self.uniforms.live, offset, ty.size()))
+                }
+            },
+            Some(VarKind::Local) | None => {
+                if let Some(index) = ident.and_then( | i | self.find_local(i)) {
+                    return self.locals[index].1.clone()
+                }
+            }
+        }
+        SwVal::Void
+    }
+
+    fn field(&self, val: &SwVal, base: &Expr, field_ident: Ident) -> SwVal {
+        match val {
+            SwVal::Struct(fields) => {
+                if let Some(index) = self.field_index(base, field_ident) {
+                    return fields[index].clone()
+                }
+                SwVal::Void
+            },
+            SwVal::Vec {kind, ..} => {
+                if let Some(swizzle) = Swizzle::parse(field_ident) {
+                    let comps: Vec<Dual> = swizzle.iter().map( | i | val.comp(*i)).collect();
+                    return SwVal::vec(*kind, &comps)
+                }
+                SwVal::Void
+            },
+            _ => SwVal::Void
+        }
+    }
+
+    fn field_index(&self, base: &Expr, field_ident: Ident) -> Option<usize> {
+        if let Some(Ty::Struct {ident}) = base.ty.borrow().as_ref() {
+            return self.shader.structs.get(ident)?.iter().position( | (f, _) | *f == field_ident)
+        }
+        None
+    }
+
+    // writes a value back into whatever the lhs expression refers to
+    fn store(&mut self, expr: &Expr, val: SwVal) {
+        match &expr.kind {
+            ExprKind::Var {kind, ident_path, ..} => {
+                let ident = if let Some(ident) = ident_path.get_single() {ident} else {return};
+                match kind.get() {
+                    Some(VarKind::Geometry) | Some(VarKind::Instance) | Some(VarKind::Varying) => {
+                        if let Some(index) = self.shader.global_map.get(&ident) {
+                            self.globals[*index] = val;
+                        }
+                    },
+                    _ => {
+                        if let Some(index) = self.find_local(ident) {
+                            self.locals[index].1 = val;
+                        }
+                    }
+                }
+            },
+            ExprKind::Field {expr: base, field_ident, ..} => {
+                let mut target = self.eval(base);
+                match &mut target {
+                    SwVal::Struct(fields) => {
+                        if let Some(index) = self.field_index(base, *field_ident) {
+                            fields[index] = val;
+                        }
+                    },
+                    SwVal::Vec {c, ..} => {
+                        if let Some(swizzle) = Swizzle::parse(*field_ident) {
+                            for (i, index) in swizzle.iter().enumerate() {
+                                c[*index] = val.comp(i);
+                            }
+                        }
+                    },
+                    _ => ()
+                }
+                self.store(base, target);
+            },
+            ExprKind::Index {expr: base, index_expr, ..} => {
+                let mut target = self.eval(base);
+                let index = self.eval(index_expr).to_int().max(0) as usize;
+                match &mut target {
+                    SwVal::Mat {n, c} => {
+                        let col = index.min(*n - 1);
+                        for row in 0..*n {
+                            c[col * *n + row] = val.comp(row);
+                        }
+                    },
+                    SwVal::Vec {len, c, ..} => {
+                        if index < *len {
+                            c[index] = val.comp(0);
+                        }
+                    },
+                    _ => ()
+                }
+                self.store(base, target);
+            },
+            _ => ()
+        }
+    }
+
+    fn call_fn(&mut self, ident_path: IdentPath, arg_exprs: &[Expr]) -> SwVal {
+        let shader = self.shader;
+        let decl = match shader.fns.get(&ident_path).map( | i | &shader.shader_ast.decls[*i]) {
+            Some(Decl::Fn(decl)) => decl,
+            _ => return SwVal::Void
+        };
+        let args: Vec<SwVal> = arg_exprs.iter().map( | e | self.eval(e)).collect();
+        let saved_base = self.frame_base;
+        let base = self.locals.len();
+        for (param, arg) in decl.params.iter().zip(args.into_iter()) {
+            self.locals.push((param.ident, arg));
+        }
+        self.frame_base = base;
+        let ret = match self.exec_block(&decl.block) {
+            Flow::Return(val) => val,
+            _ => SwVal::Void
+        };
+        let mut inouts = Vec::new();
+        for (index, param) in decl.params.iter().enumerate() {
+            if param.is_inout {
+                inouts.push((index, self.locals[base + index].1.clone()));
+            }
+        }
+        self.locals.truncate(base);
+        self.frame_base = saved_base;
+        for (index, val) in inouts {
+            self.store(&arg_exprs[index], val);
+        }
+        ret
+    }
+
+    fn eval_bin(&mut self, op: BinOp, left_expr: &Expr, right_expr: &Expr) -> SwVal {
+        match op {
+            BinOp::Assign | BinOp::AddAssign | BinOp::SubAssign | BinOp::MulAssign | BinOp::DivAssign => {
+                let mut val = self.eval(right_expr);
+                let arith = match op {
+                    BinOp::AddAssign => Some(BinOp::Add),
+                    BinOp::SubAssign => Some(BinOp::Sub),
+                    BinOp::MulAssign => Some(BinOp::Mul),
+                    BinOp::DivAssign => Some(BinOp::Div),
+                    _ => None
+                };
+                if let Some(arith) = arith {
+                    let cur = self.eval(left_expr);
+                    val = bin_op(arith, &cur, &val);
+                }
+                self.store(left_expr, val.clone());
+                val
+            },
+            BinOp::And => {
+                if !self.eval(left_expr).to_bool() {
+                    return SwVal::bool(false)
+                }
+                SwVal::bool(self.eval(right_expr).to_bool())
+            },
+            BinOp::Or => {
+                if self.eval(left_expr).to_bool() {
+                    return SwVal::bool(true)
+                }
+                SwVal::bool(self.eval(right_expr).to_bool())
+            },
+            _ => {
+                let left = self.eval(left_expr);
+                let right = self.eval(right_expr);
+                bin_op(op, &left, &right)
+            }
+        }
+    }
+
+    fn builtin(&mut self, name: &str, a: &[SwVal]) -> SwVal {
+        let arg = | i: usize | if i < a.len() {a[i].clone()} else {SwVal::float(0.0)};
+        let flt = | v: f32 | SwVal::float(v);
+        match name {
+            "abs" => arg(0).map( | d | if d.v < 0.0 {d.neg()} else {d}),
+            "sign" => arg(0).map( | d | Dual::flat(if d.v > 0.0 {1.0} else if d.v < 0.0 {-1.0} else {0.0})),
+            "floor" => arg(0).map( | d | Dual::flat(d.v.floor())),
+            "ceil" => arg(0).map( | d | Dual::flat(d.v.ceil())),
+            "fract" => arg(0).map( | d | Dual {v: d.v - d.v.floor(), dx: d.dx, dy: d.dy}),
+            "sin" => arg(0).map( | d | d.chain(d.v.sin(), d.v.cos())),
+            "cos" => arg(0).map( | d | d.chain(d.v.cos(), -d.v.sin())),
+            "tan" => arg(0).map( | d | d.chain(d.v.tan(), 1.0 / (d.v.cos() * d.v.cos()))),
+            "asin" => arg(0).map( | d | d.chain(d.v.asin(), 1.0 / (1.0 - d.v * d.v).sqrt())),
+            "acos" => arg(0).map( | d | d.chain(d.v.acos(), -1.0 / (1.0 - d.v * d.v).sqrt())),
+            "atan" => {
+                if a.len() > 1 {
+                    arg(0).zip(&arg(1), | y, x | {
+                        let r = 1.0 / (x.v * x.v + y.v * y.v);
+                        Dual {v: y.v.atan2(x.v), dx: (x.v * y.dx - y.v * x.dx) * r, dy: (x.v * y.dy - y.v * x.dy) * r}
+                    })
+                }
+                else {
+                    arg(0).map( | d | d.chain(d.v.atan(), 1.0 / (1.0 + d.v * d.v)))
+                }
+            },
+            "exp" => arg(0).map( | d | d.chain(d.v.exp(), d.v.exp())),
+            "exp2" => arg(0).map( | d | d.chain(d.v.exp2(), d.v.exp2() * std::f32::consts::LN_2)),
+            "log" => arg(0).map( | d | d.chain(d.v.ln(), 1.0 / d.v)),
+            "log2" => arg(0).map( | d | d.chain(d.v.log2(), 1.0 / (d.v * std::f32::consts::LN_2))),
+            "sqrt" => arg(0).map( | d | d.chain(d.v.sqrt(), 0.5 / d.v.sqrt())),
+            "inversesqrt" => arg(0).map( | d | d.chain(1.0 / d.v.sqrt(), -0.5 / (d.v * d.v.sqrt()))),
+            "radians" => arg(0).map( | d | d.scale(std::f32::consts::PI / 180.0)),
+            "degrees" => arg(0).map( | d | d.scale(180.0 / std::f32::consts::PI)),
+            "pow" => arg(0).zip(&arg(1), | x, y | {
+                let v = x.v.powf(y.v);
+                let dxv = if x.v != 0.0 {y.v * x.v.powf(y.v - 1.0)} else {0.0};
+                let dyv = if x.v > 0.0 {v * x.v.ln()} else {0.0};
+                Dual {v: v, dx: x.dx * dxv + y.dx * dyv, dy: x.dy * dxv + y.dy * dyv}
+            }),
+            "min" => arg(0).zip(&arg(1), | x, y | Dual::select(y.v < x.v, y, x)),
+            "max" => arg(0).zip(&arg(1), | x, y | Dual::select(x.v < y.v, y, x)),
+            "clamp" => arg(0).zip3(&arg(1), &arg(2), | x, lo, hi | {
+                let x = Dual::select(x.v < lo.v, lo, x);
+                Dual::select(hi.v < x.v, hi, x)
+            }).with_kind(arg(0).kind()),
+            "mod" => arg(0).zip(&arg(1), | x, y | x.sub(y.scale((x.v / y.v).floor()))),
+            "mix" => arg(0).zip3(&arg(1), &arg(2), | x, y, t | x.add(y.sub(x).mul(t))),
+            "step" => arg(0).zip(&arg(1), | edge, x | Dual::flat(if x.v < edge.v {0.0} else {1.0})),
+            "smoothstep" => arg(0).zip3(&arg(1), &arg(2), | e0, e1, x | {
+                let t = x.sub(e0).div(e1.sub(e0));
+                let t = if t.v < 0.0 {Dual::flat(0.0)} else if t.v > 1.0 {Dual::flat(1.0)} else {t};
+                t.mul(t).mul(Dual::flat(3.0).sub(t.scale(2.0)))
+            }),
+            "dot" => SwVal::vec(SwKind::Float, &[dot(&arg(0), &arg(1))]),
+            "length" => {
+                let v = arg(0);
+                SwVal::vec(SwKind::Float, &[dual_sqrt(dot(&v, &v))])
+            },
+            "distance" => {
+                let v = bin_op(BinOp::Sub, &arg(0), &arg(1));
+                SwVal::vec(SwKind::Float, &[dual_sqrt(dot(&v, &v))])
+            },
+            "normalize" => {
+                let v = arg(0);
+                let len = dual_sqrt(dot(&v, &v));
+                v.map( | d | d.div(len))
+            },
+            "cross" => {
+                let (x, y) = (arg(0), arg(1));
+                SwVal::vec(SwKind::Float, &[
+                    x.comp(1).mul(y.comp(2)).sub(y.comp(1).mul(x.comp(2))),
+                    x.comp(2).mul(y.comp(0)).sub(y.comp(2).mul(x.comp(0))),
+                    x.comp(0).mul(y.comp(1)).sub(y.comp(0).mul(x.comp(1))),
+                ])
+            },
+            "reflect" => {
+                let (i, n) = (arg(0), arg(1));
+                let d = dot(&n, &i).scale(2.0);
+                i.zip(&n, | iv, nv | iv.sub(nv.mul(d)))
+            },
+            "refract" => {
+                let (i, n, eta) = (arg(0), arg(1), arg(2).comp(0).v);
+                let ndi = dot(&n, &i).v;
+                let k = 1.0 - eta * eta * (1.0 - ndi * ndi);
+                if k < 0.0 {
+                    i.map( | _ | Dual::default())
+                }
+                else {
+                    let f = eta * ndi + k.sqrt();
+                    i.zip(&n, | iv, nv | iv.scale(eta).sub(nv.scale(f)))
+                }
+            },
+            "faceforward" => {
+                let (n, i, nref) = (arg(0), arg(1), arg(2));
+                if dot(&nref, &i).v < 0.0 {n} else {n.map( | d | d.neg())}
+            },
+            "dFdx" => arg(0).map( | d | Dual::flat(d.dx)),
+            "dFdy" => arg(0).map( | d | Dual::flat(d.dy)),
+            "lessThan" => cmp_vec(&arg(0), &arg(1), | x, y | x < y),
+            "lessThanEqual" => cmp_vec(&arg(0), &arg(1), | x, y | x <= y),
+            "greaterThan" => cmp_vec(&arg(0), &arg(1), | x, y | x > y),
+            "greaterThanEqual" => cmp_vec(&arg(0), &arg(1), | x, y | x >= y),
+            "equal" => cmp_vec(&arg(0), &arg(1), | x, y | x == y),
+            "notEqual" => cmp_vec(&arg(0), &arg(1), | x, y | x != y),
+            "not" => arg(0).map( | d | Dual::flat(if d.v == 0.0 {1.0} else {0.0})),
+            "any" => SwVal::bool(arg(0).comps().iter().any( | d | d.v != 0.0)),
+            "all" => SwVal::bool(arg(0).comps().iter().all( | d | d.v != 0.0)),
+            "matrixCompMult" => arg(0).zip(&arg(1), | x, y | x.mul(y)),
+            "transpose" => {
+                if let SwVal::Mat {n, c} = arg(0) {
+                    let mut o = [Dual::default(); 16];
+                    for col in 0..n {
+                        for row in 0..n {
+                            o[row * n + col] = c[col * n + row];
+                        }
+                    }
+                    return SwVal::Mat {n: n, c: o}
+                }
+                arg(0)
+            },
+            "inverse" => {
+                if let SwVal::Mat {n, c} = arg(0) {
+                    return invert(n, &c)
+                }
+                arg(0)
+            },
+            "sample2d" => {
+                if let SwVal::Texture(slot) = arg(0) {
+                    let pos = arg(1);
+                    let c = self.textures.sample(slot, pos.comp(0).v, pos.comp(1).v);
+                    return SwVal::vec(SwKind::Float, &[Dual::new(c.x), Dual::new(c.y), Dual::new(c.z), Dual::new(c.w)])
+                }
+                flt(0.0)
+            },
+            _ => {
+                eprintln!("Software shader: unsupported builtin {}", name);
+                SwVal::Void
+            }
+        }
+    }
+}
+
+fn slice_at<T>(buf: &[T], offset: usize, len: usize) -> &[T] {
+    if offset >= buf.len() {
+        return &buf[0..0]
+    }
+    &buf[offset..(offset + len).min(buf.len())]
+}
+
+fn dual_sqrt(d: Dual) -> Dual {
+    let s = d.v.sqrt();
+    if s == 0.0 {
+        return Dual::flat(0.0)
+    }
+    d.chain(s, 0.5 / s)
+}
+
+fn dot(a: &SwVal, b: &SwVal) -> Dual {
+    let mut sum = Dual::default();
+    for i in 0..a.len().max(b.len()) {
+        sum = sum.add(a.comp(i).mul(b.comp(i)));
+    }
+    sum
+}
+
+fn cmp_vec(a: &SwVal, b: &SwVal, f: impl Fn(f32, f32) -> bool) -> SwVal {
+    a.zip(b, | x, y | Dual::flat(if f(x.v, y.v) {1.0} else {0.0})).with_kind(SwKind::Bool)
+}
+
+fn invert(n: usize, c: &[Dual; 16]) -> SwVal {
+    // gauss jordan on the values, derivatives are dropped
+    let mut m = [[0.0f64; 8]; 4];
+    for row in 0..n {
+        for col in 0..n {
+            m[row][col] = c[col * n + row].v as f64;
+        }
+        m[row][n + row] = 1.0;
+    }
+    for col in 0..n {
+        let mut pivot = col;
+        for row in col + 1..n {
+            if m[row][col].abs() > m[pivot][col].abs() {
+                pivot = row;
+            }
+        }
+        m.swap(col, pivot);
+        let p = m[col][col];
+        if p == 0.0 {
+            return SwVal::Mat {n: n, c: [Dual::default(); 16]}
+        }
+        for k in 0..2 * n {
+            m[col][k] /= p;
+        }
+        for row in 0..n {
+            if row != col {
+                let f = m[row][col];
+                for k in 0..2 * n {
+                    m[row][k] -= f * m[col][k];
+                }
+            }
+        }
+    }
+    let mut o = [Dual::default(); 16];
+    for row in 0..n {
+        for col in 0..n {
+            o[col * n + row] = Dual::new(m[row][n + col] as f32);
+        }
+    }
+    SwVal::Mat {n: n, c: o}
+}
+
+fn bin_op(op: BinOp, a: &SwVal, b: &SwVal) -> SwVal {
+    match op {
+        BinOp::Eq | BinOp::Ne => {
+            let len = a.len().max(b.len());
+            let eq = (0..len).all( | i | a.comp(i).v == b.comp(i).v);
+            return SwVal::bool(if let BinOp::Eq = op {eq} else {!eq})
+        },
+        BinOp::Lt => return SwVal::bool(a.to_f32() < b.to_f32()),
+        BinOp::Le => return SwVal::bool(a.to_f32() <= b.to_f32()),
+        BinOp::Gt => return SwVal::bool(a.to_f32() > b.to_f32()),
+        BinOp::Ge => return SwVal::bool(a.to_f32() >= b.to_f32()),
+        _ => ()
+    }
+    // linear algebra products
+    if let BinOp::Mul = op {
+        match (a, b) {
+            (SwVal::Mat {n, c: ma}, SwVal::Mat {c: mb, ..}) => {
+                let n = *n;
+                let mut o = [Dual::default(); 16];
+                for col in 0..n {
+                    for row in 0..n {
+                        let mut sum = Dual::default();
+                        for k in 0..n {
+                            sum = sum.add(ma[k * n + row].mul(mb[col * n + k]));
+                        }
+                        o[col * n + row] = sum;
+                    }
+                }
+                return SwVal::Mat {n: n, c: o}
+            },
+            (SwVal::Mat {n, c: m}, SwVal::Vec {len, ..}) if *len > 1 => {
+                let n = *n;
+                let comps: Vec<Dual> = (0..n).map( | row | {
+                    let mut sum = Dual::default();
+                    for k in 0..n {
+                        sum = sum.add(m[k * n + row].mul(b.comp(k)));
+                    }
+                    sum
+                }).collect();
+                return SwVal::vec(SwKind::Float, &comps)
+            },
+            (SwVal::Vec {len, ..}, SwVal::Mat {n, c: m}) if *len > 1 => {
+                let n = *n;
+                let comps: Vec<Dual> = (0..n).map( | col | {
+                    let mut sum = Dual::default();
+                    for k in 0..n {
+                        sum = sum.add(a.comp(k).mul(m[col * n + k]));
+                    }
+                    sum
+                }).collect();
+                return SwVal::vec(SwKind::Float, &comps)
+            },
+            _ => ()
+        }
+    }
+    let is_int = a.kind() == SwKind::Int && b.kind() == SwKind::Int;
+    a.zip(b, | x, y | match op {
+        BinOp::Add => x.add(y),
+        BinOp::Sub => x.sub(y),
+        BinOp::Mul => x.mul(y),
+        BinOp::Div => if is_int {
+            Dual::flat(if y.v == 0.0 {0.0} else {(x.v / y.v).trunc()})
+        } else {
+            x.div(y)
+        },
+        _ => x
+    })
+}
+
+fn construct(ty_lit: TyLit, args: &[SwVal]) -> SwVal {
+    let ty = ty_lit.to_ty();
+    match &ty {
+        Ty::Mat2 | Ty::Mat3 | Ty::Mat4 => {
+            let n = match ty {Ty::Mat2 => 2, Ty::Mat3 => 3, _ => 4};
+            let mut c = [Dual::default(); 16];
+            if args.len() == 1 {
+                match &args[0] {
+                    SwVal::Mat {n: m, c: src} => {
+                        for col in 0..n {
+                            for row in 0..n {
+                                c[col * n + row] = if col < *m && row < *m {
+                                    src[col * m + row]
+                                } else {
+                                    Dual::flat(if col == row {1.0} else {0.0})
+                                };
+                            }
+                        }
+                    },
+                    other => {
+                        for i in 0..n {
+                            c[i * n + i] = other.comp(0);
+                        }
+                    }
+                }
+            }
+            else {
+                let comps: Vec<Dual> = args.iter().flat_map( | a | a.comps()).collect();
+                for i in 0..(n * n).min(comps.len()) {
+                    c[i] = comps[i];
+                }
+            }
+            SwVal::Mat {n: n, c: c}
+        },
+        _ => {
+            let (kind, len) = if let Some(shape) = vec_shape(&ty) {shape} else {return SwVal::Void};
+            let comps: Vec<Dual> = if args.len() == 1 && args[0].len() == 1 {
+                (0..len).map( | _ | args[0].comp(0)).collect()
+            }
+            else {
+                args.iter().flat_map( | a | a.comps()).take(len).collect()
+            };
+            let comps: Vec<Dual> = comps.into_iter().map( | d | match kind {
+                SwKind::Float => d,
+                SwKind::Int => Dual::flat(d.v.trunc()),
+                SwKind::Bool => Dual::flat(if d.v != 0.0 {1.0} else {0.0}),
+            }).collect();
+            let mut out = SwVal::vec(kind, &comps);
+            if let SwVal::Vec {len: l, ..} = &mut out {
+                *l = len;
+            }
+            out
+        }
+    }
+}
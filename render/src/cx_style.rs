@@ -9,6 +9,7 @@ impl Cx{
         crate::drawcolor::DrawColor::style(self);
         crate::drawimage::DrawImage::style(self);
         crate::drawcube::DrawCube::style(self);
+        crate::drawmesh::DrawMesh::style(self);
     }
 }
 
@@ -309,11 +309,22 @@ impl Cx {
                     self.call_event_handler(&mut Event::KeyUp(key_event));
                 },
                 14 => { // text input
-                    self.call_event_handler(&mut Event::TextInput(TextInputEvent {
-                        was_paste: to_wasm.mu32()>0,
-                        replace_last: to_wasm.mu32()>0,
-                        input: to_wasm.parse_string(),
-                    }));
+                    let was_paste = to_wasm.mu32()>0;
+                    let replace_last = to_wasm.mu32()>0;
+                    let input = to_wasm.parse_string();
+                    // the browser only hands over the text of a paste
+                    if was_paste {
+                        self.call_event_handler(&mut Event::Paste(PasteEvent {
+                            data: ClipboardData::from_text(&input)
+                        }));
+                    }
+                    else {
+                        self.call_event_handler(&mut Event::TextInput(TextInputEvent {
+                            was_paste,
+                            replace_last,
+                            input,
+                        }));
+                    }
                 },
                 15 => { // file read data
                     let read_id = to_wasm.mu32();
@@ -338,13 +349,14 @@ impl Cx {
                     let mut event = Event::TextCopy(TextCopyEvent {
                         response: None
                     });
+                    // the browser only lets us write during its copy event, so older offers are dropped
+                    self.clipboard_offer = None;
                     self.call_event_handler(&mut event);
-                    match &event {
-                        Event::TextCopy(req) => if let Some(response) = &req.response {
-                            self.platform.from_wasm.text_copy_response(&response);
+                    if let Some(data) = self.clipboard_offer.take() {
+                        if let Some(text) = data.text() {
+                            self.platform.from_wasm.text_copy_response(text);
                         }
-                        _ => ()
-                    };
+                    }
                 },
                 18 => { // timer fired
                     let timer_id = to_wasm.mf64() as u64;
@@ -448,6 +460,15 @@ impl Cx {
                         WebSocketMessageEvent {url, result: Err(err)}
                     ));
                 }
+                25 => { // text ime, the browser doesnt tell us the caret or clauses
+                    let preedit = to_wasm.parse_string();
+                    let len = preedit.chars().count();
+                    self.call_event_handler(&mut Event::TextIme(TextImeEvent {
+                        preedit: preedit,
+                        cursor: len,
+                        segments: TextImeEvent::segments_from_styles(&vec![TextImeStyle::Input; len])
+                    }));
+                }
                 _ => {
                     panic!("Message unknown")
                 }
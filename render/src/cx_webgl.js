@@ -388,6 +388,12 @@
             this.send_string(error);
         }
         
+        text_ime(preedit) {
+            let pos = this.fit(1);
+            this.mu32[pos ++] = 25;
+            this.send_string(preedit);
+        }
+        
         end() {
             let pos = this.fit(1);
             this.mu32[pos] = 0;
@@ -976,7 +982,29 @@
                 
             })
             
+            // the composition is sent as a preedit and committed on compositionend
+            ta.addEventListener('compositionupdate', e => {
+                this.to_wasm.text_ime(e.data || "");
+                this.do_wasm_io();
+            })
+            ta.addEventListener('compositionend', e => {
+                this.to_wasm.text_ime("");
+                if (e.data) {
+                    this.to_wasm.text_input({
+                        was_paste: false,
+                        input: e.data,
+                        replace_last: false,
+                    })
+                }
+                ta.value = "";
+                last_len = 0;
+                this.do_wasm_io();
+            })
+            
             ta.addEventListener('input', e => {
+                if (e.isComposing) {
+                    return
+                }
                 if (ta.value.length > 0) {
                     if (was_paste) {
                         was_paste = false;
@@ -1779,7 +1807,15 @@
             gl.texParameteri(gl.TEXTURE_2D, gl.TEXTURE_WRAP_S, gl.CLAMP_TO_EDGE)
             gl.texParameteri(gl.TEXTURE_2D, gl.TEXTURE_WRAP_T, gl.CLAMP_TO_EDGE)
             
-            let data = new Uint8Array(this.memory.buffer, data_ptr, width * height * 4);
+            // image_u32 is 0xAARRGGBB, webgl only takes rgba bytes so swap red and blue
+            let src = new Uint8Array(this.memory.buffer, data_ptr, width * height * 4);
+            let data = new Uint8Array(width * height * 4);
+            for (let i = 0; i < data.length; i += 4) {
+                data[i] = src[i + 2];
+                data[i + 1] = src[i + 1];
+                data[i + 2] = src[i];
+                data[i + 3] = src[i + 3];
+            }
             gl.texImage2D(gl.TEXTURE_2D, 0, gl.RGBA, width, height, 0, gl.RGBA, gl.UNSIGNED_BYTE, data);
             //gl.bindTexture(gl.TEXTURE_2D,0);
             this.textures[texture_id] = gl_tex;
@@ -1,7 +1,7 @@
 use crate::cx::*;
 use time::precise_time_ns;
 use std::{ptr};
-use winapi::um::{libloaderapi, winuser, winbase, dwmapi};
+use winapi::um::{libloaderapi, winuser, winbase, dwmapi, imm};
 use winapi::shared::minwindef::{LPARAM, LRESULT, DWORD, WPARAM, BOOL, UINT, FALSE};
 use winapi::shared::ntdef::{NULL};
 use winapi::um::winnt::{LPCWSTR, HRESULT, LPCSTR};
@@ -20,6 +20,48 @@ use winapi::um::uxtheme::MARGINS;
 use std::sync::{Mutex};
 use std::collections::{HashMap, BTreeSet};
 
+// imm composition string flags and clause attributes
+const GCS_COMPSTR: DWORD = 0x0008;
+const GCS_COMPATTR: DWORD = 0x0010;
+const GCS_CURSORPOS: DWORD = 0x0080;
+const ATTR_TARGET_CONVERTED: u8 = 0x01;
+const ATTR_CONVERTED: u8 = 0x02;
+const ATTR_TARGET_NOTCONVERTED: u8 = 0x03;
+
+// the HTML Format clipboard entry is utf8 behind a header with byte offsets of the fragment
+fn encode_cf_html(html: &str) -> Vec<u8> {
+    let header_len = 105;
+    let prefix = "<html><body>\r\n<!--StartFragment-->";
+    let suffix = "<!--EndFragment-->\r\n</body></html>";
+    let start_fragment = header_len + prefix.len();
+    let end_fragment = start_fragment + html.len();
+    let end_html = end_fragment + suffix.len();
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}",
+        header_len,
+        end_html,
+        start_fragment,
+        end_fragment,
+        prefix,
+        html,
+        suffix
+    ).into_bytes()
+}
+
+fn decode_cf_html(bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let offset = | key: &str | -> Option<usize> {
+        let pos = text.find(key) ? + key.len();
+        text[pos..].split(|c: char | !c.is_ascii_digit()).next() ?.parse().ok()
+    };
+    let start = offset("StartFragment:") ?;
+    let end = offset("EndFragment:") ?;
+    if start > end || end > bytes.len() {
+        return None
+    }
+    Some(String::from_utf8_lossy(&bytes[start..end]).to_string())
+}
+
 static mut GLOBAL_WIN32_APP: *mut Win32App = 0 as *mut _;
 
 pub struct Win32App {
@@ -290,6 +332,108 @@ impl Win32App {
         }
     }
     
+    // plug it into the windows clipboard, html and custom mimes are registered formats
+    pub fn set_clipboard(&mut self, data: ClipboardData) {
+        unsafe {
+            if winuser::OpenClipboard(ptr::null_mut()) == 0 {
+                return
+            }
+            winuser::EmptyClipboard();
+            for format in &data.formats {
+                match format {
+                    ClipboardFormat::Text(text) => {
+                        let wide: Vec<u16> = OsStr::new(text).encode_wide().chain(Some(0).into_iter()).collect();
+                        let bytes = std::slice::from_raw_parts(wide.as_ptr() as *const u8, wide.len() * 2);
+                        Self::set_clipboard_bytes(winuser::CF_UNICODETEXT, bytes);
+                    },
+                    ClipboardFormat::Html(html) => {
+                        Self::set_clipboard_bytes(Self::register_clipboard_format("HTML Format"), &encode_cf_html(html));
+                    },
+                    ClipboardFormat::Custom {mime, data} => {
+                        Self::set_clipboard_bytes(Self::register_clipboard_format(mime), data);
+                    },
+                    ClipboardFormat::Image(image) => {
+                        Self::set_clipboard_bytes(winuser::CF_DIB, &encode_dib(image));
+                    }
+                }
+            }
+            winuser::CloseClipboard();
+        }
+    }
+    
+    pub fn get_clipboard() -> ClipboardData {
+        let mut data = ClipboardData::default();
+        unsafe {
+            if winuser::OpenClipboard(ptr::null_mut()) == 0 {
+                return data
+            }
+            if let Some(bytes) = Self::get_clipboard_bytes(winuser::CF_UNICODETEXT) {
+                let wide: Vec<u16> = bytes.chunks_exact(2).map( | c | u16::from_le_bytes([c[0], c[1]])).take_while( | c | *c != 0).collect();
+                if let Ok(text) = String::from_utf16(&wide) {
+                    data = data.with_text(&text);
+                }
+            }
+            if let Some(bytes) = Self::get_clipboard_bytes(Self::register_clipboard_format("HTML Format")) {
+                if let Some(html) = decode_cf_html(&bytes) {
+                    data = data.with_html(&html);
+                }
+            }
+            if let Some(bytes) = Self::get_clipboard_bytes(winuser::CF_DIB) {
+                if let Ok(image) = decode_dib(&bytes, None) {
+                    data = data.with_image(image);
+                }
+            }
+            // registered formats have a name, ours are mimes
+            let mut format = winuser::EnumClipboardFormats(0);
+            while format != 0 {
+                let mut name = [0u16; 256];
+                let len = winuser::GetClipboardFormatNameW(format, name.as_mut_ptr(), name.len() as i32);
+                if len > 0 {
+                    let mime = String::from_utf16_lossy(&name[0..len as usize]);
+                    if mime.starts_with("application/x-") {
+                        if let Some(bytes) = Self::get_clipboard_bytes(format) {
+                            data = data.with_custom(&mime, &bytes);
+                        }
+                    }
+                }
+                format = winuser::EnumClipboardFormats(format);
+            }
+            winuser::CloseClipboard();
+        }
+        data
+    }
+    
+    unsafe fn register_clipboard_format(name: &str) -> UINT {
+        let wide: Vec<u16> = OsStr::new(name).encode_wide().chain(Some(0).into_iter()).collect();
+        winuser::RegisterClipboardFormatW(wide.as_ptr())
+    }
+    
+    // the clipboard has to be open
+    unsafe fn set_clipboard_bytes(format: UINT, bytes: &[u8]) {
+        let h_clipboard_data = winbase::GlobalAlloc(winbase::GMEM_MOVEABLE, bytes.len());
+        let h_clipboard_ptr = winbase::GlobalLock(h_clipboard_data) as *mut u8;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), h_clipboard_ptr, bytes.len());
+        winbase::GlobalUnlock(h_clipboard_data);
+        winuser::SetClipboardData(format, h_clipboard_data);
+    }
+    
+    unsafe fn get_clipboard_bytes(format: UINT) -> Option<Vec<u8>> {
+        if format == 0 || winuser::IsClipboardFormatAvailable(format) == 0 {
+            return None
+        }
+        let h_clipboard_data = winuser::GetClipboardData(format);
+        if h_clipboard_data == ptr::null_mut() {
+            return None
+        }
+        let h_clipboard_ptr = winbase::GlobalLock(h_clipboard_data) as *const u8;
+        if h_clipboard_ptr == ptr::null() {
+            return None
+        }
+        let bytes = std::slice::from_raw_parts(h_clipboard_ptr, winbase::GlobalSize(h_clipboard_data)).to_vec();
+        winbase::GlobalUnlock(h_clipboard_data);
+        Some(bytes)
+    }
+    
     pub fn terminate_event_loop(&mut self) {
         unsafe {
             if self.all_windows.len()>0 {
@@ -608,62 +752,20 @@ impl Win32Window {
                 if modifiers.control || modifiers.logo {
                     match key_code {
                         KeyCode::KeyV => { // paste
-                            if winuser::OpenClipboard(ptr::null_mut()) != 0 {
-                                let mut data: Vec<u16> = Vec::new();
-                                let h_clipboard_data = winuser::GetClipboardData(winuser::CF_UNICODETEXT);
-                                let h_clipboard_ptr = winbase::GlobalLock(h_clipboard_data) as *mut u16;
-                                let clipboard_size = winbase::GlobalSize(h_clipboard_data);
-                                if clipboard_size > 2 {
-                                    data.resize((clipboard_size>>1) - 1, 0);
-                                    std::ptr::copy_nonoverlapping(h_clipboard_ptr, data.as_mut_ptr(), data.len());
-                                    winbase::GlobalUnlock(h_clipboard_data);
-                                    winuser::CloseClipboard();
-                                    if let Ok(utf8) = String::from_utf16(&data) {
-                                        window.do_callback(&mut vec![
-                                            Event::TextInput(TextInputEvent {
-                                                input: utf8,
-                                                was_paste: true,
-                                                replace_last: false
-                                            })
-                                        ]);
-                                    }
-                                }
-                                else {
-                                    winbase::GlobalUnlock(h_clipboard_data);
-                                    winuser::CloseClipboard();
-                                }
+                            let data = Win32App::get_clipboard();
+                            if !data.is_empty() {
+                                window.do_callback(&mut vec![
+                                    Event::Paste(PasteEvent {data})
+                                ]);
                             }
                         }
                         KeyCode::KeyX | KeyCode::KeyC => {
-                            let mut events = vec![
+                            // the answer comes back through set_clipboard
+                            window.do_callback(&mut vec![
                                 Event::TextCopy(TextCopyEvent {
                                     response: None
                                 })
-                            ];
-                            window.do_callback(&mut events);
-                            match &events[0] {
-                                Event::TextCopy(req) => if let Some(response) = &req.response {
-                                    // plug it into the windows clipboard
-                                    // make utf16 dta
-                                    if winuser::OpenClipboard(ptr::null_mut()) != 0 {
-                                        winuser::EmptyClipboard();
-                                        
-                                        let data: Vec<u16> = OsStr::new(response).encode_wide().chain(Some(0).into_iter()).collect();
-                                        
-                                        let h_clipboard_data = winbase::GlobalAlloc(winbase::GMEM_DDESHARE, 2 * data.len());
-                                        
-                                        let h_clipboard_ptr = winbase::GlobalLock(h_clipboard_data) as *mut u16;
-                                        
-                                        std::ptr::copy_nonoverlapping(data.as_ptr(), h_clipboard_ptr, data.len());
-                                        
-                                        winbase::GlobalUnlock(h_clipboard_data);
-                                        winuser::SetClipboardData(winuser::CF_UNICODETEXT, h_clipboard_data);
-                                        winuser::CloseClipboard();
-                                    }
-                                    
-                                },
-                                _ => ()
-                            };
+                            ]);
                         }
                         _ => ()
                     }
@@ -702,6 +804,17 @@ impl Win32Window {
                     }
                 }
             },
+            winuser::WM_IME_COMPOSITION => {
+                if lparam as DWORD & GCS_COMPSTR != 0 {
+                    window.send_text_ime(Self::get_ime_composition(hwnd));
+                }
+                // the result string still comes in as WM_CHAR
+                return winuser::DefWindowProcW(hwnd, msg, wparam, lparam)
+            },
+            winuser::WM_IME_ENDCOMPOSITION => {
+                window.send_text_ime(TextImeEvent::default());
+                return winuser::DefWindowProcW(hwnd, msg, wparam, lparam)
+            },
             winuser::WM_ENTERSIZEMOVE => {
                 (*window.win32_app).start_resize();
                 window.do_callback(&mut vec![Event::WindowResizeLoop(WindowResizeLoopEvent {
@@ -1121,6 +1234,49 @@ impl Win32Window {
         })])
     }
     
+    pub fn send_text_ime(&mut self, ime: TextImeEvent) {
+        self.do_callback(&mut vec![Event::TextIme(ime)])
+    }
+    
+    pub unsafe fn get_ime_composition(hwnd: HWND) -> TextImeEvent {
+        let himc = imm::ImmGetContext(hwnd);
+        if himc == ptr::null_mut() {
+            return TextImeEvent::default()
+        }
+        let bytes = imm::ImmGetCompositionStringW(himc, GCS_COMPSTR, ptr::null_mut(), 0);
+        let mut wide = vec![0u16; bytes.max(0) as usize / 2];
+        imm::ImmGetCompositionStringW(himc, GCS_COMPSTR, wide.as_mut_ptr() as *mut c_void, bytes.max(0) as DWORD);
+        // one attribute byte per utf16 unit
+        let bytes = imm::ImmGetCompositionStringW(himc, GCS_COMPATTR, ptr::null_mut(), 0);
+        let mut attrs = vec![0u8; bytes.max(0) as usize];
+        imm::ImmGetCompositionStringW(himc, GCS_COMPATTR, attrs.as_mut_ptr() as *mut c_void, bytes.max(0) as DWORD);
+        let cursor_utf16 = imm::ImmGetCompositionStringW(himc, GCS_CURSORPOS, ptr::null_mut(), 0).max(0) as usize;
+        imm::ImmReleaseContext(hwnd, himc);
+        
+        let mut preedit = String::new();
+        let mut styles = Vec::new();
+        let mut cursor = 0;
+        let mut offset = 0;
+        for c in std::char::decode_utf16(wide.iter().cloned()) {
+            let c = c.unwrap_or(std::char::REPLACEMENT_CHARACTER);
+            if offset < cursor_utf16 {
+                cursor += 1;
+            }
+            styles.push(match attrs.get(offset).cloned().unwrap_or(0) {
+                ATTR_TARGET_CONVERTED | ATTR_TARGET_NOTCONVERTED => TextImeStyle::Target,
+                ATTR_CONVERTED => TextImeStyle::Converted,
+                _ => TextImeStyle::Input
+            });
+            offset += c.len_utf16();
+            preedit.push(c);
+        }
+        TextImeEvent {
+            preedit: preedit,
+            cursor: cursor,
+            segments: TextImeEvent::segments_from_styles(&styles)
+        }
+    }
+    
     pub fn virtual_key_to_key_code(wparam: WPARAM) -> KeyCode {
         match wparam as i32 {
             winuser::VK_ESCAPE => KeyCode::Escape,
@@ -264,6 +264,10 @@ impl Cx {
             }
             
             self.process_live_style_errors();
+            
+            if let Some(data) = self.clipboard_offer.take() {
+                win32_app.set_clipboard(data);
+            }
 
             
             if self.playing_animator_ids.len() != 0
@@ -26,7 +26,8 @@ static mut GLOBAL_XLIB_APP: *mut XlibApp = 0 as *mut _;
 pub struct XlibApp {
     pub display: *mut X11_sys::Display,
     pub xim: X11_sys::XIM,
-    pub clipboard: String,
+    pub clipboard: ClipboardData,
+    pub paste: Option<XlibPaste>,
     pub display_fd: c_int,
     pub signal_fds: [c_int; 2],
     pub window_map: HashMap<c_ulong, *mut XlibWindow>,
@@ -57,6 +58,9 @@ pub struct XlibApp {
     pub atom_multiple: X11_sys::Atom,
     pub atom_text_plain: X11_sys::Atom,
     pub atom_atom: X11_sys::Atom,
+    pub atom_text_html: X11_sys::Atom,
+    pub atom_image_png: X11_sys::Atom,
+    pub atom_image_bmp: X11_sys::Atom,
     
     pub dnd: Dnd,
 }
@@ -76,11 +80,22 @@ pub struct XlibWindow {
     pub time_start: u64,
     
     pub ime_spot: Vec2,
+    pub ime_preedit: Vec<char>,
+    pub ime_styles: Vec<TextImeStyle>,
+    pub ime_caret: usize,
     pub current_cursor: MouseCursor,
     pub last_mouse_pos: Vec2,
     pub fingers_down: Vec<bool>,
 }
 
+// a paste in flight, the owner hands over one target per SelectionNotify
+pub struct XlibPaste {
+    pub window: c_ulong,
+    pub time: c_ulong,
+    pub targets: Vec<X11_sys::Atom>,
+    pub data: ClipboardData,
+}
+
 #[derive(Clone)]
 pub struct XlibChildWindow {
     pub window: c_ulong,
@@ -110,6 +125,9 @@ impl XlibApp {
         unsafe {
             let display = X11_sys::XOpenDisplay(ptr::null());
             let display_fd = X11_sys::XConnectionNumber(display);
+            // the input method needs the locale to pick the user's IME and encode its preedit text
+            libc::setlocale(libc::LC_CTYPE, b"\0".as_ptr() as *const c_char);
+            X11_sys::XSetLocaleModifiers(b"\0".as_ptr() as *const c_char);
             let xim = X11_sys::XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
             let mut signal_fds = [0, 0];
             libc::pipe(signal_fds.as_mut_ptr());
@@ -128,11 +146,15 @@ impl XlibApp {
                 atom_text: X11_sys::XInternAtom(display, CString::new("TEXT").unwrap().as_ptr(), 0),
                 atom_text_plain: X11_sys::XInternAtom(display, CString::new("text/plain").unwrap().as_ptr(), 0),
                 atom_multiple: X11_sys::XInternAtom(display, CString::new("MULTIPLE").unwrap().as_ptr(), 0),
+                atom_text_html: X11_sys::XInternAtom(display, CString::new("text/html").unwrap().as_ptr(), 0),
+                atom_image_png: X11_sys::XInternAtom(display, CString::new("image/png").unwrap().as_ptr(), 0),
+                atom_image_bmp: X11_sys::XInternAtom(display, CString::new("image/bmp").unwrap().as_ptr(), 0),
                 xim,
                 display,
                 display_fd,
                 signal_fds,
-                clipboard: String::new(),
+                clipboard: ClipboardData::default(),
+                paste: None,
                 last_scroll_time: 0.0,
                 last_click_time: 0.0,
                 last_click_pos: (0, 0),
@@ -239,67 +261,17 @@ impl XlibApp {
                     let mut event = mem::MaybeUninit::uninit();
                     X11_sys::XNextEvent(self.display, event.as_mut_ptr());
                     let mut event = event.assume_init();
+                    // key events the input method composes with are eaten here
+                    if X11_sys::XFilterEvent(&mut event, 0) != 0 {
+                        continue;
+                    }
                     match event.type_ as u32 {
                         X11_sys::SelectionNotify => {
                             let selection = event.xselection;
                             if selection.property == self.dnd.atoms.selection {
                                 self.dnd.handle_selection_event(&selection);
                             } else {
-                                // first get the size of the thing
-                                let mut actual_type = mem::MaybeUninit::uninit();
-                                let mut actual_format = mem::MaybeUninit::uninit();
-                                let mut n_items = mem::MaybeUninit::uninit();
-                                let mut bytes_to_read = mem::MaybeUninit::uninit();
-                                let mut ret = mem::MaybeUninit::uninit();
-                                X11_sys::XGetWindowProperty(
-                                    self.display,
-                                    selection.requestor,
-                                    selection.property,
-                                    0,
-                                    0,
-                                    0,
-                                    X11_sys::AnyPropertyType as c_ulong,
-                                    actual_type.as_mut_ptr(),
-                                    actual_format.as_mut_ptr(),
-                                    n_items.as_mut_ptr(),
-                                    bytes_to_read.as_mut_ptr(),
-                                    ret.as_mut_ptr()
-                                );
-                                //let actual_type = actual_type.assume_init();
-                                //let actual_format = actual_format.assume_init();
-                                //let n_items = n_items.assume_init();
-                                let bytes_to_read = bytes_to_read.assume_init();
-                                //let mut ret = ret.assume_init();
-                                let mut bytes_after = mem::MaybeUninit::uninit();
-                                X11_sys::XGetWindowProperty(
-                                    self.display,
-                                    selection.requestor,
-                                    selection.property,
-                                    0,
-                                    bytes_to_read as c_long,
-                                    0,
-                                    X11_sys::AnyPropertyType as c_ulong,
-                                    actual_type.as_mut_ptr(),
-                                    actual_format.as_mut_ptr(),
-                                    n_items.as_mut_ptr(),
-                                    bytes_after.as_mut_ptr(),
-                                    ret.as_mut_ptr()
-                                );
-                                let ret = ret.assume_init();
-                                //let bytes_after = bytes_after.assume_init();
-                                if ret != ptr::null_mut() && bytes_to_read > 0 {
-                                    let utf8_slice = std::slice::from_raw_parts::<u8>(ret as *const _ as *const u8, bytes_to_read as usize);
-                                    if let Ok(utf8_string) = String::from_utf8(utf8_slice.to_vec()) {
-                                        self.do_callback(&mut vec![
-                                            Event::TextInput(TextInputEvent {
-                                                input: utf8_string,
-                                                was_paste: true,
-                                                replace_last: false
-                                            })
-                                        ]);
-                                    }
-                                    X11_sys::XFree(ret as *mut _ as *mut c_void);
-                                }
+                                self.handle_paste_selection(&selection);
                             }
                         },
                         X11_sys::SelectionRequest => {
@@ -316,28 +288,29 @@ impl XlibApp {
                                 property: request.property,
                             };
                             if request.target == self.atom_targets {
-                                let mut targets = [self.atom_utf8_string];
+                                let mut targets = self.clipboard_targets();
                                 X11_sys::XChangeProperty(
                                     self.display,
                                     request.requestor,
                                     request.property,
-                                    4,
+                                    self.atom_atom,
                                     32,
                                     X11_sys::PropModeReplace as i32,
-                                    targets.as_mut() as *mut _ as *mut c_uchar,
+                                    targets.as_mut_ptr() as *mut _ as *mut c_uchar,
                                     targets.len() as i32
                                 );
                             }
-                            else if request.target == self.atom_utf8_string {
+                            else if let Some(data) = self.clipboard_bytes(request.target) {
+                                // no INCR transfers, so this is limited by the max request size of the server
                                 X11_sys::XChangeProperty(
                                     self.display,
                                     request.requestor,
                                     request.property,
-                                    self.atom_utf8_string,
+                                    request.target,
                                     8,
                                     X11_sys::PropModeReplace as i32,
-                                    self.clipboard.as_ptr() as *const _ as *const c_uchar,
-                                    self.clipboard.len() as i32
+                                    data.as_ptr() as *const _ as *const c_uchar,
+                                    data.len() as i32
                                 );
                             }
                             else {
@@ -566,48 +539,30 @@ impl XlibApp {
                                     if modifiers.control || modifiers.logo {
                                         match key_code {
                                             KeyCode::KeyV => { // paste
-                                                // request the pasteable text from the other side
+                                                // ask what the owner has to offer, the formats are fetched one by one after that
+                                                let window = window.window.unwrap();
+                                                self.paste = Some(XlibPaste {
+                                                    window,
+                                                    time: event.xkey.time,
+                                                    targets: Vec::new(),
+                                                    data: ClipboardData::default()
+                                                });
                                                 X11_sys::XConvertSelection(
                                                     self.display,
                                                     self.atom_clipboard,
-                                                    self.atom_utf8_string,
+                                                    self.atom_targets,
                                                     self.atom_clipboard,
-                                                    window.window.unwrap(),
+                                                    window,
                                                     event.xkey.time
                                                 );
-                                                /*
-                                                self.do_callback(&mut vec![
-                                                    Event::TextInput(TextInputEvent {
-                                                        input: String::new(),
-                                                        was_paste: true,
-                                                        replace_last: false
-                                                    })
-                                                ]);
-                                                */
                                             }
                                             KeyCode::KeyX | KeyCode::KeyC => {
-                                                let mut events = vec![
+                                                // the answer comes back through set_clipboard
+                                                self.do_callback(&mut vec![
                                                     Event::TextCopy(TextCopyEvent {
                                                         response: None
                                                     })
-                                                ];
-                                                self.do_callback(&mut events);
-                                                match &events[0] {
-                                                    Event::TextCopy(req) => if let Some(response) = &req.response {
-                                                        // store the text on the clipboard
-                                                        self.clipboard = response.clone();
-                                                        // lets set the owner
-                                                        println!("Set selection owner");
-                                                        X11_sys::XSetSelectionOwner(
-                                                            self.display,
-                                                            self.atom_clipboard,
-                                                            window.window.unwrap(),
-                                                            event.xkey.time
-                                                        );
-                                                        X11_sys::XFlush(self.display);
-                                                    },
-                                                    _ => ()
-                                                };
+                                                ]);
                                             }
                                             _ => ()
                                         }
@@ -625,10 +580,10 @@ impl XlibApp {
                                 
                                 if !block_text {
                                     // decode the character
-                                    let mut buffer = [0u8; 32];
+                                    let mut buffer = vec![0u8; 32];
                                     let mut keysym = mem::MaybeUninit::uninit();
                                     let mut status = mem::MaybeUninit::uninit();
-                                    let count = X11_sys::Xutf8LookupString(
+                                    let mut count = X11_sys::Xutf8LookupString(
                                         window.xic.unwrap(),
                                         &mut event.xkey,
                                         buffer.as_mut_ptr() as *mut c_char,
@@ -636,6 +591,18 @@ impl XlibApp {
                                         keysym.as_mut_ptr(),
                                         status.as_mut_ptr(),
                                     );
+                                    // a committed composition can be longer, count is the size it needs
+                                    if status.assume_init() == X11_sys::XBufferOverflow {
+                                        buffer = vec![0u8; count as usize];
+                                        count = X11_sys::Xutf8LookupString(
+                                            window.xic.unwrap(),
+                                            &mut event.xkey,
+                                            buffer.as_mut_ptr() as *mut c_char,
+                                            buffer.len() as c_int,
+                                            keysym.as_mut_ptr(),
+                                            status.as_mut_ptr(),
+                                        );
+                                    }
                                     //let keysym = keysym.assume_init();
                                     let status = status.assume_init();
                                     if status != X11_sys::XBufferOverflow {
@@ -654,6 +621,20 @@ impl XlibApp {
                                 }
                             }
                         },
+                        X11_sys::FocusIn => {
+                            if let Some(window_ptr) = self.window_map.get(&event.xfocus.window) {
+                                if let Some(xic) = (**window_ptr).xic {
+                                    X11_sys::XSetICFocus(xic);
+                                }
+                            }
+                        },
+                        X11_sys::FocusOut => {
+                            if let Some(window_ptr) = self.window_map.get(&event.xfocus.window) {
+                                if let Some(xic) = (**window_ptr).xic {
+                                    X11_sys::XUnsetICFocus(xic);
+                                }
+                            }
+                        },
                         X11_sys::KeyRelease => {
                             self.do_callback(&mut vec![Event::KeyUp(KeyEvent {
                                 key_code: self.xkeyevent_to_keycode(&mut event.xkey),
@@ -809,6 +790,159 @@ impl XlibApp {
         }
     }
     
+    // we own the clipboard from now on and serve it from SelectionRequest
+    pub fn set_clipboard(&mut self, data: ClipboardData) {
+        self.clipboard = data;
+        if let Some(window) = self.window_map.keys().next().cloned() {
+            unsafe {
+                X11_sys::XSetSelectionOwner(self.display, self.atom_clipboard, window, X11_sys::CurrentTime as c_ulong);
+                X11_sys::XFlush(self.display);
+            }
+        }
+    }
+    
+    fn intern_atom(&self, name: &str) -> X11_sys::Atom {
+        unsafe {X11_sys::XInternAtom(self.display, CString::new(name).unwrap().as_ptr(), 0)}
+    }
+    
+    fn atom_name(&self, atom: X11_sys::Atom) -> Option<String> {
+        unsafe {
+            let name = X11_sys::XGetAtomName(self.display, atom);
+            if name == ptr::null_mut() {
+                return None
+            }
+            let string = CStr::from_ptr(name).to_str().ok().map( | s | s.to_string());
+            X11_sys::XFree(name as *mut c_void);
+            string
+        }
+    }
+    
+    fn clipboard_targets(&self) -> Vec<X11_sys::Atom> {
+        let mut targets = vec![self.atom_targets];
+        for format in &self.clipboard.formats {
+            match format {
+                ClipboardFormat::Text(_) => targets.extend_from_slice(&[self.atom_utf8_string, self.atom_text_plain]),
+                ClipboardFormat::Html(_) => targets.push(self.atom_text_html),
+                ClipboardFormat::Custom {mime, ..} => targets.push(self.intern_atom(mime)),
+                ClipboardFormat::Image(_) => targets.push(self.atom_image_bmp),
+            }
+        }
+        targets
+    }
+    
+    fn clipboard_bytes(&self, target: X11_sys::Atom) -> Option<Vec<u8>> {
+        for format in &self.clipboard.formats {
+            match format {
+                ClipboardFormat::Text(text) if target == self.atom_utf8_string || target == self.atom_text_plain => {
+                    return Some(text.as_bytes().to_vec())
+                },
+                ClipboardFormat::Html(html) if target == self.atom_text_html => return Some(html.as_bytes().to_vec()),
+                ClipboardFormat::Custom {mime, data} if target == self.intern_atom(mime) => return Some(data.clone()),
+                ClipboardFormat::Image(image) if target == self.atom_image_bmp => return Some(encode_bmp(image)),
+                _ => ()
+            }
+        }
+        None
+    }
+    
+    // reads and deletes the property a selection was converted into, 32 bit items come as longs
+    unsafe fn take_selection_property(&self, selection: &X11_sys::XSelectionEvent) -> Option<(c_int, usize, Vec<u8>)> {
+        if selection.property == 0 {
+            return None
+        }
+        let mut actual_type = 0;
+        let mut actual_format = 0;
+        let mut n_items = 0;
+        let mut bytes_after = 0;
+        let mut ret = ptr::null_mut();
+        X11_sys::XGetWindowProperty(
+            self.display,
+            selection.requestor,
+            selection.property,
+            0,
+            c_long::max_value() / 4,
+            1,
+            X11_sys::AnyPropertyType as c_ulong,
+            &mut actual_type,
+            &mut actual_format,
+            &mut n_items,
+            &mut bytes_after,
+            &mut ret
+        );
+        if ret == ptr::null_mut() {
+            return None
+        }
+        let item_size = if actual_format == 32 {mem::size_of::<c_ulong>()} else {actual_format as usize / 8};
+        let bytes = slice::from_raw_parts(ret as *const u8, n_items as usize * item_size).to_vec();
+        X11_sys::XFree(ret as *mut c_void);
+        Some((actual_format, n_items as usize, bytes))
+    }
+    
+    // one step of a paste, the first answer is the list of targets and every next one a format
+    unsafe fn handle_paste_selection(&mut self, selection: &X11_sys::XSelectionEvent) {
+        let property = self.take_selection_property(selection);
+        let mut paste = if let Some(paste) = self.paste.take() {paste} else {return};
+        if selection.target == self.atom_targets {
+            let atoms = match &property {
+                Some((32, n_items, bytes)) => slice::from_raw_parts(bytes.as_ptr() as *const X11_sys::Atom, *n_items).to_vec(),
+                // an owner that doesnt list targets most likely still has text
+                _ => vec![self.atom_utf8_string]
+            };
+            let has = | atom | atoms.contains(&atom);
+            if has(self.atom_utf8_string) {
+                paste.targets.push(self.atom_utf8_string);
+            }
+            if has(self.atom_text_html) {
+                paste.targets.push(self.atom_text_html);
+            }
+            if has(self.atom_image_png) {
+                paste.targets.push(self.atom_image_png);
+            }
+            else if has(self.atom_image_bmp) {
+                paste.targets.push(self.atom_image_bmp);
+            }
+            for atom in &atoms {
+                if self.atom_name(*atom).map_or(false, | name | name.starts_with("application/x-")) {
+                    paste.targets.push(*atom);
+                }
+            }
+        }
+        else if let Some((_, _, bytes)) = property {
+            let target = selection.target;
+            if target == self.atom_utf8_string {
+                paste.data = paste.data.with_text(&String::from_utf8_lossy(&bytes));
+            }
+            else if target == self.atom_text_html {
+                // browsers put html on the clipboard as utf16 with a byte order mark
+                let html = if bytes.starts_with(&[0xff, 0xfe]) {
+                    let units: Vec<u16> = bytes[2..].chunks_exact(2).map( | c | u16::from_le_bytes([c[0], c[1]])).collect();
+                    String::from_utf16_lossy(&units)
+                }
+                else {
+                    String::from_utf8_lossy(&bytes).to_string()
+                };
+                paste.data = paste.data.with_html(&html);
+            }
+            else if target == self.atom_image_png || target == self.atom_image_bmp {
+                let options = ImageOptions {premultiply_alpha: false, ..ImageOptions::default()};
+                if let Ok(image) = DecodedImage::decode(&bytes, options) {
+                    paste.data = paste.data.with_image(image);
+                }
+            }
+            else if let Some(mime) = self.atom_name(target) {
+                paste.data = paste.data.with_custom(&mime, &bytes);
+            }
+        }
+        if paste.targets.len() > 0 {
+            let target = paste.targets.remove(0);
+            X11_sys::XConvertSelection(self.display, self.atom_clipboard, target, self.atom_clipboard, paste.window, paste.time);
+            self.paste = Some(paste);
+        }
+        else if !paste.data.is_empty() {
+            self.do_callback(&mut vec![Event::Paste(PasteEvent {data: paste.data})]);
+        }
+    }
+    
     pub fn terminate_event_loop(&mut self) {
         // maybe need to do more here
         self.event_loop_running = false;
@@ -1059,6 +1193,9 @@ impl XlibWindow {
             time_start: xlib_app.time_start,
             last_nc_mode: None,
             ime_spot: Vec2::default(),
+            ime_preedit: Vec::new(),
+            ime_styles: Vec::new(),
+            ime_caret: 0,
             current_cursor: MouseCursor::Default,
             last_mouse_pos: Vec2::default(),
             fingers_down: fingers_down,
@@ -1139,7 +1276,8 @@ impl XlibWindow {
             let title_bytes = format!("{}\0",title);
             X11_sys::XStoreName(display, window, title_bytes.as_bytes().as_ptr() as *const ::std::os::raw::c_char);
             
-            let xic = X11_sys::XCreateIC((*self.xlib_app).xim, CStr::from_bytes_with_nul(X11_sys::XNInputStyle.as_ref()).unwrap().as_ptr(), (X11_sys::XIMPreeditNothing | X11_sys::XIMStatusNothing) as i32, CStr::from_bytes_with_nul(X11_sys::XNClientWindow.as_ref()).unwrap().as_ptr(), window, CStr::from_bytes_with_nul(X11_sys::XNFocusWindow.as_ref()).unwrap().as_ptr(), window, ptr::null_mut() as *mut c_void);
+            let xic = self.create_xic(window);
+            X11_sys::XSetICFocus(xic);
             
             // Create a window
             (*self.xlib_app).window_map.insert(window, self);
@@ -1386,8 +1524,76 @@ impl XlibWindow {
         (time_now - self.time_start) as f64 / 1_000_000_000.0
     }
     
+    // asks the input method for its preedit so we can draw it inline, if it can't do that it shows its own window
+    unsafe fn create_xic(&mut self, window: c_ulong) -> X11_sys::XIC {
+        let xim = (*self.xlib_app).xim;
+        let name = | name: &[u8] | CStr::from_bytes_with_nul(name).unwrap().as_ptr();
+        let client_data = self as *mut XlibWindow as X11_sys::XPointer;
+        let start = X11_sys::XICCallback {client_data, callback: Some(xim_preedit_start)};
+        let done = X11_sys::XIMCallback {client_data, callback: Some(xim_preedit_done)};
+        let draw = X11_sys::XIMCallback {client_data, callback: Some(xim_preedit_draw)};
+        let caret = X11_sys::XIMCallback {client_data, callback: Some(xim_preedit_caret)};
+        let preedit_attributes = X11_sys::XVaCreateNestedList(
+            0,
+            name(X11_sys::XNPreeditStartCallback),
+            &start,
+            name(X11_sys::XNPreeditDoneCallback),
+            &done,
+            name(X11_sys::XNPreeditDrawCallback),
+            &draw,
+            name(X11_sys::XNPreeditCaretCallback),
+            &caret,
+            ptr::null_mut() as *mut c_void
+        );
+        let xic = X11_sys::XCreateIC(
+            xim,
+            name(X11_sys::XNInputStyle),
+            (X11_sys::XIMPreeditCallbacks | X11_sys::XIMStatusNothing) as c_ulong,
+            name(X11_sys::XNClientWindow),
+            window,
+            name(X11_sys::XNFocusWindow),
+            window,
+            name(X11_sys::XNPreeditAttributes),
+            preedit_attributes,
+            ptr::null_mut() as *mut c_void
+        );
+        X11_sys::XFree(preedit_attributes);
+        if xic != ptr::null_mut() {
+            return xic
+        }
+        X11_sys::XCreateIC(
+            xim,
+            name(X11_sys::XNInputStyle),
+            (X11_sys::XIMPreeditNothing | X11_sys::XIMStatusNothing) as c_ulong,
+            name(X11_sys::XNClientWindow),
+            window,
+            name(X11_sys::XNFocusWindow),
+            window,
+            ptr::null_mut() as *mut c_void
+        )
+    }
+    
     pub fn set_ime_spot(&mut self, spot: Vec2) {
         self.ime_spot = spot;
+        // the input method puts its candidate window there
+        if let Some(xic) = self.xic {
+            unsafe {
+                let name = | name: &[u8] | CStr::from_bytes_with_nul(name).unwrap().as_ptr();
+                let dpi_factor = self.last_window_geom.dpi_factor;
+                let spot = X11_sys::XPoint {x: (spot.x * dpi_factor) as i16, y: (spot.y * dpi_factor) as i16};
+                let preedit_attributes = X11_sys::XVaCreateNestedList(0, name(X11_sys::XNSpotLocation), &spot, ptr::null_mut() as *mut c_void);
+                X11_sys::XSetICValues(xic, name(X11_sys::XNPreeditAttributes), preedit_attributes, ptr::null_mut() as *mut c_void);
+                X11_sys::XFree(preedit_attributes);
+            }
+        }
+    }
+    
+    pub fn send_text_ime(&mut self) {
+        self.do_callback(&mut vec![Event::TextIme(TextImeEvent {
+            preedit: self.ime_preedit.iter().collect(),
+            cursor: self.ime_caret,
+            segments: TextImeEvent::segments_from_styles(&self.ime_styles)
+        })])
     }
     
     pub fn get_position(&self) -> Vec2 {
@@ -1607,6 +1813,82 @@ impl XlibWindow {
     
 }
 
+// the XIM preedit callbacks, client_data is the XlibWindow
+unsafe extern "C" fn xim_preedit_start(_xic: X11_sys::XIC, client_data: X11_sys::XPointer, _call_data: X11_sys::XPointer) -> c_int {
+    let window = &mut *(client_data as *mut XlibWindow);
+    window.ime_preedit.clear();
+    window.ime_styles.clear();
+    window.ime_caret = 0;
+    -1 // no limit on the preedit length
+}
+
+unsafe extern "C" fn xim_preedit_done(_xim: X11_sys::XIM, client_data: X11_sys::XPointer, _call_data: X11_sys::XPointer) {
+    let window = &mut *(client_data as *mut XlibWindow);
+    window.ime_preedit.clear();
+    window.ime_styles.clear();
+    window.ime_caret = 0;
+    window.send_text_ime();
+}
+
+unsafe extern "C" fn xim_preedit_draw(_xim: X11_sys::XIM, client_data: X11_sys::XPointer, call_data: X11_sys::XPointer) {
+    let window = &mut *(client_data as *mut XlibWindow);
+    let draw = &*(call_data as *mut X11_sys::XIMPreeditDrawCallbackStruct);
+    // the chars from chg_first to chg_first + chg_length are replaced with text
+    let len = window.ime_preedit.len();
+    let first = (draw.chg_first.max(0) as usize).min(len);
+    let end = (first + draw.chg_length.max(0) as usize).min(len);
+    let text = if draw.text.is_null() {None} else {Some(&*draw.text)};
+    let feedback_style = | text: &X11_sys::XIMText, index: usize | {
+        if text.feedback.is_null() || index >= text.length as usize {
+            return TextImeStyle::Input
+        }
+        let feedback = *text.feedback.add(index) as u32;
+        if feedback & (X11_sys::XIMReverse | X11_sys::XIMHighlight) != 0 {TextImeStyle::Target}
+        else if feedback & X11_sys::XIMUnderline != 0 {TextImeStyle::Converted}
+        else {TextImeStyle::Input}
+    };
+    match text {
+        Some(text) if text.string.multi_byte.is_null() => { // only the styles changed
+            for index in first..(first + text.length as usize).min(len) {
+                window.ime_styles[index] = feedback_style(text, index - first);
+            }
+        },
+        Some(text) => {
+            let chars: Vec<char> = if text.encoding_is_wchar != 0 {
+                slice::from_raw_parts(text.string.wide_char, text.length as usize).iter().filter_map( | c | std::char::from_u32(*c as u32)).collect()
+            }
+            else {
+                CStr::from_ptr(text.string.multi_byte).to_string_lossy().chars().collect()
+            };
+            let styles: Vec<TextImeStyle> = (0..chars.len()).map( | index | feedback_style(text, index)).collect();
+            window.ime_preedit.splice(first..end, chars);
+            window.ime_styles.splice(first..end, styles);
+        },
+        None => {
+            window.ime_preedit.drain(first..end);
+            window.ime_styles.drain(first..end);
+        }
+    }
+    window.ime_caret = (draw.caret.max(0) as usize).min(window.ime_preedit.len());
+    window.send_text_ime();
+}
+
+unsafe extern "C" fn xim_preedit_caret(_xim: X11_sys::XIM, client_data: X11_sys::XPointer, call_data: X11_sys::XPointer) {
+    let window = &mut *(client_data as *mut XlibWindow);
+    let caret = &mut *(call_data as *mut X11_sys::XIMPreeditCaretCallbackStruct);
+    let len = window.ime_preedit.len();
+    window.ime_caret = match caret.direction {
+        X11_sys::XIMCaretDirection_XIMAbsolutePosition => caret.position.max(0) as usize,
+        X11_sys::XIMCaretDirection_XIMForwardChar => window.ime_caret + 1,
+        X11_sys::XIMCaretDirection_XIMBackwardChar => window.ime_caret.max(1) - 1,
+        X11_sys::XIMCaretDirection_XIMLineStart => 0,
+        X11_sys::XIMCaretDirection_XIMLineEnd => len,
+        _ => window.ime_caret
+    }.min(len);
+    caret.position = window.ime_caret as c_int;
+    window.send_text_ime();
+}
+
 #[derive(Clone, Copy, PartialEq)]
 #[repr(C)]
 struct MwmHints {
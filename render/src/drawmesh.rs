@@ -0,0 +1,131 @@
+use crate::cx::*;
+
+// draws a 3d GeometryGen mesh with one transform and color, lit from the camera side
+#[repr(C)]
+pub struct DrawMesh {
+    pub shader: Shader,
+    pub area: Area,
+    pub slots: usize,
+    pub geometry: Option<Geometry>,
+
+    pub transform: Mat4,
+    pub color: Vec4,
+}
+
+impl Clone for DrawMesh {
+    fn clone(&self) -> Self {
+        Self {
+            shader: self.shader.clone(),
+            area: Area::Empty,
+            slots: self.slots,
+            geometry: self.geometry,
+            transform: self.transform,
+            color: self.color,
+        }
+    }
+}
+
+impl DrawMesh {
+
+    pub fn new(cx: &mut Cx, shader: Shader) -> Self {
+        Self::with_slots(cx, default_shader_overload!(cx, shader, self::shader), 0)
+    }
+
+    pub fn with_slots(_cx: &mut Cx, shader: Shader, slots: usize) -> Self {
+        Self {
+            shader: shader,
+            slots: slots + 20,
+            area: Area::Empty,
+            geometry: None,
+
+            transform: Mat4::identity(),
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_geometry(self, geometry: Geometry) -> Self {Self {geometry: Some(geometry), ..self}}
+    pub fn with_transform(self, transform: Mat4) -> Self {Self {transform, ..self}}
+    pub fn with_color(self, color: Vec4) -> Self {Self {color, ..self}}
+
+    pub fn live_draw_input() -> LiveDrawInput {
+        let mut def = LiveDrawInput::default();
+        let mp = module_path!();
+        def.add_instance(mp, "DrawMesh", "transform", Mat4::ty_expr());
+        def.add_instance(mp, "DrawMesh", "color", Vec4::ty_expr());
+        return def
+    }
+
+    pub fn register_draw_input(cx: &mut Cx) {
+        cx.live_styles.register_draw_input(live_item_id!(self::DrawMesh), Self::live_draw_input())
+    }
+
+    pub fn style(cx: &mut Cx) {
+
+        Self::register_draw_input(cx);
+
+        live_body!(cx, {
+            self::shader: Shader {
+                use crate::shader_std::prelude::*;
+                use crate::shader_std::geometry_3d::*;
+                default_geometry: crate::shader_std::cube_3d;
+                draw_input: self::DrawMesh;
+
+                varying lit_col: vec4;
+
+                fn vertex() -> vec4 {
+                    let model_view = view_transform * transform;
+                    let normal_matrix = mat3(model_view);
+                    let normal = normalize(normal_matrix * geom_normal);
+                    let light = 0.3 + 0.7 * max(dot(normal, normalize(vec3(0.3, 0.5, 1.0))), 0.);
+
+                    lit_col = vec4(color.rgb * light, color.a);
+                    return camera_projection * (camera_view * model_view * vec4(
+                        geom_pos.x,
+                        geom_pos.y,
+                        geom_pos.z + draw_zbias,
+                        1.
+                    ));
+                }
+
+                fn pixel() -> vec4 {
+                    return lit_col;
+                }
+            }
+        })
+
+    }
+
+    pub fn area(&self) -> Area {
+        self.area
+    }
+
+    pub fn set_geometry(&mut self, geometry: Option<Geometry>) {
+        self.geometry = geometry;
+    }
+
+    pub fn set_transform(&mut self, cx: &mut Cx, transform: Mat4) {
+        self.transform = transform;
+        write_draw_input!(cx, self.area(), self::DrawMesh::transform, transform);
+    }
+
+    pub fn set_color(&mut self, cx: &mut Cx, color: Vec4) {
+        self.color = color;
+        write_draw_input!(cx, self.area(), self::DrawMesh::color, color);
+    }
+
+    // meshes get their own draw call per geometry, without one it draws the shader default cube
+    pub fn draw_mesh(&mut self, cx: &mut Cx) {
+        self.area = if let Some(geometry) = self.geometry {
+            cx.add_geometry_instance(self.shader, geometry, self.as_slice())
+        }
+        else {
+            cx.add_instance(self.shader, self.as_slice())
+        };
+    }
+
+    pub fn as_slice<'a>(&'a self) -> &'a [f32] {
+        unsafe {
+            std::slice::from_raw_parts(&self.transform as *const _ as *const f32, self.slots)
+        }
+    }
+}
@@ -2,8 +2,10 @@ use crate::cx::*;
 use makepad_microserde::*;
 use std::any::TypeId;
 use std::collections::{HashMap,BTreeSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default, SerBin, DeBin)]
 pub struct KeyModifiers {
     pub shift: bool,
     pub control: bool,
@@ -11,7 +13,7 @@ pub struct KeyModifiers {
     pub logo: bool
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub enum FingerInputType{
     Mouse,
     Touch,
@@ -29,7 +31,7 @@ impl Default for FingerInputType{
     fn default()->Self{Self::Mouse}
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerDownEvent {
     pub window_id: usize,
     pub abs: Vec2,
@@ -43,7 +45,7 @@ pub struct FingerDownEvent {
     pub time: f64
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerMoveEvent {
     pub window_id: usize,
     pub abs: Vec2,
@@ -64,7 +66,7 @@ impl FingerMoveEvent {
     }
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerUpEvent {
     pub window_id: usize,
     pub abs: Vec2,
@@ -79,7 +81,7 @@ pub struct FingerUpEvent {
     pub time: f64
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub enum HoverState {
     In,
     Over,
@@ -92,7 +94,7 @@ impl Default for HoverState {
     }
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerHoverEvent {
     pub window_id: usize,
     pub digit: usize,
@@ -106,7 +108,7 @@ pub struct FingerHoverEvent {
     pub time: f64
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerScrollEvent {
     pub window_id: usize,
     pub digit: usize,
@@ -122,39 +124,49 @@ pub struct FingerScrollEvent {
     pub time: f64
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowGeomChangeEvent {
     pub window_id: usize,
     pub old_geom: WindowGeom,
     pub new_geom: WindowGeom,
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowMovedEvent {
     pub window_id: usize,
     pub old_pos: Vec2,
     pub new_pos: Vec2,
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct AnimateEvent {
     pub frame: u64,
-    pub time: f64
+    pub time: f64,
+    // the anim events an animator passed since its last frame, filled in by is_animate
+    pub timeline_events: Vec<TimelineEvent>
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub struct TimelineEvent {
+    // the layer that played it, None for the main anim
+    pub layer: Option<LiveItemId>,
+    pub event: LiveItemId,
+    pub key_time: f64
+}
+
+#[derive(Clone, Default, Debug, PartialEq, SerBin, DeBin)]
 pub struct NextFrameEvent {
     pub frame: u64,
     pub time: f64
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct FileReadEvent {
     pub read_id: u64,
     pub data: Result<Vec<u8>, String>
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct TimerEvent {
     pub timer_id: u64
 }
@@ -186,7 +198,7 @@ pub struct LiveRecompileEvent {
     pub errors: Vec<LiveBodyError>
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct KeyEvent {
     pub key_code: KeyCode,
     //pub key_char: char,
@@ -195,42 +207,100 @@ pub struct KeyEvent {
     pub time: f64
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct KeyFocusEvent {
     pub prev: Area,
     pub focus: Area,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct TextInputEvent {
     pub input: String,
     pub replace_last: bool,
     pub was_paste: bool
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// an input method composition, the preedit is shown at the caret but not part of the text yet.
+// An empty preedit ends the composition, what gets committed arrives as a TextInput
+#[derive(Clone, Debug, Default, PartialEq, SerBin, DeBin)]
+pub struct TextImeEvent {
+    pub preedit: String,
+    // caret and segments count chars in the preedit
+    pub cursor: usize,
+    pub segments: Vec<TextImeSegment>,
+}
+
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
+pub struct TextImeSegment {
+    pub start: usize,
+    pub end: usize,
+    pub style: TextImeStyle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerBin, DeBin)]
+pub enum TextImeStyle {
+    // typed and not converted yet
+    Input,
+    // converted by the input method
+    Converted,
+    // the part that is being converted right now
+    Target,
+}
+
+impl TextImeEvent {
+    pub fn is_empty(&self) -> bool {
+        self.preedit.len() == 0
+    }
+    
+    // a segment per run of equal styles
+    pub fn segments_from_styles(styles: &[TextImeStyle]) -> Vec<TextImeSegment> {
+        let mut segments: Vec<TextImeSegment> = Vec::new();
+        for (index, style) in styles.iter().enumerate() {
+            if let Some(last) = segments.last_mut() {
+                if last.style == *style {
+                    last.end = index + 1;
+                    continue
+                }
+            }
+            segments.push(TextImeSegment {start: index, end: index + 1, style: *style});
+        }
+        segments
+    }
+    
+    // the style of a char in the preedit
+    pub fn style_at(&self, index: usize) -> TextImeStyle {
+        for segment in &self.segments {
+            if index >= segment.start && index < segment.end {
+                return segment.style
+            }
+        }
+        TextImeStyle::Input
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct TextCopyEvent {
     pub response: Option<String>
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowCloseRequestedEvent {
     pub window_id: usize,
     pub accept_close: bool
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowClosedEvent {
     pub window_id: usize
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowResizeLoopEvent {
     pub was_started: bool,
     pub window_id: usize
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub enum WindowDragQueryResponse {
     NoAnswer,
     Client,
@@ -238,7 +308,7 @@ pub enum WindowDragQueryResponse {
     SysMenu, // windows only
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct WindowDragQueryEvent {
     pub window_id: usize,
     pub abs: Vec2,
@@ -274,13 +344,13 @@ pub struct XRUpdateEvent {
     pub other_inputs: Vec<XRInput>
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct WebSocketMessageEvent{
     pub url: String, 
     pub result: Result<Vec<u8>, String>
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub struct FingerDragEvent {
     pub handled: bool,
     pub abs: Vec2,
@@ -290,14 +360,14 @@ pub struct FingerDragEvent {
     pub action: DragAction,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, SerBin, DeBin)]
 pub enum DragState {
     In,
     Over,
     Out,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, SerBin, DeBin)]
 pub enum DragAction {
     None,
     Copy,
@@ -342,7 +412,9 @@ pub enum Event {
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
     TextInput(TextInputEvent),
+    TextIme(TextImeEvent),
     TextCopy(TextCopyEvent),
+    Paste(PasteEvent),
     LiveRecompile(LiveRecompileEvent),
     WebSocketMessage(WebSocketMessageEvent),
     FingerDrag(FingerDragEvent),
@@ -383,7 +455,9 @@ impl Event {
          match self {
             Event::Animate(ae) => {
                 if cx.playing_animator_ids.get(&animator.animator_id).is_some(){
-                    return Some(ae.clone())
+                    let mut ae = ae.clone();
+                    ae.timeline_events = animator.crossed_timeline_events(cx, ae.time);
+                    return Some(ae)
                 }
             }
             _=>()
@@ -416,6 +490,11 @@ impl Event {
                     return self.clone();
                 }
             },
+            Event::TextIme(_) => {
+                if area == cx.key_focus {
+                    return self.clone();
+                }
+            },
             Event::TextCopy(_) => {
                 if area == cx.key_focus {
                     return Event::TextCopy(
@@ -423,6 +502,11 @@ impl Event {
                     );
                 }
             },
+            Event::Paste(_) => {
+                if area == cx.key_focus {
+                    return self.clone();
+                }
+            },
             Event::Triggers(te) => {
                 if let Some(triggers) = te.triggers.get(&area).cloned(){
                     return Event::Trigger(TriggerEvent{triggers})
@@ -652,22 +736,30 @@ impl Signal {
 // Status
 
 
+// statuses and triggers hold the hash of the TypeId uid!() made them from, a TypeId
+// can't be written to an event log but its hash can, and stays the same in one build
+fn type_id_hash(type_id: TypeId) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    type_id.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[derive(PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Eq, Debug)]
-pub struct StatusId(pub TypeId);
+pub struct StatusId(pub u64);
 
 impl Default for StatusId{
     fn default()->StatusId{uid!()}
 }
 
 impl Into<StatusId> for TypeId {
-    fn into(self) -> StatusId {StatusId(self)}
+    fn into(self) -> StatusId {StatusId(type_id_hash(self))}
 }
 
 #[derive(PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Eq, Debug)]
-pub struct TriggerId(pub TypeId);
+pub struct TriggerId(pub u64);
 
 impl Into<TriggerId> for TypeId {
-    fn into(self) -> TriggerId {TriggerId(self)}
+    fn into(self) -> TriggerId {TriggerId(type_id_hash(self))}
 }
 
 
@@ -754,7 +846,7 @@ impl Event {
 }
 
 // lowest common denominator keymap between desktop and web
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, SerBin, DeBin)]
 pub enum KeyCode {
     Escape,
     
@@ -14,9 +14,9 @@ impl Cx {
         for font in &mut self.fonts {
             font.atlas_pages.truncate(0);
         }
-        self.fonts_atlas.alloc_xpos = 0.;
-        self.fonts_atlas.alloc_ypos = 0.;
-        self.fonts_atlas.alloc_hmax = 0.;
+        self.fonts_atlas.alloc.clear();
+        self.fonts_atlas.atlas_todo.truncate(0);
+        self.fonts_atlas.full = false;
         self.fonts_atlas.clear_buffer = true;
         self.redraw_child_area(Area::All);
     }
@@ -274,7 +274,8 @@ pub struct CxAfterDraw {
 
 impl CxAfterDraw {
     pub fn new(cx: &mut Cx) -> Self {
-        cx.fonts_atlas.texture_size = Vec2 {x: 2048.0, y: 2048.0};
+        cx.fonts_atlas.alloc = TextureAtlas::new(2048, 2048);
+        cx.fonts_atlas.texture_size = cx.fonts_atlas.alloc.size();
         let atlas_texture = Texture::new(cx);
         
         cx.fonts_atlas.texture_id = atlas_texture.texture_id;
@@ -292,6 +293,11 @@ impl CxAfterDraw {
     pub fn after_draw(&mut self, cx: &mut Cx) {
         //let start = Cx::profile_time_ns();
         
+        // glyphs that didn't fit were drawn empty, start over with a clean atlas
+        if cx.fonts_atlas.full {
+            cx.reset_font_atlas_and_redraw();
+        }
+        
         // we need to start a pass that just uses the texture
         if cx.fonts_atlas.atlas_todo.len()>0 {
             self.atlas_pass.begin_pass(cx);
@@ -363,34 +369,26 @@ pub struct CxFontsAtlas {
     pub texture_id: u32,
     pub texture_size: Vec2,
     pub clear_buffer: bool,
-    pub alloc_xpos: f32,
-    pub alloc_ypos: f32,
-    pub alloc_hmax: f32,
+    pub alloc: TextureAtlas,
+    pub full: bool,
     pub atlas_todo: Vec<CxFontsAtlasTodo>,
 }
 
 impl CxFontsAtlas {
     pub fn alloc_atlas_glyph(&mut self, path: &str, w: f32, h: f32) -> CxFontAtlasGlyph {
-        if w + self.alloc_xpos >= self.texture_size.x {
-            self.alloc_xpos = 0.0;
-            self.alloc_ypos += self.alloc_hmax + 1.0;
-            self.alloc_hmax = 0.0;
-        }
-        if h + self.alloc_ypos >= self.texture_size.y {
-            println!("FONT ATLAS FULL {}, TODO FIX THIS", path);
+        let rect = if let Some(id) = self.alloc.alloc(w.ceil() as usize, h.ceil() as usize) {
+            self.alloc.rect(id).unwrap()
         }
-        if h > self.alloc_hmax {
-            self.alloc_hmax = h;
-        }
-        
-        let tx1 = self.alloc_xpos / self.texture_size.x;
-        let ty1 = self.alloc_ypos / self.texture_size.y;
-        
-        self.alloc_xpos += w + 1.0;
+        else {
+            if !self.full {
+                println!("Font atlas full while adding a glyph of {}, resetting it", path);
+                self.full = true;
+            }
+            return CxFontAtlasGlyph {tx1: 0., ty1: 0., tx2: 0., ty2: 0.}
+        };
         
-        if h > self.alloc_hmax {
-            self.alloc_hmax = h;
-        }
+        let tx1 = rect.x as f32 / self.texture_size.x;
+        let ty1 = rect.y as f32 / self.texture_size.y;
         
         CxFontAtlasGlyph {
             tx1: tx1,
@@ -1,3 +1,6 @@
+use crate::cx::*;
+use std::f32::consts::PI;
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct GeometryGen {
     pub vertices: Vec<f32>, // vec4 pos, vec3 normal, vec2 uv
@@ -32,6 +35,37 @@ impl GeometryGen {
         g
     }
     
+    pub fn from_sphere_3d(radius: f32, width_segments: usize, height_segments: usize) -> GeometryGen {
+        let mut g = Self::default();
+        g.add_sphere_3d(radius, width_segments, height_segments, 0.0);
+        g
+    }
+    
+    pub fn from_cylinder_3d(
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        radial_segments: usize,
+        height_segments: usize,
+        capped: bool
+    ) -> GeometryGen {
+        let mut g = Self::default();
+        g.add_cylinder_3d(radius_top, radius_bottom, height, radial_segments, height_segments, capped, 0.0);
+        g
+    }
+    
+    pub fn from_torus_3d(radius: f32, tube: f32, radial_segments: usize, tubular_segments: usize) -> GeometryGen {
+        let mut g = Self::default();
+        g.add_torus_3d(radius, tube, radial_segments, tubular_segments, 0.0);
+        g
+    }
+    
+    pub fn from_grid_plane_3d(width: f32, depth: f32, width_segments: usize, depth_segments: usize) -> GeometryGen {
+        let mut g = Self::default();
+        g.add_grid_plane_3d(width, depth, width_segments, depth_segments, 0.0);
+        g
+    }
+    
     // requires pos:vec2 normalized layout
     pub fn add_quad_2d(&mut self, x1:f32, y1:f32, x2:f32, y2:f32) {
         let vertex_offset = self.vertices.len() as u32;
@@ -137,4 +171,168 @@ impl GeometryGen {
             }
         }
     }
+    
+    // requires pos:vec3, id:float, normal:vec3, uv:vec2 layout
+    pub fn vertex_count_3d(&self) -> usize {
+        self.vertices.len() / 9
+    }
+    
+    pub fn add_vertex_3d(&mut self, pos: Vec3, id: f32, normal: Vec3, uv: Vec2) -> u32 {
+        let index = self.vertex_count_3d() as u32;
+        self.vertices.extend_from_slice(&[pos.x, pos.y, pos.z, id, normal.x, normal.y, normal.z, uv.x, uv.y]);
+        index
+    }
+
+    // merges another 3d geometry in, its indices shifted past our vertices
+    pub fn append_3d(&mut self, other: &GeometryGen) {
+        let offset = self.vertex_count_3d() as u32;
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map( | i | i + offset));
+    }
+
+    // indices of a (columns + 1) x (rows + 1) vertex grid starting at offset, counter clockwise seen from the outside
+    fn add_grid_indices_3d(&mut self, offset: u32, columns: usize, rows: usize) {
+        let stride = columns as u32 + 1;
+        for iy in 0..rows as u32 {
+            for ix in 0..columns as u32 {
+                let a = offset + iy * stride + ix;
+                let b = offset + (iy + 1) * stride + ix;
+                let c = offset + (iy + 1) * stride + ix + 1;
+                let d = offset + iy * stride + ix + 1;
+                self.indices.extend_from_slice(&[a, b, d, b, c, d]);
+            }
+        }
+    }
+    
+    // uv sphere around the origin, the poles are on the y axis
+    pub fn add_sphere_3d(&mut self, radius: f32, width_segments: usize, height_segments: usize, id: f32) {
+        let width_segments = width_segments.max(3);
+        let height_segments = height_segments.max(2);
+        let offset = self.vertex_count_3d() as u32;
+        for iy in 0..=height_segments {
+            let v = iy as f32 / height_segments as f32;
+            for ix in 0..=width_segments {
+                let u = ix as f32 / width_segments as f32;
+                let normal = Vec3 {
+                    x: -(u * 2.0 * PI).cos() * (v * PI).sin(),
+                    y: (v * PI).cos(),
+                    z: (u * 2.0 * PI).sin() * (v * PI).sin()
+                };
+                self.add_vertex_3d(normal * radius, id, normal, vec2(u, 1.0 - v));
+            }
+        }
+        self.add_grid_indices_3d(offset, width_segments, height_segments);
+    }
+    
+    // centered on the origin along the y axis, a radius of 0 makes a cone
+    pub fn add_cylinder_3d(
+        &mut self,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        radial_segments: usize,
+        height_segments: usize,
+        capped: bool,
+        id: f32
+    ) {
+        let radial_segments = radial_segments.max(3);
+        let height_segments = height_segments.max(1);
+        let half_height = height / 2.0;
+        // the side normals lean with the slope of the cone
+        let slope = (radius_bottom - radius_top) / height;
+        let offset = self.vertex_count_3d() as u32;
+        for iy in 0..=height_segments {
+            let v = iy as f32 / height_segments as f32;
+            let radius = radius_top + v * (radius_bottom - radius_top);
+            for ix in 0..=radial_segments {
+                let u = ix as f32 / radial_segments as f32;
+                let (sin, cos) = (u * 2.0 * PI).sin_cos();
+                let normal = Vec3 {x: sin, y: slope, z: cos}.normalize();
+                self.add_vertex_3d(vec3(radius * sin, half_height - v * height, radius * cos), id, normal, vec2(u, 1.0 - v));
+            }
+        }
+        self.add_grid_indices_3d(offset, radial_segments, height_segments);
+        if capped {
+            for (radius, y, sign) in [(radius_top, half_height, 1.0), (radius_bottom, -half_height, -1.0)].iter() {
+                if *radius <= 0.0 {
+                    continue;
+                }
+                let normal = vec3(0.0, *sign, 0.0);
+                let center = self.add_vertex_3d(vec3(0.0, *y, 0.0), id, normal, vec2(0.5, 0.5));
+                for ix in 0..=radial_segments {
+                    let (sin, cos) = (ix as f32 / radial_segments as f32 * 2.0 * PI).sin_cos();
+                    self.add_vertex_3d(vec3(radius * sin, *y, radius * cos), id, normal, vec2(0.5 + sin * 0.5, 0.5 + cos * 0.5 * sign));
+                }
+                for ix in 0..radial_segments as u32 {
+                    if *sign > 0.0 {
+                        self.indices.extend_from_slice(&[center, center + ix + 1, center + ix + 2]);
+                    }
+                    else {
+                        self.indices.extend_from_slice(&[center, center + ix + 2, center + ix + 1]);
+                    }
+                }
+            }
+        }
+    }
+    
+    // ring in the xy plane around the origin, radius to the middle of the tube
+    pub fn add_torus_3d(&mut self, radius: f32, tube: f32, radial_segments: usize, tubular_segments: usize, id: f32) {
+        let radial_segments = radial_segments.max(3);
+        let tubular_segments = tubular_segments.max(3);
+        let offset = self.vertex_count_3d() as u32;
+        for j in 0..=radial_segments {
+            let v = j as f32 / radial_segments as f32 * 2.0 * PI;
+            for i in 0..=tubular_segments {
+                let u = i as f32 / tubular_segments as f32 * 2.0 * PI;
+                let center = vec3(radius * u.cos(), radius * u.sin(), 0.0);
+                let pos = vec3(
+                    (radius + tube * v.cos()) * u.cos(),
+                    (radius + tube * v.cos()) * u.sin(),
+                    -tube * v.sin()
+                );
+                let normal = (pos - center).normalize();
+                self.add_vertex_3d(pos, id, normal, vec2(i as f32 / tubular_segments as f32, j as f32 / radial_segments as f32));
+            }
+        }
+        self.add_grid_indices_3d(offset, tubular_segments, radial_segments);
+    }
+    
+    // flat grid in the xz plane facing up, handy as a floor or for displacement in a shader
+    pub fn add_grid_plane_3d(&mut self, width: f32, depth: f32, width_segments: usize, depth_segments: usize, id: f32) {
+        let width_segments = width_segments.max(1);
+        let depth_segments = depth_segments.max(1);
+        let offset = self.vertex_count_3d() as u32;
+        for iz in 0..=depth_segments {
+            let v = iz as f32 / depth_segments as f32;
+            for ix in 0..=width_segments {
+                let u = ix as f32 / width_segments as f32;
+                self.add_vertex_3d(vec3((u - 0.5) * width, 0.0, (v - 0.5) * depth), id, vec3(0.0, 1.0, 0.0), vec2(u, 1.0 - v));
+            }
+        }
+        self.add_grid_indices_3d(offset, width_segments, depth_segments);
+    }
+    
+    // smooth normals from the triangles, for meshes loaded without any
+    pub fn compute_normals_3d(&mut self) {
+        let count = self.vertex_count_3d();
+        let mut normals = vec![Vec3::all(0.0); count];
+        let pos = | v: &Vec<f32>, i: usize | vec3(v[i * 9], v[i * 9 + 1], v[i * 9 + 2]);
+        for tri in self.indices.chunks(3) {
+            if tri.len() < 3 {
+                break;
+            }
+            let (a, b, c) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+            // unnormalized, so bigger triangles weigh more
+            let normal = Vec3::cross(pos(&self.vertices, b) - pos(&self.vertices, a), pos(&self.vertices, c) - pos(&self.vertices, a));
+            for i in [a, b, c].iter() {
+                normals[*i] += normal;
+            }
+        }
+        for (i, normal) in normals.iter().enumerate() {
+            let normal = normal.normalize();
+            self.vertices[i * 9 + 4] = normal.x;
+            self.vertices[i * 9 + 5] = normal.y;
+            self.vertices[i * 9 + 6] = normal.z;
+        }
+    }
 }
@@ -0,0 +1,463 @@
+use crate::cx::*;
+use std::f32::consts::PI;
+
+// gesture recognizers on top of the finger events. The handle_* functions hit test an area with multi touch on,
+// handle_finger_event takes events that were already hit tested so a sequence can be fed in by hand
+
+impl HitOpt {
+    pub fn multi_touch() -> Self {
+        HitOpt {use_multi_touch: true, ..HitOpt::default()}
+    }
+}
+
+// the fingers that are down on a gesture, in the order they went down
+#[derive(Clone, Debug, Default)]
+pub struct GestureFingers {
+    pub fingers: Vec<(usize, Vec2)>
+}
+
+impl GestureFingers {
+    pub fn handle_finger_event(&mut self, event: &Event) {
+        match event {
+            Event::FingerDown(fe) => {
+                if !self.fingers.iter().any( | (digit, _) | *digit == fe.digit) {
+                    self.fingers.push((fe.digit, fe.abs));
+                }
+            },
+            Event::FingerMove(fe) => {
+                if let Some(finger) = self.fingers.iter_mut().find( | (digit, _) | *digit == fe.digit) {
+                    finger.1 = fe.abs;
+                }
+            },
+            Event::FingerUp(fe) => {
+                self.fingers.retain( | (digit, _) | *digit != fe.digit);
+            },
+            _ => ()
+        }
+    }
+    
+    // the first two fingers, pinch and rotate look at these
+    pub fn pair(&self) -> Option<(Vec2, Vec2)> {
+        if self.fingers.len() >= 2 {
+            Some((self.fingers[0].1, self.fingers[1].1))
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TapEvent {
+    None,
+    Tap {abs: Vec2, count: u32},
+}
+
+// a short press without moving. With taps at 2 it only fires on the second tap of a double tap
+#[derive(Clone)]
+pub struct TapRecognizer {
+    pub taps: u32,
+    pub max_move: f32,
+    pub max_duration: f64,
+    pub multi_tap_time: f64,
+    pub multi_tap_distance: f32,
+    pub _down: Option<(usize, Vec2, f64)>,
+    pub _cancelled: bool,
+    pub _last_tap: Option<(Vec2, f64, u32)>,
+}
+
+impl TapRecognizer {
+    pub fn new() -> Self {
+        Self {
+            taps: 1,
+            max_move: 8.0,
+            max_duration: 0.5,
+            multi_tap_time: 0.35,
+            multi_tap_distance: 20.0,
+            _down: None,
+            _cancelled: false,
+            _last_tap: None,
+        }
+    }
+    
+    pub fn double_tap() -> Self {
+        Self {taps: 2, ..Self::new()}
+    }
+    
+    pub fn handle_tap(&mut self, cx: &mut Cx, event: &mut Event, area: Area) -> TapEvent {
+        let event = event.hits(cx, area, HitOpt::multi_touch());
+        self.handle_finger_event(&event)
+    }
+    
+    pub fn handle_finger_event(&mut self, event: &Event) -> TapEvent {
+        match event {
+            Event::FingerDown(fe) => {
+                if self._down.is_none() {
+                    self._down = Some((fe.digit, fe.abs, fe.time));
+                    self._cancelled = false;
+                }
+                else { // a second finger isnt a tap
+                    self._cancelled = true;
+                }
+            },
+            Event::FingerMove(fe) => if let Some((digit, abs, _)) = self._down {
+                if digit == fe.digit && fe.abs.distance(&abs) > self.max_move {
+                    self._cancelled = true;
+                }
+            },
+            Event::FingerUp(fe) => if let Some((digit, abs, time)) = self._down {
+                if digit != fe.digit {
+                    return TapEvent::None
+                }
+                self._down = None;
+                if self._cancelled || !fe.is_over || fe.time - time > self.max_duration || fe.abs.distance(&abs) > self.max_move {
+                    self._last_tap = None;
+                    return TapEvent::None
+                }
+                let count = match self._last_tap {
+                    Some((last_abs, last_time, count)) if fe.time - last_time <= self.multi_tap_time
+                        && fe.abs.distance(&last_abs) <= self.multi_tap_distance => count + 1,
+                    _ => 1
+                };
+                if self.taps > 1 {
+                    if count >= self.taps {
+                        self._last_tap = None;
+                        return TapEvent::Tap {abs: fe.abs, count}
+                    }
+                    self._last_tap = Some((fe.abs, fe.time, count));
+                    return TapEvent::None
+                }
+                self._last_tap = Some((fe.abs, fe.time, count));
+                return TapEvent::Tap {abs: fe.abs, count}
+            },
+            _ => ()
+        }
+        TapEvent::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LongPressEvent {
+    None,
+    LongPress {abs: Vec2},
+}
+
+// a press held in place for a while, the timer makes it fire without the finger moving
+#[derive(Clone)]
+pub struct LongPressRecognizer {
+    pub duration: f64,
+    pub max_move: f32,
+    pub _down: Option<(usize, Vec2, f64)>,
+    pub _fired: bool,
+    pub _timer: Timer,
+}
+
+impl LongPressRecognizer {
+    pub fn new() -> Self {
+        Self {
+            duration: 0.5,
+            max_move: 8.0,
+            _down: None,
+            _fired: false,
+            _timer: Timer::empty(),
+        }
+    }
+    
+    pub fn handle_long_press(&mut self, cx: &mut Cx, event: &mut Event, area: Area) -> LongPressEvent {
+        if let Event::Timer(te) = event {
+            if self._timer.is_timer(te) {
+                self._timer = Timer::empty();
+                if let Some((_, _, time)) = self._down {
+                    return self.handle_time(time + self.duration)
+                }
+            }
+            return LongPressEvent::None
+        }
+        let event = event.hits(cx, area, HitOpt::multi_touch());
+        let ret = self.handle_finger_event(&event);
+        match event {
+            Event::FingerDown(_) => if self._down.is_some() && !self._fired && self._timer.is_empty() {
+                self._timer = cx.start_timer(self.duration, false);
+            },
+            Event::FingerUp(_) | Event::FingerMove(_) => if self._down.is_none() || self._fired {
+                cx.stop_timer(&mut self._timer);
+            },
+            _ => ()
+        }
+        ret
+    }
+    
+    pub fn handle_finger_event(&mut self, event: &Event) -> LongPressEvent {
+        match event {
+            Event::FingerDown(fe) => if self._down.is_none() {
+                self._down = Some((fe.digit, fe.abs, fe.time));
+                self._fired = false;
+            },
+            Event::FingerMove(fe) => if let Some((digit, abs, _)) = self._down {
+                if digit == fe.digit {
+                    if fe.abs.distance(&abs) > self.max_move {
+                        self._down = None;
+                    }
+                    else {
+                        return self.handle_time(fe.time)
+                    }
+                }
+            },
+            Event::FingerUp(fe) => if let Some((digit, _, _)) = self._down {
+                if digit == fe.digit {
+                    self._down = None;
+                }
+            },
+            _ => ()
+        }
+        LongPressEvent::None
+    }
+    
+    // fires once the finger has been down long enough
+    pub fn handle_time(&mut self, time: f64) -> LongPressEvent {
+        if let Some((_, abs, down_time)) = self._down {
+            if !self._fired && time - down_time >= self.duration {
+                self._fired = true;
+                return LongPressEvent::LongPress {abs}
+            }
+        }
+        LongPressEvent::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PanEvent {
+    None,
+    Start {abs_start: Vec2, abs: Vec2},
+    // total is measured from where the pan started, past the slop
+    Move {abs: Vec2, delta: Vec2, total: Vec2},
+    End {abs: Vec2, total: Vec2, velocity: Vec2},
+}
+
+// a drag of the first finger once it moved past min_distance
+#[derive(Clone)]
+pub struct PanRecognizer {
+    pub min_distance: f32,
+    pub _digit: Option<usize>,
+    pub _abs_start: Vec2,
+    pub _pan_start: Vec2,
+    pub _last: Vec2,
+    pub _last_time: f64,
+    pub _velocity: Vec2,
+    pub _panning: bool,
+}
+
+impl PanRecognizer {
+    pub fn new() -> Self {
+        Self {
+            min_distance: 8.0,
+            _digit: None,
+            _abs_start: Vec2::zero(),
+            _pan_start: Vec2::zero(),
+            _last: Vec2::zero(),
+            _last_time: 0.0,
+            _velocity: Vec2::zero(),
+            _panning: false,
+        }
+    }
+    
+    pub fn is_panning(&self) -> bool {
+        self._panning
+    }
+    
+    pub fn handle_pan(&mut self, cx: &mut Cx, event: &mut Event, area: Area) -> PanEvent {
+        let event = event.hits(cx, area, HitOpt::multi_touch());
+        self.handle_finger_event(&event)
+    }
+    
+    pub fn handle_finger_event(&mut self, event: &Event) -> PanEvent {
+        match event {
+            Event::FingerDown(fe) => if self._digit.is_none() {
+                self._digit = Some(fe.digit);
+                self._abs_start = fe.abs;
+                self._last = fe.abs;
+                self._last_time = fe.time;
+                self._velocity = Vec2::zero();
+                self._panning = false;
+            },
+            Event::FingerMove(fe) => if self._digit == Some(fe.digit) {
+                if !self._panning {
+                    if fe.abs.distance(&self._abs_start) < self.min_distance {
+                        return PanEvent::None
+                    }
+                    self._panning = true;
+                    self._pan_start = fe.abs;
+                    self._last = fe.abs;
+                    self._last_time = fe.time;
+                    return PanEvent::Start {abs_start: self._abs_start, abs: fe.abs}
+                }
+                let delta = fe.abs - self._last;
+                let dt = fe.time - self._last_time;
+                if dt > 0.0 {
+                    self._velocity = delta / dt as f32;
+                }
+                self._last = fe.abs;
+                self._last_time = fe.time;
+                return PanEvent::Move {abs: fe.abs, delta, total: fe.abs - self._pan_start}
+            },
+            Event::FingerUp(fe) => if self._digit == Some(fe.digit) {
+                self._digit = None;
+                if self._panning {
+                    self._panning = false;
+                    // a finger that stopped before lifting doesnt fling
+                    let velocity = if fe.time - self._last_time > 0.1 {Vec2::zero()} else {self._velocity};
+                    return PanEvent::End {abs: fe.abs, total: fe.abs - self._pan_start, velocity}
+                }
+            },
+            _ => ()
+        }
+        PanEvent::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PinchEvent {
+    None,
+    Start {center: Vec2},
+    // scale is relative to the distance between the fingers when the pinch started
+    Pinch {center: Vec2, scale: f32, delta: f32},
+    End {scale: f32},
+}
+
+// two fingers moving apart or together
+#[derive(Clone)]
+pub struct PinchRecognizer {
+    pub min_scale: f32,
+    pub _fingers: GestureFingers,
+    pub _start_distance: Option<f32>,
+    pub _last_scale: f32,
+    pub _pinching: bool,
+}
+
+impl PinchRecognizer {
+    pub fn new() -> Self {
+        Self {
+            min_scale: 0.05,
+            _fingers: GestureFingers::default(),
+            _start_distance: None,
+            _last_scale: 1.0,
+            _pinching: false,
+        }
+    }
+    
+    pub fn handle_pinch(&mut self, cx: &mut Cx, event: &mut Event, area: Area) -> PinchEvent {
+        let event = event.hits(cx, area, HitOpt::multi_touch());
+        self.handle_finger_event(&event)
+    }
+    
+    pub fn handle_finger_event(&mut self, event: &Event) -> PinchEvent {
+        self._fingers.handle_finger_event(event);
+        let (a, b) = if let Some(pair) = self._fingers.pair() {pair} else {
+            self._start_distance = None;
+            if self._pinching {
+                self._pinching = false;
+                return PinchEvent::End {scale: self._last_scale}
+            }
+            return PinchEvent::None
+        };
+        let distance = a.distance(&b).max(1.0);
+        let center = (a + b) * 0.5;
+        let start_distance = if let Some(start_distance) = self._start_distance {start_distance} else {
+            self._start_distance = Some(distance);
+            return PinchEvent::None
+        };
+        if let Event::FingerMove(_) = event {
+            let scale = distance / start_distance;
+            if !self._pinching {
+                if (scale - 1.0).abs() < self.min_scale {
+                    return PinchEvent::None
+                }
+                self._pinching = true;
+                self._start_distance = Some(distance);
+                self._last_scale = 1.0;
+                return PinchEvent::Start {center}
+            }
+            let delta = scale / self._last_scale;
+            self._last_scale = scale;
+            return PinchEvent::Pinch {center, scale, delta}
+        }
+        PinchEvent::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RotateEvent {
+    None,
+    Start {center: Vec2},
+    // angles are in radians, clockwise on screen, measured from where the rotate started
+    Rotate {center: Vec2, angle: f32, delta: f32},
+    End {angle: f32},
+}
+
+// two fingers turning around each other
+#[derive(Clone)]
+pub struct RotateRecognizer {
+    pub min_angle: f32,
+    pub _fingers: GestureFingers,
+    pub _start_angle: Option<f32>,
+    pub _last_angle: f32,
+    pub _rotating: bool,
+}
+
+impl RotateRecognizer {
+    pub fn new() -> Self {
+        Self {
+            min_angle: 0.1,
+            _fingers: GestureFingers::default(),
+            _start_angle: None,
+            _last_angle: 0.0,
+            _rotating: false,
+        }
+    }
+    
+    pub fn handle_rotate(&mut self, cx: &mut Cx, event: &mut Event, area: Area) -> RotateEvent {
+        let event = event.hits(cx, area, HitOpt::multi_touch());
+        self.handle_finger_event(&event)
+    }
+    
+    // the angle from a to b, wrapped to stay close to near
+    fn angle_near(a: Vec2, b: Vec2, near: f32) -> f32 {
+        let mut angle = (b.y - a.y).atan2(b.x - a.x);
+        while angle - near > PI {angle -= 2.0 * PI}
+        while angle - near < -PI {angle += 2.0 * PI}
+        angle
+    }
+    
+    pub fn handle_finger_event(&mut self, event: &Event) -> RotateEvent {
+        self._fingers.handle_finger_event(event);
+        let (a, b) = if let Some(pair) = self._fingers.pair() {pair} else {
+            self._start_angle = None;
+            if self._rotating {
+                self._rotating = false;
+                return RotateEvent::End {angle: self._last_angle}
+            }
+            return RotateEvent::None
+        };
+        let center = (a + b) * 0.5;
+        let start_angle = if let Some(start_angle) = self._start_angle {start_angle} else {
+            self._start_angle = Some(Self::angle_near(a, b, 0.0));
+            return RotateEvent::None
+        };
+        if let Event::FingerMove(_) = event {
+            let angle = Self::angle_near(a, b, start_angle + self._last_angle) - start_angle;
+            if !self._rotating {
+                if angle.abs() < self.min_angle {
+                    return RotateEvent::None
+                }
+                self._rotating = true;
+                self._start_angle = Some(start_angle + angle);
+                self._last_angle = 0.0;
+                return RotateEvent::Start {center}
+            }
+            let delta = angle - self._last_angle;
+            self._last_angle = angle;
+            return RotateEvent::Rotate {center, angle, delta}
+        }
+        RotateEvent::None
+    }
+}
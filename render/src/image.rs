@@ -0,0 +1,182 @@
+use crate::cx::*;
+use makepad_microserde::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Qoi,
+    Bmp
+}
+
+impl ImageFormat {
+    // sniffs the magic bytes, file extensions lie
+    pub fn detect(data: &[u8]) -> Option<ImageFormat> {
+        if data.starts_with(&PNG_SIGNATURE) {
+            Some(ImageFormat::Png)
+        }
+        else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(ImageFormat::Jpeg)
+        }
+        else if data.starts_with(&QOI_MAGIC) {
+            Some(ImageFormat::Qoi)
+        }
+        else if data.starts_with(&BMP_MAGIC) {
+            Some(ImageFormat::Bmp)
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageColorSpace {
+    // keep the stored srgb values, the 2d shaders blend in that space
+    Srgb,
+    // convert to linear light for lighting math, at 8 bits this loses some dark detail
+    Linear
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageOptions {
+    // the renderer blends premultiplied, so this is what DrawImage expects
+    pub premultiply_alpha: bool,
+    pub color_space: ImageColorSpace,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            premultiply_alpha: true,
+            color_space: ImageColorSpace::Srgb
+        }
+    }
+}
+
+// pixels are 0xAARRGGBB like CxTexture image_u32, first row is the top
+#[derive(Clone, Debug, Default, PartialEq, SerBin, DeBin)]
+pub struct DecodedImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u32>,
+}
+
+impl DecodedImage {
+    pub fn decode(data: &[u8], options: ImageOptions) -> Result<DecodedImage, String> {
+        let mut image = match ImageFormat::detect(data) {
+            Some(ImageFormat::Png) => decode_png(data) ?,
+            Some(ImageFormat::Jpeg) => decode_jpeg(data) ?,
+            Some(ImageFormat::Qoi) => decode_qoi(data) ?,
+            Some(ImageFormat::Bmp) => decode_bmp(data) ?,
+            None => return Err("DecodedImage: unknown image format".to_string())
+        };
+        image.apply_options(options);
+        Ok(image)
+    }
+
+    // decoders return straight alpha in srgb, this converts to what the options ask for
+    pub fn apply_options(&mut self, options: ImageOptions) {
+        let linear = if options.color_space == ImageColorSpace::Linear {
+            let mut table = [0u8; 256];
+            for (i, value) in table.iter_mut().enumerate() {
+                let c = i as f32 / 255.0;
+                let l = if c <= 0.04045 {c / 12.92} else {((c + 0.055) / 1.055).powf(2.4)};
+                *value = (l * 255.0 + 0.5) as u8;
+            }
+            Some(table)
+        }
+        else {
+            None
+        };
+        if linear.is_none() && !options.premultiply_alpha {
+            return
+        }
+        for pixel in &mut self.pixels {
+            let a = *pixel >> 24;
+            let mut rgb = [(*pixel >> 16) & 0xff, (*pixel >> 8) & 0xff, *pixel & 0xff];
+            for c in &mut rgb {
+                if let Some(table) = &linear {
+                    *c = table[*c as usize] as u32;
+                }
+                if options.premultiply_alpha {
+                    *c = (*c * a + 127) / 255;
+                }
+            }
+            *pixel = a << 24 | rgb[0] << 16 | rgb[1] << 8 | rgb[2];
+        }
+    }
+
+    // hands the pixels to a texture, they get uploaded the next time it is drawn
+    pub fn set_texture(self, cx: &mut Cx, texture: &Texture) {
+        let cxtexture = &mut cx.textures[texture.texture_id as usize];
+        cxtexture.desc = TextureDesc {
+            format: TextureFormat::ImageBGRA,
+            width: Some(self.width),
+            height: Some(self.height),
+            multisample: None
+        };
+        cxtexture.image_u32 = self.pixels;
+        cxtexture.update_image = true;
+    }
+}
+
+// an image file that is read with cx.file_read and decoded when it arrives,
+// so it loads the same way on desktop and on the web
+#[derive(Clone)]
+pub struct ImageLoad {
+    pub file_read: FileRead,
+    pub texture: Texture,
+    pub options: ImageOptions,
+    pub size: Option<Vec2>,
+    pub error: Option<String>,
+}
+
+impl ImageLoad {
+    pub fn new(cx: &mut Cx, path: &str, options: ImageOptions) -> Self {
+        Self {
+            file_read: cx.file_read(path),
+            texture: Texture::new(cx),
+            options,
+            size: None,
+            error: None
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.size.is_some()
+    }
+
+    // returns the result once, when the file read for this image comes in
+    pub fn handle_image_load(&mut self, cx: &mut Cx, event: &mut Event) -> Option<Result<Vec2, String>> {
+        if let Event::FileRead(fr) = event {
+            if !self.file_read.is_pending() || fr.read_id != self.file_read.read_id {
+                return None
+            }
+            self.file_read.read_id = 0;
+            let result = match &fr.data {
+                Ok(data) => DecodedImage::decode(data, self.options).map_err( | err | format!("{} {}", self.file_read.path, err)),
+                Err(err) => Err(err.clone())
+            };
+            return Some(match result {
+                Ok(image) => {
+                    let size = Vec2 {x: image.width as f32, y: image.height as f32};
+                    image.set_texture(cx, &self.texture);
+                    self.size = Some(size);
+                    Ok(size)
+                },
+                Err(err) => {
+                    self.error = Some(err.clone());
+                    Err(err)
+                }
+            })
+        }
+        None
+    }
+}
+
+impl Cx {
+    pub fn load_image(&mut self, path: &str, options: ImageOptions) -> ImageLoad {
+        ImageLoad::new(self, path, options)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::cx::*;
+
+// windows bitmaps, the clipboard hands images around in this format (CF_DIB is a bmp without the file header)
+
+pub const BMP_MAGIC: [u8; 2] = [b'B', b'M'];
+
+const BMP_FILE_HEADER: usize = 14;
+const BMP_INFO_HEADER: usize = 40;
+
+pub fn decode_bmp(data: &[u8]) -> Result<DecodedImage, String> {
+    if data.len() < BMP_FILE_HEADER || data[0..2] != BMP_MAGIC {
+        return Err("decode_bmp: not a bmp file".to_string())
+    }
+    let offset = u32::from_le_bytes([data[10], data[11], data[12], data[13]]) as usize;
+    decode_dib(&data[BMP_FILE_HEADER..], offset.checked_sub(BMP_FILE_HEADER))
+}
+
+// a device independent bitmap, the pixels follow the header and the color masks unless an offset is given
+pub fn decode_dib(data: &[u8], pixel_offset: Option<usize>) -> Result<DecodedImage, String> {
+    if data.len() < BMP_INFO_HEADER {
+        return Err("decode_dib: header too short".to_string())
+    }
+    let u32_at = | pos: usize | u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+    let header_size = u32_at(0) as usize;
+    let width = u32_at(4) as i32;
+    let height = u32_at(8) as i32;
+    let bit_count = u16::from_le_bytes([data[14], data[15]]);
+    let compression = u32_at(16);
+    if width <= 0 || height == 0 || width > 1 << 15 || height.abs() > 1 << 15 {
+        return Err(format!("decode_dib: bad size {}x{}", width, height))
+    }
+    // only uncompressed or bitfields with the usual masks
+    if bit_count != 24 && bit_count != 32 || compression != 0 && compression != 3 {
+        return Err(format!("decode_dib: unsupported {} bit, compression {}", bit_count, compression))
+    }
+    let masks = if compression == 3 && header_size == BMP_INFO_HEADER {12} else {0};
+    let offset = pixel_offset.unwrap_or(header_size + masks);
+    let (width, rows) = (width as usize, height.abs() as usize);
+    let bytes_per_pixel = bit_count as usize / 8;
+    let stride = (width * bytes_per_pixel + 3) & !3;
+    if data.len() < offset + stride * rows {
+        return Err("decode_dib: unexpected end of data".to_string())
+    }
+    let mut pixels = vec![0u32; width * rows];
+    let mut has_alpha = false;
+    for y in 0..rows {
+        // positive heights are stored bottom up
+        let row = if height > 0 {rows - 1 - y} else {y};
+        let line = &data[offset + row * stride..];
+        for x in 0..width {
+            let p = &line[x * bytes_per_pixel..];
+            let a = if bytes_per_pixel == 4 {p[3] as u32} else {255};
+            has_alpha |= a != 0;
+            pixels[y * width + x] = a << 24 | (p[2] as u32) << 16 | (p[1] as u32) << 8 | p[0] as u32;
+        }
+    }
+    // most writers leave the fourth byte zero, thats an opaque image and not an invisible one
+    if !has_alpha {
+        for pixel in &mut pixels {
+            *pixel |= 0xff000000;
+        }
+    }
+    Ok(DecodedImage {width, height: rows, pixels})
+}
+
+pub fn encode_bmp(image: &DecodedImage) -> Vec<u8> {
+    let dib = encode_dib(image);
+    let mut data = Vec::with_capacity(BMP_FILE_HEADER + dib.len());
+    data.extend_from_slice(&BMP_MAGIC);
+    data.extend_from_slice(&((BMP_FILE_HEADER + dib.len()) as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&((BMP_FILE_HEADER + BMP_INFO_HEADER) as u32).to_le_bytes());
+    data.extend_from_slice(&dib);
+    data
+}
+
+// 32 bit bottom up with straight alpha in the fourth byte
+pub fn encode_dib(image: &DecodedImage) -> Vec<u8> {
+    let mut data = Vec::with_capacity(BMP_INFO_HEADER + image.pixels.len() * 4);
+    data.extend_from_slice(&(BMP_INFO_HEADER as u32).to_le_bytes());
+    data.extend_from_slice(&(image.width as i32).to_le_bytes());
+    data.extend_from_slice(&(image.height as i32).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&32u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&((image.pixels.len() * 4) as u32).to_le_bytes());
+    // 2835 pixels per meter is 72 dpi
+    data.extend_from_slice(&2835i32.to_le_bytes());
+    data.extend_from_slice(&2835i32.to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    for y in (0..image.height).rev() {
+        for pixel in &image.pixels[y * image.width..(y + 1) * image.width] {
+            data.extend_from_slice(&pixel.to_le_bytes());
+        }
+    }
+    data
+}
@@ -0,0 +1,718 @@
+use crate::cx::*;
+
+// jpeg decoding for baseline and progressive huffman files with 1 or 3
+// components and any sampling factors. chroma is upsampled by replication
+
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10,
+    17, 24, 32, 25, 18, 11, 4, 5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13, 6, 7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63
+];
+
+#[derive(Clone, Default)]
+struct JpegHuffman {
+    // fast lookup on the next 9 bits, entries are value << 8 | length
+    fast: Vec<u16>,
+    maxcode: [i32; 18],
+    valptr: [i32; 17],
+    mincode: [i32; 17],
+    values: Vec<u8>,
+}
+
+impl JpegHuffman {
+    fn new(counts: &[u8; 16], values: Vec<u8>) -> Result<Self, String> {
+        let mut h = JpegHuffman {
+            fast: vec![0; 512],
+            maxcode: [-1; 18],
+            valptr: [0; 17],
+            mincode: [0; 17],
+            values
+        };
+        let mut code = 0i32;
+        let mut k = 0i32;
+        for len in 1..=16 {
+            let count = counts[len - 1] as i32;
+            h.valptr[len] = k;
+            h.mincode[len] = code;
+            // more codes of a length than there are bit patterns left
+            if code + count > 1 << len {
+                return Err("decode_jpeg: bad huffman code lengths".to_string())
+            }
+            if count > 0 {
+                for _ in 0..count {
+                    if len <= 9 {
+                        if k as usize >= h.values.len() {
+                            return Err("decode_jpeg: huffman table too short".to_string())
+                        }
+                        let entry = (h.values[k as usize] as u16) << 8 | len as u16;
+                        let shift = 9 - len;
+                        for fill in 0..(1 << shift) {
+                            h.fast[((code << shift) | fill) as usize] = entry;
+                        }
+                    }
+                    code += 1;
+                    k += 1;
+                }
+                h.maxcode[len] = code - 1;
+            }
+            code <<= 1;
+        }
+        h.maxcode[17] = i32::MAX;
+        if k as usize > h.values.len() {
+            return Err("decode_jpeg: huffman table too short".to_string())
+        }
+        Ok(h)
+    }
+}
+
+struct JpegBits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+    // set when a marker is hit inside entropy coded data
+    marker: Option<u8>,
+    // zero bytes fed in after the data or a marker
+    padding: u32,
+}
+
+impl<'a> JpegBits<'a> {
+    fn fill(&mut self) {
+        while self.bit_count <= 24 {
+            let mut byte = 0;
+            if self.marker.is_none() && self.pos < self.data.len() {
+                byte = self.data[self.pos];
+                if byte == 0xff {
+                    let next = self.data.get(self.pos + 1).cloned().unwrap_or(0);
+                    if next == 0 {
+                        self.pos += 2;
+                    }
+                    else {
+                        self.marker = Some(next);
+                        byte = 0;
+                        self.padding += 1;
+                    }
+                }
+                else {
+                    self.pos += 1;
+                }
+            }
+            else {
+                self.padding = self.padding.saturating_add(1);
+            }
+            self.bit_buf |= (byte as u32) << (24 - self.bit_count);
+            self.bit_count += 8;
+        }
+    }
+
+    // counts come from the file, anything over 16 bits is a corrupt one
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        if count > 16 {
+            return Err("decode_jpeg: bad bit count".to_string())
+        }
+        if count == 0 {
+            return Ok(0)
+        }
+        if self.bit_count < count {
+            self.fill();
+        }
+        let value = self.bit_buf >> (32 - count);
+        self.bit_buf <<= count;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    fn bit(&mut self) -> u32 {
+        if self.bit_count < 1 {
+            self.fill();
+        }
+        let value = self.bit_buf >> 31;
+        self.bit_buf <<= 1;
+        self.bit_count -= 1;
+        value
+    }
+
+    // a value of `count` bits, sign extended the jpeg way
+    fn receive_extend(&mut self, count: u32) -> Result<i32, String> {
+        if count == 0 {
+            return Ok(0)
+        }
+        let value = self.bits(count) ? as i32;
+        if value < (1 << (count - 1)) {
+            Ok(value - (1 << count) + 1)
+        }
+        else {
+            Ok(value)
+        }
+    }
+
+    // more padding went out than the buffer can still hold, the scan data ran out
+    fn past_end(&self) -> bool {
+        self.padding * 8 > self.bit_count + 32
+    }
+
+    fn decode(&mut self, h: &JpegHuffman) -> Result<u8, String> {
+        if self.bit_count < 16 {
+            self.fill();
+        }
+        let entry = h.fast[(self.bit_buf >> 23) as usize];
+        if entry != 0 {
+            let len = (entry & 0xff) as u32;
+            self.bit_buf <<= len;
+            self.bit_count -= len;
+            return Ok((entry >> 8) as u8)
+        }
+        let mut code = 0i32;
+        for len in 1..=16 {
+            code = (code << 1) | self.bit() as i32;
+            if code <= h.maxcode[len] {
+                let index = h.valptr[len] + code - h.mincode[len];
+                return h.values.get(index as usize).cloned().ok_or_else( || "decode_jpeg: bad huffman code".to_string())
+            }
+        }
+        Err("decode_jpeg: bad huffman code".to_string())
+    }
+
+    // skips to the restart marker and resets the bit buffer
+    fn restart(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+        if self.marker.is_none() {
+            while self.pos + 1 < self.data.len() && !(self.data[self.pos] == 0xff && (0xd0..=0xd7).contains(&self.data[self.pos + 1])) {
+                self.pos += 1;
+            }
+        }
+        if self.marker.map_or(true, | marker | (0xd0..=0xd7).contains(&marker)) {
+            self.pos += 2;
+            self.marker = None;
+            self.padding = 0;
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct JpegComponent {
+    id: u8,
+    h: usize,
+    v: usize,
+    tq: usize,
+    blocks_w: usize,
+    blocks_h: usize,
+    coefs: Vec<[i16; 64]>,
+    dc_pred: i32,
+    dc_table: usize,
+    ac_table: usize,
+    pixels: Vec<u8>,
+}
+
+#[derive(Default)]
+struct JpegDecoder {
+    width: usize,
+    height: usize,
+    progressive: bool,
+    components: Vec<JpegComponent>,
+    qt: Vec<[u16; 64]>,
+    dc_tables: [Option<JpegHuffman>; 4],
+    ac_tables: [Option<JpegHuffman>; 4],
+    restart_interval: usize,
+    hmax: usize,
+    vmax: usize,
+    mcus_x: usize,
+    mcus_y: usize,
+    adobe_transform: Option<u8>,
+    eobrun: u32,
+}
+
+fn be_u16(data: &[u8], pos: usize) -> Result<usize, String> {
+    if pos + 2 > data.len() {
+        return Err("decode_jpeg: unexpected end of data".to_string())
+    }
+    Ok((data[pos] as usize) << 8 | data[pos + 1] as usize)
+}
+
+pub fn decode_jpeg(data: &[u8]) -> Result<DecodedImage, String> {
+    if data.len() < 4 || data[0] != 0xff || data[1] != 0xd8 {
+        return Err("decode_jpeg: not a jpeg file".to_string())
+    }
+    let mut dec = JpegDecoder {qt: vec![[0u16; 64]; 4], ..JpegDecoder::default()};
+    let mut pos = 2;
+    let mut frame_seen = false;
+    loop {
+        // find the next marker, skipping fill bytes
+        while pos < data.len() && data[pos] != 0xff {
+            pos += 1;
+        }
+        while pos < data.len() && data[pos] == 0xff {
+            pos += 1;
+        }
+        if pos >= data.len() {
+            break;
+        }
+        let marker = data[pos];
+        pos += 1;
+        match marker {
+            0xd8 | 0x01 | 0xd0..=0xd7 => continue,
+            0xd9 => break,
+            _ => ()
+        }
+        let len = be_u16(data, pos) ?;
+        if len < 2 || pos + len > data.len() {
+            return Err("decode_jpeg: segment runs past end of file".to_string())
+        }
+        let seg = &data[pos + 2..pos + len];
+        pos += len;
+        match marker {
+            0xdb => dec.read_dqt(seg) ?,
+            0xc4 => dec.read_dht(seg) ?,
+            0xdd => dec.restart_interval = be_u16(seg, 0) ?,
+            0xee => {
+                if seg.len() >= 12 && &seg[0..5] == b"Adobe" {
+                    dec.adobe_transform = Some(seg[11]);
+                }
+            },
+            0xc0 | 0xc1 | 0xc2 => {
+                dec.progressive = marker == 0xc2;
+                dec.read_sof(seg) ?;
+                frame_seen = true;
+            },
+            0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => {
+                return Err("decode_jpeg: lossless, hierarchical and arithmetic jpegs are not supported".to_string())
+            },
+            0xda => {
+                if !frame_seen {
+                    return Err("decode_jpeg: scan before frame header".to_string())
+                }
+                let consumed = dec.read_scan(seg, &data[pos..]) ?;
+                pos += consumed;
+            },
+            _ => ()
+        }
+    }
+    if !frame_seen {
+        return Err("decode_jpeg: no frame header".to_string())
+    }
+    dec.finish()
+}
+
+impl JpegDecoder {
+    fn read_dqt(&mut self, seg: &[u8]) -> Result<(), String> {
+        let mut pos = 0;
+        while pos < seg.len() {
+            let precision = seg[pos] >> 4;
+            let id = (seg[pos] & 15) as usize;
+            pos += 1;
+            if id > 3 {
+                return Err("decode_jpeg: bad quantization table id".to_string())
+            }
+            for i in 0..64 {
+                let value = if precision == 0 {
+                    let v = *seg.get(pos).ok_or("decode_jpeg: short quantization table") ? as u16;
+                    pos += 1;
+                    v
+                }
+                else {
+                    let v = be_u16(seg, pos) ? as u16;
+                    pos += 2;
+                    v
+                };
+                self.qt[id][ZIGZAG[i]] = value;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_dht(&mut self, seg: &[u8]) -> Result<(), String> {
+        let mut pos = 0;
+        while pos + 17 <= seg.len() {
+            let class = seg[pos] >> 4;
+            let id = (seg[pos] & 15) as usize;
+            if id > 3 {
+                return Err("decode_jpeg: bad huffman table id".to_string())
+            }
+            let mut counts = [0u8; 16];
+            counts.copy_from_slice(&seg[pos + 1..pos + 17]);
+            let total: usize = counts.iter().map( | c | *c as usize).sum();
+            pos += 17;
+            if pos + total > seg.len() {
+                return Err("decode_jpeg: short huffman table".to_string())
+            }
+            let table = JpegHuffman::new(&counts, seg[pos..pos + total].to_vec()) ?;
+            pos += total;
+            if class == 0 {
+                self.dc_tables[id] = Some(table);
+            }
+            else {
+                self.ac_tables[id] = Some(table);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_sof(&mut self, seg: &[u8]) -> Result<(), String> {
+        if seg.len() < 6 || seg[0] != 8 {
+            return Err("decode_jpeg: only 8 bit precision is supported".to_string())
+        }
+        self.height = be_u16(seg, 1) ?;
+        self.width = be_u16(seg, 3) ?;
+        let count = seg[5] as usize;
+        if self.width == 0 || self.height == 0 || self.width > 1 << 15 || self.height > 1 << 15 {
+            return Err(format!("decode_jpeg: bad size {}x{}", self.width, self.height))
+        }
+        if count != 1 && count != 3 {
+            return Err(format!("decode_jpeg: {} components are not supported", count))
+        }
+        if seg.len() < 6 + count * 3 {
+            return Err("decode_jpeg: short frame header".to_string())
+        }
+        self.components.clear();
+        for i in 0..count {
+            let c = &seg[6 + i * 3..];
+            let (h, v) = ((c[1] >> 4) as usize, (c[1] & 15) as usize);
+            if h == 0 || h > 4 || v == 0 || v > 4 || c[2] > 3 {
+                return Err("decode_jpeg: bad component".to_string())
+            }
+            self.components.push(JpegComponent {
+                id: c[0],
+                h,
+                v,
+                tq: c[2] as usize,
+                ..JpegComponent::default()
+            });
+        }
+        self.hmax = self.components.iter().map( | c | c.h).max().unwrap();
+        self.vmax = self.components.iter().map( | c | c.v).max().unwrap();
+        self.mcus_x = (self.width + 8 * self.hmax - 1) / (8 * self.hmax);
+        self.mcus_y = (self.height + 8 * self.vmax - 1) / (8 * self.vmax);
+        for c in &mut self.components {
+            c.blocks_w = self.mcus_x * c.h;
+            c.blocks_h = self.mcus_y * c.v;
+            c.coefs = vec![[0i16; 64]; c.blocks_w * c.blocks_h];
+        }
+        Ok(())
+    }
+
+    // decodes the entropy coded data after a scan header, returns the bytes it used
+    fn read_scan(&mut self, seg: &[u8], data: &[u8]) -> Result<usize, String> {
+        let count = *seg.get(0).ok_or("decode_jpeg: short scan header") ? as usize;
+        if count == 0 || seg.len() < 1 + count * 2 + 3 {
+            return Err("decode_jpeg: short scan header".to_string())
+        }
+        let mut scan_comps = Vec::new();
+        for i in 0..count {
+            let id = seg[1 + i * 2];
+            let tables = seg[2 + i * 2];
+            let index = self.components.iter().position( | c | c.id == id).ok_or("decode_jpeg: scan refers to unknown component") ?;
+            self.components[index].dc_table = (tables >> 4) as usize & 3;
+            self.components[index].ac_table = (tables & 15) as usize & 3;
+            scan_comps.push(index);
+        }
+        let ss = seg[1 + count * 2] as usize;
+        let se = seg[2 + count * 2] as usize;
+        let ah = (seg[3 + count * 2] >> 4) as u32;
+        let al = (seg[3 + count * 2] & 15) as u32;
+        if ss > 63 || se > 63 || ss > se {
+            return Err("decode_jpeg: bad spectral selection".to_string())
+        }
+
+        let mut bits = JpegBits {data, pos: 0, bit_buf: 0, bit_count: 0, marker: None, padding: 0};
+        for c in &mut self.components {
+            c.dc_pred = 0;
+        }
+        self.eobrun = 0;
+
+        // one component scans cover just its own blocks, interleaved ones whole mcus
+        let (units_x, units_y) = if count == 1 {
+            let c = &self.components[scan_comps[0]];
+            (
+                ((self.width * c.h + self.hmax - 1) / self.hmax + 7) / 8,
+                ((self.height * c.v + self.vmax - 1) / self.vmax + 7) / 8
+            )
+        }
+        else {
+            (self.mcus_x, self.mcus_y)
+        };
+
+        let mut todo = if self.restart_interval > 0 {self.restart_interval} else {usize::MAX};
+        for uy in 0..units_y {
+            for ux in 0..units_x {
+                if bits.past_end() {
+                    return Err("decode_jpeg: unexpected end of scan data".to_string())
+                }
+                if todo == 0 {
+                    bits.restart();
+                    for c in &mut self.components {
+                        c.dc_pred = 0;
+                    }
+                    self.eobrun = 0;
+                    todo = self.restart_interval;
+                }
+                todo -= 1;
+                if count == 1 {
+                    self.decode_block(&mut bits, scan_comps[0], ux, uy, ss, se, ah, al) ?;
+                }
+                else {
+                    for &ci in &scan_comps {
+                        let (h, v) = (self.components[ci].h, self.components[ci].v);
+                        for by in 0..v {
+                            for bx in 0..h {
+                                self.decode_block(&mut bits, ci, ux * h + bx, uy * v + by, ss, se, ah, al) ?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // the caller continues at the marker that ended the data
+        let mut end = bits.pos;
+        while end + 1 < data.len() && !(data[end] == 0xff && data[end + 1] != 0 && !(0xd0..=0xd7).contains(&data[end + 1])) {
+            end += 1;
+        }
+        Ok(end)
+    }
+
+    fn decode_block(&mut self, bits: &mut JpegBits, ci: usize, bx: usize, by: usize, ss: usize, se: usize, ah: u32, al: u32) -> Result<(), String> {
+        let comp = &mut self.components[ci];
+        if bx >= comp.blocks_w || by >= comp.blocks_h {
+            return Ok(())
+        }
+        let block = &mut comp.coefs[by * comp.blocks_w + bx];
+        if !self.progressive {
+            let dc = self.dc_tables[comp.dc_table].as_ref().ok_or("decode_jpeg: missing dc table") ?;
+            let ac = self.ac_tables[comp.ac_table].as_ref().ok_or("decode_jpeg: missing ac table") ?;
+            let s = bits.decode(dc) ? as u32;
+            comp.dc_pred = comp.dc_pred.wrapping_add(bits.receive_extend(s) ?);
+            block[0] = comp.dc_pred as i16;
+            let mut k = 1;
+            while k < 64 {
+                let rs = bits.decode(ac) ?;
+                let (r, s) = ((rs >> 4) as usize, (rs & 15) as u32);
+                if s == 0 {
+                    if r != 15 {
+                        break;
+                    }
+                    k += 16;
+                    continue;
+                }
+                k += r;
+                if k > 63 {
+                    return Err("decode_jpeg: coefficient index out of range".to_string())
+                }
+                block[ZIGZAG[k]] = bits.receive_extend(s) ? as i16;
+                k += 1;
+            }
+            return Ok(())
+        }
+
+        if ss == 0 {
+            // dc scans
+            if ah == 0 {
+                let dc = self.dc_tables[comp.dc_table].as_ref().ok_or("decode_jpeg: missing dc table") ?;
+                let s = bits.decode(dc) ? as u32;
+                comp.dc_pred = comp.dc_pred.wrapping_add(bits.receive_extend(s) ?);
+                block[0] = (comp.dc_pred << al) as i16;
+            }
+            else if bits.bit() == 1 {
+                block[0] |= (1 << al) as i16;
+            }
+            return Ok(())
+        }
+
+        let ac = self.ac_tables[comp.ac_table].as_ref().ok_or("decode_jpeg: missing ac table") ?;
+        if ah == 0 {
+            // first ac scan of a band
+            if self.eobrun > 0 {
+                self.eobrun -= 1;
+                return Ok(())
+            }
+            let mut k = ss;
+            while k <= se {
+                let rs = bits.decode(ac) ?;
+                let (r, s) = ((rs >> 4) as u32, (rs & 15) as u32);
+                if s == 0 {
+                    if r < 15 {
+                        self.eobrun = (1 << r) - 1;
+                        if r > 0 {
+                            self.eobrun += bits.bits(r) ?;
+                        }
+                        break;
+                    }
+                    k += 16;
+                    continue;
+                }
+                k += r as usize;
+                if k > 63 {
+                    return Err("decode_jpeg: coefficient index out of range".to_string())
+                }
+                block[ZIGZAG[k]] = (bits.receive_extend(s) ?).wrapping_mul(1 << al) as i16;
+                k += 1;
+            }
+            return Ok(())
+        }
+
+        // ac refinement: one correction bit per coefficient that is already nonzero,
+        // new coefficients are +-1 and land on the zero that the run counts to
+        let p1 = (1i32 << al) as i16;
+        let m1 = (-1i32 << al) as i16;
+        let mut k = ss;
+        if self.eobrun == 0 {
+            while k <= se {
+                let rs = bits.decode(ac) ?;
+                let (mut r, s) = ((rs >> 4) as i32, (rs & 15) as u32);
+                let mut value = 0i16;
+                if s == 0 {
+                    if r < 15 {
+                        self.eobrun = (1 << r) as u32;
+                        if r > 0 {
+                            self.eobrun += bits.bits(r as u32) ?;
+                        }
+                        break;
+                    }
+                }
+                else {
+                    value = if bits.bit() == 1 {p1} else {m1};
+                }
+                while k <= se {
+                    let z = ZIGZAG[k];
+                    if block[z] != 0 {
+                        if bits.bit() == 1 && (block[z] & p1) == 0 {
+                            block[z] = block[z].wrapping_add(if block[z] >= 0 {p1} else {m1});
+                        }
+                    }
+                    else {
+                        if r == 0 {
+                            if value != 0 {
+                                block[z] = value;
+                            }
+                            k += 1;
+                            break;
+                        }
+                        r -= 1;
+                    }
+                    k += 1;
+                }
+            }
+        }
+        if self.eobrun > 0 {
+            while k <= se {
+                let z = ZIGZAG[k];
+                if block[z] != 0 && bits.bit() == 1 && (block[z] & p1) == 0 {
+                    block[z] = block[z].wrapping_add(if block[z] >= 0 {p1} else {m1});
+                }
+                k += 1;
+            }
+            self.eobrun -= 1;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<DecodedImage, String> {
+        let idct = JpegIdct::new();
+        for c in &mut self.components {
+            let stride = c.blocks_w * 8;
+            c.pixels = vec![0u8; stride * c.blocks_h * 8];
+            let qt = &self.qt[c.tq];
+            let mut out = [0u8; 64];
+            for by in 0..c.blocks_h {
+                for bx in 0..c.blocks_w {
+                    idct.transform(&c.coefs[by * c.blocks_w + bx], qt, &mut out);
+                    for y in 0..8 {
+                        let row = (by * 8 + y) * stride + bx * 8;
+                        c.pixels[row..row + 8].copy_from_slice(&out[y * 8..y * 8 + 8]);
+                    }
+                }
+            }
+            c.coefs = Vec::new();
+        }
+
+        let mut pixels = vec![0u32; self.width * self.height];
+        let rgb = self.components.len() == 3 && (self.adobe_transform == Some(0) || (
+            self.components[0].id == b'R' && self.components[1].id == b'G' && self.components[2].id == b'B'
+        ));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut samples = [0u8; 3];
+                for (i, c) in self.components.iter().enumerate() {
+                    let cx = x * c.h / self.hmax;
+                    let cy = y * c.v / self.vmax;
+                    samples[i] = c.pixels[cy * c.blocks_w * 8 + cx];
+                }
+                let (r, g, b) = if self.components.len() == 1 {
+                    (samples[0], samples[0], samples[0])
+                }
+                else if rgb {
+                    (samples[0], samples[1], samples[2])
+                }
+                else {
+                    let yy = samples[0] as f32;
+                    let cb = samples[1] as f32 - 128.0;
+                    let cr = samples[2] as f32 - 128.0;
+                    (
+                        clamp_u8(yy + 1.402 * cr),
+                        clamp_u8(yy - 0.344136 * cb - 0.714136 * cr),
+                        clamp_u8(yy + 1.772 * cb)
+                    )
+                };
+                pixels[y * self.width + x] = 0xff00_0000 | (r as u32) << 16 | (g as u32) << 8 | b as u32;
+            }
+        }
+        Ok(DecodedImage {
+            width: self.width,
+            height: self.height,
+            pixels
+        })
+    }
+}
+
+fn clamp_u8(v: f32) -> u8 {
+    (v + 0.5).max(0.0).min(255.0) as u8
+}
+
+// separable float idct with a precomputed cosine table
+struct JpegIdct {
+    table: [[f32; 8]; 8],
+}
+
+impl JpegIdct {
+    fn new() -> Self {
+        let mut table = [[0f32; 8]; 8];
+        for x in 0..8 {
+            for u in 0..8 {
+                let cu = if u == 0 {std::f32::consts::FRAC_1_SQRT_2} else {1.0};
+                table[x][u] = cu * (((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI) / 16.0).cos() / 2.0;
+            }
+        }
+        Self {table}
+    }
+
+    fn transform(&self, coefs: &[i16; 64], qt: &[u16; 64], out: &mut [u8; 64]) {
+        let mut temp = [0f32; 64];
+        // rows of the dequantized block first, then columns
+        for v in 0..8 {
+            let row = &coefs[v * 8..v * 8 + 8];
+            if row.iter().all( | c | *c == 0) {
+                continue;
+            }
+            for x in 0..8 {
+                let mut sum = 0.0;
+                for u in 0..8 {
+                    sum += self.table[x][u] * (row[u] as f32 * qt[v * 8 + u] as f32);
+                }
+                temp[v * 8 + x] = sum;
+            }
+        }
+        for x in 0..8 {
+            for y in 0..8 {
+                let mut sum = 0.0;
+                for v in 0..8 {
+                    sum += self.table[y][v] * temp[v * 8 + x];
+                }
+                out[y * 8 + x] = clamp_u8(sum + 128.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::cx::*;
+use crate::inflate::*;
+
+// png decoding for all color types and bit depths, interlaced or not.
+// 16 bit channels are reduced to 8
+
+pub const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+struct PngHeader {
+    width: usize,
+    height: usize,
+    bit_depth: usize,
+    color_type: u8,
+    interlace: bool,
+}
+
+impl PngHeader {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 => 1,
+            2 => 3,
+            3 => 1,
+            4 => 2,
+            _ => 4
+        }
+    }
+}
+
+fn be_u32(data: &[u8]) -> u32 {
+    (data[0] as u32) << 24 | (data[1] as u32) << 16 | (data[2] as u32) << 8 | data[3] as u32
+}
+
+pub fn decode_png(data: &[u8]) -> Result<DecodedImage, String> {
+    if data.len() < 8 || data[0..8] != PNG_SIGNATURE {
+        return Err("decode_png: not a png file".to_string())
+    }
+    let mut header = None;
+    let mut palette = Vec::new();
+    let mut transparency = Vec::new();
+    let mut idat = Vec::new();
+    let mut pos = 8;
+    while pos + 8 <= data.len() {
+        let len = be_u32(&data[pos..]) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let start = pos + 8;
+        if start + len + 4 > data.len() {
+            return Err("decode_png: chunk runs past end of file".to_string())
+        }
+        let chunk = &data[start..start + len];
+        match kind {
+            b"IHDR" => {
+                if len < 13 {
+                    return Err("decode_png: short IHDR".to_string())
+                }
+                let h = PngHeader {
+                    width: be_u32(&chunk[0..]) as usize,
+                    height: be_u32(&chunk[4..]) as usize,
+                    bit_depth: chunk[8] as usize,
+                    color_type: chunk[9],
+                    interlace: chunk[12] == 1,
+                };
+                let valid_depth = match h.color_type {
+                    0 => [1, 2, 4, 8, 16].contains(&h.bit_depth),
+                    3 => [1, 2, 4, 8].contains(&h.bit_depth),
+                    2 | 4 | 6 => [8, 16].contains(&h.bit_depth),
+                    _ => false
+                };
+                if !valid_depth || chunk[10] != 0 || chunk[11] != 0 || chunk[12] > 1 {
+                    return Err(format!("decode_png: unsupported format color type {} depth {}", h.color_type, h.bit_depth))
+                }
+                if h.width == 0 || h.height == 0 || h.width > 1 << 15 || h.height > 1 << 15 {
+                    return Err(format!("decode_png: bad size {}x{}", h.width, h.height))
+                }
+                header = Some(h);
+            },
+            b"PLTE" => {
+                palette = chunk.chunks(3).filter( | c | c.len() == 3).map( | c | [c[0], c[1], c[2]]).collect();
+            },
+            b"tRNS" => {
+                transparency = chunk.to_vec();
+            },
+            b"IDAT" => {
+                idat.extend_from_slice(chunk);
+            },
+            b"IEND" => break,
+            _ => ()
+        }
+        pos = start + len + 4;
+    }
+    let header = header.ok_or_else( || "decode_png: missing IHDR".to_string()) ?;
+    if header.color_type == 3 && palette.len() == 0 {
+        return Err("decode_png: missing palette".to_string())
+    }
+    let raw = inflate_zlib(&idat) ?;
+
+    let bits_per_pixel = header.channels() * header.bit_depth;
+    let filter_bpp = ((bits_per_pixel + 7) / 8).max(1);
+
+    // adam7 passes as (x start, y start, x step, y step), or one pass covering everything
+    let passes: &[(usize, usize, usize, usize)] = if header.interlace {
+        &[(0, 0, 8, 8), (4, 0, 8, 8), (0, 4, 4, 8), (2, 0, 4, 4), (0, 2, 2, 4), (1, 0, 2, 2), (0, 1, 1, 2)]
+    }
+    else {
+        &[(0, 0, 1, 1)]
+    };
+
+    // a header that claims a huge image doesn't get its pixels allocated unless the data is there
+    let mut raw_len = 0;
+    for (x0, y0, dx, dy) in passes {
+        if *x0 < header.width && *y0 < header.height {
+            let pass_width = (header.width - x0 + dx - 1) / dx;
+            raw_len += (header.height - y0 + dy - 1) / dy * ((pass_width * bits_per_pixel + 7) / 8 + 1);
+        }
+    }
+    if raw_len > raw.len() {
+        return Err("decode_png: image data too short".to_string())
+    }
+    let mut pixels = vec![0u32; header.width * header.height];
+
+    let mut offset = 0;
+    for (x0, y0, dx, dy) in passes {
+        if *x0 >= header.width || *y0 >= header.height {
+            continue;
+        }
+        let pass_width = (header.width - x0 + dx - 1) / dx;
+        let pass_height = (header.height - y0 + dy - 1) / dy;
+        let stride = (pass_width * bits_per_pixel + 7) / 8;
+        if offset + pass_height * (stride + 1) > raw.len() {
+            return Err("decode_png: image data too short".to_string())
+        }
+        let mut prev = vec![0u8; stride];
+        let mut row = vec![0u8; stride];
+        for py in 0..pass_height {
+            let filter = raw[offset];
+            row.copy_from_slice(&raw[offset + 1..offset + 1 + stride]);
+            offset += stride + 1;
+            png_unfilter(filter, &mut row, &prev, filter_bpp) ?;
+            let y = y0 + py * dy;
+            for px in 0..pass_width {
+                let x = x0 + px * dx;
+                pixels[y * header.width + x] = png_pixel(&header, &row, px, &palette, &transparency);
+            }
+            std::mem::swap(&mut prev, &mut row);
+        }
+    }
+
+    Ok(DecodedImage {
+        width: header.width,
+        height: header.height,
+        pixels
+    })
+}
+
+fn png_unfilter(filter: u8, row: &mut [u8], prev: &[u8], bpp: usize) -> Result<(), String> {
+    match filter {
+        0 => (),
+        1 => for i in bpp..row.len() {
+            row[i] = row[i].wrapping_add(row[i - bpp]);
+        },
+        2 => for i in 0..row.len() {
+            row[i] = row[i].wrapping_add(prev[i]);
+        },
+        3 => for i in 0..row.len() {
+            let left = if i >= bpp {row[i - bpp] as u16} else {0};
+            row[i] = row[i].wrapping_add(((left + prev[i] as u16) / 2) as u8);
+        },
+        4 => for i in 0..row.len() {
+            let a = if i >= bpp {row[i - bpp] as i16} else {0};
+            let b = prev[i] as i16;
+            let c = if i >= bpp {prev[i - bpp] as i16} else {0};
+            let p = a + b - c;
+            let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+            let predict = if pa <= pb && pa <= pc {a} else if pb <= pc {b} else {c};
+            row[i] = row[i].wrapping_add(predict as u8);
+        },
+        _ => return Err(format!("decode_png: invalid filter {}", filter))
+    }
+    Ok(())
+}
+
+// raw sample number `index` of a row, at the image bit depth
+fn png_sample(row: &[u8], index: usize, bit_depth: usize) -> u16 {
+    match bit_depth {
+        16 => (row[index * 2] as u16) << 8 | row[index * 2 + 1] as u16,
+        8 => row[index] as u16,
+        _ => {
+            let bit = index * bit_depth;
+            let shift = 8 - bit_depth - (bit & 7);
+            ((row[bit >> 3] >> shift) & ((1 << bit_depth) - 1) as u8) as u16
+        }
+    }
+}
+
+fn png_pixel(header: &PngHeader, row: &[u8], x: usize, palette: &[[u8; 3]], transparency: &[u8]) -> u32 {
+    let depth = header.bit_depth;
+    let to_u8 = | sample: u16 | -> u32 {
+        match depth {
+            16 => (sample >> 8) as u32,
+            8 => sample as u32,
+            _ => sample as u32 * 255 / ((1 << depth) - 1)
+        }
+    };
+    let trns = | i: usize | -> Option<u16> {
+        if transparency.len() >= i * 2 + 2 {
+            Some((transparency[i * 2] as u16) << 8 | transparency[i * 2 + 1] as u16)
+        }
+        else {
+            None
+        }
+    };
+    let (r, g, b, a) = match header.color_type {
+        0 => {
+            let gray = png_sample(row, x, depth);
+            let a = if trns(0) == Some(gray) {0} else {255};
+            let v = to_u8(gray);
+            (v, v, v, a)
+        },
+        2 => {
+            let (r, g, b) = (png_sample(row, x * 3, depth), png_sample(row, x * 3 + 1, depth), png_sample(row, x * 3 + 2, depth));
+            let a = if trns(0) == Some(r) && trns(1) == Some(g) && trns(2) == Some(b) {0} else {255};
+            (to_u8(r), to_u8(g), to_u8(b), a)
+        },
+        3 => {
+            let index = png_sample(row, x, depth) as usize;
+            let [r, g, b] = palette.get(index).cloned().unwrap_or([0, 0, 0]);
+            let a = transparency.get(index).cloned().unwrap_or(255);
+            (r as u32, g as u32, b as u32, a as u32)
+        },
+        4 => {
+            let v = to_u8(png_sample(row, x * 2, depth));
+            (v, v, v, to_u8(png_sample(row, x * 2 + 1, depth)))
+        },
+        _ => (
+            to_u8(png_sample(row, x * 4, depth)),
+            to_u8(png_sample(row, x * 4 + 1, depth)),
+            to_u8(png_sample(row, x * 4 + 2, depth)),
+            to_u8(png_sample(row, x * 4 + 3, depth))
+        )
+    };
+    a << 24 | r << 16 | g << 8 | b
+}
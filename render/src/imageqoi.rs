@@ -0,0 +1,79 @@
+use crate::cx::*;
+
+// the quite ok image format, see qoiformat.org
+
+pub const QOI_MAGIC: [u8; 4] = [b'q', b'o', b'i', b'f'];
+
+pub fn decode_qoi(data: &[u8]) -> Result<DecodedImage, String> {
+    if data.len() < 14 + 8 || data[0..4] != QOI_MAGIC {
+        return Err("decode_qoi: not a qoi file".to_string())
+    }
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    if width == 0 || height == 0 || width > 1 << 15 || height > 1 << 15 {
+        return Err(format!("decode_qoi: bad size {}x{}", width, height))
+    }
+
+    let total = width * height;
+    let mut pixels = Vec::with_capacity(total);
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut pos = 14;
+    let end = data.len() - 8;
+    while pixels.len() < total {
+        if pos >= end {
+            return Err("decode_qoi: unexpected end of data".to_string())
+        }
+        let b1 = data[pos];
+        pos += 1;
+        if b1 == 0xfe {
+            px[0] = data[pos];
+            px[1] = data[pos + 1];
+            px[2] = data[pos + 2];
+            pos += 3;
+        }
+        else if b1 == 0xff {
+            px.copy_from_slice(&data[pos..pos + 4]);
+            pos += 4;
+        }
+        else {
+            match b1 >> 6 {
+                0 => px = index[(b1 & 0x3f) as usize],
+                1 => {
+                    px[0] = px[0].wrapping_add(((b1 >> 4) & 3).wrapping_sub(2));
+                    px[1] = px[1].wrapping_add(((b1 >> 2) & 3).wrapping_sub(2));
+                    px[2] = px[2].wrapping_add((b1 & 3).wrapping_sub(2));
+                },
+                2 => {
+                    let b2 = data[pos];
+                    pos += 1;
+                    let dg = (b1 & 0x3f).wrapping_sub(32);
+                    px[0] = px[0].wrapping_add(dg.wrapping_sub(8).wrapping_add(b2 >> 4));
+                    px[1] = px[1].wrapping_add(dg);
+                    px[2] = px[2].wrapping_add(dg.wrapping_sub(8).wrapping_add(b2 & 0x0f));
+                },
+                _ => {
+                    let run = (b1 & 0x3f) as usize + 1;
+                    let value = qoi_u32(px);
+                    for _ in 0..run.min(total - pixels.len()) {
+                        pixels.push(value);
+                    }
+                    continue;
+                }
+            }
+        }
+        let hash = (px[0] as usize * 3 + px[1] as usize * 5 + px[2] as usize * 7 + px[3] as usize * 11) % 64;
+        index[hash] = px;
+        pixels.push(qoi_u32(px));
+    }
+
+    Ok(DecodedImage {
+        width,
+        height,
+        pixels
+    })
+}
+
+fn qoi_u32(px: [u8; 4]) -> u32 {
+    (px[3] as u32) << 24 | (px[0] as u32) << 16 | (px[1] as u32) << 8 | px[2] as u32
+}
@@ -0,0 +1,261 @@
+// a small deflate decoder (rfc1951) with the zlib wrapper (rfc1950) around it
+
+struct InflateBits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u64,
+    bit_count: u32,
+    // zero bits handed out past the end of the data
+    overrun: u32,
+}
+
+impl<'a> InflateBits<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {data, pos: 0, bit_buf: 0, bit_count: 0, overrun: 0}
+    }
+
+    fn refill(&mut self) {
+        while self.bit_count <= 56 {
+            let byte = if self.pos < self.data.len() {
+                self.data[self.pos]
+            }
+            else {
+                self.overrun = self.overrun.saturating_add(8);
+                0
+            };
+            self.pos += 1;
+            self.bit_buf |= (byte as u64) << self.bit_count;
+            self.bit_count += 8;
+        }
+    }
+
+    fn peek(&mut self, count: u32) -> u32 {
+        if self.bit_count < count {
+            self.refill();
+        }
+        (self.bit_buf & ((1u64 << count) - 1)) as u32
+    }
+
+    fn consume(&mut self, count: u32) {
+        self.bit_buf >>= count;
+        self.bit_count -= count;
+    }
+
+    fn bits(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0
+        }
+        let value = self.peek(count);
+        self.consume(count);
+        value
+    }
+
+    fn align_to_byte(&mut self) {
+        let drop = self.bit_count & 7;
+        self.consume(drop);
+    }
+
+    fn past_end(&self) -> bool {
+        self.overrun > self.bit_count
+    }
+}
+
+// lookup table indexed by the next max_len bits, entries are symbol << 4 | length
+struct InflateHuffman {
+    table: Vec<u16>,
+    max_len: u32,
+}
+
+impl InflateHuffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; 16];
+        for len in lengths {
+            counts[*len as usize] += 1;
+        }
+        counts[0] = 0;
+        let max_len = (1..16).rev().find( | len | counts[*len] != 0).unwrap_or(1) as u32;
+
+        let mut next_code = [0u32; 16];
+        let mut code = 0u32;
+        let mut left = 1i32;
+        for len in 1..16 {
+            left <<= 1;
+            left -= counts[len] as i32;
+            if left < 0 {
+                return Err("inflate: oversubscribed huffman code".to_string())
+            }
+            code = (code + counts[len - 1] as u32) << 1;
+            next_code[len] = code;
+        }
+
+        // unused entries keep length 0, which decodes as an error
+        let mut table = vec![0u16; 1 << max_len];
+        for (symbol, len) in lengths.iter().enumerate() {
+            let len = *len as u32;
+            if len == 0 {
+                continue;
+            }
+            let code = next_code[len as usize];
+            next_code[len as usize] += 1;
+            let mut reversed = 0u32;
+            for bit in 0..len {
+                reversed |= ((code >> bit) & 1) << (len - 1 - bit);
+            }
+            let entry = ((symbol as u16) << 4) | len as u16;
+            let mut index = reversed;
+            while index < (1 << max_len) {
+                table[index as usize] = entry;
+                index += 1 << len;
+            }
+        }
+        Ok(Self {table, max_len})
+    }
+
+    fn decode(&self, bits: &mut InflateBits) -> Result<u16, String> {
+        let entry = self.table[bits.peek(self.max_len) as usize];
+        let len = (entry & 15) as u32;
+        if len == 0 {
+            return Err("inflate: invalid huffman code".to_string())
+        }
+        bits.consume(len);
+        Ok(entry >> 4)
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(data.len() * 4);
+    let mut bits = InflateBits::new(data);
+    loop {
+        if bits.past_end() {
+            return Err("inflate: unexpected end of data".to_string())
+        }
+        let last = bits.bits(1);
+        match bits.bits(2) {
+            0 => {
+                bits.align_to_byte();
+                let len = bits.bits(16);
+                let nlen = bits.bits(16);
+                if len != !nlen & 0xffff {
+                    return Err("inflate: stored block length mismatch".to_string())
+                }
+                for _ in 0..len {
+                    if bits.past_end() {
+                        return Err("inflate: unexpected end of data".to_string())
+                    }
+                    out.push(bits.bits(8) as u8);
+                }
+            },
+            1 => {
+                let mut lengths = [0u8; 288];
+                for (i, len) in lengths.iter_mut().enumerate() {
+                    *len = if i < 144 {8} else if i < 256 {9} else if i < 280 {7} else {8};
+                }
+                let litlen = InflateHuffman::new(&lengths) ?;
+                let dist = InflateHuffman::new(&[5u8; 30]) ?;
+                inflate_block(&mut bits, &mut out, &litlen, &dist) ?;
+            },
+            2 => {
+                let hlit = bits.bits(5) as usize + 257;
+                let hdist = bits.bits(5) as usize + 1;
+                let hclen = bits.bits(4) as usize + 4;
+                let mut code_lengths = [0u8; 19];
+                for i in 0..hclen {
+                    code_lengths[CODE_LENGTH_ORDER[i]] = bits.bits(3) as u8;
+                }
+                let code_huffman = InflateHuffman::new(&code_lengths) ?;
+                let mut lengths = vec![0u8; hlit + hdist];
+                let mut i = 0;
+                while i < hlit + hdist {
+                    if bits.past_end() {
+                        return Err("inflate: unexpected end of data".to_string())
+                    }
+                    let symbol = code_huffman.decode(&mut bits) ?;
+                    let (value, repeat) = match symbol {
+                        0..=15 => (symbol as u8, 1),
+                        16 => {
+                            if i == 0 {
+                                return Err("inflate: repeat without a previous length".to_string())
+                            }
+                            (lengths[i - 1], 3 + bits.bits(2) as usize)
+                        },
+                        17 => (0, 3 + bits.bits(3) as usize),
+                        _ => (0, 11 + bits.bits(7) as usize),
+                    };
+                    if i + repeat > hlit + hdist {
+                        return Err("inflate: too many code lengths".to_string())
+                    }
+                    for _ in 0..repeat {
+                        lengths[i] = value;
+                        i += 1;
+                    }
+                }
+                let litlen = InflateHuffman::new(&lengths[0..hlit]) ?;
+                let dist = InflateHuffman::new(&lengths[hlit..]) ?;
+                inflate_block(&mut bits, &mut out, &litlen, &dist) ?;
+            },
+            _ => return Err("inflate: invalid block type".to_string())
+        }
+        if bits.past_end() {
+            return Err("inflate: unexpected end of data".to_string())
+        }
+        if last == 1 {
+            return Ok(out)
+        }
+    }
+}
+
+fn inflate_block(bits: &mut InflateBits, out: &mut Vec<u8>, litlen: &InflateHuffman, dist: &InflateHuffman) -> Result<(), String> {
+    loop {
+        if bits.past_end() {
+            return Err("inflate: unexpected end of data".to_string())
+        }
+        let symbol = litlen.decode(bits) ? as usize;
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(())
+        }
+        let symbol = symbol - 257;
+        if symbol >= 29 {
+            return Err("inflate: invalid length symbol".to_string())
+        }
+        let len = LENGTH_BASE[symbol] as usize + bits.bits(LENGTH_EXTRA[symbol] as u32) as usize;
+        let dist_symbol = dist.decode(bits) ? as usize;
+        if dist_symbol >= 30 {
+            return Err("inflate: invalid distance symbol".to_string())
+        }
+        let distance = DIST_BASE[dist_symbol] as usize + bits.bits(DIST_EXTRA[dist_symbol] as u32) as usize;
+        if distance > out.len() {
+            return Err("inflate: distance before start of output".to_string())
+        }
+        // byte by byte, the copy may overlap what it writes
+        let start = out.len() - distance;
+        for i in 0..len {
+            let byte = out[start + i];
+            out.push(byte);
+        }
+    }
+}
+
+pub fn inflate_zlib(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 6 {
+        return Err("inflate_zlib: stream too short".to_string())
+    }
+    let cmf = data[0];
+    let flg = data[1];
+    if cmf & 15 != 8 || ((cmf as u16) << 8 | flg as u16) % 31 != 0 {
+        return Err("inflate_zlib: invalid header".to_string())
+    }
+    if flg & 0x20 != 0 {
+        return Err("inflate_zlib: preset dictionaries are not supported".to_string())
+    }
+    inflate(&data[2..])
+}
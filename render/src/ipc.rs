@@ -0,0 +1,372 @@
+use crate::cx::*;
+use makepad_microserde::*;
+use std::io;
+use std::io::prelude::*;
+
+// the wire protocol between a parent window and an app running out of process.
+// the parent sends input, the child sends back the draw lists of its windows and the
+// textures they sample. shaders go by their live id, so the parent draws the calls of
+// shaders it registered itself and skips the rest, like calls with geometry of their own.
+// textures go over as pixels when they change, sub passes and the font atlas are
+// painted in the child with the software renderer.
+
+pub const IPC_SOCKET_ENV: &str = "MAKEPAD_IPC_SOCKET";
+
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub enum IpcParentMsg {
+    Resize {size: Vec2, dpi_factor: f32},
+    FingerDown {abs: Vec2, digit: usize, tap_count: u32, modifiers: KeyModifiers, time: f64},
+    FingerMove {abs: Vec2, abs_start: Vec2, digit: usize, modifiers: KeyModifiers, time: f64},
+    FingerUp {abs: Vec2, abs_start: Vec2, digit: usize, modifiers: KeyModifiers, time: f64},
+    FingerHover {abs: Vec2, modifiers: KeyModifiers, time: f64},
+    FingerScroll {abs: Vec2, scroll: Vec2, modifiers: KeyModifiers, time: f64},
+    KeyDown {key_code: KeyCode, is_repeat: bool, modifiers: KeyModifiers, time: f64},
+    KeyUp {key_code: KeyCode, is_repeat: bool, modifiers: KeyModifiers, time: f64},
+    TextInput {input: String, replace_last: bool, was_paste: bool},
+    TextIme(TextImeEvent),
+    Paste(ClipboardData),
+    AppFocus,
+    AppFocusLost,
+    Close,
+}
+
+// a draw call, with everything but the clip and scroll the parent gets from the views
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub struct IpcDrawCall {
+    pub shader: LiveItemId,
+    pub instance_slots: usize,
+    pub instances: Vec<f32>,
+    pub user_uniforms: Vec<f32>,
+    // texture ids of the child, sent with IpcChildMsg::Texture before the frame using them
+    pub textures: Vec<u32>,
+    pub do_h_scroll: bool,
+    pub do_v_scroll: bool,
+}
+
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub enum IpcDrawItem {
+    DrawList(IpcDrawList),
+    DrawCall(IpcDrawCall),
+}
+
+// a view of the child, in the order it was drawn
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub struct IpcDrawList {
+    pub rect: Rect,
+    pub clipped: bool,
+    pub scroll: Vec2,
+    pub items: Vec<IpcDrawItem>,
+}
+
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub struct IpcFrame {
+    pub window_id: usize,
+    pub size: Vec2,
+    pub dpi_factor: f32,
+    pub clear_color: Vec4,
+    pub root: IpcDrawList,
+}
+
+impl IpcDrawList {
+    // every draw call in drawing order, with the draw list it is in
+    pub fn draw_calls(&self) -> Vec<(&IpcDrawList, &IpcDrawCall)> {
+        let mut out = Vec::new();
+        self.collect_draw_calls(&mut out);
+        out
+    }
+    
+    fn collect_draw_calls<'a>(&'a self, out: &mut Vec<(&'a IpcDrawList, &'a IpcDrawCall)>) {
+        for item in &self.items {
+            match item {
+                IpcDrawItem::DrawList(list) => list.collect_draw_calls(out),
+                IpcDrawItem::DrawCall(call) => out.push((self, call))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, SerBin, DeBin, PartialEq)]
+pub enum IpcChildMsg {
+    // pixels are 0xAARRGGBB like Texture image_u32, first row is the top
+    Texture {texture_id: u32, width: usize, height: usize, pixels: Vec<u32>},
+    // a painted window
+    Frame(IpcFrame),
+    SetHoverCursor(MouseCursor),
+    // the child copied something, the parent puts it on the system clipboard
+    SetClipboard(ClipboardData),
+    Exit,
+}
+
+impl IpcParentMsg {
+    // turns a message into the event the child app sees, for a window of the given size
+    pub fn to_event(&self, window_id: usize, size: Vec2) -> Event {
+        let rect = Rect {pos: Vec2::default(), size};
+        match self {
+            IpcParentMsg::Resize {..} | IpcParentMsg::Close => Event::None,
+            IpcParentMsg::FingerDown {abs, digit, tap_count, modifiers, time} => Event::FingerDown(FingerDownEvent {
+                window_id,
+                abs: *abs,
+                rel: *abs,
+                rect,
+                digit: *digit,
+                tap_count: *tap_count,
+                handled: false,
+                input_type: FingerInputType::Mouse,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::FingerMove {abs, abs_start, digit, modifiers, time} => Event::FingerMove(FingerMoveEvent {
+                window_id,
+                abs: *abs,
+                abs_start: *abs_start,
+                rel: *abs,
+                rel_start: *abs_start,
+                rect,
+                is_over: false,
+                digit: *digit,
+                input_type: FingerInputType::Mouse,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::FingerUp {abs, abs_start, digit, modifiers, time} => Event::FingerUp(FingerUpEvent {
+                window_id,
+                abs: *abs,
+                abs_start: *abs_start,
+                rel: *abs,
+                rel_start: *abs_start,
+                rect,
+                digit: *digit,
+                is_over: false,
+                input_type: FingerInputType::Mouse,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::FingerHover {abs, modifiers, time} => Event::FingerHover(FingerHoverEvent {
+                window_id,
+                digit: 0,
+                abs: *abs,
+                rel: *abs,
+                rect,
+                any_down: false,
+                handled: false,
+                hover_state: HoverState::Over,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::FingerScroll {abs, scroll, modifiers, time} => Event::FingerScroll(FingerScrollEvent {
+                window_id,
+                digit: 0,
+                abs: *abs,
+                rel: *abs,
+                rect,
+                scroll: *scroll,
+                input_type: FingerInputType::Mouse,
+                handled_x: false,
+                handled_y: false,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::KeyDown {key_code, is_repeat, modifiers, time} => Event::KeyDown(KeyEvent {
+                key_code: *key_code,
+                is_repeat: *is_repeat,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::KeyUp {key_code, is_repeat, modifiers, time} => Event::KeyUp(KeyEvent {
+                key_code: *key_code,
+                is_repeat: *is_repeat,
+                modifiers: modifiers.clone(),
+                time: *time
+            }),
+            IpcParentMsg::TextInput {input, replace_last, was_paste} => Event::TextInput(TextInputEvent {
+                input: input.clone(),
+                replace_last: *replace_last,
+                was_paste: *was_paste
+            }),
+            IpcParentMsg::TextIme(e) => Event::TextIme(e.clone()),
+            IpcParentMsg::Paste(data) => Event::Paste(PasteEvent {data: data.clone()}),
+            IpcParentMsg::AppFocus => Event::AppFocus,
+            IpcParentMsg::AppFocusLost => Event::AppFocusLost,
+        }
+    }
+}
+
+pub fn write_ipc_msg<W: Write, T: SerBin>(stream: &mut W, msg: &T) -> io::Result<()> {
+    let data = msg.serialize_bin();
+    stream.write_all(&(data.len() as u64).to_le_bytes()) ?;
+    stream.write_all(&data) ?;
+    stream.flush()
+}
+
+pub fn read_ipc_msg<R: Read, T: DeBin>(stream: &mut R) -> io::Result<T> {
+    let mut len = [0u8; 8];
+    stream.read_exact(&mut len) ?;
+    let bytes_total = u64::from_le_bytes(len) as usize;
+    if bytes_total > 250 * 1024 * 1024 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "read_ipc_msg: message more than 250mb"))
+    }
+    let mut data = vec![0u8; bytes_total];
+    stream.read_exact(&mut data) ?;
+    T::deserialize_bin(&data).map_err( | err | io::Error::new(io::ErrorKind::InvalidData, format!("read_ipc_msg: {}", err)))
+}
+
+// the parent side of a child process, spawned with the socket path in its environment.
+// nothing here blocks: the child connects on a thread, and messages sent before that wait in a queue
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub struct IpcProcess {
+    pub process: std::sync::Arc<std::sync::Mutex<std::process::Child>>,
+    pub socket_path: std::path::PathBuf,
+    sender: std::sync::mpsc::Sender<IpcParentMsg>,
+    receiver: std::sync::mpsc::Receiver<IpcChildMsg>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+impl IpcProcess {
+    pub fn status_message() -> StatusId {uid!()}
+    
+    // posts `signal` with status_message whenever a message from the child arrives.
+    // a child that doesn't connect shows up as an Exit message
+    pub fn spawn(mut command: std::process::Command, signal: Signal) -> io::Result<IpcProcess> {
+        use std::os::unix::net::UnixListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{mpsc, Arc, Mutex};
+        
+        static SOCKET_COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let socket_path = std::env::temp_dir().join(format!(
+            "makepad-ipc-{}-{}.sock",
+            std::process::id(),
+            SOCKET_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path) ?;
+        listener.set_nonblocking(true) ?;
+        
+        let process = Arc::new(Mutex::new(command.env(IPC_SOCKET_ENV, &socket_path).spawn() ?));
+        
+        let (sender, write_receiver) = mpsc::channel::<IpcParentMsg>();
+        let (read_sender, receiver) = mpsc::channel();
+        let accept_process = process.clone();
+        std::thread::spawn(move || {
+            let post = | msg: IpcChildMsg | {
+                let sent = read_sender.send(msg).is_ok();
+                if !signal.is_empty() {
+                    Cx::post_signal(signal, Self::status_message());
+                }
+                sent
+            };
+            let mut read_stream = match Self::accept(&listener, &accept_process) {
+                Ok(stream) => stream,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    post(IpcChildMsg::Exit);
+                    return
+                }
+            };
+            if let Ok(mut write_stream) = read_stream.try_clone() {
+                std::thread::spawn(move || {
+                    while let Ok(msg) = write_receiver.recv() {
+                        if write_ipc_msg(&mut write_stream, &msg).is_err() {
+                            return
+                        }
+                    }
+                });
+            }
+            loop {
+                let msg = match read_ipc_msg::<_, IpcChildMsg>(&mut read_stream) {
+                    Ok(msg) => msg,
+                    Err(_) => IpcChildMsg::Exit
+                };
+                let exit = msg == IpcChildMsg::Exit;
+                if !post(msg) || exit {
+                    return
+                }
+            }
+        });
+        
+        Ok(IpcProcess {
+            process,
+            socket_path,
+            sender,
+            receiver,
+        })
+    }
+    
+    // waits for the child to connect, or to die trying
+    fn accept(
+        listener: &std::os::unix::net::UnixListener,
+        process: &std::sync::Mutex<std::process::Child>
+    ) -> io::Result<std::os::unix::net::UnixStream> {
+        use std::time::{Duration, Instant};
+        let start = Instant::now();
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false) ?;
+                    return Ok(stream)
+                },
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    let mut process = process.lock().map_err( | _ | io::Error::new(io::ErrorKind::Other, "IpcProcess: poisoned")) ?;
+                    if let Some(status) = process.try_wait() ? {
+                        return Err(io::Error::new(io::ErrorKind::Other, format!("IpcProcess: child exited with {} before connecting", status)))
+                    }
+                    if start.elapsed() > Duration::from_secs(30) {
+                        let _ = process.kill();
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "IpcProcess: child did not connect"))
+                    }
+                },
+                Err(err) => {
+                    let _ = process.lock().map( | mut process | process.kill());
+                    return Err(err)
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+    
+    // queues the message, it goes out once the child is connected
+    pub fn send(&mut self, msg: &IpcParentMsg) -> io::Result<()> {
+        self.sender.send(msg.clone()).map_err( | _ | io::Error::new(io::ErrorKind::BrokenPipe, "IpcProcess: child is gone"))
+    }
+    
+    pub fn try_recv(&self) -> Option<IpcChildMsg> {
+        self.receiver.try_recv().ok()
+    }
+    
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Option<IpcChildMsg> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+    
+    pub fn try_wait(&self) -> io::Result<Option<std::process::ExitStatus>> {
+        match self.process.lock() {
+            Ok(mut process) => process.try_wait(),
+            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "IpcProcess: poisoned"))
+        }
+    }
+    
+    // asks the child to close, and kills it if it doesn't within the timeout
+    pub fn close(&mut self, timeout: std::time::Duration) -> io::Result<std::process::ExitStatus> {
+        let _ = self.send(&IpcParentMsg::Close);
+        let start = std::time::Instant::now();
+        loop {
+            if let Some(status) = self.try_wait() ? {
+                return Ok(status)
+            }
+            if start.elapsed() > timeout {
+                let mut process = self.process.lock().map_err( | _ | io::Error::new(io::ErrorKind::Other, "IpcProcess: poisoned")) ?;
+                process.kill() ?;
+                return process.wait()
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+impl Drop for IpcProcess {
+    fn drop(&mut self) {
+        if let Ok(None) = self.try_wait() {
+            let _ = self.close(std::time::Duration::from_secs(1));
+        }
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
@@ -0,0 +1,508 @@
+use crate::cx::*;
+use makepad_microserde::*;
+
+// keymap scopes, a widget looks its keys up in its own scope and falls back to global
+pub const KEYMAP_GLOBAL: &'static str = "global";
+pub const KEYMAP_EDITOR: &'static str = "editor";
+pub const KEYMAP_FILE_TREE: &'static str = "file_tree";
+pub const KEYMAP_TREE_VIEW: &'static str = "tree_view";
+pub const KEYMAP_DATA_GRID: &'static str = "data_grid";
+pub const KEYMAP_POPUP: &'static str = "popup";
+pub const KEYMAP_SPINNER: &'static str = "spinner";
+
+// the bindings everything starts with, files loaded later are layered on top
+pub const DEFAULT_KEYMAP_RON: &'static str = r#"(
+    bindings: [
+        (scope: "global", keys: "Mod+Q", command: "quit"),
+        (scope: "global", keys: "Mod+Z", command: "undo"),
+        (scope: "global", keys: "Mod+Shift+Z", command: "redo"),
+        (scope: "global", keys: "Mod+X", command: "cut"),
+        (scope: "global", keys: "Mod+C", command: "copy"),
+        (scope: "global", keys: "Mod+V", command: "paste"),
+        (scope: "global", keys: "Mod+A", command: "select_all"),
+        (scope: "global", keys: "Mod+Minus", command: "zoom_out"),
+        (scope: "global", keys: "Mod+Equals", command: "zoom_in"),
+        (scope: "global", keys: "Mod+M", command: "minimize"),
+        
+        (scope: "editor", keys: "Escape", command: "editor.escape"),
+        (scope: "editor", keys: "Left", command: "editor.move_left"),
+        (scope: "editor", keys: "Shift+Left", command: "editor.select_left"),
+        (scope: "editor", keys: "Right", command: "editor.move_right"),
+        (scope: "editor", keys: "Shift+Right", command: "editor.select_right"),
+        (scope: "editor", keys: "Up", command: "editor.move_up"),
+        (scope: "editor", keys: "Shift+Up", command: "editor.select_up"),
+        (scope: "editor", keys: "Down", command: "editor.move_down"),
+        (scope: "editor", keys: "Shift+Down", command: "editor.select_down"),
+        (scope: "editor", keys: "Mod+Left", command: "editor.move_token_left"),
+        (scope: "editor", keys: "Mod+Shift+Left", command: "editor.select_token_left"),
+        (scope: "editor", keys: "Mod+Right", command: "editor.move_token_right"),
+        (scope: "editor", keys: "Mod+Shift+Right", command: "editor.select_token_right"),
+        (scope: "editor", keys: "PageUp", command: "editor.move_page_up"),
+        (scope: "editor", keys: "Shift+PageUp", command: "editor.select_page_up"),
+        (scope: "editor", keys: "PageDown", command: "editor.move_page_down"),
+        (scope: "editor", keys: "Shift+PageDown", command: "editor.select_page_down"),
+        (scope: "editor", keys: "Home", command: "editor.move_home"),
+        (scope: "editor", keys: "Shift+Home", command: "editor.select_home"),
+        (scope: "editor", keys: "End", command: "editor.move_end"),
+        (scope: "editor", keys: "Shift+End", command: "editor.select_end"),
+        (scope: "editor", keys: "Backspace", command: "editor.backspace"),
+        (scope: "editor", keys: "Delete", command: "editor.delete"),
+        (scope: "editor", keys: "Tab", command: "editor.indent"),
+        (scope: "editor", keys: "Shift+Tab", command: "editor.outdent"),
+        (scope: "editor", keys: "Return", command: "editor.newline"),
+        (scope: "editor", keys: "Shift+Return", command: "editor.newline"),
+        (scope: "editor", keys: "Mod+Return", command: "editor.auto_format"),
+        (scope: "editor", keys: "Mod+Z", command: "editor.undo"),
+        (scope: "editor", keys: "Mod+Shift+Z", command: "editor.redo"),
+        (scope: "editor", keys: "Mod+X", command: "editor.cut"),
+        (scope: "editor", keys: "Mod+A", command: "editor.select_all"),
+        (scope: "editor", keys: "Mod+F", command: "editor.search"),
+        (scope: "editor", keys: "Mod+S", command: "editor.decl"),
+        (scope: "editor", keys: "Mod+D", command: "editor.next_match"),
+        (scope: "editor", keys: "Mod+Shift+D", command: "editor.add_next_match"),
+        (scope: "editor", keys: "Mod+E", command: "editor.prev_match"),
+        (scope: "editor", keys: "Mod+Shift+E", command: "editor.add_prev_match"),
+        
+        (scope: "file_tree", keys: "Return", command: "file_tree.open"),
+        
+        (scope: "tree_view", keys: "Down", command: "tree_view.next"),
+        (scope: "tree_view", keys: "Up", command: "tree_view.prev"),
+        (scope: "tree_view", keys: "Shift+Down", command: "tree_view.select_next"),
+        (scope: "tree_view", keys: "Shift+Up", command: "tree_view.select_prev"),
+        (scope: "tree_view", keys: "Left", command: "tree_view.collapse"),
+        (scope: "tree_view", keys: "Right", command: "tree_view.expand"),
+        (scope: "tree_view", keys: "Return", command: "tree_view.activate"),
+        (scope: "tree_view", keys: "Mod+A", command: "tree_view.select_all"),
+        (scope: "tree_view", keys: "Escape", command: "tree_view.filter_clear"),
+        (scope: "tree_view", keys: "Backspace", command: "tree_view.filter_back"),
+        
+        (scope: "data_grid", keys: "Up", command: "data_grid.move_up"),
+        (scope: "data_grid", keys: "Down", command: "data_grid.move_down"),
+        (scope: "data_grid", keys: "Left", command: "data_grid.move_left"),
+        (scope: "data_grid", keys: "Right", command: "data_grid.move_right"),
+        (scope: "data_grid", keys: "Shift+Up", command: "data_grid.select_up"),
+        (scope: "data_grid", keys: "Shift+Down", command: "data_grid.select_down"),
+        (scope: "data_grid", keys: "Shift+Left", command: "data_grid.select_left"),
+        (scope: "data_grid", keys: "Shift+Right", command: "data_grid.select_right"),
+        (scope: "data_grid", keys: "PageUp", command: "data_grid.move_page_up"),
+        (scope: "data_grid", keys: "PageDown", command: "data_grid.move_page_down"),
+        (scope: "data_grid", keys: "Mod+Up", command: "data_grid.move_home"),
+        (scope: "data_grid", keys: "Mod+Down", command: "data_grid.move_end"),
+        (scope: "data_grid", keys: "Mod+A", command: "data_grid.select_all"),
+        (scope: "data_grid", keys: "Return", command: "data_grid.activate"),
+        
+        (scope: "popup", keys: "Escape", command: "popup.close"),
+        (scope: "popup", keys: "Down", command: "popup.next"),
+        (scope: "popup", keys: "Up", command: "popup.prev"),
+        (scope: "popup", keys: "Right", command: "popup.open"),
+        (scope: "popup", keys: "Left", command: "popup.back"),
+        (scope: "popup", keys: "Return", command: "popup.activate"),
+        (scope: "popup", keys: "Backspace", command: "popup.erase"),
+        
+        (scope: "spinner", keys: "Up", command: "spinner.increment"),
+        (scope: "spinner", keys: "Down", command: "spinner.decrement"),
+        (scope: "spinner", keys: "Shift+Up", command: "spinner.increment_fast"),
+        (scope: "spinner", keys: "Shift+Down", command: "spinner.decrement_fast"),
+    ]
+)"#;
+
+// one key with the modifiers that have to be down. Mod is control or logo,
+// whichever the platform uses for its shortcuts
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KeyStroke {
+    pub key_code: KeyCode,
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub logo: bool,
+    pub primary: bool,
+}
+
+impl KeyStroke {
+    pub fn from_key_event(ke: &KeyEvent) -> Self {
+        Self {
+            key_code: ke.key_code,
+            shift: ke.modifiers.shift,
+            control: ke.modifiers.control,
+            alt: ke.modifiers.alt,
+            logo: ke.modifiers.logo,
+            primary: false
+        }
+    }
+    
+    // parses Mod+Shift+Z, names are the KeyCode names or their short forms (Z, 1, Left)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut stroke = KeyStroke::default();
+        let mut key_code = None;
+        for part in s.split('+') {
+            match part.trim().to_lowercase().as_ref() {
+                "shift" => stroke.shift = true,
+                "ctrl" | "control" => stroke.control = true,
+                "alt" | "option" => stroke.alt = true,
+                "logo" | "cmd" | "super" => stroke.logo = true,
+                "mod" => stroke.primary = true,
+                name => {
+                    if key_code.is_some() {
+                        return Err(format!("{} has more than one key", s))
+                    }
+                    key_code = Some(key_code_from_name(name).ok_or_else( || format!("unknown key {} in {}", part, s)) ?);
+                }
+            }
+        }
+        stroke.key_code = key_code.ok_or_else( || format!("{} has no key", s)) ?;
+        Ok(stroke)
+    }
+    
+    // a chord is strokes separated by spaces, Mod+K Mod+C
+    pub fn parse_chord(s: &str) -> Result<Vec<Self>, String> {
+        let chord = s.split_whitespace().map( | s | Self::parse(s)).collect::<Result<Vec<_>, _ >> () ?;
+        if chord.len() == 0 {
+            return Err("empty key chord".to_string())
+        }
+        Ok(chord)
+    }
+    
+    // self is the bound stroke, other what was pressed
+    pub fn matches(&self, other: &KeyStroke) -> bool {
+        self.key_code == other.key_code
+            && self.shift == other.shift
+            && self.alt == other.alt
+            && if self.primary {other.control || other.logo} else {self.control == other.control && self.logo == other.logo}
+    }
+    
+    pub fn is_modifier(&self) -> bool {
+        match self.key_code {
+            KeyCode::Shift | KeyCode::Control | KeyCode::Alt | KeyCode::Logo => true,
+            _ => false
+        }
+    }
+}
+
+fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    let name = name.to_lowercase();
+    for (key_name, key_code) in KEY_NAMES {
+        if key_name.to_lowercase() == name || format!("{:?}", key_code).to_lowercase() == name {
+            return Some(*key_code)
+        }
+    }
+    None
+}
+
+const KEY_NAMES: &'static [(&'static str, KeyCode)] = &[
+    ("Escape", KeyCode::Escape),
+    ("Backtick", KeyCode::Backtick),
+    ("0", KeyCode::Key0),
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("Minus", KeyCode::Minus),
+    ("Equals", KeyCode::Equals),
+    ("Backspace", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("Q", KeyCode::KeyQ),
+    ("W", KeyCode::KeyW),
+    ("E", KeyCode::KeyE),
+    ("R", KeyCode::KeyR),
+    ("T", KeyCode::KeyT),
+    ("Y", KeyCode::KeyY),
+    ("U", KeyCode::KeyU),
+    ("I", KeyCode::KeyI),
+    ("O", KeyCode::KeyO),
+    ("P", KeyCode::KeyP),
+    ("LBracket", KeyCode::LBracket),
+    ("RBracket", KeyCode::RBracket),
+    ("Return", KeyCode::Return),
+    ("A", KeyCode::KeyA),
+    ("S", KeyCode::KeyS),
+    ("D", KeyCode::KeyD),
+    ("F", KeyCode::KeyF),
+    ("G", KeyCode::KeyG),
+    ("H", KeyCode::KeyH),
+    ("J", KeyCode::KeyJ),
+    ("K", KeyCode::KeyK),
+    ("L", KeyCode::KeyL),
+    ("Semicolon", KeyCode::Semicolon),
+    ("Quote", KeyCode::Quote),
+    ("Backslash", KeyCode::Backslash),
+    ("Z", KeyCode::KeyZ),
+    ("X", KeyCode::KeyX),
+    ("C", KeyCode::KeyC),
+    ("V", KeyCode::KeyV),
+    ("B", KeyCode::KeyB),
+    ("N", KeyCode::KeyN),
+    ("M", KeyCode::KeyM),
+    ("Comma", KeyCode::Comma),
+    ("Period", KeyCode::Period),
+    ("Slash", KeyCode::Slash),
+    ("Control", KeyCode::Control),
+    ("Alt", KeyCode::Alt),
+    ("Shift", KeyCode::Shift),
+    ("Logo", KeyCode::Logo),
+    ("Space", KeyCode::Space),
+    ("Capslock", KeyCode::Capslock),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("PrintScreen", KeyCode::PrintScreen),
+    ("Scrolllock", KeyCode::Scrolllock),
+    ("Pause", KeyCode::Pause),
+    ("Insert", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Numpad0", KeyCode::Numpad0),
+    ("Numpad1", KeyCode::Numpad1),
+    ("Numpad2", KeyCode::Numpad2),
+    ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4),
+    ("Numpad5", KeyCode::Numpad5),
+    ("Numpad6", KeyCode::Numpad6),
+    ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8),
+    ("Numpad9", KeyCode::Numpad9),
+    ("NumpadEquals", KeyCode::NumpadEquals),
+    ("NumpadSubtract", KeyCode::NumpadSubtract),
+    ("NumpadAdd", KeyCode::NumpadAdd),
+    ("NumpadDecimal", KeyCode::NumpadDecimal),
+    ("NumpadMultiply", KeyCode::NumpadMultiply),
+    ("NumpadDivide", KeyCode::NumpadDivide),
+    ("Numlock", KeyCode::Numlock),
+    ("NumpadEnter", KeyCode::NumpadEnter),
+    ("Up", KeyCode::ArrowUp),
+    ("Down", KeyCode::ArrowDown),
+    ("Left", KeyCode::ArrowLeft),
+    ("Right", KeyCode::ArrowRight),
+];
+
+// a chord bound to a named command in a scope, an empty command unbinds the chord
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBinding {
+    pub scope: String,
+    pub keys: Vec<KeyStroke>,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct KeymapLayer {
+    pub name: String,
+    pub bindings: Vec<KeyBinding>
+}
+
+// the keymap file format
+#[derive(Clone, Debug, Default, SerRon, DeRon)]
+pub struct KeymapRon {
+    pub bindings: Vec<KeymapRonBinding>
+}
+
+#[derive(Clone, Debug, SerRon, DeRon)]
+pub struct KeymapRonBinding {
+    pub scope: String,
+    pub keys: String,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeymapMatch {
+    None,
+    Prefix,
+    Command(String)
+}
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    pub layers: Vec<KeymapLayer>,
+    // the strokes of a chord that is being typed
+    pub pending: Vec<KeyStroke>,
+    // several widgets can look up the same key event, they all see the chord as it was before it
+    pub event_stroke: Option<(f64, KeyStroke)>,
+    pub event_prefix: Vec<KeyStroke>,
+    // the commands that have a CommandId for menus
+    pub command_ids: Vec<(String, CommandId)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self {
+            layers: Vec::new(),
+            pending: Vec::new(),
+            event_stroke: None,
+            event_prefix: Vec::new(),
+            command_ids: Vec::new(),
+        };
+        keymap.load_ron("default", DEFAULT_KEYMAP_RON).expect("default keymap doesn't parse");
+        keymap
+    }
+}
+
+impl Keymap {
+    
+    pub fn parse_ron(ron: &str) -> Result<Vec<KeyBinding>, String> {
+        let file: KeymapRon = DeRon::deserialize_ron(ron).map_err( | e | format!("{:?}", e)) ?;
+        file.bindings.iter().map( | b | Ok(KeyBinding {
+            scope: b.scope.clone(),
+            keys: KeyStroke::parse_chord(&b.keys) ?,
+            command: b.command.clone()
+        })).collect()
+    }
+    
+    // replaces the layer with this name or puts a new one on top
+    pub fn set_layer(&mut self, name: &str, bindings: Vec<KeyBinding>) {
+        self.pending.clear();
+        if let Some(layer) = self.layers.iter_mut().find( | layer | layer.name == name) {
+            layer.bindings = bindings;
+        }
+        else {
+            self.layers.push(KeymapLayer {name: name.to_string(), bindings});
+        }
+    }
+    
+    pub fn load_ron(&mut self, name: &str, ron: &str) -> Result<(), String> {
+        let bindings = Self::parse_ron(ron) ?;
+        self.set_layer(name, bindings);
+        Ok(())
+    }
+    
+    // the binding that decides a chord in a scope, the topmost layer wins and later bindings in a layer override earlier ones
+    fn find_binding(&self, scope: &str, keys: &[KeyStroke]) -> Option<&KeyBinding> {
+        self.find_binding_by(scope, keys, KeyStroke::matches)
+    }
+    
+    // the binding that is in effect for a bound chord
+    fn find_bound(&self, scope: &str, keys: &[KeyStroke]) -> Option<&KeyBinding> {
+        self.find_binding_by(scope, keys, | a, b | a == b)
+    }
+    
+    fn find_binding_by<F>(&self, scope: &str, keys: &[KeyStroke], eq: F) -> Option<&KeyBinding>
+    where F: Fn(&KeyStroke, &KeyStroke) -> bool {
+        for layer in self.layers.iter().rev() {
+            for binding in layer.bindings.iter().rev() {
+                if binding.scope == scope && binding.keys.len() == keys.len() && binding.keys.iter().zip(keys).all( | (b, k) | eq(b, k)) {
+                    return Some(binding)
+                }
+            }
+        }
+        None
+    }
+    
+    fn is_prefix(&self, scope: &str, keys: &[KeyStroke]) -> bool {
+        for layer in &self.layers {
+            for binding in &layer.bindings {
+                if binding.scope == scope && binding.keys.len() > keys.len() && binding.keys.iter().zip(keys).all( | (b, k) | b.matches(k)) {
+                    // a longer chord only counts if nothing above unbound it
+                    if let Some(binding) = self.find_bound(scope, &binding.keys) {
+                        if binding.command.len() > 0 {
+                            return true
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+    
+    // what the typed keys mean in a scope, global is looked at after it
+    pub fn lookup(&self, scope: &str, keys: &[KeyStroke]) -> KeymapMatch {
+        for scope in &[scope, KEYMAP_GLOBAL] {
+            if let Some(binding) = self.find_binding(scope, keys) {
+                if binding.command.len() > 0 {
+                    return KeymapMatch::Command(binding.command.clone())
+                }
+            }
+            if self.is_prefix(scope, keys) {
+                return KeymapMatch::Prefix
+            }
+        }
+        KeymapMatch::None
+    }
+    
+    // feeds a key event through the chord state, returns the command when a chord completes
+    pub fn handle_key(&mut self, scope: &str, ke: &KeyEvent) -> Option<String> {
+        let stroke = KeyStroke::from_key_event(ke);
+        if stroke.is_modifier() {
+            return None
+        }
+        if self.event_stroke != Some((ke.time, stroke)) {
+            self.event_stroke = Some((ke.time, stroke));
+            self.event_prefix = std::mem::replace(&mut self.pending, Vec::new());
+        }
+        let mut keys = self.event_prefix.clone();
+        keys.push(stroke);
+        match self.lookup(scope, &keys) {
+            KeymapMatch::Command(command) => {
+                self.pending.clear();
+                Some(command)
+            },
+            KeymapMatch::Prefix => {
+                self.pending = keys;
+                None
+            },
+            KeymapMatch::None => None
+        }
+    }
+    
+    pub fn is_pending(&self) -> bool {
+        self.pending.len() > 0
+    }
+    
+    // the single stroke a command is bound to, for showing it in menus
+    pub fn command_key(&self, scope: &str, command: &str) -> Option<KeyStroke> {
+        for layer in self.layers.iter().rev() {
+            for binding in layer.bindings.iter().rev() {
+                if binding.scope == scope && binding.command == command && binding.keys.len() == 1 {
+                    if self.find_bound(scope, &binding.keys) == Some(binding) {
+                        return Some(binding.keys[0])
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Cx {
+    
+    // the command a key event is bound to in a scope
+    pub fn keymap_command(&mut self, scope: &str, ke: &KeyEvent) -> Option<String> {
+        self.keymap.handle_key(scope, ke)
+    }
+    
+    // loads a keymap file as a layer, layers loaded later override earlier ones
+    pub fn load_keymap_ron(&mut self, layer: &str, ron: &str) -> Result<(), String> {
+        self.keymap.load_ron(layer, ron) ?;
+        self.update_command_keys();
+        Ok(())
+    }
+    
+    // the native menus show the global keys of the commands that have a name
+    pub fn update_command_keys(&mut self) {
+        for (name, command_id) in self.keymap.command_ids.clone() {
+            let mut s = if let Some(s) = self.command_settings.get(&command_id) {*s}else {CxCommandSetting::default()};
+            if let Some(stroke) = self.keymap.command_key(KEYMAP_GLOBAL, &name) {
+                s.key_code = stroke.key_code;
+                s.shift = stroke.shift;
+            }
+            else {
+                s.key_code = KeyCode::Unknown;
+                s.shift = false;
+            }
+            self.command_settings.insert(command_id, s);
+        }
+    }
+}
@@ -5,27 +5,27 @@ mod cx;
 #[macro_use]
 mod livemacros;
 
-#[cfg(all(not(feature="ipc"),target_os = "linux"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "linux"))]
 mod cx_opengl;
-#[cfg(all(not(feature="ipc"),target_os = "linux"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "linux"))]
 mod cx_xlib;
-#[cfg(all(not(feature="ipc"),any(target_os = "linux")))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),any(target_os = "linux")))]
 mod cx_linux;
 
-#[cfg(all(not(feature="ipc"),target_os = "macos"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "macos"))]
 mod cx_metal;
-#[cfg(all(not(feature="ipc"),target_os = "macos"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "macos"))]
 mod cx_cocoa;
-#[cfg(all(not(feature="ipc"),any(target_os = "macos")))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),any(target_os = "macos")))]
 mod cx_macos;
-#[cfg(all(not(feature="ipc"),any(target_os = "macos")))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),any(target_os = "macos")))]
 mod cx_apple;
 
-#[cfg(all(not(feature="ipc"),target_os = "windows"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "windows"))]
 mod cx_dx11;
-#[cfg(all(not(feature="ipc"),target_os = "windows"))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),target_os = "windows"))]
 mod cx_win32;
-#[cfg(all(not(feature="ipc"),any(target_os = "windows")))]
+#[cfg(all(not(any(feature="ipc", feature="headless")),any(target_os = "windows")))]
 mod cx_windows;
 
 #[cfg(all(not(feature="ipc"),target_arch = "wasm32"))]
@@ -34,9 +34,23 @@ mod cx_webgl;
 #[cfg(all(not(feature="ipc"),target_arch = "wasm32"))]
 mod cx_wasm32;
 
+#[cfg(all(feature="headless", not(feature="ipc"), any(target_os = "linux", target_os="macos", target_os="windows")))]
+mod cx_headless;
+#[cfg(all(any(feature="headless", feature="ipc"), any(target_os = "linux", target_os="macos", target_os="windows")))]
+mod cx_software;
+#[cfg(all(any(feature="headless", feature="ipc"), any(target_os = "linux", target_os="macos", target_os="windows")))]
+mod cx_software_shader;
+
+#[cfg(feature="ipc")]
+mod cx_ipc_child;
+#[cfg(all(feature="ipc", any(target_os = "linux", target_os="macos")))]
+mod cx_ipc_posix;
+
 #[macro_use]
-#[cfg(all(not(feature="ipc"),any(target_os = "linux", target_os="macos", target_os="windows")))]
+#[cfg(any(target_os = "linux", target_os="macos", target_os="windows"))]
 mod cx_desktop;
+#[cfg(any(target_os = "linux", target_os="macos", target_os="windows"))]
+mod cx_record;
 
 mod cx_style;
 
@@ -47,28 +61,44 @@ mod window;
 mod view;
 mod pass;
 mod texture;
+mod atlas;
+mod inflate;
+mod imagepng;
+mod imagejpeg;
+mod imageqoi;
+mod imagebmp;
+mod image;
 mod animator;
+mod spring;
 mod elements;
 mod area;
 mod geometrygen;
+mod meshload;
 
 mod drawquad;
 mod drawtext;
 mod drawcolor;
 mod drawcube;
+mod drawmesh;
 mod drawimage;
 mod events;
 mod menu; 
+mod keymap;
+mod accessibility;
+mod gesture;
+mod clipboard;
 mod geometry;
 mod shader;
 mod shader_std;
 mod gpuinfo;
+mod ipc;
 
 pub use crate::cx::*;
 pub use crate::drawquad::*;
 pub use crate::drawtext::*;
 pub use crate::drawcolor::*;
 pub use crate::drawcube::*;
+pub use crate::drawmesh::*;
 pub use crate::drawimage::*;
 pub use crate::elements::*;
 
@@ -15,16 +15,17 @@ impl Cx{
     pub fn command_select_all()->CommandId{uid!()}
     
     pub fn command_default_keymap(&mut self){
-        Cx::command_quit().set_key(self, KeyCode::KeyQ);
-        Cx::command_undo().set_key(self, KeyCode::KeyZ);
-        Cx::command_redo().set_key_shift(self, KeyCode::KeyZ);
-        Cx::command_cut().set_key(self, KeyCode::KeyX);
-        Cx::command_copy().set_key(self, KeyCode::KeyC);
-        Cx::command_paste().set_key(self, KeyCode::KeyV);
-        Cx::command_select_all().set_key(self, KeyCode::KeyA);
-        Cx::command_zoom_out().set_key(self, KeyCode::Minus);
-        Cx::command_zoom_in().set_key(self, KeyCode::Equals);
-        Cx::command_minimize().set_key(self, KeyCode::KeyM);
+        Cx::command_quit().set_keymap_command(self, "quit");
+        Cx::command_undo().set_keymap_command(self, "undo");
+        Cx::command_redo().set_keymap_command(self, "redo");
+        Cx::command_cut().set_keymap_command(self, "cut");
+        Cx::command_copy().set_keymap_command(self, "copy");
+        Cx::command_paste().set_keymap_command(self, "paste");
+        Cx::command_select_all().set_keymap_command(self, "select_all");
+        Cx::command_zoom_out().set_keymap_command(self, "zoom_out");
+        Cx::command_zoom_in().set_keymap_command(self, "zoom_in");
+        Cx::command_minimize().set_keymap_command(self, "minimize");
+        Cx::command_zoom().set_keymap_command(self, "zoom");
     }
 }
 
@@ -42,7 +43,7 @@ impl CommandId{
         cx.command_settings.insert(*self, s);
         *self
     }
-
+    
     pub fn set_key(&self, cx:&mut Cx, key_code:KeyCode)->Self{
         let mut s = if let Some(s) = cx.command_settings.get(self){*s}else{CxCommandSetting::default()};
         s.shift = false;
@@ -58,6 +59,14 @@ impl CommandId{
         cx.command_settings.insert(*self, s);
         *self
     }
+    
+    // names the command in the keymap, its key comes from the global scope
+    pub fn set_keymap_command(&self, cx:&mut Cx, name:&str)->Self{
+        cx.keymap.command_ids.retain(|(_, id)| id != self);
+        cx.keymap.command_ids.push((name.to_string(), *self));
+        cx.update_command_keys();
+        *self
+    }
 }
 
 impl Into<CommandId> for TypeId {
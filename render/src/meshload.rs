@@ -0,0 +1,454 @@
+use crate::cx::*;
+use makepad_microserde::*;
+use std::collections::HashMap;
+
+// loads wavefront obj and binary gltf (glb) meshes into the 3d GeometryGen layout.
+// geom_id holds the material index, uvs have their origin at the top left like textures
+
+pub const GLB_MAGIC: [u8; 4] = [b'g', b'l', b'T', b'F'];
+// bytes an accessor without a buffer view may expand to
+const GLB_MAX_ZERO_ACCESSOR: usize = 1 << 26;
+
+impl GeometryGen {
+    pub fn from_mesh_file(data: &[u8]) -> Result<GeometryGen, String> {
+        if data.starts_with(&GLB_MAGIC) {
+            return Self::from_glb(data)
+        }
+        match std::str::from_utf8(data) {
+            Ok(text) => Self::from_obj(text),
+            Err(_) => Err("GeometryGen: mesh file is not glb or obj".to_string())
+        }
+    }
+
+    // polygons are triangulated as fans, normals are computed when the file has none
+    pub fn from_obj(text: &str) -> Result<GeometryGen, String> {
+        let mut positions: Vec<Vec3> = Vec::new();
+        let mut uvs: Vec<Vec2> = Vec::new();
+        let mut normals: Vec<Vec3> = Vec::new();
+        let mut materials: Vec<String> = Vec::new();
+        let mut material = 0.0;
+        let mut vertex_map: HashMap<(usize, Option<usize>, Option<usize>, u32), u32> = HashMap::new();
+        let mut missing_normals = false;
+        let mut g = GeometryGen::default();
+
+        for (line_nr, line) in text.lines().enumerate() {
+            let err = | what: &str | format!("from_obj: {} on line {}", what, line_nr + 1);
+            let mut parts = line.split_whitespace();
+            let kind = if let Some(kind) = parts.next() {kind} else {continue};
+            let floats = | parts: std::str::SplitWhitespace | -> Result<Vec<f32>, String> {
+                parts.map( | p | p.parse::<f32>().map_err( | _ | err("invalid number"))).collect()
+            };
+            match kind {
+                "v" => {
+                    let v = floats(parts) ?;
+                    if v.len() < 3 {
+                        return Err(err("vertex needs 3 coordinates"))
+                    }
+                    positions.push(vec3(v[0], v[1], v[2]));
+                },
+                "vt" => {
+                    let v = floats(parts) ?;
+                    if v.len() < 1 {
+                        return Err(err("texture coordinate needs a value"))
+                    }
+                    uvs.push(vec2(v[0], 1.0 - v.get(1).cloned().unwrap_or(0.0)));
+                },
+                "vn" => {
+                    let v = floats(parts) ?;
+                    if v.len() < 3 {
+                        return Err(err("normal needs 3 coordinates"))
+                    }
+                    normals.push(vec3(v[0], v[1], v[2]).normalize());
+                },
+                "usemtl" => {
+                    let name = parts.collect::<Vec<_>>().join(" ");
+                    let index = if let Some(index) = materials.iter().position( | m | *m == name) {
+                        index
+                    }
+                    else {
+                        materials.push(name);
+                        materials.len() - 1
+                    };
+                    material = index as f32;
+                },
+                "f" => {
+                    let mut face = Vec::new();
+                    for corner in parts {
+                        // indices are 1 based, negative ones count back from the last one
+                        let resolve = | index: Option<&str>, len: usize | -> Result<Option<usize>, String> {
+                            let index = match index {
+                                Some(index) if index.len() > 0 => index,
+                                _ => return Ok(None)
+                            };
+                            let index: i64 = index.parse().map_err( | _ | err("invalid face index")) ?;
+                            let index = if index < 0 {len as i64 + index} else {index - 1};
+                            if index < 0 || index >= len as i64 {
+                                return Err(err("face index out of range"))
+                            }
+                            Ok(Some(index as usize))
+                        };
+                        let mut refs = corner.split('/');
+                        let pos = resolve(refs.next(), positions.len()) ?.ok_or_else( || err("face without a vertex")) ?;
+                        let uv = resolve(refs.next(), uvs.len()) ?;
+                        let normal = resolve(refs.next(), normals.len()) ?;
+                        missing_normals |= normal.is_none();
+                        let key = (pos, uv, normal, material as u32);
+                        let index = if let Some(index) = vertex_map.get(&key) {
+                            *index
+                        }
+                        else {
+                            let index = g.add_vertex_3d(
+                                positions[pos],
+                                material,
+                                normal.map_or(Vec3::all(0.0), | n | normals[n]),
+                                uv.map_or(Vec2::all(0.0), | uv | uvs[uv])
+                            );
+                            vertex_map.insert(key, index);
+                            index
+                        };
+                        face.push(index);
+                    }
+                    for i in 2..face.len() {
+                        g.indices.extend_from_slice(&[face[0], face[i - 1], face[i]]);
+                    }
+                },
+                _ => ()
+            }
+        }
+        if g.indices.len() == 0 {
+            return Err("from_obj: no faces".to_string())
+        }
+        if missing_normals {
+            g.compute_normals_3d();
+        }
+        Ok(g)
+    }
+
+    // the default scene with all node transforms applied. Triangles, strips and fans are
+    // supported, points, lines, sparse accessors and external buffers are not
+    pub fn from_glb(data: &[u8]) -> Result<GeometryGen, String> {
+        let le_u32 = | pos: usize | u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        if data.len() < 20 || !data.starts_with(&GLB_MAGIC) {
+            return Err("from_glb: not a glb file".to_string())
+        }
+        if le_u32(4) != 2 {
+            return Err(format!("from_glb: unsupported version {}", le_u32(4)))
+        }
+        let mut json = None;
+        let mut bin: &[u8] = &[];
+        let mut pos = 12;
+        while pos + 8 <= data.len().min(le_u32(8)) {
+            let len = le_u32(pos);
+            let kind = le_u32(pos + 4);
+            let start = pos + 8;
+            if start + len > data.len() {
+                return Err("from_glb: chunk runs past end of file".to_string())
+            }
+            match kind {
+                0x4E4F534A => json = Some(&data[start..start + len]),
+                0x004E4942 => bin = &data[start..start + len],
+                _ => ()
+            }
+            pos = start + len;
+        }
+        let json = json.ok_or_else( || "from_glb: missing json chunk".to_string()) ?;
+        let json = std::str::from_utf8(json).map_err( | _ | "from_glb: json chunk is not utf8".to_string()) ?;
+        let gltf = JsonValue::deserialize_json(json).map_err( | err | format!("from_glb: {:?}", err)) ?;
+
+        let mut g = GeometryGen::default();
+        let nodes = gltf.get("nodes").and_then( | n | n.as_array());
+        if let Some(nodes) = nodes {
+            let scene = gltf.get("scene").and_then( | s | s.as_usize()).unwrap_or(0);
+            let roots: Vec<usize> = if let Some(scene) = gltf.get("scenes").and_then( | s | s.index(scene)) {
+                scene.get("nodes").and_then( | n | n.as_array()).map_or(Vec::new(), | n | n.iter().filter_map( | n | n.as_usize()).collect())
+            }
+            else {
+                // no scenes, draw every node that isn't a child
+                let children: Vec<usize> = nodes.iter().filter_map( | n | n.get("children")).filter_map( | c | c.as_array()).flatten().filter_map( | c | c.as_usize()).collect();
+                (0..nodes.len()).filter( | n | !children.contains(n)).collect()
+            };
+            let mut stack: Vec<(usize, Mat4, usize)> = roots.iter().map( | root | (*root, Mat4::identity(), 0)).collect();
+            while let Some((index, parent, depth)) = stack.pop() {
+                let node = nodes.get(index).ok_or_else( || format!("from_glb: node {} out of range", index)) ?;
+                if depth > 64 {
+                    return Err("from_glb: node hierarchy too deep".to_string())
+                }
+                let world = Mat4::mul(&glb_node_transform(node) ?, &parent);
+                if let Some(mesh) = node.get("mesh").and_then( | m | m.as_usize()) {
+                    glb_add_mesh(&mut g, &gltf, bin, mesh, &world) ?;
+                }
+                if let Some(children) = node.get("children").and_then( | c | c.as_array()) {
+                    for child in children.iter().filter_map( | c | c.as_usize()) {
+                        stack.push((child, world, depth + 1));
+                    }
+                }
+            }
+        }
+        else if let Some(meshes) = gltf.get("meshes").and_then( | m | m.as_array()) {
+            for mesh in 0..meshes.len() {
+                glb_add_mesh(&mut g, &gltf, bin, mesh, &Mat4::identity()) ?;
+            }
+        }
+        if g.indices.len() == 0 {
+            return Err("from_glb: no triangles in the scene".to_string())
+        }
+        Ok(g)
+    }
+}
+
+fn glb_floats(value: Option<&JsonValue>, len: usize) -> Option<Vec<f32>> {
+    let values: Vec<f32> = value ?.as_array() ?.iter().filter_map( | v | v.as_f64()).map( | v | v as f32).collect();
+    if values.len() == len {Some(values)} else {None}
+}
+
+fn glb_node_transform(node: &JsonValue) -> Result<Mat4, String> {
+    if let Some(m) = glb_floats(node.get("matrix"), 16) {
+        let mut v = [0.0; 16];
+        v.copy_from_slice(&m);
+        return Ok(Mat4 {v})
+    }
+    let t = glb_floats(node.get("translation"), 3).unwrap_or(vec![0.0, 0.0, 0.0]);
+    let r = glb_floats(node.get("rotation"), 4).unwrap_or(vec![0.0, 0.0, 0.0, 1.0]);
+    let s = glb_floats(node.get("scale"), 3).unwrap_or(vec![1.0, 1.0, 1.0]);
+    let (x, y, z, w) = (r[0], r[1], r[2], r[3]);
+    // column major translation * rotation * scale
+    Ok(Mat4 {v: [
+        (1.0 - 2.0 * (y * y + z * z)) * s[0],
+        (2.0 * (x * y + z * w)) * s[0],
+        (2.0 * (x * z - y * w)) * s[0],
+        0.0,
+        (2.0 * (x * y - z * w)) * s[1],
+        (1.0 - 2.0 * (x * x + z * z)) * s[1],
+        (2.0 * (y * z + x * w)) * s[1],
+        0.0,
+        (2.0 * (x * z + y * w)) * s[2],
+        (2.0 * (y * z - x * w)) * s[2],
+        (1.0 - 2.0 * (x * x + y * y)) * s[2],
+        0.0,
+        t[0],
+        t[1],
+        t[2],
+        1.0
+    ]})
+}
+
+// reads an accessor as doubles so u32 indices survive, returns the values and components per element
+fn glb_accessor(gltf: &JsonValue, bin: &[u8], index: usize) -> Result<(Vec<f64>, usize), String> {
+    let err = | what: &str | format!("from_glb: accessor {} {}", index, what);
+    let accessor = gltf.get("accessors").and_then( | a | a.index(index)).ok_or_else( || err("not found")) ?;
+    if accessor.get("sparse").is_some() {
+        return Err(err("is sparse, which is not supported"))
+    }
+    let count = accessor.get("count").and_then( | c | c.as_usize()).ok_or_else( || err("has no count")) ?;
+    let components = match accessor.get("type").and_then( | t | t.as_str()) {
+        Some("SCALAR") => 1,
+        Some("VEC2") => 2,
+        Some("VEC3") => 3,
+        Some("VEC4") => 4,
+        Some("MAT4") => 16,
+        _ => return Err(err("has an unsupported type"))
+    };
+    let component_type = accessor.get("componentType").and_then( | c | c.as_usize()).unwrap_or(0);
+    let component_size = match component_type {
+        5120 | 5121 => 1,
+        5122 | 5123 => 2,
+        5125 | 5126 => 4,
+        _ => return Err(err("has an unsupported component type"))
+    };
+    let normalized = accessor.get("normalized") == Some(&JsonValue::Bool(true));
+    let element_size = component_size * components;
+    let view = if let Some(view) = accessor.get("bufferView").and_then( | v | v.as_usize()) {
+        view
+    }
+    else {
+        // no buffer view means all zeros, which still has to be a sane amount of them
+        if count.checked_mul(element_size).map_or(true, | size | size > GLB_MAX_ZERO_ACCESSOR) {
+            return Err(err("is too large"))
+        }
+        return Ok((vec![0.0; count * components], components))
+    };
+    let view = gltf.get("bufferViews").and_then( | v | v.index(view)).ok_or_else( || err("has a missing buffer view")) ?;
+    if view.get("buffer").and_then( | b | b.as_usize()).unwrap_or(0) != 0 {
+        return Err(err("uses an external buffer"))
+    }
+    let view_offset = view.get("byteOffset").and_then( | o | o.as_usize()).unwrap_or(0);
+    let view_length = view.get("byteLength").and_then( | o | o.as_usize()).unwrap_or(0);
+    let stride = view.get("byteStride").and_then( | s | s.as_usize()).unwrap_or(element_size).max(element_size);
+    let start = view_offset.checked_add(accessor.get("byteOffset").and_then( | o | o.as_usize()).unwrap_or(0));
+    let view_end = view_offset.checked_add(view_length).map_or(0, | end | end.min(bin.len()));
+    // json numbers can be anything, so the end is computed without overflowing
+    let end = if count == 0 {start} else {
+        start.and_then( | start | stride.checked_mul(count - 1) ?.checked_add(element_size) ?.checked_add(start))
+    };
+    let start = match end {
+        Some(end) if end <= view_end => start.unwrap(),
+        _ => return Err(err("reads past the end of its buffer"))
+    };
+    let mut out = Vec::with_capacity(count * components);
+    for element in 0..count {
+        let base = start + element * stride;
+        for component in 0..components {
+            let p = base + component * component_size;
+            let b = &bin[p..p + component_size];
+            let value = match component_type {
+                5120 => if normalized {(b[0] as i8 as f64 / 127.0).max(-1.0)} else {b[0] as i8 as f64},
+                5121 => if normalized {b[0] as f64 / 255.0} else {b[0] as f64},
+                5122 => {
+                    let v = i16::from_le_bytes([b[0], b[1]]) as f64;
+                    if normalized {(v / 32767.0).max(-1.0)} else {v}
+                },
+                5123 => {
+                    let v = u16::from_le_bytes([b[0], b[1]]) as f64;
+                    if normalized {v / 65535.0} else {v}
+                },
+                5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            };
+            out.push(value);
+        }
+    }
+    Ok((out, components))
+}
+
+fn glb_add_mesh(g: &mut GeometryGen, gltf: &JsonValue, bin: &[u8], mesh: usize, world: &Mat4) -> Result<(), String> {
+    let primitives = gltf.get("meshes").and_then( | m | m.index(mesh)).and_then( | m | m.get("primitives")).and_then( | p | p.as_array())
+        .ok_or_else( || format!("from_glb: mesh {} has no primitives", mesh)) ?;
+
+    // normals go through the inverse transpose, mirrored transforms flip the winding
+    let inv = world.invert();
+    let m = &world.v;
+    let det = m[0] * (m[5] * m[10] - m[9] * m[6]) - m[4] * (m[1] * m[10] - m[9] * m[2]) + m[8] * (m[1] * m[6] - m[5] * m[2]);
+
+    for primitive in primitives {
+        let mode = primitive.get("mode").and_then( | m | m.as_usize()).unwrap_or(4);
+        if mode < 4 {
+            continue;
+        }
+        let attributes = primitive.get("attributes").ok_or_else( || format!("from_glb: mesh {} primitive without attributes", mesh)) ?;
+        let attribute = | name: &str | attributes.get(name).and_then( | a | a.as_usize());
+        let position = attribute("POSITION").ok_or_else( || format!("from_glb: mesh {} primitive without positions", mesh)) ?;
+        let (positions, pc) = glb_accessor(gltf, bin, position) ?;
+        if pc != 3 {
+            return Err(format!("from_glb: mesh {} positions are not VEC3", mesh))
+        }
+        let vertex_count = positions.len() / pc;
+        // every attribute has one element per vertex
+        let vertex_attribute = | name: &str, components: usize, kind: &str | -> Result<Option<(Vec<f64>, usize)>, String> {
+            let index = if let Some(index) = attribute(name) {index} else {return Ok(None)};
+            let (values, c) = glb_accessor(gltf, bin, index) ?;
+            if c != components {
+                return Err(format!("from_glb: mesh {} {} is not {}", mesh, name, kind))
+            }
+            if values.len() / c != vertex_count {
+                return Err(format!("from_glb: mesh {} {} has {} elements for {} vertices", mesh, name, values.len() / c, vertex_count))
+            }
+            Ok(Some((values, c)))
+        };
+        let normals = vertex_attribute("NORMAL", 3, "VEC3") ?;
+        let uvs = vertex_attribute("TEXCOORD_0", 2, "VEC2") ?;
+        let id = primitive.get("material").and_then( | m | m.as_usize()).unwrap_or(0) as f32;
+
+        let mut prim = GeometryGen::default();
+        for i in 0..vertex_count {
+            let p = world.transform_vec4(vec4(positions[i * pc] as f32, positions[i * pc + 1] as f32, positions[i * pc + 2] as f32, 1.0));
+            let normal = if let Some((n, nc)) = &normals {
+                let (x, y, z) = (n[i * nc] as f32, n[i * nc + 1] as f32, n[i * nc + 2] as f32);
+                let iv = &inv.v;
+                vec3(iv[0] * x + iv[1] * y + iv[2] * z, iv[4] * x + iv[5] * y + iv[6] * z, iv[8] * x + iv[9] * y + iv[10] * z).normalize()
+            }
+            else {
+                Vec3::all(0.0)
+            };
+            let uv = if let Some((t, tc)) = &uvs {vec2(t[i * tc] as f32, t[i * tc + 1] as f32)} else {Vec2::all(0.0)};
+            prim.add_vertex_3d(vec3(p.x, p.y, p.z), id, normal, uv);
+        }
+
+        let indices: Vec<u32> = if let Some(indices) = primitive.get("indices").and_then( | i | i.as_usize()) {
+            let (indices, ic) = glb_accessor(gltf, bin, indices) ?;
+            if ic != 1 {
+                return Err(format!("from_glb: mesh {} indices are not SCALAR", mesh))
+            }
+            indices.iter().map( | i | *i as u32).collect()
+        }
+        else {
+            (0..vertex_count as u32).collect()
+        };
+        if indices.iter().any( | i | *i as usize >= vertex_count) {
+            return Err(format!("from_glb: mesh {} has an index out of range", mesh))
+        }
+        match mode {
+            4 => prim.indices = indices.chunks_exact(3).flatten().cloned().collect(),
+            5 => for i in 2..indices.len() {
+                // every other strip triangle is wound the other way
+                if i % 2 == 0 {
+                    prim.indices.extend_from_slice(&[indices[i - 2], indices[i - 1], indices[i]]);
+                }
+                else {
+                    prim.indices.extend_from_slice(&[indices[i - 1], indices[i - 2], indices[i]]);
+                }
+            },
+            6 => for i in 2..indices.len() {
+                prim.indices.extend_from_slice(&[indices[0], indices[i - 1], indices[i]]);
+            },
+            _ => continue
+        }
+        if det < 0.0 {
+            for tri in prim.indices.chunks_mut(3) {
+                tri.swap(1, 2);
+            }
+        }
+        if normals.is_none() {
+            prim.compute_normals_3d();
+        }
+        g.append_3d(&prim);
+    }
+    Ok(())
+}
+
+// a mesh file read with cx.file_read and turned into a Geometry when it arrives
+#[derive(Clone)]
+pub struct MeshLoad {
+    pub file_read: FileRead,
+    pub geometry: Option<Geometry>,
+    pub error: Option<String>,
+}
+
+impl MeshLoad {
+    pub fn new(cx: &mut Cx, path: &str) -> Self {
+        Self {
+            file_read: cx.file_read(path),
+            geometry: None,
+            error: None
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.geometry.is_some()
+    }
+
+    // returns the result once, when the file read for this mesh comes in
+    pub fn handle_mesh_load(&mut self, cx: &mut Cx, event: &mut Event) -> Option<Result<Geometry, String>> {
+        if let Event::FileRead(fr) = event {
+            if !self.file_read.is_pending() || fr.read_id != self.file_read.read_id {
+                return None
+            }
+            self.file_read.read_id = 0;
+            let result = match &fr.data {
+                Ok(data) => GeometryGen::from_mesh_file(data).map_err( | err | format!("{} {}", self.file_read.path, err)),
+                Err(err) => Err(err.clone())
+            };
+            return Some(match result {
+                Ok(gen) => {
+                    let geometry = Geometry::from_geometry_gen(cx, gen);
+                    self.geometry = Some(geometry);
+                    Ok(geometry)
+                },
+                Err(err) => {
+                    self.error = Some(err.clone());
+                    Err(err)
+                }
+            })
+        }
+        None
+    }
+}
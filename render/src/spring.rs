@@ -0,0 +1,101 @@
+use crate::cx::*;
+
+// a damped spring towards a target, retargeting keeps the velocity so interrupted motion stays smooth.
+// Values are held as Vec4, the TrackValue types convert in and out of it
+#[derive(Clone, Copy, Debug)]
+pub struct Spring {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub precision: f32,
+    pub value: Vec4,
+    pub velocity: Vec4,
+    pub target: Vec4,
+    pub last_time: f64,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Spring::critical(200.0)
+    }
+}
+
+impl Spring {
+    
+    pub fn new(stiffness: f64, damping: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            precision: 0.001,
+            value: Vec4::all(0.0),
+            velocity: Vec4::all(0.0),
+            target: Vec4::all(0.0),
+            last_time: std::f64::NAN,
+        }
+    }
+    
+    // the fastest spring that doesnt overshoot
+    pub fn critical(stiffness: f64) -> Self {
+        Self::new(stiffness, 2.0 * stiffness.sqrt())
+    }
+    
+    pub fn with_precision(self, precision: f32) -> Self {Self {precision, ..self}}
+    
+    // jumps to a value and stops there
+    pub fn set_value<T: TrackValue>(&mut self, value: T) {
+        self.value = value.to_vec4();
+        self.target = self.value;
+        self.velocity = Vec4::all(0.0);
+        self.last_time = std::f64::NAN;
+    }
+    
+    pub fn set_target<T: TrackValue>(&mut self, target: T) {
+        self.target = target.to_vec4();
+    }
+    
+    pub fn value<T: TrackValue>(&self) -> T {
+        T::from_vec4(self.value)
+    }
+    
+    pub fn target<T: TrackValue>(&self) -> T {
+        T::from_vec4(self.target)
+    }
+    
+    pub fn is_settled(&self) -> bool {
+        let d = self.target - self.value;
+        let v = self.velocity;
+        d.x.abs().max(d.y.abs()).max(d.z.abs()).max(d.w.abs()) < self.precision
+            && v.x.abs().max(v.y.abs()).max(v.z.abs()).max(v.w.abs()) < self.precision
+    }
+    
+    // moves the spring to time, the first step after a rest counts as one frame
+    pub fn step(&mut self, time: f64) -> Vec4 {
+        let dt = if self.last_time.is_nan() {1.0 / 60.0} else {time - self.last_time};
+        self.last_time = time;
+        self.advance(dt);
+        self.value
+    }
+    
+    // semi-implicit euler in small substeps, long frames are capped so a hitch doesnt explode it
+    pub fn advance(&mut self, dt: f64) {
+        if self.is_settled() {
+            self.value = self.target;
+            self.velocity = Vec4::all(0.0);
+            self.last_time = std::f64::NAN;
+            return
+        }
+        let dt = dt.max(0.0).min(0.1);
+        let steps = (dt * 240.0).ceil().max(1.0);
+        let h = (dt / steps) as f32;
+        let start = self.value;
+        for _ in 0..steps as usize {
+            let force = (self.target - self.value) * self.stiffness as f32 - self.velocity * self.damping as f32;
+            self.velocity += force * h;
+            self.value += self.velocity * h;
+        }
+        // far from zero the last bit of a glide can be smaller than an f32 step, then it would never arrive
+        if self.is_settled() || self.value == start {
+            self.value = self.target;
+            self.velocity = Vec4::all(0.0);
+        }
+    }
+}
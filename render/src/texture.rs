@@ -33,6 +33,7 @@ impl TextureCx for Texture{
 #[derive(Default)]
 pub struct CxTexture {
     pub desc: TextureDesc,
+    // 0xAARRGGBB on every platform, first row is the top
     pub image_u32: Vec<u32>,
     pub image_f32: Vec<f32>,
     pub update_image: bool,
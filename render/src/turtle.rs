@@ -47,6 +47,13 @@ impl Cx {
         let (width, min_width) = self.eval_width(&layout.walk.width, layout.walk.margin, is_abs_origin, abs_size.x);
         let (height, min_height) = self.eval_height(&layout.walk.height, layout.walk.margin, is_abs_origin, abs_size.y);
         
+        let grid = layout.grid.map( | grid | TurtleGrid::new(
+            &grid,
+            width - (layout.padding.l + layout.padding.r),
+            height - (layout.padding.t + layout.padding.b),
+            layout.gap
+        ));
+        
         let turtle = Turtle {
             align_list_x: self.align_list.len(),
             align_list_y: self.align_list.len(),
@@ -64,6 +71,7 @@ impl Cx {
             height_used: 0.,
             abs_size: abs_size,
             guard_area: guard_area,
+            grid: grid,
             //..Default::default()
         };
         
@@ -122,6 +130,81 @@ impl Cx {
         return self.walk_turtle_with_old(Walk {width: w, height: h, margin}, Some(&old))
     }
     
+    // a measure pass: whatever draws between begin_measure and end_measure walks a turtle
+    // at the current position with the space that is left, and everything it emitted is
+    // taken out again. Areas it got point past the instances, so the real draw right after
+    // moves finger and key focus over to the real areas like a normal redraw does.
+    // Measured content can't begin views
+    pub fn begin_measure(&mut self) {
+        let view = self.view_stack.last().map( | view_id | {
+            let cxview = &self.views[*view_id];
+            (*view_id, cxview.draw_calls_len, cxview.draw_calls[0..cxview.draw_calls_len].iter().map( | dc | dc.instances.len()).collect())
+        });
+        self.measures.push(CxMeasure {
+            align_list_len: self.align_list.len(),
+            turtles_len: self.turtles.len(),
+            view
+        });
+        let pos = self.get_turtle_pos();
+        let (width, height) = (self.get_width_left(), self.get_height_left());
+        self.begin_turtle(Layout {
+            abs_origin: Some(pos),
+            walk: Walk::wh(Width::Fix(width), Height::Fix(height)),
+            ..Layout::default()
+        }, Area::Empty);
+    }
+    
+    // the size the measured content walked, from the position it started at
+    pub fn end_measure(&mut self) -> Vec2 {
+        let measure = self.measures.pop().expect("end_measure without begin_measure");
+        if self.turtles.len() != measure.turtles_len + 1 {
+            panic!("end_measure turtles not matched, begin/end pairs inside the measure are unbalanced");
+        }
+        let size = if let Some(turtle) = self.turtles.last() {
+            vec2(
+                if turtle.bound_right_bottom.x == std::f32::NEG_INFINITY {0.0} else {turtle.bound_right_bottom.x - turtle.origin.x},
+                if turtle.bound_right_bottom.y == std::f32::NEG_INFINITY {0.0} else {turtle.bound_right_bottom.y - turtle.origin.y}
+            )
+        }
+        else {
+            Vec2::default()
+        };
+        self.end_turtle(Area::Empty);
+        
+        self.align_list.truncate(measure.align_list_len);
+        if let Some((view_id, draw_calls_len, instances_lens)) = measure.view {
+            let cxview = &mut self.views[view_id];
+            for (draw_call, len) in cxview.draw_calls.iter_mut().zip(instances_lens) {
+                if draw_call.instances.len() != len {
+                    draw_call.instances.truncate(len);
+                    draw_call.instance_dirty = true;
+                }
+            }
+            cxview.draw_calls_len = draw_calls_len;
+        }
+        size
+    }
+    
+    pub fn measure<F: FnOnce(&mut Cx)>(&mut self, f: F) -> Vec2 {
+        self.begin_measure();
+        f(self);
+        self.end_measure()
+    }
+    
+    pub fn is_measuring(&self) -> bool {
+        self.measures.len() > 0
+    }
+    
+    // moves the turtle so that something of a measured size lands aligned in the space that is left
+    pub fn move_turtle_to_align(&mut self, size: Vec2, align: Align) {
+        let dx = self.get_width_left() - size.x;
+        let dy = self.get_height_left() - size.y;
+        self.move_turtle(
+            if dx > 0.0 {dx * align.fx} else {0.0},
+            if dy > 0.0 {dy * align.fy} else {0.0}
+        );
+    }
+    
     pub fn walk_turtle(&mut self, walk: Walk) -> Rect {
         self.walk_turtle_with_old(walk, None)
     }
@@ -135,6 +218,9 @@ impl Cx {
         
         let ret = if let Some(turtle) = self.turtles.last_mut() {
             let (x, y) = match turtle.layout.direction {
+                _ if turtle.grid.is_some() => {
+                    turtle.walk_grid(h, walk.margin)
+                },
                 Direction::Right => {
                     match turtle.layout.line_wrap {
                         LineWrap::NewLine => {
@@ -144,7 +230,7 @@ impl Cx {
                                 let old_x = turtle.pos.x;
                                 let old_y = turtle.pos.y;
                                 turtle.pos.x = turtle.origin.x + turtle.layout.padding.l;
-                                turtle.pos.y += turtle.biggest;
+                                turtle.pos.y += turtle.biggest + turtle.layout.gap.y;
                                 turtle.biggest = 0.0;
                                 align_dx = turtle.pos.x - old_x;
                                 align_dy = turtle.pos.y - old_y;
@@ -158,7 +244,7 @@ impl Cx {
                                 let old_x = turtle.pos.x;
                                 let old_y = turtle.pos.y;
                                 turtle.pos.x = turtle.origin.x + turtle.layout.padding.l;
-                                turtle.pos.y += turtle.biggest;
+                                turtle.pos.y += turtle.biggest + turtle.layout.gap.y;
                                 turtle.biggest = 0.0;
                                 align_dx = turtle.pos.x - old_x;
                                 align_dy = turtle.pos.y - old_y;
@@ -171,7 +257,7 @@ impl Cx {
                     let x = turtle.pos.x + walk.margin.l;
                     let y = turtle.pos.y + walk.margin.t;
                     // walk it normally
-                    turtle.pos.x += w + walk.margin.l + walk.margin.r;
+                    turtle.pos.x += w + walk.margin.l + walk.margin.r + turtle.layout.gap.x;
                     
                     // keep track of biggest item in the line (include item margin bottom)
                     let biggest = h + walk.margin.t + walk.margin.b;
@@ -189,7 +275,7 @@ impl Cx {
                                 let old_x = turtle.pos.x;
                                 let old_y = turtle.pos.y;
                                 turtle.pos.y = turtle.origin.y + turtle.layout.padding.t;
-                                turtle.pos.x += turtle.biggest;
+                                turtle.pos.x += turtle.biggest + turtle.layout.gap.x;
                                 turtle.biggest = 0.0;
                                 align_dx = turtle.pos.x - old_x;
                                 align_dy = turtle.pos.y - old_y;
@@ -203,7 +289,7 @@ impl Cx {
                                 let old_x = turtle.pos.x;
                                 let old_y = turtle.pos.y;
                                 turtle.pos.y = turtle.origin.y + turtle.layout.padding.t;
-                                turtle.pos.x += turtle.biggest;
+                                turtle.pos.x += turtle.biggest + turtle.layout.gap.x;
                                 turtle.biggest = 0.0;
                                 align_dx = turtle.pos.x - old_x;
                                 align_dy = turtle.pos.y - old_y;
@@ -216,7 +302,7 @@ impl Cx {
                     let x = turtle.pos.x + walk.margin.l;
                     let y = turtle.pos.y + walk.margin.t;
                     // walk it normally
-                    turtle.pos.y += h + walk.margin.t + walk.margin.b;
+                    turtle.pos.y += h + walk.margin.t + walk.margin.b + turtle.layout.gap.y;
                     
                     // keep track of biggest item in the line (include item margin bottom)
                     let biggest = w + walk.margin.r + walk.margin.l;
@@ -311,15 +397,23 @@ impl Cx {
     
     pub fn turtle_new_line(&mut self) {
         if let Some(turtle) = self.turtles.last_mut() {
+            if let Some(grid) = &turtle.grid {
+                // the rest of the row stays empty
+                if grid.col != 0 {
+                    let row = grid.row + 1;
+                    turtle.goto_grid_cell(0, row);
+                }
+                return
+            }
             match turtle.layout.direction {
                 Direction::Right => {
                     turtle.pos.x = turtle.origin.x + turtle.layout.padding.l;
-                    turtle.pos.y += turtle.biggest + turtle.layout.new_line_padding;
+                    turtle.pos.y += turtle.biggest + turtle.layout.new_line_padding + turtle.layout.gap.y;
                     turtle.biggest = 0.0;
                 },
                 Direction::Down => {
                     turtle.pos.y = turtle.origin.y + turtle.layout.padding.t;
-                    turtle.pos.x += turtle.biggest + turtle.layout.new_line_padding;
+                    turtle.pos.x += turtle.biggest + turtle.layout.new_line_padding + turtle.layout.gap.x;
                     turtle.biggest = 0.0;
                 },
                 _ => ()
@@ -347,11 +441,37 @@ impl Cx {
     pub fn turtle_new_line_min_height(&mut self, min_height: f32) {
         if let Some(turtle) = self.turtles.last_mut() {
             turtle.pos.x = turtle.origin.x + turtle.layout.padding.l;
-            turtle.pos.y += turtle.biggest.max(min_height);
+            turtle.pos.y += turtle.biggest.max(min_height) + turtle.layout.gap.y;
             turtle.biggest = 0.0;
         }
     }
     
+    // places the next walk in a cell of the current grid turtle, later walks continue after it
+    pub fn set_turtle_grid_cell(&mut self, col: usize, row: usize) {
+        if let Some(turtle) = self.turtles.last_mut() {
+            if turtle.grid.is_some() {
+                turtle.goto_grid_cell(col, row);
+            }
+        }
+    }
+    
+    // lets the next walk cover more than one cell
+    pub fn set_turtle_grid_span(&mut self, col_span: usize, row_span: usize) {
+        if let Some(turtle) = self.turtles.last_mut() {
+            if let Some(grid) = &mut turtle.grid {
+                grid.span = (col_span.max(1), row_span.max(1));
+            }
+        }
+    }
+    
+    // the cell the next walk goes into
+    pub fn get_turtle_grid_cell(&self) -> Option<Rect> {
+        if let Some(turtle) = self.turtles.last() {
+            return turtle.grid_cell()
+        }
+        None
+    }
+    
     fn do_align_x(&mut self, dx: f32, align_start: usize) {
         let dx = (dx * self.current_dpi_factor).floor() / self.current_dpi_factor;
         for i in align_start..self.align_list.len() {
@@ -643,6 +763,9 @@ impl Cx {
     
     pub fn get_width_left(&self) -> f32 {
         if let Some(turtle) = self.turtles.last() {
+            if let Some(cell) = turtle.grid_cell() {
+                return max_zero_keep_nan(cell.pos.x + cell.size.x - turtle.pos.x)
+            }
             let nan_val = max_zero_keep_nan(turtle.width - turtle.width_used - (turtle.pos.x - turtle.origin.x));
             if nan_val.is_nan() { // if we are a computed height, if some value is known, use that
                 if turtle.bound_right_bottom.x != std::f32::NEG_INFINITY {
@@ -665,6 +788,9 @@ impl Cx {
     
     pub fn get_width_total(&self) -> f32 {
         if let Some(turtle) = self.turtles.last() {
+            if let Some(cell) = turtle.grid_cell() {
+                return max_zero_keep_nan(cell.size.x)
+            }
             let nan_val = max_zero_keep_nan(turtle.width/* - (turtle.layout.padding.l + turtle.layout.padding.r)*/);
             if nan_val.is_nan() { // if we are a computed width, if some value is known, use that
                 if turtle.bound_right_bottom.x != std::f32::NEG_INFINITY {
@@ -687,6 +813,9 @@ impl Cx {
     
     pub fn get_height_left(&self) -> f32 {
         if let Some(turtle) = self.turtles.last() {
+            if let Some(cell) = turtle.grid_cell() {
+                return max_zero_keep_nan(cell.pos.y + cell.size.y - turtle.pos.y)
+            }
             let nan_val = max_zero_keep_nan(turtle.height - turtle.height_used - (turtle.pos.y - turtle.origin.y));
             if nan_val.is_nan() { // if we are a computed height, if some value is known, use that
                 if turtle.bound_right_bottom.y != std::f32::NEG_INFINITY {
@@ -709,6 +838,9 @@ impl Cx {
     
     pub fn get_height_total(&self) -> f32 {
         if let Some(turtle) = self.turtles.last() {
+            if let Some(cell) = turtle.grid_cell() {
+                return max_zero_keep_nan(cell.size.y)
+            }
             let nan_val = max_zero_keep_nan(turtle.height /*- (turtle.layout.padding.t + turtle.layout.padding.b)*/);
             if nan_val.is_nan() { // if we are a computed height, if some value is known, use that
                 if turtle.bound_right_bottom.y != std::f32::NEG_INFINITY {
@@ -806,6 +938,14 @@ pub fn debug_pt(x:f32, y:f32, color:i32, s:&str){
 
 
 
+// what a measure pass has to roll back
+#[derive(Clone, Default, Debug)]
+pub struct CxMeasure {
+    pub align_list_len: usize,
+    pub turtles_len: usize,
+    pub view: Option<(usize, usize, Vec<usize>)>,
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct Turtle {
     pub align_list_x: usize,
@@ -823,5 +963,150 @@ pub struct Turtle {
     pub height_used: f32,
     pub biggest: f32,
     pub layout: Layout,
-    pub guard_area: Area
+    pub guard_area: Area,
+    pub grid: Option<TurtleGrid>
+}
+
+// where a grid turtle is placing, track offsets are relative to the padded origin.
+// Rows are entered in order so auto rows know their height before the next one starts
+#[derive(Clone, Default, Debug)]
+pub struct TurtleGrid {
+    pub columns: Vec<(f32, f32)>,
+    pub rows: Vec<(f32, f32)>,
+    pub row_tops: Vec<f32>,
+    pub row_heights: Vec<f32>,
+    pub gap: Vec2,
+    pub col: usize,
+    pub row: usize,
+    pub span: (usize, usize),
+}
+
+impl TurtleGrid {
+    pub fn new(grid: &Grid, width: f32, height: f32, gap: Vec2) -> Self {
+        let columns = if grid.columns.len == 0 {
+            GridTemplate::new(&[GridTrack::Weight(1.0)]).resolve(width, gap.x)
+        }
+        else {
+            grid.columns.resolve(width, gap.x)
+        };
+        let mut grid = Self {
+            columns,
+            rows: grid.rows.resolve(height, gap.y),
+            row_tops: Vec::new(),
+            row_heights: Vec::new(),
+            gap,
+            col: 0,
+            row: 0,
+            span: (1, 1),
+        };
+        grid.enter_row(0);
+        grid
+    }
+    
+    // rows past the template are auto rows, an auto row has a NaN height until its items are in
+    fn enter_row(&mut self, row: usize) {
+        while self.row_tops.len() <= row {
+            let r = self.row_tops.len();
+            let top = if r == 0 {0.0} else {
+                let h = self.row_heights[r - 1];
+                self.row_tops[r - 1] + if h.is_nan() {0.0} else {h} + self.gap.y
+            };
+            self.row_tops.push(top);
+            self.row_heights.push(if r < self.rows.len() {self.rows[r].1} else {std::f32::NAN});
+        }
+    }
+    
+    fn is_auto_row(&self, row: usize) -> bool {
+        row >= self.rows.len() || self.rows[row].1.is_nan()
+    }
+    
+    // the cell rect relative to the padded origin, auto rows give a NaN height
+    fn cell(&self, col: usize, row: usize, span: (usize, usize)) -> Rect {
+        let col = col.min(self.columns.len() - 1);
+        let last_col = (col + span.0 - 1).min(self.columns.len() - 1);
+        let x = self.columns[col].0;
+        let w = self.columns[last_col].0 + self.columns[last_col].1 - x;
+        let y = self.row_tops[row];
+        let mut h = self.gap.y * (span.1 - 1) as f32;
+        for r in row..row + span.1 {
+            if self.is_auto_row(r) {
+                h = std::f32::NAN;
+                break;
+            }
+            h += self.rows[r].1;
+        }
+        Rect {pos: vec2(x, y), size: vec2(w, h)}
+    }
+}
+
+impl Turtle {
+    pub fn grid_cell(&self) -> Option<Rect> {
+        if let Some(grid) = &self.grid {
+            if grid.row < grid.row_tops.len() {
+                let cell = grid.cell(grid.col, grid.row, grid.span);
+                return Some(Rect {pos: self.grid_origin() + cell.pos, size: cell.size})
+            }
+        }
+        None
+    }
+    
+    fn grid_origin(&self) -> Vec2 {
+        vec2(self.origin.x + self.layout.padding.l, self.origin.y + self.layout.padding.t)
+    }
+    
+    fn goto_grid_cell(&mut self, col: usize, row: usize) {
+        let origin = self.grid_origin();
+        if let Some(grid) = &mut self.grid {
+            grid.col = col.min(grid.columns.len() - 1);
+            grid.row = row;
+            grid.enter_row(row);
+            self.pos = origin + grid.cell(grid.col, grid.row, (1, 1)).pos;
+        }
+    }
+    
+    // puts an item in the current cell and moves on to the next free one
+    fn walk_grid(&mut self, h: f32, margin: Margin) -> (f32, f32) {
+        let origin = self.grid_origin();
+        let (col, row) = if let Some(grid) = &mut self.grid {
+            grid.enter_row(grid.row);
+            (grid.col, grid.row)
+        }
+        else {
+            return (self.pos.x, self.pos.y)
+        };
+        let cell = self.grid_cell().unwrap();
+        let x = cell.pos.x + margin.l;
+        let y = cell.pos.y + margin.t;
+        
+        let grid = self.grid.as_mut().unwrap();
+        let (col_span, row_span) = grid.span;
+        grid.span = (1, 1);
+        // auto rows grow to their tallest single row item
+        if row_span == 1 && grid.is_auto_row(row) {
+            let item_h = h + margin.t + margin.b;
+            let row_h = grid.row_heights[row];
+            if row_h.is_nan() || item_h > row_h {
+                grid.row_heights[row] = item_h;
+            }
+        }
+        // cells count in the bounds so computed grids are as big as their tracks
+        let cell_x2 = cell.pos.x + cell.size.x;
+        let cell_y2 = if cell.size.y.is_nan() {origin.y + grid.row_tops[row] + grid.row_heights[row]} else {cell.pos.y + cell.size.y};
+        if cell_x2 > self.bound_right_bottom.x {
+            self.bound_right_bottom.x = cell_x2;
+        }
+        if cell_y2 > self.bound_right_bottom.y {
+            self.bound_right_bottom.y = cell_y2;
+        }
+        
+        let next = col + col_span;
+        let columns = self.grid.as_ref().unwrap().columns.len();
+        if next >= columns {
+            self.goto_grid_cell(0, row + 1);
+        }
+        else {
+            self.goto_grid_cell(next, row);
+        }
+        (x, y)
+    }
 }
@@ -289,14 +289,19 @@ impl View {
 impl Cx {
     
     pub fn new_draw_call(&mut self, shader: Shader) -> &mut DrawCall {
-        return self.get_draw_call(false, shader, None);
+        return self.get_draw_call(false, shader, None, None);
     }
     
     pub fn append_to_draw_call(&mut self, shader: Shader, slots: usize) -> &mut DrawCall {
-        return self.get_draw_call(true, shader, Some(slots));
+        return self.get_draw_call(true, shader, None, Some(slots));
     }
     
-    pub fn get_draw_call(&mut self, append: bool, shader: Shader, slots: Option<usize>) -> &mut DrawCall {
+    // draw calls with their own geometry only append to ones using the same geometry
+    pub fn append_to_geometry_draw_call(&mut self, shader: Shader, geometry: Geometry, slots: usize) -> &mut DrawCall {
+        return self.get_draw_call(true, shader, Some(geometry), Some(slots));
+    }
+    
+    pub fn get_draw_call(&mut self, append: bool, shader: Shader, geometry: Option<Geometry>, slots: Option<usize>) -> &mut DrawCall {
         let sh = &self.shaders[shader.shader_id];
         
         let current_view_id = *self.view_stack.last().unwrap();
@@ -304,7 +309,7 @@ impl Cx {
         let draw_call_id = cxview.draw_calls_len;
         
         if append {
-            if let Some(index) = cxview.find_appendable_drawcall(shader) {
+            if let Some(index) = cxview.find_appendable_drawcall(shader, geometry) {
                 return &mut cxview.draw_calls[index];
             }
         }
@@ -320,7 +325,7 @@ impl Cx {
         // see if we need to add a new one
         if draw_call_id >= cxview.draw_calls.len() {
             cxview.draw_calls.push(DrawCall {
-                geometry: None,
+                geometry: geometry,
                 draw_call_id: draw_call_id,
                 view_id: current_view_id,
                 redraw_id: self.redraw_id,
@@ -353,7 +358,7 @@ impl Cx {
         // reuse an older one, keeping all GPU resources attached
         let dc = &mut cxview.draw_calls[draw_call_id];
         dc.shader = shader;
-        dc.geometry = None;
+        dc.geometry = geometry;
         dc.sub_view_id = 0; // make sure its recognised as a draw call
         // truncate buffers and set update frame
         dc.redraw_id = self.redraw_id;
@@ -433,6 +438,24 @@ impl Cx {
         ia.into()
     }
     
+    pub fn add_geometry_instance(&mut self, shader: Shader, geometry: Geometry, data: &[f32]) -> Area {
+        let dc = self.append_to_geometry_draw_call(shader, geometry, data.len());
+        let instance_count = data.len() / dc.total_instance_slots;
+        let check = data.len() % dc.total_instance_slots;
+        if check > 0 {
+            panic!("Data not multiple of total slots");
+        }
+        let ia = InstanceArea {
+            view_id: dc.view_id,
+            draw_call_id: dc.draw_call_id,
+            instance_count: instance_count,
+            instance_offset: dc.instances.len(),
+            redraw_id: dc.redraw_id
+        };
+        dc.instances.extend_from_slice(data);
+        ia.into()
+    }
+    
     pub fn add_aligned_instance(&mut self, shader: Shader, data: &[f32]) -> Area {
         let dc = self.append_to_draw_call(shader, data.len());
         let instance_count = data.len() / dc.total_instance_slots;
@@ -687,12 +710,12 @@ impl CxView {
         }
     }
     
-    pub fn find_appendable_drawcall(&mut self, shader: Shader) -> Option<usize> {
+    pub fn find_appendable_drawcall(&mut self, shader: Shader, geometry: Option<Geometry>) -> Option<usize> {
         // find our drawcall to append to the current layer
         if self.draw_calls_len > 0 {
             for i in (0..self.draw_calls_len).rev() {
                 let dc = &mut self.draw_calls[i];
-                if dc.sub_view_id == 0 && dc.shader == shader {
+                if dc.sub_view_id == 0 && dc.shader == shader && dc.geometry == geometry {
                     //dc.current_instance_offset = dc.instances.len();
                     return Some(i)
                 }
@@ -1,4 +1,5 @@
 use crate::cx::*;
+use makepad_microserde::*;
 
 #[derive(Clone)]
 pub struct Window {
@@ -190,7 +191,7 @@ impl Window {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, SerBin, DeBin)]
 pub struct WindowGeom {
     pub dpi_factor: f32,
     pub can_fullscreen: bool,
@@ -0,0 +1,71 @@
+// builds an accessibility tree over a couple of headless frames and snapshots it
+use makepad_render::*;
+use makepad_microserde::*;
+
+struct App {
+    pass: Pass,
+    root: View,
+    panel: View,
+    button_name: String,
+}
+
+impl App {
+    fn draw(&mut self, cx: &mut Cx) {
+        cx.redraw_id += 1;
+        cx.in_redraw_cycle = true;
+        std::mem::swap(&mut cx._redraw_child_areas, &mut cx.redraw_child_areas);
+        cx.redraw_child_areas.truncate(0);
+        self.pass.begin_pass(cx);
+        if self.root.begin_view(cx, Layout::default()).is_ok() {
+            cx.begin_access_node(AccessNode::new(AccessRole::Window, self.root.area()).with_name("main"));
+            if self.panel.begin_view(cx, Layout::default()).is_ok() {
+                cx.begin_access_node(AccessNode::new(AccessRole::TabList, self.panel.area()));
+                cx.add_access_node(AccessNode::new(AccessRole::Tab, Area::Empty).with_name("one").with_selected(true));
+                cx.add_access_node(AccessNode::new(AccessRole::Tab, Area::Empty).with_name("two"));
+                cx.end_access_node();
+                self.panel.end_view(cx);
+            }
+            cx.add_access_node(AccessNode::new(AccessRole::Button, Area::Empty).with_name(&self.button_name));
+            cx.end_access_node();
+            self.root.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        cx.in_redraw_cycle = false;
+        cx.prune_access_nodes();
+    }
+}
+
+#[test]
+fn tree_follows_redraws() {
+    let mut cx = Cx::default();
+    let mut app = App {pass: Pass::default(), root: View::new(), panel: View::new(), button_name: "ok".to_string()};
+    app.draw(&mut cx);
+    
+    let tree = cx.access_tree();
+    assert_eq!(tree.children.len(), 1);
+    let window = &tree.children[0];
+    assert_eq!((window.role, window.name.as_str()), (AccessRole::Window, "main"));
+    assert_eq!(window.children.iter().map( | n | n.role).collect::<Vec<_>>(), vec![AccessRole::TabList, AccessRole::Button]);
+    assert!(tree.find(AccessRole::Tab, "one").unwrap().selected);
+    assert!(!tree.find(AccessRole::Tab, "two").unwrap().selected);
+    
+    // redrawing only the root replaces its nodes, the panel keeps its tabs under the new window node
+    app.button_name = "cancel".to_string();
+    app.root.redraw_view(&mut cx);
+    app.draw(&mut cx);
+    let tree = cx.access_tree();
+    assert!(tree.find(AccessRole::Button, "ok").is_none());
+    assert!(tree.find(AccessRole::Button, "cancel").is_some());
+    let window = &tree.children[0];
+    assert_eq!(window.children.len(), 2);
+    assert_eq!(window.children.iter().find( | n | n.role == AccessRole::TabList).unwrap().children.len(), 2);
+    
+    // focus comes from the key focus
+    cx.key_focus = app.panel.area();
+    assert_eq!(cx.access_tree().focused().unwrap().role, AccessRole::TabList);
+    
+    // the snapshot goes through ron
+    let ron = tree.serialize_ron();
+    let back: AccessTree = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(back, tree);
+}
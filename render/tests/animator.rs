@@ -0,0 +1,217 @@
+// key curves, additive layers and anim events, played by an Animator without a window
+use makepad_render::*;
+
+fn style(cx: &mut Cx) {
+    live_body!(cx, {
+        self::anim_curves: Anim {
+            play: Cut {duration: 1.0},
+            tracks: [
+                Float {keys: {0.0: 0.0 step, 1.0: 1.0}, bind_to: self::step},
+                Float {keys: {0.0: 0.0 ease InQuad, 1.0: 1.0}, bind_to: self::eased},
+                Float {keys: {0.0: 0.0 hermite(0.0, 0.0), 1.0: 1.0}, bind_to: self::hermite},
+                Float {keys: {0.0: 0.0 bezier(0.0, 1.0), 1.0: 1.0}, bind_to: self::bezier},
+                Vec4 {keys: {0.0: #000 bezier(#fff, #fff), 1.0: #fff}, bind_to: self::color},
+            ]
+            events: {0.0: self::started, 0.5: self::half, 1.0: self::done}
+        }
+        self::anim_layer: Anim {
+            play: Loop {duration: 1.0, end: 1.0, repeats: 3.0, cut: true, term: false},
+            tracks: [
+                Float {keys: {0.0: 10.0, 1.0: 10.0}, bind_to: self::value},
+            ]
+            events: {0.25: self::tick}
+        }
+        self::anim_loop: Anim {
+            play: Loop {duration: 1.0, end: 1.0, repeats: 3.0, cut: true, term: false},
+            tracks: [
+                Float {keys: {0.0: 0.0, 1.0: 1.0}, bind_to: self::value},
+            ]
+            events: {0.5: self::tick}
+        }
+        self::anim_bounce: Anim {
+            play: Bounce {duration: 1.0, end: 1.0, repeats: 2.0, cut: true, term: false},
+            tracks: [
+                Float {keys: {0.0: 0.0, 1.0: 1.0}, bind_to: self::value},
+            ]
+            events: {0.25: self::tick}
+        }
+    });
+}
+
+fn new_cx() -> Cx {
+    let mut cx = Cx::default();
+    style(&mut cx);
+    cx.init_live_styles();
+    cx
+}
+
+// what a widget does on an animate event: collect the anim events, then read the value
+fn frame(cx: &mut Cx, animator: &mut Animator, bind_id: LiveItemId, time: f64) -> (Option<f32>, Vec<TimelineEvent>) {
+    let event = Event::Animate(AnimateEvent {frame: 0, time, timeline_events: Vec::new()});
+    let events = event.is_animate(cx, animator).map_or(Vec::new(), | ae | ae.timeline_events);
+    let value = animator.calc_float(cx, bind_id, time);
+    cx.check_ended_animator_ids(time);
+    (value, events)
+}
+
+fn near(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn key_curve_syntax() {
+    let cx = new_cx();
+    let anim = live_anim!(cx, self::anim_curves);
+    assert_eq!(anim.events, vec![
+        (0.0, live_item_id!(self::started)),
+        (0.5, live_item_id!(self::half)),
+        (1.0, live_item_id!(self::done))
+    ]);
+    let curves: Vec<KeyCurve> = anim.tracks.iter().map( | track | match track {
+        Track::Float {curves, ..} | Track::Vec4 {curves, ..} => curves[0].clone(),
+        _ => panic!("unexpected track")
+    }).collect();
+    assert_eq!(curves[0], KeyCurve::Step);
+    assert_eq!(curves[1], KeyCurve::Ease(Ease::InQuad));
+    assert_eq!(curves[2], KeyCurve::Hermite {out_tangent: Vec4::all(0.0), in_tangent: Vec4::all(0.0)});
+    assert_eq!(curves[3], KeyCurve::Bezier {out_handle: Vec4::all(0.0), in_handle: vec4(1.0, 0.0, 0.0, 0.0)});
+    assert_eq!(curves[4], KeyCurve::Bezier {out_handle: vec4(1.0, 1.0, 1.0, 1.0), in_handle: vec4(1.0, 1.0, 1.0, 1.0)});
+    // keys without a curve keep the track ease
+    if let Track::Float {curves, keys, ..} = &anim.tracks[0] {
+        assert_eq!((keys.len(), curves.len()), (2, 1));
+    }
+}
+
+#[test]
+fn key_curves() {
+    let (v0, v1) = (Vec4::all(0.0), Vec4::all(1.0));
+    let at = | curve: &KeyCurve, time: f64 | curve.interpolate(&Ease::Lin, 0.0, v0, 1.0, v1, time).x;
+    // zero tangents and handles at 0 and 1 both make a smoothstep
+    let smooth = KeyCurve::Hermite {out_tangent: v0, in_tangent: v0};
+    assert!(near(at(&smooth, 0.5), 0.5) && near(at(&smooth, 0.25), 0.15625));
+    let smooth = KeyCurve::Bezier {out_handle: v0, in_handle: v1};
+    assert!(near(at(&smooth, 0.5), 0.5) && near(at(&smooth, 0.25), 0.15625));
+    // tangents matching the slope, and handles on thirds, are a straight line
+    let line = KeyCurve::Hermite {out_tangent: v1, in_tangent: v1};
+    assert!(near(at(&line, 0.25), 0.25) && near(at(&line, 0.8), 0.8));
+    let line = KeyCurve::Bezier {out_handle: Vec4::all(1.0 / 3.0), in_handle: Vec4::all(2.0 / 3.0)};
+    assert!(near(at(&line, 0.25), 0.25) && near(at(&line, 0.8), 0.8));
+    // tangents are per unit of key time, so a longer segment bulges more
+    let steep = KeyCurve::Hermite {out_tangent: Vec4::all(4.0), in_tangent: v0};
+    let short = steep.interpolate(&Ease::Lin, 0.0, v0, 1.0, v1, 0.5).x;
+    let long = steep.interpolate(&Ease::Lin, 0.0, v0, 2.0, v1, 1.0).x;
+    assert!(near(short, 1.0) && near(long, 1.5), "{} {}", short, long);
+    // ends are exact and times outside the segment clamp
+    for curve in &[KeyCurve::Default, KeyCurve::Step, KeyCurve::Ease(Ease::InQuad), KeyCurve::Hermite {out_tangent: v1, in_tangent: v0}] {
+        assert_eq!(at(curve, 0.0), 0.0);
+        assert_eq!(at(curve, 1.0), 1.0);
+        assert_eq!(at(curve, 2.0), 1.0);
+    }
+    assert_eq!(at(&KeyCurve::Step, 0.99), 0.0);
+    assert!(near(at(&KeyCurve::Ease(Ease::InQuad), 0.5), 0.25));
+    
+    // before the first key the value comes from where it was when the anim started
+    let keys = vec![(0.5, 2.0f32), (1.0, 4.0)];
+    let mut cut_init = None;
+    assert!(near(Track::compute_track(0.25, &keys, &vec![], &mut cut_init, 0.0, &Ease::Lin), 1.0));
+    assert_eq!(cut_init, Some(0.0));
+    assert!(near(Track::compute_track(0.25, &keys, &vec![], &mut cut_init, 100.0, &Ease::Lin), 1.0));
+    assert!(near(Track::compute_track(0.75, &keys, &vec![], &mut cut_init, 0.0, &Ease::Lin), 3.0));
+    assert_eq!(Track::compute_track(5.0, &keys, &vec![], &mut cut_init, 0.0, &Ease::Lin), 4.0);
+}
+
+#[test]
+fn curves_and_events_in_an_animator() {
+    let mut cx = new_cx();
+    let mut animator = Animator::default();
+    let anim = live_anim!(cx, self::anim_curves);
+    animator.play_anim(&mut cx, anim);
+    let (step, events) = frame(&mut cx, &mut animator, live_item_id!(self::step), 10.0);
+    assert_eq!(step, Some(0.0));
+    assert_eq!(events, vec![TimelineEvent {layer: None, event: live_item_id!(self::started), key_time: 0.0}]);
+    
+    let (step, events) = frame(&mut cx, &mut animator, live_item_id!(self::step), 10.5);
+    assert_eq!(step, Some(0.0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event, live_item_id!(self::half));
+    assert!(near(animator.calc_float(&mut cx, live_item_id!(self::eased), 10.5).unwrap(), 0.25));
+    assert!(near(animator.calc_float(&mut cx, live_item_id!(self::hermite), 10.25).unwrap(), 0.15625));
+    assert!(near(animator.calc_float(&mut cx, live_item_id!(self::bezier), 10.5).unwrap(), 0.5));
+    let color = animator.calc_vec4(&mut cx, live_item_id!(self::color), 10.5).unwrap();
+    assert!(near(color.x, 0.875) && near(color.z, 0.875) && color.w == 1.0, "{:?}", color);
+    
+    let (step, events) = frame(&mut cx, &mut animator, live_item_id!(self::step), 11.0);
+    assert_eq!(step, Some(1.0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event, live_item_id!(self::done));
+    assert!(cx.playing_animator_ids.is_empty());
+}
+
+#[test]
+fn events_under_loop_and_bounce() {
+    let mut cx = new_cx();
+    let value = live_item_id!(self::value);
+    let mut animator = Animator::default();
+    let anim = live_anim!(cx, self::anim_loop);
+    animator.play_anim(&mut cx, anim);
+    // the tick is halfway every loop, each crossing is reported once
+    let counts: Vec<usize> = [10.0, 10.25, 10.75, 11.0, 11.6, 12.9, 13.5].iter().map( | time | frame(&mut cx, &mut animator, value, *time).1.len()).collect();
+    assert_eq!(counts, vec![0, 0, 1, 0, 1, 1, 0]);
+    
+    // a long frame reports every crossing it skipped, and nothing after the last repeat
+    let mut animator = Animator::default();
+    let anim = live_anim!(cx, self::anim_loop);
+    animator.play_anim(&mut cx, anim);
+    frame(&mut cx, &mut animator, value, 20.0);
+    assert_eq!(frame(&mut cx, &mut animator, value, 22.9).1.len(), 3);
+    assert_eq!(frame(&mut cx, &mut animator, value, 23.5).1.len(), 0);
+    
+    // bounce passes the key going out and again coming back
+    let mut animator = Animator::default();
+    let anim = live_anim!(cx, self::anim_bounce);
+    animator.play_anim(&mut cx, anim);
+    let frames: Vec<(Option<f32>, usize)> = [30.0, 30.5, 31.0, 31.9, 32.5].iter().map( | time | {
+        let (value, events) = frame(&mut cx, &mut animator, value, *time);
+        (value, events.len())
+    }).collect();
+    assert_eq!(frames.iter().map( | f | f.1).collect::<Vec<_>>(), vec![0, 1, 0, 1, 0]);
+    assert!(near(frames[1].0.unwrap(), 0.5));
+    assert!(near(frames[3].0.unwrap(), 0.1));
+    
+    let bounce = Play::Bounce {duration: 1.0, end: 1.0, repeats: 2.0, cut: true, term: false};
+    assert_eq!(bounce.key_crossings(0.25, -1.0, 2.0), 2);
+    assert_eq!(bounce.key_crossings(0.25, 0.25, 1.75), 1);
+    assert_eq!(bounce.key_crossings(0.25, 0.0, 10.0), 2);
+}
+
+#[test]
+fn layer_weight_fades() {
+    let mut cx = new_cx();
+    let value = live_item_id!(self::value);
+    let layer = live_item_id!(self::layer);
+    let mut animator = Animator::default();
+    animator.set_last_float(value, 4.0);
+    // the layer adds 10, fading in over half a second from the first frame
+    let anim = live_anim!(cx, self::anim_layer);
+    animator.play_layer(&mut cx, layer, anim, 0.5);
+    assert!(animator.has_layer(layer));
+    assert_eq!(frame(&mut cx, &mut animator, value, 11.0).0, Some(4.0));
+    let (v, events) = frame(&mut cx, &mut animator, value, 11.25);
+    assert!(near(v.unwrap(), 9.0), "{:?}", v);
+    assert_eq!(events, vec![TimelineEvent {layer: Some(layer), event: live_item_id!(self::tick), key_time: 0.25}]);
+    assert_eq!(frame(&mut cx, &mut animator, value, 12.5).0, Some(14.0));
+    // the base value isnt changed by what the layer adds
+    assert_eq!(animator.last_float(value), Some(4.0));
+    
+    animator.set_layer_weight(&mut cx, layer, 0.5, 0.0);
+    assert!(near(frame(&mut cx, &mut animator, value, 12.6).0.unwrap(), 9.0));
+    
+    // stopping fades out from the current weight and then drops the layer
+    animator.stop_layer(&mut cx, layer, 1.0);
+    assert!(near(frame(&mut cx, &mut animator, value, 12.7).0.unwrap(), 9.0));
+    assert!(near(frame(&mut cx, &mut animator, value, 13.2).0.unwrap(), 6.5));
+    assert!(near(frame(&mut cx, &mut animator, value, 13.7).0.unwrap(), 4.0));
+    assert!(!animator.has_layer(layer));
+    assert_eq!(frame(&mut cx, &mut animator, value, 13.8).0, None);
+    assert!(cx.playing_animator_ids.is_empty());
+}
@@ -0,0 +1,169 @@
+// allocating, freeing, growing and defragmenting the shelf packing texture atlas
+use makepad_render::*;
+
+// every item keeps its size, stays inside the atlas and keeps its padding to the others
+fn check(atlas: &TextureAtlas, live: &[(AtlasId, usize, usize)]) {
+    let rects: Vec<AtlasRect> = live.iter().map( | (id, w, h) | {
+        let rect = atlas.rect(*id).unwrap();
+        assert_eq!((rect.w, rect.h), (*w, *h));
+        rect
+    }).collect();
+    let p = atlas.padding;
+    for (i, a) in rects.iter().enumerate() {
+        assert!(a.x + a.w + p <= atlas.width && a.y + a.h + p <= atlas.height, "{:?} outside {}x{}", a, atlas.width, atlas.height);
+        for b in &rects[i + 1..] {
+            let overlap = a.x < b.x + b.w + p && b.x < a.x + a.w + p && a.y < b.y + b.h + p && b.y < a.y + a.h + p;
+            assert!(!overlap, "{:?} overlaps {:?}", a, b);
+        }
+    }
+    assert_eq!(atlas.len(), live.len());
+}
+
+#[test]
+fn alloc_and_free() {
+    let mut atlas = TextureAtlas::new(128, 128);
+    let mut count = 0;
+    while atlas.alloc(9, 9).is_some() {
+        count += 1;
+    }
+    // 9 pixels and 1 of padding
+    assert_eq!(count, (128 / 10) * (128 / 10));
+    assert!(atlas.alloc(300, 1).is_none());
+    
+    let mut atlas = TextureAtlas::new(64, 64).with_padding(0);
+    let a = atlas.alloc(16, 16).unwrap();
+    let b = atlas.alloc(16, 16).unwrap();
+    let c = atlas.alloc(16, 16).unwrap();
+    assert_eq!(atlas.rect(b), Some(AtlasRect {x: 16, y: 0, w: 16, h: 16}));
+    let (pt1, pt2) = atlas.uv(b).unwrap();
+    assert_eq!((pt1, pt2), (vec2(0.25, 0.), vec2(0.5, 0.25)));
+    
+    // a freed id is gone and its space and id get reused
+    atlas.free(b);
+    assert_eq!(atlas.rect(b), None);
+    assert_eq!(atlas.len(), 2);
+    let d = atlas.alloc(16, 12).unwrap();
+    assert_eq!(d, b);
+    assert_eq!(atlas.rect(d), Some(AtlasRect {x: 16, y: 0, w: 16, h: 12}));
+    
+    // neighbouring free spans merge, so a wider item fits where two were
+    atlas.free(a);
+    atlas.free(d);
+    let wide = atlas.alloc(32, 16).unwrap();
+    assert_eq!(atlas.rect(wide), Some(AtlasRect {x: 0, y: 0, w: 32, h: 16}));
+    check(&atlas, &[(c, 16, 16), (wide, 32, 16)]);
+    
+    // freeing twice or freeing an unknown id does nothing
+    atlas.free(a);
+    atlas.free(AtlasId(99));
+    assert_eq!(atlas.len(), 2);
+    atlas.clear();
+    assert!(atlas.is_empty());
+}
+
+#[test]
+fn grow() {
+    let mut atlas = TextureAtlas::new(16, 16).with_max_size(64).with_padding(0);
+    let a = atlas.alloc(16, 16).unwrap();
+    // height grows first, then width, and existing items stay put
+    let b = atlas.alloc(16, 16).unwrap();
+    assert_eq!((atlas.width, atlas.height), (16, 32));
+    let c = atlas.alloc(16, 16).unwrap();
+    assert_eq!((atlas.width, atlas.height), (32, 32));
+    assert_eq!(atlas.rect(a), Some(AtlasRect {x: 0, y: 0, w: 16, h: 16}));
+    // the widened shelves take items on their new right half
+    assert_eq!(atlas.rect(c).unwrap().y, 0);
+    // uvs follow the new size
+    assert_eq!(atlas.uv(a).unwrap().1, vec2(0.5, 0.5));
+    
+    let mut live = vec![(a, 16, 16), (b, 16, 16), (c, 16, 16)];
+    while let Some(id) = atlas.alloc(16, 16) {
+        live.push((id, 16, 16));
+    }
+    assert_eq!((atlas.width, atlas.height), (64, 64));
+    assert_eq!(live.len(), 16);
+    check(&atlas, &live);
+    assert!(atlas.alloc(65, 1).is_none());
+}
+
+#[test]
+fn defragment() {
+    let mut atlas = TextureAtlas::new(64, 64);
+    let ids: Vec<AtlasId> = (0..12).map( | i | atlas.alloc(7, 3 + i % 4 * 4).unwrap()).collect();
+    let mut live = Vec::new();
+    for (i, id) in ids.iter().enumerate() {
+        if i % 3 == 0 {
+            atlas.free(*id);
+        }
+        else {
+            live.push((*id, 7, 3 + i % 4 * 4));
+        }
+    }
+    let before: Vec<AtlasRect> = live.iter().map( | (id, _, _) | atlas.rect(*id).unwrap()).collect();
+    let moves = atlas.defragment();
+    check(&atlas, &live);
+    assert!(moves.len() > 0);
+    // every move reports where the item was and where it is now, ids dont change
+    for m in &moves {
+        let index = live.iter().position( | (id, _, _) | *id == m.id).unwrap();
+        assert_eq!(m.from, before[index]);
+        assert_eq!(atlas.rect(m.id), Some(m.to));
+    }
+    // a second pass has nothing left to move
+    assert_eq!(atlas.defragment(), vec![]);
+}
+
+#[test]
+fn defragment_keeps_layout_when_repacking_doesnt_fit() {
+    let mut atlas = TextureAtlas::new(16, 16).with_padding(0);
+    // a short item sits on a tall shelf it got while the shelf was empty
+    let x = atlas.alloc(16, 8).unwrap();
+    let y = atlas.alloc(16, 8).unwrap();
+    atlas.free(x);
+    let q = atlas.alloc(8, 5).unwrap();
+    let p = atlas.alloc(8, 8).unwrap();
+    atlas.free(y);
+    let r = atlas.alloc(16, 5).unwrap();
+    let live = [(q, 8, 5), (p, 8, 8), (r, 16, 5)];
+    check(&atlas, &live);
+    let before: Vec<AtlasRect> = live.iter().map( | (id, _, _) | atlas.rect(*id).unwrap()).collect();
+    
+    // tallest first needs shelves of 8, 5 and 5, more than the 16 pixels there are
+    assert_eq!(atlas.defragment(), vec![]);
+    assert_eq!((atlas.width, atlas.height), (16, 16));
+    let after: Vec<AtlasRect> = live.iter().map( | (id, _, _) | atlas.rect(*id).unwrap()).collect();
+    assert_eq!(after, before);
+    // and the atlas still works afterwards
+    atlas.free(r);
+    let s = atlas.alloc(16, 8).unwrap();
+    check(&atlas, &[(q, 8, 5), (p, 8, 8), (s, 16, 8)]);
+}
+
+#[test]
+fn random_alloc_free_defragment() {
+    let mut seed = 7u32;
+    let mut rnd = | n: usize | {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (seed >> 8) as usize % n
+    };
+    let mut atlas = TextureAtlas::new(64, 64).with_max_size(512);
+    let mut live = Vec::new();
+    for step in 0..5000 {
+        if live.len() > 0 && rnd(3) == 0 {
+            let (id, _, _) = live.swap_remove(rnd(live.len()));
+            atlas.free(id);
+        }
+        else {
+            let (w, h) = (1 + rnd(40), 1 + rnd(40));
+            if let Some(id) = atlas.alloc(w, h) {
+                live.push((id, w, h));
+            }
+        }
+        if step % 250 == 0 {
+            check(&atlas, &live);
+            atlas.defragment();
+            check(&atlas, &live);
+        }
+    }
+    check(&atlas, &live);
+}
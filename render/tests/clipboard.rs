@@ -0,0 +1,64 @@
+// clipboard data helpers, the bitmap codec the platforms exchange images in, and how copies become offers
+use makepad_render::*;
+
+#[test]
+fn formats() {
+    let image = DecodedImage {width: 1, height: 1, pixels: vec![0xff102030]};
+    let data = ClipboardData::from_text("a")
+        .with_html("<b>a</b>")
+        .with_custom("application/x-test", &[1, 2, 3])
+        .with_image(image.clone())
+        .with_text("b");
+
+    // the second text replaced the first
+    assert_eq!(data.mimes(), vec!["text/html", "application/x-test", "image/bmp", "text/plain"]);
+    assert_eq!(data.text(), Some("b"));
+    assert_eq!(data.html(), Some("<b>a</b>"));
+    assert_eq!(data.custom("application/x-test"), Some(&[1u8, 2, 3][..]));
+    assert_eq!(data.custom("application/x-other"), None);
+    assert_eq!(data.image(), Some(&image));
+    assert!(ClipboardData::default().is_empty());
+}
+
+#[test]
+fn bmp_roundtrip() {
+    let image = DecodedImage {width: 3, height: 2, pixels: vec![
+        0xff0000ff, 0x8000ff00, 0x00ff0000,
+        0xffffffff, 0xff000000, 0x40404040,
+    ]};
+    let bmp = encode_bmp(&image);
+    assert_eq!(ImageFormat::detect(&bmp), Some(ImageFormat::Bmp));
+    assert_eq!(decode_bmp(&bmp).unwrap(), image);
+    assert_eq!(decode_dib(&encode_dib(&image), None).unwrap(), image);
+
+    // 24 bit top down rows are padded to 4 bytes and come out opaque
+    let mut dib = vec![0u8; 40];
+    dib[0] = 40;
+    dib[4] = 1;
+    dib[8..12].copy_from_slice(&(-2i32).to_le_bytes());
+    dib[14] = 24;
+    dib.extend_from_slice(&[0x30, 0x20, 0x10, 0, 0x03, 0x02, 0x01, 0]);
+    let decoded = decode_dib(&dib, None).unwrap();
+    assert_eq!(decoded.pixels, vec![0xff102030, 0xff010203]);
+    assert!(decode_dib(&dib[0..44], None).is_err());
+}
+
+#[test]
+fn copy_and_paste_events() {
+    let mut cx = Cx::default();
+    cx.process_text_copy_response(&Event::TextCopy(TextCopyEvent {response: Some("copied".to_string())}));
+    assert_eq!(cx.clipboard_offer.take(), Some(ClipboardData::from_text("copied")));
+
+    // a widget that offered richer data wins over the plain answer
+    let rich = ClipboardData::from_text("x").with_custom("application/x-test", b"x");
+    cx.set_clipboard(rich.clone());
+    cx.process_text_copy_response(&Event::TextCopy(TextCopyEvent {response: Some("y".to_string())}));
+    assert_eq!(cx.clipboard_offer, Some(rich.clone()));
+
+    // pastes go to the key focus only
+    let focus = Area::Instance(InstanceArea {view_id: 1, draw_call_id: 0, instance_offset: 0, instance_count: 1, redraw_id: 0});
+    cx.key_focus = focus;
+    let mut event = Event::Paste(PasteEvent {data: rich.clone()});
+    assert_eq!(event.hits(&mut cx, focus, HitOpt::default()), Event::Paste(PasteEvent {data: rich}));
+    assert_eq!(event.hits(&mut cx, Area::Empty, HitOpt::default()), Event::None);
+}
@@ -0,0 +1,137 @@
+// feeds synthetic finger sequences through the gesture recognizers
+use makepad_render::*;
+
+fn down(digit: usize, x: f32, y: f32, time: f64) -> Event {
+    Event::FingerDown(FingerDownEvent {digit, abs: vec2(x, y), input_type: FingerInputType::Touch, time, ..Default::default()})
+}
+
+fn move_to(digit: usize, x: f32, y: f32, time: f64) -> Event {
+    Event::FingerMove(FingerMoveEvent {digit, abs: vec2(x, y), is_over: true, input_type: FingerInputType::Touch, time, ..Default::default()})
+}
+
+fn up(digit: usize, x: f32, y: f32, time: f64) -> Event {
+    Event::FingerUp(FingerUpEvent {digit, abs: vec2(x, y), is_over: true, input_type: FingerInputType::Touch, time, ..Default::default()})
+}
+
+fn near(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.001
+}
+
+#[test]
+fn tap_and_double_tap() {
+    let mut tap = TapRecognizer::new();
+    let mut double = TapRecognizer::double_tap();
+    let mut feed = | event: Event | (tap.handle_finger_event(&event), double.handle_finger_event(&event));
+
+    feed(down(0, 10.0, 10.0, 0.0));
+    assert_eq!(feed(up(0, 12.0, 10.0, 0.1)), (TapEvent::Tap {abs: vec2(12.0, 10.0), count: 1}, TapEvent::None));
+    feed(down(0, 11.0, 11.0, 0.2));
+    assert_eq!(feed(up(0, 11.0, 11.0, 0.25)), (TapEvent::Tap {abs: vec2(11.0, 11.0), count: 2}, TapEvent::Tap {abs: vec2(11.0, 11.0), count: 2}));
+
+    // too slow for a third tap, so it starts over
+    feed(down(0, 11.0, 11.0, 1.0));
+    assert_eq!(feed(up(0, 11.0, 11.0, 1.1)), (TapEvent::Tap {abs: vec2(11.0, 11.0), count: 1}, TapEvent::None));
+
+    // moving or holding too long isnt a tap
+    feed(down(0, 10.0, 10.0, 2.0));
+    feed(move_to(0, 40.0, 10.0, 2.05));
+    assert_eq!(feed(up(0, 10.0, 10.0, 2.1)), (TapEvent::None, TapEvent::None));
+    feed(down(0, 10.0, 10.0, 3.0));
+    assert_eq!(feed(up(0, 10.0, 10.0, 4.0)), (TapEvent::None, TapEvent::None));
+
+    // a second finger cancels it
+    feed(down(0, 10.0, 10.0, 5.0));
+    feed(down(1, 50.0, 10.0, 5.01));
+    feed(up(1, 50.0, 10.0, 5.05));
+    assert_eq!(feed(up(0, 10.0, 10.0, 5.1)), (TapEvent::None, TapEvent::None));
+}
+
+#[test]
+fn long_press() {
+    let mut press = LongPressRecognizer::new();
+    press.handle_finger_event(&down(0, 10.0, 10.0, 0.0));
+    assert_eq!(press.handle_time(0.3), LongPressEvent::None);
+    // small jitter is fine and the move carries the time
+    assert_eq!(press.handle_finger_event(&move_to(0, 12.0, 11.0, 0.6)), LongPressEvent::LongPress {abs: vec2(10.0, 10.0)});
+    // only once
+    assert_eq!(press.handle_time(1.0), LongPressEvent::None);
+    press.handle_finger_event(&up(0, 12.0, 11.0, 1.1));
+
+    press.handle_finger_event(&down(0, 10.0, 10.0, 2.0));
+    press.handle_finger_event(&move_to(0, 30.0, 10.0, 2.1));
+    assert_eq!(press.handle_time(3.0), LongPressEvent::None);
+}
+
+#[test]
+fn pan_with_slop_and_velocity() {
+    let mut pan = PanRecognizer::new();
+    assert_eq!(pan.handle_finger_event(&down(0, 0.0, 0.0, 0.0)), PanEvent::None);
+    assert_eq!(pan.handle_finger_event(&move_to(0, 3.0, 0.0, 0.01)), PanEvent::None);
+    assert_eq!(pan.handle_finger_event(&move_to(0, 10.0, 0.0, 0.02)), PanEvent::Start {abs_start: vec2(0.0, 0.0), abs: vec2(10.0, 0.0)});
+    assert!(pan.is_panning());
+    // a second finger doesnt take over
+    assert_eq!(pan.handle_finger_event(&down(1, 100.0, 100.0, 0.025)), PanEvent::None);
+    assert_eq!(pan.handle_finger_event(&move_to(1, 200.0, 100.0, 0.03)), PanEvent::None);
+    assert_eq!(pan.handle_finger_event(&move_to(0, 20.0, 5.0, 0.04)), PanEvent::Move {abs: vec2(20.0, 5.0), delta: vec2(10.0, 5.0), total: vec2(10.0, 5.0)});
+    match pan.handle_finger_event(&up(0, 20.0, 5.0, 0.05)) {
+        PanEvent::End {total, velocity, ..} => {
+            assert_eq!(total, vec2(10.0, 5.0));
+            assert!(near(velocity.x, 500.0) && near(velocity.y, 250.0), "{:?}", velocity);
+        },
+        other => panic!("{:?}", other)
+    }
+    assert!(!pan.is_panning());
+    // never past the slop, never a pan
+    pan.handle_finger_event(&up(1, 200.0, 100.0, 0.06));
+    pan.handle_finger_event(&down(0, 0.0, 0.0, 1.0));
+    assert_eq!(pan.handle_finger_event(&up(0, 2.0, 0.0, 1.1)), PanEvent::None);
+}
+
+#[test]
+fn pinch_zoom() {
+    let mut pinch = PinchRecognizer::new();
+    assert_eq!(pinch.handle_finger_event(&down(0, 0.0, 0.0, 0.0)), PinchEvent::None);
+    assert_eq!(pinch.handle_finger_event(&down(1, 100.0, 0.0, 0.0)), PinchEvent::None);
+    // under the threshold
+    assert_eq!(pinch.handle_finger_event(&move_to(1, 102.0, 0.0, 0.1)), PinchEvent::None);
+    assert_eq!(pinch.handle_finger_event(&move_to(1, 110.0, 0.0, 0.2)), PinchEvent::Start {center: vec2(55.0, 0.0)});
+    match pinch.handle_finger_event(&move_to(1, 220.0, 0.0, 0.3)) {
+        PinchEvent::Pinch {center, scale, delta} => {
+            assert_eq!(center, vec2(110.0, 0.0));
+            assert!(near(scale, 2.0) && near(delta, 2.0));
+        },
+        other => panic!("{:?}", other)
+    }
+    match pinch.handle_finger_event(&move_to(0, 110.0, 0.0, 0.4)) {
+        PinchEvent::Pinch {scale, delta, ..} => assert!(near(scale, 1.0) && near(delta, 0.5)),
+        other => panic!("{:?}", other)
+    }
+    assert_eq!(pinch.handle_finger_event(&up(0, 110.0, 0.0, 0.5)), PinchEvent::End {scale: 1.0});
+    assert_eq!(pinch.handle_finger_event(&up(1, 220.0, 0.0, 0.5)), PinchEvent::None);
+}
+
+#[test]
+fn rotate() {
+    let mut rotate = RotateRecognizer::new();
+    rotate.handle_finger_event(&down(0, 0.0, 0.0, 0.0));
+    rotate.handle_finger_event(&down(1, 100.0, 0.0, 0.0));
+    // a quarter turn clockwise on screen, y goes down
+    assert_eq!(rotate.handle_finger_event(&move_to(1, 0.0, 100.0, 0.1)), RotateEvent::Start {center: vec2(0.0, 50.0)});
+    match rotate.handle_finger_event(&move_to(1, -100.0, 0.0, 0.2)) {
+        RotateEvent::Rotate {angle, delta, ..} => assert!(near(angle, std::f32::consts::FRAC_PI_2) && near(delta, std::f32::consts::FRAC_PI_2)),
+        other => panic!("{:?}", other)
+    }
+    // keeps counting past half a turn instead of wrapping
+    match rotate.handle_finger_event(&move_to(1, 0.0, -100.0, 0.3)) {
+        RotateEvent::Rotate {angle, ..} => assert!(near(angle, std::f32::consts::PI), "{}", angle),
+        other => panic!("{:?}", other)
+    }
+    match rotate.handle_finger_event(&move_to(1, 100.0, 0.0, 0.4)) {
+        RotateEvent::Rotate {angle, ..} => assert!(near(angle, 1.5 * std::f32::consts::PI), "{}", angle),
+        other => panic!("{:?}", other)
+    }
+    match rotate.handle_finger_event(&up(1, 100.0, 0.0, 0.5)) {
+        RotateEvent::End {angle} => assert!(near(angle, 1.5 * std::f32::consts::PI)),
+        other => panic!("{:?}", other)
+    }
+}
@@ -0,0 +1,126 @@
+// draws quads and text without a gpu and checks the pixels that come out of the software renderer
+use makepad_render::*;
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+struct SolidQuad {
+    #[default_shader(self::shader_solid)]
+    base: DrawQuad,
+}
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+struct CircleQuad {
+    #[default_shader(self::shader_circle)]
+    base: DrawQuad,
+}
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    rect: SolidQuad,
+    circle: CircleQuad,
+    text: DrawText,
+}
+
+impl App {
+    fn style(cx: &mut Cx) {
+        SolidQuad::register_draw_input(cx);
+        CircleQuad::register_draw_input(cx);
+        live_body!(cx, {
+            self::shader_solid: Shader {
+                use makepad_render::drawquad::shader::*;
+                draw_input: self::SolidQuad;
+                fn pixel() -> vec4 {
+                    return #0f0;
+                }
+            }
+            
+            self::shader_circle: Shader {
+                use makepad_render::drawquad::shader::*;
+                draw_input: self::CircleQuad;
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.circle(0.5 * rect_size.x, 0.5 * rect_size.y, 0.5 * rect_size.x - 1.);
+                    return df.fill(#00f);
+                }
+            }
+        });
+    }
+    
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window::new(cx),
+            pass: Pass::default(),
+            view: View::new(),
+            rect: SolidQuad::new(cx, default_shader!()),
+            circle: CircleQuad::new(cx, default_shader!()),
+            text: DrawText::new(cx, default_shader!()),
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        self.pass.set_window_clear_color(cx, Vec4 {x: 0.2, y: 0., z: 0., w: 1.});
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            self.rect.draw_quad_abs(cx, Rect {pos: vec2(10., 20.), size: vec2(30., 40.)});
+            self.circle.draw_quad_abs(cx, Rect {pos: vec2(100., 100.), size: vec2(50., 50.)});
+            self.text.draw_text_abs(cx, vec2(10., 80.), "Hello");
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+#[test]
+fn draws_quads_and_text() {
+    // fonts are loaded relative to the repository root
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    App::style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.platform.headless.window_size = Some(vec2(200., 160.));
+    let mut draws = 0;
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            draws += 1;
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+        }
+    });
+    assert!(draws >= 1);
+    
+    let fb = cx.window_framebuffer(0).unwrap();
+    assert_eq!((fb.width, fb.height), (200, 160));
+    let is_bg = | c: Vec4 | (c.x - 0.2).abs() < 1e-3 && c.y == 0. && c.z == 0.;
+    
+    assert!(is_bg(fb.pixel(1, 1)));
+    // inside and just outside the solid quad
+    assert_eq!(fb.pixel(20, 30), Vec4 {x: 0., y: 1., z: 0., w: 1.});
+    assert!(is_bg(fb.pixel(41, 30)));
+    assert!(is_bg(fb.pixel(20, 61)));
+    // the circle fills its middle but not the corners of its rect
+    assert!(fb.pixel(125, 125).z > 0.99);
+    assert!(is_bg(fb.pixel(101, 101)));
+    
+    // glyphs put light pixels in the text line and nowhere past its end
+    let mut lit = 0;
+    for y in 75..100 {
+        for x in 5..95 {
+            let c = fb.pixel(x, y);
+            if c.y > 0.5 && c.z > 0.5 {
+                lit += 1;
+            }
+        }
+    }
+    assert!(lit > 20, "text drew {} light pixels", lit);
+    for y in 75..100 {
+        assert!(is_bg(fb.pixel(98, y)));
+    }
+}
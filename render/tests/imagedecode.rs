@@ -0,0 +1,252 @@
+// decodes small generated images, then truncated and corrupted copies of them, which have to fail without panicking
+use makepad_render::*;
+
+// a baseline jpeg writer for images of flat 8x8 blocks, every block is a dc value and an end of block
+struct JpegWriter {
+    out: Vec<u8>,
+    acc: u32,
+    count: u32,
+}
+
+impl JpegWriter {
+    fn put(&mut self, bits: u32, count: u32) {
+        for i in (0..count).rev() {
+            self.acc = (self.acc << 1) | ((bits >> i) & 1);
+            self.count += 1;
+            if self.count == 8 {
+                self.out.push(self.acc as u8);
+                if self.acc == 0xff {
+                    self.out.push(0);
+                }
+                self.acc = 0;
+                self.count = 0;
+            }
+        }
+    }
+    
+    fn flush(&mut self) {
+        while self.count != 0 {
+            self.put(1, 1);
+        }
+    }
+}
+
+fn segment(out: &mut Vec<u8>, marker: u8, data: &[u8]) {
+    out.extend_from_slice(&[0xff, marker]);
+    out.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+// the dc table gives categories 0..12 4 bit codes, the ac table only has end of block as a 1 bit code.
+// `means` holds the value of every block per component, in mcu order
+fn jpeg(width: usize, height: usize, components: usize, dc_counts: [u8; 16], dc_values: &[u8], means: &[Vec<i32>]) -> Vec<u8> {
+    let mut out = vec![0xff, 0xd8];
+    let mut dqt = vec![0];
+    dqt.extend_from_slice(&[1; 64]);
+    segment(&mut out, 0xdb, &dqt);
+    let mut sof = vec![8];
+    sof.extend_from_slice(&(height as u16).to_be_bytes());
+    sof.extend_from_slice(&(width as u16).to_be_bytes());
+    sof.push(components as u8);
+    for id in 0..components {
+        sof.extend_from_slice(&[id as u8 + 1, 0x11, 0]);
+    }
+    segment(&mut out, 0xc0, &sof);
+    let mut dht = vec![0x00];
+    dht.extend_from_slice(&dc_counts);
+    dht.extend_from_slice(dc_values);
+    segment(&mut out, 0xc4, &dht);
+    let mut dht = vec![0x10, 1];
+    dht.extend_from_slice(&[0; 15]);
+    dht.push(0);
+    segment(&mut out, 0xc4, &dht);
+    let mut sos = vec![components as u8];
+    for id in 0..components {
+        sos.extend_from_slice(&[id as u8 + 1, 0]);
+    }
+    sos.extend_from_slice(&[0, 63, 0]);
+    segment(&mut out, 0xda, &sos);
+    
+    let mut writer = JpegWriter {out: Vec::new(), acc: 0, count: 0};
+    let mut pred = vec![0; components];
+    for block in 0..means[0].len() {
+        for c in 0..components {
+            let dc = (means[c][block] - 128) * 8;
+            let diff = dc - pred[c];
+            pred[c] = dc;
+            let category = 32 - (diff.abs() as u32).leading_zeros();
+            writer.put(category, 4);
+            writer.put(if diff < 0 {(diff + (1 << category) - 1) as u32} else {diff as u32}, category);
+            writer.put(0, 1);
+        }
+    }
+    writer.flush();
+    out.extend_from_slice(&writer.out);
+    out.extend_from_slice(&[0xff, 0xd9]);
+    out
+}
+
+fn dc_counts() -> [u8; 16] {
+    let mut counts = [0; 16];
+    counts[3] = 12;
+    counts
+}
+
+const DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+fn gray_jpeg() -> Vec<u8> {
+    let means = (0..64).map( | block | 16 * ((block % 8 + block / 8) % 8) + 8).collect();
+    jpeg(64, 64, 1, dc_counts(), &DC_VALUES, &[means])
+}
+
+fn gray(v: u32) -> u32 {
+    0xff000000 | v << 16 | v << 8 | v
+}
+
+fn decode(data: &[u8]) -> Result<DecodedImage, String> {
+    DecodedImage::decode(data, ImageOptions::default())
+}
+
+// every shorter copy, and copies with each byte replaced by a few others
+fn check_mangled(data: &[u8]) {
+    for len in 0..data.len() {
+        let _ = decode(&data[0..len]);
+    }
+    let mut copy = data.to_vec();
+    for pos in 0..data.len() {
+        for value in &[0x00, 0xff, data[pos] ^ 0x55, data[pos].wrapping_add(1)] {
+            copy[pos] = *value;
+            let _ = decode(&copy);
+        }
+        copy[pos] = data[pos];
+    }
+}
+
+#[test]
+fn jpeg_baseline() {
+    let image = decode(&gray_jpeg()).unwrap();
+    assert_eq!((image.width, image.height), (64, 64));
+    for y in 0..64 {
+        for x in 0..64 {
+            let (bx, by) = (x / 8, y / 8);
+            assert_eq!(image.pixels[y * 64 + x], gray(16 * ((bx + by) % 8) as u32 + 8));
+        }
+    }
+    
+    // one mcu of y, cb and cr, the red chroma pushes red up and green down
+    let data = jpeg(8, 8, 3, dc_counts(), &DC_VALUES, &[vec![128], vec![128], vec![192]]);
+    let image = decode(&data).unwrap();
+    assert_eq!(image.pixels[0], 0xff000000 | 218 << 16 | 82 << 8 | 128);
+}
+
+#[test]
+fn jpeg_corrupt() {
+    // two 1 bit codes and then a 2 bit one, there is no 2 bit pattern left for it
+    let mut counts = [0; 16];
+    counts[0] = 2;
+    counts[1] = 1;
+    let err = decode(&jpeg(8, 8, 1, counts, &[0, 1, 2], &[vec![128]])).unwrap_err();
+    assert!(err.contains("huffman"), "{}", err);
+    
+    // a dc category of 200 bits
+    let mut values = DC_VALUES;
+    values[0] = 200;
+    let err = decode(&jpeg(8, 8, 1, dc_counts(), &values, &[vec![128]])).unwrap_err();
+    assert!(err.contains("bit count"), "{}", err);
+    
+    // half the scan data is gone
+    let data = gray_jpeg();
+    let scan_end = data.len() - 2;
+    let scan_start = scan_end - (0..scan_end).rev().position( | i | data[i] == 0xff && data[i + 1] == 0xda).unwrap() - 1 + 14;
+    assert!(decode(&data[0..(scan_start + scan_end) / 2]).is_err());
+    
+    check_mangled(&data);
+    check_mangled(&jpeg(8, 8, 3, dc_counts(), &DC_VALUES, &[vec![100], vec![128], vec![192]]));
+}
+
+fn png(width: u32, height: u32, bit_depth: u8, color_type: u8, idat: &[u8]) -> Vec<u8> {
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut chunk = | kind: &[u8], data: &[u8] | {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        // the decoder doesnt check crcs
+        out.extend_from_slice(&[0; 4]);
+    };
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+    chunk(b"IHDR", &ihdr);
+    chunk(b"IDAT", idat);
+    chunk(b"IEND", &[]);
+    out
+}
+
+// 16x16 gray, ((x + y) % 4) * 85, one fixed huffman block
+const FIXED_ZLIB: [u8; 25] = [
+    0x78, 0xda, 0x63, 0x60, 0x08, 0x5d, 0xf5, 0x1f, 0x2f, 0x66, 0x40, 0x17, 0x63, 0x40, 0x57, 0xc3,
+    0x30, 0x8c, 0xcc, 0x00, 0x00, 0xc0, 0xfd, 0x7f, 0x81
+];
+
+// 16x16 gray picked from a skewed palette by an lcg, one dynamic huffman block
+const DYNAMIC_ZLIB: [u8; 101] = [
+    0x78, 0xda, 0x5d, 0x4e, 0xc9, 0x11, 0x00, 0x21, 0x0c, 0xa2, 0x49, 0x9a, 0xa4, 0x49, 0x36, 0x60,
+    0xf4, 0xb1, 0xd1, 0x89, 0xca, 0x65, 0x00, 0x0a, 0x36, 0x0c, 0xc9, 0x32, 0xb1, 0x15, 0x64, 0x36,
+    0xb9, 0xaf, 0x88, 0xc4, 0x01, 0xe6, 0x26, 0x15, 0x9c, 0x3e, 0xc6, 0x1c, 0xd1, 0x8e, 0x97, 0x7d,
+    0x2d, 0x49, 0x64, 0x59, 0xe1, 0x18, 0xa1, 0x16, 0x8e, 0xab, 0x2a, 0x95, 0x28, 0xda, 0x9e, 0xb0,
+    0xe6, 0xe3, 0x7c, 0xfa, 0x66, 0x51, 0x27, 0xa9, 0x80, 0xcd, 0x3b, 0x29, 0x87, 0x88, 0xd7, 0xe6,
+    0x9b, 0x3c, 0x21, 0x1d, 0xd0, 0xbc, 0x49, 0x0c, 0xe2, 0x97, 0x59, 0x37, 0x1b, 0x69, 0xfc, 0x8a,
+    0x1f, 0x88, 0xf2, 0x4a, 0x61
+];
+
+fn dynamic_pixels() -> Vec<u32> {
+    let mut seed = 1u32;
+    (0..256).map( | _ | {
+        seed = (seed.wrapping_mul(1103515245).wrapping_add(12345)) & 0x7fffffff;
+        gray([0, 0, 0, 0, 85, 170, 255][((seed >> 16) % 7) as usize])
+    }).collect()
+}
+
+#[test]
+fn png_inflate() {
+    let image = decode(&png(16, 16, 8, 0, &FIXED_ZLIB)).unwrap();
+    for y in 0..16 {
+        for x in 0..16 {
+            assert_eq!(image.pixels[y * 16 + x], gray(((x + y) % 4) as u32 * 85));
+        }
+    }
+    let image = decode(&png(16, 16, 8, 0, &DYNAMIC_ZLIB)).unwrap();
+    assert_eq!(image.pixels, dynamic_pixels());
+}
+
+#[test]
+fn png_corrupt() {
+    // any deflate data cut off before the checksum runs out
+    for zlib in &[&FIXED_ZLIB[..], &DYNAMIC_ZLIB[..]] {
+        for len in 0..zlib.len() - 4 {
+            assert!(decode(&png(16, 16, 8, 0, &zlib[0..len])).is_err(), "{} bytes", len);
+        }
+    }
+    // a stored block that claims more than there is
+    let err = decode(&png(16, 16, 8, 0, &[0x78, 0x01, 0x01, 0xff, 0xff, 0x00, 0x00, 1, 2, 3])).unwrap_err();
+    assert!(err.contains("end of data"), "{}", err);
+    // the biggest allowed header with next to no data
+    assert!(decode(&png(1 << 15, 1 << 15, 8, 6, &FIXED_ZLIB)).is_err());
+    
+    check_mangled(&png(16, 16, 8, 0, &FIXED_ZLIB));
+    check_mangled(&png(16, 16, 8, 0, &DYNAMIC_ZLIB));
+}
+
+#[test]
+fn bmp_corrupt() {
+    let image = DecodedImage {width: 3, height: 2, pixels: vec![0xff102030, 0x80405060, 0xff000000, 0x00ffffff, 0xffff0000, 0xff00ff00]};
+    let data = encode_bmp(&image);
+    assert_eq!(decode_bmp(&data).unwrap(), image);
+    
+    // a pixel offset past the end of the file
+    let mut far = data.clone();
+    far[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(decode_bmp(&far).is_err());
+    check_mangled(&data);
+}
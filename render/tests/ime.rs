@@ -0,0 +1,21 @@
+// builds preedit segments from per char styles
+use makepad_render::*;
+
+#[test]
+fn segments_from_styles() {
+    use TextImeStyle::*;
+    let segments = TextImeEvent::segments_from_styles(&[Input, Input, Target, Target, Converted]);
+    assert_eq!(segments, vec![
+        TextImeSegment {start: 0, end: 2, style: Input},
+        TextImeSegment {start: 2, end: 4, style: Target},
+        TextImeSegment {start: 4, end: 5, style: Converted},
+    ]);
+    assert_eq!(TextImeEvent::segments_from_styles(&[]), vec![]);
+
+    let ime = TextImeEvent {preedit: "にほんご".to_string(), cursor: 4, segments};
+    assert!(!ime.is_empty());
+    assert_eq!(ime.style_at(3), Target);
+    // past the segments is plain input
+    assert_eq!(ime.style_at(9), Input);
+    assert!(TextImeEvent::default().is_empty());
+}
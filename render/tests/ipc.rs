@@ -0,0 +1,170 @@
+// runs itself twice: as the parent it spawns its own binary, which then
+// connects back as an ipc child and draws a quad that changes color on click
+use makepad_render::*;
+use std::process::Command;
+use std::time::Duration;
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+struct RedQuad {
+    #[default_shader(self::shader_red)]
+    base: DrawQuad,
+}
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+struct BlueQuad {
+    #[default_shader(self::shader_blue)]
+    base: DrawQuad,
+}
+
+struct ChildApp {
+    window: Window,
+    pass: Pass,
+    view: View,
+    red: RedQuad,
+    blue: BlueQuad,
+    clicked: bool,
+}
+
+impl ChildApp {
+    fn style(cx: &mut Cx) {
+        RedQuad::register_draw_input(cx);
+        BlueQuad::register_draw_input(cx);
+        live_body!(cx, {
+            self::shader_red: Shader {
+                use makepad_render::drawquad::shader::*;
+                draw_input: self::RedQuad;
+                fn pixel() -> vec4 {
+                    return #f00;
+                }
+            }
+            self::shader_blue: Shader {
+                use makepad_render::drawquad::shader::*;
+                draw_input: self::BlueQuad;
+                fn pixel() -> vec4 {
+                    return #00f;
+                }
+            }
+        });
+    }
+    
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window::new(cx),
+            pass: Pass::default(),
+            view: View::new(),
+            red: RedQuad::new(cx, default_shader!()),
+            blue: BlueQuad::new(cx, default_shader!()),
+            clicked: false,
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        if let Event::FingerDown(_) = event {
+            self.clicked = true;
+            self.view.redraw_view(cx);
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            let rect = Rect {pos: vec2(0., 0.), size: vec2(cx.get_width_total(), cx.get_height_total())};
+            if self.clicked {
+                self.blue.draw_quad_abs(cx, rect);
+            }
+            else {
+                self.red.draw_quad_abs(cx, rect);
+            }
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn run_child() {
+    let mut cx = Cx::default();
+    cx.style();
+    ChildApp::style(&mut cx);
+    cx.init_live_styles();
+    let mut app = ChildApp::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+    });
+}
+
+// waits for a frame of the given size, and returns the shader and rect of its one draw call
+fn wait_for_frame(process: &IpcProcess, width: f32, height: f32) -> (LiveItemId, Vec<f32>) {
+    loop {
+        match process.recv_timeout(Duration::from_secs(30)) {
+            Some(IpcChildMsg::Frame(frame)) => {
+                if frame.size == vec2(width, height) {
+                    assert_eq!(frame.root.rect, Rect {pos: vec2(0., 0.), size: vec2(width, height)});
+                    let calls = frame.root.draw_calls();
+                    assert_eq!(calls.len(), 1);
+                    let call = calls[0].1;
+                    assert_eq!(call.instances.len(), call.instance_slots);
+                    return (call.shader, call.instances[0..4].to_vec())
+                }
+            },
+            Some(IpcChildMsg::Texture {..}) => (),
+            Some(IpcChildMsg::SetHoverCursor(_)) => (),
+            Some(IpcChildMsg::SetClipboard(_)) => (),
+            Some(IpcChildMsg::Exit) => panic!("child exited early"),
+            None => panic!("timed out waiting for a frame")
+        }
+    }
+}
+
+fn run_parent() {
+    let red = live_item_id!(self::shader_red);
+    let blue = live_item_id!(self::shader_blue);
+    let command = Command::new(std::env::current_exe().unwrap());
+    // returns before the child is up, what we send waits for it
+    let mut process = IpcProcess::spawn(command, Signal::empty()).unwrap();
+    
+    process.send(&IpcParentMsg::Resize {size: vec2(64., 48.), dpi_factor: 1.0}).unwrap();
+    assert_eq!(wait_for_frame(&process, 64., 48.), (red, vec![0., 0., 64., 48.]));
+    
+    process.send(&IpcParentMsg::FingerDown {
+        abs: vec2(10., 10.),
+        digit: 0,
+        tap_count: 1,
+        modifiers: KeyModifiers::default(),
+        time: 0.0
+    }).unwrap();
+    let mut shader = wait_for_frame(&process, 64., 48.).0;
+    while shader == red {
+        shader = wait_for_frame(&process, 64., 48.).0;
+    }
+    assert_eq!(shader, blue);
+    
+    let status = process.close(Duration::from_secs(10)).unwrap();
+    assert!(status.success());
+    
+    // a child that never connects shows up as an exit, without holding up spawn
+    let process = IpcProcess::spawn(Command::new("true"), Signal::empty()).unwrap();
+    assert_eq!(process.recv_timeout(Duration::from_secs(30)), Some(IpcChildMsg::Exit));
+    assert!(process.try_wait().unwrap().unwrap().success());
+    println!("ipc: ok");
+}
+
+fn main() {
+    // fonts are loaded relative to the workspace root
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    if std::env::var(IPC_SOCKET_ENV).is_ok() {
+        run_child();
+    }
+    else {
+        run_parent();
+    }
+}
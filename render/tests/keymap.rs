@@ -0,0 +1,84 @@
+// looks key events up in layered keymaps with scopes and chords
+use makepad_render::*;
+
+fn key(key_code: KeyCode, control: bool, shift: bool, time: f64) -> KeyEvent {
+    KeyEvent {
+        key_code,
+        is_repeat: false,
+        modifiers: KeyModifiers {shift, control, alt: false, logo: false},
+        time
+    }
+}
+
+#[test]
+fn parse_strokes_and_chords() {
+    let stroke = KeyStroke::parse("Mod+Shift+Z").unwrap();
+    assert_eq!(stroke.key_code, KeyCode::KeyZ);
+    assert!(stroke.primary && stroke.shift && !stroke.control);
+    assert_eq!(KeyStroke::parse("ctrl+ArrowLeft").unwrap().key_code, KeyCode::ArrowLeft);
+    assert_eq!(KeyStroke::parse("Left").unwrap().key_code, KeyCode::ArrowLeft);
+    assert_eq!(KeyStroke::parse_chord("Mod+K Mod+C").unwrap().len(), 2);
+    assert!(KeyStroke::parse("Mod+Nope").is_err());
+    assert!(KeyStroke::parse("A+B").is_err());
+    assert!(KeyStroke::parse_chord("  ").is_err());
+    // Mod takes control or logo, plain strokes want exactly their modifiers
+    let pressed = KeyStroke::from_key_event(&key(KeyCode::KeyZ, true, false, 0.0));
+    assert!(KeyStroke::parse("Mod+Z").unwrap().matches(&pressed));
+    assert!(KeyStroke::parse("Ctrl+Z").unwrap().matches(&pressed));
+    assert!(!KeyStroke::parse("Z").unwrap().matches(&pressed));
+    assert!(!KeyStroke::parse("Mod+Shift+Z").unwrap().matches(&pressed));
+}
+
+#[test]
+fn scopes_layers_and_chords() {
+    let mut cx = Cx::default();
+    // the defaults are there without loading anything
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::ArrowLeft, false, true, 1.0)), Some("editor.select_left".to_string()));
+    // editor falls back to global
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyQ, true, false, 2.0)), Some("quit".to_string()));
+
+    cx.load_keymap_ron("user", r#"(
+        bindings: [
+            (scope: "editor", keys: "Mod+K Mod+C", command: "editor.comment"),
+            (scope: "editor", keys: "Mod+Z", command: "editor.my_undo"),
+            (scope: "global", keys: "Mod+Q", command: ""),
+        ]
+    )"#).unwrap();
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyZ, true, false, 3.0)), Some("editor.my_undo".to_string()));
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyQ, true, false, 4.0)), None);
+    assert_eq!(cx.keymap_command(KEYMAP_GLOBAL, &key(KeyCode::KeyZ, true, false, 5.0)), Some("undo".to_string()));
+
+    // the first stroke of a chord waits, holding a modifier down doesnt break it
+    let first = key(KeyCode::KeyK, true, false, 6.0);
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &first), None);
+    // another widget looking at the same event sees the same chord state
+    assert_eq!(cx.keymap_command(KEYMAP_GLOBAL, &first), None);
+    assert!(cx.keymap.is_pending());
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::Control, true, false, 6.5)), None);
+    let second = key(KeyCode::KeyC, true, false, 7.0);
+    assert_eq!(cx.keymap_command(KEYMAP_GLOBAL, &second), None);
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &second), Some("editor.comment".to_string()));
+    assert!(!cx.keymap.is_pending());
+    // a key that doesnt continue the chord drops it
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyK, true, false, 8.0)), None);
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyX, true, false, 9.0)), None);
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyX, true, false, 10.0)), Some("editor.cut".to_string()));
+
+    // reloading a layer replaces it
+    cx.load_keymap_ron("user", "(bindings: [])").unwrap();
+    assert_eq!(cx.keymap_command(KEYMAP_EDITOR, &key(KeyCode::KeyZ, true, false, 11.0)), Some("editor.undo".to_string()));
+    assert!(cx.load_keymap_ron("user", r#"(bindings: [(scope: "editor", keys: "Mod+", command: "x")])"#).is_err());
+}
+
+#[test]
+fn menu_commands_take_their_keys_from_the_keymap() {
+    let mut cx = Cx::default();
+    cx.command_default_keymap();
+    let redo = cx.command_settings[&Cx::command_redo()];
+    assert_eq!(redo.key_code, KeyCode::KeyZ);
+    assert!(redo.shift);
+    cx.load_keymap_ron("user", r#"(bindings: [(scope: "global", keys: "Mod+Y", command: "redo")])"#).unwrap();
+    let redo = cx.command_settings[&Cx::command_redo()];
+    assert_eq!(redo.key_code, KeyCode::KeyY);
+    assert!(!redo.shift);
+}
@@ -0,0 +1,212 @@
+// obj and glb loading, and the generated sphere, cylinder, torus and grid plane
+use makepad_render::*;
+
+fn pos(g: &GeometryGen, i: usize) -> Vec3 {
+    vec3(g.vertices[i * 9], g.vertices[i * 9 + 1], g.vertices[i * 9 + 2])
+}
+
+fn normal(g: &GeometryGen, i: usize) -> Vec3 {
+    vec3(g.vertices[i * 9 + 4], g.vertices[i * 9 + 5], g.vertices[i * 9 + 6])
+}
+
+fn uv(g: &GeometryGen, i: usize) -> Vec2 {
+    vec2(g.vertices[i * 9 + 7], g.vertices[i * 9 + 8])
+}
+
+fn length(v: Vec3) -> f32 {
+    v.dot(v).sqrt()
+}
+
+fn close(a: Vec3, b: Vec3) -> bool {
+    length(a - b) < 1e-4
+}
+
+#[test]
+fn obj() {
+    let g = GeometryGen::from_obj("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 1\nusemtl a\nf 1/1 2 3 4\nusemtl b\nf -4 -2 -1\n").unwrap();
+    // the quad is a fan of two triangles, the second face reuses positions under another material
+    assert_eq!(g.indices.len(), 9);
+    assert_eq!(g.vertex_count_3d(), 7);
+    for i in 0..g.vertex_count_3d() {
+        assert!(close(normal(&g, i), vec3(0., 0., 1.)), "{:?}", normal(&g, i));
+    }
+    assert_eq!(g.vertices[3], 0.0);
+    assert_eq!(g.vertices[g.vertices.len() - 6], 1.0);
+    // v is flipped so the origin is at the top
+    assert_eq!(uv(&g, 0), vec2(0., 1.));
+    
+    let g = GeometryGen::from_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 -2\nf 1//1 2//1 3//1\n").unwrap();
+    assert!(close(normal(&g, 1), vec3(0., 0., -1.)));
+    
+    for bad in &["v 0 0 0\nf 1 2 3\n", "v 0 0\n", "v 0 0 x\n", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/4 2 3\n", "v 0 0 0\n", "f 0 0 0\n"] {
+        assert!(GeometryGen::from_obj(bad).is_err(), "{:?}", bad);
+    }
+}
+
+// one triangle: positions, u16 indices, normals and uvs in that order in the bin chunk
+fn bin() -> Vec<u8> {
+    let mut bin = Vec::new();
+    for f in &[0f32, 0., 0., 1., 0., 0., 0., 1., 0.] {
+        bin.extend_from_slice(&f.to_le_bytes());
+    }
+    for i in &[0u16, 1, 2, 0] {
+        bin.extend_from_slice(&i.to_le_bytes());
+    }
+    for f in &[0f32, 0., 1., 0., 0., 1., 0., 0., 1.] {
+        bin.extend_from_slice(&f.to_le_bytes());
+    }
+    for f in &[0f32, 0., 1., 0., 0., 1.] {
+        bin.extend_from_slice(&f.to_le_bytes());
+    }
+    bin
+}
+
+fn accessors() -> Vec<String> {
+    vec![
+        r#"{"bufferView":0,"componentType":5126,"count":3,"type":"VEC3"}"#.to_string(),
+        r#"{"bufferView":1,"componentType":5123,"count":3,"type":"SCALAR"}"#.to_string(),
+        r#"{"bufferView":2,"componentType":5126,"count":3,"type":"VEC3"}"#.to_string(),
+        r#"{"bufferView":3,"componentType":5126,"count":3,"type":"VEC2"}"#.to_string(),
+    ]
+}
+
+fn glb_with(nodes: &str, attributes: &str, accessors: &[String]) -> Vec<u8> {
+    let bin = bin();
+    let json = format!(r#"{{"asset":{{"version":"2.0"}},"scene":0,"scenes":[{{"nodes":[0]}}],"nodes":{},
+        "meshes":[{{"primitives":[{{"attributes":{{{}}},"indices":1,"material":3}}]}}],
+        "buffers":[{{"byteLength":{}}}],
+        "bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":36}},{{"buffer":0,"byteOffset":36,"byteLength":6}},
+            {{"buffer":0,"byteOffset":44,"byteLength":36}},{{"buffer":0,"byteOffset":80,"byteLength":24}}],
+        "accessors":[{}]}}"#, nodes, attributes, bin.len(), accessors.join(","));
+    let mut json = json.into_bytes();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut out = Vec::new();
+    out.extend_from_slice(&GLB_MAGIC);
+    out.extend_from_slice(&2u32.to_le_bytes());
+    out.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0x4E4F534Au32.to_le_bytes());
+    out.extend_from_slice(&json);
+    out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0x004E4942u32.to_le_bytes());
+    out.extend_from_slice(&bin);
+    out
+}
+
+fn glb(nodes: &str) -> Vec<u8> {
+    glb_with(nodes, r#""POSITION":0"#, &accessors())
+}
+
+#[test]
+fn glb_transforms() {
+    let g = GeometryGen::from_mesh_file(&glb(r#"[{"translation":[10,0,0],"children":[1]},{"scale":[2,2,2],"mesh":0}]"#)).unwrap();
+    assert!(close(pos(&g, 0), vec3(10., 0., 0.)) && close(pos(&g, 1), vec3(12., 0., 0.)) && close(pos(&g, 2), vec3(10., 2., 0.)), "{:?}", g.vertices);
+    // the material ends up in the id
+    assert_eq!(g.vertices[3], 3.0);
+    
+    let s = std::f32::consts::FRAC_1_SQRT_2;
+    let g = GeometryGen::from_glb(&glb(&format!(r#"[{{"rotation":[0,0,{},{}],"mesh":0}}]"#, s, s))).unwrap();
+    assert!(close(pos(&g, 1), vec3(0., 1., 0.)) && close(pos(&g, 2), vec3(-1., 0., 0.)), "{:?}", g.vertices);
+    
+    // a mirror flips the winding so computed normals still face the same way
+    let g = GeometryGen::from_glb(&glb(r#"[{"scale":[-1,1,1],"mesh":0}]"#)).unwrap();
+    for i in 0..3 {
+        assert!(close(normal(&g, i), vec3(0., 0., 1.)), "{:?}", normal(&g, i));
+    }
+    
+    let g = GeometryGen::from_glb(&glb_with(r#"[{"mesh":0}]"#, r#""POSITION":0,"NORMAL":2,"TEXCOORD_0":3"#, &accessors())).unwrap();
+    assert_eq!(uv(&g, 1), vec2(1., 0.));
+    assert!(close(normal(&g, 2), vec3(0., 0., 1.)));
+}
+
+#[test]
+fn glb_rejects_bad_accessors() {
+    let all = r#""POSITION":0,"NORMAL":2,"TEXCOORD_0":3"#;
+    let check = | attributes: &str, accessors: Vec<String>, what: &str | {
+        let err = GeometryGen::from_glb(&glb_with(r#"[{"mesh":0}]"#, attributes, &accessors)).unwrap_err();
+        assert!(err.contains(what), "{} doesnt mention {}", err, what);
+    };
+    // positions, normals, uvs and indices need their own shapes
+    let mut a = accessors();
+    a[0] = r#"{"bufferView":0,"componentType":5126,"count":4,"type":"VEC2"}"#.to_string();
+    check(all, a, "positions are not VEC3");
+    check(r#""POSITION":0,"NORMAL":3"#, accessors(), "NORMAL is not VEC3");
+    check(r#""POSITION":0,"TEXCOORD_0":2"#, accessors(), "TEXCOORD_0 is not VEC2");
+    let mut a = accessors();
+    a[1] = r#"{"bufferView":1,"componentType":5123,"count":1,"type":"VEC3"}"#.to_string();
+    check(all, a, "indices are not SCALAR");
+    
+    // attributes with fewer elements than there are vertices
+    let mut a = accessors();
+    a[2] = r#"{"bufferView":2,"componentType":5126,"count":2,"type":"VEC3"}"#.to_string();
+    check(all, a, "NORMAL has 2 elements for 3 vertices");
+    let mut a = accessors();
+    a[3] = r#"{"bufferView":3,"componentType":5126,"count":1,"type":"VEC2"}"#.to_string();
+    check(all, a, "TEXCOORD_0 has 1 elements for 3 vertices");
+    
+    // counts, offsets and strides that overflow or point outside the buffer
+    let mut a = accessors();
+    a[0] = r#"{"bufferView":0,"componentType":5126,"count":4611686018427387904,"type":"VEC3"}"#.to_string();
+    check(all, a, "past the end");
+    let mut a = accessors();
+    a[0] = r#"{"bufferView":0,"byteOffset":18446744073709551615,"componentType":5126,"count":3,"type":"VEC3"}"#.to_string();
+    check(all, a, "past the end");
+    let mut a = accessors();
+    a[0] = r#"{"bufferView":0,"componentType":5126,"count":4,"type":"VEC3"}"#.to_string();
+    check(all, a, "past the end");
+    let mut a = accessors();
+    a[2] = r#"{"componentType":5126,"count":4611686018427387904,"type":"VEC3"}"#.to_string();
+    check(all, a, "too large");
+    
+    // cut off or scribbled over, without panicking
+    let data = glb_with(r#"[{"mesh":0}]"#, all, &accessors());
+    let json_end = data.len() - bin().len() - 8;
+    for len in 0..data.len() {
+        let _ = GeometryGen::from_glb(&data[0..len]);
+    }
+    assert!(GeometryGen::from_glb(&data[0..data.len() - 4]).is_err());
+    let mut copy = data.clone();
+    for pos in (0..20).chain(json_end..data.len()) {
+        for value in &[0x00, 0xff, data[pos] ^ 0x55] {
+            copy[pos] = *value;
+            let _ = GeometryGen::from_glb(&copy);
+        }
+        copy[pos] = data[pos];
+    }
+}
+
+#[test]
+fn generated_shapes() {
+    let shapes = [
+        GeometryGen::from_sphere_3d(1.0, 16, 8),
+        GeometryGen::from_cylinder_3d(0.5, 1.0, 2.0, 12, 2, true),
+        GeometryGen::from_torus_3d(1.0, 0.3, 12, 24),
+        GeometryGen::from_grid_plane_3d(2.0, 2.0, 4, 4)
+    ];
+    for g in shapes.iter() {
+        assert!(g.indices.len() > 0 && g.indices.len() % 3 == 0);
+        assert!(g.indices.iter().all( | i | (*i as usize) < g.vertex_count_3d()));
+        for i in 0..g.vertex_count_3d() {
+            assert!((length(normal(g, i)) - 1.0).abs() < 1e-4);
+            let uv = uv(g, i);
+            assert!(uv.x >= 0.0 && uv.x <= 1.0 && uv.y >= 0.0 && uv.y <= 1.0);
+        }
+        // the given normals face the same way as the ones the winding gives
+        let mut computed = g.clone();
+        computed.compute_normals_3d();
+        let agree = (0..g.vertex_count_3d()).filter( | i | normal(g, *i).dot(normal(&computed, *i)) > 0.5).count();
+        assert!(agree * 10 > g.vertex_count_3d() * 9, "{} of {}", agree, g.vertex_count_3d());
+    }
+    
+    let sphere = &shapes[0];
+    assert_eq!(sphere.vertex_count_3d(), 17 * 9);
+    assert_eq!(sphere.indices.len(), 16 * 8 * 6);
+    for i in 0..sphere.vertex_count_3d() {
+        assert!((length(pos(sphere, i)) - 1.0).abs() < 1e-4);
+        assert!(close(pos(sphere, i), normal(sphere, i)));
+    }
+    let plane = &shapes[3];
+    assert!((0..plane.vertex_count_3d()).all( | i | pos(plane, i).y == 0.0 && pos(plane, i).x.abs() <= 1.0));
+}
@@ -0,0 +1,146 @@
+// records a headless session to a file, loads it back and replays it into a fresh app
+use makepad_render::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    text: DrawText,
+    signal: Signal,
+    file: FileRead,
+    file_len: usize,
+    clicks: usize,
+    signalled: usize,
+    triggered: usize,
+}
+
+impl App {
+    fn status_clicked() -> StatusId {uid!()}
+    fn trigger_clicked() -> TriggerId {uid!()}
+    
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window::new(cx),
+            pass: Pass::default(),
+            view: View::new(),
+            text: DrawText::new(cx, default_shader!()),
+            signal: Signal::empty(),
+            file: FileRead::default(),
+            file_len: 0,
+            clicks: 0,
+            signalled: 0,
+            triggered: 0,
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        match event {
+            Event::Construct => {
+                self.signal = cx.new_signal();
+                self.file = cx.file_read("render/Cargo.toml");
+            },
+            Event::FileRead(fr) => if let Some(Ok(data)) = self.file.resolve_utf8(fr) {
+                self.file_len = data.len();
+            },
+            Event::Signal(se) => if let Some(statuses) = se.signals.get(&self.signal) {
+                if statuses.contains(&Self::status_clicked()) {
+                    self.signalled += 1;
+                }
+            },
+            Event::Triggers(te) => if let Some(triggers) = te.triggers.get(&self.view.area()) {
+                if triggers.contains(&Self::trigger_clicked()) {
+                    self.triggered += 1;
+                }
+            },
+            _ => ()
+        }
+        if let Event::FingerDown(_) = event.hits(cx, self.view.area(), HitOpt::default()) {
+            self.clicks += 1;
+            cx.send_trigger(self.view.area(), Self::trigger_clicked());
+            Cx::post_signal(self.signal, Self::status_clicked());
+            self.view.redraw_view(cx);
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            self.text.draw_text_abs(cx, vec2(10., 10.), &format!("clicks {}", self.clicks));
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn run_app(cx: &mut Cx, app: &mut App, event: &mut Event, cxafterdraw: &mut CxAfterDraw) {
+    if let Event::Draw = event {
+        app.draw_app(cx);
+        cxafterdraw.after_draw(cx);
+        return
+    }
+    app.handle_app(cx, event);
+}
+
+fn finger_down(time: f64) -> Event {
+    Event::FingerDown(FingerDownEvent {abs: vec2(20., 20.), time: time, ..FingerDownEvent::default()})
+}
+
+fn finger_up(time: f64) -> Event {
+    Event::FingerUp(FingerUpEvent {abs: vec2(20., 20.), time: time, ..FingerUpEvent::default()})
+}
+
+#[test]
+fn record_and_replay() {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let path = std::env::temp_dir().join(format!("makepad_replay_{}.log", std::process::id())).display().to_string();
+    
+    let mut cx = Cx::default();
+    cx.style();
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.platform.headless.window_size = Some(vec2(200., 160.));
+    // the first frame handles its input before anything is drawn
+    cx.headless_push_event(Event::None);
+    for time in &[1.0, 2.0] {
+        cx.headless_push_event(finger_down(*time));
+        cx.headless_push_event(finger_up(*time + 0.1));
+    }
+    cx.start_event_recording(Some(path.clone()));
+    cx.event_loop( | cx, event | run_app(cx, &mut app, event, &mut cxafterdraw));
+    // everything went to the file as it was painted
+    let unflushed = cx.stop_event_recording().unwrap();
+    assert_eq!(unflushed.records.len(), 0);
+    
+    assert_eq!((app.clicks, app.signalled, app.triggered), (2, 2, 2));
+    assert!(app.file_len > 0);
+    
+    let log = EventLog::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(EventLog::from_bytes(&log.to_bytes()).unwrap(), log);
+    // signals keep their statuses and triggers their ids
+    let status = App::status_clicked().0;
+    let trigger = App::trigger_clicked().0;
+    let inputs_signal = log.records.iter().any( | record | match record {
+        EventRecord::Input {event: RecordedEvent::Signal(signals), ..} => signals.iter().any( | (_, statuses) | statuses.contains(&status)),
+        _ => false
+    });
+    assert!(inputs_signal);
+    let delivered_triggers = log.delivered_events().iter().any( | event | match event {
+        RecordedEvent::Triggers(triggers) => triggers.iter().any( | (_, triggers) | triggers.contains(&trigger)),
+        _ => false
+    });
+    assert!(delivered_triggers);
+    
+    let mut cx = Cx::default();
+    cx.style();
+    cx.init_live_styles();
+    let mut replayed = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    let replay = cx.replay_event_log(&log, | cx, event | run_app(cx, &mut replayed, event, &mut cxafterdraw));
+    assert!(replay.matches(), "{:?}", replay.mismatch);
+    assert_eq!((replayed.clicks, replayed.signalled, replayed.triggered), (2, 2, 2));
+    assert_eq!(replayed.file_len, app.file_len);
+}
@@ -0,0 +1,143 @@
+// the damped spring on its own, and Play::Spring anims taking over from whatever was playing
+use makepad_render::*;
+
+fn style(cx: &mut Cx) {
+    live_body!(cx, {
+        self::anim_key: Anim {
+            play: Cut {duration: 1.0},
+            tracks: [Float {keys: {0.0: 0.0, 1.0: 100.0}, bind_to: self::value}]
+        }
+        self::anim_bouncy: Anim {
+            play: Spring {stiffness: 200.0, damping: 10.0},
+            tracks: [Float {keys: {1.0: 100.0}, bind_to: self::value}]
+        }
+        self::anim_critical: Anim {
+            play: Spring {stiffness: 200.0, damping: 28.28},
+            tracks: [Float {keys: {1.0: 0.0}, bind_to: self::value}]
+        }
+    });
+}
+
+fn frame(cx: &mut Cx, animator: &mut Animator, time: f64) -> Option<f32> {
+    let event = Event::Animate(AnimateEvent {frame: 0, time, timeline_events: Vec::new()});
+    let _ = event.is_animate(cx, animator);
+    let value = animator.calc_float(cx, live_item_id!(self::value), time);
+    cx.check_ended_animator_ids(time);
+    value
+}
+
+#[test]
+fn spring_settles() {
+    let mut spring = Spring::critical(400.0);
+    spring.set_value(0.0f32);
+    spring.set_target(10.0f32);
+    let mut time = 0.0;
+    let mut last = 0.0;
+    while !spring.is_settled() {
+        time += 1.0 / 60.0;
+        spring.step(time);
+        // critical damping never overshoots and never turns back
+        let value: f32 = spring.value();
+        assert!(value <= 10.0 + 1e-3 && value >= last - 1e-4, "{}", value);
+        last = value;
+        assert!(time < 3.0);
+    }
+    assert_eq!(spring.value::<f32>(), 10.0);
+    
+    // a new target keeps the velocity it has
+    spring.set_target(20.0f32);
+    spring.step(time + 1.0 / 60.0);
+    spring.step(time + 2.0 / 60.0);
+    let velocity = spring.velocity.x;
+    assert!(velocity > 0.0);
+    spring.set_target(0.0f32);
+    assert_eq!(spring.velocity.x, velocity);
+    spring.step(time + 3.0 / 60.0);
+    assert!(spring.value::<f32>() > 10.0);
+    
+    // setting the value stops it where it is
+    spring.set_value(5.0f32);
+    assert!(spring.is_settled());
+    spring.step(time + 4.0 / 60.0);
+    assert_eq!(spring.value::<f32>(), 5.0);
+    
+    // a hitch of a whole second is stepped as a tenth
+    let mut a = Spring::new(200.0, 10.0);
+    let mut b = a.clone();
+    a.set_target(1.0f32);
+    b.set_target(1.0f32);
+    a.advance(1.0);
+    b.advance(0.1);
+    assert_eq!(a.value::<f32>(), b.value::<f32>());
+    
+    // far from zero it still arrives, even when the last steps are below what an f32 can resolve
+    let mut spring = Spring::critical(200.0);
+    spring.set_value(0.0f32);
+    spring.set_target(400.0f32);
+    let mut time = 0.0;
+    while !spring.is_settled() {
+        time += 1.0 / 60.0;
+        spring.step(time);
+        assert!(time < 10.0, "stuck at {:?}", spring);
+    }
+    assert_eq!(spring.value::<f32>(), 400.0);
+}
+
+#[test]
+fn spring_anims() {
+    let mut cx = Cx::default();
+    style(&mut cx);
+    cx.init_live_styles();
+    let value = live_item_id!(self::value);
+    let mut animator = Animator::default();
+    animator.set_last_float(value, 0.0);
+    let anim = live_anim!(cx, self::anim_bouncy);
+    assert_eq!(anim.play, Play::Spring {stiffness: 200.0, damping: 10.0});
+    animator.play_anim(&mut cx, anim);
+    let mut time = 1.0;
+    let mut max = 0.0f32;
+    for _ in 0..20 {
+        max = max.max(frame(&mut cx, &mut animator, time).unwrap());
+        time += 1.0 / 60.0;
+    }
+    assert!(max > 50.0);
+    
+    // a spring interrupting a spring carries on from its value and velocity, without a jump
+    let before = animator.last_float(value).unwrap();
+    let anim = live_anim!(cx, self::anim_critical);
+    animator.play_anim(&mut cx, anim);
+    let after = frame(&mut cx, &mut animator, time).unwrap();
+    assert!((after - before).abs() < 20.0, "{} jumped to {}", before, after);
+    // and ends once it settles
+    let mut settled = false;
+    for _ in 0..600 {
+        time += 1.0 / 60.0;
+        if frame(&mut cx, &mut animator, time).is_none() || cx.playing_animator_ids.len() == 0 {
+            settled = true;
+            break;
+        }
+    }
+    assert!(settled);
+    assert!(animator.last_float(value).unwrap().abs() < 0.01);
+    
+    // a keyframe anim interrupted by a spring hands over its velocity too
+    let mut animator = Animator::default();
+    animator.set_last_float(value, 0.0);
+    let anim = live_anim!(cx, self::anim_key);
+    animator.play_anim(&mut cx, anim);
+    frame(&mut cx, &mut animator, 100.0);
+    let mid = frame(&mut cx, &mut animator, 100.5).unwrap();
+    assert!((mid - 50.0).abs() < 1e-3);
+    let anim = live_anim!(cx, self::anim_critical);
+    animator.play_anim(&mut cx, anim);
+    let moving = frame(&mut cx, &mut animator, 100.5 + 1.0 / 60.0).unwrap();
+    
+    // the same spring started from rest at the same value
+    let mut rest = Animator::default();
+    rest.set_last_float(value, mid);
+    let anim = live_anim!(cx, self::anim_critical);
+    rest.play_anim(&mut cx, anim);
+    frame(&mut cx, &mut rest, 100.5);
+    let resting = frame(&mut cx, &mut rest, 100.5 + 1.0 / 60.0).unwrap();
+    assert!(moving > resting + 1.0, "handed over {} from rest {}", moving, resting);
+}
@@ -0,0 +1,153 @@
+// lays out walks in grid, flex and flow turtles and checks the rects that come out
+use makepad_render::*;
+
+fn begin_root(cx: &mut Cx, layout: Layout) {
+    cx.in_redraw_cycle = true;
+    cx.begin_turtle(Layout {
+        abs_origin: Some(vec2(0.0, 0.0)),
+        abs_size: Some(vec2(400.0, 300.0)),
+        walk: Walk::wh(Width::Fill, Height::Fill),
+        ..layout
+    }, Area::Empty);
+}
+
+fn walk(cx: &mut Cx, w: Width, h: Height) -> Rect {
+    cx.walk_turtle(Walk::wh(w, h))
+}
+
+fn assert_rect(r: Rect, x: f32, y: f32, w: f32, h: f32) {
+    let near = | a: f32, b: f32 | (a - b).abs() < 0.01;
+    assert!(near(r.pos.x, x) && near(r.pos.y, y) && near(r.size.x, w) && near(r.size.y, h), "{:?} != ({}, {}, {}, {})", r, x, y, w, h);
+}
+
+#[test]
+fn grid_template_resolve() {
+    let template = GridTemplate::new(&[GridTrack::Fix(100.0), GridTrack::Weight(1.0), GridTrack::Weight(3.0)]);
+    let tracks = template.resolve(420.0, 10.0);
+    assert_eq!(tracks, vec![(0.0, 100.0), (110.0, 75.0), (195.0, 225.0)]);
+    // without a known size the weights are auto
+    let tracks = template.resolve(std::f32::NAN, 10.0);
+    assert!(tracks[1].1.is_nan() && tracks[2].1.is_nan());
+}
+
+#[test]
+fn grid_fixed_weight_and_auto_tracks() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout::grid(
+        &[GridTrack::Fix(100.0), GridTrack::Weight(1.0), GridTrack::Weight(2.0)],
+        &[GridTrack::Fix(20.0), GridTrack::Fix(30.0)],
+        vec2(10.0, 5.0)
+    ));
+    let unit = (400.0 - 120.0) / 3.0;
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 0.0, 0.0, 100.0, 20.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 110.0, 0.0, unit, 20.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(8.0)), 120.0 + unit, 0.0, 2.0 * unit, 8.0);
+    assert_rect(walk(&mut cx, Width::Fix(50.0), Height::Fill), 0.0, 25.0, 50.0, 30.0);
+    // skip the rest of the row, past the template rows are auto
+    cx.turtle_new_line();
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(12.0)), 0.0, 60.0, 100.0, 12.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(40.0)), 110.0, 60.0, unit, 40.0);
+    cx.turtle_new_line();
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(10.0)), 0.0, 105.0, 100.0, 10.0);
+    cx.end_turtle(Area::Empty);
+}
+
+#[test]
+fn grid_spans_cells_and_nested_turtles() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout {
+        padding: Padding {l: 4.0, t: 6.0, r: 4.0, b: 6.0},
+        ..Layout::grid(&[GridTrack::Weight(1.0), GridTrack::Weight(1.0), GridTrack::Weight(1.0)], &[GridTrack::Fix(50.0), GridTrack::Weight(1.0)], vec2(8.0, 8.0))
+    });
+    // 392 wide, 3 columns of 125.33 and rows of 50 and 288 - 58 = 230
+    let col = (392.0 - 16.0) / 3.0;
+    cx.set_turtle_grid_span(2, 1);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 4.0, 6.0, 2.0 * col + 8.0, 50.0);
+    // a turtle in a cell fills the cell
+    cx.begin_turtle(Layout {walk: Walk::wh(Width::Fill, Height::Fill), ..Layout::default()}, Area::Empty);
+    assert_rect(cx.get_turtle_rect(), 4.0 + 2.0 * (col + 8.0), 6.0, col, 50.0);
+    walk(&mut cx, Width::Fix(10.0), Height::Fix(10.0));
+    assert_rect(cx.end_turtle(Area::Empty), 4.0 + 2.0 * (col + 8.0), 6.0, col, 50.0);
+    // jump to the last cell, then back to span both rows of the first column
+    cx.set_turtle_grid_cell(2, 1);
+    assert_rect(cx.get_turtle_grid_cell().unwrap(), 4.0 + 2.0 * (col + 8.0), 64.0, col, 230.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 4.0 + 2.0 * (col + 8.0), 64.0, col, 230.0);
+    cx.set_turtle_grid_cell(0, 0);
+    cx.set_turtle_grid_span(1, 2);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 4.0, 6.0, col, 288.0);
+    cx.end_turtle(Area::Empty);
+}
+
+#[test]
+fn computed_grid_is_as_big_as_its_rows() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout::default());
+    cx.begin_turtle(Layout {
+        walk: Walk::wh(Width::Fix(200.0), Height::Compute),
+        ..Layout::grid(&[GridTrack::Fix(60.0), GridTrack::Weight(1.0)], &[], vec2(0.0, 4.0))
+    }, Area::Empty);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(20.0)), 0.0, 0.0, 60.0, 20.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(30.0)), 60.0, 0.0, 140.0, 30.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(10.0)), 0.0, 34.0, 60.0, 10.0);
+    assert_rect(cx.end_turtle(Area::Empty), 0.0, 0.0, 200.0, 44.0);
+    cx.end_turtle(Area::Empty);
+}
+
+#[test]
+fn flex_distributes_by_weight() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout::flex(Direction::Right, &[GridTrack::Fix(50.0), GridTrack::Weight(1.0), GridTrack::Weight(3.0)], 10.0));
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 0.0, 0.0, 50.0, 300.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fix(20.0)), 60.0, 0.0, 82.5, 20.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 152.5, 0.0, 247.5, 300.0);
+    cx.end_turtle(Area::Empty);
+
+    begin_root(&mut cx, Layout::flex(Direction::Down, &[GridTrack::Weight(1.0), GridTrack::Fix(100.0), GridTrack::Weight(1.0)], 0.0));
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 0.0, 0.0, 400.0, 100.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 0.0, 100.0, 400.0, 100.0);
+    assert_rect(walk(&mut cx, Width::Fill, Height::Fill), 0.0, 200.0, 400.0, 100.0);
+    cx.end_turtle(Area::Empty);
+}
+
+#[test]
+fn flow_gaps_and_wrapping() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout::default());
+    cx.begin_turtle(Layout {
+        line_wrap: LineWrap::NewLine,
+        gap: vec2(10.0, 4.0),
+        walk: Walk::wh(Width::Fix(100.0), Height::Compute),
+        ..Layout::default()
+    }, Area::Empty);
+    assert_rect(walk(&mut cx, Width::Fix(45.0), Height::Fix(10.0)), 0.0, 0.0, 45.0, 10.0);
+    assert_rect(walk(&mut cx, Width::Fix(45.0), Height::Fix(12.0)), 55.0, 0.0, 45.0, 12.0);
+    assert_rect(walk(&mut cx, Width::Fix(45.0), Height::Fix(10.0)), 0.0, 16.0, 45.0, 10.0);
+    // the trailing gap is not part of the size
+    assert_rect(cx.end_turtle(Area::Empty), 0.0, 0.0, 100.0, 26.0);
+    cx.end_turtle(Area::Empty);
+}
+
+#[test]
+fn measure_then_draw_aligned() {
+    let mut cx = Cx::default();
+    begin_root(&mut cx, Layout::default());
+    walk(&mut cx, Width::Fix(20.0), Height::Fix(10.0));
+    let align_len = cx.align_list.len();
+    let size = cx.measure( | cx | {
+        assert!(cx.is_measuring());
+        walk(cx, Width::Fix(30.0), Height::Fix(12.0));
+        cx.begin_turtle(Layout {walk: Walk::wh(Width::Compute, Height::Compute), ..Layout::default()}, Area::Empty);
+        walk(cx, Width::Fix(40.0), Height::Fix(20.0));
+        cx.end_turtle(Area::Empty);
+    });
+    assert!(!cx.is_measuring());
+    assert_eq!(size, vec2(70.0, 20.0));
+    // nothing of the measure is left behind
+    assert_eq!(cx.align_list.len(), align_len);
+    assert_eq!(cx.get_turtle_pos(), vec2(20.0, 0.0));
+    assert_eq!(cx.get_width_left(), 380.0);
+    // centered in what is left
+    cx.move_turtle_to_align(size, Align::center());
+    assert_rect(walk(&mut cx, Width::Fix(70.0), Height::Fix(20.0)), 175.0, 140.0, 70.0, 20.0);
+    cx.end_turtle(Area::Empty);
+}
@@ -0,0 +1,245 @@
+use makepad_render::*;
+use crate::buttonlogic::*;
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+pub struct DrawCheckBox {
+    #[default_shader(self::shader_check)]
+    base: DrawQuad,
+    hover: f32,
+    down: f32,
+    selected: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckType {
+    Check,
+    Radio,
+    Toggle
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckBoxEvent {
+    None,
+    Toggled(bool)
+}
+
+// a label with a mark in front of it that flips between on and off. Radio buttons and
+// toggle switches are the same widget drawn with another shader
+#[derive(Clone)]
+pub struct CheckBox {
+    pub button: ButtonLogic,
+    pub bg: DrawCheckBox,
+    pub text: DrawText,
+    pub animator: Animator,
+    pub check_animator: Animator,
+    pub check_type: CheckType,
+    pub checked: bool,
+}
+
+impl CheckBox {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            button: ButtonLogic::default(),
+            bg: DrawCheckBox::new(cx, live_shader!(cx, self::shader_check)),
+            text: DrawText::new(cx, default_shader!()),
+            animator: Animator::default(),
+            check_animator: Animator::default(),
+            check_type: CheckType::Check,
+            checked: false,
+        }
+    }
+    
+    pub fn with_check_type(self, check_type: CheckType) -> Self {Self {check_type, ..self}}
+    pub fn with_checked(self, checked: bool) -> Self {Self {checked, ..self}}
+    
+    pub fn style(cx: &mut Cx) {
+        self::DrawCheckBox::register_draw_input(cx);
+        
+        live_body!(cx, {
+            self::layout_bg: Layout {
+                align: {fx: 0.0, fy: 0.5},
+                walk: Walk {
+                    width: Compute,
+                    height: Compute,
+                    margin: all(1.0),
+                },
+                padding: {l: 24.0, t: 5.0, r: 6.0, b: 5.0},
+            }
+            
+            self::layout_toggle: Layout {
+                align: {fx: 0.0, fy: 0.5},
+                walk: Walk {
+                    width: Compute,
+                    height: Compute,
+                    margin: all(1.0),
+                },
+                padding: {l: 36.0, t: 5.0, r: 6.0, b: 5.0},
+            }
+            
+            self::text_style_label: TextStyle {
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::anim_default: Anim {
+                play: Cut {duration: 0.1}
+                tracks: [
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawCheckBox::hover}
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawCheckBox::down}
+                    Vec4 {keys: {1.0: #9}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_over: Anim {
+                play: Cut {duration: 0.1},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawCheckBox::hover},
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawCheckBox::down},
+                    Vec4 {keys: {1.0: #f}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_down: Anim {
+                play: Cut {duration: 0.2},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawCheckBox::down},
+                    Float {keys: {1.0: 1.0}, bind_to: self::DrawCheckBox::hover},
+                    Vec4 {keys: {0.0: #c}, bind_to: makepad_render::drawtext::DrawText::color},
+                ]
+            }
+            
+            self::anim_on: Anim {
+                play: Cut {duration: 0.15},
+                tracks: [
+                    Float {keys: {1.0: 1.0}, bind_to: self::DrawCheckBox::selected},
+                ]
+            }
+            
+            self::anim_off: Anim {
+                play: Cut {duration: 0.15},
+                tracks: [
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawCheckBox::selected},
+                ]
+            }
+            
+            self::shader_check: Shader {
+                use makepad_render::drawquad::shader::*;
+                
+                draw_input: self::DrawCheckBox;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let sz = 7.;
+                    let c = vec2(4. + sz, rect_size.y * 0.5);
+                    df.box(c.x - sz, c.y - sz, 2. * sz, 2. * sz, 2.);
+                    df.fill(mix(mix(#3, #4, hover), #2a, down));
+                    df.move_to(c.x - 4., c.y);
+                    df.line_to(c.x - 1., c.y + 3.);
+                    df.line_to(c.x + 4., c.y - 3.);
+                    return df.stroke(mix(#0000, #f, selected), 1.5);
+                }
+            }
+            
+            self::shader_radio: Shader {
+                use makepad_render::drawquad::shader::*;
+                
+                draw_input: self::DrawCheckBox;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let sz = 7.;
+                    let c = vec2(4. + sz, rect_size.y * 0.5);
+                    df.circle(c.x, c.y, sz);
+                    df.fill(mix(mix(#3, #4, hover), #2a, down));
+                    df.circle(c.x, c.y, 3.5 * selected);
+                    return df.fill(mix(#0000, #f, selected));
+                }
+            }
+            
+            self::shader_toggle: Shader {
+                use makepad_render::drawquad::shader::*;
+                
+                draw_input: self::DrawCheckBox;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let sz = 7.;
+                    let cy = rect_size.y * 0.5;
+                    df.box(4., cy - sz, 26., 2. * sz, sz);
+                    df.fill(mix(mix(#3, #4, hover), #x2a6a9a, selected));
+                    df.circle(4. + sz + 12. * selected, cy, sz - 2. + down);
+                    return df.fill(mix(#9, #f, hover));
+                }
+            }
+        });
+    }
+    
+    pub fn set_checked(&mut self, cx: &mut Cx, checked: bool) {
+        if self.checked == checked {
+            return
+        }
+        self.checked = checked;
+        if checked {
+            self.check_animator.play_anim(cx, live_anim!(cx, self::anim_on));
+        }
+        else {
+            self.check_animator.play_anim(cx, live_anim!(cx, self::anim_off));
+        }
+    }
+    
+    pub fn handle_check_box(&mut self, cx: &mut Cx, event: &mut Event) -> CheckBoxEvent {
+        if let Some(ae) = event.is_animate(cx, &self.animator) {
+            self.bg.animate(cx, &mut self.animator, ae.time);
+            self.text.animate(cx, &mut self.animator, ae.time);
+        }
+        if let Some(ae) = event.is_animate(cx, &self.check_animator) {
+            self.bg.animate(cx, &mut self.check_animator, ae.time);
+        }
+        let animator = &mut self.animator;
+        let button_event = self.button.handle_button_logic(cx, event, self.bg.area(), | cx, logic_event, _ | match logic_event {
+            ButtonLogicEvent::Down => animator.play_anim(cx, live_anim!(cx, self::anim_down)),
+            ButtonLogicEvent::Default => animator.play_anim(cx, live_anim!(cx, self::anim_default)),
+            ButtonLogicEvent::Over => animator.play_anim(cx, live_anim!(cx, self::anim_over))
+        });
+        if let ButtonEvent::Clicked = button_event {
+            // a radio button is switched off by its group, not by clicking it again
+            if self.check_type == CheckType::Radio && self.checked {
+                return CheckBoxEvent::None
+            }
+            self.set_checked(cx, !self.checked);
+            return CheckBoxEvent::Toggled(self.checked)
+        }
+        CheckBoxEvent::None
+    }
+    
+    pub fn draw_check_box(&mut self, cx: &mut Cx, label: &str) {
+        if self.animator.need_init(cx) {
+            self.animator.init(cx, live_anim!(cx, self::anim_default));
+            self.bg.last_animate(&self.animator);
+            self.text.last_animate(&self.animator);
+        }
+        if self.check_animator.need_init(cx) {
+            if self.checked {
+                self.check_animator.init(cx, live_anim!(cx, self::anim_on));
+            }
+            else {
+                self.check_animator.init(cx, live_anim!(cx, self::anim_off));
+            }
+            self.bg.last_animate(&self.check_animator);
+        }
+        
+        let (shader, layout, role) = match self.check_type {
+            CheckType::Check => (live_shader!(cx, self::shader_check), live_layout!(cx, self::layout_bg), AccessRole::CheckBox),
+            CheckType::Radio => (live_shader!(cx, self::shader_radio), live_layout!(cx, self::layout_bg), AccessRole::RadioButton),
+            CheckType::Toggle => (live_shader!(cx, self::shader_toggle), live_layout!(cx, self::layout_toggle), AccessRole::Switch),
+        };
+        self.bg.set_shader(shader);
+        self.bg.begin_quad(cx, layout);
+        
+        self.text.text_style = live_text_style!(cx, self::text_style_label);
+        self.text.draw_text_walk(cx, label);
+        
+        self.bg.end_quad(cx);
+        cx.add_access_node(AccessNode::new(role, self.bg.area()).with_name(label).with_selected(self.checked));
+    }
+}
@@ -50,7 +50,7 @@ impl ColorPicker {
     }
     
     pub fn handle_finger(&mut self, cx: &mut Cx, rel: Vec2) -> ColorPickerEvent {
-        fn clamp(x: f32, mi: f32, ma: f32) -> f32 {if x < mi {mi} else if x > ma {ma} else {x}};
+        fn clamp(x: f32, mi: f32, ma: f32) -> f32 {if x < mi {mi} else if x > ma {ma} else {x}}
         let vx = rel.x - 0.5 * self.size;
         let vy = rel.y - 0.5 * self.size;
         let rsize = (self.size * 0.28) / 2.0f32.sqrt();
@@ -182,6 +182,7 @@ impl ColorPicker {
             width: Width::Fill,
             height: Height::Fix(self.size * height_scale)
         });
+        cx.add_access_node(AccessNode::new(AccessRole::ColorWell, self.wheel.area()));
     }
 
     pub fn style(cx: &mut Cx) {
@@ -0,0 +1,352 @@
+use makepad_render::*;
+use crate::popup::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextMenuEvent {
+    None,
+    Command(CommandId),
+    Dismiss
+}
+
+// one open panel, the first is the menu itself and every open submenu adds one
+#[derive(Clone, Default)]
+pub struct MenuLevel {
+    pub hover: Option<usize>,
+    pub open: Option<usize>,
+    pub _items: Vec<Area>,
+}
+
+// draws a Menu in a popup. Items are enabled through CommandId::set_enabled like in the native menu,
+// a picked item comes back as a command the app routes the same way it does Event::Command
+#[derive(Clone)]
+pub struct ContextMenu {
+    pub popup: Popup,
+    pub menu: Menu,
+    pub levels: Vec<MenuLevel>,
+    pub bg: DrawColor,
+    pub item_bg: DrawColor,
+    pub text: DrawText,
+    pub line: DrawColor,
+    pub arrow: DrawColor,
+    pub _origin: Vec2,
+}
+
+impl ContextMenu {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            popup: Popup::new(),
+            menu: Menu::main(vec![]),
+            levels: Vec::new(),
+            bg: DrawColor::new(cx, live_shader!(cx, self::shader_bg)),
+            item_bg: DrawColor::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.2),
+            line: DrawColor::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            arrow: DrawColor::new(cx, live_shader!(cx, self::shader_arrow))
+                .with_draw_depth(0.2),
+            _origin: Vec2::default(),
+        }
+    }
+    
+    pub fn style(cx: &mut Cx) {
+        live_body!(cx, {
+            self::color_bg: #x2a;
+            self::color_hover: #x11466d;
+            self::color_text: #c;
+            self::color_text_disabled: #6;
+            self::color_line: #4a;
+            self::color_arrow: #9;
+            
+            self::layout_menu: Layout {
+                padding: {l: 1., t: 4., r: 1., b: 4.},
+                direction: Down,
+                walk: Walk {width: Compute, height: Compute}
+            }
+            
+            self::layout_item: Layout {
+                align: {fx: 0.0, fy: 0.5},
+                padding: {l: 12., t: 0., r: 28., b: 0.},
+                walk: Walk {width: Compute, height: Fix(22.)}
+            }
+            
+            self::walk_line: Walk {
+                width: Fill,
+                height: Fix(1.),
+                margin: {l: 6., t: 4., r: 6., b: 4.},
+            }
+            
+            self::text_style_item: TextStyle {
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::shader_bg: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0., 0., rect_size.x, rect_size.y, 3.);
+                    df.fill(#4a);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 2.);
+                    return df.fill(color);
+                }
+            }
+            
+            self::shader_arrow: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let c = rect_size * 0.5;
+                    let sz = 3.0;
+                    df.move_to(c.x - 0.5 * sz, c.y - sz);
+                    df.line_to(c.x + sz, c.y);
+                    df.line_to(c.x - 0.5 * sz, c.y + sz);
+                    df.close_path();
+                    return df.fill(color);
+                }
+            }
+        })
+    }
+    
+    pub fn open_at(&mut self, cx: &mut Cx, menu: Menu, pos: Vec2) {
+        self.menu = menu;
+        self.levels = vec![MenuLevel::default()];
+        self._origin = pos;
+        self.popup.close(cx);
+        self.popup.open(cx);
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.popup.close(cx);
+        self.levels.truncate(0);
+    }
+    
+    pub fn is_enabled(cx: &Cx, item: &Menu) -> bool {
+        match item {
+            Menu::Item {command, ..} => cx.command_settings.get(command).map_or(false, | s | s.enabled),
+            Menu::Sub {..} => true,
+            _ => false
+        }
+    }
+    
+    // the items of a level, found by following the open submenus down from the top
+    fn level_items(&self, level: usize) -> &[Menu] {
+        let mut items = match &self.menu {
+            Menu::Main {items} | Menu::Sub {items, ..} => &items[..],
+            _ => &[]
+        };
+        for lvl in &self.levels[0..level] {
+            items = match lvl.open.and_then( | open | items.get(open)) {
+                Some(Menu::Sub {items, ..}) => &items[..],
+                _ => &[]
+            };
+        }
+        items
+    }
+    
+    // the next enabled item in a direction, wrapping around
+    fn step(&self, cx: &Cx, level: usize, from: Option<usize>, dir: isize) -> Option<usize> {
+        let items = self.level_items(level);
+        let len = items.len() as isize;
+        let mut index = from.map_or(if dir > 0 {-1} else {len}, | from | from as isize);
+        for _ in 0..len {
+            index = (index + dir + len) % len;
+            if Self::is_enabled(cx, &items[index as usize]) {
+                return Some(index as usize)
+            }
+        }
+        from
+    }
+    
+    // hovering closes deeper levels, and opens the submenu under the item when asked to
+    fn set_hover(&mut self, cx: &mut Cx, level: usize, hover: Option<usize>, open_sub: bool) {
+        self.levels.truncate(level + 1);
+        let is_sub = match hover.and_then( | hover | self.level_items(level).get(hover)) {
+            Some(Menu::Sub {..}) => true,
+            _ => false
+        };
+        self.levels[level].hover = hover;
+        self.levels[level].open = None;
+        if open_sub && is_sub {
+            self.levels[level].open = hover;
+            self.levels.push(MenuLevel::default());
+        }
+        self.popup.view.redraw_view(cx);
+    }
+    
+    fn activate(&mut self, cx: &mut Cx, level: usize, index: usize) -> ContextMenuEvent {
+        match self.level_items(level).get(index).cloned() {
+            Some(Menu::Item {command, ..}) => if cx.command_settings.get(&command).map_or(false, | s | s.enabled) {
+                self.close(cx);
+                return ContextMenuEvent::Command(command)
+            },
+            Some(Menu::Sub {..}) => {
+                self.set_hover(cx, level, Some(index), true);
+                let first = self.step(cx, level + 1, None, 1);
+                self.levels[level + 1].hover = first;
+            },
+            _ => ()
+        }
+        ContextMenuEvent::None
+    }
+    
+    pub fn handle_context_menu(&mut self, cx: &mut Cx, event: &mut Event) -> ContextMenuEvent {
+        if let PopupEvent::Dismiss = self.popup.handle_popup(cx, event) {
+            self.levels.truncate(0);
+            return ContextMenuEvent::Dismiss
+        }
+        if !self.popup.is_open {
+            return ContextMenuEvent::None
+        }
+        
+        let mut hits = Vec::new();
+        for (level, lvl) in self.levels.iter().enumerate() {
+            for (index, area) in lvl._items.iter().enumerate() {
+                match event.hits(cx, *area, HitOpt::default()) {
+                    Event::None => (),
+                    hit => hits.push((level, index, hit))
+                }
+            }
+        }
+        for (level, index, hit) in hits {
+            if level >= self.levels.len() {
+                continue
+            }
+            match hit {
+                Event::FingerHover(fe) => match fe.hover_state {
+                    HoverState::Out => if self.levels[level].hover == Some(index) && self.levels[level].open != Some(index) {
+                        self.set_hover(cx, level, None, false);
+                    },
+                    _ => if self.levels[level].hover != Some(index) {
+                        let enabled = Self::is_enabled(cx, &self.level_items(level)[index]);
+                        self.set_hover(cx, level, if enabled {Some(index)} else {None}, true);
+                    }
+                },
+                Event::FingerUp(fe) => if fe.is_over {
+                    if let ContextMenuEvent::Command(command) = self.activate(cx, level, index) {
+                        return ContextMenuEvent::Command(command)
+                    }
+                },
+                _ => ()
+            }
+        }
+        
+        if let Event::KeyDown(ke) = event.hits(cx, self.popup.view.area(), HitOpt::default()) {
+            let level = self.levels.len() - 1;
+            let hover = self.levels[level].hover;
+            match cx.keymap_command(KEYMAP_POPUP, &ke).as_deref() {
+                Some("popup.next") => {
+                    let next = self.step(cx, level, hover, 1);
+                    self.set_hover(cx, level, next, false);
+                },
+                Some("popup.prev") => {
+                    let prev = self.step(cx, level, hover, -1);
+                    self.set_hover(cx, level, prev, false);
+                },
+                Some("popup.open") => if let Some(index) = hover {
+                    if let Some(Menu::Sub {..}) = self.level_items(level).get(index) {
+                        self.activate(cx, level, index);
+                    }
+                },
+                Some("popup.back") => if level > 0 {
+                    self.set_hover(cx, level - 1, self.levels[level - 1].hover, false);
+                },
+                Some("popup.activate") => if let Some(index) = hover {
+                    return self.activate(cx, level, index)
+                },
+                _ => ()
+            }
+        }
+        ContextMenuEvent::None
+    }
+    
+    // measured with computed widths, then drawn with every item as wide as the widest
+    fn draw_level(&mut self, cx: &mut Cx, level: usize, width: Option<f32>) -> Area {
+        let items = self.level_items(level).to_vec();
+        let hover = self.levels[level].hover;
+        let mut layout_menu = live_layout!(cx, self::layout_menu);
+        let mut layout_item = live_layout!(cx, self::layout_item);
+        if let Some(width) = width {
+            layout_menu.walk.width = Width::Fix(width);
+            layout_item.walk.width = Width::Fill;
+        }
+        
+        self.bg.color = live_vec4!(cx, self::color_bg);
+        self.bg.begin_quad(cx, layout_menu);
+        self.text.text_style = live_text_style!(cx, self::text_style_item);
+        let mut areas = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            match item {
+                Menu::Item {name, ..} | Menu::Sub {name, ..} => {
+                    let enabled = Self::is_enabled(cx, item);
+                    self.item_bg.color = if enabled && hover == Some(index) {
+                        live_vec4!(cx, self::color_hover)
+                    } else {
+                        live_vec4!(cx, self::color_bg)
+                    };
+                    self.item_bg.begin_quad(cx, layout_item);
+                    self.text.color = if enabled {
+                        live_vec4!(cx, self::color_text)
+                    } else {
+                        live_vec4!(cx, self::color_text_disabled)
+                    };
+                    self.text.draw_text_walk(cx, name);
+                    self.item_bg.end_quad(cx);
+                    let area = self.item_bg.area();
+                    if let Menu::Sub {..} = item {
+                        let rect = area.get_rect(cx);
+                        self.arrow.color = live_vec4!(cx, self::color_arrow);
+                        self.arrow.draw_quad_abs(cx, Rect {pos: vec2(rect.pos.x + rect.size.x - 20., rect.pos.y), size: vec2(12., rect.size.y)});
+                    }
+                    if !cx.is_measuring() {
+                        let node = AccessNode::new(AccessRole::MenuItem, area).with_name(name).with_selected(hover == Some(index));
+                        cx.add_access_node(if let Menu::Sub {..} = item {node.with_expanded(self.levels[level].open == Some(index))} else {node});
+                    }
+                    areas.push(area);
+                },
+                _ => {
+                    self.line.color = live_vec4!(cx, self::color_line);
+                    self.line.draw_quad_walk(cx, live_walk!(cx, self::walk_line));
+                    areas.push(Area::Empty);
+                }
+            }
+            cx.turtle_new_line();
+        }
+        self.bg.end_quad(cx);
+        self.levels[level]._items = areas;
+        self.bg.area()
+    }
+    
+    pub fn draw_context_menu(&mut self, cx: &mut Cx) {
+        if self.popup.begin_popup(cx, Layout::default()).is_err() {
+            return
+        }
+        for level in 0..self.levels.len() {
+            let (anchor, placement) = if level == 0 {
+                (Rect {pos: self._origin, size: Vec2::default()}, PopupPlacement::Point)
+            }
+            else {
+                let open = self.levels[level - 1].open.unwrap_or(0);
+                let rect = self.levels[level - 1]._items.get(open).map_or(Rect::default(), | area | area.get_rect(cx));
+                (Rect {pos: vec2(rect.pos.x, rect.pos.y - 4.), size: rect.size}, PopupPlacement::Right)
+            };
+            let size = cx.measure( | cx | {self.draw_level(cx, level, None);});
+            // a submenu gets its own draw calls so it covers the level it hangs off
+            if level > 0 {
+                cx.new_draw_call(self.bg.shader());
+                cx.new_draw_call(self.item_bg.shader());
+                cx.new_draw_call(self.text.shader());
+                cx.new_draw_call(self.arrow.shader());
+            }
+            self.popup.begin_panel(cx, anchor, placement, size);
+            cx.begin_access_node(AccessNode::new(AccessRole::Menu, self.popup.view.area()));
+            let area = self.draw_level(cx, level, Some(size.x));
+            cx.end_access_node();
+            self.popup.end_panel(cx);
+            self.popup.add_panel(area);
+        }
+        self.popup.end_popup(cx);
+    }
+}
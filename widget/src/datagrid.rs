@@ -0,0 +1,652 @@
+use makepad_render::*;
+use crate::scrollview::*;
+use crate::scrollshadow::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending
+}
+
+// the rows behind a grid, only the cells in view get asked for
+pub trait GridDataSource {
+    fn row_count(&self) -> usize;
+    fn cell_text(&self, row: usize, column: usize, out: &mut String);
+    // reorders the rows when a header is clicked, sources that can't sort leave it alone
+    fn sort_rows(&mut self, _column: usize, _order: SortOrder) {}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridColumn {
+    pub name: String,
+    pub width: f32,
+    pub min_width: f32,
+    pub sortable: bool,
+}
+
+impl GridColumn {
+    pub fn new(name: &str, width: f32) -> Self {
+        Self {name: name.to_string(), width, min_width: 30., sortable: true}
+    }
+    
+    pub fn with_min_width(self, min_width: f32) -> Self {Self {min_width, ..self}}
+    pub fn with_sortable(self, sortable: bool) -> Self {Self {sortable, ..self}}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridCell {
+    pub row: usize,
+    pub column: usize
+}
+
+// the rectangle of cells spanned by the anchor and the cursor
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridSelection {
+    pub anchor: GridCell,
+    pub cursor: GridCell
+}
+
+impl GridSelection {
+    pub fn new(cell: GridCell) -> Self {
+        Self {anchor: cell, cursor: cell}
+    }
+    
+    pub fn rows(&self) -> (usize, usize) {
+        (self.anchor.row.min(self.cursor.row), self.anchor.row.max(self.cursor.row))
+    }
+    
+    pub fn columns(&self) -> (usize, usize) {
+        (self.anchor.column.min(self.cursor.column), self.anchor.column.max(self.cursor.column))
+    }
+    
+    pub fn contains(&self, cell: GridCell) -> bool {
+        let (r0, r1) = self.rows();
+        let (c0, c1) = self.columns();
+        cell.row >= r0 && cell.row <= r1 && cell.column >= c0 && cell.column <= c1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataGridEvent {
+    None,
+    Select {selection: GridSelection},
+    // return or a double click
+    Activate {cell: GridCell},
+    Sort {column: usize, order: SortOrder},
+    ColumnResize {column: usize, width: f32},
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GridHit {
+    Header(usize),
+    HeaderEdge(usize),
+    Cell(GridCell)
+}
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+pub struct DrawSortArrow {
+    #[default_shader(self::shader_sort_arrow)]
+    base: DrawColor,
+    descending: f32,
+}
+
+#[derive(Clone)]
+pub struct DataGrid {
+    pub view: ScrollView,
+    pub columns: Vec<GridColumn>,
+    pub sort: Option<(usize, SortOrder)>,
+    pub selection: Option<GridSelection>,
+    pub freeze_first_column: bool,
+    pub name: String,
+    
+    pub cell_bg: DrawColor,
+    pub cell_text: DrawText,
+    pub frozen_bg: DrawColor,
+    pub frozen_text: DrawText,
+    pub header_bg: DrawColor,
+    pub header_text: DrawText,
+    pub sort_arrow: DrawSortArrow,
+    pub shadow: ScrollShadow,
+    
+    pub _resize: Option<(usize, f32)>,
+    pub _select_drag: bool,
+    pub _page_rows: usize,
+}
+
+impl DataGrid {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            view: ScrollView::new_standard_hv(cx),
+            columns: Vec::new(),
+            sort: None,
+            selection: None,
+            freeze_first_column: true,
+            name: String::new(),
+            
+            cell_bg: DrawColor::new(cx, live_shader!(cx, self::shader_cell)),
+            cell_text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            frozen_bg: DrawColor::new(cx, live_shader!(cx, self::shader_cell))
+                .with_draw_depth(0.2),
+            frozen_text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.3),
+            header_bg: DrawColor::new(cx, live_shader!(cx, self::shader_cell))
+                .with_draw_depth(0.4),
+            header_text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.5),
+            sort_arrow: DrawSortArrow::new(cx, default_shader!())
+                .with_draw_depth(0.5),
+            shadow: ScrollShadow::new(cx),
+            
+            _resize: None,
+            _select_drag: false,
+            _page_rows: 1,
+        }
+    }
+    
+    pub fn with_columns(self, columns: Vec<GridColumn>) -> Self {
+        Self {columns, ..self}
+    }
+    
+    pub fn with_freeze_first_column(self, freeze_first_column: bool) -> Self {
+        Self {freeze_first_column, ..self}
+    }
+    
+    pub fn with_name(self, name: &str) -> Self {
+        Self {name: name.to_string(), ..self}
+    }
+    
+    pub fn style(cx: &mut Cx) {
+        DrawSortArrow::register_draw_input(cx);
+        live_body!(cx, {
+            self::header_height: 22.0;
+            self::resize_margin: 4.0;
+            self::color_header_bg: #3a;
+            self::color_header_text: #d;
+            self::color_cell_text: #b;
+            self::color_bg_selected: #x28;
+            self::color_bg_odd: #25;
+            self::color_bg_marked: #x11466e;
+            self::color_bg_frozen: #x2e;
+            self::color_sort_arrow: #9;
+            
+            self::layout_cell: Layout {
+                walk: Walk {width: Fill, height: Fix(20.)},
+                align: {fx: 0.0, fy: 0.5},
+                padding: {l: 5., t: 0., r: 5., b: 0.},
+            }
+            
+            self::text_style_cell: TextStyle {
+                top_drop: 1.3,
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::shader_cell: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.rect(-1., -1., rect_size.x + 2., rect_size.y + 2.);
+                    df.fill(color);
+                    df.move_to(rect_size.x, 0.);
+                    df.line_to(rect_size.x, rect_size.y);
+                    return df.stroke(#0006, 1.);
+                }
+            }
+            
+            self::shader_sort_arrow: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                draw_input: self::DrawSortArrow;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let c = rect_size * 0.5;
+                    let sz = 3.0;
+                    if descending > 0.5 {
+                        df.move_to(c.x - sz, c.y - 0.5 * sz);
+                        df.line_to(c.x + sz, c.y - 0.5 * sz);
+                        df.line_to(c.x, c.y + sz);
+                    }
+                    else {
+                        df.move_to(c.x - sz, c.y + 0.5 * sz);
+                        df.line_to(c.x + sz, c.y + 0.5 * sz);
+                        df.line_to(c.x, c.y - sz);
+                    }
+                    df.close_path();
+                    return df.fill(color);
+                }
+            }
+        })
+    }
+    
+    pub fn set_columns(&mut self, cx: &mut Cx, columns: Vec<GridColumn>) {
+        self.columns = columns;
+        self.sort = None;
+        self.selection = None;
+        self.view.redraw_view(cx);
+    }
+    
+    pub fn set_selection(&mut self, cx: &mut Cx, selection: Option<GridSelection>) {
+        self.selection = selection;
+        self.view.redraw_view(cx);
+    }
+    
+    // the source changed under us, the selection is kept if it still fits
+    pub fn rows_changed(&mut self, cx: &mut Cx, source: &dyn GridDataSource) {
+        if let Some(sel) = self.selection {
+            if sel.rows().1 >= source.row_count() {
+                self.selection = None;
+            }
+        }
+        self.view.redraw_view(cx);
+    }
+    
+    // the selected cells, tabs between columns and newlines between rows like spreadsheets want them
+    pub fn selection_text(&self, source: &dyn GridDataSource) -> String {
+        let mut text = String::new();
+        if let Some(sel) = self.selection {
+            let (r0, r1) = sel.rows();
+            let (c0, c1) = sel.columns();
+            let mut cell = String::new();
+            for row in r0..=r1.min(source.row_count().max(1) - 1) {
+                for column in c0..=c1 {
+                    cell.truncate(0);
+                    source.cell_text(row, column, &mut cell);
+                    text.push_str(&cell);
+                    text.push(if column == c1 {'\n'} else {'\t'});
+                }
+                if text.len() > 1000000 { // safety break
+                    break;
+                }
+            }
+        }
+        text
+    }
+    
+    fn frozen_width(&self) -> f32 {
+        if self.freeze_first_column && self.columns.len() > 0 {self.columns[0].width} else {0.}
+    }
+    
+    fn column_x(&self, column: usize) -> f32 {
+        self.columns[..column].iter().map( | c | c.width).sum()
+    }
+    
+    fn total_width(&self) -> f32 {
+        self.columns.iter().map( | c | c.width).sum()
+    }
+    
+    fn hit_test(&self, cx: &Cx, abs: Vec2, row_count: usize, clamp: bool) -> Option<GridHit> {
+        if self.columns.len() == 0 {
+            return None
+        }
+        let rect = self.view.area().get_rect(cx);
+        let scroll = self.view.get_scroll_pos(cx);
+        let rel = abs - rect.pos;
+        let header_height = live_float!(cx, self::header_height);
+        let row_height = live_layout!(cx, self::layout_cell).walk.height.fixed();
+        let x = if rel.x < self.frozen_width() {rel.x} else {rel.x + scroll.x};
+        if rel.y < header_height {
+            let margin = live_float!(cx, self::resize_margin);
+            let mut right = 0.;
+            for (index, column) in self.columns.iter().enumerate() {
+                right += column.width;
+                if (x - right).abs() < margin {
+                    return Some(GridHit::HeaderEdge(index))
+                }
+                if x < right {
+                    return Some(GridHit::Header(index))
+                }
+            }
+            return None
+        }
+        if row_count == 0 {
+            return None
+        }
+        let row = ((rel.y - header_height + scroll.y) / row_height).floor().max(0.) as usize;
+        let mut right = 0.;
+        let mut column = None;
+        for (index, c) in self.columns.iter().enumerate() {
+            right += c.width;
+            if x < right {
+                column = Some(index);
+                break;
+            }
+        }
+        match column {
+            Some(column) if row < row_count => Some(GridHit::Cell(GridCell {row, column})),
+            _ if clamp => Some(GridHit::Cell(GridCell {
+                row: row.min(row_count - 1),
+                column: column.unwrap_or(self.columns.len() - 1)
+            })),
+            _ => None
+        }
+    }
+    
+    fn scroll_cell_in_view(&mut self, cx: &mut Cx, cell: GridCell) {
+        let header_height = live_float!(cx, self::header_height);
+        let row_height = live_layout!(cx, self::layout_cell).walk.height.fixed();
+        let frozen_width = self.frozen_width();
+        let scroll = self.view.get_scroll_pos(cx);
+        // the header and the frozen column cover part of the view, so they are added to the cell
+        let (x, w) = if self.freeze_first_column && cell.column == 0 {
+            (scroll.x, 0.)
+        }
+        else {
+            (self.column_x(cell.column) - frozen_width, self.columns[cell.column].width + frozen_width)
+        };
+        self.view.scroll_into_view(cx, Rect {
+            pos: vec2(x, cell.row as f32 * row_height),
+            size: vec2(w, row_height + header_height)
+        });
+    }
+    
+    fn move_cursor(&mut self, cx: &mut Cx, row_count: usize, dr: isize, dc: isize, extend: bool) -> DataGridEvent {
+        if row_count == 0 || self.columns.len() == 0 {
+            return DataGridEvent::None
+        }
+        let sel = self.selection.unwrap_or_default();
+        let cursor = GridCell {
+            row: (sel.cursor.row as isize + dr).max(0).min(row_count as isize - 1) as usize,
+            column: (sel.cursor.column as isize + dc).max(0).min(self.columns.len() as isize - 1) as usize,
+        };
+        let sel = if extend {GridSelection {anchor: sel.anchor, cursor}} else {GridSelection::new(cursor)};
+        self.selection = Some(sel);
+        self.scroll_cell_in_view(cx, cursor);
+        self.view.redraw_view(cx);
+        DataGridEvent::Select {selection: sel}
+    }
+    
+    pub fn handle_data_grid(&mut self, cx: &mut Cx, event: &mut Event, source: &mut dyn GridDataSource) -> DataGridEvent {
+        if self.view.handle_scroll_view(cx, event) {
+            self.view.redraw_view(cx);
+        }
+        let row_count = source.row_count();
+        
+        match event.hits(cx, self.view.area(), HitOpt::default()) {
+            Event::FingerHover(fe) => {
+                if let Some(GridHit::HeaderEdge(_)) = self.hit_test(cx, fe.abs, row_count, false) {
+                    cx.set_hover_mouse_cursor(MouseCursor::ColResize);
+                }
+            },
+            Event::FingerDown(fe) => {
+                // keys go to the grid once it has been clicked
+                cx.set_key_focus(self.view.area());
+                match self.hit_test(cx, fe.abs, row_count, false) {
+                    Some(GridHit::HeaderEdge(column)) => {
+                        cx.set_down_mouse_cursor(MouseCursor::ColResize);
+                        self._resize = Some((column, self.columns[column].width));
+                    },
+                    Some(GridHit::Header(column)) => if self.columns[column].sortable {
+                        let order = match self.sort {
+                            Some((c, SortOrder::Ascending)) if c == column => SortOrder::Descending,
+                            _ => SortOrder::Ascending
+                        };
+                        source.sort_rows(column, order);
+                        self.sort = Some((column, order));
+                        // the rows moved, the selected cells are not the same data anymore
+                        self.selection = None;
+                        self.view.redraw_view(cx);
+                        return DataGridEvent::Sort {column, order}
+                    },
+                    Some(GridHit::Cell(cell)) => {
+                        let sel = match self.selection {
+                            Some(sel) if fe.modifiers.shift => GridSelection {anchor: sel.anchor, cursor: cell},
+                            _ => GridSelection::new(cell)
+                        };
+                        self.selection = Some(sel);
+                        self._select_drag = true;
+                        self.view.redraw_view(cx);
+                        if fe.tap_count > 1 {
+                            return DataGridEvent::Activate {cell}
+                        }
+                        return DataGridEvent::Select {selection: sel}
+                    },
+                    None => ()
+                }
+            },
+            Event::FingerMove(fe) => {
+                if let Some((column, start_width)) = self._resize {
+                    let width = (start_width + fe.abs.x - fe.abs_start.x).max(self.columns[column].min_width);
+                    if width != self.columns[column].width {
+                        self.columns[column].width = width;
+                        self.view.redraw_view(cx);
+                        return DataGridEvent::ColumnResize {column, width}
+                    }
+                }
+                else if self._select_drag {
+                    if let Some(GridHit::Cell(cell)) = self.hit_test(cx, fe.abs, row_count, true) {
+                        if let Some(sel) = self.selection {
+                            if sel.cursor != cell {
+                                let sel = GridSelection {anchor: sel.anchor, cursor: cell};
+                                self.selection = Some(sel);
+                                self.scroll_cell_in_view(cx, cell);
+                                self.view.redraw_view(cx);
+                                return DataGridEvent::Select {selection: sel}
+                            }
+                        }
+                    }
+                }
+            },
+            Event::FingerUp(_) => {
+                self._resize = None;
+                self._select_drag = false;
+            },
+            Event::KeyDown(ke) => {
+                let page = self._page_rows.max(1) as isize;
+                return match cx.keymap_command(KEYMAP_DATA_GRID, &ke).as_deref() {
+                    Some("data_grid.move_up") => self.move_cursor(cx, row_count, -1, 0, false),
+                    Some("data_grid.move_down") => self.move_cursor(cx, row_count, 1, 0, false),
+                    Some("data_grid.move_left") => self.move_cursor(cx, row_count, 0, -1, false),
+                    Some("data_grid.move_right") => self.move_cursor(cx, row_count, 0, 1, false),
+                    Some("data_grid.select_up") => self.move_cursor(cx, row_count, -1, 0, true),
+                    Some("data_grid.select_down") => self.move_cursor(cx, row_count, 1, 0, true),
+                    Some("data_grid.select_left") => self.move_cursor(cx, row_count, 0, -1, true),
+                    Some("data_grid.select_right") => self.move_cursor(cx, row_count, 0, 1, true),
+                    Some("data_grid.move_page_up") => self.move_cursor(cx, row_count, -page, 0, false),
+                    Some("data_grid.move_page_down") => self.move_cursor(cx, row_count, page, 0, false),
+                    Some("data_grid.move_home") => self.move_cursor(cx, row_count, -(row_count as isize), 0, false),
+                    Some("data_grid.move_end") => self.move_cursor(cx, row_count, row_count as isize, 0, false),
+                    Some("data_grid.select_all") => if row_count > 0 && self.columns.len() > 0 {
+                        let sel = GridSelection {
+                            anchor: GridCell {row: 0, column: 0},
+                            cursor: GridCell {row: row_count - 1, column: self.columns.len() - 1}
+                        };
+                        self.set_selection(cx, Some(sel));
+                        DataGridEvent::Select {selection: sel}
+                    } else {DataGridEvent::None},
+                    Some("data_grid.activate") => match self.selection {
+                        Some(sel) => DataGridEvent::Activate {cell: sel.cursor},
+                        None => DataGridEvent::None
+                    },
+                    _ => DataGridEvent::None
+                }
+            },
+            Event::TextCopy(_) => match event { // access the original event
+                Event::TextCopy(req) => if self.selection.is_some() {
+                    req.response = Some(self.selection_text(source));
+                },
+                _ => ()
+            },
+            _ => ()
+        }
+        DataGridEvent::None
+    }
+    
+    // one cell of text clipped to its column
+    fn draw_cell_text(cx: &mut Cx, text: &mut DrawText, layout: Layout, rect: Rect, value: &str) {
+        cx.begin_turtle(Layout {
+            abs_origin: Some(rect.pos),
+            walk: Walk::wh(Width::Fix(rect.size.x), Height::Fix(rect.size.y)),
+            ..layout
+        }, Area::Empty);
+        text.wrapping = Wrapping::Ellipsis(rect.size.x - layout.padding.l - layout.padding.r - 10.);
+        text.draw_text_walk(cx, value);
+        cx.end_turtle(Area::Empty);
+    }
+    
+    fn row_color(cx: &Cx, row: usize, selected: bool) -> Vec4 {
+        if selected {
+            live_vec4!(cx, self::color_bg_marked)
+        } else if row & 1 == 0 {
+            live_vec4!(cx, self::color_bg_selected)
+        } else {
+            live_vec4!(cx, self::color_bg_odd)
+        }
+    }
+    
+    // returns if the sort arrow got drawn
+    fn draw_header_cell(&mut self, cx: &mut Cx, layout: Layout, column: usize, rect: Rect) -> bool {
+        self.header_bg.draw_quad_abs(cx, rect);
+        let mut has_arrow = false;
+        let name = self.columns[column].name.clone();
+        let mut text_rect = rect;
+        if let Some((sort_column, order)) = self.sort {
+            if sort_column == column {
+                // the arrow sits at the right edge, the name gets what is left
+                let size = rect.size.y.min(rect.size.x);
+                self.sort_arrow.descending = if order == SortOrder::Descending {1.0} else {0.0};
+                self.sort_arrow.draw_quad_abs(cx, Rect {
+                    pos: vec2(rect.pos.x + rect.size.x - size, rect.pos.y),
+                    size: vec2(size, size)
+                });
+                text_rect.size.x -= size;
+                has_arrow = true;
+            }
+        }
+        Self::draw_cell_text(cx, &mut self.header_text, layout, text_rect, &name);
+        has_arrow
+    }
+    
+    pub fn draw_data_grid(&mut self, cx: &mut Cx, source: &dyn GridDataSource) {
+        if self.view.begin_view(cx, Layout::default()).is_err() {return}
+        
+        let layout = live_layout!(cx, self::layout_cell);
+        let row_height = layout.walk.height.fixed();
+        let header_height = live_float!(cx, self::header_height);
+        let text_style = live_text_style!(cx, self::text_style_cell);
+        self.cell_text.text_style = text_style;
+        self.frozen_text.text_style = text_style;
+        self.header_text.text_style = text_style;
+        self.cell_text.color = live_vec4!(cx, self::color_cell_text);
+        self.frozen_text.color = live_vec4!(cx, self::color_cell_text);
+        self.header_text.color = live_vec4!(cx, self::color_header_text);
+        self.header_bg.color = live_vec4!(cx, self::color_header_bg);
+        self.sort_arrow.base.color = live_vec4!(cx, self::color_sort_arrow);
+        
+        let row_count = source.row_count();
+        let view_rect = cx.get_turtle_rect();
+        let origin = cx.get_turtle_pos();
+        let scroll = self.view.get_scroll_pos(cx);
+        let total_width = self.total_width();
+        let frozen_width = self.frozen_width();
+        let first_column = if frozen_width > 0. {1} else {0};
+        
+        // the scroll area is the full table, only the cells in view get drawn
+        cx.walk_turtle(Walk::wh(Width::Fix(total_width), Height::Fix(header_height + row_count as f32 * row_height)));
+        
+        cx.begin_access_node(AccessNode::new(AccessRole::Table, self.view.area()).with_name(&self.name));
+        
+        let body_height = (view_rect.size.y - header_height).max(0.);
+        let first_row = (scroll.y / row_height).floor() as usize;
+        let end_fill = ((scroll.y + body_height) / row_height).ceil() as usize + 1;
+        let end_row = end_fill.min(row_count);
+        self._page_rows = (body_height / row_height).floor() as usize;
+        
+        let visible_columns: Vec<(usize, f32)> = {
+            let mut x = 0.;
+            let mut visible = Vec::new();
+            for (index, column) in self.columns.iter().enumerate() {
+                if index >= first_column && x + column.width > scroll.x + frozen_width && x < scroll.x + view_rect.size.x {
+                    visible.push((index, x));
+                }
+                x += column.width;
+            }
+            visible
+        };
+        let right_fill = (scroll.x + view_rect.size.x - total_width).max(0.);
+        
+        // the body scrolls both ways
+        let mut cell = String::new();
+        for row in first_row..end_fill {
+            let y = origin.y + header_height + row as f32 * row_height;
+            for (column, x) in &visible_columns {
+                let selected = row < row_count && self.selection.map_or(false, | s | s.contains(GridCell {row, column: *column}));
+                let rect = Rect {pos: vec2(origin.x + x, y), size: vec2(self.columns[*column].width, row_height)};
+                self.cell_bg.color = Self::row_color(cx, row, selected);
+                self.cell_bg.draw_quad_abs(cx, rect);
+                if row < end_row {
+                    cell.truncate(0);
+                    source.cell_text(row, *column, &mut cell);
+                    Self::draw_cell_text(cx, &mut self.cell_text, layout, rect, &cell);
+                }
+            }
+            if right_fill > 0. {
+                self.cell_bg.color = Self::row_color(cx, row, false);
+                self.cell_bg.draw_quad_abs(cx, Rect {pos: vec2(origin.x + total_width, y), size: vec2(right_fill, row_height)});
+            }
+        }
+        
+        // the frozen column only scrolls vertically
+        if frozen_width > 0. {
+            cx.new_draw_call(self.frozen_bg.shader());
+            cx.new_draw_call(self.frozen_text.shader());
+            for row in first_row..end_fill {
+                let y = origin.y + header_height + row as f32 * row_height;
+                let selected = row < row_count && self.selection.map_or(false, | s | s.contains(GridCell {row, column: 0}));
+                let rect = Rect {pos: vec2(origin.x, y), size: vec2(frozen_width, row_height)};
+                self.frozen_bg.color = if selected {Self::row_color(cx, row, true)} else {live_vec4!(cx, self::color_bg_frozen)};
+                self.frozen_bg.draw_quad_abs(cx, rect);
+                if row < end_row {
+                    cell.truncate(0);
+                    source.cell_text(row, 0, &mut cell);
+                    Self::draw_cell_text(cx, &mut self.frozen_text, layout, rect, &cell);
+                }
+            }
+            self.frozen_bg.area().set_do_scroll(cx, false, true);
+            self.frozen_text.area().set_do_scroll(cx, false, true);
+        }
+        
+        // the header only scrolls horizontally
+        cx.new_draw_call(self.header_bg.shader());
+        cx.new_draw_call(self.sort_arrow.shader());
+        cx.new_draw_call(self.header_text.shader());
+        let mut has_arrow = false;
+        for (column, x) in &visible_columns {
+            let rect = Rect {pos: vec2(origin.x + x, origin.y), size: vec2(self.columns[*column].width, header_height)};
+            has_arrow |= self.draw_header_cell(cx, layout, *column, rect);
+            cx.add_access_node(AccessNode::new(AccessRole::ColumnHeader, self.header_bg.area()).with_name(&self.columns[*column].name));
+        }
+        if right_fill > 0. {
+            self.header_bg.draw_quad_abs(cx, Rect {pos: vec2(origin.x + total_width, origin.y), size: vec2(right_fill, header_height)});
+        }
+        self.header_bg.area().set_do_scroll(cx, true, false);
+        if has_arrow {
+            self.sort_arrow.area().set_do_scroll(cx, true, false);
+        }
+        self.header_text.area().set_do_scroll(cx, true, false);
+        
+        // and the corner above the frozen column stays put
+        if frozen_width > 0. {
+            cx.new_draw_call(self.header_bg.shader());
+            cx.new_draw_call(self.sort_arrow.shader());
+            cx.new_draw_call(self.header_text.shader());
+            let has_arrow = self.draw_header_cell(cx, layout, 0, Rect {pos: origin, size: vec2(frozen_width, header_height)});
+            cx.add_access_node(AccessNode::new(AccessRole::ColumnHeader, self.header_bg.area()).with_name(&self.columns[0].name));
+            self.header_bg.area().set_do_scroll(cx, false, false);
+            if has_arrow {
+                self.sort_arrow.area().set_do_scroll(cx, false, false);
+            }
+            self.header_text.area().set_do_scroll(cx, false, false);
+        }
+        
+        self.shadow.draw_shadow_top_at(cx, Rect {pos: vec2(0., header_height), size: vec2(view_rect.size.x, 0.)});
+        if frozen_width > 0. {
+            self.shadow.draw_shadow_left_at(cx, Rect {pos: vec2(frozen_width, header_height), size: vec2(0., body_height)});
+        }
+        
+        cx.end_access_node();
+        self.view.end_view(cx);
+    }
+}
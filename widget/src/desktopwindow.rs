@@ -28,8 +28,6 @@ pub struct DesktopWindow {
     pub window_menu: WindowMenu,
     pub default_menu: Menu,
     
-    last_menu: Option<Menu>,
-    
     // testing
     pub inner_over_chrome: bool,
 }
@@ -72,7 +70,6 @@ impl DesktopWindow {
             caption_size: Vec2::default(),
             caption: "Makepad".to_string(),
             inner_over_chrome: false,
-            last_menu: None
         }
     }
     
@@ -182,7 +179,7 @@ impl DesktopWindow {
             self.end_desktop_window(cx);
         }
     }
-
+    
     pub fn begin_desktop_window(&mut self, cx: &mut Cx, menu: Option<&Menu>) -> ViewRedraw {
         
         if !self.main_view.view_will_redraw(cx) {
@@ -212,7 +209,6 @@ impl DesktopWindow {
                     PlatformType::Windows | PlatformType::Unknown | PlatformType::Linux {..} => {
                         
                         self.caption_bg.begin_quad(cx, Layout {
-                            align: Align::right_top(),
                             walk: Walk::wh(Width::Fill, Height::Compute),
                             ..Default::default()
                         });
@@ -223,23 +219,17 @@ impl DesktopWindow {
                             // then draw it
                         }
                         
-                        self.min_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMin);
-                        if self.window.is_fullscreen(cx) {
-                            self.max_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMaxToggled);
-                        }
-                        else {
-                            self.max_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMax);
-                        }
-                        self.close_btn.draw_desktop_button(cx, DesktopButtonType::WindowsClose);
+                        // measure the buttons and the caption first so they are drawn in place
+                        let buttons = cx.measure( | cx | self.draw_window_buttons(cx));
+                        let caption = cx.measure( | cx | {self.caption_text.draw_text_walk(cx, &self.caption);});
                         
-                        // change alignment
-                        cx.change_turtle_align_x_cab(0.5); //Align::center());
-                        cx.compute_turtle_height();
-                        cx.change_turtle_align_y_cab(0.5); //Align::center());
+                        cx.move_turtle_to_align(buttons, Align::right_top());
+                        self.draw_window_buttons(cx);
                         cx.reset_turtle_pos();
                         cx.move_turtle(50., 0.);
                         // we need to store our caption rect somewhere.
-                        self.caption_size = Vec2 {x: cx.get_width_left(), y: cx.get_height_left()};
+                        self.caption_size = Vec2 {x: cx.get_width_left() - buttons.x, y: buttons.y};
+                        cx.move_turtle(0.5 * (self.caption_size.x - caption.x).max(0.), 0.5 * (buttons.y - caption.y).max(0.));
                         self.caption_text.draw_text_walk(cx, &self.caption);
                         self.caption_bg.end_quad(cx);
                         cx.turtle_new_line();
@@ -288,6 +278,17 @@ impl DesktopWindow {
         Ok(())
     }
     
+    fn draw_window_buttons(&mut self, cx: &mut Cx) {
+        self.min_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMin);
+        if self.window.is_fullscreen(cx) {
+            self.max_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMaxToggled);
+        }
+        else {
+            self.max_btn.draw_desktop_button(cx, DesktopButtonType::WindowsMax);
+        }
+        self.close_btn.draw_desktop_button(cx, DesktopButtonType::WindowsClose);
+    }
+    
     pub fn end_desktop_window(&mut self, cx: &mut Cx) {
         self.inner_view.end_view(cx);
         // lets draw a VR button top right over the UI.
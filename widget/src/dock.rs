@@ -4,6 +4,7 @@ use makepad_render::*;
 use makepad_microserde::*;
 use crate::splitter::*;
 use crate::tabcontrol::*;
+use crate::desktopwindow::*;
 
 #[derive(Clone)]
 pub struct Dock<TItem>
@@ -16,14 +17,28 @@ where TItem: Clone
     pub drop_size: Vec2,
     pub drop_quad: DrawColor,
     pub drop_quad_view: View,
+    pub float_tabs: bool,
     //pub drop_quad_color: ColorId,
     pub _drag_move: Option<FingerMoveEvent>,
     pub _drag_end: Option<DockDragEnd<TItem >>,
     pub _close_tab: Option<DockTabIdent>,
     pub _tab_select: Option<(usize, usize)>,
+    pub _floated: Option<DockFloat<TItem >>,
+    pub _tweens: Vec<DockTween>,
+    pub _tween_start: Option<f64>,
+    pub _next_frame: NextFrame,
     //pub _tweening_quad: Option<(usize, Rect, f32)>
 }
 
+// a splitter sliding from one position to another while the layout switches,
+// found by its path from the root, false going into first and true into last
+#[derive(Clone)]
+pub struct DockTween {
+    pub path: Vec<bool>,
+    pub from: f32,
+    pub to: f32
+}
+
 #[derive(Clone, Debug)]
 pub struct DockTabIdent {
     tab_control_id: usize,
@@ -64,6 +79,120 @@ where TItem: Clone
     }
 }
 
+// saves items that aren't SerRon themselves, or that are saved differently than they are kept
+pub trait DockItemSerializer<TItem> {
+    fn serialize_item(&self, item: &TItem) -> String;
+    fn deserialize_item(&self, data: &str) -> Option<TItem>;
+}
+
+// a dock tree with a name, kept in DockLayouts to switch to with Dock::switch_to_layout
+#[derive(Clone, SerRon, DeRon)]
+pub struct DockLayout<TItem>
+where TItem: Clone
+{
+    pub name: String,
+    pub items: DockItem<TItem>
+}
+
+// the named layouts a dock switches between. the tree in use is the dock items themselves,
+// it goes back into its layout when switching away so changes made to it are kept
+#[derive(Clone, SerRon, DeRon)]
+pub struct DockLayouts<TItem>
+where TItem: Clone
+{
+    pub current: Option<String>,
+    pub layouts: Vec<DockLayout<TItem>>
+}
+
+impl<TItem> DockLayouts<TItem>
+where TItem: Clone
+{
+    pub fn new(layouts: Vec<DockLayout<TItem>>) -> Self {
+        Self {current: None, layouts}
+    }
+    
+    pub fn with_current(self, current: &str) -> Self {Self {current: Some(current.to_string()), ..self}}
+    
+    // replaces the layout of the same name, if there is one
+    pub fn add_layout(&mut self, name: &str, items: DockItem<TItem>) {
+        if let Some(layout) = self.layouts.iter_mut().find( | layout | layout.name == name) {
+            layout.items = items;
+        }
+        else {
+            self.layouts.push(DockLayout {name: name.to_string(), items});
+        }
+    }
+    
+    pub fn layout(&self, name: &str) -> Option<&DockLayout<TItem>> {
+        self.layouts.iter().find( | layout | layout.name == name)
+    }
+    
+    pub fn names(&self) -> Vec<String> {
+        self.layouts.iter().map( | layout | layout.name.clone()).collect()
+    }
+}
+
+// tabs dragged out of a dock live on in a window of their own, position and size are in screen space
+#[derive(Clone, SerRon, DeRon)]
+pub struct DockFloat<TItem>
+where TItem: Clone
+{
+    pub position: Vec2,
+    pub size: Vec2,
+    pub items: DockItem<TItem>
+}
+
+impl<TItem> DockItem<TItem>
+where TItem: Clone
+{
+    // the same tree with every item converted, tabs whose item doesn't convert are left out
+    pub fn map_items<T, F>(&self, f: &mut F) -> DockItem<T>
+    where T: Clone, F: FnMut(&TItem) -> Option<T>
+    {
+        match self {
+            DockItem::TabControl {current, previous, tabs} => {
+                let tabs: Vec<DockTab<T >> = tabs.iter().filter_map( | tab | f(&tab.item).map( | item | DockTab {
+                    closeable: tab.closeable,
+                    title: tab.title.clone(),
+                    item: item
+                })).collect();
+                let last = tabs.len().max(1) - 1;
+                DockItem::TabControl {current: (*current).min(last), previous: (*previous).min(last), tabs: tabs}
+            },
+            DockItem::Splitter {pos, align, axis, first, last} => DockItem::Splitter {
+                pos: *pos,
+                align: align.clone(),
+                axis: axis.clone(),
+                first: Box::new(first.map_items(f)),
+                last: Box::new(last.map_items(f))
+            }
+        }
+    }
+    
+    pub fn serialize_dock_ron(&self, serializer: &dyn DockItemSerializer<TItem>) -> String {
+        self.map_items(&mut | item | Some(serializer.serialize_item(item))).serialize_ron()
+    }
+    
+    pub fn deserialize_dock_ron(ron: &str, serializer: &dyn DockItemSerializer<TItem>) -> Result<Self, DeRonErr> {
+        let dock_items: DockItem<String> = DeRon::deserialize_ron(ron) ?;
+        let mut dock_items = dock_items.map_items(&mut | data | serializer.deserialize_item(data));
+        Dock::recur_collapse_empty(&mut dock_items);
+        Ok(dock_items)
+    }
+    
+    // all tabs in walk order
+    pub fn tabs(&self) -> Vec<DockTab<TItem >> {
+        match self {
+            DockItem::TabControl {tabs, ..} => tabs.clone(),
+            DockItem::Splitter {first, last, ..} => {
+                let mut tabs = first.tabs();
+                tabs.extend(last.tabs());
+                tabs
+            }
+        }
+    }
+}
+
 
 struct DockWalkStack<'a, TItem>
 where TItem: Clone
@@ -77,6 +206,7 @@ pub enum DockEvent {
     None,
     DockTabClosed,
     DockTabCloned {tab_control_id: usize, tab_id: usize},
+    DockTabFloated,
     DockChanged
 }
 
@@ -411,14 +541,28 @@ where TItem: Clone
             splitters: Elements::new(Splitter::new(cx)),
             tab_controls: Elements::new(TabControl::new(cx)),
             drop_quad_view: View::proto_overlay(cx),
+            float_tabs: false,
             _close_tab: None,
             _drag_move: None,
             _drag_end: None,
             _tab_select: None,
+            _floated: None,
+            _tweens: Vec::new(),
+            _tween_start: None,
+            _next_frame: NextFrame::default(),
             //_tweening_quad: None
         }
     }
     
+    // tabs dropped outside the dock float off, the app then hosts them in a DockFloatWindow
+    pub fn with_float_tabs(self, float_tabs: bool) -> Self {Self {float_tabs, ..self}}
+    
+    pub fn style(cx: &mut Cx) {
+        live_body!(cx, {
+            self::transition_duration: 0.3;
+        })
+    }
+    
     pub fn close_tab(&self, cx:&mut Cx, dock_tab_ident: DockTabIdent){
         // lets trigger the tabcontrol tab
         if let Some(tab_ctrl) = self.tab_controls.get(dock_tab_ident.tab_control_id){
@@ -469,6 +613,29 @@ where TItem: Clone
         None
     }
     
+    fn recur_find_tab<'b>(dock_walk: &'b DockItem<TItem>, control_id: usize, tab_id: usize, counter: &mut usize) -> Option<&'b DockTab<TItem >>
+    where TItem: Clone
+    {
+        match dock_walk {
+            DockItem::TabControl {tabs, ..} => {
+                let id = *counter;
+                *counter += 1;
+                if id == control_id {
+                    return tabs.get(tab_id)
+                }
+            },
+            DockItem::Splitter {first, last, ..} => {
+                *counter += 1;
+                let left = Self::recur_find_tab(first, control_id, tab_id, counter);
+                if !left.is_none() {
+                    return left
+                }
+                return Self::recur_find_tab(last, control_id, tab_id, counter)
+            }
+        }
+        None
+    }
+    
     fn recur_collapse_empty(dock_walk: &mut DockItem<TItem>) -> bool
     where TItem: Clone
     {
@@ -577,6 +744,118 @@ where TItem: Clone
         None
     }
     
+    // splitters with their path from the root, alignment and whether they are vertical
+    fn recur_splitters(dock_walk: &DockItem<TItem>, path: &mut Vec<bool>, out: &mut Vec<(Vec<bool>, f32, SplitterAlign, bool)>)
+    where TItem: Clone
+    {
+        if let DockItem::Splitter {pos, align, axis, first, last} = dock_walk {
+            let is_vertical = if let Axis::Vertical = axis {true} else {false};
+            out.push((path.clone(), *pos, align.clone(), is_vertical));
+            path.push(false);
+            Self::recur_splitters(first, path, out);
+            path.pop();
+            path.push(true);
+            Self::recur_splitters(last, path, out);
+            path.pop();
+        }
+    }
+    
+    fn splitter_pos_at<'b>(dock_walk: &'b mut DockItem<TItem>, path: &[bool]) -> Option<&'b mut f32>
+    where TItem: Clone
+    {
+        match dock_walk {
+            DockItem::Splitter {pos, first, last, ..} => match path.split_first() {
+                None => Some(pos),
+                Some((false, rest)) => Self::splitter_pos_at(first, rest),
+                Some((true, rest)) => Self::splitter_pos_at(last, rest),
+            },
+            DockItem::TabControl {..} => None
+        }
+    }
+    
+    // replaces the dock tree with a named layout, splitters both layouts share slide to their new position
+    pub fn switch_layout(&mut self, cx: &mut Cx, dock_items: &mut DockItem<TItem>, layout: &DockItem<TItem>) {
+        let mut old = Vec::new();
+        Self::recur_splitters(dock_items, &mut Vec::new(), &mut old);
+        *dock_items = layout.clone();
+        let mut new = Vec::new();
+        Self::recur_splitters(dock_items, &mut Vec::new(), &mut new);
+        self._tweens.truncate(0);
+        for (path, to, align, is_vertical) in new {
+            if let Some((_, from, _, _)) = old.iter().find( | (p, _, a, v) | *p == path && *a == align && *v == is_vertical) {
+                if *from != to {
+                    self._tweens.push(DockTween {path, from: *from, to});
+                }
+            }
+        }
+        for tween in &self._tweens {
+            if let Some(pos) = Self::splitter_pos_at(dock_items, &tween.path) {
+                *pos = tween.from;
+            }
+        }
+        if self._tweens.len() > 0 {
+            self._tween_start = None;
+            self._next_frame = cx.new_next_frame();
+        }
+        cx.redraw_child_area(Area::All);
+    }
+    
+    // switches to the layout of that name, after saving the tree in use into the current one.
+    // returns false if there is no such layout
+    pub fn switch_to_layout(&mut self, cx: &mut Cx, dock_items: &mut DockItem<TItem>, layouts: &mut DockLayouts<TItem>, name: &str) -> bool {
+        let items = if let Some(layout) = layouts.layout(name) {layout.items.clone()} else {return false};
+        if layouts.current.as_deref() == Some(name) {
+            return true
+        }
+        if let Some(current) = layouts.current.clone() {
+            // splitters still sliding are saved where they were going
+            let mut saved = dock_items.clone();
+            for tween in &self._tweens {
+                if let Some(pos) = Self::splitter_pos_at(&mut saved, &tween.path) {
+                    *pos = tween.to;
+                }
+            }
+            layouts.add_layout(&current, saved);
+        }
+        self.switch_layout(cx, dock_items, &items);
+        layouts.current = Some(name.to_string());
+        true
+    }
+    
+    // the dock item a DockTabFloated event moved into a window of its own
+    pub fn take_floated(&mut self) -> Option<DockFloat<TItem >> {
+        self._floated.take()
+    }
+    
+    // puts the tabs of a floating window back, next to those in the first tab control
+    pub fn dock_float(&mut self, cx: &mut Cx, dock_items: &mut DockItem<TItem>, float: &DockFloat<TItem>) {
+        let tabs = float.items.tabs();
+        if tabs.len() == 0 {
+            return
+        }
+        let mut counter = 0;
+        let mut control_id = 0;
+        Self::recur_first_tab_control(dock_items, &mut counter, &mut control_id);
+        Self::recur_split_dock(dock_items, &tabs, control_id, &DockDropKind::Center, &mut 0);
+        cx.redraw_child_area(Area::All);
+    }
+    
+    fn recur_first_tab_control(dock_walk: &DockItem<TItem>, counter: &mut usize, control_id: &mut usize) -> bool
+    where TItem: Clone
+    {
+        let id = *counter;
+        *counter += 1;
+        match dock_walk {
+            DockItem::TabControl {..} => {
+                *control_id = id;
+                true
+            },
+            DockItem::Splitter {first, last, ..} => {
+                Self::recur_first_tab_control(first, counter, control_id) || Self::recur_first_tab_control(last, counter, control_id)
+            }
+        }
+    }
+    
     fn get_drop_kind(pos: Vec2, drop_size: Vec2, tvr: Rect, cdr: Rect, tab_rects: Vec<Rect>) -> (DockDropKind, Rect) {
         // this is how the drop areas look
         //    |            Tab                |
@@ -650,7 +929,26 @@ where TItem: Clone
         });
     }
     
-    pub fn handle_dock(&mut self, cx: &mut Cx, _event: &mut Event, dock_items: &mut DockItem<TItem>) -> DockEvent {
+    pub fn handle_dock(&mut self, cx: &mut Cx, event: &mut Event, dock_items: &mut DockItem<TItem>) -> DockEvent {
+        if let Some(ne) = event.is_next_frame(cx, self._next_frame) {
+            let start = *self._tween_start.get_or_insert(ne.time);
+            let duration = live_float!(cx, self::transition_duration) as f64;
+            let t = if duration > 0. {((ne.time - start) / duration).min(1.)} else {1.};
+            let eased = Ease::InOutQuad.map(t) as f32;
+            for tween in &self._tweens {
+                if let Some(pos) = Self::splitter_pos_at(dock_items, &tween.path) {
+                    *pos = tween.from + (tween.to - tween.from) * eased;
+                }
+            }
+            if t < 1. {
+                self._next_frame = cx.new_next_frame();
+            }
+            else {
+                self._tweens.truncate(0);
+                self._tween_start = None;
+            }
+            cx.redraw_child_area(Area::All);
+        }
         if let Some(close_tab) = &self._close_tab {
             Self::recur_remove_tab(dock_items, close_tab.tab_control_id, close_tab.tab_id, &mut 0, false, false);
             Self::recur_collapse_empty(dock_items);
@@ -661,12 +959,14 @@ where TItem: Clone
         if let Some(drag_end) = self._drag_end.clone() {
             self._drag_end = None;
             let mut tab_clone_ident = None;
+            let mut dropped = false;
             let fe = match &drag_end {DockDragEnd::OldTab {fe, ..} => fe, DockDragEnd::NewItems {fe, ..} => fe};
             for (target_id, tab_control) in self.tab_controls.enumerate() {
                 
                 let cdr = tab_control.get_content_drop_rect(cx);
                 let tvr = tab_control.get_tabs_view_rect(cx);
                 if tvr.contains(fe.abs) || cdr.contains(fe.abs) { // we might got dropped elsewhere
+                    dropped = true;
                     // ok now, we ask the tab_controls rect
                     let tab_rects = tab_control.get_tab_rects(cx);
                     let (kind, _rect) = Self::get_drop_kind(fe.abs, self.drop_size, tvr, cdr, tab_rects);
@@ -711,6 +1011,25 @@ where TItem: Clone
                     };
                 }
             }
+            // an existing tab let go outside every drop zone floats off into a window of its own
+            if let DockDragEnd::OldTab {fe, ident} = &drag_end {
+                let closeable = Self::recur_find_tab(dock_items, ident.tab_control_id, ident.tab_id, &mut 0).map_or(false, | tab | tab.closeable);
+                if self.float_tabs && !dropped && closeable {
+                    let size = if let Some(tab_control) = self.tab_controls.get_mut(ident.tab_control_id) {
+                        tab_control.get_content_drop_rect(cx).size
+                    } else {Vec2::default()};
+                    if let Some(tab) = Self::recur_remove_tab(dock_items, ident.tab_control_id, ident.tab_id, &mut 0, false, true) {
+                        self._floated = Some(DockFloat {
+                            position: cx.windows[fe.window_id].window_geom.position + fe.abs,
+                            size: size,
+                            items: DockItem::TabControl {current: 0, previous: 0, tabs: vec![tab]}
+                        });
+                        Self::recur_collapse_empty(dock_items);
+                        cx.redraw_child_area(Area::All);
+                        return DockEvent::DockTabFloated
+                    }
+                }
+            }
             Self::recur_collapse_empty(dock_items);
             cx.redraw_child_area(Area::All);
             //Self::recur_debug_dock(self.dock_items.as_mut().unwrap(), &mut 0, 0);
@@ -798,3 +1117,48 @@ Self::recur_debug_dock(last, counter, depth + 1);
 }
 }
 }*/
+
+// a window holding tabs dragged out of a dock. It has a Dock of its own, walked like the main one
+#[derive(Clone)]
+pub struct DockFloatWindow<TItem>
+where TItem: Clone
+{
+    pub desktop_window: DesktopWindow,
+    pub dock: Dock<TItem>,
+}
+
+impl<TItem> DockFloatWindow<TItem>
+where TItem: Clone
+{
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            desktop_window: DesktopWindow::new(cx),
+            dock: Dock::new(cx),
+        }
+    }
+    
+    // keeps the float's position and size current, so it reopens where it was
+    pub fn handle_dock_float(&mut self, cx: &mut Cx, event: &mut Event, float: &mut DockFloat<TItem>) -> DesktopWindowEvent {
+        let window_event = self.desktop_window.handle_desktop_window(cx, event);
+        if let DesktopWindowEvent::WindowGeomChange(ge) = &window_event {
+            float.position = ge.new_geom.position;
+            float.size = ge.new_geom.inner_size;
+        }
+        window_event
+    }
+    
+    pub fn begin_dock_float(&mut self, cx: &mut Cx, float: &DockFloat<TItem>) -> ViewRedraw {
+        if self.desktop_window.window.window_id.is_none() {
+            self.desktop_window.window.create_position = Some(float.position);
+            self.desktop_window.window.create_inner_size = Some(float.size);
+            if let Some(tab) = float.items.tabs().first() {
+                self.desktop_window.caption = tab.title.clone();
+            }
+        }
+        self.desktop_window.begin_desktop_window(cx, None)
+    }
+    
+    pub fn end_dock_float(&mut self, cx: &mut Cx) {
+        self.desktop_window.end_desktop_window(cx);
+    }
+}
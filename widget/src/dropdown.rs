@@ -0,0 +1,344 @@
+use makepad_render::*;
+use crate::buttonlogic::*;
+use crate::popup::*;
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+pub struct DrawDropDown {
+    #[default_shader(self::shader_bg)]
+    base: DrawQuad,
+    hover: f32,
+    down: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DropDownEvent {
+    None,
+    Select(usize)
+}
+
+// a button that opens a list of items below it. An editable one is a combobox,
+// typing while the list is open narrows it down
+#[derive(Clone)]
+pub struct DropDown {
+    pub button: ButtonLogic,
+    pub bg: DrawDropDown,
+    pub text: DrawText,
+    pub animator: Animator,
+    pub popup: Popup,
+    pub list_bg: DrawColor,
+    pub item_bg: DrawColor,
+    pub item_text: DrawText,
+    pub items: Vec<String>,
+    pub selected: usize,
+    pub editable: bool,
+    pub filter: String,
+    pub _hover: Option<usize>,
+    pub _item_areas: Vec<(usize, Area)>,
+}
+
+impl DropDown {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            button: ButtonLogic::default(),
+            bg: DrawDropDown::new(cx, live_shader!(cx, self::shader_bg)),
+            text: DrawText::new(cx, default_shader!()),
+            animator: Animator::default(),
+            popup: Popup::new(),
+            list_bg: DrawColor::new(cx, live_shader!(cx, self::shader_list_bg)),
+            item_bg: DrawColor::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            item_text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.2),
+            items: Vec::new(),
+            selected: 0,
+            editable: false,
+            filter: String::new(),
+            _hover: None,
+            _item_areas: Vec::new(),
+        }
+    }
+    
+    pub fn with_items(self, items: Vec<String>) -> Self {Self {items, ..self}}
+    pub fn with_editable(self, editable: bool) -> Self {Self {editable, ..self}}
+    
+    pub fn style(cx: &mut Cx) {
+        self::DrawDropDown::register_draw_input(cx);
+        
+        live_body!(cx, {
+            self::color_list_bg: #x2a;
+            self::color_hover: #x11466d;
+            self::color_selected: #3a;
+            self::color_text: #c;
+            self::color_filter: #x5a9bd6;
+            
+            self::layout_bg: Layout {
+                align: {fx: 0.0, fy: 0.5},
+                walk: Walk {
+                    width: Compute,
+                    height: Compute,
+                    margin: all(1.0),
+                },
+                padding: {l: 10.0, t: 6.0, r: 26.0, b: 6.0},
+            }
+            
+            self::layout_list: Layout {
+                padding: {l: 1., t: 3., r: 1., b: 3.},
+                direction: Down,
+                walk: Walk {width: Compute, height: Compute}
+            }
+            
+            self::layout_item: Layout {
+                align: {fx: 0.0, fy: 0.5},
+                padding: {l: 10., t: 0., r: 10., b: 0.},
+                walk: Walk {width: Compute, height: Fix(22.)}
+            }
+            
+            self::text_style_label: TextStyle {
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::anim_default: Anim {
+                play: Cut {duration: 0.1}
+                tracks: [
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawDropDown::hover}
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawDropDown::down}
+                    Vec4 {keys: {1.0: #9}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_over: Anim {
+                play: Cut {duration: 0.1},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawDropDown::hover},
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawDropDown::down},
+                    Vec4 {keys: {1.0: #f}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_down: Anim {
+                play: Cut {duration: 0.2},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawDropDown::down},
+                    Float {keys: {1.0: 1.0}, bind_to: self::DrawDropDown::hover},
+                    Vec4 {keys: {0.0: #c}, bind_to: makepad_render::drawtext::DrawText::color},
+                ]
+            }
+            
+            self::shader_bg: Shader {
+                use makepad_render::drawquad::shader::*;
+                
+                draw_input: self::DrawDropDown;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 2.5);
+                    df.fill(mix(mix(#3, #4, hover), #2a, down));
+                    let c = vec2(rect_size.x - 13., rect_size.y * 0.5);
+                    let sz = 3.0;
+                    df.move_to(c.x - sz, c.y - 0.5 * sz);
+                    df.line_to(c.x + sz, c.y - 0.5 * sz);
+                    df.line_to(c.x, c.y + sz);
+                    df.close_path();
+                    return df.fill(mix(#9, #c, hover));
+                }
+            }
+            
+            self::shader_list_bg: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0., 0., rect_size.x, rect_size.y, 3.);
+                    df.fill(#4a);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 2.);
+                    return df.fill(color);
+                }
+            }
+        });
+    }
+    
+    // the items the filter leaves, all of them when it is empty
+    pub fn visible_items(&self) -> Vec<usize> {
+        let filter = self.filter.to_lowercase();
+        (0..self.items.len()).filter( | index | self.items[*index].to_lowercase().contains(&filter)).collect()
+    }
+    
+    pub fn set_selected(&mut self, cx: &mut Cx, selected: usize) {
+        self.selected = selected;
+        cx.redraw_child_area(self.bg.area());
+    }
+    
+    pub fn open(&mut self, cx: &mut Cx) {
+        self.filter.truncate(0);
+        self._hover = Some(self.selected);
+        self.popup.open(cx);
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.popup.close(cx);
+        self.filter.truncate(0);
+        self.animator.play_anim(cx, live_anim!(cx, self::anim_default));
+    }
+    
+    fn select(&mut self, cx: &mut Cx, index: usize) -> DropDownEvent {
+        self.close(cx);
+        self.set_selected(cx, index);
+        DropDownEvent::Select(index)
+    }
+    
+    fn set_filter(&mut self, cx: &mut Cx, filter: String) {
+        self.filter = filter;
+        self._hover = self.visible_items().first().cloned();
+        self.popup.view.redraw_view(cx);
+    }
+    
+    pub fn handle_drop_down(&mut self, cx: &mut Cx, event: &mut Event) -> DropDownEvent {
+        if let Some(ae) = event.is_animate(cx, &self.animator) {
+            self.bg.animate(cx, &mut self.animator, ae.time);
+            self.text.animate(cx, &mut self.animator, ae.time);
+        }
+        
+        if let PopupEvent::Dismiss = self.popup.handle_popup(cx, event) {
+            self.filter.truncate(0);
+            self.animator.play_anim(cx, live_anim!(cx, self::anim_default));
+            return DropDownEvent::None
+        }
+        
+        if self.popup.is_open {
+            for (index, area) in self._item_areas.clone() {
+                match event.hits(cx, area, HitOpt::default()) {
+                    Event::FingerHover(fe) => if fe.hover_state != HoverState::Out && self._hover != Some(index) {
+                        self._hover = Some(index);
+                        self.popup.view.redraw_view(cx);
+                    },
+                    Event::FingerUp(fe) => if fe.is_over {
+                        return self.select(cx, index)
+                    },
+                    _ => ()
+                }
+            }
+            if let Event::KeyDown(ke) = event.hits(cx, self.popup.view.area(), HitOpt::default()) {
+                let visible = self.visible_items();
+                let pos = self._hover.and_then( | hover | visible.iter().position( | index | *index == hover));
+                match cx.keymap_command(KEYMAP_POPUP, &ke).as_deref() {
+                    Some("popup.next") => if visible.len() > 0 {
+                        self._hover = Some(visible[pos.map_or(0, | pos | (pos + 1).min(visible.len() - 1))]);
+                        self.popup.view.redraw_view(cx);
+                    },
+                    Some("popup.prev") => if visible.len() > 0 {
+                        self._hover = Some(visible[pos.map_or(0, | pos | pos.max(1) - 1)]);
+                        self.popup.view.redraw_view(cx);
+                    },
+                    Some("popup.activate") => if let Some(pos) = pos {
+                        return self.select(cx, visible[pos])
+                    },
+                    Some("popup.erase") => if self.editable && self.filter.len() > 0 {
+                        let mut filter = self.filter.clone();
+                        filter.pop();
+                        self.set_filter(cx, filter);
+                    },
+                    _ => ()
+                }
+            }
+            if self.editable {
+                if let Event::TextInput(te) = event.hits(cx, self.popup.view.area(), HitOpt::default()) {
+                    let input: String = te.input.chars().filter( | c | !c.is_control()).collect();
+                    if input.len() > 0 {
+                        let filter = format!("{}{}", self.filter, input);
+                        self.set_filter(cx, filter);
+                    }
+                }
+            }
+        }
+        
+        let animator = &mut self.animator;
+        let button = self.button.handle_button_logic(cx, event, self.bg.area(), | cx, logic_event, _ | match logic_event {
+            ButtonLogicEvent::Down => animator.play_anim(cx, live_anim!(cx, self::anim_down)),
+            ButtonLogicEvent::Default => animator.play_anim(cx, live_anim!(cx, self::anim_default)),
+            ButtonLogicEvent::Over => animator.play_anim(cx, live_anim!(cx, self::anim_over))
+        });
+        if let ButtonEvent::Down = button {
+            if !self.popup.is_open {
+                self.open(cx);
+            }
+        }
+        DropDownEvent::None
+    }
+    
+    // measured with computed widths, then drawn at least as wide as the button
+    fn draw_list(&mut self, cx: &mut Cx, width: Option<f32>) -> Area {
+        let mut layout_list = live_layout!(cx, self::layout_list);
+        let mut layout_item = live_layout!(cx, self::layout_item);
+        if let Some(width) = width {
+            layout_list.walk.width = Width::Fix(width);
+            layout_item.walk.width = Width::Fill;
+        }
+        self.list_bg.color = live_vec4!(cx, self::color_list_bg);
+        self.list_bg.begin_quad(cx, layout_list);
+        self.item_text.text_style = live_text_style!(cx, self::text_style_label);
+        
+        if self.editable && self.filter.len() > 0 {
+            self.item_bg.color = live_vec4!(cx, self::color_list_bg);
+            self.item_bg.begin_quad(cx, layout_item);
+            self.item_text.color = live_vec4!(cx, self::color_filter);
+            self.item_text.draw_text_walk(cx, &self.filter);
+            self.item_bg.end_quad(cx);
+            cx.turtle_new_line();
+        }
+        
+        let mut areas = Vec::new();
+        for index in self.visible_items() {
+            self.item_bg.color = if self._hover == Some(index) {
+                live_vec4!(cx, self::color_hover)
+            } else if self.selected == index {
+                live_vec4!(cx, self::color_selected)
+            } else {
+                live_vec4!(cx, self::color_list_bg)
+            };
+            self.item_bg.begin_quad(cx, layout_item);
+            self.item_text.color = live_vec4!(cx, self::color_text);
+            self.item_text.draw_text_walk(cx, &self.items[index]);
+            self.item_bg.end_quad(cx);
+            if !cx.is_measuring() {
+                cx.add_access_node(AccessNode::new(AccessRole::MenuItem, self.item_bg.area())
+                    .with_name(&self.items[index])
+                    .with_selected(self.selected == index));
+            }
+            areas.push((index, self.item_bg.area()));
+            cx.turtle_new_line();
+        }
+        self.list_bg.end_quad(cx);
+        self._item_areas = areas;
+        self.list_bg.area()
+    }
+    
+    pub fn draw_drop_down(&mut self, cx: &mut Cx) {
+        if self.animator.need_init(cx) {
+            self.animator.init(cx, live_anim!(cx, self::anim_default));
+            self.bg.last_animate(&self.animator);
+            self.text.last_animate(&self.animator);
+        }
+        
+        let label = self.items.get(self.selected).cloned().unwrap_or(String::new());
+        self.bg.begin_quad(cx, live_layout!(cx, self::layout_bg));
+        self.text.text_style = live_text_style!(cx, self::text_style_label);
+        self.text.draw_text_walk(cx, &label);
+        self.bg.end_quad(cx);
+        let anchor = self.bg.area().get_rect(cx);
+        cx.add_access_node(AccessNode::new(AccessRole::ComboBox, self.bg.area())
+            .with_value(&label)
+            .with_expanded(self.popup.is_open));
+        
+        if self.popup.begin_popup(cx, Layout::default()).is_err() {
+            return
+        }
+        let size = cx.measure( | cx | {self.draw_list(cx, None);});
+        let width = size.x.max(anchor.size.x);
+        self.popup.begin_panel(cx, anchor, PopupPlacement::Below, vec2(width, size.y));
+        let area = self.draw_list(cx, Some(width));
+        self.popup.end_panel(cx);
+        self.popup.add_panel(area);
+        self.popup.end_popup(cx);
+    }
+}
@@ -208,6 +208,7 @@ impl FloatSlider {
             width: Width::FillPad(pad),
             height: Height::Fix(35.0 * height_scale)
         });
+        cx.add_access_node(AccessNode::new(AccessRole::Slider, self.slider.area()).with_value(&format!("{}", self.scaled_value)));
     }
     
 }
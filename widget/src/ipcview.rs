@@ -0,0 +1,278 @@
+// shows an app running in a child process, and forwards our input to it.
+// the child's draw lists are drawn with our own shaders into a pass of their own
+use makepad_render::*;
+use std::collections::HashMap;
+use std::io;
+use std::process::Command;
+use std::time::Duration;
+
+pub enum IpcViewEvent {
+    None,
+    Frame,
+    Exited,
+}
+
+pub struct IpcView {
+    pub view: View,
+    pub image: DrawImage,
+    pub pass: Pass,
+    pub color_texture: Texture,
+    // one per draw list of the child, in the order they come in a frame
+    pub child_views: Vec<View>,
+    // the child's texture ids to ours
+    pub child_textures: HashMap<u32, Texture>,
+    pub frame: Option<IpcFrame>,
+    pub process: Option<IpcProcess>,
+    pub signal: Signal,
+    pub measured_size: Vec2,
+    pub sent_size: Vec2,
+    pub sent_dpi_factor: f32,
+}
+
+impl IpcView {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            view: View::new(),
+            image: DrawImage::new(cx, default_shader!()),
+            pass: Pass::default(),
+            color_texture: Texture::new(cx),
+            child_views: Vec::new(),
+            child_textures: HashMap::new(),
+            frame: None,
+            process: None,
+            signal: cx.new_signal(),
+            measured_size: Vec2::default(),
+            sent_size: Vec2::default(),
+            sent_dpi_factor: 0.0,
+        }
+    }
+    
+    // starts the child, the command should run an app built with the ipc feature
+    pub fn spawn(&mut self, cx: &mut Cx, command: Command) -> io::Result<()> {
+        self.close();
+        self.process = Some(IpcProcess::spawn(command, self.signal) ?);
+        self.sent_size = Vec2::default();
+        self.view.redraw_view(cx);
+        Ok(())
+    }
+    
+    pub fn close(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            let _ = process.close(Duration::from_secs(1));
+        }
+        self.frame = None;
+    }
+    
+    pub fn is_running(&self) -> bool {
+        self.process.is_some()
+    }
+    
+    fn send(&mut self, msg: IpcParentMsg) {
+        if let Some(process) = &mut self.process {
+            let _ = process.send(&msg);
+        }
+    }
+    
+    // a message from the child, handle_ipc_view feeds these in as they arrive
+    pub fn handle_child_msg(&mut self, cx: &mut Cx, msg: IpcChildMsg) -> IpcViewEvent {
+        match msg {
+            IpcChildMsg::Texture {texture_id, width, height, pixels} => {
+                let texture = *self.child_textures.entry(texture_id).or_insert_with( || Texture::new(cx));
+                let cxtexture = &mut cx.textures[texture.texture_id as usize];
+                cxtexture.desc.width = Some(width);
+                cxtexture.desc.height = Some(height);
+                cxtexture.image_u32 = pixels;
+                cxtexture.update_image = true;
+            },
+            IpcChildMsg::Frame(frame) => {
+                self.frame = Some(frame);
+                self.view.redraw_view(cx);
+                return IpcViewEvent::Frame
+            },
+            IpcChildMsg::SetHoverCursor(cursor) => {
+                cx.set_hover_mouse_cursor(cursor);
+            },
+            IpcChildMsg::SetClipboard(data) => {
+                cx.set_clipboard(data);
+            },
+            IpcChildMsg::Exit => {
+                self.close();
+                self.view.redraw_view(cx);
+                return IpcViewEvent::Exited
+            }
+        }
+        IpcViewEvent::None
+    }
+    
+    pub fn handle_ipc_view(&mut self, cx: &mut Cx, event: &mut Event) -> IpcViewEvent {
+        let mut view_event = IpcViewEvent::None;
+        if let Event::Signal(se) = event {
+            if se.signals.contains_key(&self.signal) {
+                while let Some(msg) = self.process.as_ref().and_then( | process | process.try_recv()) {
+                    match self.handle_child_msg(cx, msg) {
+                        IpcViewEvent::None => (),
+                        IpcViewEvent::Frame => view_event = IpcViewEvent::Frame,
+                        IpcViewEvent::Exited => return IpcViewEvent::Exited
+                    }
+                }
+            }
+            return view_event
+        }
+        if self.process.is_none() {
+            return view_event
+        }
+        
+        match event.hits(cx, self.view.area(), HitOpt::default()) {
+            Event::FingerDown(fe) => {
+                cx.set_key_focus(self.view.area());
+                self.send(IpcParentMsg::FingerDown {
+                    abs: fe.rel,
+                    digit: fe.digit,
+                    tap_count: fe.tap_count,
+                    modifiers: fe.modifiers.clone(),
+                    time: fe.time
+                });
+            },
+            Event::FingerMove(fe) => {
+                self.send(IpcParentMsg::FingerMove {
+                    abs: fe.rel,
+                    abs_start: fe.rel_start,
+                    digit: fe.digit,
+                    modifiers: fe.modifiers.clone(),
+                    time: fe.time
+                });
+            },
+            Event::FingerUp(fe) => {
+                self.send(IpcParentMsg::FingerUp {
+                    abs: fe.rel,
+                    abs_start: fe.rel_start,
+                    digit: fe.digit,
+                    modifiers: fe.modifiers.clone(),
+                    time: fe.time
+                });
+            },
+            Event::FingerHover(fe) => {
+                self.send(IpcParentMsg::FingerHover {
+                    abs: fe.rel,
+                    modifiers: fe.modifiers.clone(),
+                    time: fe.time
+                });
+            },
+            Event::FingerScroll(fe) => {
+                self.send(IpcParentMsg::FingerScroll {
+                    abs: fe.rel,
+                    scroll: fe.scroll,
+                    modifiers: fe.modifiers.clone(),
+                    time: fe.time
+                });
+            },
+            Event::KeyDown(ke) => {
+                self.send(IpcParentMsg::KeyDown {
+                    key_code: ke.key_code,
+                    is_repeat: ke.is_repeat,
+                    modifiers: ke.modifiers.clone(),
+                    time: ke.time
+                });
+            },
+            Event::KeyUp(ke) => {
+                self.send(IpcParentMsg::KeyUp {
+                    key_code: ke.key_code,
+                    is_repeat: ke.is_repeat,
+                    modifiers: ke.modifiers.clone(),
+                    time: ke.time
+                });
+            },
+            Event::TextInput(te) => {
+                self.send(IpcParentMsg::TextInput {
+                    input: te.input.clone(),
+                    replace_last: te.replace_last,
+                    was_paste: te.was_paste
+                });
+            },
+            Event::TextIme(te) => {
+                self.send(IpcParentMsg::TextIme(te.clone()));
+            },
+            Event::Paste(pe) => {
+                self.send(IpcParentMsg::Paste(pe.data.clone()));
+            },
+            Event::KeyFocus(_) => {
+                self.send(IpcParentMsg::AppFocus);
+            },
+            Event::KeyFocusLost(_) => {
+                self.send(IpcParentMsg::AppFocusLost);
+            },
+            _ => ()
+        }
+        view_event
+    }
+    
+    pub fn draw_ipc_view(&mut self, cx: &mut Cx) {
+        if self.view.begin_view(cx, Layout::default()).is_err() {
+            return
+        };
+        self.measured_size = vec2(cx.get_width_total(), cx.get_height_total());
+        let dpi_factor = cx.current_dpi_factor;
+        if self.measured_size != self.sent_size || dpi_factor != self.sent_dpi_factor {
+            self.sent_size = self.measured_size;
+            self.sent_dpi_factor = dpi_factor;
+            self.send(IpcParentMsg::Resize {size: self.measured_size, dpi_factor});
+        }
+        if let Some(frame) = &self.frame {
+            self.pass.begin_pass(cx);
+            self.pass.set_size(cx, self.measured_size);
+            self.pass.add_color_texture(cx, self.color_texture, ClearColor::ClearWith(frame.clear_color));
+            let mut next_view = 0;
+            Self::draw_child_list(cx, &mut self.child_views, &self.child_textures, &frame.root, &mut next_view);
+            self.pass.end_pass(cx);
+            
+            self.image.texture = self.color_texture.into();
+            self.image.draw_quad_rel(cx, Rect {pos: vec2(0., 0.), size: self.measured_size});
+        }
+        self.view.end_view(cx);
+    }
+    
+    // rebuilds a view of the child, calls of shaders we don't have are skipped
+    fn draw_child_list(cx: &mut Cx, views: &mut Vec<View>, textures: &HashMap<u32, Texture>, list: &IpcDrawList, next_view: &mut usize) {
+        let index = *next_view;
+        *next_view += 1;
+        if index >= views.len() {
+            views.push(View::new().with_always_redraw(true));
+        }
+        let layout = Layout {
+            abs_origin: Some(list.rect.pos),
+            abs_size: Some(list.rect.size),
+            ..Layout::default()
+        };
+        if views[index].begin_view(cx, layout).is_err() {
+            return
+        }
+        for item in &list.items {
+            match item {
+                IpcDrawItem::DrawList(list) => Self::draw_child_list(cx, views, textures, list, next_view),
+                IpcDrawItem::DrawCall(call) => {
+                    let shader = if let Some(shader) = cx.live_styles.shader_alloc.get(&call.shader) {*shader} else {continue};
+                    if cx.shaders[shader.shader_id].mapping.instance_props.total_slots != call.instance_slots {
+                        continue;
+                    }
+                    let draw_call = cx.new_draw_call(shader);
+                    draw_call.instances.extend_from_slice(&call.instances);
+                    for (slot, value) in draw_call.user_uniforms.iter_mut().zip(call.user_uniforms.iter()) {
+                        *slot = *value;
+                    }
+                    for (slot, texture_id) in draw_call.textures_2d.iter_mut().zip(call.textures.iter()) {
+                        *slot = textures.get(texture_id).map_or(0, | texture | texture.texture_id);
+                    }
+                    draw_call.do_h_scroll = call.do_h_scroll;
+                    draw_call.do_v_scroll = call.do_v_scroll;
+                }
+            }
+        }
+        views[index].end_view(cx);
+        // the child laid it out already
+        let view_id = views[index].view_id.unwrap();
+        cx.views[view_id].rect = list.rect;
+        cx.views[view_id].clipped = list.clipped;
+        cx.set_view_scroll_x(view_id, list.scroll.x);
+        cx.set_view_scroll_y(view_id, list.scroll.y);
+    }
+}
@@ -26,6 +26,30 @@ mod windowmenu;
 pub use crate::windowmenu::*;
 mod listlogic;
 pub use crate::listlogic::*;
+mod treeview;
+pub use crate::treeview::*;
+mod datagrid;
+pub use crate::datagrid::*;
+mod popup;
+pub use crate::popup::*;
+mod contextmenu;
+pub use crate::contextmenu::*;
+mod tooltip;
+pub use crate::tooltip::*;
+mod dropdown;
+pub use crate::dropdown::*;
+mod modal;
+pub use crate::modal::*;
+mod checkbox;
+pub use crate::checkbox::*;
+mod radiogroup;
+pub use crate::radiogroup::*;
+mod floatslider;
+pub use crate::floatslider::*;
+mod spinner;
+pub use crate::spinner::*;
+mod colorpicker;
+pub use crate::colorpicker::*;
 
 mod textbuffer;
 pub use crate::textbuffer::*;
@@ -51,5 +75,10 @@ pub use crate::foldcaption::*;
 mod viewport3d;
 pub use crate::viewport3d::*;
 
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod ipcview;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub use crate::ipcview::*;
+
 //mod texteditor;
 //pub use crate::texteditor::*;
@@ -0,0 +1,117 @@
+use makepad_render::*;
+use crate::popup::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModalEvent {
+    None,
+    Dismiss
+}
+
+// a dialog centered over a dimmed window. While it is open the rest of the ui gets no
+// clicks, hovers, scrolls or keys, Escape closes it
+#[derive(Clone)]
+pub struct Modal {
+    pub popup: Popup,
+    pub dim: DrawColor,
+    pub bg: DrawColor,
+}
+
+impl Modal {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            popup: Popup::new()
+                .with_modal(true)
+                .with_dismiss_outside(false),
+            dim: DrawColor::new(cx, live_shader!(cx, self::shader_dim)),
+            bg: DrawColor::new(cx, live_shader!(cx, self::shader_bg)),
+        }
+    }
+    
+    pub fn with_dismiss_outside(self, dismiss_outside: bool) -> Self {
+        Self {popup: self.popup.with_dismiss_outside(dismiss_outside), ..self}
+    }
+    
+    pub fn style(cx: &mut Cx) {
+        live_body!(cx, {
+            self::color_dim: #0008;
+            self::color_bg: #x2a;
+            
+            self::layout_modal: Layout {
+                align: all(0.5),
+            }
+            
+            self::layout_bg: Layout {
+                padding: all(16.),
+                direction: Down,
+                walk: Walk {width: Compute, height: Compute}
+            }
+            
+            self::shader_dim: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    return vec4(color.rgb * color.a, color.a);
+                }
+            }
+            
+            self::shader_bg: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0., 0., rect_size.x, rect_size.y, 4.);
+                    df.fill(#4a);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 3.);
+                    return df.fill(color);
+                }
+            }
+        })
+    }
+    
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open
+    }
+    
+    pub fn open(&mut self, cx: &mut Cx) {
+        self.popup.open(cx);
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.popup.close(cx);
+    }
+    
+    // goes before everything the dialog covers, the dialog content itself is handled after
+    pub fn handle_modal(&mut self, cx: &mut Cx, event: &mut Event) -> ModalEvent {
+        if let PopupEvent::Dismiss = self.popup.handle_popup(cx, event) {
+            return ModalEvent::Dismiss
+        }
+        ModalEvent::None
+    }
+    
+    pub fn begin_modal(&mut self, cx: &mut Cx) -> ViewRedraw {
+        self.popup.begin_popup(cx, Layout::default())?;
+        // the dim quad has a shader of its own and is drawn at the end, into this draw call under the dialog
+        cx.new_draw_call(self.dim.shader());
+        let rect = cx.get_turtle_rect();
+        cx.begin_turtle(Layout {
+            abs_origin: Some(rect.pos),
+            abs_size: Some(rect.size),
+            ..live_layout!(cx, self::layout_modal)
+        }, Area::Empty);
+        self.bg.color = live_vec4!(cx, self::color_bg);
+        self.bg.begin_quad(cx, live_layout!(cx, self::layout_bg));
+        cx.begin_access_node(AccessNode::new(AccessRole::Dialog, self.bg.area()));
+        Ok(())
+    }
+    
+    pub fn end_modal(&mut self, cx: &mut Cx) {
+        cx.end_access_node();
+        self.bg.end_quad(cx);
+        cx.end_turtle(Area::Empty);
+        self.popup.add_panel(self.bg.area());
+        self.dim.color = live_vec4!(cx, self::color_dim);
+        let rect = cx.get_turtle_rect();
+        self.dim.draw_quad_abs(cx, rect);
+        self.popup.end_popup(cx);
+    }
+}
@@ -120,5 +120,6 @@ impl NormalButton {
         self.text.draw_text_walk(cx, label);
         
         self.bg.end_quad(cx);
+        cx.add_access_node(AccessNode::new(AccessRole::Button, self.bg.area()).with_name(label));
     }
 }
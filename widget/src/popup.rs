@@ -0,0 +1,222 @@
+use makepad_render::*;
+
+// where a panel goes relative to the rect it hangs off
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PopupPlacement {
+    Below, // flips above when there is no room below
+    Above,
+    Right, // submenus, flips to the left
+    Point, // context menus at the finger, flips up and to the left
+    Center
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PopupEvent {
+    None,
+    Dismiss
+}
+
+// an overlay covering the window that context menus, dropdowns, tooltips and modals draw
+// their panels in. handle_popup has to see events before the rest of the ui does
+#[derive(Clone)]
+pub struct Popup {
+    pub view: View,
+    pub is_open: bool,
+    pub modal: bool,
+    pub dismiss_outside: bool,
+    pub trap_focus: bool,
+    pub _panels: Vec<Area>,
+    pub _prev_focus: Area,
+    pub _focus_pending: bool,
+}
+
+impl Popup {
+    pub fn new() -> Self {
+        Self {
+            view: View::new()
+                .with_is_overlay(true),
+            is_open: false,
+            modal: false,
+            dismiss_outside: true,
+            trap_focus: true,
+            _panels: Vec::new(),
+            _prev_focus: Area::Empty,
+            _focus_pending: false,
+        }
+    }
+    
+    pub fn with_modal(self, modal: bool) -> Self {Self {modal, ..self}}
+    pub fn with_dismiss_outside(self, dismiss_outside: bool) -> Self {Self {dismiss_outside, ..self}}
+    pub fn with_trap_focus(self, trap_focus: bool) -> Self {Self {trap_focus, ..self}}
+    
+    pub fn open(&mut self, cx: &mut Cx) {
+        if self.is_open {
+            return
+        }
+        self.is_open = true;
+        self._panels.truncate(0);
+        self._prev_focus = cx.key_focus;
+        self._focus_pending = self.trap_focus;
+        self.view.redraw_view(cx);
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        if !self.is_open {
+            return
+        }
+        self.is_open = false;
+        self._focus_pending = false;
+        if self.trap_focus && (cx.key_focus.is_empty() || self.is_inside(cx, cx.key_focus)) {
+            cx.set_key_focus(self._prev_focus);
+        }
+        self.view.redraw_view(cx);
+    }
+    
+    // is the area drawn in the popup view or a view nested in it
+    pub fn is_inside(&self, cx: &Cx, area: Area) -> bool {
+        let view_id = if let Some(view_id) = self.view.view_id {view_id} else {return false};
+        let mut vw = match area {
+            Area::Instance(ia) => ia.view_id,
+            Area::View(va) => va.view_id,
+            _ => return false
+        };
+        for _ in 0..cx.views.len() {
+            if vw == view_id {
+                return true
+            }
+            if vw == 0 {
+                break
+            }
+            vw = cx.views[vw].nesting_view_id;
+        }
+        false
+    }
+    
+    pub fn is_over(&self, cx: &Cx, abs: Vec2) -> bool {
+        self._panels.iter().any( | area | area.get_rect(cx).contains(abs))
+    }
+    
+    // clicks on a panel aren't outside clicks
+    pub fn add_panel(&mut self, area: Area) {
+        self._panels.push(area);
+    }
+    
+    pub fn handle_popup(&mut self, cx: &mut Cx, event: &mut Event) -> PopupEvent {
+        if !self.is_open {
+            return PopupEvent::None
+        }
+        
+        // focus that wandered outside comes back, and the keys that came with it go nowhere
+        if self.trap_focus && !self._focus_pending && !self.is_inside(cx, cx.key_focus) {
+            cx.set_key_focus(self.view.area());
+            match event {
+                Event::KeyDown(_) | Event::KeyUp(_) | Event::TextInput(_) | Event::TextCopy(_) | Event::Paste(_) => {
+                    *event = Event::None;
+                },
+                _ => ()
+            }
+        }
+        
+        match event {
+            Event::FingerDown(fe) => if !fe.handled && !self.is_over(cx, fe.abs) {
+                if self.dismiss_outside {
+                    fe.handled = true;
+                    self.close(cx);
+                    return PopupEvent::Dismiss
+                }
+                if self.modal {
+                    fe.handled = true;
+                }
+            },
+            Event::FingerHover(fe) => if self.modal && !self.is_over(cx, fe.abs) {
+                fe.handled = true;
+            },
+            Event::FingerScroll(fe) => if self.modal && !self.is_over(cx, fe.abs) {
+                fe.handled_x = true;
+                fe.handled_y = true;
+            },
+            Event::KeyDown(ke) => if self.trap_focus && self.is_inside(cx, cx.key_focus) {
+                if cx.keymap_command(KEYMAP_POPUP, ke).as_deref() == Some("popup.close") {
+                    self.close(cx);
+                    return PopupEvent::Dismiss
+                }
+            },
+            _ => ()
+        }
+        PopupEvent::None
+    }
+    
+    // where a panel of a size goes so it stays inside the bounds
+    pub fn place(anchor: Rect, placement: PopupPlacement, size: Vec2, bounds: Rect) -> Vec2 {
+        let (lo, hi) = (bounds.pos, bounds.pos + bounds.size);
+        let mut pos = match placement {
+            PopupPlacement::Below => vec2(anchor.pos.x, anchor.pos.y + anchor.size.y),
+            PopupPlacement::Above => vec2(anchor.pos.x, anchor.pos.y - size.y),
+            PopupPlacement::Right => vec2(anchor.pos.x + anchor.size.x, anchor.pos.y),
+            PopupPlacement::Point => anchor.pos,
+            PopupPlacement::Center => vec2(lo.x + 0.5 * (bounds.size.x - size.x), lo.y + 0.5 * (bounds.size.y - size.y)),
+        };
+        // flip to the other side of the anchor when that side has room
+        match placement {
+            PopupPlacement::Below => if pos.y + size.y > hi.y && anchor.pos.y - size.y >= lo.y {
+                pos.y = anchor.pos.y - size.y;
+            },
+            PopupPlacement::Above => if pos.y < lo.y && anchor.pos.y + anchor.size.y + size.y <= hi.y {
+                pos.y = anchor.pos.y + anchor.size.y;
+            },
+            PopupPlacement::Right => if pos.x + size.x > hi.x && anchor.pos.x - size.x >= lo.x {
+                pos.x = anchor.pos.x - size.x;
+            },
+            PopupPlacement::Point => {
+                if pos.x + size.x > hi.x && pos.x - size.x >= lo.x {
+                    pos.x -= size.x;
+                }
+                if pos.y + size.y > hi.y && pos.y - size.y >= lo.y {
+                    pos.y -= size.y;
+                }
+            },
+            PopupPlacement::Center => ()
+        }
+        // whatever still sticks out is pushed back in
+        vec2(pos.x.min(hi.x - size.x).max(lo.x), pos.y.min(hi.y - size.y).max(lo.y))
+    }
+    
+    pub fn begin_popup(&mut self, cx: &mut Cx, layout: Layout) -> ViewRedraw {
+        if !self.is_open {
+            return Err(())
+        }
+        let pass_size = cx.passes[*cx.pass_stack.last().unwrap()].pass_size;
+        // an abs turtle around the view, so a view that isn't redrawn walks nothing in the parent
+        cx.begin_turtle(Layout {abs_origin: Some(vec2(0., 0.)), abs_size: Some(pass_size), ..Layout::default()}, Area::Empty);
+        if self.view.begin_view(cx, Layout {abs_origin: Some(vec2(0., 0.)), abs_size: Some(pass_size), ..layout}).is_err() {
+            cx.end_turtle(Area::Empty);
+            return Err(())
+        }
+        self._panels.truncate(0);
+        Ok(())
+    }
+    
+    // panels are measured first and then drawn in an abs turtle at their placed position
+    pub fn begin_panel(&mut self, cx: &mut Cx, anchor: Rect, placement: PopupPlacement, size: Vec2) {
+        let pos = Self::place(anchor, placement, size, cx.get_turtle_rect());
+        cx.begin_turtle(Layout {
+            abs_origin: Some(pos),
+            walk: Walk::wh(Width::Compute, Height::Compute),
+            ..Layout::default()
+        }, Area::Empty);
+    }
+    
+    pub fn end_panel(&mut self, cx: &mut Cx) -> Rect {
+        cx.end_turtle(Area::Empty)
+    }
+    
+    pub fn end_popup(&mut self, cx: &mut Cx) -> Area {
+        let area = self.view.end_view(cx);
+        cx.end_turtle(Area::Empty);
+        if self._focus_pending {
+            self._focus_pending = false;
+            cx.set_key_focus(area);
+        }
+        area
+    }
+}
@@ -0,0 +1,56 @@
+use makepad_render::*;
+use crate::checkbox::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadioGroupEvent {
+    None,
+    Select(usize)
+}
+
+// radio buttons of which one is on, laid out in the direction of the enclosing turtle
+#[derive(Clone)]
+pub struct RadioGroup {
+    pub radios: Elements<usize, CheckBox, CheckBox>,
+    pub selected: usize,
+}
+
+impl RadioGroup {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            radios: Elements::new(CheckBox::new(cx).with_check_type(CheckType::Radio)),
+            selected: 0,
+        }
+    }
+    
+    pub fn with_selected(self, selected: usize) -> Self {Self {selected, ..self}}
+    
+    pub fn set_selected(&mut self, cx: &mut Cx, selected: usize) {
+        self.selected = selected;
+        for (index, radio) in self.radios.enumerate() {
+            radio.set_checked(cx, *index == selected);
+        }
+    }
+    
+    pub fn handle_radio_group(&mut self, cx: &mut Cx, event: &mut Event) -> RadioGroupEvent {
+        let mut select = None;
+        for (index, radio) in self.radios.enumerate() {
+            if let CheckBoxEvent::Toggled(true) = radio.handle_check_box(cx, event) {
+                select = Some(*index);
+            }
+        }
+        if let Some(index) = select {
+            self.set_selected(cx, index);
+            return RadioGroupEvent::Select(index)
+        }
+        RadioGroupEvent::None
+    }
+    
+    pub fn draw_radio_group(&mut self, cx: &mut Cx, labels: &[&str]) {
+        for (index, label) in labels.iter().enumerate() {
+            let selected = index == self.selected;
+            let radio = self.radios.get_draw(cx, index, | _cx, tmpl | tmpl.clone().with_checked(selected));
+            radio.draw_check_box(cx, label);
+        }
+        self.radios.sweep(cx, | _, _ | ());
+    }
+}
@@ -9,7 +9,7 @@ pub struct ScrollBar {
     pub axis: Axis,
     pub animator: Animator,
     pub use_vertical_finger_scroll: bool,
-    pub smoothing: Option<f32>,
+    pub smoothing: Option<Spring>,
     
     next_frame: NextFrame,
     visible: bool,
@@ -21,7 +21,6 @@ pub struct ScrollBar {
     scroll_pos: f32, // scrolling position non normalised
     
     scroll_target: f32,
-    scroll_delta: f32,
     
     drag_point: Option<f32>, // the point in pixels where we are dragging
 }
@@ -67,7 +66,6 @@ impl ScrollBar {
             scroll_pos: 0.0,
             
             scroll_target: 0.0,
-            scroll_delta: 0.0,
             
             drag_point: None,
         }
@@ -124,7 +122,7 @@ impl ScrollBar {
     }
     
     pub fn with_bar_size(self, bar_size: f32) -> Self {Self {bar_size, ..self}}
-    pub fn with_smoothing(self, s: f32) -> Self {Self {smoothing: Some(s), ..self}}
+    pub fn with_smoothing(self, spring: Spring) -> Self {Self {smoothing: Some(spring), ..self}}
     pub fn with_use_vertical_finger_scroll(self, use_vertical_finger_scroll: bool) -> Self {Self {use_vertical_finger_scroll, ..self}}
     
     // reads back normalized scroll position info
@@ -152,6 +150,10 @@ impl ScrollBar {
         let changed = self.scroll_pos != new_scroll_pos;
         self.scroll_pos = new_scroll_pos;
         self.scroll_target = new_scroll_pos;
+        // dragging stops any glide, or the next frame pulls the handle back
+        if let Some(spring) = &mut self.smoothing {
+            spring.set_value(new_scroll_pos);
+        }
         if changed {
             self.update_shader_scroll_pos(cx);
             return self.make_scroll_event();
@@ -174,32 +176,18 @@ impl ScrollBar {
         }
     }
     
-    pub fn move_towards_scroll_target(&mut self, cx: &mut Cx) -> bool {
-        
-        if self.smoothing.is_none() {
-            return false;
-        }
-        if (self.scroll_target - self.scroll_pos).abs() < 0.01 {
+    // steps the smoothing spring to time, returns true while it is still moving
+    pub fn move_towards_scroll_target(&mut self, cx: &mut Cx, time: f64) -> bool {
+        let spring = if let Some(spring) = &mut self.smoothing {spring} else {return false};
+        if spring.is_settled() && spring.value::<f32>() == self.scroll_pos {
             return false
         }
-        if self.scroll_pos > self.scroll_target { // go back
-            self.scroll_pos = self.scroll_pos + (self.smoothing.unwrap() * self.scroll_delta).min(-1.);
-            if self.scroll_pos <= self.scroll_target { // hit the target
-                self.scroll_pos = self.scroll_target;
-                self.update_shader_scroll_pos(cx);
-                return false;
-            }
-        }
-        else { // go forward
-            self.scroll_pos = self.scroll_pos + (self.smoothing.unwrap() * self.scroll_delta).max(1.);
-            if self.scroll_pos > self.scroll_target { // hit the target
-                self.scroll_pos = self.scroll_target;
-                self.update_shader_scroll_pos(cx);
-                return false;
-            }
-        }
+        spring.step(time);
+        // an underdamped spring can swing past the ends
+        self.scroll_pos = spring.value::<f32>().min(self.view_total - self.view_visible).max(0.);
+        let moving = !spring.is_settled();
         self.update_shader_scroll_pos(cx);
-        true
+        moving
     }
     
     pub fn get_scroll_pos(&self) -> f32 {
@@ -213,6 +201,10 @@ impl ScrollBar {
         if self.scroll_pos != scroll_pos {
             self.scroll_pos = scroll_pos;
             self.scroll_target = scroll_pos;
+            // a jump also stops the spring, else it glides back to where it was
+            if let Some(spring) = &mut self.smoothing {
+                spring.set_value(scroll_pos);
+            }
             self.update_shader_scroll_pos(cx);
             self.next_frame = cx.new_next_frame();
             return true
@@ -238,7 +230,13 @@ impl ScrollBar {
         let new_target = scroll_pos_target.min(self.view_total - self.view_visible).max(0.);
         if self.scroll_target != new_target {
             self.scroll_target = new_target;
-            self.scroll_delta = new_target - self.scroll_pos;
+            if let Some(spring) = &mut self.smoothing {
+                // keep the velocity if we were already gliding, otherwise start from rest
+                if spring.value::<f32>() != self.scroll_pos {
+                    spring.set_value(self.scroll_pos);
+                }
+                spring.set_target(new_target);
+            }
             self.next_frame = cx.new_next_frame();
             return true
         };
@@ -292,7 +290,7 @@ impl ScrollBar {
                                     Axis::Vertical => fe.handled_y = true
                                 }
                             };
-                            self.move_towards_scroll_target(cx); // take the first step now
+                            self.move_towards_scroll_target(cx, fe.time); // take the first step now
                             return self.make_scroll_event();
                         }
                         else {
@@ -315,8 +313,8 @@ impl ScrollBar {
             if let Some(ae) = event.is_animate(cx, &self.animator) {
                 self.bg.animate(cx, &mut self.animator, ae.time);
             }
-            if let Some(_) = event.is_next_frame(cx, self.next_frame) {
-                if self.move_towards_scroll_target(cx) {
+            if let Some(ne) = event.is_next_frame(cx, self.next_frame) {
+                if self.move_towards_scroll_target(cx, ne.time) {
                     self.next_frame = cx.new_next_frame();
                 }
                 return self.make_scroll_event()
@@ -470,6 +468,14 @@ impl ScrollBar {
             self.next_frame = cx.new_next_frame();
         }
         
+        if self.visible {
+            cx.add_access_node(
+                AccessNode::new(AccessRole::ScrollBar, self.bg.area())
+                    .with_name(match self.axis {Axis::Horizontal => "horizontal", Axis::Vertical => "vertical"})
+                    .with_value(&format!("{}", self.scroll_pos))
+            );
+        }
+        
         self.scroll_pos
     }
 }
@@ -23,7 +23,7 @@ impl ScrollView{
             view: View::new(),
             scroll_h: Some(ScrollBar::new(cx)),
             scroll_v: Some(ScrollBar::new(cx)
-                .with_smoothing(0.15)),
+                .with_smoothing(Spring::critical(1000.0))),
         }
     }
    
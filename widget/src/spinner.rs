@@ -0,0 +1,252 @@
+use makepad_render::*;
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+pub struct DrawSpinner {
+    #[default_shader(self::shader_bg)]
+    base: DrawQuad,
+    hover: f32,
+    down: f32,
+}
+
+pub enum SpinnerEvent {
+    Change {value: f32},
+    DoneChanging,
+    None
+}
+
+// a number that changes by dragging left or right across it, clicking the arrows at
+// its ends, scrolling over it or with the arrow keys while it has focus
+#[derive(Clone)]
+pub struct Spinner {
+    pub bg: DrawSpinner,
+    pub text: DrawText,
+    pub animator: Animator,
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub step: f32,
+    pub precision: usize,
+    pub _drag_start: Option<f32>,
+    pub _moved: bool,
+    pub _scroll: f32,
+}
+
+impl Spinner {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            bg: DrawSpinner::new(cx, live_shader!(cx, self::shader_bg)),
+            text: DrawText::new(cx, default_shader!()),
+            animator: Animator::default(),
+            value: 0.0,
+            min: None,
+            max: None,
+            step: 1.0,
+            precision: 0,
+            _drag_start: None,
+            _moved: false,
+            _scroll: 0.0,
+        }
+    }
+    
+    pub fn with_range(self, min: f32, max: f32) -> Self {Self {min: Some(min), max: Some(max), ..self}}
+    pub fn with_step(self, step: f32) -> Self {Self {step, ..self}}
+    pub fn with_precision(self, precision: usize) -> Self {Self {precision, ..self}}
+    
+    pub fn style(cx: &mut Cx) {
+        self::DrawSpinner::register_draw_input(cx);
+        
+        live_body!(cx, {
+            // steps per pixel dragged, and pixels scrolled per step
+            self::drag_speed: 0.25;
+            self::scroll_distance: 10.0;
+            self::fast_steps: 10.0;
+            self::arrow_width: 16.0;
+            
+            self::layout_bg: Layout {
+                align: all(0.5),
+                walk: Walk {
+                    width: Fix(80.0),
+                    height: Compute,
+                    margin: all(1.0),
+                },
+                padding: {l: 16.0, t: 5.0, r: 16.0, b: 5.0},
+            }
+            
+            self::text_style_value: TextStyle {
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::anim_default: Anim {
+                play: Cut {duration: 0.1}
+                tracks: [
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawSpinner::hover}
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawSpinner::down}
+                    Vec4 {keys: {1.0: #9}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_over: Anim {
+                play: Cut {duration: 0.1},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawSpinner::hover},
+                    Float {keys: {1.0: 0.0}, bind_to: self::DrawSpinner::down},
+                    Vec4 {keys: {1.0: #f}, bind_to: makepad_render::drawtext::DrawText::color}
+                ]
+            }
+            
+            self::anim_down: Anim {
+                play: Cut {duration: 0.2},
+                tracks: [
+                    Float {keys: {0.0: 1.0, 1.0: 1.0}, bind_to: self::DrawSpinner::down},
+                    Float {keys: {1.0: 1.0}, bind_to: self::DrawSpinner::hover},
+                    Vec4 {keys: {0.0: #c}, bind_to: makepad_render::drawtext::DrawText::color},
+                ]
+            }
+            
+            self::shader_bg: Shader {
+                use makepad_render::drawquad::shader::*;
+                
+                draw_input: self::DrawSpinner;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 2.5);
+                    df.fill(mix(mix(#2a, #3, hover), #1a, down));
+                    let cy = rect_size.y * 0.5;
+                    let sz = 3.0;
+                    df.move_to(9. - 0.5 * sz, cy);
+                    df.line_to(9. + 0.5 * sz, cy - sz);
+                    df.line_to(9. + 0.5 * sz, cy + sz);
+                    df.close_path();
+                    df.fill(mix(#5, #9, hover));
+                    let rx = rect_size.x - 9.;
+                    df.move_to(rx + 0.5 * sz, cy);
+                    df.line_to(rx - 0.5 * sz, cy - sz);
+                    df.line_to(rx - 0.5 * sz, cy + sz);
+                    df.close_path();
+                    return df.fill(mix(#5, #9, hover));
+                }
+            }
+        });
+    }
+    
+    // snaps to the step and clamps to the range
+    pub fn set_value(&mut self, cx: &mut Cx, value: f32) -> SpinnerEvent {
+        let mut value = value;
+        if self.step > 0.0 {
+            value = (value / self.step).round() * self.step;
+        }
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+        if value == self.value {
+            return SpinnerEvent::None
+        }
+        self.value = value;
+        cx.redraw_child_area(self.bg.area());
+        SpinnerEvent::Change {value}
+    }
+    
+    pub fn handle_spinner(&mut self, cx: &mut Cx, event: &mut Event) -> SpinnerEvent {
+        if let Some(ae) = event.is_animate(cx, &self.animator) {
+            self.bg.animate(cx, &mut self.animator, ae.time);
+            self.text.animate(cx, &mut self.animator, ae.time);
+        }
+        
+        match event.hits(cx, self.bg.area(), HitOpt::default()) {
+            Event::FingerHover(fe) => {
+                cx.set_hover_mouse_cursor(MouseCursor::EwResize);
+                match fe.hover_state {
+                    HoverState::In => {
+                        self.animator.play_anim(cx, live_anim!(cx, self::anim_over));
+                    },
+                    HoverState::Out => {
+                        self.animator.play_anim(cx, live_anim!(cx, self::anim_default));
+                    },
+                    _ => ()
+                }
+            },
+            Event::FingerDown(_fe) => {
+                self.animator.play_anim(cx, live_anim!(cx, self::anim_down));
+                cx.set_down_mouse_cursor(MouseCursor::EwResize);
+                cx.set_key_focus(self.bg.area());
+                self._drag_start = Some(self.value);
+                self._moved = false;
+            },
+            Event::FingerMove(fe) => if let Some(start) = self._drag_start {
+                let steps = ((fe.rel.x - fe.rel_start.x) * live_float!(cx, self::drag_speed)).round();
+                if steps != 0.0 {
+                    self._moved = true;
+                }
+                return self.set_value(cx, start + steps * self.step)
+            },
+            Event::FingerUp(fe) => {
+                if fe.is_over && fe.input_type.has_hovers() {
+                    self.animator.play_anim(cx, live_anim!(cx, self::anim_over));
+                }
+                else {
+                    self.animator.play_anim(cx, live_anim!(cx, self::anim_default));
+                }
+                self._drag_start = None;
+                // a click without a drag steps by the arrow it landed on
+                if !self._moved && fe.is_over {
+                    let arrow_width = live_float!(cx, self::arrow_width);
+                    if fe.rel.x < arrow_width {
+                        return self.set_value(cx, self.value - self.step)
+                    }
+                    if fe.rel.x > fe.rect.size.x - arrow_width {
+                        return self.set_value(cx, self.value + self.step)
+                    }
+                }
+                return SpinnerEvent::DoneChanging
+            },
+            Event::FingerScroll(fs) => {
+                let distance = live_float!(cx, self::scroll_distance);
+                self._scroll += fs.scroll.y;
+                let steps = (self._scroll / distance).trunc();
+                if steps != 0.0 {
+                    self._scroll -= steps * distance;
+                    return self.set_value(cx, self.value - steps * self.step)
+                }
+            },
+            Event::KeyDown(ke) => {
+                let fast = live_float!(cx, self::fast_steps);
+                let steps = match cx.keymap_command(KEYMAP_SPINNER, &ke).as_deref() {
+                    Some("spinner.increment") => 1.0,
+                    Some("spinner.decrement") => -1.0,
+                    Some("spinner.increment_fast") => fast,
+                    Some("spinner.decrement_fast") => -fast,
+                    _ => 0.0
+                };
+                if steps != 0.0 {
+                    return self.set_value(cx, self.value + steps * self.step)
+                }
+            },
+            _ => ()
+        }
+        SpinnerEvent::None
+    }
+    
+    pub fn draw_spinner(&mut self, cx: &mut Cx, value: f32) {
+        if self.animator.need_init(cx) {
+            self.animator.init(cx, live_anim!(cx, self::anim_default));
+            self.bg.last_animate(&self.animator);
+            self.text.last_animate(&self.animator);
+        }
+        // the value from outside wins unless a drag is changing it
+        if self._drag_start.is_none() {
+            self.value = value;
+        }
+        let label = format!("{:.*}", self.precision, self.value);
+        
+        self.bg.begin_quad(cx, live_layout!(cx, self::layout_bg));
+        self.text.text_style = live_text_style!(cx, self::text_style_value);
+        self.text.draw_text_walk(cx, &label);
+        self.bg.end_quad(cx);
+        cx.add_access_node(AccessNode::new(AccessRole::SpinButton, self.bg.area()).with_value(&label).with_focused(cx.key_focus == self.bg.area()));
+    }
+}
@@ -140,21 +140,25 @@ impl Tab {
                     ease: Ease::Lin,
                     keys: vec![(1.0, self.get_bg_color(cx))],
                     bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 },
                 Track::Vec4 {
                     ease: Ease::Lin,
                     keys: vec![(1.0, live_vec4!(cx, self::color_bg_selected))],
                     bind_to: live_item_id!(self::DrawTab::border_color),
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 },
                 Track::Vec4 {
                     ease: Ease::Lin,
                     keys: vec![(1.0, self.get_text_color(cx))],
                     bind_to: live_item_id!(makepad_render::drawtext::DrawText::color),
-                    cut_init: None
+                    cut_init: None,
+                    curves: vec![]
                 },
-            ]
+            ],
+            events: vec![]
         }
     }
     
@@ -310,6 +314,10 @@ impl Tab {
     
     pub fn end_tab(&mut self, cx: &mut Cx) {
         self.bg.end_quad(cx);
+        // the dragged copy of a tab isnt a tab of its own
+        if self.abs_origin.is_none() {
+            cx.add_access_node(AccessNode::new(AccessRole::Tab, self.bg.area()).with_name(&self.label).with_selected(self._is_selected));
+        }
     }
     
     pub fn draw_tab(&mut self, cx: &mut Cx) {
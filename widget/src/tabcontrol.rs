@@ -39,7 +39,7 @@ impl TabControl {
             tabs_view: ScrollView::new()
                 .with_scroll_h(ScrollBar::new(cx)
                     .with_bar_size(8.0)
-                    .with_smoothing(0.15)
+                    .with_smoothing(Spring::critical(1000.0))
                     .with_use_vertical_finger_scroll(true) 
                 ),
 
@@ -191,6 +191,7 @@ impl TabControl {
         }) {
             return Err(())
         }
+        cx.begin_access_node(AccessNode::new(AccessRole::TabList, self.tabs_view.area()));
         self._tab_now_selected = None;
         self._tab_id_alloc = 0;
         Ok(())
@@ -237,6 +238,7 @@ impl TabControl {
         live_style_begin!(cx, self::tab_control_style);
         self.tabs_view.end_view(cx);
         live_style_end!(cx, self::tab_control_style);
+        cx.end_access_node();
         if self._tab_now_selected != self._tab_last_selected {
             // lets scroll the thing into view
             if let Some(tab_id) = self._tab_now_selected {
@@ -51,6 +51,8 @@ pub struct TextEditor {
     pub shadow: ScrollShadow,
     pub message_marker: DrawColor,
     pub search_marker: DrawColor,
+    pub ime_bg: DrawColor,
+    pub ime_underline: DrawColor,
     pub text: DrawText,
     pub line_number_text: DrawText,
     pub cursors: TextCursorSet,
@@ -107,6 +109,7 @@ pub struct TextEditor {
     //pub _highlight_selection: Vec<char>,
     //pub _highlight_token: Vec<char>,
     pub _last_cursor_pos: TextPos,
+    pub _ime_preedit: TextImeEvent,
     
     pub _anim_font_scale: f32,
     pub _line_largest_font: f32,
@@ -216,6 +219,11 @@ impl TextEditor {
             paren_pair: DrawColor::new(cx, live_shader!(cx, self::shader_paren_pair)),
             message_marker: DrawColor::new(cx, live_shader!(cx, self::shader_message_marker)),
             search_marker: DrawColor::new(cx, live_shader!(cx, self::shader_search_marker)),
+            // the preedit covers the text under it
+            ime_bg: DrawColor::new(cx, default_shader!())
+                .with_draw_depth(0.6),
+            ime_underline: DrawColor::new(cx, default_shader!())
+                .with_draw_depth(0.8),
             //code_icon: CodeIcon::proto(cx),
             //view_layout: Layout::default(),
             text: DrawText::new(cx, default_shader!())
@@ -286,6 +294,7 @@ impl TextEditor {
             //_highlight_selection: Vec::new(),
             //_highlight_token: Vec::new(),
             _last_cursor_pos: TextPos::zero(),
+            _ime_preedit: TextImeEvent::default(),
             _last_indent_color: Vec4::default(),
             
             _cursor_blink_timer: Timer::empty(),
@@ -330,6 +339,7 @@ impl TextEditor {
             self::color_message_marker_log: #c8;
             
             self::color_search_marker: #804000;
+            self::color_ime_underline: #d4;
             
             self::color_line_number_normal: #88;
             self::color_line_number_highlight: #d4;
@@ -523,6 +533,8 @@ impl TextEditor {
         self.colors.defocus = live_vec4!(cx, self::color_defocus);
         self.bg.color = live_vec4!(cx, self::color_bg);
         self.gutter_bg.color = live_vec4!(cx, self::color_gutter_bg);
+        self.ime_bg.color = live_vec4!(cx, self::color_bg);
+        self.ime_underline.color = live_vec4!(cx, self::color_ime_underline);
         
         self.line_number_width = live_float!(cx, self::gutter_width);
         self.top_padding = live_float!(cx, self::padding_top);
@@ -689,134 +701,120 @@ impl TextEditor {
         self.reset_cursor_blinker(cx);
     }
     
-    fn handle_key_down(&mut self, cx: &mut Cx, ke: &KeyEvent, text_buffer: &mut TextBuffer) -> bool {
-        let cursor_moved = match ke.key_code {
-            KeyCode::KeyE => {
-                if ke.modifiers.logo || ke.modifiers.control {
-                    let pos = self.cursors.get_last_cursor_head();
-                    let mut moved = false;
-                    for result in text_buffer.markers.search_cursors.iter().rev() {
-                        if result.head < pos {
-                            if ke.modifiers.shift {
-                                self.cursors.add_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
-                            }
-                            else {
-                                self.cursors.set_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
-                            }
-                            moved = true;
-                            break;
+    fn handle_key_command(&mut self, cx: &mut Cx, command: &str, text_buffer: &mut TextBuffer) -> bool {
+        // the select_ and add_ variants extend the selection
+        let select = command.starts_with("editor.select_") || command.starts_with("editor.add_");
+        let cursor_moved = match command {
+            "editor.prev_match" | "editor.add_prev_match" => {
+                let pos = self.cursors.get_last_cursor_head();
+                let mut moved = false;
+                for result in text_buffer.markers.search_cursors.iter().rev() {
+                    if result.head < pos {
+                        if select {
+                            self.cursors.add_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
+                        }
+                        else {
+                            self.cursors.set_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
                         }
+                        moved = true;
+                        break;
                     }
-                    
-                    moved
-                }
-                else {
-                    false
                 }
-            }
-            KeyCode::KeyD => {
-                if ke.modifiers.logo || ke.modifiers.control {
-                    let pos = self.cursors.get_last_cursor_head();
-                    let mut moved = false;
-                    for result in text_buffer.markers.search_cursors.iter() {
-                        if result.tail > pos {
-                            if ke.modifiers.shift {
-                                self.cursors.add_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
-                            }
-                            else {
-                                self.cursors.set_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
-                            }
-                            moved = true;
-                            break;
+                
+                moved
+            }
+            "editor.next_match" | "editor.add_next_match" => {
+                let pos = self.cursors.get_last_cursor_head();
+                let mut moved = false;
+                for result in text_buffer.markers.search_cursors.iter() {
+                    if result.tail > pos {
+                        if select {
+                            self.cursors.add_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
+                        }
+                        else {
+                            self.cursors.set_last_cursor_head_and_tail(result.head, result.tail, text_buffer);
                         }
+                        moved = true;
+                        break;
                     }
-                    moved
-                }
-                else {
-                    false
                 }
+                moved
             }
-            KeyCode::ArrowUp => {
+            "editor.move_up" | "editor.select_up" => {
                 if !self.multiline {
                     false
                 }
-                else if ke.modifiers.logo || ke.modifiers.control {
-                    false
-                }
                 else {
                     if self._anim_folding.state.is_folded() && self.cursors.set.len() == 1 {
                         // compute the nearest nonfolded line up
                         let delta = self.compute_next_unfolded_line_up(text_buffer);
-                        self.cursors.move_up(delta, ke.modifiers.shift, text_buffer);
+                        self.cursors.move_up(delta, select, text_buffer);
                     }
                     else {
-                        self.cursors.move_up(1, ke.modifiers.shift, text_buffer);
+                        self.cursors.move_up(1, select, text_buffer);
                     }
                     self._undo_id += 1;
                     true
                 }
             },
-            KeyCode::ArrowDown => {
+            "editor.move_down" | "editor.select_down" => {
                 if !self.multiline {
                     false
                 }
-                else if ke.modifiers.logo || ke.modifiers.control {
-                    false
-                }
                 else {
                     if self._anim_folding.state.is_folded() && self.cursors.set.len() == 1 {
                         // compute the nearest nonfolded line down
                         let delta = self.compute_next_unfolded_line_down(text_buffer);
-                        self.cursors.move_down(delta, ke.modifiers.shift, text_buffer);
+                        self.cursors.move_down(delta, select, text_buffer);
                     }
                     else {
-                        self.cursors.move_down(1, ke.modifiers.shift, text_buffer);
+                        self.cursors.move_down(1, select, text_buffer);
                     }
                     self._undo_id += 1;
                     true
                 }
             },
-            KeyCode::ArrowLeft => {
-                if ke.modifiers.logo || ke.modifiers.control { // token skipping
-                    self.cursors.move_left_nearest_token(ke.modifiers.shift, text_buffer)
-                }
-                else {
-                    self.cursors.move_left(1, ke.modifiers.shift, text_buffer);
-                }
+            "editor.move_token_left" | "editor.select_token_left" => {
+                self.cursors.move_left_nearest_token(select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::ArrowRight => {
-                if ke.modifiers.logo || ke.modifiers.control { // token skipping
-                    self.cursors.move_right_nearest_token(ke.modifiers.shift, text_buffer)
-                }
-                else {
-                    self.cursors.move_right(1, ke.modifiers.shift, text_buffer);
-                }
+            "editor.move_left" | "editor.select_left" => {
+                self.cursors.move_left(1, select, text_buffer);
+                self._undo_id += 1;
+                true
+            },
+            "editor.move_token_right" | "editor.select_token_right" => {
+                self.cursors.move_right_nearest_token(select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::PageUp => {
-                self.cursors.move_up(self._visible_lines.max(5) - 4, ke.modifiers.shift, text_buffer);
+            "editor.move_right" | "editor.select_right" => {
+                self.cursors.move_right(1, select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::PageDown => {
-                self.cursors.move_down(self._visible_lines.max(5) - 4, ke.modifiers.shift, text_buffer);
+            "editor.move_page_up" | "editor.select_page_up" => {
+                self.cursors.move_up(self._visible_lines.max(5) - 4, select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::Home => {
-                self.cursors.move_home(ke.modifiers.shift, text_buffer);
+            "editor.move_page_down" | "editor.select_page_down" => {
+                self.cursors.move_down(self._visible_lines.max(5) - 4, select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::End => {
-                self.cursors.move_end(ke.modifiers.shift, text_buffer);
+            "editor.move_home" | "editor.select_home" => {
+                self.cursors.move_home(select, text_buffer);
                 self._undo_id += 1;
                 true
             },
-            KeyCode::Backspace => {
+            "editor.move_end" | "editor.select_end" => {
+                self.cursors.move_end(select, text_buffer);
+                self._undo_id += 1;
+                true
+            },
+            "editor.backspace" => {
                 if !self.read_only {
                     self.cursors.backspace(text_buffer, self._undo_id);
                     true
@@ -825,7 +823,7 @@ impl TextEditor {
                     false
                 }
             },
-            KeyCode::Delete => {
+            "editor.delete" => {
                 if !self.read_only {
                     self.cursors.delete(text_buffer);
                     true
@@ -834,56 +832,42 @@ impl TextEditor {
                     false
                 }
             },
-            KeyCode::KeyZ => {
+            "editor.undo" => {
                 if !self.read_only {
-                    if ke.modifiers.logo || ke.modifiers.control {
-                        if ke.modifiers.shift { // redo
-                            text_buffer.redo(true, &mut self.cursors);
-                            true
-                        }
-                        else { // undo
-                            text_buffer.undo(true, &mut self.cursors);
-                            true
-                        }
-                    }
-                    else {
-                        false
-                    }
+                    text_buffer.undo(true, &mut self.cursors);
+                    true
                 }
                 else {
                     false
                 }
             },
-            KeyCode::KeyX => { // cut, the actual copy comes from the TextCopy event from the platform layer
-                if !self.read_only && (ke.modifiers.logo || ke.modifiers.control) { // cut
-                    self.cursors.replace_text("", text_buffer, None);
+            "editor.redo" => {
+                if !self.read_only {
+                    text_buffer.redo(true, &mut self.cursors);
                     true
                 }
                 else {
                     false
                 }
             },
-            KeyCode::KeyA => { // select all
-                if ke.modifiers.logo || ke.modifiers.control { // cut
-                    self.cursors.select_all(text_buffer);
-                    // don't scroll!
-                    self.view.redraw_view(cx);
-                    false
+            "editor.cut" => { // cut, the actual copy comes from the TextCopy event from the platform layer
+                if !self.read_only {
+                    self.cursors.replace_text("", text_buffer, None);
+                    true
                 }
                 else {
                     false
                 }
             },
-            KeyCode::Alt => {
-                // how do we find the center line of the view
-                // its simply the top line
-                self.start_code_folding(cx, text_buffer);
+            "editor.select_all" => {
+                self.cursors.select_all(text_buffer);
+                // don't scroll!
+                self.view.redraw_view(cx);
                 false
-                //return CodeEditorEvent::FoldStart
             },
-            KeyCode::Tab => {
+            "editor.indent" | "editor.outdent" => {
                 if !self.read_only {
-                    if ke.modifiers.shift {
+                    if command == "editor.outdent" {
                         self.cursors.remove_tab(text_buffer, 4);
                     }
                     else {
@@ -895,11 +879,9 @@ impl TextEditor {
                     false
                 }
             },
-            KeyCode::Return => {
+            "editor.newline" => {
                 if !self.read_only && self.multiline {
-                    if !ke.modifiers.control && !ke.modifiers.logo {
-                        self.cursors.insert_newline_with_indent(text_buffer);
-                    }
+                    self.cursors.insert_newline_with_indent(text_buffer);
                     true
                 }
                 else {
@@ -1088,6 +1070,7 @@ impl TextEditor {
                 return TextEditorEvent::KeyFocus
             },
             Event::KeyFocusLost(_kf) => {
+                self._ime_preedit = TextImeEvent::default();
                 self.view.redraw_view(cx);
                 return TextEditorEvent::KeyFocusLost
             },
@@ -1104,41 +1087,34 @@ impl TextEditor {
                 self.handle_finger_move(cx, &fe, text_buffer);
             },
             Event::KeyDown(ke) => {
-                if ke.key_code == KeyCode::Escape {
-                    let pos = self.cursors.get_last_cursor_head();
-                    self.cursors.clear_and_set_last_cursor_head_and_tail(pos, pos, text_buffer);
-                    return TextEditorEvent::Escape
-                }/*
-                if ke.key_code == KeyCode::KeyD && (ke.modifiers.logo || ke.modifiers.control) {
-                    // check if d is inside a search cursor
-                    let pos = self.cursors.get_last_cursor_head();
-                    let mut repeat = false;
-                    for result in text_buffer.markers.search_cursors.iter().rev(){
-                        if pos >= result.tail && pos <= result.head {
-                            repeat = true;
-                            break;
+                if ke.key_code == KeyCode::Alt {
+                    // how do we find the center line of the view
+                    // its simply the top line
+                    self.start_code_folding(cx, text_buffer);
+                }
+                if let Some(command) = cx.keymap_command(KEYMAP_EDITOR, &ke) {
+                    match command.as_str() {
+                        "editor.escape" => {
+                            let pos = self.cursors.get_last_cursor_head();
+                            self.cursors.clear_and_set_last_cursor_head_and_tail(pos, pos, text_buffer);
+                            return TextEditorEvent::Escape
+                        },
+                        "editor.search" => {
+                            let search = self.cursors.get_ident_around_last_cursor_and_set(text_buffer);
+                            return TextEditorEvent::Search(search)
+                        },
+                        "editor.decl" => {
+                            let search = self.cursors.get_ident_around_last_cursor_and_set(text_buffer);
+                            return TextEditorEvent::Decl(search)
+                        },
+                        "editor.auto_format" => {
+                            return TextEditorEvent::AutoFormat
+                        },
+                        _ => {
+                            cursor_moved = self.handle_key_command(cx, &command, text_buffer);
                         }
-                    }/*
-                    if !repeat{
-                        let search = self.cursors.get_ident_around_last_cursor_and_set(text_buffer);
-                        // lets select the ident
-                        
-                        return TextEditorEvent::Decl(search)
-                    }*/
-                }*/
-                if ke.key_code == KeyCode::KeyF && (ke.modifiers.logo || ke.modifiers.control) {
-                    let search = self.cursors.get_ident_around_last_cursor_and_set(text_buffer);
-                    return TextEditorEvent::Search(search)
-                }
-                if ke.key_code == KeyCode::KeyS && (ke.modifiers.logo || ke.modifiers.control) {
-                    let search = self.cursors.get_ident_around_last_cursor_and_set(text_buffer);
-                    return TextEditorEvent::Decl(search)
-                }
-                
-                if ke.key_code == KeyCode::Return && (ke.modifiers.logo || ke.modifiers.control) {
-                    return TextEditorEvent::AutoFormat
+                    }
                 }
-                cursor_moved = self.handle_key_down(cx, &ke, text_buffer);
             },
             Event::KeyUp(ke) => {
                 match ke.key_code {
@@ -1151,9 +1127,25 @@ impl TextEditor {
             },
             Event::TextInput(te) => {
                 if !self.read_only {
+                    self._ime_preedit = TextImeEvent::default();
                     self.handle_text_input(cx, &te, text_buffer);
                 }
             },
+            Event::Paste(pe) => {
+                // only the text format makes sense in here, images and app data are for other widgets
+                if let Some(text) = pe.data.text() {
+                    if !self.read_only {
+                        self._ime_preedit = TextImeEvent::default();
+                        self.handle_text_input(cx, &TextInputEvent {input: text.to_string(), replace_last: false, was_paste: true}, text_buffer);
+                    }
+                }
+            },
+            Event::TextIme(te) => {
+                if !self.read_only {
+                    self._ime_preedit = te;
+                    self.view.redraw_view(cx);
+                }
+            },
             Event::TextCopy(_) => match event { // access the original event
                 Event::TextCopy(req) => {
                     req.response = Some(self.cursors.get_all_as_string(text_buffer));
@@ -1840,6 +1832,7 @@ impl TextEditor {
         
         // unmatched highlighting
         self.draw_paren_unmatched(cx);
+        self.draw_ime_preedit(cx);
         self.draw_cursors(cx);
         //self.do_selection_animations(cx);
         self.draw_selections(cx);
@@ -1905,6 +1898,55 @@ impl TextEditor {
         }
     }
     
+    // draws the uncommitted IME text at the last cursor, underlined per segment, and moves the cursor into it
+    fn draw_ime_preedit(&mut self, cx: &mut Cx) {
+        if self._ime_preedit.is_empty() || !self.has_key_focus(cx) {
+            return
+        }
+        let last_cursor = if let Some(last_cursor) = self._draw_cursors.last_cursor {last_cursor} else {return};
+        let rc = self._draw_cursors.cursors[last_cursor];
+        let chunk: Vec<char> = self._ime_preedit.preedit.chars().collect();
+        
+        let mut glyphs = Vec::new();
+        let old_depth = self.text.draw_depth;
+        self.text.draw_depth = 0.7;
+        self.text.color = self.colors.identifier;
+        self.text.draw_text_chunk(cx, vec2(rc.x, rc.y), 0, &chunk, | _, _, x, w | {
+            glyphs.push((x, w));
+            0.0
+        });
+        self.text.draw_depth = old_depth;
+        if glyphs.len() == 0 {
+            return
+        }
+        let end_x = glyphs[glyphs.len() - 1].0 + glyphs[glyphs.len() - 1].1;
+        self.ime_bg.draw_quad_abs(cx, Rect {
+            pos: vec2(rc.x, rc.y),
+            size: vec2(end_x - rc.x, rc.h)
+        });
+        
+        // the segment being converted gets a thicker line
+        let mut segments = self._ime_preedit.segments.clone();
+        if segments.len() == 0 {
+            segments.push(TextImeSegment {start: 0, end: glyphs.len(), style: TextImeStyle::Input});
+        }
+        for segment in &segments {
+            if segment.start >= segment.end || segment.end > glyphs.len() {
+                continue
+            }
+            let x1 = glyphs[segment.start].0 + 1.0;
+            let x2 = glyphs[segment.end - 1].0 + glyphs[segment.end - 1].1 - 1.0;
+            let height = if segment.style == TextImeStyle::Target {2.0} else {1.0};
+            self.ime_underline.draw_quad_abs(cx, Rect {
+                pos: vec2(x1, rc.y + rc.h - height),
+                size: vec2((x2 - x1).max(1.0), height)
+            });
+        }
+        
+        let cursor = self._ime_preedit.cursor;
+        self._draw_cursors.cursors[last_cursor].x = if cursor < glyphs.len() {glyphs[cursor].0} else {end_x};
+    }
+    
     fn draw_shadows(&mut self, cx: &mut Cx) {
         let gutter_width = live_float!(cx, self::gutter_width);
         self.shadow.draw_shadow_left_at(cx, Rect {
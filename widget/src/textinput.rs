@@ -118,6 +118,11 @@ impl TextInput {
             live_style_end!(cx, self::style_text_input);
             return;
         }
+        cx.begin_access_node(
+            AccessNode::new(AccessRole::TextInput, self.text_editor.view.area())
+                .with_name(&self.empty_message)
+                .with_value(&text_buffer.get_as_string())
+        );
         
         if text_buffer.is_empty() {
             let pos = cx.get_turtle_pos();
@@ -131,6 +136,7 @@ impl TextInput {
         }
         
         self.text_editor.end_text_editor(cx, text_buffer);
+        cx.end_access_node();
         live_style_end!(cx, self::style_text_input);
     }
 }
@@ -0,0 +1,124 @@
+use makepad_render::*;
+use crate::popup::*;
+
+// shows a text under the finger after it rested on an area for a while. One tooltip
+// can serve several areas, handle_tooltip goes before the widget that owns the area
+#[derive(Clone)]
+pub struct Tooltip {
+    pub popup: Popup,
+    pub bg: DrawColor,
+    pub text: DrawText,
+    pub _timer: Timer,
+    pub _target: Area,
+    pub _label: String,
+    pub _pos: Vec2,
+}
+
+impl Tooltip {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            popup: Popup::new()
+                .with_dismiss_outside(false)
+                .with_trap_focus(false),
+            bg: DrawColor::new(cx, live_shader!(cx, self::shader_bg)),
+            text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            _timer: Timer::empty(),
+            _target: Area::Empty,
+            _label: String::new(),
+            _pos: Vec2::default(),
+        }
+    }
+    
+    pub fn style(cx: &mut Cx) {
+        live_body!(cx, {
+            self::delay: 0.6;
+            self::cursor_height: 20.0;
+            self::color_bg: #x3a3a2a;
+            self::color_text: #d;
+            
+            self::layout_bg: Layout {
+                padding: {l: 6., t: 4., r: 6., b: 4.},
+                walk: Walk {width: Compute, height: Compute}
+            }
+            
+            self::text_style_label: TextStyle {
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::shader_bg: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0., 0., rect_size.x, rect_size.y, 2.);
+                    df.fill(#5);
+                    df.box(1., 1., rect_size.x - 2., rect_size.y - 2., 1.);
+                    return df.fill(color);
+                }
+            }
+        })
+    }
+    
+    pub fn hide(&mut self, cx: &mut Cx) {
+        cx.stop_timer(&mut self._timer);
+        self.popup.close(cx);
+    }
+    
+    pub fn handle_tooltip(&mut self, cx: &mut Cx, event: &mut Event, area: Area, label: &str) {
+        match event {
+            Event::FingerHover(fe) => {
+                let over = !fe.any_down && fe.hover_state != HoverState::Out && area.get_rect(cx).contains(fe.abs);
+                if over {
+                    if self._target != area {
+                        self.hide(cx);
+                        self._target = area;
+                        self._label = label.to_string();
+                        self._timer = cx.start_timer(live_float!(cx, self::delay) as f64, false);
+                    }
+                    if !self.popup.is_open {
+                        self._pos = fe.abs;
+                    }
+                }
+                else if self._target == area {
+                    self.hide(cx);
+                    self._target = Area::Empty;
+                }
+            },
+            Event::Timer(te) => if self._timer.is_timer(te) {
+                self._timer = Timer::empty();
+                if self._target == area {
+                    self.popup.open(cx);
+                }
+            },
+            // pressing or typing puts it away until the finger leaves and comes back
+            Event::FingerDown(_) | Event::FingerScroll(_) | Event::KeyDown(_) => if self._target == area {
+                self.hide(cx);
+            },
+            _ => ()
+        }
+    }
+    
+    pub fn draw_tooltip(&mut self, cx: &mut Cx) {
+        if self.popup.begin_popup(cx, Layout::default()).is_err() {
+            return
+        }
+        let label = self._label.clone();
+        let size = cx.measure( | cx | self.draw_label(cx, &label));
+        let anchor = Rect {pos: self._pos, size: vec2(0., live_float!(cx, self::cursor_height))};
+        self.popup.begin_panel(cx, anchor, PopupPlacement::Below, size);
+        self.draw_label(cx, &label);
+        self.popup.end_panel(cx);
+        cx.add_access_node(AccessNode::new(AccessRole::Tooltip, self.bg.area()).with_name(&label));
+        self.popup.end_popup(cx);
+    }
+    
+    fn draw_label(&mut self, cx: &mut Cx, label: &str) {
+        self.bg.color = live_vec4!(cx, self::color_bg);
+        self.bg.begin_quad(cx, live_layout!(cx, self::layout_bg));
+        self.text.text_style = live_text_style!(cx, self::text_style_label);
+        self.text.color = live_vec4!(cx, self::color_text);
+        self.text.draw_text_walk(cx, label);
+        self.bg.end_quad(cx);
+    }
+}
@@ -0,0 +1,861 @@
+use makepad_render::*;
+use crate::scrollview::*;
+use crate::scrollbar::*;
+use crate::scrollshadow::*;
+use crate::listlogic::*;
+
+// where a node sits in the tree, the child index on every level
+pub type TreePath = Vec<usize>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TreeChildren<T> {
+    // a leaf
+    None,
+    // a folder that asks for its children the first time it opens
+    Unloaded,
+    Loading,
+    Loaded(Vec<TreeNode<T>>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode<T> {
+    pub name: String,
+    pub value: T,
+    pub open: bool,
+    pub children: TreeChildren<T>
+}
+
+// one visible line of the tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeRow {
+    pub path: TreePath,
+    pub depth: usize,
+    pub is_folder: bool,
+    pub is_open: bool,
+    pub is_loading: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeDropPos {
+    Before,
+    Into,
+    After
+}
+
+#[derive(Clone, PartialEq)]
+pub enum TreeViewEvent {
+    None,
+    Select {paths: Vec<TreePath>},
+    // return or a double click on a leaf
+    Activate {path: TreePath},
+    // a lazy folder opened, answer with set_children
+    LoadChildren {path: TreePath},
+    // the selection got dropped inside the tree, apply it with move_nodes if the move is ok
+    Reorder {paths: Vec<TreePath>, target: TreePath, pos: TreeDropPos},
+    DragMove {fe: FingerMoveEvent, paths: Vec<TreePath>},
+    DragEnd {fe: FingerUpEvent, paths: Vec<TreePath>},
+    DragCancel,
+}
+
+impl<T> TreeNode<T> {
+    pub fn leaf(name: &str, value: T) -> Self {
+        Self {name: name.to_string(), value, open: false, children: TreeChildren::None}
+    }
+    
+    pub fn folder(name: &str, value: T, children: Vec<TreeNode<T>>) -> Self {
+        Self {name: name.to_string(), value, open: false, children: TreeChildren::Loaded(children)}
+    }
+    
+    pub fn lazy(name: &str, value: T) -> Self {
+        Self {name: name.to_string(), value, open: false, children: TreeChildren::Unloaded}
+    }
+    
+    pub fn with_open(self, open: bool) -> Self {
+        Self {open, ..self}
+    }
+    
+    pub fn is_folder(&self) -> bool {
+        match self.children {
+            TreeChildren::None => false,
+            _ => true
+        }
+    }
+    
+    pub fn get<'a>(roots: &'a [TreeNode<T>], path: &[usize]) -> Option<&'a TreeNode<T>> {
+        let (first, rest) = path.split_first() ?;
+        let mut node = roots.get(*first) ?;
+        for index in rest {
+            node = match &node.children {
+                TreeChildren::Loaded(children) => children.get(*index) ?,
+                _ => return None
+            }
+        }
+        Some(node)
+    }
+    
+    pub fn get_mut<'a>(roots: &'a mut [TreeNode<T>], path: &[usize]) -> Option<&'a mut TreeNode<T>> {
+        let (first, rest) = path.split_first() ?;
+        let mut node = roots.get_mut(*first) ?;
+        for index in rest {
+            node = match &mut node.children {
+                TreeChildren::Loaded(children) => children.get_mut(*index) ?,
+                _ => return None
+            }
+        }
+        Some(node)
+    }
+    
+    fn children_mut<'a>(roots: &'a mut Vec<TreeNode<T>>, parent: &[usize]) -> Option<&'a mut Vec<TreeNode<T>>> {
+        if parent.len() == 0 {
+            return Some(roots)
+        }
+        match &mut Self::get_mut(roots, parent) ?.children {
+            TreeChildren::Loaded(children) => Some(children),
+            _ => None
+        }
+    }
+    
+    // the rows to draw. A filter keeps the nodes whose name contains it, case insensitive,
+    // and opens the folders on the way to them
+    pub fn flatten(roots: &[TreeNode<T>], filter: &str) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        Self::flatten_level(roots, &filter.to_lowercase(), &mut Vec::new(), &mut rows);
+        rows
+    }
+    
+    fn flatten_level(nodes: &[TreeNode<T>], filter: &str, path: &mut TreePath, rows: &mut Vec<TreeRow>) -> bool {
+        let mut any_match = false;
+        for (index, node) in nodes.iter().enumerate() {
+            path.push(index);
+            let row = rows.len();
+            rows.push(TreeRow {
+                path: path.clone(),
+                depth: path.len() - 1,
+                is_folder: node.is_folder(),
+                is_open: node.open,
+                is_loading: if let TreeChildren::Loading = node.children {true} else {false}
+            });
+            let is_match = filter.len() == 0 || node.name.to_lowercase().contains(filter);
+            let child_match = match &node.children {
+                TreeChildren::Loaded(children) if node.open || filter.len() > 0 => Self::flatten_level(children, filter, path, rows),
+                _ => false
+            };
+            if filter.len() > 0 {
+                if child_match {
+                    rows[row].is_open = true;
+                }
+                else {
+                    rows[row].is_open = false;
+                    if !is_match {
+                        rows.truncate(row);
+                    }
+                }
+            }
+            any_match |= is_match || child_match;
+            path.pop();
+        }
+        any_match
+    }
+    
+    // nested paths travel with their parent, so only the outermost ones count
+    fn outermost_paths(paths: &[TreePath]) -> Vec<TreePath> {
+        let mut paths: Vec<TreePath> = paths.iter().filter( | p | !paths.iter().any( | q | q.len() < p.len() && p.starts_with(q))).cloned().collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+    
+    // a node can't go next to or into itself or its own children, and only into folders that are loaded
+    pub fn can_move(roots: &[TreeNode<T>], paths: &[TreePath], target: &[usize], pos: TreeDropPos) -> bool {
+        let target_node = if let Some(node) = Self::get(roots, target) {node} else {return false};
+        if pos == TreeDropPos::Into {
+            if let TreeChildren::Loaded(_) = target_node.children {} else {return false}
+        }
+        paths.len() > 0
+            && paths.iter().all( | p | Self::get(roots, p).is_some())
+            && !paths.iter().any( | p | target.starts_with(p))
+    }
+    
+    // moves the nodes before, after or into the target keeping their order, returns their new paths
+    pub fn move_nodes(roots: &mut Vec<TreeNode<T>>, paths: &[TreePath], target: &[usize], pos: TreeDropPos) -> Option<Vec<TreePath>> {
+        if !Self::can_move(roots, paths, target, pos) {
+            return None
+        }
+        let paths = Self::outermost_paths(paths);
+        // take them out back to front so the paths that are left stay valid
+        let mut nodes = Vec::new();
+        for path in paths.iter().rev() {
+            let (index, parent) = path.split_last() ?;
+            nodes.push(Self::children_mut(roots, parent) ?.remove(*index));
+        }
+        nodes.reverse();
+        // the target shifts up for every node taken out before it under the same parent
+        let mut new_target = target.to_vec();
+        for level in 0..target.len() {
+            new_target[level] -= paths.iter().filter( | p | {
+                p.len() == level + 1 && p[..level] == target[..level] && p[level] < target[level]
+            }).count();
+        }
+        let (parent, index) = match pos {
+            TreeDropPos::Before => (new_target[..new_target.len() - 1].to_vec(), new_target[new_target.len() - 1]),
+            TreeDropPos::After => (new_target[..new_target.len() - 1].to_vec(), new_target[new_target.len() - 1] + 1),
+            TreeDropPos::Into => {
+                let folder = Self::get_mut(roots, &new_target) ?;
+                folder.open = true;
+                let len = if let TreeChildren::Loaded(children) = &folder.children {children.len()} else {0};
+                (new_target, len)
+            }
+        };
+        let children = Self::children_mut(roots, &parent) ?;
+        let mut moved = Vec::new();
+        for (i, node) in nodes.into_iter().enumerate() {
+            children.insert(index + i, node);
+            let mut path = parent.clone();
+            path.push(index + i);
+            moved.push(path);
+        }
+        Some(moved)
+    }
+}
+
+#[derive(Clone, DrawQuad)]
+#[repr(C)]
+pub struct DrawTreeArrow {
+    #[default_shader(self::shader_arrow)]
+    base: DrawColor,
+    open: f32,
+}
+
+#[derive(Clone)]
+pub struct TreeView<T> {
+    pub view: ScrollView,
+    pub list: ListLogic,
+    pub roots: Vec<TreeNode<T>>,
+    pub rows: Vec<TreeRow>,
+    pub selected: Vec<TreePath>,
+    pub filter: String,
+    pub name: String,
+    
+    pub row_bg: DrawColor,
+    pub arrow: DrawTreeArrow,
+    pub text: DrawText,
+    pub drop_marker: DrawColor,
+    pub shadow: ScrollShadow,
+    
+    pub label_view: View,
+    pub label_bg: DrawColor,
+    pub label_text: DrawText,
+    
+    pub _rows_dirty: bool,
+    pub _drag_move: Option<FingerMoveEvent>,
+    pub _drop_target: Option<(usize, TreeDropPos)>,
+}
+
+impl<T> TreeView<T> {
+    pub fn new(cx: &mut Cx) -> Self {
+        Self {
+            view: ScrollView::new()
+                .with_scroll_v(ScrollBar::new(cx).with_smoothing(Spring::critical(1000.0))),
+            list: ListLogic::default()
+                .with_multi_select(true),
+            roots: Vec::new(),
+            rows: Vec::new(),
+            selected: Vec::new(),
+            filter: String::new(),
+            name: String::new(),
+            
+            row_bg: DrawColor::new(cx, default_shader!()),
+            arrow: DrawTreeArrow::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            text: DrawText::new(cx, default_shader!())
+                .with_draw_depth(0.1),
+            drop_marker: DrawColor::new(cx, live_shader!(cx, self::shader_drop_marker))
+                .with_draw_depth(0.2),
+            shadow: ScrollShadow::new(cx)
+                .with_draw_depth(0.25),
+            
+            label_view: View::new()
+                .with_is_overlay(true),
+            label_bg: DrawColor::new(cx, live_shader!(cx, self::shader_label_bg)),
+            label_text: DrawText::new(cx, default_shader!()),
+            
+            _rows_dirty: true,
+            _drag_move: None,
+            _drop_target: None,
+        }
+    }
+    
+    pub fn with_name(self, name: &str) -> Self {
+        Self {name: name.to_string(), ..self}
+    }
+    
+    pub fn style(cx: &mut Cx) {
+        DrawTreeArrow::register_draw_input(cx);
+        live_body!(cx, {
+            self::indent: 12.0;
+            self::drag_distance: 10.0;
+            self::color_folder: #f;
+            self::color_leaf: #9D9D9D;
+            self::color_arrow: #9;
+            self::color_drop_marker: #x5a9bd6;
+            self::color_label_bg: #x11466e;
+            self::color_label_text: #f;
+            self::color_bg_marked: #x11466e;
+            self::color_bg_selected: #x28;
+            self::color_bg_odd: #25;
+            self::color_bg_marked_over: #x11466e;
+            self::color_bg_selected_over: #3d;
+            self::color_bg_odd_over: #38;
+            
+            self::layout_row: Layout {
+                walk: Walk {width: Fill, height: Fix(20.)},
+                align: {fx: 0.0, fy: 0.5},
+                padding: {l: 5., t: 0., r: 0., b: 1.},
+            }
+            
+            self::layout_label: Layout {
+                padding: {l: 5., t: 5., r: 5., b: 5.},
+                walk: {
+                    width: Compute,
+                    height: Compute
+                }
+            }
+            
+            self::walk_arrow: Walk {
+                width: Fix(12.),
+                height: Fill,
+                margin: {l: 0., t: 0., r: 2., b: 0.}
+            }
+            
+            self::text_style_label: TextStyle {
+                top_drop: 1.3,
+                ..crate::widgetstyle::text_style_normal
+            }
+            
+            self::shader_arrow: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                draw_input: self::DrawTreeArrow;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    let c = rect_size * 0.5;
+                    let sz = 3.0;
+                    if open > 0.5 {
+                        df.move_to(c.x - sz, c.y - 0.5 * sz);
+                        df.line_to(c.x + sz, c.y - 0.5 * sz);
+                        df.line_to(c.x, c.y + sz);
+                    }
+                    else {
+                        df.move_to(c.x - 0.5 * sz, c.y - sz);
+                        df.line_to(c.x + sz, c.y);
+                        df.line_to(c.x - 0.5 * sz, c.y + sz);
+                    }
+                    df.close_path();
+                    return df.fill(color);
+                }
+            }
+            
+            self::shader_drop_marker: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0.5, 0.5, rect_size.x - 1., rect_size.y - 1., 1.);
+                    return df.stroke(color, 1.);
+                }
+            }
+            
+            self::shader_label_bg: Shader {
+                use makepad_render::drawcolor::shader::*;
+                
+                fn pixel() -> vec4 {
+                    let df = Df::viewport(pos * rect_size);
+                    df.box(0., 0., rect_size.x, rect_size.y, 2.);
+                    return df.fill(color);
+                }
+            }
+        })
+    }
+    
+    pub fn get_default_anim(cx: &Cx, counter: usize, marked: bool) -> Anim {
+        let default_color = if marked {
+            live_vec4!(cx, self::color_bg_marked)
+        } else if counter & 1 == 0 {
+            live_vec4!(cx, self::color_bg_selected)
+        } else {
+            live_vec4!(cx, self::color_bg_odd)
+        };
+        Anim {
+            play: Play::Chain {duration: 0.01},
+            tracks: vec![
+                Track::Vec4 {
+                    bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
+                    ease: Ease::Lin,
+                    keys: vec![(1.0, default_color)],
+                    cut_init: None,
+                    curves: vec![]
+                }
+            ],
+            events: vec![]
+        }
+    }
+    
+    pub fn get_over_anim(cx: &Cx, counter: usize, marked: bool) -> Anim {
+        let over_color = if marked {
+            live_vec4!(cx, self::color_bg_marked_over)
+        } else if counter & 1 == 0 {
+            live_vec4!(cx, self::color_bg_selected_over)
+        } else {
+            live_vec4!(cx, self::color_bg_odd_over)
+        };
+        Anim {
+            play: Play::Cut {duration: 0.02},
+            tracks: vec![
+                Track::Vec4 {
+                    bind_to: live_item_id!(makepad_render::drawcolor::DrawColor::color),
+                    ease: Ease::Lin,
+                    keys: vec![(0., over_color), (1., over_color)],
+                    cut_init: None,
+                    curves: vec![]
+                }
+            ],
+            events: vec![]
+        }
+    }
+    
+    pub fn set_roots(&mut self, cx: &mut Cx, roots: Vec<TreeNode<T>>) {
+        self.roots = roots;
+        self.selected.truncate(0);
+        self.rows_changed(cx);
+    }
+    
+    // fills in a folder that sent LoadChildren
+    pub fn set_children(&mut self, cx: &mut Cx, path: &[usize], children: Vec<TreeNode<T>>) {
+        if let Some(node) = TreeNode::get_mut(&mut self.roots, path) {
+            node.children = TreeChildren::Loaded(children);
+            self.rows_changed(cx);
+        }
+    }
+    
+    pub fn set_open(&mut self, cx: &mut Cx, path: &[usize], open: bool) -> TreeViewEvent {
+        let node = match TreeNode::get_mut(&mut self.roots, path) {
+            Some(node) if node.is_folder() => node,
+            _ => return TreeViewEvent::None
+        };
+        node.open = open;
+        let load = open && if let TreeChildren::Unloaded = node.children {true} else {false};
+        if load {
+            node.children = TreeChildren::Loading;
+        }
+        self.rows_changed(cx);
+        if load {
+            return TreeViewEvent::LoadChildren {path: path.to_vec()}
+        }
+        TreeViewEvent::None
+    }
+    
+    pub fn set_filter(&mut self, cx: &mut Cx, filter: &str) {
+        self.filter = filter.to_string();
+        self.rows_changed(cx);
+        self.label_view.redraw_view(cx);
+    }
+    
+    // applies a Reorder, returns false when the nodes can't go there
+    pub fn move_nodes(&mut self, cx: &mut Cx, paths: &[TreePath], target: &[usize], pos: TreeDropPos) -> bool {
+        if let Some(moved) = TreeNode::move_nodes(&mut self.roots, paths, target, pos) {
+            self.selected = moved;
+            self.rows_changed(cx);
+            return true
+        }
+        false
+    }
+    
+    pub fn selected_nodes(&self) -> Vec<&TreeNode<T>> {
+        self.selected.iter().filter_map( | path | TreeNode::get(&self.roots, path)).collect()
+    }
+    
+    pub fn rows_changed(&mut self, cx: &mut Cx) {
+        self._rows_dirty = true;
+        self.view.redraw_view(cx);
+    }
+    
+    // rebuilds the rows and puts the selection back on the paths that are still visible
+    fn update_rows(&mut self) {
+        if !self._rows_dirty {
+            return
+        }
+        self._rows_dirty = false;
+        self.rows = TreeNode::flatten(&self.roots, &self.filter);
+        self.list.list_items.truncate(0);
+        self.list.set_list_len(self.rows.len());
+        self.list.selection.truncate(0);
+        let mut selected = Vec::new();
+        for (index, row) in self.rows.iter().enumerate() {
+            if self.selected.contains(&row.path) {
+                self.list.list_items[index].is_selected = true;
+                self.list.selection.push(index);
+                selected.push(row.path.clone());
+            }
+        }
+        self.selected = selected;
+        self._drop_target = None;
+    }
+    
+    fn cursor_row(&self) -> Option<usize> {
+        self.list.selection.last().cloned().filter( | row | *row < self.rows.len())
+    }
+    
+    fn find_drop_target(&self, cx: &Cx, abs: Vec2) -> Option<(usize, TreeDropPos)> {
+        for index in self.list.start_item..self.list.end_item.min(self.rows.len()) {
+            let rect = self.list.list_items[index].area.get_rect(cx);
+            if !rect.contains(abs) {
+                continue
+            }
+            let row = &self.rows[index];
+            let fy = (abs.y - rect.pos.y) / rect.size.y;
+            let pos = if row.is_folder {
+                if fy < 0.25 {TreeDropPos::Before} else if fy > 0.75 {TreeDropPos::After} else {TreeDropPos::Into}
+            }
+            else {
+                if fy < 0.5 {TreeDropPos::Before} else {TreeDropPos::After}
+            };
+            if TreeNode::can_move(&self.roots, &self.selected, &row.path, pos) {
+                return Some((index, pos))
+            }
+            return None
+        }
+        None
+    }
+    
+    fn handle_drag_move(&mut self, cx: &mut Cx, fe: FingerMoveEvent) -> TreeViewEvent {
+        if self._drag_move.is_none() && fe.move_distance() < live_float!(cx, self::drag_distance) {
+            return TreeViewEvent::None
+        }
+        cx.set_down_mouse_cursor(MouseCursor::Hand);
+        self._drag_move = Some(fe.clone());
+        self.view.redraw_view(cx);
+        self.label_view.redraw_view(cx);
+        if self.view.area().get_rect(cx).contains(fe.abs) {
+            self._drop_target = self.find_drop_target(cx, fe.abs);
+            return TreeViewEvent::DragCancel
+        }
+        self._drop_target = None;
+        TreeViewEvent::DragMove {fe, paths: self.selected.clone()}
+    }
+    
+    fn handle_drag_end(&mut self, cx: &mut Cx, fe: FingerUpEvent) -> TreeViewEvent {
+        if self._drag_move.take().is_none() {
+            return TreeViewEvent::None
+        }
+        self.view.redraw_view(cx);
+        self.label_view.redraw_view(cx);
+        let drop_target = self._drop_target.take();
+        if !self.view.area().get_rect(cx).contains(fe.abs) {
+            return TreeViewEvent::DragEnd {fe, paths: self.selected.clone()}
+        }
+        if let Some((row, pos)) = drop_target {
+            return TreeViewEvent::Reorder {paths: self.selected.clone(), target: self.rows[row].path.clone(), pos}
+        }
+        TreeViewEvent::None
+    }
+    
+    pub fn handle_tree_view(&mut self, cx: &mut Cx, event: &mut Event) -> TreeViewEvent {
+        self.update_rows();
+        self.list.handle_list_scroll_bars(cx, event, &mut self.view);
+        
+        let mut ret = TreeViewEvent::None;
+        
+        // dragging the selection, the list logic only cares about the finger going down
+        if let Event::FingerMove(_) | Event::FingerUp(_) = event {
+            for index in self.list.start_item..self.list.end_item.min(self.rows.len()) {
+                match event.hits(cx, self.list.list_items[index].area, HitOpt::default()) {
+                    Event::FingerMove(fe) => {
+                        ret = self.handle_drag_move(cx, fe);
+                        break;
+                    },
+                    Event::FingerUp(fe) => {
+                        ret = self.handle_drag_end(cx, fe);
+                        break;
+                    },
+                    _ => ()
+                }
+            }
+        }
+        
+        // only keys and text go to the view, a finger hitting it would be captured before the rows see it
+        let key_event = match event {
+            Event::KeyDown(_) | Event::TextInput(_) => event.hits(cx, self.view.area(), HitOpt::default()),
+            _ => Event::None
+        };
+        let mut select = ListSelect::None;
+        if let Event::KeyDown(ke) = &key_event {
+            let last = self.rows.len().max(1) - 1;
+            match cx.keymap_command(KEYMAP_TREE_VIEW, ke).as_deref() {
+                Some("tree_view.next") => select = self.list.get_next_single_selection(),
+                Some("tree_view.prev") => select = self.list.get_prev_single_selection(),
+                Some("tree_view.select_next") => if let Some(row) = self.list.selection.last() {
+                    select = ListSelect::Range((row + 1).min(last));
+                },
+                Some("tree_view.select_prev") => if let Some(row) = self.list.selection.first() {
+                    select = ListSelect::Range(row.max(&1) - 1);
+                },
+                Some("tree_view.select_all") => select = ListSelect::All,
+                Some("tree_view.collapse") => if let Some(row) = self.cursor_row() {
+                    let row = self.rows[row].clone();
+                    if row.is_folder && row.is_open && self.filter.len() == 0 {
+                        ret = self.set_open(cx, &row.path, false);
+                    }
+                    else if let Some(parent) = self.rows.iter().position( | r | r.path[..] == row.path[..row.path.len() - 1]) {
+                        select = ListSelect::Single(parent);
+                    }
+                },
+                Some("tree_view.expand") => if let Some(row) = self.cursor_row() {
+                    let next = row + 1;
+                    let row = self.rows[row].clone();
+                    if row.is_folder && !row.is_open {
+                        ret = self.set_open(cx, &row.path, true);
+                    }
+                    else if next < self.rows.len() && self.rows[next].depth > row.depth {
+                        select = ListSelect::Single(next);
+                    }
+                },
+                Some("tree_view.activate") => if let Some(row) = self.cursor_row() {
+                    let row = self.rows[row].clone();
+                    if row.is_folder {
+                        ret = self.set_open(cx, &row.path, !row.is_open);
+                    }
+                    else {
+                        ret = TreeViewEvent::Activate {path: row.path};
+                    }
+                },
+                Some("tree_view.filter_clear") => if self.filter.len() > 0 {
+                    self.set_filter(cx, "");
+                },
+                Some("tree_view.filter_back") => if self.filter.len() > 0 {
+                    let mut filter = self.filter.clone();
+                    filter.pop();
+                    self.set_filter(cx, &filter);
+                },
+                _ => ()
+            }
+            self.list.scroll_item_in_view = match select {
+                ListSelect::Single(row) | ListSelect::Range(row) => Some(row),
+                _ => None
+            };
+        }
+        
+        // typing narrows the tree down
+        if let Event::TextInput(te) = &key_event {
+            let input: String = te.input.chars().filter( | c | !c.is_control()).collect();
+            if input.len() > 0 {
+                let filter = format!("{}{}", self.filter, input);
+                self.set_filter(cx, &filter);
+            }
+        }
+        
+        // the filter can have changed the rows under the keys
+        self.update_rows();
+        let from_keys = select != ListSelect::None;
+        let row_bg = &mut self.row_bg;
+        let le = self.list.handle_list_logic(cx, event, select, false, | cx, item_event, item, item_index | match item_event {
+            ListLogicEvent::Animate(ae) => {
+                row_bg.set_area(item.area);
+                row_bg.animate(cx, &mut item.animator, ae.time);
+            },
+            ListLogicEvent::AnimEnded => {
+                item.animator.end();
+            },
+            ListLogicEvent::Select => {
+                item.animator.play_anim(cx, Self::get_over_anim(cx, item_index, true));
+            },
+            ListLogicEvent::Deselect => {
+                item.animator.play_anim(cx, Self::get_default_anim(cx, item_index, false));
+            },
+            ListLogicEvent::Cleanup => {
+                item.animator.play_anim(cx, Self::get_default_anim(cx, item_index, item.is_selected));
+            },
+            ListLogicEvent::Over => {
+                item.animator.play_anim(cx, Self::get_over_anim(cx, item_index, item.is_selected));
+            },
+            ListLogicEvent::Out => {
+                item.animator.play_anim(cx, Self::get_default_anim(cx, item_index, item.is_selected));
+            }
+        });
+        
+        let rows = &self.rows;
+        let le_row = match le {
+            ListEvent::SelectSingle(row) | ListEvent::SelectDouble(row) => Some(row),
+            ListEvent::SelectMultiple => None,
+            ListEvent::None => return ret
+        };
+        self.selected = self.list.selection.iter().filter_map( | row | rows.get(*row)).map( | row | row.path.clone()).collect();
+        self.view.redraw_view(cx);
+        if !from_keys {
+            // keys go to the tree once it has been clicked
+            cx.set_key_focus(self.view.area());
+        }
+        if let Some(row) = le_row.filter( | _ | !from_keys).and_then( | row | self.rows.get(row)).cloned() {
+            // a click folds a folder, a double click opens a leaf
+            if row.is_folder {
+                if let TreeViewEvent::LoadChildren {path} = self.set_open(cx, &row.path, !row.is_open) {
+                    return TreeViewEvent::LoadChildren {path}
+                }
+            }
+            else if let ListEvent::SelectDouble(_) = le {
+                return TreeViewEvent::Activate {path: row.path}
+            }
+        }
+        if let TreeViewEvent::None = ret {
+            return TreeViewEvent::Select {paths: self.selected.clone()}
+        }
+        ret
+    }
+    
+    fn draw_filler(&mut self, cx: &mut Cx, counter: usize, walk: Walk) {
+        let view_total = cx.get_turtle_bounds();
+        self.row_bg.color = if counter & 1 == 0 {
+            live_vec4!(cx, self::color_bg_selected)
+        } else {
+            live_vec4!(cx, self::color_bg_odd)
+        };
+        self.row_bg.draw_quad_walk(cx, walk);
+        cx.set_turtle_bounds(view_total);
+    }
+    
+    fn draw_label(&mut self, cx: &mut Cx, text: &str) {
+        self.label_bg.color = live_vec4!(cx, self::color_label_bg);
+        self.label_bg.begin_quad(cx, live_layout!(cx, self::layout_label));
+        self.label_text.color = live_vec4!(cx, self::color_label_text);
+        self.label_text.draw_text_walk(cx, text);
+        self.label_bg.end_quad(cx);
+    }
+    
+    pub fn draw_tree_view(&mut self, cx: &mut Cx) {
+        self.update_rows();
+        
+        let layout_row = live_layout!(cx, self::layout_row);
+        let row_height = layout_row.walk.height.fixed();
+        let indent = live_float!(cx, self::indent);
+        let walk_arrow = live_walk!(cx, self::walk_arrow);
+        self.text.text_style = live_text_style!(cx, self::text_style_label);
+        self.label_text.text_style = live_text_style!(cx, self::text_style_label);
+        self.arrow.base.color = live_vec4!(cx, self::color_arrow);
+        self.drop_marker.color = live_vec4!(cx, self::color_drop_marker);
+        
+        if self.list.begin_list(cx, &mut self.view, false, row_height).is_err() {return}
+        let view_rect = cx.get_turtle_rect();
+        
+        cx.begin_access_node(AccessNode::new(AccessRole::Tree, self.view.area()).with_name(&self.name));
+        let mut access_stack = Vec::new();
+        
+        for index in self.list.start_item..self.list.end_item {
+            let row = &self.rows[index];
+            let node = if let Some(node) = TreeNode::get(&self.roots, &row.path) {node} else {continue};
+            let item = &mut self.list.list_items[index];
+            
+            // close the folders we walked out of
+            while access_stack.last().map_or(false, | d | *d >= row.depth) {
+                access_stack.pop();
+                cx.end_access_node();
+            }
+            
+            if item.animator.need_init(cx) {
+                item.animator.init(cx, Self::get_default_anim(cx, index, item.is_selected));
+            }
+            self.row_bg.set_area(item.area);
+            self.row_bg.last_animate(&item.animator);
+            
+            let row_pos = cx.get_turtle_pos();
+            self.row_bg.begin_quad(cx, layout_row);
+            item.area = self.row_bg.area();
+            
+            let row_indent = row.depth as f32 * indent;
+            cx.walk_turtle(Walk::wh(Width::Fix(row_indent), Height::Fill));
+            if row.is_folder {
+                self.arrow.open = if row.is_open {1.0} else {0.0};
+                self.arrow.draw_quad_walk(cx, walk_arrow);
+            }
+            else {
+                cx.walk_turtle(walk_arrow);
+            }
+            cx.turtle_align_y();
+            
+            self.text.color = if row.is_folder || item.is_selected {
+                live_vec4!(cx, self::color_folder)
+            }
+            else {
+                live_vec4!(cx, self::color_leaf)
+            };
+            let wleft = cx.get_width_left() - 10.;
+            self.text.wrapping = Wrapping::Ellipsis(wleft);
+            if row.is_loading {
+                self.text.draw_text_walk(cx, &format!("{} ...", node.name));
+            }
+            else {
+                self.text.draw_text_walk(cx, &node.name);
+            }
+            
+            self.row_bg.end_quad(cx);
+            item.area = self.row_bg.area();
+            
+            let access = AccessNode::new(AccessRole::TreeItem, item.area).with_name(&node.name).with_selected(item.is_selected);
+            if row.is_folder {
+                cx.begin_access_node(access.with_expanded(row.is_open));
+                access_stack.push(row.depth);
+            }
+            else {
+                cx.add_access_node(access);
+            }
+            
+            // where the drag would land
+            if let Some((drop_row, pos)) = self._drop_target {
+                if drop_row == index {
+                    let x = row_pos.x + row_indent;
+                    let w = view_rect.size.x - row_indent;
+                    self.drop_marker.draw_quad_abs(cx, match pos {
+                        TreeDropPos::Before => Rect {pos: vec2(x, row_pos.y - 1.), size: vec2(w, 2.)},
+                        TreeDropPos::After => Rect {pos: vec2(x, row_pos.y + row_height - 1.), size: vec2(w, 2.)},
+                        TreeDropPos::Into => Rect {pos: vec2(x, row_pos.y), size: vec2(w, row_height)},
+                    });
+                }
+            }
+        }
+        for _ in access_stack {
+            cx.end_access_node();
+        }
+        
+        self.list.walk_turtle_to_end(cx, row_height);
+        
+        // draw filler nodes
+        for counter in self.list.end_item..self.list.end_fill {
+            self.draw_filler(cx, counter, layout_row.walk);
+        }
+        
+        // the dragged names follow the finger, otherwise the filter shows in the corner
+        if let Some(fe) = &self._drag_move {
+            let abs_origin = Some(vec2(fe.abs.x + 5., fe.abs.y + 5.));
+            if self.label_view.begin_view(cx, Layout {abs_origin, ..Layout::default()}).is_ok() {
+                let names: Vec<String> = self.selected_nodes().iter().map( | node | node.name.clone()).collect();
+                for name in names {
+                    self.draw_label(cx, &name);
+                    cx.turtle_new_line();
+                }
+                self.label_view.end_view(cx);
+            }
+        }
+        else if self.filter.len() > 0 {
+            let abs_origin = Some(vec2(view_rect.pos.x + 4., view_rect.pos.y + 4.));
+            if self.label_view.begin_view(cx, Layout {abs_origin, ..Layout::default()}).is_ok() {
+                let filter = self.filter.clone();
+                self.draw_label(cx, &filter);
+                self.label_view.end_view(cx);
+            }
+        }
+        
+        self.shadow.draw_shadow_top(cx);
+        
+        cx.end_access_node();
+        self.list.end_list(cx, &mut self.view);
+    }
+}
@@ -14,6 +14,8 @@ pub struct Viewport3D {
     pub camera_pos: Vec3,
     pub camera_rot: Vec3,
     pub camera_start: Option<(Vec3, Vec3)>,
+    pub pan: PanRecognizer,
+    pub pinch: PinchRecognizer,
     pub image: DrawImage
 }
 
@@ -25,6 +27,8 @@ impl Viewport3D {
             camera_pos: Vec3 {x: 0.0, y: -0.5, z: -1.1},
             camera_rot: Vec3 {x: 0.0, y: 0.0, z: 0.0},
             camera_start: None,
+            pan: PanRecognizer {min_distance: 0.0, ..PanRecognizer::new()},
+            pinch: PinchRecognizer::new(),
             clear_color: Vec4::color("fff"),
             color_texture: Texture::new(cx),
             depth_texture: Texture::new(cx),
@@ -42,36 +46,48 @@ impl Viewport3D {
     }
      
     pub fn handle_viewport_2d(&mut self, cx: &mut Cx, event: &mut Event) {
-        match event.hits(cx, self.view_2d.area(), HitOpt::default()) {
+        let event = event.hits(cx, self.view_2d.area(), HitOpt::multi_touch());
+        match &event {
             Event::FingerHover(_fe) => {
                 cx.set_hover_mouse_cursor(MouseCursor::Move);
             },
-            Event::FingerDown(_fe) => { 
-                
+            Event::FingerDown(_fe) => {
                 cx.set_down_mouse_cursor(MouseCursor::Move);
-                self.camera_start = Some((self.camera_pos, self.camera_rot));
-            }, 
-            Event::FingerUp(_fe) => {
-            } 
+            },
             Event::FingerScroll(fe) => {
-                self.camera_pos.z += fe.scroll.y / 300.0;
-                self.camera_center.z = -self.camera_pos.z + 1.5;
-                self.pass_set_matrix_mode(cx);
+                self.zoom_camera(cx, fe.scroll.y / 300.0);
             }
-            Event::FingerMove(fe) => {
-                if let Some((_pos, rot)) = self.camera_start {
-                    self.camera_rot = Vec3 {
-                        x: rot.x + (fe.abs.y - fe.abs_start.y),
-                        y: rot.y + (fe.abs.x - fe.abs_start.x),
-                        z: rot.z
-                    };
-                    self.pass_set_matrix_mode(cx)
-                }
+            _ => ()
+        }
+        // two fingers zoom, one rotates
+        if let PinchEvent::Pinch {delta, ..} = self.pinch.handle_finger_event(&event) {
+            self.zoom_camera(cx, delta - 1.0);
+        }
+        match self.pan.handle_finger_event(&event) {
+            PanEvent::Start {..} => {
+                self.camera_start = Some((self.camera_pos, self.camera_rot));
+            },
+            PanEvent::Move {total, ..} => if let Some((_pos, rot)) = self.camera_start {
+                self.camera_rot = Vec3 {
+                    x: rot.x + total.y,
+                    y: rot.y + total.x,
+                    z: rot.z
+                };
+                self.pass_set_matrix_mode(cx)
+            },
+            PanEvent::End {..} => {
+                self.camera_start = None;
             },
             _ => ()
         }
     }
     
+    fn zoom_camera(&mut self, cx: &mut Cx, delta: f32) {
+        self.camera_pos.z += delta;
+        self.camera_center.z = -self.camera_pos.z + 1.5;
+        self.pass_set_matrix_mode(cx);
+    }
+    
     pub fn pass_set_matrix_mode(&mut self, cx: &mut Cx) {
         //self.pass.set_matrix_mode(cx, PassMatrixMode::Ortho);
         
@@ -42,8 +42,19 @@ pub fn set_widget_style(cx: &mut Cx) {
     crate::desktopbutton::DesktopButton::style(cx);
     crate::splitter::Splitter::style(cx);
     crate::tabcontrol::TabControl::style(cx);
+    crate::dock::Dock::<()>::style(cx);
     crate::xrcontrol::XRControl::style(cx);
     crate::viewport3d::Viewport3D::style(cx);
+    crate::treeview::TreeView::<()>::style(cx);
+    crate::datagrid::DataGrid::style(cx);
+    crate::contextmenu::ContextMenu::style(cx);
+    crate::tooltip::Tooltip::style(cx);
+    crate::dropdown::DropDown::style(cx);
+    crate::modal::Modal::style(cx);
+    crate::checkbox::CheckBox::style(cx);
+    crate::floatslider::FloatSlider::style(cx);
+    crate::spinner::Spinner::style(cx);
+    crate::colorpicker::ColorPicker::style(cx);
 }
 
   /*
@@ -0,0 +1,113 @@
+// clicking a checkbox and a toggle on and off, and radios in a group switching each other off
+use makepad_render::*;
+use makepad_widget::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    check: CheckBox,
+    toggle: CheckBox,
+    radios: RadioGroup,
+    events: Vec<String>,
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window {create_inner_size: Some(vec2(120., 160.)), ..Window::new(cx)},
+            pass: Pass::default(),
+            view: View::new(),
+            check: CheckBox::new(cx),
+            toggle: CheckBox::new(cx).with_check_type(CheckType::Toggle).with_checked(true),
+            radios: RadioGroup::new(cx).with_selected(1),
+            events: Vec::new(),
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        if let CheckBoxEvent::Toggled(on) = self.check.handle_check_box(cx, event) {
+            self.events.push(format!("check {}", on));
+        }
+        if let CheckBoxEvent::Toggled(on) = self.toggle.handle_check_box(cx, event) {
+            self.events.push(format!("toggle {}", on));
+        }
+        if let RadioGroupEvent::Select(index) = self.radios.handle_radio_group(cx, event) {
+            self.events.push(format!("radio {}", index));
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout {direction: Direction::Down, ..Layout::default()}).is_ok() {
+            self.check.draw_check_box(cx, "check");
+            self.toggle.draw_check_box(cx, "toggle");
+            self.radios.draw_radio_group(cx, &["one", "two", "three"]);
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+    
+    fn radios_checked(&self) -> Vec<bool> {
+        (0..3).map( | index | self.radios.radios.get(index).unwrap().checked).collect()
+    }
+}
+
+fn run(cx: &mut Cx, app: &mut App) {
+    let mut cxafterdraw = CxAfterDraw::new(cx);
+    cx.platform.headless.window_size = Some(vec2(120., 160.));
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+    });
+}
+
+fn click(cx: &mut Cx, rect: Rect) {
+    let abs = rect.pos + rect.size * 0.5;
+    cx.headless_push_event(Event::FingerDown(FingerDownEvent {abs, ..FingerDownEvent::default()}));
+    cx.headless_push_event(Event::FingerUp(FingerUpEvent {abs, ..FingerUpEvent::default()}));
+}
+
+#[test]
+fn checks_toggles_and_radios() {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    run(&mut cx, &mut app);
+    assert_eq!((app.check.checked, app.toggle.checked), (false, true));
+    assert_eq!(app.radios_checked(), vec![false, true, false]);
+    
+    let check = app.check.bg.area().get_rect(&cx);
+    let toggle = app.toggle.bg.area().get_rect(&cx);
+    let radios: Vec<Rect> = (0..3).map( | index | app.radios.radios.get(index).unwrap().bg.area().get_rect(&cx)).collect();
+    // each on a line of its own
+    assert!(check.pos.y < toggle.pos.y && toggle.pos.y < radios[0].pos.y && radios[0].pos.y < radios[2].pos.y);
+    
+    click(&mut cx, check);
+    click(&mut cx, toggle);
+    click(&mut cx, check);
+    // a radio that is on stays on when clicked again
+    click(&mut cx, radios[1]);
+    click(&mut cx, radios[2]);
+    click(&mut cx, radios[0]);
+    run(&mut cx, &mut app);
+    
+    assert_eq!(app.events, vec!["check true", "toggle false", "check false", "radio 2", "radio 0"]);
+    assert_eq!((app.check.checked, app.toggle.checked), (false, false));
+    // only the last radio clicked is on
+    assert_eq!(app.radios_checked(), vec![true, false, false]);
+    assert_eq!(app.radios.selected, 0);
+    
+    // selecting from code switches the radios over the same way
+    app.radios.set_selected(&mut cx, 2);
+    assert_eq!(app.radios_checked(), vec![false, false, true]);
+}
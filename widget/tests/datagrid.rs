@@ -0,0 +1,183 @@
+// a DataGrid over a thousand rows without a window: which rows it asks for, resizing columns and sorting from the header
+use makepad_render::*;
+use makepad_widget::*;
+use std::cell::RefCell;
+
+struct Source {
+    values: Vec<u32>,
+    // the rows cell_text got asked for since the last draw
+    asked: RefCell<Vec<usize>>,
+    sorts: Vec<(usize, SortOrder)>,
+}
+
+impl GridDataSource for Source {
+    fn row_count(&self) -> usize {
+        self.values.len()
+    }
+    
+    fn cell_text(&self, row: usize, column: usize, out: &mut String) {
+        self.asked.borrow_mut().push(row);
+        out.push_str(&format!("{} {}", column, self.values[row]));
+    }
+    
+    fn sort_rows(&mut self, column: usize, order: SortOrder) {
+        self.sorts.push((column, order));
+        self.values.sort();
+        if order == SortOrder::Descending {
+            self.values.reverse();
+        }
+    }
+}
+
+struct App {
+    window: Window,
+    pass: Pass,
+    grid: DataGrid,
+    source: Source,
+    // every event the grid returned for an input, with the rows the draw before it asked for
+    steps: Vec<(DataGridEvent, usize, usize)>,
+    drawn: (usize, usize),
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        let mut grid = DataGrid::new(cx).with_columns(vec![
+            GridColumn::new("name", 80.),
+            GridColumn::new("value", 60.),
+            GridColumn::new("note", 60.).with_sortable(false),
+        ]);
+        // jumps land at once, every frame of a glide would be another paint
+        grid.view.scroll_v = Some(ScrollBar::new(cx));
+        Self {
+            // the first frame is drawn before the headless size applies, keep it small too
+            window: Window {create_inner_size: Some(vec2(200., 100.)), ..Window::new(cx)},
+            pass: Pass::default(),
+            grid: grid,
+            source: Source {
+                values: (0..1000).map( | i | (i * 7919) % 1000).collect(),
+                asked: RefCell::new(Vec::new()),
+                sorts: Vec::new(),
+            },
+            steps: Vec::new(),
+            drawn: (0, 0),
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        let is_input = match event {
+            Event::FingerDown(_) | Event::FingerMove(_) | Event::FingerUp(_) | Event::KeyDown(_) => true,
+            _ => false
+        };
+        let grid_event = self.grid.handle_data_grid(cx, event, &mut self.source);
+        if is_input {
+            self.steps.push((grid_event, self.drawn.0, self.drawn.1));
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        self.source.asked.borrow_mut().truncate(0);
+        self.grid.draw_data_grid(cx, &self.source);
+        let asked = self.source.asked.borrow();
+        self.drawn = (*asked.iter().min().unwrap(), *asked.iter().max().unwrap());
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn finger_down(x: f32, y: f32) -> Event {
+    Event::FingerDown(FingerDownEvent {abs: vec2(x, y), ..FingerDownEvent::default()})
+}
+
+fn finger_move(x: f32, y: f32) -> Event {
+    Event::FingerMove(FingerMoveEvent {abs: vec2(x, y), ..FingerMoveEvent::default()})
+}
+
+fn finger_up(x: f32, y: f32) -> Event {
+    Event::FingerUp(FingerUpEvent {abs: vec2(x, y), ..FingerUpEvent::default()})
+}
+
+fn key(key_code: KeyCode, control: bool) -> Event {
+    Event::KeyDown(KeyEvent {
+        key_code,
+        is_repeat: false,
+        modifiers: KeyModifiers {control, ..KeyModifiers::default()},
+        time: 0.0
+    })
+}
+
+#[test]
+fn rows_in_view_resize_and_sort() {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    // a header of 22 and rows of 20 leave room for 3 whole rows
+    cx.platform.headless.window_size = Some(vec2(200., 100.));
+    cx.headless_push_event(Event::None);
+    for event in vec![
+        // sorting from the value header, twice, then the note header that can't sort
+        finger_down(110., 10.),
+        finger_up(110., 10.),
+        finger_down(110., 10.),
+        finger_up(110., 10.),
+        finger_down(170., 10.),
+        finger_up(170., 10.),
+        // dragging the right edge of the value column out and then past its minimum
+        finger_down(140., 10.),
+        finger_move(180., 10.),
+        finger_move(0., 10.),
+        finger_up(0., 10.),
+        // the value column now spans 80 to 110, the third row
+        finger_down(100., 67.),
+        finger_up(100., 67.),
+        key(KeyCode::ArrowDown, true),
+        key(KeyCode::PageUp, false),
+    ] {
+        cx.headless_push_event(event);
+    }
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+    });
+    
+    let cell = | row, column | GridCell {row, column};
+    let select = | row, column | DataGridEvent::Select {selection: GridSelection::new(cell(row, column))};
+    let events: Vec<DataGridEvent> = app.steps.iter().map( | step | step.0.clone()).collect();
+    assert_eq!(events, vec![
+        DataGridEvent::Sort {column: 1, order: SortOrder::Ascending},
+        DataGridEvent::None,
+        DataGridEvent::Sort {column: 1, order: SortOrder::Descending},
+        DataGridEvent::None,
+        DataGridEvent::None,
+        DataGridEvent::None,
+        DataGridEvent::None,
+        DataGridEvent::ColumnResize {column: 1, width: 100.},
+        DataGridEvent::ColumnResize {column: 1, width: 30.},
+        DataGridEvent::None,
+        select(2, 1),
+        DataGridEvent::None,
+        select(999, 1),
+        select(996, 1),
+    ]);
+    
+    // the header went to the source, the column that can't sort never did
+    assert_eq!(app.source.sorts, vec![(1, SortOrder::Ascending), (1, SortOrder::Descending)]);
+    assert_eq!(app.grid.sort, Some((1, SortOrder::Descending)));
+    assert_eq!(app.source.values[0], 999);
+    assert_eq!(app.grid.columns[1].width, 30.);
+    
+    // at the top the rows in view and one more, at the bottom the last 4 and nothing past them
+    assert_eq!((app.steps[0].1, app.steps[0].2), (0, 4));
+    assert_eq!(app.drawn, (996, 999));
+    assert_eq!(app.grid._page_rows, 3);
+    assert_eq!(app.grid.selection, Some(GridSelection::new(cell(996, 1))));
+}
@@ -0,0 +1,130 @@
+// saving a dock tree to RON through a DockItemSerializer and loading it back, with tabs that no longer load left out
+use makepad_render::*;
+use makepad_widget::*;
+
+// a tab item that isn't SerRon itself
+#[derive(Clone, Debug, PartialEq)]
+enum Panel {
+    File(String),
+    Log
+}
+
+// files are saved as their path and only come back if they still exist
+struct Loader {
+    files: Vec<&'static str>
+}
+
+impl DockItemSerializer<Panel> for Loader {
+    fn serialize_item(&self, item: &Panel) -> String {
+        match item {
+            Panel::File(path) => format!("file:{}", path),
+            Panel::Log => "log".to_string()
+        }
+    }
+    
+    fn deserialize_item(&self, data: &str) -> Option<Panel> {
+        if data == "log" {
+            return Some(Panel::Log)
+        }
+        let path = data.strip_prefix("file:") ?;
+        if self.files.contains(&path) {Some(Panel::File(path.to_string()))} else {None}
+    }
+}
+
+fn tab(path: &str) -> DockTab<Panel> {
+    DockTab {closeable: true, title: path.to_string(), item: Panel::File(path.to_string())}
+}
+
+fn dock() -> DockItem<Panel> {
+    DockItem::Splitter {
+        pos: 0.3,
+        align: SplitterAlign::First,
+        axis: Axis::Vertical,
+        first: Box::new(DockItem::TabControl {
+            current: 1,
+            previous: 0,
+            tabs: vec![tab("a.rs"), DockTab {closeable: false, title: "log".to_string(), item: Panel::Log}]
+        }),
+        last: Box::new(DockItem::Splitter {
+            pos: 0.5,
+            align: SplitterAlign::Weighted,
+            axis: Axis::Horizontal,
+            first: Box::new(DockItem::TabControl {current: 0, previous: 0, tabs: vec![tab("b.rs")]}),
+            last: Box::new(DockItem::TabControl {current: 1, previous: 1, tabs: vec![tab("c.rs"), tab("d.rs")]}),
+        })
+    }
+}
+
+// the tree as one line, splitters with their axis, align and position and tab controls with current/previous
+fn shape(item: &DockItem<Panel>) -> String {
+    match item {
+        DockItem::TabControl {current, previous, tabs} => {
+            let titles: Vec<String> = tabs.iter().map( | tab | tab.title.clone()).collect();
+            format!("tabs {}/{} [{}]", current, previous, titles.join(", "))
+        },
+        DockItem::Splitter {pos, align, axis, first, last} => {
+            let align = match align {
+                SplitterAlign::First => "first",
+                SplitterAlign::Last => "last",
+                SplitterAlign::Weighted => "weighted"
+            };
+            format!("split {:?} {} {} ({}) ({})", axis, align, pos, shape(first), shape(last))
+        }
+    }
+}
+
+fn load(ron: &str, files: Vec<&'static str>) -> String {
+    shape(&DockItem::deserialize_dock_ron(ron, &Loader {files}).unwrap())
+}
+
+#[test]
+fn round_trip() {
+    let all = vec!["a.rs", "b.rs", "c.rs", "d.rs"];
+    let ron = dock().serialize_dock_ron(&Loader {files: all.clone()});
+    // items are saved the way the serializer writes them
+    assert!(ron.contains("\"file:a.rs\"") && ron.contains("\"log\""), "{}", ron);
+    
+    let loaded = DockItem::deserialize_dock_ron(&ron, &Loader {files: all}).unwrap();
+    assert_eq!(shape(&loaded), shape(&dock()));
+    assert_eq!(
+        shape(&loaded),
+        "split Vertical first 0.3 (tabs 1/0 [a.rs, log]) (split Horizontal weighted 0.5 (tabs 0/0 [b.rs]) (tabs 1/1 [c.rs, d.rs]))"
+    );
+    let items: Vec<Panel> = loaded.tabs().into_iter().map( | tab | tab.item).collect();
+    assert_eq!(items, vec![
+        Panel::File("a.rs".to_string()),
+        Panel::Log,
+        Panel::File("b.rs".to_string()),
+        Panel::File("c.rs".to_string()),
+        Panel::File("d.rs".to_string()),
+    ]);
+    assert_eq!(loaded.tabs().iter().map( | tab | tab.closeable).collect::<Vec<_>>(), vec![true, false, true, true, true]);
+    
+    // and saving what was loaded gives the same RON
+    assert_eq!(loaded.serialize_dock_ron(&Loader {files: vec![]}), ron);
+}
+
+#[test]
+fn tabs_that_no_longer_load() {
+    let ron = dock().serialize_dock_ron(&Loader {files: vec![]});
+    
+    // a tab control left without tabs goes, and its splitter with it
+    assert_eq!(
+        load(&ron, vec!["a.rs", "c.rs", "d.rs"]),
+        "split Vertical first 0.3 (tabs 1/0 [a.rs, log]) (tabs 1/1 [c.rs, d.rs])"
+    );
+    // current and previous stay on a tab that is still there
+    assert_eq!(
+        load(&ron, vec!["b.rs", "c.rs"]),
+        "split Vertical first 0.3 (tabs 0/0 [log]) (split Horizontal weighted 0.5 (tabs 0/0 [b.rs]) (tabs 0/0 [c.rs]))"
+    );
+    // a whole side going collapses the root into the other side
+    assert_eq!(load(&ron, vec![]), "tabs 0/0 [log]");
+    
+    // with nothing that loads, an empty tab control is all that is left
+    let ron = DockItem::TabControl {current: 0, previous: 0, tabs: vec![tab("a.rs"), tab("b.rs")]}.serialize_dock_ron(&Loader {files: vec![]});
+    assert_eq!(load(&ron, vec![]), "tabs 0/0 []");
+    
+    // RON that doesn't parse is an error, not an empty dock
+    assert!(DockItem::deserialize_dock_ron("TabControl(current:0", &Loader {files: vec![]}).is_err());
+}
@@ -0,0 +1,182 @@
+// switching a dock between named layouts with its splitters sliding over, and floating a tab off and docking it back
+use makepad_render::*;
+use makepad_widget::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    dock: Dock<String>,
+    dock_items: DockItem<String>,
+    layouts: DockLayouts<String>,
+    floated: Option<DockFloat<String >>,
+    // the root splitter position after every next frame
+    positions: Vec<f32>,
+}
+
+impl App {
+    fn new(cx: &mut Cx, dock_items: DockItem<String>) -> Self {
+        Self {
+            window: Window {create_inner_size: Some(vec2(200., 100.)), ..Window::new(cx)},
+            pass: Pass::default(),
+            dock: Dock::new(cx).with_float_tabs(true),
+            dock_items: dock_items,
+            layouts: DockLayouts::new(vec![]),
+            floated: None,
+            positions: Vec::new(),
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        let is_next_frame = if let Event::NextFrame(_) = event {true} else {false};
+        let mut dock_walker = self.dock.walker(&mut self.dock_items);
+        while let Some(_) = dock_walker.walk_handle_dock(cx, event) {}
+        if let DockEvent::DockTabFloated = self.dock.handle_dock(cx, event, &mut self.dock_items) {
+            self.floated = self.dock.take_floated();
+        }
+        if is_next_frame {
+            self.positions.push(root_pos(&self.dock_items));
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        self.dock.draw_dock(cx);
+        let mut dock_walker = self.dock.walker(&mut self.dock_items);
+        while let Some(_) = dock_walker.walk_draw_dock(cx, | cx, tab_control, tab, selected | {
+            tab_control.draw_tab(cx, &tab.title, selected)
+        }) {}
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn tab(title: &str, closeable: bool) -> DockTab<String> {
+    DockTab {closeable, title: title.to_string(), item: title.to_string()}
+}
+
+fn tabs(titles: &[&str]) -> Box<DockItem<String >> {
+    Box::new(DockItem::TabControl {current: 0, previous: 0, tabs: titles.iter().map( | title | tab(title, true)).collect()})
+}
+
+fn split(pos: f32, first: Box<DockItem<String >>, last: Box<DockItem<String >>) -> DockItem<String> {
+    DockItem::Splitter {pos, align: SplitterAlign::First, axis: Axis::Vertical, first, last}
+}
+
+fn root_pos(item: &DockItem<String>) -> f32 {
+    if let DockItem::Splitter {pos, ..} = item {*pos} else {panic!("no splitter at the root")}
+}
+
+fn titles(item: &DockItem<String>) -> Vec<String> {
+    item.tabs().into_iter().map( | tab | tab.title).collect()
+}
+
+fn run(cx: &mut Cx, app: &mut App, mut on_event: impl FnMut(&mut Cx, &mut App, &Event)) {
+    let mut cxafterdraw = CxAfterDraw::new(cx);
+    cx.platform.headless.window_size = Some(vec2(200., 100.));
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+        on_event(cx, app, event);
+    });
+}
+
+fn new_cx() -> Cx {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    cx
+}
+
+#[test]
+fn switch_layout_by_name() {
+    let mut cx = new_cx();
+    let mut app = App::new(&mut cx, split(50., tabs(&["a.rs"]), tabs(&["log"])));
+    app.layouts = DockLayouts::new(vec![
+        DockLayout {name: "narrow".to_string(), items: split(50., tabs(&["a.rs"]), tabs(&["log"]))},
+        DockLayout {name: "wide".to_string(), items: split(150., tabs(&["b.rs"]), tabs(&["log"]))},
+    ]).with_current("narrow");
+    assert_eq!(app.layouts.names(), vec!["narrow".to_string(), "wide".to_string()]);
+    
+    let mut switched = Vec::new();
+    run(&mut cx, &mut app, | cx, app, event | {
+        if let Event::Construct = event {
+            // the tree in use goes back into narrow, with a tab opened since
+            if let DockItem::Splitter {first, ..} = &mut app.dock_items {
+                **first = *tabs(&["a.rs", "c.rs"]);
+            }
+            assert!(!app.dock.switch_to_layout(cx, &mut app.dock_items, &mut app.layouts, "nothing"));
+            assert!(app.dock.switch_to_layout(cx, &mut app.dock_items, &mut app.layouts, "wide"));
+            // the tabs are there at once, the splitter starts where it was
+            switched.push((titles(&app.dock_items), root_pos(&app.dock_items)));
+        }
+    });
+    assert_eq!(switched, vec![(vec!["b.rs".to_string(), "log".to_string()], 50.)]);
+    assert_eq!(app.layouts.current.as_deref(), Some("wide"));
+    assert_eq!(titles(&app.layouts.layout("narrow").unwrap().items), vec!["a.rs", "c.rs", "log"]);
+    
+    // the splitter slides over several frames, only ever forward, and ends on its target
+    assert!(app.positions.len() > 3, "{:?}", app.positions);
+    assert!(app.positions.windows(2).all( | w | w[0] <= w[1]), "{:?}", app.positions);
+    assert!(app.positions.iter().any( | pos | *pos > 50. && *pos < 150.), "{:?}", app.positions);
+    assert_eq!(root_pos(&app.dock_items), 150.);
+    assert_eq!(*app.positions.last().unwrap(), 150.);
+    
+    // switching back halfway saves wide where its splitter was going, and slides back to narrow
+    app.positions.truncate(0);
+    let mut halfway = None;
+    run(&mut cx, &mut app, | cx, app, event | {
+        if let Event::Construct = event {
+            app.dock.switch_to_layout(cx, &mut app.dock_items, &mut app.layouts, "narrow");
+        }
+        if let Event::NextFrame(_) = event {
+            if app.positions.len() == 3 {
+                halfway = Some(root_pos(&app.dock_items));
+                app.dock.switch_to_layout(cx, &mut app.dock_items, &mut app.layouts, "wide");
+            }
+        }
+    });
+    let halfway = halfway.unwrap();
+    assert!(halfway > 50. && halfway < 150., "{}", halfway);
+    assert_eq!(root_pos(&app.layouts.layout("narrow").unwrap().items), 50.);
+    assert_eq!(titles(&app.dock_items), vec!["b.rs", "log"]);
+    assert!((root_pos(&app.dock_items) - 150.).abs() < 0.001, "{:?}", app.positions);
+}
+
+#[test]
+fn float_tab_and_dock_it_back() {
+    let mut cx = new_cx();
+    let mut app = App::new(&mut cx, split(100., tabs(&["a.rs", "b.rs"]), tabs(&["c.rs"])));
+    for event in vec![
+        Event::None,
+        // a.rs dragged out of the window and let go where there is no tab control
+        Event::FingerDown(FingerDownEvent {abs: vec2(10., 10.), ..FingerDownEvent::default()}),
+        Event::FingerMove(FingerMoveEvent {abs: vec2(10., 80.), ..FingerMoveEvent::default()}),
+        Event::FingerMove(FingerMoveEvent {abs: vec2(300., 300.), ..FingerMoveEvent::default()}),
+        Event::FingerUp(FingerUpEvent {abs: vec2(300., 300.), ..FingerUpEvent::default()}),
+    ] {
+        cx.headless_push_event(event);
+    }
+    run(&mut cx, &mut app, | _, _, _ | {});
+    
+    let float = app.floated.take().expect("a.rs didn't float");
+    assert_eq!(titles(&float.items), vec!["a.rs"]);
+    assert_eq!(float.position, vec2(300., 300.));
+    assert_eq!(float.size.x, 100.);
+    assert_eq!(titles(&app.dock_items), vec!["b.rs", "c.rs"]);
+    
+    // docking the float puts its tab next to those in the first tab control
+    run(&mut cx, &mut app, | cx, app, event | {
+        if let Event::Construct = event {
+            app.dock.dock_float(cx, &mut app.dock_items, &float);
+        }
+    });
+    assert_eq!(titles(&app.dock_items), vec!["b.rs", "a.rs", "c.rs"]);
+    assert!(app.floated.is_none());
+}
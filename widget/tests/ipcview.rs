@@ -0,0 +1,112 @@
+// a frame from a child, with a clipped sub view sampling a texture the child sent, drawn by the parent
+use makepad_render::*;
+use makepad_widget::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    ipc_view: IpcView,
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window {create_inner_size: Some(vec2(32., 24.)), ..Window::new(cx)},
+            pass: Pass::default(),
+            ipc_view: IpcView::new(cx),
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        self.ipc_view.draw_ipc_view(cx);
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+    Rect {pos: vec2(x, y), size: vec2(w, h)}
+}
+
+fn draw_call(shader: LiveItemId, instances: Vec<f32>, textures: Vec<u32>) -> IpcDrawItem {
+    IpcDrawItem::DrawCall(IpcDrawCall {
+        shader,
+        instance_slots: instances.len(),
+        instances,
+        user_uniforms: Vec::new(),
+        textures,
+        do_h_scroll: true,
+        do_v_scroll: true,
+    })
+}
+
+// red over the window, and a blue image from x 8 in a view that starts at x 16
+fn child_msgs() -> Vec<IpcChildMsg> {
+    let color = live_str_to_id("makepad_render::drawcolor", "self::shader");
+    let image = live_str_to_id("makepad_render::drawimage", "self::shader");
+    vec![
+        IpcChildMsg::Texture {texture_id: 7, width: 2, height: 2, pixels: vec![0xff0000ff; 4]},
+        IpcChildMsg::Frame(IpcFrame {
+            window_id: 0,
+            size: vec2(32., 24.),
+            dpi_factor: 1.0,
+            clear_color: Vec4::default(),
+            root: IpcDrawList {
+                rect: rect(0., 0., 32., 24.),
+                clipped: false,
+                scroll: Vec2::default(),
+                items: vec![
+                    draw_call(color, vec![0., 0., 32., 24., 0., 1., 0., 0., 1.], Vec::new()),
+                    // a shader the parent doesn't have is left out
+                    draw_call(LiveItemId(1), vec![0., 0., 32., 24., 0.], Vec::new()),
+                    IpcDrawItem::DrawList(IpcDrawList {
+                        rect: rect(16., 0., 16., 24.),
+                        clipped: true,
+                        scroll: Vec2::default(),
+                        items: vec![draw_call(image, vec![8., 0., 24., 24., 0., 0., 0., 1., 1., 1.], vec![7])]
+                    })
+                ]
+            }
+        })
+    ]
+}
+
+#[test]
+fn draws_child_frame() {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.platform.headless.window_size = Some(vec2(32., 24.));
+    let mut frames = 0;
+    cx.event_loop( | cx, event | {
+        if let Event::Construct = event {
+            for msg in child_msgs() {
+                if let IpcViewEvent::Frame = app.ipc_view.handle_child_msg(cx, msg) {
+                    frames += 1;
+                }
+            }
+        }
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+        }
+    });
+    assert_eq!(frames, 1);
+    assert_eq!(app.ipc_view.child_textures.len(), 1);
+    
+    let framebuffer = cx.window_framebuffer(0).unwrap();
+    assert_eq!((framebuffer.width, framebuffer.height), (32, 24));
+    let red = Vec4 {x: 1., y: 0., z: 0., w: 1.};
+    let blue = Vec4 {x: 0., y: 0., z: 1., w: 1.};
+    assert_eq!(framebuffer.pixel(4, 12), red);
+    // the image starts at 8, but its view clips it to 16
+    assert_eq!(framebuffer.pixel(12, 12), red);
+    assert_eq!(framebuffer.pixel(20, 12), blue);
+    assert_eq!(framebuffer.pixel(30, 2), blue);
+}
@@ -0,0 +1,94 @@
+// where Popup::place puts a panel for every placement, in the open and against each edge of the bounds
+use makepad_render::*;
+use makepad_widget::*;
+
+fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+    Rect {pos: vec2(x, y), size: vec2(w, h)}
+}
+
+// a panel of 100 by 50 in a window of 400 by 300
+fn place(anchor: Rect, placement: PopupPlacement) -> Vec2 {
+    Popup::place(anchor, placement, vec2(100., 50.), rect(0., 0., 400., 300.))
+}
+
+#[test]
+fn below() {
+    assert_eq!(place(rect(50., 100., 60., 20.), PopupPlacement::Below), vec2(50., 120.));
+    // at the top edge there is room below
+    assert_eq!(place(rect(50., 0., 60., 20.), PopupPlacement::Below), vec2(50., 20.));
+    // at the bottom it flips above
+    assert_eq!(place(rect(50., 270., 60., 20.), PopupPlacement::Below), vec2(50., 220.));
+    // at the sides it is pushed back in
+    assert_eq!(place(rect(350., 100., 60., 20.), PopupPlacement::Below), vec2(300., 120.));
+    assert_eq!(place(rect(-20., 100., 60., 20.), PopupPlacement::Below), vec2(0., 120.));
+    // no room on either side, it stays as far down as it fits
+    let pos = Popup::place(rect(10., 20., 60., 20.), PopupPlacement::Below, vec2(100., 50.), rect(0., 0., 400., 60.));
+    assert_eq!(pos, vec2(10., 10.));
+}
+
+#[test]
+fn above() {
+    assert_eq!(place(rect(50., 100., 60., 20.), PopupPlacement::Above), vec2(50., 50.));
+    // at the top it flips below
+    assert_eq!(place(rect(50., 30., 60., 20.), PopupPlacement::Above), vec2(50., 50.));
+    // at the bottom there is room above
+    assert_eq!(place(rect(50., 280., 60., 20.), PopupPlacement::Above), vec2(50., 230.));
+    assert_eq!(place(rect(350., 100., 60., 20.), PopupPlacement::Above), vec2(300., 50.));
+    assert_eq!(place(rect(-20., 100., 60., 20.), PopupPlacement::Above), vec2(0., 50.));
+    // no room on either side, it stays as far up as it fits
+    let pos = Popup::place(rect(10., 20., 60., 20.), PopupPlacement::Above, vec2(100., 50.), rect(0., 0., 400., 60.));
+    assert_eq!(pos, vec2(10., 0.));
+}
+
+#[test]
+fn right() {
+    assert_eq!(place(rect(100., 100., 60., 20.), PopupPlacement::Right), vec2(160., 100.));
+    // at the left edge there is room to the right
+    assert_eq!(place(rect(0., 100., 60., 20.), PopupPlacement::Right), vec2(60., 100.));
+    // at the right it flips to the left
+    assert_eq!(place(rect(300., 100., 60., 20.), PopupPlacement::Right), vec2(200., 100.));
+    // at the top and bottom it is pushed back in
+    assert_eq!(place(rect(100., -10., 60., 20.), PopupPlacement::Right), vec2(160., 0.));
+    assert_eq!(place(rect(100., 280., 60., 20.), PopupPlacement::Right), vec2(160., 250.));
+    // too wide for either side, it stays as far right as it fits
+    let pos = Popup::place(rect(60., 100., 60., 20.), PopupPlacement::Right, vec2(100., 50.), rect(0., 0., 200., 300.));
+    assert_eq!(pos, vec2(100., 100.));
+}
+
+#[test]
+fn point() {
+    let at = | x, y | rect(x, y, 0., 0.);
+    assert_eq!(place(at(100., 100.), PopupPlacement::Point), vec2(100., 100.));
+    assert_eq!(place(at(0., 0.), PopupPlacement::Point), vec2(0., 0.));
+    // it flips to the left at the right edge and up at the bottom, and both in the corner
+    assert_eq!(place(at(350., 100.), PopupPlacement::Point), vec2(250., 100.));
+    assert_eq!(place(at(100., 280.), PopupPlacement::Point), vec2(100., 230.));
+    assert_eq!(place(at(350., 280.), PopupPlacement::Point), vec2(250., 230.));
+    // outside the bounds it comes back in
+    assert_eq!(place(at(-30., -30.), PopupPlacement::Point), vec2(0., 0.));
+    // no room to flip, it is pushed back in
+    let pos = Popup::place(at(60., 40.), PopupPlacement::Point, vec2(100., 50.), rect(0., 0., 120., 60.));
+    assert_eq!(pos, vec2(20., 10.));
+}
+
+#[test]
+fn center() {
+    // the anchor doesn't matter
+    assert_eq!(place(rect(0., 0., 10., 10.), PopupPlacement::Center), vec2(150., 125.));
+    assert_eq!(place(rect(390., 290., 10., 10.), PopupPlacement::Center), vec2(150., 125.));
+    // a panel bigger than the bounds keeps its top left corner in view
+    let pos = Popup::place(rect(0., 0., 10., 10.), PopupPlacement::Center, vec2(500., 400.), rect(0., 0., 400., 300.));
+    assert_eq!(pos, vec2(0., 0.));
+}
+
+#[test]
+fn bounds_not_at_the_origin() {
+    let bounds = rect(100., 100., 200., 200.);
+    let size = vec2(100., 50.);
+    assert_eq!(Popup::place(rect(0., 0., 10., 10.), PopupPlacement::Center, size, bounds), vec2(150., 175.));
+    // flips and pushes are against the edges of the bounds, not the window
+    assert_eq!(Popup::place(rect(120., 280., 40., 10.), PopupPlacement::Below, size, bounds), vec2(120., 230.));
+    assert_eq!(Popup::place(rect(120., 110., 40., 10.), PopupPlacement::Above, size, bounds), vec2(120., 120.));
+    assert_eq!(Popup::place(rect(250., 150., 40., 10.), PopupPlacement::Right, size, bounds), vec2(150., 150.));
+    assert_eq!(Popup::place(rect(50., 50., 0., 0.), PopupPlacement::Point, size, bounds), vec2(100., 100.));
+}
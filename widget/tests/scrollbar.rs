@@ -0,0 +1,126 @@
+// a smoothed scrollbar drawn once without a window, then glided, jumped and dragged
+use makepad_render::*;
+use makepad_widget::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    scroll_bar: ScrollBar,
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window::new(cx),
+            pass: Pass::default(),
+            view: View::new(),
+            scroll_bar: ScrollBar::new(cx).with_smoothing(Spring::critical(200.0)),
+        }
+    }
+    
+    // 1000 pixels of content in a view 200 high
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            let area = self.view.area();
+            self.scroll_bar.draw_scroll_bar(cx, Axis::Vertical, area, Rect {pos: vec2(0., 0.), size: vec2(100., 200.)}, vec2(100., 1000.));
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn drawn_scroll_bar() -> (Cx, ScrollBar) {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.platform.headless.window_size = Some(vec2(100., 200.));
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+        }
+    });
+    (cx, app.scroll_bar)
+}
+
+// steps a frame at a time until the glide stops, returns the time it stopped at
+fn glide(cx: &mut Cx, scroll_bar: &mut ScrollBar, mut time: f64) -> f64 {
+    while scroll_bar.move_towards_scroll_target(cx, time) {
+        time += 1.0 / 60.0;
+        assert!(time < 100.0);
+    }
+    time
+}
+
+#[test]
+fn smoothing_glides_to_the_target() {
+    let (mut cx, mut scroll_bar) = drawn_scroll_bar();
+    assert!(scroll_bar.set_scroll_target(&mut cx, 400.));
+    assert!(scroll_bar.move_towards_scroll_target(&mut cx, 1.0));
+    let first = scroll_bar.get_scroll_pos();
+    assert!(first > 0. && first < 400.);
+    glide(&mut cx, &mut scroll_bar, 1.0 + 1.0 / 60.0);
+    assert_eq!(scroll_bar.get_scroll_pos(), 400.);
+    // targets past the end clamp to the last page
+    scroll_bar.set_scroll_target(&mut cx, 5000.);
+    assert_eq!(scroll_bar.get_scroll_target(), 800.);
+}
+
+#[test]
+fn jumping_stops_the_glide() {
+    let (mut cx, mut scroll_bar) = drawn_scroll_bar();
+    scroll_bar.set_scroll_target(&mut cx, 800.);
+    let mut time = 1.0;
+    for _ in 0..3 {
+        scroll_bar.move_towards_scroll_target(&mut cx, time);
+        time += 1.0 / 60.0;
+    }
+    assert!(scroll_bar.get_scroll_pos() > 0. && scroll_bar.get_scroll_pos() < 800.);
+    
+    // the spring used to keep its old value and pull the view back on the next frame
+    assert!(scroll_bar.set_scroll_pos(&mut cx, 300.));
+    assert!(!scroll_bar.move_towards_scroll_target(&mut cx, time));
+    assert_eq!(scroll_bar.get_scroll_pos(), 300.);
+    assert_eq!(scroll_bar.get_scroll_target(), 300.);
+    
+    // and a new glide starts from where it jumped to
+    scroll_bar.set_scroll_target(&mut cx, 100.);
+    scroll_bar.move_towards_scroll_target(&mut cx, time + 1.0 / 60.0);
+    let pos = scroll_bar.get_scroll_pos();
+    assert!(pos < 300. && pos > 100., "{}", pos);
+    glide(&mut cx, &mut scroll_bar, time + 2.0 / 60.0);
+    assert_eq!(scroll_bar.get_scroll_pos(), 100.);
+}
+
+#[test]
+fn dragging_stops_the_glide() {
+    let (mut cx, mut scroll_bar) = drawn_scroll_bar();
+    scroll_bar.set_scroll_target(&mut cx, 800.);
+    let mut time = 1.0;
+    for _ in 0..3 {
+        scroll_bar.move_towards_scroll_target(&mut cx, time);
+        time += 1.0 / 60.0;
+    }
+    let gliding = scroll_bar.get_scroll_pos();
+    assert!(gliding > 0. && gliding < 800.);
+    
+    // the handle is dragged back to the top while the view still glides down
+    let event = scroll_bar.set_scroll_pos_from_finger(&mut cx, 0.);
+    assert_eq!(event, ScrollBarEvent::Scroll {scroll_pos: 0., view_total: 1000., view_visible: 200.});
+    assert!(!scroll_bar.move_towards_scroll_target(&mut cx, time));
+    assert_eq!(scroll_bar.get_scroll_pos(), 0.);
+    
+    // dragging to the bottom lands on the last page and stays there
+    scroll_bar.set_scroll_pos_from_finger(&mut cx, 1000.);
+    assert_eq!(scroll_bar.get_scroll_pos(), 800.);
+    glide(&mut cx, &mut scroll_bar, time + 1.0 / 60.0);
+    assert_eq!(scroll_bar.get_scroll_pos(), 800.);
+}
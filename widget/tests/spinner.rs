@@ -0,0 +1,157 @@
+// a spinner snapping and clamping what it is set to, then dragged, scrolled, clicked on its arrows and stepped with keys
+use makepad_render::*;
+use makepad_widget::*;
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    spinner: Spinner,
+    value: f32,
+    // the values changed to, with a 'done' when a drag ends
+    steps: Vec<String>,
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        Self {
+            window: Window {create_inner_size: Some(vec2(100., 40.)), ..Window::new(cx)},
+            pass: Pass::default(),
+            view: View::new(),
+            spinner: Spinner::new(cx).with_range(0., 100.),
+            value: 10.,
+            steps: Vec::new(),
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        match self.spinner.handle_spinner(cx, event) {
+            SpinnerEvent::Change {value} => {
+                self.value = value;
+                self.steps.push(format!("{}", value));
+            },
+            SpinnerEvent::DoneChanging => self.steps.push("done".to_string()),
+            SpinnerEvent::None => ()
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            self.spinner.draw_spinner(cx, self.value);
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn run(cx: &mut Cx, app: &mut App) {
+    let mut cxafterdraw = CxAfterDraw::new(cx);
+    cx.platform.headless.window_size = Some(vec2(100., 40.));
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+    });
+}
+
+fn new_cx() -> Cx {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    cx
+}
+
+fn key(key_code: KeyCode, shift: bool) -> Event {
+    Event::KeyDown(KeyEvent {
+        key_code,
+        is_repeat: false,
+        modifiers: KeyModifiers {shift, ..KeyModifiers::default()},
+        time: 0.0
+    })
+}
+
+fn scroll(x: f32, y: f32, scroll_y: f32) -> Event {
+    Event::FingerScroll(FingerScrollEvent {abs: vec2(x, y), scroll: vec2(0., scroll_y), ..FingerScrollEvent::default()})
+}
+
+#[test]
+fn set_value_snaps_and_clamps() {
+    let mut cx = new_cx();
+    let mut spinner = Spinner::new(&mut cx).with_range(-5., 5.).with_step(0.5);
+    let mut set = | cx: &mut Cx, value: f32 | match spinner.set_value(cx, value) {
+        SpinnerEvent::Change {value} => Some(value),
+        _ => None
+    };
+    // to the nearest step
+    assert_eq!(set(&mut cx, 1.26), Some(1.5));
+    assert_eq!(set(&mut cx, -0.74), Some(-0.5));
+    // the same value again is no change
+    assert_eq!(set(&mut cx, -0.6), None);
+    // into the range
+    assert_eq!(set(&mut cx, 7.), Some(5.));
+    assert_eq!(set(&mut cx, -9.), Some(-5.));
+    assert_eq!(set(&mut cx, -5.2), None);
+    
+    // without a step or a range anything goes
+    let mut spinner = Spinner::new(&mut cx).with_step(0.);
+    assert!(matches!(spinner.set_value(&mut cx, 1234.567), SpinnerEvent::Change {value} if value == 1234.567));
+}
+
+#[test]
+fn drag_scroll_arrows_and_keys() {
+    let mut cx = new_cx();
+    let mut app = App::new(&mut cx);
+    run(&mut cx, &mut app);
+    let rect = app.spinner.bg.area().get_rect(&cx);
+    assert_eq!(rect.size.x, 80.);
+    let (left, mid, right) = (rect.pos.x + 5., rect.pos.x + 40., rect.pos.x + rect.size.x - 5.);
+    let y = rect.pos.y + 0.5 * rect.size.y;
+    
+    for event in vec![
+        // 20 pixels to the right at 0.25 steps a pixel, and back 8
+        Event::FingerDown(FingerDownEvent {abs: vec2(mid, y), ..FingerDownEvent::default()}),
+        Event::FingerMove(FingerMoveEvent {abs: vec2(mid + 20., y), ..FingerMoveEvent::default()}),
+        Event::FingerMove(FingerMoveEvent {abs: vec2(mid + 12., y), ..FingerMoveEvent::default()}),
+        Event::FingerUp(FingerUpEvent {abs: vec2(mid + 12., y), ..FingerUpEvent::default()}),
+        // 10 pixels scrolled is a step down, what is left over counts towards the next
+        scroll(mid, y, 25.),
+        scroll(mid, y, 5.),
+        scroll(mid, y, -10.),
+        // clicks on the arrows
+        Event::FingerDown(FingerDownEvent {abs: vec2(left, y), ..FingerDownEvent::default()}),
+        Event::FingerUp(FingerUpEvent {abs: vec2(left, y), ..FingerUpEvent::default()}),
+        Event::FingerDown(FingerDownEvent {abs: vec2(right, y), ..FingerDownEvent::default()}),
+        Event::FingerUp(FingerUpEvent {abs: vec2(right, y), ..FingerUpEvent::default()}),
+        // keys, with shift stepping 10 at a time
+        key(KeyCode::ArrowUp, false),
+        key(KeyCode::ArrowUp, true),
+        key(KeyCode::ArrowDown, false),
+        key(KeyCode::ArrowDown, true),
+    ] {
+        cx.headless_push_event(event);
+    }
+    // and up to the top of the range, where it stays
+    for _ in 0..9 {
+        cx.headless_push_event(key(KeyCode::ArrowUp, true));
+    }
+    cx.headless_push_event(key(KeyCode::ArrowUp, false));
+    run(&mut cx, &mut app);
+    
+    assert_eq!(app.steps, vec![
+        "15", "13", "done",
+        "11", "10", "11",
+        "10", "11",
+        "12", "22", "21", "11",
+        "21", "31", "41", "51", "61", "71", "81", "91", "100",
+    ]);
+    assert_eq!(app.value, 100.);
+    assert_eq!(app.spinner.value, 100.);
+}
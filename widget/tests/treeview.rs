@@ -0,0 +1,206 @@
+// flattening and filtering tree nodes, and driving a TreeView with clicks, keys and typing without a window
+use makepad_render::*;
+use makepad_widget::*;
+
+fn roots() -> Vec<TreeNode<u32>> {
+    vec![
+        TreeNode::folder("src", 0, vec![
+            TreeNode::leaf("main.rs", 1),
+            TreeNode::folder("util", 2, vec![TreeNode::leaf("Mod.rs", 3)]),
+        ]).with_open(true),
+        TreeNode::lazy("lazy", 4),
+        TreeNode::leaf("README.md", 5),
+    ]
+}
+
+fn paths(rows: &[TreeRow]) -> Vec<TreePath> {
+    rows.iter().map( | row | row.path.clone()).collect()
+}
+
+#[test]
+fn flatten_and_filter() {
+    let roots = roots();
+    let rows = TreeNode::flatten(&roots, "");
+    // closed folders hide their children
+    assert_eq!(paths(&rows), vec![vec![0], vec![0, 0], vec![0, 1], vec![1], vec![2]]);
+    assert_eq!(rows.iter().map( | row | row.depth).collect::<Vec<_>>(), vec![0, 1, 1, 0, 0]);
+    assert!(rows[0].is_folder && rows[0].is_open);
+    assert!(rows[2].is_folder && !rows[2].is_open);
+    assert!(rows[3].is_folder && !rows[4].is_folder);
+    assert_eq!(TreeNode::get(&roots, &[0, 1, 0]).unwrap().name, "Mod.rs");
+    assert!(TreeNode::get(&roots, &[1, 0]).is_none());
+    
+    // a filter looks inside closed folders and opens the way to what it finds, any case
+    let rows = TreeNode::flatten(&roots, "mod");
+    assert_eq!(paths(&rows), vec![vec![0], vec![0, 1], vec![0, 1, 0]]);
+    assert!(rows[0].is_open && rows[1].is_open);
+    // a folder that matches by itself shows up closed
+    let rows = TreeNode::flatten(&roots, "UTIL");
+    assert_eq!(paths(&rows), vec![vec![0], vec![0, 1]]);
+    assert!(rows[0].is_open && !rows[1].is_open);
+    // children that aren't loaded can't match
+    assert_eq!(paths(&TreeNode::flatten(&roots, "lazy")), vec![vec![1]]);
+    assert_eq!(TreeNode::flatten(&roots, "nothing"), vec![]);
+    
+    // loading shows on the row
+    let mut roots = roots;
+    roots[1].children = TreeChildren::Loading;
+    assert!(TreeNode::flatten(&roots, "")[3].is_loading);
+}
+
+// what came back from one input and what the tree looked like after it
+#[derive(Debug, PartialEq)]
+struct Step {
+    event: String,
+    selected: Vec<TreePath>,
+    rows: Vec<TreePath>,
+}
+
+struct App {
+    window: Window,
+    pass: Pass,
+    view: View,
+    tree: TreeView<u32>,
+    steps: Vec<Step>,
+}
+
+impl App {
+    fn new(cx: &mut Cx) -> Self {
+        let mut tree = TreeView::new(cx);
+        tree.set_roots(cx, roots());
+        Self {
+            window: Window::new(cx),
+            pass: Pass::default(),
+            view: View::new(),
+            tree: tree,
+            steps: Vec::new(),
+        }
+    }
+    
+    fn handle_app(&mut self, cx: &mut Cx, event: &mut Event) {
+        let is_input = match event {
+            Event::FingerDown(_) | Event::KeyDown(_) | Event::TextInput(_) => true,
+            _ => false
+        };
+        let event = match self.tree.handle_tree_view(cx, event) {
+            TreeViewEvent::Select {paths} => format!("select {:?}", paths),
+            TreeViewEvent::Activate {path} => format!("activate {:?}", path),
+            TreeViewEvent::LoadChildren {path} => {
+                self.tree.set_children(cx, &path, vec![TreeNode::leaf("a.txt", 6)]);
+                format!("load {:?}", path)
+            },
+            TreeViewEvent::None => "none".to_string(),
+            _ => "drag".to_string(),
+        };
+        if is_input {
+            self.steps.push(Step {
+                event: event,
+                selected: self.tree.selected.clone(),
+                rows: paths(&TreeNode::flatten(&self.tree.roots, &self.tree.filter)),
+            });
+        }
+    }
+    
+    fn draw_app(&mut self, cx: &mut Cx) {
+        self.window.begin_window(cx);
+        self.pass.begin_pass(cx);
+        if self.view.begin_view(cx, Layout::default()).is_ok() {
+            self.tree.draw_tree_view(cx);
+            self.view.end_view(cx);
+        }
+        self.pass.end_pass(cx);
+        self.window.end_window(cx);
+    }
+}
+
+fn key(key_code: KeyCode, shift: bool) -> Event {
+    Event::KeyDown(KeyEvent {
+        key_code,
+        is_repeat: false,
+        modifiers: KeyModifiers {shift, ..KeyModifiers::default()},
+        time: 0.0
+    })
+}
+
+fn text(input: &str) -> Event {
+    Event::TextInput(TextInputEvent {input: input.to_string(), replace_last: false, was_paste: false})
+}
+
+#[test]
+fn clicks_keys_and_typing() {
+    let _ = std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut cx = Cx::default();
+    cx.style();
+    set_widget_style(&mut cx);
+    cx.init_live_styles();
+    let mut app = App::new(&mut cx);
+    let mut cxafterdraw = CxAfterDraw::new(&mut cx);
+    cx.platform.headless.window_size = Some(vec2(200., 200.));
+    cx.headless_push_event(Event::None);
+    // rows are 20 high, the second one is main.rs
+    cx.headless_push_event(Event::FingerDown(FingerDownEvent {abs: vec2(60., 30.), ..FingerDownEvent::default()}));
+    cx.headless_push_event(Event::FingerUp(FingerUpEvent {abs: vec2(60., 30.), ..FingerUpEvent::default()}));
+    for event in vec![
+        key(KeyCode::ArrowDown, false),
+        key(KeyCode::ArrowRight, false),
+        key(KeyCode::ArrowRight, false),
+        key(KeyCode::ArrowLeft, false),
+        key(KeyCode::ArrowLeft, false),
+        key(KeyCode::ArrowDown, false),
+        key(KeyCode::ArrowRight, false),
+        key(KeyCode::ArrowDown, false),
+        key(KeyCode::Return, false),
+        key(KeyCode::ArrowDown, true),
+        key(KeyCode::ArrowUp, false),
+        text("MO"),
+        key(KeyCode::Backspace, false),
+        key(KeyCode::Escape, false),
+    ] {
+        cx.headless_push_event(event);
+    }
+    cx.event_loop( | cx, event | {
+        if let Event::Draw = event {
+            app.draw_app(cx);
+            cxafterdraw.after_draw(cx);
+            return
+        }
+        app.handle_app(cx, event);
+    });
+    
+    let all = vec![vec![0], vec![0, 0], vec![0, 1], vec![1], vec![2]];
+    let util_open = vec![vec![0], vec![0, 0], vec![0, 1], vec![0, 1, 0], vec![1], vec![2]];
+    let loaded = vec![vec![0], vec![0, 0], vec![0, 1], vec![1], vec![1, 0], vec![2]];
+    let step = | event: &str, selected: Vec<TreePath>, rows: &Vec<TreePath> | Step {event: event.to_string(), selected, rows: rows.clone()};
+    let expected = vec![
+        // clicking a leaf selects it
+        step("select [[0, 0]]", vec![vec![0, 0]], &all),
+        step("select [[0, 1]]", vec![vec![0, 1]], &all),
+        // right opens a closed folder, then steps into it
+        step("none", vec![vec![0, 1]], &util_open),
+        step("select [[0, 1, 0]]", vec![vec![0, 1, 0]], &util_open),
+        // left goes up to the parent, then closes it
+        step("select [[0, 1]]", vec![vec![0, 1]], &util_open),
+        step("none", vec![vec![0, 1]], &all),
+        step("select [[1]]", vec![vec![1]], &all),
+        // a lazy folder asks for its children
+        step("load [1]", vec![vec![1]], &loaded),
+        step("select [[1, 0]]", vec![vec![1, 0]], &loaded),
+        step("activate [1, 0]", vec![vec![1, 0]], &loaded),
+        // shift extends the selection
+        step("select [[1, 0], [2]]", vec![vec![1, 0], vec![2]], &loaded),
+        // up goes from the last selected row back to a single one
+        step("select [[1, 0]]", vec![vec![1, 0]], &loaded),
+        // typing filters, the selection stays on the rows that are left
+        step("none", vec![], &vec![vec![0], vec![0, 1], vec![0, 1, 0]]),
+        // backspace widens it to everything with an m
+        step("none", vec![], &vec![vec![0], vec![0, 0], vec![0, 1], vec![0, 1, 0], vec![2]]),
+        // and escape clears it
+        step("none", vec![], &loaded),
+    ];
+    for (index, (got, want)) in app.steps.iter().zip(expected.iter()).enumerate() {
+        assert_eq!(got, want, "step {}", index);
+    }
+    assert_eq!(app.steps.len(), expected.len());
+    assert_eq!(app.tree.filter, "");
+    assert_eq!(app.tree.rows, TreeNode::flatten(&app.tree.roots, ""));
+}
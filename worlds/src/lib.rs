@@ -2,10 +2,12 @@ pub mod skybox;
 pub mod worldview;
 pub mod treeworld;
 pub mod fieldworld;
+pub mod meshworld;
 
 use makepad_render::*;
 pub fn set_worlds_style(cx:&mut Cx){
     crate::skybox::SkyBox::style(cx);
     crate::worldview::WorldView::style(cx);
     crate::treeworld::TreeWorld::style(cx);
+    crate::meshworld::MeshWorld::style(cx);
 }
@@ -0,0 +1,104 @@
+// the procedural meshes on a floor, with a model file in the middle when there is one
+use makepad_render::*;
+use crate::skybox::SkyBox;
+
+#[derive(Clone)]
+pub struct MeshWorld {
+    pub area: Area,
+    pub sky_box: SkyBox,
+    pub mesh: DrawMesh,
+    pub shapes: Vec<(Geometry, Vec3, Vec4)>,
+    pub floor: Geometry,
+    pub model_path: String,
+    pub model_load: Option<MeshLoad>,
+    pub model_fit: Mat4,
+}
+
+impl MeshWorld {
+    pub fn new(cx: &mut Cx) -> Self {
+        let shapes = vec![
+            (GeometryGen::from_sphere_3d(0.1, 32, 16), vec3(-0.45, 0.0, -1.5), vec4(0.9, 0.5, 0.2, 1.0)),
+            (GeometryGen::from_cylinder_3d(0.05, 0.1, 0.2, 24, 1, true), vec3(0.45, 0.0, -1.5), vec4(0.3, 0.7, 0.9, 1.0)),
+            (GeometryGen::from_torus_3d(0.1, 0.03, 16, 48), vec3(0.0, 0.0, -1.95), vec4(0.6, 0.9, 0.3, 1.0)),
+        ];
+        Self {
+            area: Area::Empty,
+            sky_box: SkyBox::new(cx),
+            mesh: DrawMesh::new(cx, default_shader!()),
+            shapes: shapes.into_iter().map( | (gen, pos, color) | (Geometry::from_geometry_gen(cx, gen), pos, color)).collect(),
+            floor: Geometry::from_geometry_gen(cx, GeometryGen::from_grid_plane_3d(1.5, 1.5, 8, 8)),
+            model_path: "resources/mesh_world.glb".to_string(),
+            model_load: None,
+            model_fit: Mat4::identity(),
+        }
+    }
+
+    pub fn style(cx: &mut Cx) {
+        live_body!(cx, {
+            self::floor_color: #555;
+            self::model_color: #ddd;
+        });
+    }
+
+    // the model is optional, without it the world just shows the shapes
+    pub fn handle_mesh_world(&mut self, cx: &mut Cx, event: &mut Event) {
+        if let Some(model_load) = &mut self.model_load {
+            if let Some(Ok(geometry)) = model_load.handle_mesh_load(cx, event) {
+                self.model_fit = Self::fit_transform(cx, geometry);
+            }
+        }
+    }
+
+    // scales and centers a model of any size onto the middle of the floor
+    fn fit_transform(cx: &Cx, geometry: Geometry) -> Mat4 {
+        let vertices = &cx.geometries[geometry.geometry_id].vertices;
+        let mut min = Vec3::all(std::f32::INFINITY);
+        let mut max = Vec3::all(std::f32::NEG_INFINITY);
+        for v in vertices.chunks(9) {
+            min = vec3(min.x.min(v[0]), min.y.min(v[1]), min.z.min(v[2]));
+            max = vec3(max.x.max(v[0]), max.y.max(v[1]), max.z.max(v[2]));
+        }
+        let size = (max.x - min.x).max(max.y - min.y).max(max.z - min.z);
+        if !(size > 0.0) {
+            return Mat4::identity()
+        }
+        let scale = 0.35 / size;
+        Mat4::scaled_translation(
+            scale,
+            -0.5 * (min.x + max.x) * scale,
+            -min.y * scale - 0.1,
+            -0.5 * (min.z + max.z) * scale,
+        )
+    }
+
+    // everything turns on the spot with time, the world view redraws us every frame
+    pub fn draw_mesh_world(&mut self, cx: &mut Cx, time: f32) {
+        self.sky_box.draw_sky_box(cx);
+
+        if self.model_load.is_none() {
+            self.model_load = Some(MeshLoad::new(cx, &self.model_path));
+        }
+
+        let spin = Mat4::rotation(0.0, time * 20.0, 0.0);
+
+        self.mesh.geometry = Some(self.floor);
+        self.mesh.transform = Mat4::translation(0.0, -0.1, -1.7);
+        self.mesh.color = live_vec4!(cx, self::floor_color);
+        self.mesh.draw_mesh(cx);
+
+        for (geometry, pos, color) in &self.shapes {
+            self.mesh.geometry = Some(*geometry);
+            self.mesh.transform = Mat4::mul(&spin, &Mat4::translation(pos.x, pos.y, pos.z));
+            self.mesh.color = *color;
+            self.mesh.draw_mesh(cx);
+        }
+
+        if let Some(geometry) = self.model_load.as_ref().and_then( | m | m.geometry) {
+            self.mesh.geometry = Some(geometry);
+            self.mesh.transform = Mat4::mul(&Mat4::mul(&self.model_fit, &spin), &Mat4::translation(0.0, 0.0, -1.5));
+            self.mesh.color = live_vec4!(cx, self::model_color);
+            self.mesh.draw_mesh(cx);
+        }
+        self.area = self.mesh.area();
+    }
+}
@@ -3,6 +3,7 @@ use makepad_render::*;
 use makepad_widget::*;
 use crate::treeworld::TreeWorld;
 use crate::fieldworld::FieldWorld;
+use crate::meshworld::MeshWorld;
 
 #[derive(Clone)]
 pub struct WorldSelect {
@@ -18,6 +19,7 @@ pub enum WorldSelectEvent {
 pub enum WorldType {
     TreeWorld,
     FieldWorld,
+    MeshWorld,
 }
 
 impl WorldType {
@@ -25,6 +27,7 @@ impl WorldType {
         match self {
             Self::TreeWorld => "TreeWorld".to_string(),
             Self::FieldWorld => "FieldWorld".to_string(),
+            Self::MeshWorld => "MeshWorld".to_string(),
         }
     }
 }
@@ -53,6 +56,7 @@ pub struct WorldView {
     pub world_type: WorldType,
     pub tree_world: TreeWorld,
     pub field_world: FieldWorld,
+    pub mesh_world: MeshWorld,
     pub next_frame: NextFrame
 }
 
@@ -70,6 +74,7 @@ impl WorldView {
             last_xr_update_event: None, 
             tree_world: TreeWorld::new(cx),
             field_world: FieldWorld::new(cx),
+            mesh_world: MeshWorld::new(cx),
             next_frame:NextFrame::default()
         }
     }
@@ -108,7 +113,7 @@ impl WorldView {
         self.bg.draw_quad_rel(cx, cx.get_turtle_rect());
         //let inst = self.bg.begin_quad_fill(cx);
        
-        let world_types = vec![WorldType::TreeWorld, WorldType::FieldWorld];
+        let world_types = vec![WorldType::TreeWorld, WorldType::FieldWorld, WorldType::MeshWorld];
         
         for world_type in world_types {
             self.buttons.get_draw(cx, world_type.clone(), | _cx, templ | {
@@ -129,6 +134,10 @@ impl WorldView {
             WorldType::FieldWorld => {
                 vec![self.field_world.area]
             }
+            WorldType::MeshWorld => {
+                // the mesh shader has no world uniforms, it is redrawn every frame instead
+                vec![]
+            }
         }; 
         for area in areas{ // lets find some uniforms
             write_draw_input!(cx, area, self::uniforms::time, self.time);
@@ -148,6 +157,9 @@ impl WorldView {
         if let Some(ae) = event.is_next_frame(cx, self.next_frame) {
             self.time = ae.time as f32;
             self.update_uniforms(cx);
+            if self.world_type == WorldType::MeshWorld {
+                self.view.redraw_view(cx);
+            }
             self.next_frame = cx.new_next_frame();
         } 
 
@@ -167,6 +179,9 @@ impl WorldView {
             },
             WorldType::FieldWorld => {
                 self.field_world.handle_field_world(cx, event);
+            },
+            WorldType::MeshWorld => {
+                self.mesh_world.handle_mesh_world(cx, event);
             }, 
         } 
     }
@@ -207,6 +222,9 @@ impl WorldView {
             WorldType::FieldWorld => {
                 self.field_world.draw_field_world(cx);
             }
+            WorldType::MeshWorld => {
+                self.mesh_world.draw_mesh_world(cx, self.time);
+            }
         }
         
         self.view.end_view(cx,);